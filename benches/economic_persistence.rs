@@ -0,0 +1,61 @@
+//! Write throughput of the economic tracker with and without batching.
+//!
+//! Logs 200 grants (a balance checkpoint and a grant record each) through a
+//! fresh tracker, once syncing every record as it is logged and once with
+//! `[economic.persistence]` batching, then flushes.
+//!
+//! Run: `cargo bench --bench economic_persistence`
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use tempfile::TempDir;
+
+use zeroclaw::economic::{EconomicConfig, EconomicTracker, PersistencePolicy};
+
+const GRANTS: usize = 200;
+
+fn tracker(persistence: PersistencePolicy) -> (TempDir, EconomicTracker) {
+    let tmp = TempDir::new().unwrap();
+    let config = EconomicConfig {
+        enabled: true,
+        persistence,
+        ..Default::default()
+    };
+    let tracker = EconomicTracker::new("bench-agent", config, Some(tmp.path().to_path_buf()));
+    tracker.initialize().unwrap();
+    (tmp, tracker)
+}
+
+fn log_grants(tracker: &EconomicTracker) {
+    for n in 0..GRANTS {
+        tracker
+            .add_grant_income(1.0, &format!("grant-{n}"), "")
+            .unwrap();
+    }
+    tracker.flush().unwrap();
+}
+
+fn bench_persistence(c: &mut Criterion) {
+    let batched = PersistencePolicy {
+        max_buffered_records: 100,
+        ..PersistencePolicy::default()
+    };
+
+    let mut group = c.benchmark_group("economic_persistence");
+    group.sample_size(10);
+    for (name, policy) in [
+        ("write_through", PersistencePolicy::default()),
+        ("batched_100", batched),
+    ] {
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || tracker(policy.clone()),
+                |(_tmp, tracker)| log_grants(&tracker),
+                BatchSize::PerIteration,
+            );
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_persistence);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+//! Streaming benchmark for economic JSONL record files.
+//!
+//! Reads a 1M-line `token_costs.jsonl`-style file through `RecordReader` and
+//! reports resident memory before and after the pass. Memory should stay flat:
+//! the reader holds one line at a time, whatever the file size.
+//!
+//! Run: `cargo bench --bench record_reader`
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::fs::File;
+use std::hint::black_box;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use zeroclaw::economic::{RecordReader, TokenPricing};
+
+const LINES: usize = 1_000_000;
+
+fn write_records(path: &Path) {
+    let mut file = BufWriter::new(File::create(path).unwrap());
+    for n in 0..LINES {
+        writeln!(
+            file,
+            r#"{{"input_price_per_million":{n},"output_price_per_million":15.0}}"#
+        )
+        .unwrap();
+    }
+    file.flush().unwrap();
+}
+
+fn sum_prices(path: &Path) -> f64 {
+    RecordReader::<TokenPricing>::open(path)
+        .unwrap()
+        .filter_map(Result::ok)
+        .map(|pricing| pricing.input_price_per_million)
+        .sum()
+}
+
+/// Resident set size in KiB (Linux only).
+fn resident_kib() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    line.split_whitespace().nth(1)?.parse().ok()
+}
+
+fn bench_record_reader(c: &mut Criterion) {
+    let tmp = tempfile::TempDir::new().unwrap();
+    let path = tmp.path().join("token_costs.jsonl");
+    write_records(&path);
+
+    let before = resident_kib();
+    black_box(sum_prices(&path));
+    if let (Some(before), Some(after)) = (before, resident_kib()) {
+        println!(
+            "record_reader: {LINES} lines, RSS {before} KiB -> {after} KiB ({:+} KiB)",
+            after.cast_signed() - before.cast_signed()
+        );
+    }
+
+    let mut group = c.benchmark_group("record_reader");
+    group.sample_size(10);
+    group.bench_function("stream_1m_lines", |b| {
+        b.iter(|| black_box(sum_prices(&path)));
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_record_reader);
+criterion_main!(benches);
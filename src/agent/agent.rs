@@ -2,6 +2,7 @@ use crate::agent::dispatcher::{
     NativeToolDispatcher, ParsedToolCall, ToolDispatcher, ToolExecutionResult, XmlToolDispatcher,
 };
 use crate::agent::loop_::detection::{DetectionVerdict, LoopDetectionConfig, LoopDetector};
+use crate::agent::loop_::scrub_credentials;
 use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
 use crate::agent::research;
@@ -408,6 +409,10 @@ impl Agent {
                         tool: call.name.clone(),
                         duration: start.elapsed(),
                         success: r.success,
+                        error_message: (!r.success)
+                            .then(|| scrub_credentials(r.error.as_deref().unwrap_or(&r.output))),
+                        cost_usd: tool.cost_per_call(),
+                        task_id: None,
                     });
                     if r.success {
                         r.output
@@ -420,6 +425,9 @@ impl Agent {
                         tool: call.name.clone(),
                         duration: start.elapsed(),
                         success: false,
+                        error_message: Some(scrub_credentials(&e.to_string())),
+                        cost_usd: None,
+                        task_id: None,
                     });
                     format!("Error executing {}: {e}", call.name)
                 }
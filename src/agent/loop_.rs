@@ -809,6 +809,9 @@ pub(crate) async fn run_tool_call_loop(
         {
             return Err(ToolLoopCancelled.into());
         }
+        if observability::BudgetGuardObserver::tripped_in(observer) {
+            anyhow::bail!("Spending limit in [cost.budget] reached, not calling the provider");
+        }
 
         let image_marker_count = multimodal::count_image_markers(history);
         let provider_supports_vision =
@@ -919,6 +922,10 @@ pub(crate) async fn run_tool_call_loop(
                     error_message: None,
                     input_tokens: resp_input_tokens,
                     output_tokens: resp_output_tokens,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
+                    reasoning_tokens: None,
+                    session_id: None,
                 });
 
                 let response_text = resp.text_or_empty().to_string();
@@ -1018,6 +1025,10 @@ pub(crate) async fn run_tool_call_loop(
                     error_message: Some(safe_error.clone()),
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
+                    reasoning_tokens: None,
+                    session_id: None,
                 });
                 runtime_trace::record_event(
                     "llm_response",
@@ -1700,7 +1711,11 @@ pub async fn run(
     interactive: bool,
 ) -> Result<String> {
     // ── Wire up agnostic subsystems ──────────────────────────────
-    let base_observer = observability::create_observer(&config.observability);
+    let base_observer = observability::create_observer_with_cost_tracking(
+        &config.observability,
+        None,
+        &config.cost,
+    );
     let observer: Arc<dyn Observer> = Arc::from(base_observer);
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
@@ -2238,8 +2253,11 @@ pub async fn run(
 /// Process a single message through the full agent (with tools, peripherals, memory).
 /// Used by channels (Telegram, Discord, etc.) to enable hardware and tool use.
 pub async fn process_message(config: Config, message: &str) -> Result<String> {
-    let observer: Arc<dyn Observer> =
-        Arc::from(observability::create_observer(&config.observability));
+    let observer: Arc<dyn Observer> = Arc::from(observability::create_observer_with_cost_tracking(
+        &config.observability,
+        None,
+        &config.cost,
+    ));
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
     let security = Arc::new(SecurityPolicy::from_config(
@@ -29,6 +29,9 @@ async fn execute_one_tool(
             tool: call_name.to_string(),
             duration,
             success: false,
+            error_message: Some(scrub_credentials(&reason)),
+            cost_usd: None,
+            task_id: None,
         });
         return Ok(ToolExecutionOutcome {
             output: reason.clone(),
@@ -55,6 +58,10 @@ async fn execute_one_tool(
                 tool: call_name.to_string(),
                 duration,
                 success: r.success,
+                error_message: (!r.success)
+                    .then(|| scrub_credentials(r.error.as_deref().unwrap_or(&r.output))),
+                cost_usd: tool.cost_per_call(),
+                task_id: None,
             });
             if r.success {
                 Ok(ToolExecutionOutcome {
@@ -75,12 +82,15 @@ async fn execute_one_tool(
         }
         Err(e) => {
             let duration = start.elapsed();
+            let reason = format!("Error executing {call_name}: {e}");
             observer.record_event(&ObserverEvent::ToolCall {
                 tool: call_name.to_string(),
                 duration,
                 success: false,
+                error_message: Some(scrub_credentials(&reason)),
+                cost_usd: None,
+                task_id: None,
             });
-            let reason = format!("Error executing {call_name}: {e}");
             Ok(ToolExecutionOutcome {
                 output: reason.clone(),
                 success: false,
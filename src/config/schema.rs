@@ -41,6 +41,7 @@ const SUPPORTED_PROXY_SERVICE_KEYS: &[&str] = &[
     "tool.http_request",
     "tool.pushover",
     "memory.embeddings",
+    "observability.webhook",
     "tunnel.custom",
     "transcription.groq",
 ];
@@ -50,6 +51,7 @@ const SUPPORTED_PROXY_SERVICE_SELECTORS: &[&str] = &[
     "channel.*",
     "tool.*",
     "memory.*",
+    "observability.*",
     "tunnel.*",
     "transcription.*",
 ];
@@ -988,10 +990,96 @@ pub struct CostConfig {
     /// Per-model pricing (USD per 1M tokens)
     #[serde(default)]
     pub prices: std::collections::HashMap<String, ModelPricing>,
+
+    /// LiteLLM-style model prices JSON to import; entries in `prices` take
+    /// precedence (default: none)
+    #[serde(default)]
+    pub pricing_file: Option<String>,
+
+    /// Check `pricing_file` for changes every this many seconds and reload
+    /// it; 0 disables reloading (default: 0)
+    #[serde(default)]
+    pub pricing_reload_secs: u64,
+
+    /// Input price for models without pricing, USD per 1M tokens (default: 3.00)
+    #[serde(default = "default_unknown_input_price")]
+    pub default_input_price: f64,
+
+    /// Output price for models without pricing, USD per 1M tokens (default: 15.00)
+    #[serde(default = "default_unknown_output_price")]
+    pub default_output_price: f64,
+
+    /// How calls to models without pricing are recorded (default: `use_default`)
+    #[serde(default)]
+    pub unknown_model_policy: UnknownModelPolicy,
+
+    /// Record the token usage of failed LLM responses, which providers may
+    /// still bill (default: false)
+    #[serde(default)]
+    pub record_failed_response_costs: bool,
+
+    /// Hard spending limits enforced by the budget-guard observer
+    #[serde(default)]
+    pub budget: CostBudgetConfig,
 }
 
-/// Per-model pricing entry (USD per 1M tokens).
+/// Budget-guard limits (`[cost.budget]` section).
+///
+/// Independent of `[cost] enabled`: the guard prices LLM responses itself
+/// and stops further provider calls once a limit is reached.
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct CostBudgetConfig {
+    /// Enable the budget guard (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// Spending limit of one session in USD; 0 disables it (default: 0)
+    #[serde(default)]
+    pub session_limit_usd: f64,
+
+    /// Spending limit per day in USD; 0 disables it (default: 0)
+    #[serde(default)]
+    pub daily_limit_usd: f64,
+
+    /// Warn when spending reaches these percentages of a limit
+    /// (default: [50, 80])
+    #[serde(default = "default_budget_warn_percents")]
+    pub warn_at_percent: Vec<u8>,
+
+    /// UTC hour (0-23) at which the daily spending resets (default: 0)
+    #[serde(default)]
+    pub daily_reset_hour_utc: u8,
+}
+
+impl Default for CostBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            session_limit_usd: 0.0,
+            daily_limit_usd: 0.0,
+            warn_at_percent: default_budget_warn_percents(),
+            daily_reset_hour_utc: 0,
+        }
+    }
+}
+
+/// How the cost observer records calls to models without pricing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UnknownModelPolicy {
+    /// Price them at `default_input_price` / `default_output_price`.
+    #[default]
+    UseDefault,
+    /// Record their tokens at no cost.
+    Zero,
+    /// Price them at the defaults and log a warning once per model.
+    Warn,
+    /// Keep them out of the cost tracker, on the observer's dead-letter list.
+    Error,
+}
+
+/// Per-model pricing entry (USD per 1M tokens).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct ModelPricing {
     /// Input price per 1M tokens
     #[serde(default)]
@@ -1000,6 +1088,20 @@ pub struct ModelPricing {
     /// Output price per 1M tokens
     #[serde(default)]
     pub output: f64,
+
+    /// Price per 1M input tokens read from the prompt cache. Unset: the
+    /// input price less the cost observer's cache discount.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+
+    /// Price per 1M input tokens written to the prompt cache. Unset: the
+    /// input price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+
+    /// Price per 1M reasoning tokens. Unset: the output price.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<f64>,
 }
 
 fn default_daily_limit() -> f64 {
@@ -1014,6 +1116,18 @@ fn default_warn_percent() -> u8 {
     80
 }
 
+fn default_budget_warn_percents() -> Vec<u8> {
+    vec![50, 80]
+}
+
+fn default_unknown_input_price() -> f64 {
+    crate::cost::pricing::DEFAULT_INPUT_PRICE
+}
+
+fn default_unknown_output_price() -> f64 {
+    crate::cost::pricing::DEFAULT_OUTPUT_PRICE
+}
+
 impl Default for CostConfig {
     fn default() -> Self {
         Self {
@@ -1023,6 +1137,13 @@ impl Default for CostConfig {
             warn_at_percent: default_warn_percent(),
             allow_override: false,
             prices: get_default_pricing(),
+            pricing_file: None,
+            pricing_reload_secs: 0,
+            default_input_price: default_unknown_input_price(),
+            default_output_price: default_unknown_output_price(),
+            unknown_model_policy: UnknownModelPolicy::default(),
+            record_failed_response_costs: false,
+            budget: CostBudgetConfig::default(),
         }
     }
 }
@@ -1037,6 +1158,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            ..Default::default()
         },
     );
     prices.insert(
@@ -1044,6 +1166,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 75.0,
+            ..Default::default()
         },
     );
     prices.insert(
@@ -1051,6 +1174,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            ..Default::default()
         },
     );
     prices.insert(
@@ -1058,6 +1182,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.25,
             output: 1.25,
+            ..Default::default()
         },
     );
 
@@ -1067,6 +1192,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 5.0,
             output: 15.0,
+            ..Default::default()
         },
     );
     prices.insert(
@@ -1074,6 +1200,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.15,
             output: 0.60,
+            ..Default::default()
         },
     );
     prices.insert(
@@ -1081,6 +1208,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 60.0,
+            ..Default::default()
         },
     );
 
@@ -1090,6 +1218,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.10,
             output: 0.40,
+            ..Default::default()
         },
     );
     prices.insert(
@@ -1097,6 +1226,7 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 1.25,
             output: 5.0,
+            ..Default::default()
         },
     );
 
@@ -2627,6 +2757,11 @@ pub struct ObservabilityConfig {
     #[serde(default)]
     pub otel_service_name: Option<String>,
 
+    /// Label Prometheus metrics with full, dated model names instead of the
+    /// model family. Only used when backend = "prometheus".
+    #[serde(default)]
+    pub prometheus_full_model_names: bool,
+
     /// Runtime trace storage mode: "none" | "rolling" | "full".
     /// Controls whether model replies and tool-call diagnostics are persisted.
     #[serde(default = "default_runtime_trace_mode")]
@@ -2647,6 +2782,7 @@ impl Default for ObservabilityConfig {
             backend: "none".into(),
             otel_endpoint: None,
             otel_service_name: None,
+            prometheus_full_model_names: false,
             runtime_trace_mode: default_runtime_trace_mode(),
             runtime_trace_path: default_runtime_trace_path(),
             runtime_trace_max_entries: default_runtime_trace_max_entries(),
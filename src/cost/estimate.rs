@@ -0,0 +1,119 @@
+//! Pre-flight cost estimates for LLM requests.
+//!
+//! [`CostEstimator`] prices a request before it is sent, with the same model
+//! lookup `CostObserver` uses to price it afterwards, so an agent can check
+//! `EconomicTracker::can_afford` and switch to a cheaper model or trim its
+//! context first. Token counts come from the caller's tokenizer.
+
+use super::pricing::{PricingRule, SharedPricing};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Expected cost of an LLM request, from [`CostEstimator::estimate`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostEstimate {
+    /// Model the request would be sent to
+    pub model: String,
+    /// Input tokens of the request
+    pub input_tokens: u64,
+    /// Output tokens the request is expected to produce
+    pub expected_output_tokens: u64,
+    /// Input price used (USD per 1M tokens)
+    pub input_price_per_million: f64,
+    /// Output price used (USD per 1M tokens)
+    pub output_price_per_million: f64,
+    /// Whether the model had no pricing and the defaults were used
+    pub default_pricing: bool,
+    /// Expected cost in USD
+    pub cost_usd: f64,
+}
+
+/// Prices LLM requests before they are sent.
+#[derive(Debug, Clone)]
+pub struct CostEstimator {
+    pricing: Arc<SharedPricing>,
+}
+
+impl CostEstimator {
+    /// Estimator reading the current prices of `pricing`, e.g. those of a
+    /// `CostObserver`.
+    pub fn new(pricing: Arc<SharedPricing>) -> Self {
+        Self { pricing }
+    }
+
+    /// Expected cost of sending `input_tokens` to `model` and receiving
+    /// `expected_output_tokens`.
+    ///
+    /// `model` may be qualified as `provider/model`. Models without pricing
+    /// are estimated at the conservative defaults.
+    pub fn estimate(
+        &self,
+        model: &str,
+        input_tokens: u64,
+        expected_output_tokens: u64,
+    ) -> CostEstimate {
+        let resolution = self.pricing.snapshot().explain(model);
+        let input_price = resolution.pricing.input;
+        let output_price = resolution.pricing.output;
+        let cost_usd = (input_tokens as f64 / 1_000_000.0) * input_price
+            + (expected_output_tokens as f64 / 1_000_000.0) * output_price;
+        CostEstimate {
+            model: model.to_string(),
+            input_tokens,
+            expected_output_tokens,
+            input_price_per_million: input_price,
+            output_price_per_million: output_price,
+            default_pricing: resolution.rule == PricingRule::Default,
+            cost_usd,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::ModelPricing;
+    use crate::cost::pricing::DEFAULT_INPUT_PRICE;
+    use std::collections::HashMap;
+
+    fn estimator() -> CostEstimator {
+        let prices = HashMap::from([
+            (
+                "anthropic/claude-sonnet-4".to_string(),
+                ModelPricing {
+                    input: 3.0,
+                    output: 15.0,
+                    ..Default::default()
+                },
+            ),
+            (
+                "gpt-4o-mini".to_string(),
+                ModelPricing {
+                    input: 0.15,
+                    output: 0.6,
+                    ..Default::default()
+                },
+            ),
+        ]);
+        CostEstimator::new(Arc::new(SharedPricing::fixed(prices)))
+    }
+
+    #[test]
+    fn estimate_prices_input_and_expected_output() {
+        let estimate = estimator().estimate("gpt-4o-mini", 1_000_000, 500_000);
+        assert!((estimate.cost_usd - 0.45).abs() < 1e-9);
+        assert!(!estimate.default_pricing);
+
+        let qualified =
+            estimator().estimate("anthropic/claude-sonnet-4-20250514", 100_000, 10_000);
+        assert!((qualified.cost_usd - 0.45).abs() < 1e-9);
+        assert!(!qualified.default_pricing);
+    }
+
+    #[test]
+    fn estimate_uses_defaults_for_unknown_models() {
+        let estimate = estimator().estimate("mystery-model", 1_000_000, 0);
+        assert!(estimate.default_pricing);
+        assert!((estimate.cost_usd - DEFAULT_INPUT_PRICE).abs() < 1e-9);
+    }
+}
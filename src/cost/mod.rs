@@ -1,8 +1,20 @@
+pub mod estimate;
+pub mod pricing;
 pub mod tracker;
 pub mod types;
 
 // Re-exported for potential external use (public API)
 #[allow(unused_imports)]
+pub use estimate::{CostEstimate, CostEstimator};
+#[allow(unused_imports)]
+pub use pricing::{
+    PricingImportReport, PricingReload, PricingResolution, PricingResolver, PricingRule,
+    PricingTable, SharedPricing,
+};
+#[allow(unused_imports)]
 pub use tracker::CostTracker;
 #[allow(unused_imports)]
-pub use types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
+pub use types::{
+    BudgetCheck, CostRecord, CostSummary, ImageSizeClass, MergedSessionSummary, ModelStats,
+    SessionCost, TokenUsage, ToolCost, UsagePeriod,
+};
@@ -0,0 +1,718 @@
+//! Model pricing imported from LiteLLM-style model price lists.
+//!
+//! The community `model_prices_and_context_window.json` maps model names to
+//! per-token costs. [`PricingTable`] converts those to the per-million
+//! [`ModelPricing`] entries used by `CostObserver`, so `[cost.prices]` only
+//! needs the models whose pricing differs.
+//!
+//! [`PricingResolver`] finds the price of a model in those entries, falling
+//! back to conservative defaults; `CostObserver`, `CostEstimator`, and the
+//! economic tracker all price calls through it, so they agree on the cost of
+//! a call. [`PricingResolver::explain`] tells which rule matched.
+//!
+//! [`SharedPricing`] holds the merged prices for long-running agents and can
+//! reload them from the pricing file while the agent runs.
+
+use crate::config::schema::ModelPricing;
+use anyhow::{bail, Context, Result};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, SystemTime};
+
+/// Top-level key LiteLLM uses to document the entry format.
+const SAMPLE_SPEC_KEY: &str = "sample_spec";
+
+/// Input price for models without pricing (USD per 1M tokens); deliberately
+/// conservative.
+pub const DEFAULT_INPUT_PRICE: f64 = 3.0;
+/// Output price for models without pricing (USD per 1M tokens).
+pub const DEFAULT_OUTPUT_PRICE: f64 = 15.0;
+
+/// Name tokens an entry needs before it prices other models of its family,
+/// so `o1` or `gpt` alone never match by prefix.
+const MIN_FAMILY_TOKENS: usize = 2;
+
+/// Models already warned about matching several entries equally well.
+static AMBIGUOUS_WARNED: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+
+/// Outcome of [`PricingTable::from_litellm_json`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PricingImportReport {
+    /// Entries converted to per-million pricing
+    pub imported: usize,
+    /// Entries without valid input and output costs per token
+    pub skipped: usize,
+}
+
+/// Per-model pricing (USD per 1M tokens), keyed like `[cost.prices]`.
+#[derive(Debug, Clone, Default)]
+pub struct PricingTable {
+    prices: HashMap<String, ModelPricing>,
+}
+
+impl PricingTable {
+    /// Parse a LiteLLM-style pricing JSON, given inline or as a file path.
+    ///
+    /// Each entry needs `input_cost_per_token` and `output_cost_per_token`;
+    /// entries without them (image or audio models, malformed values) are
+    /// skipped and counted. Model names without a `/` are prefixed with
+    /// their `litellm_provider`, matching the `provider/model` keys of
+    /// `[cost.prices]`.
+    pub fn from_litellm_json(path_or_str: &str) -> Result<(Self, PricingImportReport)> {
+        let json = if path_or_str.trim_start().starts_with('{') {
+            path_or_str.to_string()
+        } else {
+            fs::read_to_string(path_or_str)
+                .with_context(|| format!("Failed to read pricing file {path_or_str}"))?
+        };
+        let entries: serde_json::Value =
+            serde_json::from_str(&json).context("Failed to parse pricing JSON")?;
+        let Some(entries) = entries.as_object() else {
+            bail!("Pricing JSON must be an object keyed by model name");
+        };
+
+        let mut table = Self::default();
+        let mut report = PricingImportReport::default();
+        for (model, entry) in entries {
+            if model == SAMPLE_SPEC_KEY {
+                continue;
+            }
+            let Some(pricing) = parse_entry(entry) else {
+                report.skipped += 1;
+                continue;
+            };
+            let key = match entry.get("litellm_provider").and_then(|p| p.as_str()) {
+                Some(provider) if !model.contains('/') => format!("{provider}/{model}"),
+                _ => model.clone(),
+            };
+            table.prices.insert(key, pricing);
+            report.imported += 1;
+        }
+        Ok((table, report))
+    }
+
+    /// Pricing for a `provider/model` key.
+    pub fn get(&self, model: &str) -> Option<&ModelPricing> {
+        self.prices.get(model)
+    }
+
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    /// Add the imported entries to `prices`, keeping entries already there.
+    ///
+    /// Returns the number of imported entries left out because `prices`
+    /// (the user's configuration) already defines the model.
+    pub fn merge_into(self, prices: &mut HashMap<String, ModelPricing>) -> usize {
+        let mut shadowed = 0;
+        for (model, pricing) in self.prices {
+            match prices.entry(model) {
+                Entry::Occupied(_) => shadowed += 1,
+                Entry::Vacant(entry) => {
+                    entry.insert(pricing);
+                }
+            }
+        }
+        shadowed
+    }
+}
+
+/// Rule by which [`PricingResolver`] found the price of a model, in the
+/// order the rules are tried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PricingRule {
+    /// Entry keyed `provider/model`
+    ProviderModel,
+    /// Entry keyed by the bare model name
+    Model,
+    /// Entry naming the same model once dashes and dots are treated alike
+    /// (`claude-3-5-haiku` matches `claude-3.5-haiku`)
+    NormalizedName,
+    /// Entry whose name tokens start the requested model's
+    /// (`claude-sonnet-4` matches `claude-sonnet-4-20250514`, but `gpt-4`
+    /// does not match `gpt-4o-mini`)
+    Family,
+    /// No entry matched; the default prices apply
+    Default,
+}
+
+/// How [`PricingResolver::explain`] priced a model.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PricingResolution {
+    /// Rule that matched
+    pub rule: PricingRule,
+    /// Key of the entry used (`None` for defaults)
+    pub matched_key: Option<String>,
+    /// Price applied (USD per 1M tokens)
+    pub pricing: ModelPricing,
+}
+
+/// Model prices with the lookup rules used to price an LLM call.
+#[derive(Debug, Clone)]
+pub struct PricingResolver {
+    prices: HashMap<String, ModelPricing>,
+    default_pricing: ModelPricing,
+}
+
+impl PricingResolver {
+    /// Resolver over `prices` with the default prices for unknown models.
+    pub fn new(prices: HashMap<String, ModelPricing>) -> Self {
+        Self {
+            prices,
+            default_pricing: ModelPricing {
+                input: DEFAULT_INPUT_PRICE,
+                output: DEFAULT_OUTPUT_PRICE,
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Use `pricing` for models without an entry.
+    pub fn with_default_pricing(mut self, pricing: ModelPricing) -> Self {
+        self.default_pricing = pricing;
+        self
+    }
+
+    /// Prices applied to models without an entry.
+    pub fn default_pricing(&self) -> &ModelPricing {
+        &self.default_pricing
+    }
+
+    /// Pricing entries keyed by `provider/model` or model name.
+    pub fn prices(&self) -> &HashMap<String, ModelPricing> {
+        &self.prices
+    }
+
+    /// Entry stored under exactly `key`.
+    pub fn get(&self, key: &str) -> Option<&ModelPricing> {
+        self.prices.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.prices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.prices.is_empty()
+    }
+
+    /// Price of `model` served by `provider`.
+    pub fn resolve(&self, provider: &str, model: &str) -> ModelPricing {
+        self.explain_for(provider, model).pricing
+    }
+
+    /// How `model` is priced; `model` may be qualified as `provider/model`.
+    pub fn explain(&self, model: &str) -> PricingResolution {
+        let resolution = self.explain_for("", model);
+        if resolution.rule != PricingRule::Default {
+            return resolution;
+        }
+        match model.split_once('/') {
+            Some((provider, model)) => self.explain_for(provider, model),
+            None => resolution,
+        }
+    }
+
+    /// How `model` served by `provider` is priced. An empty `provider` skips
+    /// the `provider/model` lookup.
+    ///
+    /// Names are compared token by token, never by substring. When several
+    /// entries match equally well at different prices, the first key in
+    /// order is used and a warning is logged once per model.
+    pub fn explain_for(&self, provider: &str, model: &str) -> PricingResolution {
+        let found = |rule, key: &str, pricing: &ModelPricing| PricingResolution {
+            rule,
+            matched_key: Some(key.to_string()),
+            pricing: pricing.clone(),
+        };
+
+        // Try exact match first: "provider/model"
+        if !provider.is_empty() {
+            let full_name = format!("{provider}/{model}");
+            if let Some(pricing) = self.prices.get(&full_name) {
+                return found(PricingRule::ProviderModel, &full_name, pricing);
+            }
+        }
+
+        // Try just the model name
+        if let Some(pricing) = self.prices.get(model) {
+            return found(PricingRule::Model, model, pricing);
+        }
+
+        // Then entries for the same model or its family, by name tokens.
+        // Same-name entries win, then those of the serving provider, then
+        // the longest prefix.
+        let model_tokens = name_tokens(model);
+        let mut matches: Vec<((bool, bool, usize), &String, &ModelPricing)> = Vec::new();
+        for (key, pricing) in &self.prices {
+            let key_provider = key.split_once('/').map_or("", |(key_provider, _)| key_provider);
+            let key_tokens = name_tokens(key);
+            let same_name = key_tokens == model_tokens;
+            if !same_name
+                && (key_tokens.len() < MIN_FAMILY_TOKENS || !model_tokens.starts_with(&key_tokens))
+            {
+                continue;
+            }
+            let own_provider = !provider.is_empty() && key_provider == provider;
+            matches.push(((same_name, own_provider, key_tokens.len()), key, pricing));
+        }
+        if let Some(best) = matches.iter().map(|(rank, ..)| *rank).max() {
+            matches.retain(|(rank, ..)| *rank == best);
+            matches.sort_by(|a, b| a.1.cmp(b.1));
+            let (_, key, pricing) = matches[0];
+            let ambiguous = matches.iter().any(|(_, _, other)| {
+                other.input != pricing.input || other.output != pricing.output
+            });
+            if ambiguous && AMBIGUOUS_WARNED.lock().insert(model.to_string()) {
+                let keys: Vec<&str> = matches.iter().map(|(_, key, _)| key.as_str()).collect();
+                tracing::warn!(
+                    "Model {model} matches several priced entries equally well ({}); using {key}",
+                    keys.join(", ")
+                );
+            }
+            let rule = if best.0 {
+                PricingRule::NormalizedName
+            } else {
+                PricingRule::Family
+            };
+            return found(rule, key, pricing);
+        }
+
+        PricingResolution {
+            rule: PricingRule::Default,
+            matched_key: None,
+            pricing: self.default_pricing.clone(),
+        }
+    }
+}
+
+/// Entry counts before and after [`SharedPricing::reload_pricing`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PricingReload {
+    /// Entries in the table that was replaced
+    pub previous_entries: usize,
+    /// Entries in the new table
+    pub entries: usize,
+    /// Import outcome for the pricing file
+    pub report: PricingImportReport,
+}
+
+/// Model prices that can be replaced while the agent runs.
+///
+/// Readers take a [`snapshot`](Self::snapshot) and price a whole call from
+/// it, so a reload never mixes input and output prices of different tables.
+/// Entries from `[cost.prices]` always take precedence over the file.
+#[derive(Debug)]
+pub struct SharedPricing {
+    /// Prices from the configuration
+    configured: HashMap<String, ModelPricing>,
+    /// LiteLLM-style pricing file merged under `configured`
+    pricing_file: Option<PathBuf>,
+    current: RwLock<Arc<PricingResolver>>,
+    /// Modification time of the pricing file when it was last loaded
+    loaded_modified: Mutex<Option<SystemTime>>,
+}
+
+impl SharedPricing {
+    /// Prices that never change.
+    pub fn fixed(prices: HashMap<String, ModelPricing>) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(PricingResolver::new(prices.clone()))),
+            configured: prices,
+            pricing_file: None,
+            loaded_modified: Mutex::new(None),
+        }
+    }
+
+    /// `configured` prices completed with the entries of `pricing_file`.
+    pub fn from_file(
+        configured: HashMap<String, ModelPricing>,
+        pricing_file: impl Into<PathBuf>,
+    ) -> Result<Self> {
+        let pricing = Self {
+            pricing_file: Some(pricing_file.into()),
+            ..Self::fixed(configured)
+        };
+        pricing.reload_pricing()?;
+        Ok(pricing)
+    }
+
+    /// The current prices.
+    pub fn snapshot(&self) -> Arc<PricingResolver> {
+        Arc::clone(&self.current.read())
+    }
+
+    /// Price models without an entry at `pricing`, e.g. the `[cost]`
+    /// defaults. Kept across reloads.
+    pub fn set_default_pricing(&self, pricing: ModelPricing) {
+        let mut current = self.current.write();
+        let resolver = PricingResolver::clone(&current).with_default_pricing(pricing);
+        *current = Arc::new(resolver);
+    }
+
+    /// Re-read the pricing file and swap in the new table.
+    ///
+    /// The file is fully parsed before anything is replaced; on error the
+    /// current table stays in use.
+    pub fn reload_pricing(&self) -> Result<PricingReload> {
+        let Some(path) = &self.pricing_file else {
+            bail!("No pricing file to reload");
+        };
+        let modified = file_modified(path);
+        let (table, report) = PricingTable::from_litellm_json(&path.to_string_lossy())?;
+        let mut prices = self.configured.clone();
+        table.merge_into(&mut prices);
+
+        let entries = prices.len();
+        let default_pricing = self.current.read().default_pricing.clone();
+        let resolver = PricingResolver::new(prices).with_default_pricing(default_pricing);
+        let previous = std::mem::replace(&mut *self.current.write(), Arc::new(resolver));
+        let previous_entries = previous.len();
+        *self.loaded_modified.lock() = modified;
+        tracing::info!(
+            "Reloaded model prices from {}: {previous_entries} -> {entries} entries ({} skipped)",
+            path.display(),
+            report.skipped
+        );
+        Ok(PricingReload {
+            previous_entries,
+            entries,
+            report,
+        })
+    }
+
+    /// Reload if the pricing file changed since it was last loaded.
+    pub fn reload_if_modified(&self) -> Result<Option<PricingReload>> {
+        let Some(path) = &self.pricing_file else {
+            return Ok(None);
+        };
+        if file_modified(path) == *self.loaded_modified.lock() {
+            return Ok(None);
+        }
+        self.reload_pricing().map(Some)
+    }
+
+    /// Check the pricing file every `interval` and reload it when its
+    /// modification time changes. Reload failures are logged and the old
+    /// prices kept. The task ends once the pricing is dropped.
+    ///
+    /// Must be called within a Tokio runtime.
+    pub fn watch_pricing_file(self: &Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        let pricing: Weak<Self> = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(pricing) = pricing.upgrade() else {
+                    break;
+                };
+                if let Err(error) = pricing.reload_if_modified() {
+                    tracing::warn!("Keeping current model prices: {error:#}");
+                    // Do not retry the same broken file on every tick
+                    *pricing.loaded_modified.lock() =
+                        pricing.pricing_file.as_deref().and_then(file_modified);
+                }
+            }
+        })
+    }
+}
+
+/// Lowercase tokens of a model name without its provider prefix, split at
+/// `-`, `.`, `_`, and `:`, so `claude-3.5-sonnet` and `claude-3-5-sonnet`
+/// compare equal.
+fn name_tokens(model: &str) -> Vec<String> {
+    model
+        .rsplit('/')
+        .next()
+        .unwrap_or(model)
+        .split(['-', '.', '_', ':'])
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Modification time of `path`, if it can be read.
+pub(crate) fn file_modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+fn parse_entry(entry: &serde_json::Value) -> Option<ModelPricing> {
+    let per_million = |field: &str| {
+        let cost = entry.get(field)?.as_f64()?;
+        (cost.is_finite() && cost >= 0.0).then_some(cost * 1_000_000.0)
+    };
+    Some(ModelPricing {
+        input: per_million("input_cost_per_token")?,
+        output: per_million("output_cost_per_token")?,
+        cache_read: per_million("cache_read_input_token_cost"),
+        cache_write: per_million("cache_creation_input_token_cost"),
+        reasoning: per_million("output_cost_per_reasoning_token"),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    const FIXTURE: &str = r#"{
+        "sample_spec": {
+            "input_cost_per_token": 0.0,
+            "output_cost_per_token": 0.0,
+            "litellm_provider": "one of https://docs.litellm.ai/docs/providers"
+        },
+        "gpt-4o": {
+            "input_cost_per_token": 2.5e-6,
+            "output_cost_per_token": 1e-5,
+            "litellm_provider": "openai",
+            "mode": "chat"
+        },
+        "claude-3-haiku-20240307": {
+            "input_cost_per_token": 2.5e-7,
+            "output_cost_per_token": 1.25e-6,
+            "cache_read_input_token_cost": 3e-8,
+            "cache_creation_input_token_cost": 3e-7,
+            "litellm_provider": "anthropic"
+        },
+        "openrouter/meta-llama/llama-3-70b-instruct": {
+            "input_cost_per_token": 5.9e-7,
+            "output_cost_per_token": 7.9e-7,
+            "litellm_provider": "openrouter"
+        },
+        "dall-e-3": {
+            "input_cost_per_pixel": 1e-8,
+            "litellm_provider": "openai",
+            "mode": "image_generation"
+        },
+        "broken-model": {
+            "input_cost_per_token": "free",
+            "output_cost_per_token": 1e-6
+        }
+    }"#;
+
+    fn assert_price(pricing: &ModelPricing, input: f64, output: f64) {
+        assert!((pricing.input - input).abs() < 1e-9);
+        assert!((pricing.output - output).abs() < 1e-9);
+    }
+
+    #[test]
+    fn litellm_entries_are_converted_to_per_million_pricing() {
+        let (table, report) = PricingTable::from_litellm_json(FIXTURE).unwrap();
+        assert_eq!(
+            report,
+            PricingImportReport {
+                imported: 3,
+                skipped: 2,
+            }
+        );
+        assert_price(table.get("openai/gpt-4o").unwrap(), 2.5, 10.0);
+        assert_price(
+            table.get("anthropic/claude-3-haiku-20240307").unwrap(),
+            0.25,
+            1.25,
+        );
+        let haiku = table.get("anthropic/claude-3-haiku-20240307").unwrap();
+        assert!((haiku.cache_read.unwrap() - 0.03).abs() < 1e-9);
+        assert!((haiku.cache_write.unwrap() - 0.3).abs() < 1e-9);
+        assert!(haiku.reasoning.is_none());
+        assert!(table.get("openai/gpt-4o").unwrap().cache_read.is_none());
+        // Names that already carry a provider prefix are kept as-is
+        assert_price(
+            table
+                .get("openrouter/meta-llama/llama-3-70b-instruct")
+                .unwrap(),
+            0.59,
+            0.79,
+        );
+        assert!(table.get("openai/dall-e-3").is_none());
+
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("prices.json");
+        fs::write(&path, FIXTURE).unwrap();
+        let (from_file, _) = PricingTable::from_litellm_json(path.to_str().unwrap()).unwrap();
+        assert_eq!(from_file.len(), 3);
+
+        assert!(PricingTable::from_litellm_json("[1, 2]").is_err());
+        assert!(PricingTable::from_litellm_json("/nonexistent/prices.json").is_err());
+    }
+
+    #[test]
+    fn configured_prices_take_precedence_over_imports() {
+        let (table, _) = PricingTable::from_litellm_json(FIXTURE).unwrap();
+        let mut prices = HashMap::new();
+        prices.insert(
+            "openai/gpt-4o".to_string(),
+            ModelPricing {
+                input: 2.0,
+                output: 8.0,
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(table.merge_into(&mut prices), 1);
+        assert_eq!(prices.len(), 3);
+        assert_price(&prices["openai/gpt-4o"], 2.0, 8.0);
+        assert_price(&prices["anthropic/claude-3-haiku-20240307"], 0.25, 1.25);
+    }
+
+    fn pricing(input: f64, output: f64) -> ModelPricing {
+        ModelPricing { input, output, ..Default::default() }
+    }
+
+    #[test]
+    fn resolver_uses_default_pricing_for_unknown_models() {
+        let resolver = PricingResolver::new(HashMap::new());
+        let resolution = resolver.explain_for("unknown", "mystery-model");
+        assert_eq!(resolution.rule, PricingRule::Default);
+        assert_eq!(resolution.matched_key, None);
+        assert_price(&resolution.pricing, DEFAULT_INPUT_PRICE, DEFAULT_OUTPUT_PRICE);
+
+        let resolver = resolver.with_default_pricing(pricing(1.0, 2.0));
+        assert_price(&resolver.resolve("unknown", "mystery-model"), 1.0, 2.0);
+    }
+
+    #[test]
+    fn resolver_matches_model_family() {
+        let prices = HashMap::from([("openai/gpt-4o".to_string(), pricing(5.0, 15.0))]);
+        let resolver = PricingResolver::new(prices);
+
+        // Model name with version suffix should still match
+        let resolution = resolver.explain_for("openai", "gpt-4o-2024-05-13");
+        assert_eq!(resolution.rule, PricingRule::Family);
+        assert_eq!(resolution.matched_key.as_deref(), Some("openai/gpt-4o"));
+        assert_price(&resolution.pricing, 5.0, 15.0);
+    }
+
+    #[test]
+    fn resolver_family_match_never_crosses_model_names() {
+        let resolver = PricingResolver::new(HashMap::from([
+            ("gpt-4".to_string(), pricing(30.0, 60.0)),
+            ("gpt-4o-mini".to_string(), pricing(0.15, 0.6)),
+            ("claude-3-5-sonnet".to_string(), pricing(3.0, 15.0)),
+            ("claude-3-5-haiku".to_string(), pricing(0.8, 4.0)),
+            ("o1".to_string(), pricing(15.0, 60.0)),
+        ]));
+        let key = |model: &str| resolver.explain_for("", model).matched_key;
+
+        assert_eq!(key("gpt-4o-mini-2024-07-18").as_deref(), Some("gpt-4o-mini"));
+        assert_eq!(key("gpt-4-0613").as_deref(), Some("gpt-4"));
+        assert_eq!(key("gpt-4o"), None);
+        assert_eq!(
+            key("claude-3-5-haiku-20241022").as_deref(),
+            Some("claude-3-5-haiku")
+        );
+        assert_eq!(
+            key("claude-3.5-sonnet-latest").as_deref(),
+            Some("claude-3-5-sonnet")
+        );
+        // Single-token entries only match by name
+        assert_eq!(key("o1").as_deref(), Some("o1"));
+        assert_eq!(key("o1-mini"), None);
+        assert_eq!(key("gpt-4o1"), None);
+    }
+
+    #[test]
+    fn resolver_prefers_the_serving_provider_then_the_longest_prefix() {
+        let resolver = PricingResolver::new(HashMap::from([
+            ("openai/gpt-4o".to_string(), pricing(2.5, 10.0)),
+            ("azure/gpt-4o".to_string(), pricing(5.0, 15.0)),
+            ("claude-sonnet-4".to_string(), pricing(3.0, 15.0)),
+            ("claude-sonnet-4-5".to_string(), pricing(3.3, 16.5)),
+        ]));
+
+        let resolution = resolver.explain_for("azure", "gpt-4o-2024-08-06");
+        assert_eq!(resolution.matched_key.as_deref(), Some("azure/gpt-4o"));
+        // Equally good matches at different prices pick the first key
+        let resolution = resolver.explain_for("", "gpt-4o-2024-08-06");
+        assert_eq!(resolution.matched_key.as_deref(), Some("azure/gpt-4o"));
+
+        let resolution = resolver.explain_for("anthropic", "claude-sonnet-4-5-20250929");
+        assert_eq!(resolution.rule, PricingRule::Family);
+        assert_eq!(resolution.matched_key.as_deref(), Some("claude-sonnet-4-5"));
+    }
+
+    #[test]
+    fn resolver_explains_which_rule_matched() {
+        let resolver = PricingResolver::new(HashMap::from([
+            ("anthropic/claude-sonnet-4".to_string(), pricing(3.0, 15.0)),
+            ("gpt-4o-mini".to_string(), pricing(0.15, 0.6)),
+            ("claude-3.5-haiku".to_string(), pricing(0.8, 4.0)),
+        ]));
+
+        let rule = |provider: &str, model: &str| resolver.explain_for(provider, model).rule;
+        assert_eq!(rule("anthropic", "claude-sonnet-4"), PricingRule::ProviderModel);
+        assert_eq!(rule("openai", "gpt-4o-mini"), PricingRule::Model);
+        assert_eq!(rule("anthropic", "claude-3-5-haiku"), PricingRule::NormalizedName);
+
+        // A qualified name is looked up as `provider/model`
+        let resolution = resolver.explain("anthropic/claude-sonnet-4");
+        assert_eq!(resolution.rule, PricingRule::Model);
+        assert_eq!(resolution.matched_key.as_deref(), Some("anthropic/claude-sonnet-4"));
+    }
+
+    #[tokio::test]
+    async fn shared_pricing_reloads_and_keeps_old_table_on_errors() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("prices.json");
+        fs::write(&path, FIXTURE).unwrap();
+        let mut configured = HashMap::new();
+        configured.insert(
+            "openai/gpt-4o".to_string(),
+            ModelPricing {
+                input: 2.0,
+                output: 8.0,
+                ..Default::default()
+            },
+        );
+        let pricing = Arc::new(SharedPricing::from_file(configured, &path).unwrap());
+        let before = pricing.snapshot();
+        assert_eq!(before.len(), 3);
+        assert!(pricing.reload_if_modified().unwrap().is_none());
+
+        fs::write(
+            &path,
+            r#"{"gpt-4o-mini": {"input_cost_per_token": 1.5e-7, "output_cost_per_token": 6e-7,
+                "litellm_provider": "openai"}}"#,
+        )
+        .unwrap();
+        let reload = pricing.reload_pricing().unwrap();
+        assert_eq!((reload.previous_entries, reload.entries), (3, 2));
+        // Snapshots taken before the reload are unchanged
+        assert_eq!(before.len(), 3);
+        let after = pricing.snapshot();
+        assert_price(after.get("openai/gpt-4o").unwrap(), 2.0, 8.0);
+        assert_price(after.get("openai/gpt-4o-mini").unwrap(), 0.15, 0.6);
+
+        fs::write(&path, "{ not json").unwrap();
+        assert!(pricing.reload_pricing().is_err());
+        assert_eq!(pricing.snapshot().len(), 2);
+
+        // The watcher picks up a fixed file
+        fs::write(&path, FIXTURE).unwrap();
+        let watcher = pricing.watch_pricing_file(Duration::from_millis(10));
+        for _ in 0..100 {
+            if pricing.snapshot().len() == 3 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(pricing.snapshot().len(), 3);
+        watcher.abort();
+
+        assert!(SharedPricing::fixed(HashMap::new())
+            .reload_pricing()
+            .is_err());
+    }
+}
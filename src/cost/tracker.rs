@@ -1,11 +1,15 @@
-use super::types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
+use super::types::{
+    BudgetCheck, CostRecord, CostSummary, MergedSessionSummary, ModelStats, SessionCost,
+    TokenUsage, ToolCost, UsagePeriod,
+};
 use crate::config::schema::CostConfig;
 use anyhow::{anyhow, Context, Result};
 use chrono::{Datelike, NaiveDate, Utc};
 use parking_lot::{Mutex, MutexGuard};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
+use std::ops::RangeBounds;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
@@ -150,6 +154,11 @@ impl CostTracker {
             .map(|record| record.usage.total_tokens)
             .sum();
         let request_count = session_costs.len();
+        let failed_call_cost: f64 = session_costs
+            .iter()
+            .filter(|record| record.usage.failed)
+            .map(|record| record.usage.cost_usd)
+            .sum();
         let by_model = build_session_model_stats(&session_costs);
 
         Ok(CostSummary {
@@ -158,6 +167,7 @@ impl CostTracker {
             monthly_cost_usd: monthly_cost,
             total_tokens,
             request_count,
+            failed_call_cost_usd: failed_call_cost,
             by_model,
         })
     }
@@ -173,6 +183,114 @@ impl CostTracker {
         let storage = self.lock_storage();
         storage.get_cost_for_month(year, month)
     }
+
+    /// Spend per conversation for usage recorded on days within `range`,
+    /// most expensive first. Usage recorded without a conversation id is
+    /// grouped under `none`.
+    pub fn cost_by_session(&self, range: impl RangeBounds<NaiveDate>) -> Result<Vec<SessionCost>> {
+        let mut by_session: HashMap<String, SessionCost> = HashMap::new();
+        self.lock_storage().for_each_record(|record| {
+            if !range.contains(&record.usage.timestamp.date_naive()) {
+                return;
+            }
+            let session_id = record
+                .usage
+                .session_id
+                .unwrap_or_else(|| "none".to_string());
+            let entry = by_session
+                .entry(session_id.clone())
+                .or_insert_with(|| SessionCost {
+                    session_id,
+                    cost_usd: 0.0,
+                    total_tokens: 0,
+                    request_count: 0,
+                });
+            entry.cost_usd += record.usage.cost_usd;
+            entry.total_tokens += record.usage.total_tokens;
+            entry.request_count += 1;
+        })?;
+
+        let mut sessions: Vec<SessionCost> = by_session.into_values().collect();
+        sessions.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then_with(|| a.session_id.cmp(&b.session_id))
+        });
+        Ok(sessions)
+    }
+
+    /// Direct spend per tool for tool calls recorded on days within
+    /// `range`, most expensive first; e.g. what web search cost this week.
+    pub fn cost_by_tool(&self, range: impl RangeBounds<NaiveDate>) -> Result<Vec<ToolCost>> {
+        let mut by_tool: HashMap<String, ToolCost> = HashMap::new();
+        self.lock_storage().for_each_record(|record| {
+            if !range.contains(&record.usage.timestamp.date_naive()) {
+                return;
+            }
+            let Some(tool) = record.usage.tool else {
+                return;
+            };
+            let entry = by_tool.entry(tool.clone()).or_insert_with(|| ToolCost {
+                tool,
+                cost_usd: 0.0,
+                call_count: 0,
+            });
+            entry.cost_usd += record.usage.cost_usd;
+            entry.call_count += 1;
+        })?;
+
+        let mut tools: Vec<ToolCost> = by_tool.into_values().collect();
+        tools.sort_by(|a, b| {
+            b.cost_usd
+                .total_cmp(&a.cost_usd)
+                .then_with(|| a.tool.cmp(&b.tool))
+        });
+        Ok(tools)
+    }
+
+    /// Merge the cost records of several sessions into one JSONL file.
+    ///
+    /// Each path is either a JSONL file or a directory whose `*.jsonl` files
+    /// are all read. Records with the same timestamp and model are counted
+    /// once (the first one read wins), and the merged records are written to
+    /// `output` oldest first, replacing any existing file.
+    pub fn merge_sessions(sessions: Vec<&Path>, output: &Path) -> Result<MergedSessionSummary> {
+        let mut records = Vec::new();
+        for session in &sessions {
+            for path in session_jsonl_files(session)? {
+                for_each_cost_record(&path, |record| records.push(record))?;
+            }
+        }
+
+        // Stable sort keeps the first-read record ahead of its duplicates
+        records.sort_by_key(|record| record.usage.timestamp);
+        let read_count = records.len();
+        let mut seen = HashSet::new();
+        records.retain(|record| seen.insert((record.usage.timestamp, record.usage.model.clone())));
+
+        if let Some(parent) = output
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create directory {}", parent.display()))?;
+        }
+        let mut file = File::create(output)
+            .with_context(|| format!("Failed to create merged cost file {}", output.display()))?;
+        for record in &records {
+            writeln!(file, "{}", serde_json::to_string(record)?)
+                .with_context(|| format!("Failed to write cost record to {}", output.display()))?;
+        }
+        file.sync_all()
+            .with_context(|| format!("Failed to sync merged cost file {}", output.display()))?;
+
+        Ok(MergedSessionSummary {
+            session_count: sessions.len(),
+            merged_record_count: records.len(),
+            duplicate_count: read_count - records.len(),
+            total_cost_usd: records.iter().map(|record| record.usage.cost_usd).sum(),
+        })
+    }
 }
 
 fn resolve_storage_path(workspace_dir: &Path) -> Result<PathBuf> {
@@ -204,6 +322,28 @@ fn resolve_storage_path(workspace_dir: &Path) -> Result<PathBuf> {
     Ok(storage_path)
 }
 
+/// JSONL files of a session path: the path itself, or the `*.jsonl` files of
+/// a directory in name order.
+fn session_jsonl_files(session: &Path) -> Result<Vec<PathBuf>> {
+    if !session.is_dir() {
+        return Ok(vec![session.to_path_buf()]);
+    }
+
+    let entries = fs::read_dir(session)
+        .with_context(|| format!("Failed to read session directory {}", session.display()))?;
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("Failed to read session directory {}", session.display()))?
+            .path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "jsonl") {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
 fn build_session_model_stats(session_costs: &[CostRecord]) -> HashMap<String, ModelStats> {
     let mut by_model: HashMap<String, ModelStats> = HashMap::new();
 
@@ -225,6 +365,48 @@ fn build_session_model_stats(session_costs: &[CostRecord]) -> HashMap<String, Mo
     by_model
 }
 
+/// Stream the cost records of a JSONL file, skipping malformed lines.
+fn for_each_cost_record<F>(path: &Path, mut on_record: F) -> Result<()>
+where
+    F: FnMut(CostRecord),
+{
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let file = File::open(path)
+        .with_context(|| format!("Failed to read cost storage from {}", path.display()))?;
+    let reader = BufReader::new(file);
+
+    for (line_number, line) in reader.lines().enumerate() {
+        let raw_line = line.with_context(|| {
+            format!(
+                "Failed to read line {} from cost storage {}",
+                line_number + 1,
+                path.display()
+            )
+        })?;
+
+        let trimmed = raw_line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str::<CostRecord>(trimmed) {
+            Ok(record) => on_record(record),
+            Err(error) => {
+                tracing::warn!(
+                    "Skipping malformed cost record at {}:{}: {error}",
+                    path.display(),
+                    line_number + 1
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Persistent storage for cost records.
 struct CostStorage {
     path: PathBuf,
@@ -262,45 +444,11 @@ impl CostStorage {
         Ok(storage)
     }
 
-    fn for_each_record<F>(&self, mut on_record: F) -> Result<()>
+    fn for_each_record<F>(&self, on_record: F) -> Result<()>
     where
         F: FnMut(CostRecord),
     {
-        if !self.path.exists() {
-            return Ok(());
-        }
-
-        let file = File::open(&self.path)
-            .with_context(|| format!("Failed to read cost storage from {}", self.path.display()))?;
-        let reader = BufReader::new(file);
-
-        for (line_number, line) in reader.lines().enumerate() {
-            let raw_line = line.with_context(|| {
-                format!(
-                    "Failed to read line {} from cost storage {}",
-                    line_number + 1,
-                    self.path.display()
-                )
-            })?;
-
-            let trimmed = raw_line.trim();
-            if trimmed.is_empty() {
-                continue;
-            }
-
-            match serde_json::from_str::<CostRecord>(trimmed) {
-                Ok(record) => on_record(record),
-                Err(error) => {
-                    tracing::warn!(
-                        "Skipping malformed cost record at {}:{}: {error}",
-                        self.path.display(),
-                        line_number + 1
-                    );
-                }
-            }
-        }
-
-        Ok(())
+        for_each_cost_record(&self.path, on_record)
     }
 
     fn rebuild_aggregates(&mut self, day: NaiveDate, year: i32, month: u32) -> Result<()> {
@@ -523,6 +671,102 @@ mod tests {
         assert!((today_cost - valid_usage.cost_usd).abs() < f64::EPSILON);
     }
 
+    fn write_session(dir: &Path, records: &[(i64, &str, f64)]) {
+        fs::create_dir_all(dir).unwrap();
+        let mut file = File::create(dir.join("costs.jsonl")).unwrap();
+        for (second, model, cost) in records {
+            let mut usage = TokenUsage::new(*model, 100, 50, 1.0, 1.0);
+            usage.timestamp = chrono::DateTime::from_timestamp(1_700_000_000 + second, 0).unwrap();
+            usage.cost_usd = *cost;
+            let record = CostRecord::new("session", usage);
+            writeln!(file, "{}", serde_json::to_string(&record).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn merge_sessions_drops_duplicates_and_sorts() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("a");
+        let b = tmp.path().join("b");
+        let c = tmp.path().join("c");
+        write_session(&a, &[(30, "m/one", 1.0), (10, "m/one", 2.0)]);
+        // Same timestamp and model as a record in `a`, plus a different model
+        write_session(&b, &[(10, "m/one", 2.0), (10, "m/two", 4.0)]);
+        write_session(&c, &[(20, "m/one", 8.0), (30, "m/one", 1.0)]);
+        fs::write(c.join("notes.txt"), "not a cost log").unwrap();
+
+        let output = tmp.path().join("merged").join("costs.jsonl");
+        let summary =
+            CostTracker::merge_sessions(vec![a.as_path(), b.as_path(), c.as_path()], &output)
+                .unwrap();
+
+        assert_eq!(summary.session_count, 3);
+        assert_eq!(summary.merged_record_count, 4);
+        assert_eq!(summary.duplicate_count, 2);
+        assert!((summary.total_cost_usd - 15.0).abs() < f64::EPSILON);
+
+        let mut merged = Vec::new();
+        for_each_cost_record(&output, |record| merged.push(record)).unwrap();
+        let seconds: Vec<i64> = merged
+            .iter()
+            .map(|record| record.usage.timestamp.timestamp() - 1_700_000_000)
+            .collect();
+        assert_eq!(seconds, [10, 10, 20, 30]);
+        assert_eq!(merged[0].usage.model, "m/one");
+        assert_eq!(merged[1].usage.model, "m/two");
+    }
+
+    #[test]
+    fn cost_by_session_groups_conversations() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        for (session, input) in [(Some("chat-a"), 1000), (Some("chat-b"), 4000), (None, 2000)] {
+            let mut usage = TokenUsage::new("test/model", input, 0, 1.0, 1.0);
+            if let Some(session) = session {
+                usage = usage.with_session_id(session);
+            }
+            tracker.record_usage(usage).unwrap();
+        }
+        let repeat = TokenUsage::new("test/model", 1000, 0, 1.0, 1.0).with_session_id("chat-a");
+        tracker.record_usage(repeat).unwrap();
+
+        let today = Utc::now().date_naive();
+        let sessions = tracker.cost_by_session(today..=today).unwrap();
+        let names: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+        assert_eq!(names, ["chat-b", "chat-a", "none"]);
+        assert_eq!(sessions[1].request_count, 2);
+        assert_eq!(sessions[1].total_tokens, 2000);
+        assert!((sessions[0].cost_usd - 0.004).abs() < 1e-12);
+
+        let yesterday = today.pred_opt().unwrap();
+        assert!(tracker.cost_by_session(..yesterday).unwrap().is_empty());
+    }
+
+    #[test]
+    fn cost_by_tool_sums_tool_calls_only() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        tracker
+            .record_usage(TokenUsage::new("test/model", 1000, 0, 1.0, 1.0))
+            .unwrap();
+        for (tool, cost) in [("web_search", 0.005), ("browser", 0.02), ("web_search", 0.005)] {
+            tracker.record_usage(TokenUsage::tool_call(tool, cost)).unwrap();
+        }
+
+        let today = Utc::now().date_naive();
+        let tools = tracker.cost_by_tool(today..=today).unwrap();
+        let names: Vec<&str> = tools.iter().map(|t| t.tool.as_str()).collect();
+        assert_eq!(names, ["browser", "web_search"]);
+        assert_eq!(tools[1].call_count, 2);
+        assert!((tools[1].cost_usd - 0.01).abs() < 1e-12);
+        assert!(tracker.get_summary().unwrap().by_model.contains_key("tool/web_search"));
+
+        let yesterday = today.pred_opt().unwrap();
+        assert!(tracker.cost_by_tool(..yesterday).unwrap().is_empty());
+    }
+
     #[test]
     fn invalid_budget_estimate_is_rejected() {
         let tmp = TempDir::new().unwrap();
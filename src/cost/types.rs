@@ -11,10 +11,28 @@ pub struct TokenUsage {
     pub output_tokens: u64,
     /// Total tokens
     pub total_tokens: u64,
+    /// Input tokens read from the prompt cache
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    /// Input tokens written to the prompt cache
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+    /// Output tokens spent on reasoning
+    #[serde(default)]
+    pub reasoning_tokens: u64,
     /// Calculated cost in USD
     pub cost_usd: f64,
     /// Timestamp of the request
     pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Conversation the request served, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    /// The request failed; its cost is wasted spend
+    #[serde(default)]
+    pub failed: bool,
+    /// Tool whose call this is, for the direct cost of a tool call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
 }
 
 impl TokenUsage {
@@ -49,11 +67,33 @@ impl TokenUsage {
             input_tokens,
             output_tokens,
             total_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+            reasoning_tokens: 0,
             cost_usd,
             timestamp: chrono::Utc::now(),
+            session_id: None,
+            failed: false,
+            tool: None,
         }
     }
 
+    /// Create a record for the direct cost of a call to `tool`, listed
+    /// under the model name `tool/<tool>`.
+    pub fn tool_call(tool: impl Into<String>, cost_usd: f64) -> Self {
+        let tool = tool.into();
+        let mut usage = Self::new(format!("tool/{tool}"), 0, 0, 0.0, 0.0);
+        usage.cost_usd = cost_usd;
+        usage.tool = Some(tool);
+        usage
+    }
+
+    /// Attribute the usage to a conversation.
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
     /// Get the total cost.
     pub fn cost(&self) -> f64 {
         self.cost_usd
@@ -68,6 +108,18 @@ pub enum UsagePeriod {
     Month,
 }
 
+/// Output size class used to price image generation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageSizeClass {
+    /// Up to 512x512
+    Small,
+    /// Up to 1024x1024
+    Medium,
+    /// Larger or HD outputs
+    Large,
+}
+
 /// A single cost record for persistent storage.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CostRecord {
@@ -122,6 +174,9 @@ pub struct CostSummary {
     pub total_tokens: u64,
     /// Number of requests
     pub request_count: usize,
+    /// Session spend on requests that failed
+    #[serde(default)]
+    pub failed_call_cost_usd: f64,
     /// Breakdown by model
     pub by_model: std::collections::HashMap<String, ModelStats>,
 }
@@ -139,6 +194,43 @@ pub struct ModelStats {
     pub request_count: usize,
 }
 
+/// Spend of one conversation, as returned by `CostTracker::cost_by_session`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionCost {
+    /// Conversation identifier (`none` for usage recorded without one)
+    pub session_id: String,
+    /// Total cost for this conversation
+    pub cost_usd: f64,
+    /// Total tokens for this conversation
+    pub total_tokens: u64,
+    /// Number of requests for this conversation
+    pub request_count: usize,
+}
+
+/// Spend on one tool, as returned by `CostTracker::cost_by_tool`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ToolCost {
+    /// Tool name
+    pub tool: String,
+    /// Total direct cost of the tool's calls
+    pub cost_usd: f64,
+    /// Number of calls with a recorded cost
+    pub call_count: usize,
+}
+
+/// Result of `CostTracker::merge_sessions`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedSessionSummary {
+    /// Number of session paths merged
+    pub session_count: usize,
+    /// Number of records written to the merged file
+    pub merged_record_count: usize,
+    /// Number of records dropped as duplicates
+    pub duplicate_count: usize,
+    /// Total cost of the merged records
+    pub total_cost_usd: f64,
+}
+
 impl Default for CostSummary {
     fn default() -> Self {
         Self {
@@ -147,6 +239,7 @@ impl Default for CostSummary {
             monthly_cost_usd: 0.0,
             total_tokens: 0,
             request_count: 0,
+            failed_call_cost_usd: 0.0,
             by_model: std::collections::HashMap::new(),
         }
     }
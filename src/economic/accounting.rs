@@ -0,0 +1,272 @@
+//! Double-entry bookkeeping export for economic records.
+//!
+//! Turns the tracker's cost, income, and refund logs into journal entries
+//! and renders them as CSV, QuickBooks IIF, or a JSON ledger.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+use super::costs::CostBreakdown;
+use super::error::Result;
+
+/// Cash account every entry posts against.
+pub const CASH_ACCOUNT: &str = "Assets:Cash";
+/// Account credited with work income.
+pub const INCOME_ACCOUNT: &str = "Income:Work";
+/// Account credited with grant income.
+pub const GRANT_INCOME_ACCOUNT: &str = "Income:Grants";
+/// Account credited with interest earned on a positive balance.
+pub const INTEREST_INCOME_ACCOUNT: &str = "Income:Interest";
+/// Account debited with interest paid on a negative balance.
+pub const INTEREST_EXPENSE_ACCOUNT: &str = "Expenses:Interest";
+/// Contra-expense account credited with refunds.
+pub const REFUNDS_ACCOUNT: &str = "Expenses:Refunds";
+/// Account balancing the opening cash balance.
+pub const OPENING_BALANCE_ACCOUNT: &str = "Equity:Opening Balance";
+/// Account absorbing balance changes without a persisted record
+/// (trading P&L, costs recorded outside a task).
+pub const ADJUSTMENTS_ACCOUNT: &str = "Equity:Adjustments";
+
+/// Output format for `EconomicTracker::export_for_accounting`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountingFormat {
+    /// Cash register CSV: `date,description,debit,credit,balance`
+    SimpleCsv,
+    /// QuickBooks IIF general journal transactions
+    QboIif,
+    /// JSON array of `{date, debit_account, credit_account, amount, memo}`
+    JsonLedger,
+}
+
+/// A single balanced journal entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerEntry {
+    /// Posting date (YYYY-MM-DD)
+    pub date: String,
+    /// Account debited
+    pub debit_account: String,
+    /// Account credited
+    pub credit_account: String,
+    /// Amount in USD (always positive)
+    pub amount: f64,
+    /// Description of the entry
+    pub memo: String,
+    /// Ordering key; not exported
+    #[serde(skip)]
+    pub(crate) timestamp: DateTime<Utc>,
+}
+
+impl LedgerEntry {
+    pub(crate) fn new(
+        timestamp: DateTime<Utc>,
+        debit_account: &str,
+        credit_account: &str,
+        amount: f64,
+        memo: impl Into<String>,
+    ) -> Self {
+        Self {
+            date: timestamp.format("%Y-%m-%d").to_string(),
+            debit_account: debit_account.to_string(),
+            credit_account: credit_account.to_string(),
+            amount,
+            memo: memo.into(),
+            timestamp,
+        }
+    }
+
+    /// Signed effect of the entry on the cash account.
+    fn cash_delta(&self) -> f64 {
+        if self.debit_account == CASH_ACCOUNT {
+            self.amount
+        } else if self.credit_account == CASH_ACCOUNT {
+            -self.amount
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Expense entries for each non-zero channel of a task's costs.
+pub(crate) fn expense_entries(
+    timestamp: DateTime<Utc>,
+    task_id: &str,
+    costs: &CostBreakdown,
+) -> Vec<LedgerEntry> {
+    [
+        ("Expenses:LLM Tokens", costs.llm_tokens),
+        ("Expenses:Search API", costs.search_api),
+        ("Expenses:OCR API", costs.ocr_api),
+        ("Expenses:Embedding API", costs.embedding_api),
+        ("Expenses:Image Generation", costs.image_api),
+        ("Expenses:Other API", costs.other_api),
+    ]
+    .into_iter()
+    .filter(|(_, amount)| *amount > 0.0)
+    .map(|(account, amount)| {
+        LedgerEntry::new(timestamp, account, CASH_ACCOUNT, amount, format!("Task {task_id}"))
+    })
+    .collect()
+}
+
+/// Net effect of the entries on the cash account.
+pub(crate) fn cash_balance(entries: &[LedgerEntry]) -> f64 {
+    entries.iter().map(LedgerEntry::cash_delta).sum()
+}
+
+/// Render entries in the requested format.
+pub(crate) fn render(entries: &[LedgerEntry], format: AccountingFormat) -> Result<String> {
+    match format {
+        AccountingFormat::SimpleCsv => Ok(render_csv(entries)),
+        AccountingFormat::QboIif => Ok(render_iif(entries)),
+        AccountingFormat::JsonLedger => Ok(serde_json::to_string_pretty(entries)?),
+    }
+}
+
+fn render_csv(entries: &[LedgerEntry]) -> String {
+    let mut out = String::from("date,description,debit,credit,balance\n");
+    let mut balance = 0.0;
+    for entry in entries {
+        let delta = entry.cash_delta();
+        balance += delta;
+        let (debit, credit) = if delta >= 0.0 { (delta, 0.0) } else { (0.0, -delta) };
+        let description = if delta >= 0.0 {
+            format!("{} ({})", entry.memo, entry.credit_account)
+        } else {
+            format!("{} ({})", entry.memo, entry.debit_account)
+        };
+        let _ = writeln!(
+            out,
+            "{},{},{:.6},{:.6},{:.6}",
+            entry.date,
+            csv_field(&description),
+            debit,
+            credit,
+            balance
+        );
+    }
+    out
+}
+
+/// QuickBooks IIF: one TRNS/SPL pair per entry. Positive amounts debit,
+/// negative amounts credit; QuickBooks only keeps cents, so both lines are
+/// rounded identically and each transaction still balances.
+fn render_iif(entries: &[LedgerEntry]) -> String {
+    let mut out = String::from(
+        "!TRNS\tTRNSTYPE\tDATE\tACCNT\tAMOUNT\tMEMO\n!SPL\tTRNSTYPE\tDATE\tACCNT\tAMOUNT\tMEMO\n!ENDTRNS\n",
+    );
+    for entry in entries {
+        let date = entry.timestamp.format("%m/%d/%Y");
+        let memo = entry.memo.replace(['\t', '\n', '\r'], " ");
+        let _ = writeln!(
+            out,
+            "TRNS\tGENERAL JOURNAL\t{date}\t{}\t{:.2}\t{memo}",
+            entry.debit_account, entry.amount
+        );
+        let _ = writeln!(
+            out,
+            "SPL\tGENERAL JOURNAL\t{date}\t{}\t{:.2}\t{memo}",
+            entry.credit_account, -entry.amount
+        );
+        out.push_str("ENDTRNS\n");
+    }
+    out
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{EconomicConfig, EconomicTracker};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn tracker_with_activity(tmp: &TempDir) -> EconomicTracker {
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(1.0), Duration::ZERO).unwrap();
+        let charge = tracker.last_charge_id().unwrap();
+        tracker.track_flat_api_call(0.5, "tavily_search");
+        tracker.end_task("task-1").unwrap();
+        tracker.add_work_income(10.0, "task-1", 0.9, "report, final").unwrap();
+        tracker.track_refund(&charge, 0.25, "provider outage").unwrap();
+        tracker.add_trading_profit(2.0, "");
+        tracker
+    }
+
+    #[test]
+    fn simple_csv_debits_and_credits_balance() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = tracker_with_activity(&tmp);
+
+        let csv = tracker.export_for_accounting(AccountingFormat::SimpleCsv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some("date,description,debit,credit,balance"));
+
+        let (mut debits, mut credits, mut running, mut rows) = (0.0, 0.0, 0.0, 0);
+        for line in lines {
+            // Descriptions may be quoted; the numeric columns are always last
+            let columns: Vec<&str> = line.rsplitn(4, ',').collect();
+            let balance: f64 = columns[0].parse().unwrap();
+            let credit: f64 = columns[1].parse().unwrap();
+            let debit: f64 = columns[2].parse().unwrap();
+            assert!(debit == 0.0 || credit == 0.0);
+            debits += debit;
+            credits += credit;
+            running += debit - credit;
+            assert!((running - balance).abs() < 1e-6);
+            rows += 1;
+        }
+
+        // Opening, LLM and search expenses, income, refund, trading adjustment
+        assert_eq!(rows, 6);
+        assert!((debits - (100.0 + 10.0 + 0.25 + 2.0)).abs() < 1e-6);
+        assert!((credits - 1.5).abs() < 1e-6);
+        assert!((debits - credits - tracker.get_balance()).abs() < 1e-6);
+        assert!(csv.contains("\"Work income for task task-1: report, final (Income:Work)\""));
+    }
+
+    #[test]
+    fn json_ledger_and_iif_entries_balance() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = tracker_with_activity(&tmp);
+
+        let json = tracker.export_for_accounting(AccountingFormat::JsonLedger).unwrap();
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(entries.len(), 6);
+        for entry in &entries {
+            for field in ["date", "debit_account", "credit_account", "amount", "memo"] {
+                assert!(entry.get(field).is_some(), "missing {field}");
+            }
+        }
+        assert_eq!(entries[0]["credit_account"], OPENING_BALANCE_ACCOUNT);
+
+        let iif = tracker.export_for_accounting(AccountingFormat::QboIif).unwrap();
+        assert_eq!(iif.lines().filter(|l| l.starts_with("TRNS\t")).count(), 6);
+        let mut transaction = 0.0_f64;
+        for line in iif.lines().filter(|l| !l.starts_with('!')) {
+            if line == "ENDTRNS" {
+                assert!(transaction.abs() < 1e-9);
+                transaction = 0.0;
+            } else {
+                let amount: f64 = line.split('\t').nth(4).unwrap().parse().unwrap();
+                transaction += amount;
+            }
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! JSONL log rotation for the economic tracker.
+//!
+//! Moves records older than a cutoff out of the active JSONL files into
+//! gzip-compressed archives under `archive/`, so that replaying the active
+//! files at startup stays fast for long-running agents.
+
+use super::error::{IoResultExt, Result};
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Outcome of `EconomicTracker::drain_to_archive`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArchiveSummary {
+    /// Number of records moved out of the active files (archived or dropped)
+    pub records_archived: usize,
+    /// Bytes removed from the active files
+    pub bytes_freed: u64,
+    /// Archive files written (`archive/<name>-<timestamp>.jsonl.gz`)
+    pub archive_files: Vec<PathBuf>,
+}
+
+/// How records in a file are dated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum RecordClock {
+    /// `timestamp_end` or `timestamp` (RFC 3339)
+    Timestamp,
+    /// `date` (YYYY-MM-DD); a day counts as older once it has fully elapsed.
+    /// The last record is always kept since it carries the current balance.
+    BalanceDate,
+}
+
+/// Active JSONL logs that can be drained, with how their records are dated.
+pub(crate) const ARCHIVED_LOGS: [(&str, RecordClock); 6] = [
+    ("balance.jsonl", RecordClock::BalanceDate),
+    ("token_costs.jsonl", RecordClock::Timestamp),
+    ("task_completions.jsonl", RecordClock::Timestamp),
+    ("refunds.jsonl", RecordClock::Timestamp),
+    ("grant_income.jsonl", RecordClock::Timestamp),
+    ("interest.jsonl", RecordClock::Timestamp),
+];
+
+/// Move every dated record older than `cutoff` from `path` into a new
+/// archive under `archive_dir`, or drop them when there is none.
+///
+/// Blank lines are dropped and lines without a recognizable date are kept.
+pub(crate) fn drain_file(
+    path: &Path,
+    archive_dir: Option<&Path>,
+    cutoff: DateTime<Utc>,
+    clock: RecordClock,
+    summary: &mut ArchiveSummary,
+) -> Result<()> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let lines = read_lines(path).at_path(path)?;
+
+    let last = lines.len().saturating_sub(1);
+    let (archived, kept): (Vec<_>, Vec<_>) = lines.into_iter().enumerate().partition(|(i, line)| {
+        if clock == RecordClock::BalanceDate && *i == last {
+            return false;
+        }
+        record_time(line, clock).is_some_and(|time| time < cutoff)
+    });
+    if archived.is_empty() {
+        return Ok(());
+    }
+
+    let archive_path = match archive_dir {
+        Some(archive_dir) => {
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("records");
+            let archive_path = archive_dir.join(format!(
+                "{stem}-{}.jsonl.gz",
+                Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+            ));
+            write_archive(&archive_path, archived.iter().map(|(_, line)| line))
+                .at_path(&archive_path)?;
+            Some(archive_path)
+        }
+        None => None,
+    };
+
+    // Rewrite the active file via a temp file so a crash never truncates it
+    let before_len = fs::metadata(path).at_path(path)?.len();
+    let tmp_path = path.with_extension("jsonl.tmp");
+    let write_kept = || -> std::io::Result<()> {
+        let mut tmp = File::create(&tmp_path)?;
+        for (_, line) in &kept {
+            writeln!(tmp, "{line}")?;
+        }
+        tmp.sync_all()
+    };
+    write_kept().at_path(&tmp_path)?;
+    fs::rename(&tmp_path, path).at_path(path)?;
+    let after_len = fs::metadata(path).at_path(path)?.len();
+
+    summary.records_archived += archived.len();
+    summary.bytes_freed += before_len.saturating_sub(after_len);
+    summary.archive_files.extend(archive_path);
+
+    Ok(())
+}
+
+/// Non-blank lines of `path`.
+fn read_lines(path: &Path) -> std::io::Result<Vec<String>> {
+    let mut lines = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            lines.push(line);
+        }
+    }
+    Ok(lines)
+}
+
+/// Write `lines` to a new gzip archive at `path`.
+fn write_archive<'a>(path: &Path, lines: impl Iterator<Item = &'a String>) -> std::io::Result<()> {
+    let mut encoder = GzEncoder::new(File::create(path)?, Compression::default());
+    for line in lines {
+        writeln!(encoder, "{line}")?;
+    }
+    encoder.finish()?.sync_all()
+}
+
+/// Point in time a JSONL record describes, if it can be determined.
+fn record_time(line: &str, clock: RecordClock) -> Option<DateTime<Utc>> {
+    let value: serde_json::Value = serde_json::from_str(line).ok()?;
+    match clock {
+        RecordClock::Timestamp => value
+            .get("timestamp_end")
+            .or_else(|| value.get("timestamp"))?
+            .as_str()?
+            .parse()
+            .ok(),
+        RecordClock::BalanceDate => {
+            let date: NaiveDate = value.get("date")?.as_str()?.parse().ok()?;
+            let end_of_day = date.checked_add_days(Days::new(1))?;
+            Some(end_of_day.and_hms_opt(0, 0, 0)?.and_utc())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{EconomicConfig, EconomicTracker};
+    use flate2::read::GzDecoder;
+    use std::io::Read;
+    use std::time::{Duration, SystemTime};
+    use tempfile::TempDir;
+
+    fn read_lines(path: &Path) -> Vec<String> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(str::to_string)
+            .collect()
+    }
+
+    #[test]
+    fn drain_moves_old_records_into_readable_archives() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        let completions = tmp.path().join("task_completions.jsonl");
+        let balance = tmp.path().join("balance.jsonl");
+        fs::write(
+            &completions,
+            concat!(
+                r#"{"task_id":"old","date":"2020-01-01","attempt":1,"work_submitted":true,"evaluation_score":0.9,"money_earned":1.0,"wall_clock_seconds":5.0,"timestamp":"2020-01-01T10:00:00Z"}"#,
+                "\n",
+                r#"{"task_id":"new","date":"2099-01-01","attempt":1,"work_submitted":true,"evaluation_score":0.9,"money_earned":1.0,"wall_clock_seconds":5.0,"timestamp":"2099-01-01T10:00:00Z"}"#,
+                "\n",
+            ),
+        )
+        .unwrap();
+        tracker
+            .save_daily_state("2020-01-01", 0.0, 0.0, Vec::new(), false)
+            .unwrap();
+        tracker
+            .save_daily_state("2020-01-02", 0.0, 0.0, Vec::new(), false)
+            .unwrap();
+        let balance_before = read_lines(&balance).len();
+
+        let cutoff = SystemTime::now() - Duration::from_secs(24 * 3600);
+        let summary = tracker.drain_to_archive(cutoff).unwrap();
+
+        // One completion plus the 2020-01-01 balance snapshot; the latest
+        // balance snapshot always stays active
+        assert_eq!(summary.records_archived, 2);
+        assert!(summary.bytes_freed > 0);
+        assert_eq!(summary.archive_files.len(), 2);
+        assert_eq!(read_lines(&completions).len(), 1);
+        assert!(read_lines(&completions)[0].contains(r#""task_id":"new""#));
+        assert_eq!(read_lines(&balance).len(), balance_before - 1);
+
+        let completions_archive = summary
+            .archive_files
+            .iter()
+            .find(|path| {
+                path.file_name()
+                    .unwrap()
+                    .to_string_lossy()
+                    .starts_with("task_completions-")
+            })
+            .unwrap();
+        let mut archived = String::new();
+        GzDecoder::new(File::open(completions_archive).unwrap())
+            .read_to_string(&mut archived)
+            .unwrap();
+        assert_eq!(archived.lines().count(), 1);
+        assert!(archived.contains(r#""task_id":"old""#));
+
+        // State is untouched and a second drain finds nothing new
+        assert!((tracker.get_balance() - 100.0).abs() < f64::EPSILON);
+        assert_eq!(tracker.drain_to_archive(cutoff).unwrap().records_archived, 0);
+    }
+}
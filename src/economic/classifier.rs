@@ -23,8 +23,30 @@
 //! println!("Max payment: ${:.2}", result.max_payment);
 //! ```
 
+use super::accounting::csv_field;
+use super::costs::PricingModel;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Write;
+
+/// Keywords listed per occupation in a Markdown catalog.
+const CATALOG_SAMPLE_KEYWORDS: usize = 5;
+
+/// Occupations matching fewer distinct corpus terms than this are reported
+/// as low coverage by `suggest_keywords`.
+const LOW_COVERAGE_TERMS: usize = 2;
+
+/// Collision terms reported by `suggest_keywords`.
+const MAX_COLLISION_TERMS: usize = 20;
+
+/// Common words that are never suggested as keywords.
+const STOPWORDS: &[&str] = &[
+    "about", "after", "all", "also", "and", "any", "are", "been", "before", "but", "can", "each",
+    "for", "from", "has", "have", "how", "into", "its", "more", "most", "new", "not", "our",
+    "out", "over", "per", "should", "some", "than", "that", "the", "their", "them", "then",
+    "there", "these", "they", "this", "use", "using", "via", "was", "what", "when", "which",
+    "who", "will", "with", "would", "you", "your",
+];
 
 /// Occupation category groupings based on BLS major groups
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -40,6 +62,14 @@ pub enum OccupationCategory {
 }
 
 impl OccupationCategory {
+    /// All categories, in catalog order
+    pub const ALL: [Self; 4] = [
+        Self::TechnologyEngineering,
+        Self::BusinessFinance,
+        Self::HealthcareSocialServices,
+        Self::LegalMediaOperations,
+    ];
+
     /// Returns a human-readable name for the category
     pub fn display_name(&self) -> &'static str {
         match self {
@@ -51,6 +81,55 @@ impl OccupationCategory {
     }
 }
 
+/// Output format for `TaskClassifier::export_occupation_catalog`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogFormat {
+    /// One table per category: name, wage, and sample keywords
+    Markdown,
+    /// `name,category,hourly_wage,keywords`, one row per occupation
+    Csv,
+    /// JSON array of `{name, category, hourly_wage, keywords}`
+    Json,
+}
+
+/// How time-sensitive a task is, for `ClassificationResult::adjust_for_urgency`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UrgencyLevel {
+    /// Regular turnaround, paid at the BLS wage
+    #[default]
+    Standard,
+    /// Expedited, 1.25× the wage
+    Rush,
+    /// Needed within hours, 1.5× the wage
+    Emergency,
+    /// Drop everything, 2× the wage
+    Immediate,
+}
+
+impl UrgencyLevel {
+    /// Factor applied to the hourly wage
+    pub fn multiplier(self) -> f64 {
+        match self {
+            Self::Standard => 1.0,
+            Self::Rush => 1.25,
+            Self::Emergency => 1.5,
+            Self::Immediate => 2.0,
+        }
+    }
+
+    /// Returns a human-readable name for the urgency level
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::Rush => "Rush",
+            Self::Emergency => "Emergency",
+            Self::Immediate => "Immediate",
+        }
+    }
+}
+
 /// A single occupation with BLS wage data
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Occupation {
@@ -65,6 +144,22 @@ pub struct Occupation {
     pub keywords: Vec<&'static str>,
 }
 
+impl Occupation {
+    /// Net earnings per hour of work after overhead (USD/hour)
+    ///
+    /// `(hourly_wage * typical_task_hours - overhead_rate * typical_task_hours)
+    /// / typical_task_hours`; returns 0.0 when `typical_task_hours` is not
+    /// positive.
+    pub fn profitability_score(&self, typical_task_hours: f64, overhead_rate: f64) -> f64 {
+        if typical_task_hours <= 0.0 {
+            return 0.0;
+        }
+        let revenue = self.hourly_wage * typical_task_hours;
+        let overhead = overhead_rate * typical_task_hours;
+        (revenue - overhead) / typical_task_hours
+    }
+}
+
 /// Result of classifying a task instruction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ClassificationResult {
@@ -82,8 +177,91 @@ pub struct ClassificationResult {
     pub category: OccupationCategory,
     /// Brief reasoning for the classification
     pub reasoning: String,
+    /// Minimum quality score at which `max_payment` covers the expected
+    /// cost (0.0 until a cost is set with `with_expected_cost`)
+    #[serde(default)]
+    pub break_even_quality_score: f64,
+}
+
+impl ClassificationResult {
+    /// Set the expected cost of the task, updating the break-even quality.
+    pub fn with_expected_cost(mut self, cost_usd: f64) -> Self {
+        self.break_even_quality_score =
+            PricingModel::compute_break_even_quality(cost_usd, self.max_payment);
+        self
+    }
+
+    /// Price the task at the premium for `urgency`.
+    ///
+    /// Multiplies `hourly_wage` and `max_payment` and notes the premium in
+    /// `reasoning`. An unclamped break-even quality shrinks
+    /// with the larger payment; `Standard` returns the result unchanged.
+    pub fn adjust_for_urgency(&self, urgency: UrgencyLevel) -> ClassificationResult {
+        let mut adjusted = self.clone();
+        if urgency == UrgencyLevel::Standard {
+            return adjusted;
+        }
+        let multiplier = urgency.multiplier();
+        adjusted.hourly_wage = self.hourly_wage * multiplier;
+        // Scaled rather than rounded again, so it stays in proportion to
+        // the standard price
+        adjusted.max_payment = self.max_payment * multiplier;
+        if self.break_even_quality_score < 1.0 {
+            adjusted.break_even_quality_score = self.break_even_quality_score / multiplier;
+        }
+        adjusted.reasoning = format!(
+            "{} ({} urgency: {multiplier}× wage premium)",
+            self.reasoning,
+            urgency.display_name()
+        );
+        adjusted
+    }
+}
+
+/// Keyword gap analysis of a task corpus, from
+/// `TaskClassifier::suggest_keywords`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SuggestedKeywords {
+    /// Terms no occupation keyword matches, with how often they occur, most
+    /// frequent first
+    pub unmatched_terms: Vec<(String, usize)>,
+    /// Occupations whose keywords match fewer than two distinct corpus
+    /// terms, in catalog order
+    pub low_coverage_occupations: Vec<String>,
+    /// Terms matched by keywords of several occupations, with those
+    /// occupations, most ambiguous first
+    pub top_collision_terms: Vec<(String, Vec<String>)>,
+}
+
+/// Result of calibrating classifier confidence against labeled examples
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationResult {
+    /// Number of labeled examples evaluated
+    pub examples: usize,
+    /// Share of examples classified into the labeled occupation (0.0 - 1.0)
+    pub accuracy: f64,
+    /// Expected calibration error with the current divisor: the
+    /// example-weighted gap between mean confidence and accuracy, grouped by
+    /// keyword score
+    pub calibration_error: f64,
+    /// Divisor that minimizes the calibration error on these examples
+    pub recommended_divisor: f64,
+    /// Expected calibration error with the recommended divisor
+    pub recommended_calibration_error: f64,
 }
 
+/// Default keyword-score divisor used to normalize confidence
+const DEFAULT_CONFIDENCE_DIVISOR: f64 = 3.0;
+
+/// Confidence reported for fallback classifications
+const FALLBACK_CONFIDENCE: f64 = 0.3;
+
+/// Keyword weights of the parts of a function descriptor in
+/// `classify_structured_prompt`
+const DESCRIPTION_WEIGHT: f64 = 3.0;
+const FUNCTION_NAME_WEIGHT: f64 = 2.0;
+const PARAMETER_WEIGHT: f64 = 1.0;
+
 /// Task classifier that maps instructions to BLS occupations
 #[derive(Debug)]
 pub struct TaskClassifier {
@@ -91,6 +269,7 @@ pub struct TaskClassifier {
     keyword_index: HashMap<&'static str, Vec<usize>>,
     fallback_occupation: String,
     fallback_wage: f64,
+    confidence_divisor: f64,
 }
 
 impl Default for TaskClassifier {
@@ -110,6 +289,7 @@ impl TaskClassifier {
             keyword_index,
             fallback_occupation: "General and Operations Managers".to_string(),
             fallback_wage: 64.0,
+            confidence_divisor: DEFAULT_CONFIDENCE_DIVISOR,
         }
     }
 
@@ -506,28 +686,13 @@ impl TaskClassifier {
     /// This is a synchronous keyword-based classifier. For LLM-based
     /// classification, use `classify_with_llm` instead.
     pub fn classify(&self, instruction: &str) -> ClassificationResult {
-        let lower = instruction.to_lowercase();
-        let mut scores: HashMap<usize, f64> = HashMap::new();
-
-        // Score each occupation by keyword matches
-        for (keyword, occ_indices) in &self.keyword_index {
-            if lower.contains(keyword) {
-                for &idx in occ_indices {
-                    *scores.entry(idx).or_default() += 1.0;
-                }
-            }
-        }
-
-        // Find best match
-        let (best_idx, best_score) = scores
-            .iter()
-            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
-            .map(|(&idx, &score)| (idx, score))
+        let (best_idx, best_score) = self
+            .best_match(instruction)
             .unwrap_or((usize::MAX, 0.0));
 
         let (occupation, hourly_wage, category, confidence, reasoning) = if best_idx < self.occupations.len() {
             let occ = &self.occupations[best_idx];
-            let confidence = (best_score / 3.0).min(1.0); // Normalize confidence
+            let confidence = Self::normalize_confidence(best_score, self.confidence_divisor);
             (
                 occ.name.clone(),
                 occ.hourly_wage,
@@ -541,7 +706,7 @@ impl TaskClassifier {
                 self.fallback_occupation.clone(),
                 self.fallback_wage,
                 OccupationCategory::BusinessFinance,
-                0.3,
+                FALLBACK_CONFIDENCE,
                 "Fallback classification - no strong keyword match".to_string(),
             )
         };
@@ -557,7 +722,211 @@ impl TaskClassifier {
             confidence,
             category,
             reasoning,
+            break_even_quality_score: 0.0,
+        }
+    }
+
+    /// Classify an OpenAI-style function descriptor
+    ///
+    /// Keyword matches count three times in the description, twice in the
+    /// function name, and once in each parameter name; snake_case and
+    /// camelCase identifiers are split into words first. Confidence is
+    /// normalized in description matches, and the reasoning names the part
+    /// that contributed most to the chosen occupation.
+    pub fn classify_structured_prompt(
+        &self,
+        name: &str,
+        description: &str,
+        parameter_names: &[&str],
+    ) -> ClassificationResult {
+        let mut components = vec![
+            ("description", DESCRIPTION_WEIGHT, description.to_string()),
+            ("function name", FUNCTION_NAME_WEIGHT, identifier_words(name)),
+        ];
+        components.extend(
+            parameter_names
+                .iter()
+                .map(|param| ("parameters", PARAMETER_WEIGHT, identifier_words(param))),
+        );
+
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        let mut contributions: HashMap<(usize, &str), f64> = HashMap::new();
+        for (component, weight, text) in &components {
+            for (idx, score) in self.keyword_scores(text) {
+                *scores.entry(idx).or_default() += score * weight;
+                *contributions.entry((idx, component)).or_default() += score * weight;
+            }
+        }
+
+        let Some((best_idx, best_score)) = Self::top_score(&scores) else {
+            // No keyword anywhere, so the description alone falls back too
+            return self.classify(description);
+        };
+        let occ = &self.occupations[best_idx];
+        let parts: Vec<(&str, f64)> = ["description", "function name", "parameters"]
+            .into_iter()
+            .map(|component| {
+                let score = contributions.get(&(best_idx, component)).copied();
+                (component, score.unwrap_or(0.0))
+            })
+            .collect();
+        let driver = parts
+            .iter()
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map_or("description", |(component, _)| component);
+        let breakdown: Vec<String> = parts
+            .iter()
+            .map(|(component, score)| format!("{component} {score}"))
+            .collect();
+
+        let estimated_hours = Self::estimate_hours(description);
+        let max_payment = (estimated_hours * occ.hourly_wage * 100.0).round() / 100.0;
+        ClassificationResult {
+            occupation: occ.name.clone(),
+            hourly_wage: occ.hourly_wage,
+            estimated_hours,
+            max_payment,
+            confidence: Self::normalize_confidence(
+                best_score / DESCRIPTION_WEIGHT,
+                self.confidence_divisor,
+            ),
+            category: occ.category,
+            reasoning: format!(
+                "Weighted keyword score {best_score} ({}), driven by the {driver}",
+                breakdown.join(", ")
+            ),
+            break_even_quality_score: 0.0,
+        }
+    }
+
+    /// Score occupations by keyword matches and return the best (index, score)
+    fn best_match(&self, instruction: &str) -> Option<(usize, f64)> {
+        Self::top_score(&self.keyword_scores(instruction))
+    }
+
+    /// Number of keywords of each occupation found in `text`
+    fn keyword_scores(&self, text: &str) -> HashMap<usize, f64> {
+        let lower = text.to_lowercase();
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+
+        // Score each occupation by keyword matches
+        for (keyword, occ_indices) in &self.keyword_index {
+            if lower.contains(keyword) {
+                for &idx in occ_indices {
+                    *scores.entry(idx).or_default() += 1.0;
+                }
+            }
+        }
+        scores
+    }
+
+    /// Highest (index, score) of `scores`
+    fn top_score(scores: &HashMap<usize, f64>) -> Option<(usize, f64)> {
+        scores
+            .iter()
+            .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+            .map(|(&idx, &score)| (idx, score))
+    }
+
+    /// Map a keyword score to a confidence in 0.0 - 1.0
+    fn normalize_confidence(score: f64, divisor: f64) -> f64 {
+        (score / divisor).min(1.0)
+    }
+
+    /// Set the keyword-score divisor used to normalize confidence
+    ///
+    /// Typically the `recommended_divisor` from `calibrate_confidence`.
+    /// Non-finite or non-positive values are ignored.
+    pub fn set_confidence_divisor(&mut self, divisor: f64) {
+        if divisor.is_finite() && divisor > 0.0 {
+            self.confidence_divisor = divisor;
+        }
+    }
+
+    /// Get the keyword-score divisor used to normalize confidence
+    pub fn confidence_divisor(&self) -> f64 {
+        self.confidence_divisor
+    }
+
+    /// Calibrate confidence against labeled `(instruction, occupation name)` examples
+    ///
+    /// The divisor only rescales confidence, so it never changes which
+    /// occupation is picked; it changes how well confidence tracks the
+    /// observed accuracy. Candidate divisors from 1.0 to 10.0 (step 0.25) are
+    /// evaluated and the one with the lowest calibration error is recommended.
+    pub fn calibrate_confidence(&self, labeled_examples: &[(String, String)]) -> CalibrationResult {
+        // (keyword score, correct) per example; fallbacks have no score
+        let outcomes: Vec<(Option<f64>, bool)> = labeled_examples
+            .iter()
+            .map(|(instruction, label)| {
+                let expected = self
+                    .fuzzy_match(label)
+                    .map_or(label.as_str(), |occ| occ.name.as_str());
+                match self.best_match(instruction) {
+                    Some((idx, score)) => (Some(score), self.occupations[idx].name == expected),
+                    None => (None, self.fallback_occupation == expected),
+                }
+            })
+            .collect();
+
+        let correct = outcomes.iter().filter(|(_, ok)| *ok).count();
+        let accuracy = if outcomes.is_empty() {
+            0.0
+        } else {
+            correct as f64 / outcomes.len() as f64
+        };
+
+        let mut recommended_divisor = self.confidence_divisor;
+        let mut recommended_calibration_error = Self::calibration_error(&outcomes, recommended_divisor);
+        for step in 0..=36 {
+            let divisor = 1.0 + f64::from(step) * 0.25;
+            let error = Self::calibration_error(&outcomes, divisor);
+            if error < recommended_calibration_error - 1e-12 {
+                recommended_divisor = divisor;
+                recommended_calibration_error = error;
+            }
+        }
+
+        CalibrationResult {
+            examples: outcomes.len(),
+            accuracy,
+            calibration_error: Self::calibration_error(&outcomes, self.confidence_divisor),
+            recommended_divisor,
+            recommended_calibration_error,
+        }
+    }
+
+    /// Calibration bucket of a keyword score; scores past 10 share the top
+    /// bucket
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn score_bucket(score: f64) -> u32 {
+        // Clamped to 0..=10 first, so the cast cannot wrap
+        score.clamp(0.0, 10.0) as u32
+    }
+
+    /// Expected calibration error, bucketing examples by keyword score
+    fn calibration_error(outcomes: &[(Option<f64>, bool)], divisor: f64) -> f64 {
+        if outcomes.is_empty() {
+            return 0.0;
+        }
+
+        // bucket -> (examples, summed confidence, correct)
+        let mut buckets: HashMap<Option<u32>, (usize, f64, usize)> = HashMap::new();
+        for (score, ok) in outcomes {
+            let confidence = score.map_or(FALLBACK_CONFIDENCE, |s| Self::normalize_confidence(s, divisor));
+            let bucket = buckets.entry(score.map(Self::score_bucket)).or_default();
+            bucket.0 += 1;
+            bucket.1 += confidence;
+            bucket.2 += usize::from(*ok);
         }
+
+        buckets
+            .values()
+            .map(|&(n, confidence, correct)| {
+                let gap = (confidence / n as f64 - correct as f64 / n as f64).abs();
+                gap * n as f64 / outcomes.len() as f64
+            })
+            .sum()
     }
 
     /// Estimate hours based on instruction complexity
@@ -603,6 +972,186 @@ impl TaskClassifier {
             .collect()
     }
 
+    /// Top `n` occupations by profitability score, highest first
+    pub fn most_profitable_occupations(
+        &self,
+        typical_hours: f64,
+        overhead_rate: f64,
+        n: usize,
+    ) -> Vec<(&Occupation, f64)> {
+        let mut ranked: Vec<(&Occupation, f64)> = self
+            .occupations
+            .iter()
+            .map(|o| (o, o.profitability_score(typical_hours, overhead_rate)))
+            .collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.name.cmp(&b.0.name)));
+        ranked.truncate(n);
+        ranked
+    }
+
+    /// Find gaps in keyword coverage over a corpus of task instructions
+    ///
+    /// The corpus is split into lowercase words, skipping stopwords, numbers,
+    /// and words under three characters. A word is matched by an occupation
+    /// the way `classify` matches it: when one of the occupation's keywords
+    /// occurs within the word, or the word is part of a multi-word keyword
+    /// found in the same instruction.
+    pub fn suggest_keywords(&self, corpus: &[&str]) -> SuggestedKeywords {
+        let mut counts: HashMap<String, usize> = HashMap::new();
+        let mut matches: HashMap<String, BTreeSet<usize>> = HashMap::new();
+
+        for instruction in corpus {
+            let lower = instruction.to_lowercase();
+            let phrases: Vec<(&str, &Vec<usize>)> = self
+                .keyword_index
+                .iter()
+                .filter(|(keyword, _)| keyword.contains(' ') && lower.contains(*keyword))
+                .map(|(keyword, indices)| (*keyword, indices))
+                .collect();
+
+            for term in corpus_terms(&lower) {
+                *counts.entry(term.to_string()).or_default() += 1;
+                let occupations = matches.entry(term.to_string()).or_default();
+                for (keyword, indices) in &self.keyword_index {
+                    if term.contains(keyword) {
+                        occupations.extend(indices);
+                    }
+                }
+                for (phrase, indices) in &phrases {
+                    if phrase.split(' ').any(|word| word == term) {
+                        occupations.extend(*indices);
+                    }
+                }
+            }
+        }
+
+        let mut unmatched_terms: Vec<(String, usize)> = counts
+            .iter()
+            .filter(|(term, _)| matches[*term].is_empty())
+            .map(|(term, &count)| (term.clone(), count))
+            .collect();
+        unmatched_terms.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let mut matched_terms = vec![0usize; self.occupations.len()];
+        for occupations in matches.values() {
+            for &idx in occupations {
+                matched_terms[idx] += 1;
+            }
+        }
+        let low_coverage_occupations = self
+            .occupations
+            .iter()
+            .zip(&matched_terms)
+            .filter(|(_, &terms)| terms < LOW_COVERAGE_TERMS)
+            .map(|(occ, _)| occ.name.clone())
+            .collect();
+
+        let mut collisions: Vec<(&String, &BTreeSet<usize>)> = matches
+            .iter()
+            .filter(|(_, occupations)| occupations.len() > 1)
+            .collect();
+        collisions.sort_by(|a, b| {
+            b.1.len()
+                .cmp(&a.1.len())
+                .then_with(|| counts[b.0].cmp(&counts[a.0]))
+                .then_with(|| a.0.cmp(b.0))
+        });
+        let top_collision_terms = collisions
+            .into_iter()
+            .take(MAX_COLLISION_TERMS)
+            .map(|(term, occupations)| {
+                let names = occupations
+                    .iter()
+                    .map(|&idx| self.occupations[idx].name.clone())
+                    .collect();
+                (term.clone(), names)
+            })
+            .collect();
+
+        SuggestedKeywords {
+            unmatched_terms,
+            low_coverage_occupations,
+            top_collision_terms,
+        }
+    }
+
+    /// Render the occupation catalog, e.g. for reference documentation
+    pub fn export_occupation_catalog(&self, format: CatalogFormat) -> String {
+        match format {
+            CatalogFormat::Markdown => self.catalog_markdown(),
+            CatalogFormat::Csv => self.catalog_csv(),
+            CatalogFormat::Json => self.catalog_json(),
+        }
+    }
+
+    fn catalog_markdown(&self) -> String {
+        let mut out = String::new();
+        for category in OccupationCategory::ALL {
+            let occupations = self.occupations_by_category(category);
+            if occupations.is_empty() {
+                continue;
+            }
+            let _ = writeln!(out, "## {}\n", category.display_name());
+            out.push_str("| Occupation | Hourly wage | Sample keywords |\n");
+            out.push_str("|---|---:|---|\n");
+            for occupation in occupations {
+                let keywords: Vec<&str> = occupation
+                    .keywords
+                    .iter()
+                    .take(CATALOG_SAMPLE_KEYWORDS)
+                    .copied()
+                    .collect();
+                let _ = writeln!(
+                    out,
+                    "| {} | ${:.2} | {} |",
+                    occupation.name.replace('|', "\\|"),
+                    occupation.hourly_wage,
+                    keywords.join(", ")
+                );
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    fn catalog_csv(&self) -> String {
+        let mut out = String::from("name,category,hourly_wage,keywords\n");
+        for occupation in &self.occupations {
+            let _ = writeln!(
+                out,
+                "{},{},{:.2},{}",
+                csv_field(&occupation.name),
+                csv_field(occupation.category.display_name()),
+                occupation.hourly_wage,
+                csv_field(&occupation.keywords.join(";"))
+            );
+        }
+        out
+    }
+
+    fn catalog_json(&self) -> String {
+        // `Occupation` skips its keywords when serialized
+        #[derive(Serialize)]
+        struct CatalogEntry<'a> {
+            name: &'a str,
+            category: OccupationCategory,
+            hourly_wage: f64,
+            keywords: &'a [&'static str],
+        }
+
+        let entries: Vec<CatalogEntry> = self
+            .occupations
+            .iter()
+            .map(|occupation| CatalogEntry {
+                name: &occupation.name,
+                category: occupation.category,
+                hourly_wage: occupation.hourly_wage,
+                keywords: &occupation.keywords,
+            })
+            .collect();
+        serde_json::to_string_pretty(&entries).expect("occupation catalog serializes")
+    }
+
     /// Get the fallback occupation name
     pub fn fallback_occupation(&self) -> &str {
         &self.fallback_occupation
@@ -643,6 +1192,35 @@ impl TaskClassifier {
     }
 }
 
+/// Words of a snake_case, kebab-case, or camelCase identifier, lowercased
+/// and space-separated (`generateInvoice` -> `generate invoice`)
+fn identifier_words(identifier: &str) -> String {
+    let mut words = String::with_capacity(identifier.len() + 4);
+    let mut previous_lower = false;
+    for c in identifier.chars() {
+        if c == '_' || c == '-' {
+            words.push(' ');
+            previous_lower = false;
+        } else {
+            if c.is_uppercase() && previous_lower {
+                words.push(' ');
+            }
+            previous_lower = c.is_lowercase() || c.is_ascii_digit();
+            words.extend(c.to_lowercase());
+        }
+    }
+    words
+}
+
+/// Candidate keyword terms of a lowercased instruction
+fn corpus_terms(lower: &str) -> impl Iterator<Item = &str> {
+    lower
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| term.chars().count() >= 3)
+        .filter(|term| !term.chars().all(|c| c.is_ascii_digit()))
+        .filter(|term| !STOPWORDS.contains(term))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -676,6 +1254,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn urgency_premium_scales_the_payment() {
+        let classifier = TaskClassifier::new();
+        let standard = classifier.classify("Write a REST API in Rust with authentication");
+
+        let emergency = standard.adjust_for_urgency(UrgencyLevel::Emergency);
+        assert_eq!(emergency.occupation, standard.occupation);
+        assert_eq!(emergency.estimated_hours, standard.estimated_hours);
+        assert!((emergency.hourly_wage - 104.25).abs() < 1e-9);
+        assert!((emergency.max_payment - standard.max_payment * 1.5).abs() < 1e-9);
+        assert!(emergency.reasoning.contains("Emergency urgency: 1.5× wage premium"));
+
+        let immediate = standard.adjust_for_urgency(UrgencyLevel::Immediate);
+        assert!((immediate.max_payment - standard.max_payment * 2.0).abs() < 1e-9);
+        let unchanged = standard.adjust_for_urgency(UrgencyLevel::Standard);
+        assert_eq!(unchanged.max_payment, standard.max_payment);
+        assert_eq!(unchanged.reasoning, standard.reasoning);
+    }
+
     #[test]
     fn test_classify_fallback() {
         let classifier = TaskClassifier::new();
@@ -685,6 +1282,114 @@ mod tests {
         assert_eq!(result.confidence, 0.3);
     }
 
+    #[test]
+    fn test_classify_structured_prompt() {
+        let classifier = TaskClassifier::new();
+
+        let search = classifier.classify_structured_prompt(
+            "search_documents",
+            "Search the legal contract archive for clauses relevant to a litigation case",
+            &["query", "caseId", "jurisdiction"],
+        );
+        assert_eq!(search.occupation, "Lawyers");
+        assert!(search.reasoning.contains("driven by the description"));
+        assert_eq!(search.confidence, 1.0);
+
+        let invoice = classifier.classify_structured_prompt(
+            "generate_invoice",
+            "Create an invoice and record it",
+            &["customer_id", "amount", "tax_rate", "ledger_account"],
+        );
+        assert_eq!(invoice.occupation, "Accountants and Auditors");
+        assert!(invoice.reasoning.contains("driven by the parameters"));
+        assert!((invoice.confidence - 2.0 / 3.0 / 3.0).abs() < 1e-9);
+
+        let unknown = classifier.classify_structured_prompt("xyzzy", "foobar baz", &[]);
+        assert_eq!(unknown.occupation, "General and Operations Managers");
+    }
+
+    #[test]
+    fn test_identifier_words() {
+        assert_eq!(identifier_words("generate_invoice"), "generate invoice");
+        assert_eq!(identifier_words("searchDocuments"), "search documents");
+        assert_eq!(identifier_words("tax-rate2"), "tax rate2");
+    }
+
+    fn labeled(examples: &[(&str, &str)]) -> Vec<(String, String)> {
+        examples
+            .iter()
+            .map(|(instruction, label)| (instruction.to_string(), label.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_calibrate_confidence() {
+        let mut classifier = TaskClassifier::new();
+        let training = labeled(&[
+            ("Write python code to implement a REST api backend", "Software Developers"),
+            ("Refactor the rust algorithm and debug the app", "Software Developers"),
+            ("Debug the backend api in python", "Software Developers"),
+            ("Build a mobile app frontend with javascript", "Software Developers"),
+            ("Prepare a tax return and audit the accounting ledger", "Accountants and Auditors"),
+            ("Audit the payroll and tax accounting books", "Accountants and Auditors"),
+            ("Draft a legal contract for the lawsuit", "Lawyers"),
+            (
+                "Compose a newspaper article and interview the journalist sources",
+                "News Analysts, Reporters, and Journalists",
+            ),
+            (
+                "Plan the social media marketing campaign and brand advertising",
+                "Market Research Analysts and Marketing Specialists",
+            ),
+            // Two keyword matches: all correct
+            ("Organize the warehouse inventory", "Transportation, Storage, and Distribution Managers"),
+            ("Calculate portfolio risk for the hedge fund stocks", "Financial and Investment Analysts"),
+            (
+                "Design a marketing campaign for social media",
+                "Market Research Analysts and Marketing Specialists",
+            ),
+            ("Write a news article about the election", "News Analysts, Reporters, and Journalists"),
+            // One keyword match: mostly wrong
+            ("Fix the spreadsheet formulas in the budget", "Accountants and Auditors"),
+            ("Review this patient chart", "Registered Nurses"),
+            ("Edit the podcast audio", "Producers and Directors"),
+            ("Translate the contract clauses", "Editors"),
+            ("Review the lawsuit filings and court brief", "Lawyers"),
+        ]);
+        let held_out = labeled(&[
+            (
+                "Negotiate the real estate lease for the property",
+                "Property, Real Estate, and Community Association Managers",
+            ),
+            (
+                "Track the shipping logistics for the warehouse",
+                "Transportation, Storage, and Distribution Managers",
+            ),
+            ("Draft a privacy policy for compliance", "Compliance Officers"),
+            ("Prepare the quarterly budget forecast", "Financial Managers"),
+            ("Check the medication dosage", "Registered Nurses"),
+        ]);
+
+        let calibration = classifier.calibrate_confidence(&training);
+        assert_eq!(calibration.examples, 18);
+        assert!((calibration.accuracy - 14.0 / 18.0).abs() < 1e-9);
+        assert!((calibration.recommended_divisor - 2.0).abs() < f64::EPSILON);
+        assert!(calibration.recommended_calibration_error < calibration.calibration_error);
+
+        let before = classifier.calibrate_confidence(&held_out);
+        classifier.set_confidence_divisor(calibration.recommended_divisor);
+        let after = classifier.calibrate_confidence(&held_out);
+        assert!(after.calibration_error < before.calibration_error);
+        assert!((after.accuracy - before.accuracy).abs() < f64::EPSILON);
+
+        // One matched keyword now maps to 0.5 confidence
+        let result = classifier.classify("Review the court ruling on the lawsuit");
+        assert!((result.confidence - 0.5).abs() < f64::EPSILON);
+
+        classifier.set_confidence_divisor(-1.0);
+        assert!((classifier.confidence_divisor() - 2.0).abs() < f64::EPSILON);
+    }
+
     #[test]
     fn test_estimate_hours_complex() {
         let hours = TaskClassifier::estimate_hours(
@@ -721,4 +1426,107 @@ mod tests {
         assert!(!tech.is_empty());
         assert!(tech.iter().any(|o| o.name == "Software Developers"));
     }
+
+    #[test]
+    fn test_export_occupation_catalog() {
+        let classifier = TaskClassifier::new();
+        let count = classifier.occupations().len();
+
+        let markdown = classifier.export_occupation_catalog(CatalogFormat::Markdown);
+        for category in OccupationCategory::ALL {
+            assert!(markdown.contains(&format!("## {}", category.display_name())));
+        }
+        assert!(markdown.contains("| Software Developers | $69.50 | software, code, programming,"));
+        let rows = markdown
+            .lines()
+            .filter(|line| line.starts_with("| "))
+            .count();
+        assert_eq!(rows, count + OccupationCategory::ALL.len());
+
+        let csv = classifier.export_occupation_catalog(CatalogFormat::Csv);
+        assert_eq!(csv.lines().count(), count + 1);
+        assert!(csv.contains("Software Developers,Technology & Engineering,69.50,software;code;"));
+
+        let json = classifier.export_occupation_catalog(CatalogFormat::Json);
+        let entries: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(entries.len(), count);
+        assert_eq!(entries[0]["name"], "Software Developers");
+        assert_eq!(entries[0]["keywords"][0], "software");
+    }
+
+    #[test]
+    fn test_most_profitable_occupations() {
+        let classifier = TaskClassifier::new();
+        let count = classifier.occupations().len();
+
+        let top = classifier.most_profitable_occupations(2.0, 0.0, 3);
+        assert_eq!(top.len(), 3);
+        assert!(top.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        let highest_wage = classifier
+            .occupations()
+            .iter()
+            .map(|o| o.hourly_wage)
+            .fold(f64::MIN, f64::max);
+        assert!((top[0].1 - highest_wage).abs() < 1e-9);
+
+        // A higher overhead lowers every score by the same hourly amount
+        let all_low = classifier.most_profitable_occupations(2.0, 10.0, count);
+        let all_high = classifier.most_profitable_occupations(2.0, 60.0, count);
+        for (low, high) in all_low.iter().zip(&all_high) {
+            assert_eq!(low.0.name, high.0.name);
+            assert!((low.1 - high.1 - 50.0).abs() < 1e-9);
+        }
+        // Overhead above the wage makes an occupation unprofitable
+        let cheapest = all_high.last().unwrap();
+        assert!(cheapest.1 < 0.0);
+        assert!((cheapest.0.profitability_score(2.0, 60.0) - cheapest.1).abs() < 1e-9);
+
+        assert!(classifier.most_profitable_occupations(2.0, 0.0, 0).is_empty());
+        let software = classifier.get_occupation("Software Developers").unwrap();
+        assert!(software.profitability_score(0.0, 5.0).abs() < f64::EPSILON);
+        assert!((software.profitability_score(3.0, 9.5) - 60.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_suggest_keywords() {
+        let classifier = TaskClassifier::new();
+        let suggested = classifier.suggest_keywords(&[
+            "Terraform the grafana stack",
+            "Terraform module for auditing operations",
+            "auditing terraform outputs, 2024",
+            "Refactor the Python backend",
+        ]);
+
+        assert_eq!(suggested.unmatched_terms[0], ("terraform".to_string(), 3));
+        let mut unmatched: Vec<&str> = suggested
+            .unmatched_terms
+            .iter()
+            .map(|(term, _)| term.as_str())
+            .collect();
+        unmatched.sort_unstable();
+        assert_eq!(unmatched, ["grafana", "module", "outputs", "stack", "terraform"]);
+
+        // "audit" belongs to two occupations; "refactor" contains "cto"
+        let collisions = &suggested.top_collision_terms;
+        assert_eq!(collisions.len(), 3);
+        assert_eq!(collisions[0].0, "auditing");
+        assert_eq!(
+            collisions[0].1,
+            ["Accountants and Auditors", "Compliance Officers"]
+        );
+        assert_eq!(collisions[1].0, "operations");
+        assert_eq!(collisions[2].0, "refactor");
+
+        let low = &suggested.low_coverage_occupations;
+        assert!(low.iter().any(|name| name == "Accountants and Auditors"));
+        assert!(!low.iter().any(|name| name == "Software Developers"));
+        assert_eq!(low.len(), classifier.occupations().len() - 1);
+
+        let empty = classifier.suggest_keywords(&[]);
+        assert!(empty.unmatched_terms.is_empty());
+        assert_eq!(
+            empty.low_coverage_occupations.len(),
+            classifier.occupations().len()
+        );
+    }
 }
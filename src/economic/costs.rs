@@ -3,9 +3,44 @@
 //! Separates costs by channel (LLM, search API, OCR, etc.) following
 //! the ClawWork economic model.
 
-use chrono::{DateTime, Utc};
+use super::accounting::csv_field;
+use super::classifier::OccupationCategory;
+use super::error::{EconomicError, IoResultExt, Result};
+use super::history::CostLogRecord;
+use super::logs;
+use super::range::DateRange;
+use crate::cost::ImageSizeClass;
+use chrono::{DateTime, NaiveDate, Utc};
+#[cfg(feature = "archive-read")]
+use flate2::read::GzDecoder;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fs::File;
+use std::fmt::{self, Write as _};
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::marker::PhantomData;
+use std::ops::RangeBounds;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Most metadata entries a record may carry.
+pub const MAX_METADATA_KEYS: usize = 64;
+
+/// Most bytes of metadata (keys plus values) a record may carry.
+pub const MAX_METADATA_BYTES: usize = 1024;
+
+/// Check `metadata` against [`MAX_METADATA_KEYS`] and [`MAX_METADATA_BYTES`].
+pub(crate) fn validate_metadata(metadata: &BTreeMap<String, String>) -> Result<()> {
+    let bytes = metadata.iter().map(|(key, value)| key.len() + value.len()).sum();
+    if metadata.len() > MAX_METADATA_KEYS || bytes > MAX_METADATA_BYTES {
+        return Err(EconomicError::MetadataTooLarge {
+            keys: metadata.len(),
+            bytes,
+        });
+    }
+    Ok(())
+}
 
 /// Channel-separated cost breakdown for a task or session.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -18,6 +53,12 @@ pub struct CostBreakdown {
     pub ocr_api: f64,
     /// Cost from other API calls
     pub other_api: f64,
+    /// Cost from embedding API calls
+    #[serde(default)]
+    pub embedding_api: f64,
+    /// Cost from image-generation API calls
+    #[serde(default)]
+    pub image_api: f64,
 }
 
 impl CostBreakdown {
@@ -28,7 +69,12 @@ impl CostBreakdown {
 
     /// Get total cost across all channels.
     pub fn total(&self) -> f64 {
-        self.llm_tokens + self.search_api + self.ocr_api + self.other_api
+        self.llm_tokens
+            + self.search_api
+            + self.ocr_api
+            + self.other_api
+            + self.embedding_api
+            + self.image_api
     }
 
     /// Add another breakdown to this one.
@@ -37,6 +83,8 @@ impl CostBreakdown {
         self.search_api += other.search_api;
         self.ocr_api += other.ocr_api;
         self.other_api += other.other_api;
+        self.embedding_api += other.embedding_api;
+        self.image_api += other.image_api;
     }
 
     /// Reset all costs to zero.
@@ -45,6 +93,8 @@ impl CostBreakdown {
         self.search_api = 0.0;
         self.ocr_api = 0.0;
         self.other_api = 0.0;
+        self.embedding_api = 0.0;
+        self.image_api = 0.0;
     }
 }
 
@@ -76,9 +126,96 @@ impl TokenPricing {
     }
 }
 
+/// Range of UTC hours `[start, end)`.
+///
+/// A range with `end < start` wraps past midnight (`22..6` covers 22:00 to
+/// 05:59); `start == end` covers the whole day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HourRange {
+    /// First hour in the range (0-23)
+    pub start: u8,
+    /// First hour after the range (0-24)
+    pub end: u8,
+}
+
+impl HourRange {
+    pub fn new(start: u8, end: u8) -> Self {
+        Self { start, end }
+    }
+
+    /// Whether `hour` (0-23) falls in the range.
+    pub fn contains(&self, hour: u32) -> bool {
+        let (start, end) = (u32::from(self.start), u32::from(self.end));
+        match start.cmp(&end) {
+            std::cmp::Ordering::Less => (start..end).contains(&hour),
+            std::cmp::Ordering::Greater => hour >= start || hour < end,
+            std::cmp::Ordering::Equal => true,
+        }
+    }
+}
+
+/// Token price multipliers by time of day, for providers that charge more
+/// at peak hours.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TimeOfUsePricing {
+    /// Hour ranges and the multiplier applied within them; the first
+    /// matching range wins
+    pub periods: Vec<(HourRange, f64)>,
+}
+
+impl TimeOfUsePricing {
+    pub fn new(periods: Vec<(HourRange, f64)>) -> Self {
+        Self { periods }
+    }
+
+    /// Multiplier for UTC `hour`, or `1.0` outside every range.
+    pub fn multiplier_at(&self, hour: u32) -> f64 {
+        self.periods
+            .iter()
+            .find(|(range, _)| range.contains(hour))
+            .map_or(1.0, |(_, multiplier)| *multiplier)
+    }
+}
+
+/// Per-image pricing for an image-generation model (USD per image).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImagePricing {
+    /// Price per small image
+    pub small: f64,
+    /// Price per medium image
+    pub medium: f64,
+    /// Price per large image
+    pub large: f64,
+}
+
+impl Default for ImagePricing {
+    fn default() -> Self {
+        // Default to DALL-E 3 standard/HD pricing
+        Self {
+            small: 0.016,
+            medium: 0.04,
+            large: 0.08,
+        }
+    }
+}
+
+impl ImagePricing {
+    /// Get the per-image price for a size class.
+    pub fn price_for(&self, size_class: ImageSizeClass) -> f64 {
+        match size_class {
+            ImageSizeClass::Small => self.small,
+            ImageSizeClass::Medium => self.medium,
+            ImageSizeClass::Large => self.large,
+        }
+    }
+}
+
 /// A single LLM call record with token details.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LlmCallRecord {
+    /// Unique charge identifier (used to reference the call in refunds)
+    #[serde(default)]
+    pub id: String,
     /// Timestamp of the call
     pub timestamp: DateTime<Utc>,
     /// API name/source (e.g., "agent", "wrapup", "research")
@@ -89,11 +226,162 @@ pub struct LlmCallRecord {
     pub output_tokens: u64,
     /// Cost in USD
     pub cost: f64,
+    /// Whether part of the input was served from the provider's prompt cache
+    #[serde(default)]
+    pub cache_hit: bool,
+    /// Cost avoided thanks to cached input tokens (USD)
+    #[serde(default)]
+    pub cache_savings_usd: f64,
+    /// Model that served the call, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// Provider that served the call (e.g. `openrouter`), when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+    /// Kind of prompt that drove the usage, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompt_type: Option<PromptType>,
+    /// Request identifier, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Retry attempt of the request (0 for the first attempt)
+    #[serde(default, skip_serializing_if = "is_first_attempt")]
+    pub retry_attempt: u8,
+    /// Wall-clock time the call took in milliseconds (0 when not measured)
+    #[serde(default)]
+    pub latency_ms: u64,
+    /// Conversation the call served, when known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+}
+
+// serde's `skip_serializing_if` passes the field by reference
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_first_attempt(attempt: &u8) -> bool {
+    *attempt == 0
+}
+
+/// Kind of prompt that drove an LLM call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PromptType {
+    System,
+    User,
+    Assistant,
+    Tool,
+}
+
+/// Request-level metadata for `EconomicTracker::track_tokens_with_context`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenContext {
+    /// Kind of prompt that drove the usage
+    pub prompt_type: PromptType,
+    /// Request identifier, to correlate retries
+    #[serde(default)]
+    pub request_id: Option<String>,
+    /// Retry attempt of the request (0 for the first attempt)
+    #[serde(default)]
+    pub retry_attempt: u8,
+    /// Conversation the request serves
+    #[serde(default)]
+    pub session_id: Option<String>,
+}
+
+impl TokenContext {
+    /// First attempt of a request without an identifier.
+    pub fn new(prompt_type: PromptType) -> Self {
+        Self {
+            prompt_type,
+            request_id: None,
+            retry_attempt: 0,
+            session_id: None,
+        }
+    }
+
+    /// Attribute the request to a conversation.
+    pub fn with_session_id(mut self, session_id: impl Into<String>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+}
+
+/// Where `EconomicTracker` records LLM token usage, and embedding,
+/// image-generation, and API costs, from.
+///
+/// Only one source is counted, so a call reported both ways is not charged
+/// twice.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LlmUsageSource {
+    /// Explicit `track_*` calls; observer events are ignored
+    #[default]
+    Manual,
+    /// `LlmResponse`, `EmbeddingResponse`, `ImageGeneration`, and
+    /// `ToolCall` events through `EconomicObserver`; explicit calls are
+    /// ignored
+    Observer,
+}
+
+/// What a cost call charged.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Charge {
+    /// Cost in USD (0 when the call was ignored)
+    pub cost: f64,
+    /// The balance is exhausted and the call was paid from the bankruptcy
+    /// grace budget (see `EconomicConfig::bankruptcy_grace`)
+    pub grace_warning: bool,
+}
+
+/// LLM call reported by an `LlmResponse` observer event, for
+/// `EconomicTracker::track_observed_llm_call`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObservedLlmCall {
+    pub provider: String,
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    /// Subset of `input_tokens` read from the prompt cache
+    pub cached_input_tokens: u64,
+    /// Wall-clock time the call took
+    pub duration: Duration,
+    /// Conversation or task the call served
+    pub session_id: Option<String>,
+}
+
+/// Direct API cost reported by an observer event, for
+/// `EconomicTracker::track_observed_api_call`.
+///
+/// `cost_usd` is the cost the emitter already priced the call at, if any.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ObservedApiCall {
+    /// An `EmbeddingResponse` event
+    Embedding {
+        model: String,
+        tokens: u64,
+        cost_usd: Option<f64>,
+    },
+    /// An `ImageGeneration` event
+    ImageGeneration {
+        model: String,
+        count: u32,
+        size_class: ImageSizeClass,
+        cost_usd: Option<f64>,
+    },
+    /// A `ToolCall` event of a tool backed by a paid API
+    Tool {
+        tool: String,
+        cost_usd: f64,
+        /// Economic task the call served
+        task_id: Option<String>,
+    },
 }
 
 /// A single API call record (non-LLM).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiCallRecord {
+    /// Unique charge identifier (used to reference the call in refunds)
+    #[serde(default)]
+    pub id: String,
     /// Timestamp of the call
     pub timestamp: DateTime<Utc>,
     /// API name (e.g., "tavily_search", "jina_reader")
@@ -120,6 +408,23 @@ pub enum PricingModel {
     FlatRate,
 }
 
+impl PricingModel {
+    /// Minimum quality score at which a task paying up to `max_payment`
+    /// covers `cost_usd`, assuming payment scales with quality.
+    ///
+    /// Returns `cost_usd / max_payment` clamped to `[0.0, 1.0]`: `0.0` for a
+    /// free task, `1.0` when no quality covers the cost.
+    pub fn compute_break_even_quality(cost_usd: f64, max_payment: f64) -> f64 {
+        if cost_usd <= 0.0 {
+            return 0.0;
+        }
+        if max_payment <= 0.0 {
+            return 1.0;
+        }
+        (cost_usd / max_payment).clamp(0.0, 1.0)
+    }
+}
+
 /// Comprehensive task cost record (one per task).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaskCostRecord {
@@ -131,6 +436,15 @@ pub struct TaskCostRecord {
     pub date: String,
     /// Unique task identifier
     pub task_id: String,
+    /// Normalized task tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Caller-supplied context (run id, template version, customer id, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Cost the task was expected to stay within (USD), when one was set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_budget: Option<f64>,
     /// LLM usage summary
     pub llm_usage: LlmUsageSummary,
     /// API usage summary
@@ -143,6 +457,77 @@ pub struct TaskCostRecord {
     pub session_cost: f64,
     /// Daily cost so far
     pub daily_cost: f64,
+    /// Share of costs incurred outside any task attributed to this task
+    /// (USD), already charged and not part of `cost_summary`
+    #[serde(default)]
+    pub prorated_overhead: f64,
+}
+
+/// How `EconomicTracker::prorate_shared_cost` attributes a cost incurred
+/// outside any task.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProrationStrategy {
+    /// Equal shares for every completed task
+    EvenlyAcrossAllTasks,
+    /// Shares of completed tasks by the LLM tokens they used
+    ProportionalToTokens,
+    /// All of it to the next task that ends
+    AddToNextTask,
+    /// No task; counted as general overhead
+    WriteOffAsOverhead,
+}
+
+/// Shared cost attributed by `EconomicTracker::prorate_shared_cost`,
+/// persisted to `overhead.jsonl`.
+///
+/// A record with [`ProrationStrategy::AddToNextTask`] and no shares holds
+/// `amount` for the next task; the task that ends with it is recorded with
+/// its share and a zero `amount`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OverheadRecord {
+    /// Timestamp of the attribution
+    pub timestamp: DateTime<Utc>,
+    /// How the cost was attributed
+    pub strategy: ProrationStrategy,
+    /// Shared cost attributed by this record (USD)
+    pub amount: f64,
+    /// Share of each task (USD)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub shares: BTreeMap<String, f64>,
+    /// Cost held for the next task after this record (USD)
+    pub pending_overhead: f64,
+    /// Cost written off as overhead so far, this record included (USD)
+    pub written_off_overhead: f64,
+}
+
+impl OverheadRecord {
+    /// Shares of a cost split across completed tasks. Cost added to the
+    /// next task is already in that task's `prorated_overhead`.
+    pub fn split_shares(&self) -> impl Iterator<Item = (&str, f64)> {
+        let split = matches!(
+            self.strategy,
+            ProrationStrategy::EvenlyAcrossAllTasks | ProrationStrategy::ProportionalToTokens
+        );
+        self.shares
+            .iter()
+            .filter(move |_| split)
+            .map(|(task_id, share)| (task_id.as_str(), *share))
+    }
+}
+
+impl TaskCostRecord {
+    /// Output tokens produced per dollar spent on the task.
+    ///
+    /// Higher values indicate better cost efficiency. The metric is undefined
+    /// when the task cost nothing, in which case `f64::NAN` is returned.
+    pub fn token_efficiency(&self) -> f64 {
+        let total_cost_usd = self.cost_summary.total();
+        if total_cost_usd == 0.0 {
+            return f64::NAN;
+        }
+        self.llm_usage.total_output_tokens as f64 / total_cost_usd
+    }
 }
 
 /// Aggregated LLM usage for a task.
@@ -158,6 +543,9 @@ pub struct LlmUsageSummary {
     pub total_tokens: u64,
     /// Total cost in USD
     pub total_cost: f64,
+    /// Total cost avoided through prompt caching (USD)
+    #[serde(default)]
+    pub total_cache_savings_usd: f64,
     /// Pricing used
     pub input_price_per_million: f64,
     pub output_price_per_million: f64,
@@ -166,6 +554,53 @@ pub struct LlmUsageSummary {
     pub calls_detail: Vec<LlmCallRecord>,
 }
 
+impl LlmUsageSummary {
+    /// Cost of the detailed calls per prompt type. Calls recorded without a
+    /// prompt type are left out.
+    pub fn cost_by_prompt_type(&self) -> HashMap<PromptType, f64> {
+        let mut by_type: HashMap<PromptType, f64> = HashMap::new();
+        for call in &self.calls_detail {
+            if let Some(prompt_type) = call.prompt_type {
+                *by_type.entry(prompt_type).or_default() += call.cost;
+            }
+        }
+        by_type
+    }
+
+    /// Median latency of the detailed calls (ms); `None` when no call has
+    /// a measured latency.
+    pub fn p50_latency_ms(&self) -> Option<u64> {
+        self.latency_percentile_ms(50)
+    }
+
+    /// 95th-percentile latency of the detailed calls (ms).
+    pub fn p95_latency_ms(&self) -> Option<u64> {
+        self.latency_percentile_ms(95)
+    }
+
+    /// 99th-percentile latency of the detailed calls (ms).
+    pub fn p99_latency_ms(&self) -> Option<u64> {
+        self.latency_percentile_ms(99)
+    }
+
+    /// Nearest-rank percentile of the measured call latencies; calls with a
+    /// latency of 0 (not measured) are left out.
+    fn latency_percentile_ms(&self, percentile: usize) -> Option<u64> {
+        let mut latencies: Vec<u64> = self
+            .calls_detail
+            .iter()
+            .map(|call| call.latency_ms)
+            .filter(|&latency| latency > 0)
+            .collect();
+        if latencies.is_empty() {
+            return None;
+        }
+        latencies.sort_unstable();
+        let rank = (percentile * latencies.len()).div_ceil(100).max(1);
+        Some(latencies[rank - 1])
+    }
+}
+
 /// Aggregated API usage for a task.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ApiUsageSummary {
@@ -177,6 +612,12 @@ pub struct ApiUsageSummary {
     pub ocr_api_cost: f64,
     /// Other API costs
     pub other_api_cost: f64,
+    /// Embedding API costs
+    #[serde(default)]
+    pub embedding_api_cost: f64,
+    /// Image-generation API costs
+    #[serde(default)]
+    pub image_generation_cost: f64,
     /// Number of token-based calls
     pub token_based_calls: usize,
     /// Number of flat-rate calls
@@ -186,6 +627,63 @@ pub struct ApiUsageSummary {
     pub calls_detail: Vec<ApiCallRecord>,
 }
 
+/// Grant income record (income not tied to a task).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GrantIncomeRecord {
+    /// Timestamp
+    pub timestamp: DateTime<Utc>,
+    /// Date (YYYY-MM-DD)
+    pub date: String,
+    /// Grant or funding program identifier
+    pub grant_id: String,
+    /// Amount credited
+    pub amount: f64,
+    /// Optional description
+    #[serde(default)]
+    pub description: String,
+    /// Balance after this income
+    pub balance_after: f64,
+}
+
+/// Direction of a daily interest charge or credit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterestKind {
+    /// Yield earned on a positive balance
+    Earned,
+    /// Interest paid on a negative balance
+    Paid,
+}
+
+/// One day of interest on the balance, persisted to `interest.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestRecord {
+    /// When the interest was applied
+    pub timestamp: DateTime<Utc>,
+    /// Day the interest accrued for (YYYY-MM-DD)
+    pub date: String,
+    /// Whether interest was earned or paid
+    pub kind: InterestKind,
+    /// Daily rate applied
+    pub rate: f64,
+    /// Balance the rate was applied to
+    pub principal: f64,
+    /// Interest amount (always positive)
+    pub amount: f64,
+    /// Balance after this interest
+    pub balance_after: f64,
+}
+
+impl InterestRecord {
+    /// Signed balance change: positive when earned, negative when paid.
+    pub fn delta(&self) -> f64 {
+        match self.kind {
+            InterestKind::Earned => self.amount,
+            InterestKind::Paid => -self.amount,
+        }
+    }
+}
+
 /// Work income record.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WorkIncomeRecord {
@@ -205,11 +703,110 @@ pub struct WorkIncomeRecord {
     pub threshold: f64,
     /// Whether payment was awarded
     pub payment_awarded: bool,
+    /// Whether the offered payment was withheld, for a low score or by an
+    /// income validator. False in records written before this field existed.
+    #[serde(default)]
+    pub was_rejected: bool,
+    /// Why an income validator rejected the payment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rejection_reason: Option<String>,
+    /// Reasoning of the quality evaluator that produced the score
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub evaluation_reasoning: Option<String>,
     /// Optional description
     #[serde(default)]
     pub description: String,
     /// Balance after this income
     pub balance_after: f64,
+    /// Caller-supplied context (run id, template version, customer id, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Refund or billing correction credited against an earlier charge.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefundRecord {
+    /// Timestamp of the refund
+    pub timestamp: DateTime<Utc>,
+    /// ID of the refunded `LlmCallRecord` or `ApiCallRecord`
+    pub original_record_id: String,
+    /// Refunded amount, stored as a negative cost in USD
+    pub cost: f64,
+    /// Why the charge was reversed
+    pub reason: String,
+    /// Balance after the refund
+    pub balance_after: f64,
+}
+
+impl RefundRecord {
+    /// Refunded amount as a positive number.
+    pub fn amount(&self) -> f64 {
+        -self.cost
+    }
+}
+
+/// Manual correction of a task's tracked cost to the billed amount,
+/// persisted to `cost_corrections.jsonl`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostCorrectionRecord {
+    /// Timestamp of the correction
+    pub timestamp: DateTime<Utc>,
+    /// Task whose cost was corrected
+    pub task_id: String,
+    /// Cost of the task before this correction, including earlier
+    /// corrections
+    pub original_cost: f64,
+    /// Cost the task should have been charged
+    pub corrected_cost: f64,
+    /// `corrected_cost - original_cost`; positive when the cost was
+    /// under-reported
+    pub delta: f64,
+    /// Why the cost was corrected
+    pub reason: String,
+    /// Balance after the correction
+    pub balance_after: f64,
+}
+
+/// Side of an agent-to-agent transfer a tracker logged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferDirection {
+    /// Paid to the counterparty
+    Sent,
+    /// Received from the counterparty
+    Received,
+}
+
+/// One side of an agent-to-agent payment, persisted to `transfers.jsonl`.
+///
+/// Both trackers log the transfer under the same `transfer_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferRecord {
+    /// When the transfer was applied
+    pub timestamp: DateTime<Utc>,
+    /// Identifier shared by the sending and receiving record
+    pub transfer_id: String,
+    /// Whether this tracker sent or received the amount
+    pub direction: TransferDirection,
+    /// Signature of the other agent
+    pub counterparty: String,
+    /// Amount transferred (always positive)
+    pub amount: f64,
+    /// Why the payment was made
+    #[serde(default)]
+    pub reason: String,
+    /// Balance after this transfer
+    pub balance_after: f64,
+}
+
+impl TransferRecord {
+    /// Signed balance change: positive when received, negative when sent.
+    pub fn delta(&self) -> f64 {
+        match self.direction {
+            TransferDirection::Sent => -self.amount,
+            TransferDirection::Received => self.amount,
+        }
+    }
 }
 
 /// Daily balance record for persistence.
@@ -247,6 +844,93 @@ pub struct BalanceRecord {
     /// Whether session was aborted by API error
     #[serde(default)]
     pub api_error: bool,
+    /// Cumulative total of refunds credited
+    #[serde(default)]
+    pub total_refunds: f64,
+    /// Cumulative grant income
+    #[serde(default)]
+    pub total_grant_income: f64,
+    /// Cumulative interest earned on a positive balance
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub total_interest_earned: f64,
+    /// Cumulative interest paid on a negative balance
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub total_interest_paid: f64,
+    /// Costs recorded since the bankruptcy grace period began
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub grace_spent: f64,
+    /// Mid-period snapshot written by `EconomicTracker::flush` (zero deltas)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub checkpoint: bool,
+    /// When the snapshot was taken (absent in older records)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<DateTime<Utc>>,
+    /// Balance changes since the previous snapshot that no other log
+    /// records, already included in `balance`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub unlogged_changes: Vec<BalanceChange>,
+}
+
+/// Balance change with no log of its own (a charge outside any task,
+/// trading P&L), carried by the next balance snapshot so it keeps its time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BalanceChange {
+    /// When the balance changed
+    pub timestamp: DateTime<Utc>,
+    /// Signed change (USD); negative for a charge
+    pub delta: f64,
+}
+
+/// Status of a task.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskStatus {
+    /// Task is still being tracked (never written to completion records)
+    Active,
+    /// Task ran to completion and is eligible for income
+    #[default]
+    Completed,
+    /// Task was cancelled
+    Aborted,
+    /// Task failed (API error, quality rejection, ...)
+    Failed,
+    /// Task exceeded its time allowance
+    TimedOut,
+    /// Task was refused or its work rejected
+    Rejected,
+}
+
+impl TaskStatus {
+    /// Whether the task can count towards income-eligible work.
+    pub fn is_income_eligible(&self) -> bool {
+        matches!(self, Self::Completed)
+    }
+}
+
+/// Reason code passed to `EconomicTracker::abort_task`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskAbortReason {
+    /// Cancelled by the user or the scheduler
+    Cancelled,
+    /// Failed with an unrecoverable error
+    Failed,
+    /// Ran out of time
+    TimedOut,
+    /// Refused by the agent or rejected by the requester
+    Rejected,
+}
+
+impl TaskAbortReason {
+    /// Terminal status recorded for a task aborted with this reason.
+    pub fn status(&self) -> TaskStatus {
+        match self {
+            Self::Cancelled => TaskStatus::Aborted,
+            Self::Failed => TaskStatus::Failed,
+            Self::TimedOut => TaskStatus::TimedOut,
+            Self::Rejected => TaskStatus::Rejected,
+        }
+    }
 }
 
 /// Task completion record for analytics.
@@ -268,6 +952,44 @@ pub struct TaskCompletionRecord {
     pub wall_clock_seconds: f64,
     /// Timestamp of completion
     pub timestamp: DateTime<Utc>,
+    /// When the task was started, for tasks ended through the tracker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<DateTime<Utc>>,
+    /// When the task was ended, for tasks ended through the tracker
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<DateTime<Utc>>,
+    /// Cost summary captured when the task ended
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_summary: Option<TaskCostSummary>,
+    /// Normalized task tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// How the task ended
+    #[serde(default)]
+    pub status: TaskStatus,
+    /// Reason code for aborted tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_reason: Option<TaskAbortReason>,
+    /// Free-form detail for aborted tasks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub abort_detail: Option<String>,
+    /// Caller-supplied context (run id, template version, customer id, ...)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Occupation category the task was classified under
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occupation_category: Option<OccupationCategory>,
+}
+
+impl TaskCompletionRecord {
+    /// How long the task ran: from `started_at` to `ended_at` when both were
+    /// recorded, `wall_clock_seconds` otherwise.
+    pub fn duration(&self) -> Duration {
+        match (self.started_at, self.ended_at) {
+            (Some(started), Some(ended)) => (ended - started).to_std().unwrap_or_default(),
+            _ => Duration::try_from_secs_f64(self.wall_clock_seconds).unwrap_or_default(),
+        }
+    }
 }
 
 /// Economic analytics summary.
@@ -287,6 +1009,223 @@ pub struct EconomicAnalytics {
     pub tasks_paid: usize,
     /// Number of tasks rejected (below threshold)
     pub tasks_rejected: usize,
+    /// Number of tasks that ended with `TaskStatus::Completed`
+    #[serde(default)]
+    pub tasks_completed: usize,
+    /// Number of tasks that ended aborted, failed, timed out, or rejected
+    #[serde(default)]
+    pub tasks_aborted: usize,
+    /// Share of ended tasks that completed (0.0-1.0)
+    #[serde(default)]
+    pub completion_rate: f64,
+    /// Cost spent on tasks that did not complete
+    #[serde(default)]
+    pub aborted_task_cost: f64,
+    /// Balance when the analytics were computed
+    #[serde(default)]
+    pub balance: f64,
+    /// Initial balance (for status projections)
+    #[serde(default)]
+    pub initial_balance: f64,
+    /// EWMA smoothing factor for `forecast_spend` (0.0-1.0)
+    #[serde(default)]
+    pub forecast_alpha: f64,
+    /// LLM usage per call date, provider, and model
+    #[serde(default)]
+    pub llm_usage: Vec<LlmUsageEntry>,
+    /// Date and cost of each LLM call, for `cost_distribution`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub llm_call_costs: Vec<(NaiveDate, f64)>,
+}
+
+impl EconomicAnalytics {
+    /// Costs per tag for tasks dated within `range`.
+    ///
+    /// A task with several tags counts fully towards each of them, so totals
+    /// summed across tags can exceed total spend. Untagged tasks and tasks
+    /// without a parseable date are left out.
+    pub fn cost_by_tag(&self, range: impl RangeBounds<NaiveDate>) -> HashMap<String, TagSummary> {
+        let mut by_tag: HashMap<String, TagSummary> = HashMap::new();
+        for task in self.by_task.values() {
+            let Ok(date) = task.date.parse::<NaiveDate>() else {
+                continue;
+            };
+            if !range.contains(&date) {
+                continue;
+            }
+            for tag in &task.tags {
+                let summary = by_tag.entry(tag.clone()).or_default();
+                summary.costs.add(&task.costs);
+                summary.total += task.total;
+                summary.tasks += 1;
+            }
+        }
+        by_tag
+    }
+
+    /// Tasks dated within `range` whose metadata maps `key` to `value`,
+    /// oldest first.
+    pub fn filter_by_metadata(
+        &self,
+        key: &str,
+        value: &str,
+        range: impl RangeBounds<NaiveDate>,
+    ) -> Vec<&TaskCostSummary> {
+        let mut tasks: Vec<&TaskCostSummary> = self
+            .by_task
+            .values()
+            .filter(|task| task.metadata.get(key).is_some_and(|v| v == value))
+            .filter(|task| task.date.parse::<NaiveDate>().is_ok_and(|date| range.contains(&date)))
+            .collect();
+        tasks.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.task_id.cmp(&b.task_id)));
+        tasks
+    }
+
+    /// LLM spend per provider for calls made within `range`, most
+    /// expensive first. Calls recorded without a provider count as
+    /// `unknown`.
+    pub fn cost_by_provider(&self, range: impl RangeBounds<NaiveDate>) -> Vec<UsageBreakdown> {
+        self.usage_breakdown(range, |entry| &entry.provider)
+    }
+
+    /// LLM spend per model for calls made within `range`, most expensive
+    /// first. Calls recorded without a model count as `unknown`.
+    pub fn cost_by_model(&self, range: impl RangeBounds<NaiveDate>) -> Vec<UsageBreakdown> {
+        self.usage_breakdown(range, |entry| &entry.model)
+    }
+
+    /// LLM spend per conversation for calls made within `range`, most
+    /// expensive first. Calls recorded without a session id count as
+    /// `none`.
+    pub fn cost_by_session(&self, range: impl RangeBounds<NaiveDate>) -> Vec<UsageBreakdown> {
+        self.usage_breakdown(range, |entry| &entry.session_id)
+    }
+
+    fn usage_breakdown(
+        &self,
+        range: impl RangeBounds<NaiveDate>,
+        key: impl Fn(&LlmUsageEntry) -> &String,
+    ) -> Vec<UsageBreakdown> {
+        let mut by_key: HashMap<&str, UsageBreakdown> = HashMap::new();
+        for entry in self.llm_usage.iter().filter(|entry| range.contains(&entry.date)) {
+            let name = key(entry);
+            let breakdown = by_key.entry(name).or_insert_with(|| UsageBreakdown {
+                name: name.clone(),
+                ..Default::default()
+            });
+            breakdown.cost += entry.cost;
+            breakdown.input_tokens += entry.input_tokens;
+            breakdown.output_tokens += entry.output_tokens;
+            breakdown.calls += entry.calls;
+        }
+        let mut breakdown: Vec<UsageBreakdown> = by_key.into_values().collect();
+        breakdown.sort_by(|a, b| b.cost.total_cmp(&a.cost).then_with(|| a.name.cmp(&b.name)));
+        breakdown
+    }
+
+    /// Share of `completions` that ended with `TaskStatus::Failed`
+    /// (0.0-1.0); 0.0 when there are none.
+    pub fn failure_rate(completions: &[TaskCompletionRecord]) -> f64 {
+        if completions.is_empty() {
+            return 0.0;
+        }
+        let failed = completions
+            .iter()
+            .filter(|record| record.status == TaskStatus::Failed)
+            .count();
+        failed as f64 / completions.len() as f64
+    }
+
+    /// Percentage (0-100) of the task spend in `completions` that went to
+    /// failed tasks; 0.0 when nothing was spent. Records without a cost
+    /// summary count as zero cost.
+    pub fn failure_cost_percentage(completions: &[TaskCompletionRecord]) -> f64 {
+        let (failed, total) = completions.iter().fold((0.0, 0.0), |(failed, total), record| {
+            let cost = record.cost_summary.as_ref().map_or(0.0, |summary| summary.total);
+            if record.status == TaskStatus::Failed {
+                (failed + cost, total + cost)
+            } else {
+                (failed, total + cost)
+            }
+        });
+        if total <= 0.0 {
+            return 0.0;
+        }
+        failed / total * 100.0
+    }
+
+    /// Mean `token_efficiency` across records.
+    ///
+    /// Records with an undefined efficiency (zero cost) are skipped; returns
+    /// `f64::NAN` if no record has a defined efficiency.
+    pub fn average_token_efficiency(records: &[TaskCostRecord]) -> f64 {
+        let (sum, count) = records
+            .iter()
+            .map(TaskCostRecord::token_efficiency)
+            .filter(|efficiency| !efficiency.is_nan())
+            .fold((0.0, 0usize), |(sum, count), efficiency| (sum + efficiency, count + 1));
+        if count == 0 {
+            return f64::NAN;
+        }
+        sum / count as f64
+    }
+
+    /// Record with the highest `token_efficiency`, ignoring zero-cost records.
+    pub fn most_efficient_task(records: &[TaskCostRecord]) -> Option<&TaskCostRecord> {
+        records
+            .iter()
+            .filter(|record| !record.token_efficiency().is_nan())
+            .max_by(|a, b| a.token_efficiency().total_cmp(&b.token_efficiency()))
+    }
+}
+
+/// LLM usage of one provider, model, and conversation on one day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LlmUsageEntry {
+    /// Date of the calls (UTC)
+    pub date: NaiveDate,
+    /// Provider name (`unknown` when not recorded)
+    pub provider: String,
+    /// Model name (`unknown` when not recorded)
+    pub model: String,
+    /// Conversation id (`none` when not recorded)
+    #[serde(default = "no_session")]
+    pub session_id: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub calls: usize,
+    /// Total cost in USD
+    pub cost: f64,
+}
+
+fn no_session() -> String {
+    "none".to_string()
+}
+
+/// Spend of one provider, model, or conversation, as returned by
+/// `EconomicAnalytics::cost_by_provider`, `cost_by_model`, and
+/// `cost_by_session`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UsageBreakdown {
+    /// Provider or model name, or conversation id
+    pub name: String,
+    /// Total cost in USD
+    pub cost: f64,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    pub calls: usize,
+}
+
+/// Cost summary for a single tag.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TagSummary {
+    /// Costs by channel
+    #[serde(flatten)]
+    pub costs: CostBreakdown,
+    /// Total cost
+    pub total: f64,
+    /// Number of tasks carrying the tag
+    pub tasks: usize,
 }
 
 /// Cost summary for a single date.
@@ -311,12 +1250,906 @@ pub struct TaskCostSummary {
     pub total: f64,
     /// Date of the task
     pub date: String,
+    /// Task identifier
+    #[serde(default)]
+    pub task_id: String,
+    /// Normalized task tags
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Token counts keyed by model (`"unknown"` when the model was not reported)
+    #[serde(default)]
+    pub tokens_by_model: HashMap<String, ModelTokenUsage>,
+    /// Number of LLM calls made during the task
+    #[serde(default)]
+    pub llm_calls: usize,
+    /// Wall-clock duration of the task in seconds
+    #[serde(default)]
+    pub duration_seconds: f64,
+    /// Metadata the task was started with
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub metadata: BTreeMap<String, String>,
+    /// Share of costs incurred outside any task attributed to this task
+    /// (USD), not part of `total`
+    #[serde(default)]
+    pub prorated_overhead: f64,
+}
+
+impl TaskCostSummary {
+    /// Cost of non-LLM services (search, OCR, embeddings, images, other).
+    pub fn service_cost(&self) -> f64 {
+        self.costs.total() - self.costs.llm_tokens
+    }
+}
+
+/// Token counts for a single model.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelTokenUsage {
+    /// Number of input tokens
+    pub input_tokens: u64,
+    /// Number of output tokens
+    pub output_tokens: u64,
+    /// Number of calls
+    pub calls: usize,
+}
+
+/// Spend on one LLM model, as ranked by
+/// `EconomicTracker::get_model_cost_ranking`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModelCostEntry {
+    /// Model name (`unknown` for calls tracked without one)
+    pub model_name: String,
+    /// Total cost in USD
+    pub total_cost_usd: f64,
+    /// Number of input tokens
+    pub total_input_tokens: u64,
+    /// Number of output tokens
+    pub total_output_tokens: u64,
+    /// Number of calls
+    pub call_count: u64,
+    /// Total cost divided by call count
+    pub average_cost_per_call: f64,
+}
+
+/// How the tasks of one occupation category fared, as reported by
+/// `EconomicTracker::get_category_performance_summary`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CategoryPerformance {
+    /// Number of ended tasks in the category
+    pub task_count: u64,
+    /// Cost of those tasks (USD)
+    pub total_cost: f64,
+    /// Work income paid for those tasks (USD)
+    pub total_income: f64,
+    /// Income minus cost (USD)
+    pub net_pnl: f64,
+    /// Mean evaluation score (0.0-1.0)
+    pub average_quality: f64,
+    /// Share of the tasks that were paid (0.0-1.0)
+    pub success_rate: f64,
+}
+
+/// How many tasks it takes to recover the fixed overhead, as reported by
+/// `EconomicTracker::get_break_even_task_count`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BreakEvenAnalysis {
+    /// Startup cost to recover (USD), from `EconomicConfig::fixed_overhead_usd`
+    pub fixed_overhead: f64,
+    /// Mean work income minus cost of the ended tasks (USD)
+    pub average_task_profit: f64,
+    /// Ended tasks needed to recover the overhead at that profit
+    pub break_even_tasks: u64,
+    /// Tasks ended so far
+    pub current_task_count: u64,
+    /// Whether enough tasks have ended to recover the overhead
+    pub is_profitable: bool,
+}
+
+/// A task that spent more than its estimated budget, as listed by
+/// `EconomicTracker::get_tasks_over_budget`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverBudgetTask {
+    /// Task identifier
+    pub task_id: String,
+    /// Budget the task was started with (USD)
+    pub estimated_cost: f64,
+    /// Total cost of the task (USD)
+    pub actual_cost: f64,
+    /// `actual_cost - estimated_cost`
+    pub overage: f64,
+    /// Overage as a percentage of the budget
+    pub overage_pct: f64,
+}
+
+/// Answer of `EconomicTracker::can_afford` for an estimated request.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "decision", rename_all = "snake_case")]
+pub enum AffordabilityDecision {
+    /// The request fits within every limit
+    Affordable {
+        /// Most that could be spent now (USD)
+        headroom_usd: f64,
+    },
+    /// The request would break `limit`; a cheaper model or a shorter
+    /// context that fits in `headroom_usd` would not
+    Unaffordable {
+        /// Tightest limit, the one the request would break
+        limit: SpendingLimit,
+        /// Most that could be spent now (USD)
+        headroom_usd: f64,
+    },
+}
+
+impl AffordabilityDecision {
+    /// Whether the request can be sent as estimated.
+    pub fn is_affordable(&self) -> bool {
+        matches!(self, Self::Affordable { .. })
+    }
+
+    /// Most that could be spent now (USD).
+    pub fn headroom_usd(&self) -> f64 {
+        match self {
+            Self::Affordable { headroom_usd } | Self::Unaffordable { headroom_usd, .. } => {
+                *headroom_usd
+            }
+        }
+    }
+}
+
+/// Limit checked by `EconomicTracker::can_afford`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpendingLimit {
+    /// The current balance
+    Balance,
+    /// The balance above `reserve_floor`
+    ReserveFloor,
+    /// What is left of `daily_spend_limit` today
+    DailyLimit,
+    /// What is left of `max_cost_per_task` for the current task
+    TaskBudget,
+}
+
+/// Projected effect of LLM price changes on past spend, from
+/// `EconomicTracker::cost_sensitivity_analysis`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityReport {
+    /// Total task cost as recorded (USD)
+    pub current_total_cost: f64,
+    /// Total task cost had the new prices applied (USD)
+    pub revised_total_cost: f64,
+    /// `revised_total_cost - current_total_cost`
+    pub delta_usd: f64,
+    /// Delta as a percentage of the current total (0 when nothing was spent)
+    pub delta_pct: f64,
+    /// Cost delta per repriced model, largest change first
+    pub per_model_impact: Vec<(String, f64)>,
+}
+
+/// Past LLM spend replayed at alternative token prices, from
+/// `EconomicTracker::simulate_pricing_model`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PricingSimulationResult {
+    /// Recorded cost of the replayed LLM calls (USD)
+    pub current_total_cost: f64,
+    /// Cost of the same calls at the alternative prices (USD)
+    pub alternative_total_cost: f64,
+    /// `current_total_cost - alternative_total_cost`
+    pub savings_usd: f64,
+    /// Savings as a percentage of the current total (0 when nothing was spent)
+    pub savings_pct: f64,
+    /// Cumulative tokens after which the alternative stayed at or below the
+    /// recorded cost for the rest of the history; `None` if it ends up dearer
+    pub breakeven_at_tokens: Option<u64>,
+    /// Task ID, current cost, and alternative cost of each task with LLM
+    /// calls, in the order the tasks were recorded
+    pub per_task_comparison: Vec<(String, f64, f64)>,
+}
+
+/// A task that cost unusually much, as found by
+/// `EconomicTracker::detect_cost_anomalies`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostAnomaly {
+    /// When the task ended (now for a task still running)
+    pub timestamp: SystemTime,
+    /// Task identifier
+    pub task_id: String,
+    /// Total task cost in USD
+    pub cost_usd: f64,
+    /// Standard deviations above the mean task cost
+    pub z_score: f64,
+    /// How far past the threshold the Z-score is
+    pub severity: AnomalySeverity,
+}
+
+/// How far a [`CostAnomaly`] is past the detection threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnomalySeverity {
+    /// Above the threshold, but less than 1.5 times it
+    Elevated,
+    /// At least 1.5 times the threshold
+    High,
+    /// At least twice the threshold
+    Critical,
+}
+
+impl AnomalySeverity {
+    /// Severity of `z_score` against the threshold `sensitivity`.
+    pub fn from_z_score(z_score: f64, sensitivity: f64) -> Self {
+        if z_score >= 2.0 * sensitivity {
+            Self::Critical
+        } else if z_score >= 1.5 * sensitivity {
+            Self::High
+        } else {
+            Self::Elevated
+        }
+    }
+}
+
+/// Position in a JSONL record file to resume reading from.
+///
+/// Taken from [`RecordReader::resume_token`] and passed to
+/// [`RecordReader::open_at`]; it can be persisted between runs.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResumeToken {
+    /// Byte offset of the next unread line
+    pub offset: u64,
+    /// Number of lines before `offset` (for error line numbers)
+    pub line: usize,
+}
+
+/// Streaming reader over the records of a JSONL file.
+///
+/// Lines are read one at a time through a buffered reader, so memory use
+/// does not grow with the file. A line that does not decode as `T` yields
+/// [`EconomicError::MalformedRecord`] and reading continues; an I/O error
+/// ends the stream. Blank lines are skipped.
+///
+/// An unterminated last line that does not decode is treated as a write in
+/// progress: the stream ends before it and the resume token points at its
+/// start, so a consumer tailing the file picks it up once it is complete.
+///
+/// With the `archive-read` feature, files ending in `.gz` (rotated archives)
+/// are decompressed as they are read; resume offsets then count
+/// decompressed bytes. Without it, opening one fails with
+/// [`EconomicError::ArchiveReadDisabled`].
+pub struct RecordReader<T> {
+    path: PathBuf,
+    reader: RecordSource,
+    position: ResumeToken,
+    buf: Vec<u8>,
+    done: bool,
+    _record: PhantomData<fn() -> T>,
+}
+
+impl<T: DeserializeOwned> RecordReader<T> {
+    /// Read the records of `path` from the beginning.
+    pub fn open(path: &Path) -> Result<Self> {
+        Self::open_at(path, ResumeToken::default())
+    }
+
+    /// Read the records of `path` from `token`.
+    pub fn open_at(path: &Path, token: ResumeToken) -> Result<Self> {
+        let mut file = File::open(path).at_path(path)?;
+        if path.extension().is_some_and(|ext| ext == "gz") {
+            #[cfg(not(feature = "archive-read"))]
+            return Err(EconomicError::ArchiveReadDisabled {
+                path: path.to_path_buf(),
+            });
+            #[cfg(feature = "archive-read")]
+            {
+                // Gzip streams cannot seek, so skip the decompressed prefix
+                let mut reader = BufReader::new(GzDecoder::new(file));
+                let mut prefix = std::io::Read::take(&mut reader, token.offset);
+                std::io::copy(&mut prefix, &mut std::io::sink()).at_path(path)?;
+                return Ok(Self::from_source(path, RecordSource::Gzip(reader), token));
+            }
+        }
+        file.seek(SeekFrom::Start(token.offset)).at_path(path)?;
+        Ok(Self::from_source(path, RecordSource::Plain(BufReader::new(file)), token))
+    }
+
+    fn from_source(path: &Path, reader: RecordSource, token: ResumeToken) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            reader,
+            position: token,
+            buf: Vec::new(),
+            done: false,
+            _record: PhantomData,
+        }
+    }
+
+    /// Where the next call to `next` would continue from.
+    pub fn resume_token(&self) -> ResumeToken {
+        self.position
+    }
+}
+
+/// Line source of a [`RecordReader`].
+enum RecordSource {
+    Plain(BufReader<File>),
+    #[cfg(feature = "archive-read")]
+    Gzip(BufReader<GzDecoder<File>>),
+}
+
+impl RecordSource {
+    fn read_until(&mut self, byte: u8, buf: &mut Vec<u8>) -> std::io::Result<usize> {
+        match self {
+            Self::Plain(reader) => reader.read_until(byte, buf),
+            #[cfg(feature = "archive-read")]
+            Self::Gzip(reader) => reader.read_until(byte, buf),
+        }
+    }
+}
+
+impl<T: DeserializeOwned> Iterator for RecordReader<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.done {
+            self.buf.clear();
+            let read = match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) => {
+                    self.done = true;
+                    return None;
+                }
+                Ok(read) => read,
+                Err(source) => {
+                    self.done = true;
+                    return Some(Err(EconomicError::Io {
+                        path: self.path.clone(),
+                        source,
+                    }));
+                }
+            };
+
+            let terminated = self.buf.ends_with(b"\n");
+            let line = self.buf.trim_ascii();
+            if line.is_empty() {
+                if terminated {
+                    self.position.offset += read as u64;
+                    self.position.line += 1;
+                }
+                continue;
+            }
+
+            let record = serde_json::from_slice::<T>(line);
+            if record.is_err() && !terminated {
+                self.done = true;
+                return None;
+            }
+            self.position.offset += read as u64;
+            self.position.line += 1;
+            return Some(record.map_err(|source| EconomicError::MalformedRecord {
+                path: self.path.clone(),
+                line: self.position.line,
+                source,
+            }));
+        }
+        None
+    }
+}
+
+/// Kind of an [`EconomicRecord`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordKind {
+    LlmCall,
+    ApiCall,
+    WorkIncome,
+    GrantIncome,
+    Refund,
+}
+
+impl RecordKind {
+    /// Snake-case name, as used in exports.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::LlmCall => "llm_call",
+            Self::ApiCall => "api_call",
+            Self::WorkIncome => "work_income",
+            Self::GrantIncome => "grant_income",
+            Self::Refund => "refund",
+        }
+    }
+}
+
+impl fmt::Display for RecordKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A charge or credit read from the economic logs, as returned by
+/// [`RecordQuery`].
+///
+/// LLM and API calls carry the id, tags, and metadata of the task that
+/// made them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EconomicRecord {
+    LlmCall {
+        task_id: String,
+        tags: Vec<String>,
+        metadata: BTreeMap<String, String>,
+        call: LlmCallRecord,
+    },
+    ApiCall {
+        task_id: String,
+        tags: Vec<String>,
+        metadata: BTreeMap<String, String>,
+        call: ApiCallRecord,
+    },
+    WorkIncome(WorkIncomeRecord),
+    GrantIncome(GrantIncomeRecord),
+    Refund(RefundRecord),
+}
+
+impl EconomicRecord {
+    pub fn kind(&self) -> RecordKind {
+        match self {
+            Self::LlmCall { .. } => RecordKind::LlmCall,
+            Self::ApiCall { .. } => RecordKind::ApiCall,
+            Self::WorkIncome(_) => RecordKind::WorkIncome,
+            Self::GrantIncome(_) => RecordKind::GrantIncome,
+            Self::Refund(_) => RecordKind::Refund,
+        }
+    }
+
+    pub fn timestamp(&self) -> DateTime<Utc> {
+        match self {
+            Self::LlmCall { call, .. } => call.timestamp,
+            Self::ApiCall { call, .. } => call.timestamp,
+            Self::WorkIncome(record) => record.timestamp,
+            Self::GrantIncome(record) => record.timestamp,
+            Self::Refund(record) => record.timestamp,
+        }
+    }
+
+    /// USD charged (calls) or credited (income, refunds); never negative
+    /// for well-formed records.
+    pub fn amount_usd(&self) -> f64 {
+        match self {
+            Self::LlmCall { call, .. } => call.cost,
+            Self::ApiCall { call, .. } => call.cost,
+            Self::WorkIncome(record) => record.actual_payment,
+            Self::GrantIncome(record) => record.amount,
+            Self::Refund(record) => record.amount(),
+        }
+    }
+
+    /// Task the record belongs to; `None` for grants and refunds.
+    pub fn task_id(&self) -> Option<&str> {
+        match self {
+            Self::LlmCall { task_id, .. } | Self::ApiCall { task_id, .. } => Some(task_id),
+            Self::WorkIncome(record) => Some(&record.task_id),
+            Self::GrantIncome(_) | Self::Refund(_) => None,
+        }
+    }
+
+    /// Model that served an LLM call, when known.
+    pub fn model(&self) -> Option<&str> {
+        match self {
+            Self::LlmCall { call, .. } => call.model.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Provider that served an LLM call, when known.
+    pub fn provider(&self) -> Option<&str> {
+        match self {
+            Self::LlmCall { call, .. } => call.provider.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Tags of the task that made a call.
+    pub fn tags(&self) -> &[String] {
+        match self {
+            Self::LlmCall { tags, .. } | Self::ApiCall { tags, .. } => tags,
+            _ => &[],
+        }
+    }
+
+    /// Caller-supplied context of the task or payment, when the record has
+    /// any.
+    pub fn metadata(&self) -> Option<&BTreeMap<String, String>> {
+        match self {
+            Self::LlmCall { metadata, .. } | Self::ApiCall { metadata, .. } => Some(metadata),
+            Self::WorkIncome(record) => Some(&record.metadata),
+            Self::GrantIncome(_) | Self::Refund(_) => None,
+        }
+    }
+
+    /// What the amount was paid to or received for: the API name of a
+    /// call, the grant id, or the refunded record id.
+    pub fn reference(&self) -> &str {
+        match self {
+            Self::LlmCall { call, .. } => &call.api_name,
+            Self::ApiCall { call, .. } => &call.api_name,
+            Self::WorkIncome(_) => "",
+            Self::GrantIncome(record) => &record.grant_id,
+            Self::Refund(record) => &record.original_record_id,
+        }
+    }
+}
+
+/// Composable filter over the records of an economic data directory.
+///
+/// Every filter narrows the result; a record matches when it passes all of
+/// them. Filters on a field a record kind does not have (e.g. `model` on a
+/// grant) exclude that record. The query reads the logs on disk, including
+/// rotated archives that can overlap the date range, so records still in a
+/// tracker's write buffer are only seen after `EconomicTracker::flush`.
+///
+/// ```ignore
+/// let total = RecordQuery::new()
+///     .kind(RecordKind::LlmCall)
+///     .provider("openrouter")
+///     .tag("research")
+///     .sum_cost(&data_dir)?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RecordQuery {
+    range: Option<DateRange>,
+    model: Option<String>,
+    provider: Option<String>,
+    task_id: Option<String>,
+    tags: Vec<String>,
+    metadata: Vec<(String, String)>,
+    min_cost: Option<f64>,
+    max_cost: Option<f64>,
+    kinds: Vec<RecordKind>,
+}
+
+impl RecordQuery {
+    /// Query matching every record.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only records timestamped within `range`.
+    pub fn range(mut self, range: DateRange) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Only LLM calls served by `model`.
+    pub fn model(mut self, model: impl Into<String>) -> Self {
+        self.model = Some(model.into());
+        self
+    }
+
+    /// Only LLM calls served by `provider`.
+    pub fn provider(mut self, provider: impl Into<String>) -> Self {
+        self.provider = Some(provider.into());
+        self
+    }
+
+    /// Only calls and work income of the task `task_id`.
+    pub fn task_id(mut self, task_id: impl Into<String>) -> Self {
+        self.task_id = Some(task_id.into());
+        self
+    }
+
+    /// Only calls of tasks tagged `tag`; repeat to require several tags.
+    /// Tags are compared case-insensitively.
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tags.push(tag.trim().to_lowercase());
+        self
+    }
+
+    /// Only records whose task or payment metadata has `key` set to `value`;
+    /// repeat to require several entries.
+    pub fn metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.push((key.into(), value.into()));
+        self
+    }
+
+    /// Only records of at least `usd` (see [`EconomicRecord::amount_usd`]).
+    pub fn min_cost(mut self, usd: f64) -> Self {
+        self.min_cost = Some(usd);
+        self
+    }
+
+    /// Only records of at most `usd` (see [`EconomicRecord::amount_usd`]).
+    pub fn max_cost(mut self, usd: f64) -> Self {
+        self.max_cost = Some(usd);
+        self
+    }
+
+    /// Only records of `kind`; repeat to allow several kinds. Without this
+    /// filter every kind matches.
+    pub fn kind(mut self, kind: RecordKind) -> Self {
+        if !self.kinds.contains(&kind) {
+            self.kinds.push(kind);
+        }
+        self
+    }
+
+    /// Whether `record` passes every filter.
+    pub fn matches(&self, record: &EconomicRecord) -> bool {
+        if !self.wants(record.kind()) {
+            return false;
+        }
+        if self.range.is_some_and(|range| !range.contains(&record.timestamp())) {
+            return false;
+        }
+        if self.model.as_deref().is_some_and(|model| record.model() != Some(model))
+            || self
+                .provider
+                .as_deref()
+                .is_some_and(|provider| record.provider() != Some(provider))
+            || self.task_id.as_deref().is_some_and(|task_id| record.task_id() != Some(task_id))
+        {
+            return false;
+        }
+        if !self.tags.iter().all(|tag| record.tags().contains(tag)) {
+            return false;
+        }
+        if !self.metadata.is_empty() {
+            let Some(metadata) = record.metadata() else {
+                return false;
+            };
+            if !self
+                .metadata
+                .iter()
+                .all(|(key, value)| metadata.get(key) == Some(value))
+            {
+                return false;
+            }
+        }
+        let amount = record.amount_usd();
+        !(self.min_cost.is_some_and(|min| amount < min)
+            || self.max_cost.is_some_and(|max| amount > max))
+    }
+
+    /// Stream the matching records of `data_dir`.
+    ///
+    /// Logs are opened lazily and read one line at a time; each log is in
+    /// time order, but records of different logs are not interleaved.
+    /// Undecodable lines are skipped. Missing logs yield no records.
+    pub fn records(&self, data_dir: &Path) -> QueryResults {
+        let range = self
+            .range
+            .unwrap_or_else(|| DateRange::new(DateTime::<Utc>::MIN_UTC, DateTime::<Utc>::MAX_UTC));
+        let mut files = Vec::new();
+        for (name, log) in [
+            ("token_costs.jsonl", QueryLog::CostLog),
+            ("grant_income.jsonl", QueryLog::Grants),
+            ("refunds.jsonl", QueryLog::Refunds),
+        ] {
+            if log.kinds().iter().any(|kind| self.wants(*kind)) {
+                let paths = logs::log_files(data_dir, name, &range);
+                files.extend(paths.into_iter().map(|path| (path, log)));
+            }
+        }
+        QueryResults {
+            query: self.clone(),
+            files: files.into_iter(),
+            current: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Number of matching records in `data_dir`.
+    pub fn count(&self, data_dir: &Path) -> Result<usize> {
+        let mut count = 0;
+        for record in self.records(data_dir) {
+            record?;
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    /// Total [`amount_usd`](EconomicRecord::amount_usd) of the matching
+    /// records in `data_dir`. Charges and credits add up alike, so filter
+    /// by kind to keep them apart.
+    pub fn sum_cost(&self, data_dir: &Path) -> Result<f64> {
+        let mut total = 0.0;
+        for record in self.records(data_dir) {
+            total += record?.amount_usd();
+        }
+        Ok(total)
+    }
+
+    /// The matching records of `data_dir`.
+    pub fn collect_vec(&self, data_dir: &Path) -> Result<Vec<EconomicRecord>> {
+        self.records(data_dir).collect()
+    }
+
+    /// The matching records of `data_dir` as CSV:
+    /// `timestamp,kind,task_id,reference,model,provider,amount_usd`.
+    pub fn export_csv(&self, data_dir: &Path) -> Result<String> {
+        let mut out = String::from("timestamp,kind,task_id,reference,model,provider,amount_usd\n");
+        for record in self.records(data_dir) {
+            let record = record?;
+            let _ = writeln!(
+                out,
+                "{},{},{},{},{},{},{:.6}",
+                record.timestamp().to_rfc3339(),
+                record.kind(),
+                csv_field(record.task_id().unwrap_or_default()),
+                csv_field(record.reference()),
+                csv_field(record.model().unwrap_or_default()),
+                csv_field(record.provider().unwrap_or_default()),
+                record.amount_usd()
+            );
+        }
+        Ok(out)
+    }
+
+    fn wants(&self, kind: RecordKind) -> bool {
+        self.kinds.is_empty() || self.kinds.contains(&kind)
+    }
+}
+
+/// Streaming results of a [`RecordQuery`], from [`RecordQuery::records`].
+///
+/// An I/O error is yielded once and ends the log it occurred in; reading
+/// continues with the next log.
+pub struct QueryResults {
+    query: RecordQuery,
+    files: std::vec::IntoIter<(PathBuf, QueryLog)>,
+    current: Option<QuerySource>,
+    pending: VecDeque<EconomicRecord>,
+}
+
+/// Log a [`QueryResults`] reads from.
+#[derive(Debug, Clone, Copy)]
+enum QueryLog {
+    CostLog,
+    Grants,
+    Refunds,
+}
+
+impl QueryLog {
+    fn kinds(self) -> &'static [RecordKind] {
+        match self {
+            Self::CostLog => &[RecordKind::LlmCall, RecordKind::ApiCall, RecordKind::WorkIncome],
+            Self::Grants => &[RecordKind::GrantIncome],
+            Self::Refunds => &[RecordKind::Refund],
+        }
+    }
+}
+
+/// Open reader of a [`QueryLog`].
+enum QuerySource {
+    CostLog(RecordReader<CostLogRecord>),
+    Grants(RecordReader<GrantIncomeRecord>),
+    Refunds(RecordReader<RefundRecord>),
+}
+
+impl QuerySource {
+    fn open(path: &Path, log: QueryLog) -> Result<Self> {
+        Ok(match log {
+            QueryLog::CostLog => Self::CostLog(RecordReader::open(path)?),
+            QueryLog::Grants => Self::Grants(RecordReader::open(path)?),
+            QueryLog::Refunds => Self::Refunds(RecordReader::open(path)?),
+        })
+    }
+
+    /// Append the records of the next log line to `out`; `false` once the
+    /// log is exhausted.
+    fn read_next(&mut self, out: &mut VecDeque<EconomicRecord>) -> Result<bool> {
+        match self {
+            Self::CostLog(reader) => match logs::next_record(reader)? {
+                Some(CostLogRecord::Task(task)) => {
+                    let TaskCostRecord {
+                        task_id,
+                        tags,
+                        metadata,
+                        llm_usage,
+                        api_usage,
+                        ..
+                    } = *task;
+                    for call in llm_usage.calls_detail {
+                        out.push_back(EconomicRecord::LlmCall {
+                            task_id: task_id.clone(),
+                            tags: tags.clone(),
+                            metadata: metadata.clone(),
+                            call,
+                        });
+                    }
+                    for call in api_usage.calls_detail {
+                        out.push_back(EconomicRecord::ApiCall {
+                            task_id: task_id.clone(),
+                            tags: tags.clone(),
+                            metadata: metadata.clone(),
+                            call,
+                        });
+                    }
+                }
+                Some(CostLogRecord::Income(income)) => {
+                    out.push_back(EconomicRecord::WorkIncome(income));
+                }
+                None => return Ok(false),
+            },
+            Self::Grants(reader) => match logs::next_record(reader)? {
+                Some(grant) => out.push_back(EconomicRecord::GrantIncome(grant)),
+                None => return Ok(false),
+            },
+            Self::Refunds(reader) => match logs::next_record(reader)? {
+                Some(refund) => out.push_back(EconomicRecord::Refund(refund)),
+                None => return Ok(false),
+            },
+        }
+        Ok(true)
+    }
+}
+
+impl Iterator for QueryResults {
+    type Item = Result<EconomicRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(record) = self.pending.pop_front() {
+                return Some(Ok(record));
+            }
+            let source = match &mut self.current {
+                Some(source) => source,
+                None => {
+                    let (path, log) = self.files.next()?;
+                    match QuerySource::open(&path, log) {
+                        Ok(source) => self.current.insert(source),
+                        Err(error) => return Some(Err(error)),
+                    }
+                }
+            };
+            match source.read_next(&mut self.pending) {
+                Ok(true) => {
+                    let query = &self.query;
+                    self.pending.retain(|record| query.matches(record));
+                }
+                Ok(false) => self.current = None,
+                Err(error) => {
+                    self.current = None;
+                    return Some(Err(error));
+                }
+            }
+        }
+    }
+}
+
+/// Keeps totals that are only non-zero with optional features enabled out
+/// of older-format records. Takes a reference because serde's
+/// `skip_serializing_if` passes the field by reference.
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(value: &f64) -> bool {
+    *value == 0.0
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn time_of_use_multiplier_matches_hour_ranges() {
+        let pricing = TimeOfUsePricing::new(vec![
+            (HourRange::new(9, 17), 1.5),
+            (HourRange::new(22, 6), 0.5),
+        ]);
+        let base = TokenPricing::default().calculate_cost(1_000_000, 1_000_000);
+        let peak = base * pricing.multiplier_at(14);
+        assert!((peak - base * 1.5).abs() < 1e-12);
+
+        assert!((pricing.multiplier_at(17) - 1.0).abs() < f64::EPSILON);
+        assert!((pricing.multiplier_at(23) - 0.5).abs() < f64::EPSILON);
+        assert!((pricing.multiplier_at(3) - 0.5).abs() < f64::EPSILON);
+        assert!((TimeOfUsePricing::default().multiplier_at(14) - 1.0).abs() < f64::EPSILON);
+        assert!(HourRange::new(0, 0).contains(12));
+    }
+
+    #[test]
+    fn break_even_quality_is_cost_share_of_payment() {
+        assert!((PricingModel::compute_break_even_quality(25.0, 100.0) - 0.25).abs() < 1e-9);
+        assert!((PricingModel::compute_break_even_quality(150.0, 100.0) - 1.0).abs() < 1e-9);
+        assert!(PricingModel::compute_break_even_quality(0.0, 100.0).abs() < f64::EPSILON);
+        assert!(PricingModel::compute_break_even_quality(0.0, 0.0).abs() < f64::EPSILON);
+        assert!((PricingModel::compute_break_even_quality(1.0, 0.0) - 1.0).abs() < 1e-9);
+    }
+
     #[test]
     fn cost_breakdown_total() {
         let breakdown = CostBreakdown {
@@ -324,6 +2157,7 @@ mod tests {
             search_api: 0.5,
             ocr_api: 0.25,
             other_api: 0.1,
+            ..Default::default()
         };
         assert!((breakdown.total() - 1.85).abs() < f64::EPSILON);
     }
@@ -335,12 +2169,14 @@ mod tests {
             search_api: 0.5,
             ocr_api: 0.0,
             other_api: 0.0,
+            ..Default::default()
         };
         let b = CostBreakdown {
             llm_tokens: 0.5,
             search_api: 0.25,
             ocr_api: 0.1,
             other_api: 0.05,
+            ..Default::default()
         };
         a.add(&b);
         assert!((a.llm_tokens - 1.5).abs() < f64::EPSILON);
@@ -348,6 +2184,240 @@ mod tests {
         assert!((a.total() - 2.4).abs() < f64::EPSILON);
     }
 
+    #[test]
+    fn cost_breakdown_includes_embedding_and_image_costs() {
+        let mut a = CostBreakdown {
+            llm_tokens: 1.0,
+            embedding_api: 0.02,
+            image_api: 0.08,
+            ..Default::default()
+        };
+        assert!((a.total() - 1.1).abs() < 1e-9);
+
+        a.add(&a.clone());
+        assert!((a.embedding_api - 0.04).abs() < f64::EPSILON);
+        assert!((a.image_api - 0.16).abs() < f64::EPSILON);
+
+        a.reset();
+        assert!(a.total().abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn image_pricing_by_size_class() {
+        let pricing = ImagePricing::default();
+        assert!((pricing.price_for(ImageSizeClass::Small) - 0.016).abs() < f64::EPSILON);
+        assert!((pricing.price_for(ImageSizeClass::Medium) - 0.04).abs() < f64::EPSILON);
+        assert!((pricing.price_for(ImageSizeClass::Large) - 0.08).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn abort_reasons_map_to_statuses() {
+        assert_eq!(TaskAbortReason::Cancelled.status(), TaskStatus::Aborted);
+        assert_eq!(TaskAbortReason::Failed.status(), TaskStatus::Failed);
+        assert_eq!(TaskAbortReason::TimedOut.status(), TaskStatus::TimedOut);
+        assert_eq!(TaskAbortReason::Rejected.status(), TaskStatus::Rejected);
+        assert!(TaskStatus::Completed.is_income_eligible());
+        assert!(!TaskStatus::TimedOut.is_income_eligible());
+    }
+
+    #[test]
+    fn token_efficiency_is_output_tokens_per_dollar() {
+        use crate::economic::{EconomicConfig, EconomicTracker};
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let tracker =
+            EconomicTracker::new("efficiency-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        for (task_id, cost) in [("a", 0.5), ("b", 0.1), ("free", 0.0)] {
+            tracker.start_task(task_id, None, &[]).unwrap();
+            tracker.track_tokens(0, 1000, "agent", Some(cost), Duration::ZERO).unwrap();
+            tracker.end_task(task_id).unwrap();
+        }
+        tracker.flush().unwrap();
+
+        let records: Vec<TaskCostRecord> = RecordReader::open(&tmp.path().join("token_costs.jsonl"))
+            .unwrap()
+            .collect::<Result<_>>()
+            .unwrap();
+        assert_eq!(records.len(), 3);
+        assert!((records[0].token_efficiency() - 2000.0).abs() < 1e-9);
+        assert!((records[1].token_efficiency() - 10_000.0).abs() < 1e-9);
+        assert!(records[2].token_efficiency().is_nan());
+
+        let average = EconomicAnalytics::average_token_efficiency(&records);
+        assert!((average - 6000.0).abs() < 1e-9);
+        let best = EconomicAnalytics::most_efficient_task(&records).unwrap();
+        assert_eq!(best.task_id, "b");
+
+        assert!(EconomicAnalytics::average_token_efficiency(&records[2..]).is_nan());
+        assert!(EconomicAnalytics::most_efficient_task(&records[2..]).is_none());
+    }
+
+    #[test]
+    fn cost_by_tag_counts_tasks_in_every_tag() {
+        let mut analytics = EconomicAnalytics::default();
+        for (task_id, date, tags, cost) in [
+            ("a", "2025-01-01", vec!["client:acme", "experiment:prompt-v2"], 1.0),
+            ("b", "2025-01-02", vec!["client:acme"], 2.0),
+            ("c", "2025-02-01", vec!["client:acme"], 4.0),
+            ("d", "2025-01-01", vec![], 8.0),
+        ] {
+            analytics.by_task.insert(
+                task_id.into(),
+                TaskCostSummary {
+                    costs: CostBreakdown {
+                        llm_tokens: cost,
+                        ..Default::default()
+                    },
+                    total: cost,
+                    date: date.into(),
+                    task_id: task_id.into(),
+                    tags: tags.into_iter().map(String::from).collect(),
+                    ..Default::default()
+                },
+            );
+        }
+
+        let january = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap()
+            ..NaiveDate::from_ymd_opt(2025, 2, 1).unwrap();
+        let by_tag = analytics.cost_by_tag(january);
+        assert_eq!(by_tag.len(), 2);
+        assert_eq!(by_tag["client:acme"].tasks, 2);
+        assert!((by_tag["client:acme"].total - 3.0).abs() < f64::EPSILON);
+        assert!((by_tag["experiment:prompt-v2"].costs.llm_tokens - 1.0).abs() < f64::EPSILON);
+
+        let all_time = analytics.cost_by_tag(..);
+        assert!((all_time["client:acme"].total - 7.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn record_reader_reports_bad_lines_and_resumes() {
+        use std::io::Write;
+
+        let pricing = |input: f64| {
+            format!(r#"{{"input_price_per_million":{input},"output_price_per_million":1.0}}"#)
+        };
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("records.jsonl");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "{}\nnot json\n\n{}", pricing(1.0), pricing(3.0)).unwrap();
+
+        let mut reader = RecordReader::<TokenPricing>::open(&path).unwrap();
+        let first = reader.next().unwrap().unwrap();
+        assert!((first.input_price_per_million - 1.0).abs() < f64::EPSILON);
+        let error = reader.next().unwrap().unwrap_err();
+        assert!(matches!(
+            error,
+            EconomicError::MalformedRecord { line: 2, .. }
+        ));
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+        let token = reader.resume_token();
+        assert_eq!(token.line, 4);
+
+        // A half-written line is left for the next read
+        let partial = pricing(5.0);
+        let (head, rest) = partial.split_at(20);
+        write!(file, "{head}").unwrap();
+        let mut tail = RecordReader::<TokenPricing>::open_at(&path, token).unwrap();
+        assert!(tail.next().is_none());
+        assert_eq!(tail.resume_token(), token);
+
+        writeln!(file, "{rest}").unwrap();
+        let mut tail = RecordReader::<TokenPricing>::open_at(&path, token).unwrap();
+        let resumed = tail.next().unwrap().unwrap();
+        assert!((resumed.input_price_per_million - 5.0).abs() < f64::EPSILON);
+        assert!(tail.next().is_none());
+        let len = std::fs::metadata(&path).unwrap().len();
+        assert_eq!(tail.resume_token().offset, len);
+    }
+
+    #[test]
+    fn record_query_combines_filters() {
+        use crate::economic::{EconomicConfig, EconomicTracker};
+        use tempfile::TempDir;
+
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            min_evaluation_threshold: 0.5,
+            ..Default::default()
+        };
+        let tracker =
+            EconomicTracker::new("query-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        let metadata = BTreeMap::from([("customer".to_string(), "acme".to_string())]);
+        tracker
+            .start_task_with_metadata("task-1", None, &["Research"], metadata)
+            .unwrap();
+        tracker
+            .track_provider_tokens("openrouter", "gpt-4o", 1000, 500, "agent", Some(2.0))
+            .unwrap();
+        tracker
+            .track_provider_tokens("anthropic", "claude-sonnet", 2000, 100, "agent", Some(0.5))
+            .unwrap();
+        tracker.track_flat_api_call(0.25, "tavily_search");
+        tracker.end_task("task-1").unwrap();
+        tracker.add_work_income(10.0, "task-1", 0.9, "report").unwrap();
+
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker
+            .track_provider_tokens("openrouter", "gpt-4o", 100, 50, "agent", Some(1.0))
+            .unwrap();
+        tracker.end_task("task-2").unwrap();
+        tracker.add_grant_income(5.0, "grant-1", "seed").unwrap();
+        tracker.flush().unwrap();
+
+        let dir = tmp.path();
+        assert_eq!(RecordQuery::new().count(dir).unwrap(), 6);
+
+        let openrouter = RecordQuery::new().provider("openrouter");
+        assert_eq!(openrouter.count(dir).unwrap(), 2);
+        assert!((openrouter.sum_cost(dir).unwrap() - 3.0).abs() < 1e-9);
+
+        let records = openrouter.clone().tag(" research ").collect_vec(dir).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].task_id(), Some("task-1"));
+        assert_eq!(records[0].model(), Some("gpt-4o"));
+
+        let acme = RecordQuery::new().metadata("customer", "acme");
+        assert_eq!(acme.count(dir).unwrap(), 3);
+        let acme_calls = acme.clone().kind(RecordKind::LlmCall).kind(RecordKind::ApiCall);
+        assert!((acme_calls.sum_cost(dir).unwrap() - 2.75).abs() < 1e-9);
+
+        let income = RecordQuery::new()
+            .kind(RecordKind::WorkIncome)
+            .kind(RecordKind::GrantIncome)
+            .min_cost(6.0);
+        let records = income.collect_vec(dir).unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].kind(), RecordKind::WorkIncome);
+
+        let cheap = RecordQuery::new().task_id("task-1").max_cost(0.5);
+        assert_eq!(cheap.count(dir).unwrap(), 2);
+        let csv = cheap.export_csv(dir).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "timestamp,kind,task_id,reference,model,provider,amount_usd");
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1..]
+            .iter()
+            .any(|line| line.ends_with(",api_call,task-1,tavily_search,,,0.250000")));
+
+        let past = DateRange::new(
+            Utc::now() - chrono::Duration::days(2),
+            Utc::now() - chrono::Duration::days(1),
+        );
+        assert_eq!(RecordQuery::new().range(past).count(dir).unwrap(), 0);
+    }
+
     #[test]
     fn token_pricing_calculation() {
         let pricing = TokenPricing {
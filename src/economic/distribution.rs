@@ -0,0 +1,313 @@
+//! Cost distribution statistics for economic agents.
+//!
+//! Averages hide outliers: one task in twenty can cost ten times the median.
+//! [`CostDistribution`] reports percentiles of cost per task, per LLM call,
+//! and per day, plus trailing moving averages of daily spend and income for
+//! plotting. [`DurationBucket`]s do the same for how long tasks run.
+
+use super::costs::EconomicAnalytics;
+use chrono::{Days, NaiveDate};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::ops::RangeBounds;
+use std::time::Duration;
+
+/// Short moving-average window in days.
+const SHORT_WINDOW_DAYS: usize = 7;
+
+/// Long moving-average window in days.
+const LONG_WINDOW_DAYS: usize = 30;
+
+/// Nearest-rank percentiles of a set of costs (USD).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Percentiles {
+    /// Number of values the percentiles are taken from
+    pub count: usize,
+    pub p50: f64,
+    pub p90: f64,
+    pub p99: f64,
+}
+
+impl Percentiles {
+    /// Percentiles of `values`; all zero when there are none.
+    pub fn from_values(mut values: Vec<f64>) -> Self {
+        if values.is_empty() {
+            return Self::default();
+        }
+        values.sort_by(f64::total_cmp);
+        let len = values.len() as f64;
+        let rank = |p: f64| {
+            // Clamped to 1..=len first, so the cast is exact
+            let rank = (p * len).ceil().clamp(1.0, len);
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let index = rank as usize - 1;
+            values[index]
+        };
+        Self {
+            count: values.len(),
+            p50: rank(0.50),
+            p90: rank(0.90),
+            p99: rank(0.99),
+        }
+    }
+}
+
+/// One bucket of a task duration histogram.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DurationBucket {
+    /// Shortest duration in the bucket
+    pub lower_bound: Duration,
+    /// Upper end of the bucket, exclusive except for the last bucket
+    pub upper_bound: Duration,
+    /// Number of tasks in the bucket
+    pub count: u64,
+}
+
+/// Daily spend and income with trailing moving averages.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MovingAveragePoint {
+    pub date: NaiveDate,
+    /// Spend on this day
+    pub spend: f64,
+    /// Income on this day
+    pub income: f64,
+    /// Mean daily spend over the 7 days ending on this day
+    pub spend_7d: f64,
+    /// Mean daily spend over the 30 days ending on this day
+    pub spend_30d: f64,
+    /// Mean daily income over the 7 days ending on this day
+    pub income_7d: f64,
+    /// Mean daily income over the 30 days ending on this day
+    pub income_30d: f64,
+}
+
+/// Result of [`EconomicAnalytics::cost_distribution`].
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostDistribution {
+    /// Total cost per task
+    pub per_task: Percentiles,
+    /// Cost per LLM call
+    pub per_call: Percentiles,
+    /// Spend per calendar day, counting days without records as zero
+    pub per_day: Percentiles,
+    /// One point per calendar day, oldest first
+    pub moving_averages: Vec<MovingAveragePoint>,
+}
+
+impl EconomicAnalytics {
+    /// Cost percentiles and moving averages for activity within `range`.
+    ///
+    /// Tasks are filtered by their date and LLM calls by the date they were
+    /// made. Days run from the first to the last recorded day in `range`,
+    /// with gaps filled by zeros; near the start, where fewer days than the
+    /// window are available, the moving averages cover the days so far.
+    pub fn cost_distribution(&self, range: impl RangeBounds<NaiveDate>) -> CostDistribution {
+        let per_task = self
+            .by_task
+            .values()
+            .filter(|task| {
+                task.date
+                    .parse::<NaiveDate>()
+                    .is_ok_and(|date| range.contains(&date))
+            })
+            .map(|task| task.total)
+            .collect();
+        let per_call = self
+            .llm_call_costs
+            .iter()
+            .filter(|(date, _)| range.contains(date))
+            .map(|(_, cost)| *cost)
+            .collect();
+
+        let dated: BTreeMap<NaiveDate, (f64, f64)> = self
+            .by_date
+            .iter()
+            .filter_map(|(date, summary)| {
+                let date = date.parse::<NaiveDate>().ok()?;
+                range
+                    .contains(&date)
+                    .then_some((date, (summary.total, summary.income)))
+            })
+            .collect();
+        let daily = fill_gaps(&dated);
+        let spend: Vec<f64> = daily.iter().map(|(_, spend, _)| *spend).collect();
+        let income: Vec<f64> = daily.iter().map(|(_, _, income)| *income).collect();
+        let moving_averages = daily
+            .iter()
+            .enumerate()
+            .map(|(day, (date, day_spend, day_income))| MovingAveragePoint {
+                date: *date,
+                spend: *day_spend,
+                income: *day_income,
+                spend_7d: trailing_mean(&spend, day, SHORT_WINDOW_DAYS),
+                spend_30d: trailing_mean(&spend, day, LONG_WINDOW_DAYS),
+                income_7d: trailing_mean(&income, day, SHORT_WINDOW_DAYS),
+                income_30d: trailing_mean(&income, day, LONG_WINDOW_DAYS),
+            })
+            .collect();
+
+        CostDistribution {
+            per_task: Percentiles::from_values(per_task),
+            per_call: Percentiles::from_values(per_call),
+            per_day: Percentiles::from_values(spend),
+            moving_averages,
+        }
+    }
+}
+
+/// Nearest-rank `percentile` (`0..=100`) of `sorted`; zero when empty.
+pub(crate) fn duration_percentile(sorted: &[Duration], percentile: usize) -> Duration {
+    if sorted.is_empty() {
+        return Duration::ZERO;
+    }
+    let rank = (percentile * sorted.len()).div_ceil(100);
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+/// `buckets` equal-width buckets from the shortest to the longest of
+/// `durations`; empty when either is zero.
+pub(crate) fn duration_histogram(durations: &[Duration], buckets: usize) -> Vec<DurationBucket> {
+    let (Some(min), Some(max)) = (durations.iter().min(), durations.iter().max()) else {
+        return Vec::new();
+    };
+    if buckets == 0 {
+        return Vec::new();
+    }
+    let span = max.saturating_sub(*min).as_nanos();
+    let buckets_u128 = buckets as u128;
+    let bound = |i: u128| {
+        let offset = u64::try_from(span * i / buckets_u128).unwrap_or(u64::MAX);
+        *min + Duration::from_nanos(offset)
+    };
+    let mut histogram: Vec<DurationBucket> = (0..buckets_u128)
+        .map(|i| DurationBucket {
+            lower_bound: bound(i),
+            upper_bound: bound(i + 1),
+            count: 0,
+        })
+        .collect();
+    for duration in durations {
+        let offset = duration.saturating_sub(*min).as_nanos();
+        let index = (offset * buckets_u128)
+            .checked_div(span)
+            .map_or(0, |index| usize::try_from(index).unwrap_or(usize::MAX));
+        histogram[index.min(buckets - 1)].count += 1;
+    }
+    histogram
+}
+
+/// Spend and income for every day from the first to the last in `dated`.
+fn fill_gaps(dated: &BTreeMap<NaiveDate, (f64, f64)>) -> Vec<(NaiveDate, f64, f64)> {
+    let (Some(first), Some(last)) = (dated.keys().next(), dated.keys().next_back()) else {
+        return Vec::new();
+    };
+    let mut daily = Vec::new();
+    let mut date = *first;
+    while date <= *last {
+        let (spend, income) = dated.get(&date).copied().unwrap_or_default();
+        daily.push((date, spend, income));
+        date = date + Days::new(1);
+    }
+    daily
+}
+
+/// Mean of the up to `window` values ending at `end`.
+fn trailing_mean(values: &[f64], end: usize, window: usize) -> f64 {
+    let start = (end + 1).saturating_sub(window);
+    let values = &values[start..=end];
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{DateCostSummary, TaskCostSummary};
+
+    /// 100 tasks costing $1..$100, one call per task, each on its own day
+    /// starting 2025-01-01, with $1 of income on every other day.
+    fn fixture() -> EconomicAnalytics {
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let mut analytics = EconomicAnalytics::default();
+        for n in 1..=100u32 {
+            let date = start + Days::new(u64::from(n - 1));
+            let cost = f64::from(n);
+            analytics.by_task.insert(
+                format!("task-{n}"),
+                TaskCostSummary {
+                    total: cost,
+                    date: date.to_string(),
+                    task_id: format!("task-{n}"),
+                    ..Default::default()
+                },
+            );
+            analytics.llm_call_costs.push((date, cost));
+            analytics.by_date.insert(
+                date.to_string(),
+                DateCostSummary {
+                    total: cost,
+                    income: if n % 2 == 0 { 1.0 } else { 0.0 },
+                    ..Default::default()
+                },
+            );
+        }
+        analytics
+    }
+
+    #[test]
+    fn percentiles_use_nearest_rank() {
+        let distribution = fixture().cost_distribution(..);
+        let expected = Percentiles {
+            count: 100,
+            p50: 50.0,
+            p90: 90.0,
+            p99: 99.0,
+        };
+        assert_eq!(distribution.per_task, expected);
+        assert_eq!(distribution.per_call, expected);
+        assert_eq!(distribution.per_day, expected);
+
+        let single = Percentiles::from_values(vec![4.0]);
+        assert!((single.p50 - 4.0).abs() < 1e-9 && (single.p99 - 4.0).abs() < 1e-9);
+        assert_eq!(Percentiles::from_values(Vec::new()), Percentiles::default());
+    }
+
+    #[test]
+    fn moving_averages_trail_daily_spend() {
+        let distribution = fixture().cost_distribution(..);
+        let points = &distribution.moving_averages;
+        assert_eq!(points.len(), 100);
+
+        // Fewer days than the window: average of the days so far
+        assert!((points[1].spend_7d - 1.5).abs() < 1e-9);
+        // Days 94..=100 and 71..=100
+        assert!((points[99].spend_7d - 97.0).abs() < 1e-9);
+        assert!((points[99].spend_30d - 85.5).abs() < 1e-9);
+        assert!((points[99].income_30d - 0.5).abs() < 1e-9);
+
+        let json = serde_json::to_string(&distribution).unwrap();
+        let parsed: CostDistribution = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.per_task, distribution.per_task);
+        assert_eq!(parsed.moving_averages.len(), points.len());
+        assert_eq!(parsed.moving_averages[99].date, points[99].date);
+        assert!((parsed.moving_averages[99].spend_30d - 85.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn range_filters_and_gaps_count_as_zero() {
+        let mut analytics = fixture();
+        analytics.by_date.remove("2025-01-02");
+        let from = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2025, 1, 3).unwrap();
+        let distribution = analytics.cost_distribution(from..=to);
+
+        assert_eq!(distribution.per_task.count, 3);
+        assert_eq!(distribution.per_call.count, 3);
+        let spend: Vec<f64> = distribution
+            .moving_averages
+            .iter()
+            .map(|p| p.spend)
+            .collect();
+        assert_eq!(spend, [1.0, 0.0, 3.0]);
+        assert!((distribution.per_day.p50 - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,180 @@
+//! Error types for the economic module.
+//!
+//! Public tracker and analytics APIs return [`EconomicError`] so callers can
+//! match on the failure. It implements `std::error::Error`, so `?` still
+//! converts it into `anyhow::Error` in code that uses anyhow.
+
+use super::status::SurvivalStatus;
+use chrono::{DateTime, Utc};
+use std::path::{Path, PathBuf};
+
+/// Result type used throughout the economic module.
+pub type Result<T, E = EconomicError> = std::result::Result<T, E>;
+
+/// Errors returned by the economic tracker.
+#[derive(Debug, thiserror::Error)]
+pub enum EconomicError {
+    /// Reading or writing a file in the data directory failed.
+    #[error("I/O error on {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    /// A record could not be encoded or decoded as JSON.
+    #[error("serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// The balance cannot cover a cost.
+    #[error("insufficient funds: need ${needed:.6}, have ${available:.6}")]
+    InsufficientFunds { needed: f64, available: f64 },
+
+    /// A transfer names the same tracker as sender and receiver.
+    #[error("cannot transfer balance from agent {agent} to itself")]
+    SelfTransfer { agent: String },
+
+    /// A transfer moved both balances, but one or both agents failed to
+    /// log it; each failure is paired with the agent's signature.
+    #[error(
+        "transfer {transfer_id} applied but not logged: {}",
+        failures
+            .iter()
+            .map(|(agent, source)| format!("{agent}: {source}"))
+            .collect::<Vec<_>>()
+            .join("; ")
+    )]
+    TransferNotLogged {
+        transfer_id: String,
+        failures: Vec<(String, EconomicError)>,
+    },
+
+    /// The task has already been paid.
+    #[error("income already credited for task {task_id}")]
+    DuplicateIncome { task_id: String },
+
+    /// The task id does not refer to an active task.
+    #[error("task not found: {task_id}")]
+    TaskNotFound { task_id: String },
+
+    /// The task has already spent its `max_cost_per_task` ceiling.
+    #[error("task {task_id} reached its cost ceiling of ${ceiling:.4}")]
+    TaskCostCeilingExceeded { task_id: String, ceiling: f64 },
+
+    /// Record metadata exceeds `MAX_METADATA_KEYS` or `MAX_METADATA_BYTES`.
+    #[error("record metadata too large: {keys} keys, {bytes} bytes (limit 64 keys, 1024 bytes)")]
+    MetadataTooLarge { keys: usize, bytes: usize },
+
+    /// No charge with this id was recorded.
+    #[error("charge record not found: {record_id}")]
+    RecordNotFound { record_id: String },
+
+    /// A gzip archive has to be read, but the `archive-read` feature is
+    /// compiled out.
+    #[error("reading archive {} needs the `archive-read` feature", path.display())]
+    ArchiveReadDisabled { path: PathBuf },
+
+    /// A line of a JSONL record file could not be decoded.
+    #[error("malformed record at {}:{line}: {source}", path.display())]
+    MalformedRecord {
+        path: PathBuf,
+        line: usize,
+        source: serde_json::Error,
+    },
+
+    /// A monetary amount was negative, zero, or not finite.
+    #[error("invalid amount: {amount}")]
+    InvalidAmount { amount: f64 },
+
+    /// The intake policy has paused task intake.
+    #[error("task intake is paused (status: {status})")]
+    IntakePaused { status: SurvivalStatus },
+
+    /// A refund would exceed what is left of the original charge.
+    #[error("refund of ${requested:.6} exceeds refundable ${refundable:.6} for {record_id}")]
+    RefundExceedsCharge {
+        record_id: String,
+        requested: f64,
+        refundable: f64,
+    },
+
+    /// The session has no elapsed time to extrapolate from.
+    #[error("session has no elapsed time to project costs from")]
+    EmptySession,
+
+    /// A configuration value is out of range.
+    #[error("invalid economic config: {0}")]
+    InvalidConfig(String),
+
+    /// A data directory that must be empty already holds economic records.
+    #[error("{} already contains economic data", path.display())]
+    DataDirNotEmpty { path: PathBuf },
+
+    /// Neither data directory has a balance history to merge.
+    #[error("no balance history in {} or {}", primary.display(), secondary.display())]
+    NoBalanceHistory { primary: PathBuf, secondary: PathBuf },
+
+    /// An anomaly detection threshold was not a positive finite number.
+    #[error("invalid anomaly sensitivity: {sensitivity}")]
+    InvalidSensitivity { sensitivity: f64 },
+
+    /// `balance.jsonl` holds no readable balance record.
+    #[error("no balance records in {}", path.display())]
+    EmptyBalanceLog { path: PathBuf },
+
+    /// No income goal has been set.
+    #[error("no income goal is set")]
+    NoIncomeGoal,
+
+    /// A deadline is not in the future.
+    #[error("deadline {deadline} has already passed")]
+    DeadlinePassed { deadline: DateTime<Utc> },
+
+    /// Too few work income records to compute a statistic.
+    #[error("need at least {needed} work income records, found {found}")]
+    NotEnoughIncomeRecords { needed: usize, found: usize },
+
+    /// A statistic is undefined because an income field never varies.
+    #[error("every work income record has the same {field}")]
+    ConstantIncomeField { field: &'static str },
+
+    /// A shared cost cannot be split because no task has completed.
+    #[error("no completed tasks to prorate a shared cost across")]
+    NoCompletedTasks,
+
+    /// A per-hour rate is undefined because no task time was recorded.
+    #[error("no task time recorded to compute an hourly rate from")]
+    NoTaskTime,
+
+    /// Tasks do not earn more than they cost, so the fixed overhead is
+    /// never recovered.
+    #[error("break-even is unreachable: tasks average ${average_task_profit:.6} profit")]
+    BreakEvenUnreachable { average_task_profit: f64 },
+
+    /// A log's hash chain is broken, so rewriting it would reseal the
+    /// damage; see `EconomicTracker::verify_integrity`.
+    #[error("integrity chain of {} is broken at line {line}", path.display())]
+    IntegrityBroken { path: PathBuf, line: usize },
+
+    /// A balance was requested for a time before the tracker's first
+    /// session, when it had no balance yet.
+    #[error("no balance at {at}: the first session started at {session_start}")]
+    BeforeSessionStart {
+        at: DateTime<Utc>,
+        session_start: DateTime<Utc>,
+    },
+}
+
+/// Attach the path to an I/O error, like `anyhow::Context` for
+/// [`EconomicError::Io`].
+pub(crate) trait IoResultExt<T> {
+    fn at_path(self, path: &Path) -> Result<T>;
+}
+
+impl<T> IoResultExt<T> for std::io::Result<T> {
+    fn at_path(self, path: &Path) -> Result<T> {
+        self.map_err(|source| EconomicError::Io {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+}
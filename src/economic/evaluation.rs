@@ -0,0 +1,240 @@
+//! Pluggable quality evaluation for work income.
+//!
+//! `EconomicTracker::complete_and_evaluate` asks the configured
+//! [`QualityEvaluator`] to score a deliverable instead of trusting a score
+//! chosen by the caller. When the evaluator fails, the payment is held in
+//! escrow (`escrow.jsonl`) until `EconomicTracker::release_escrow` settles
+//! it, so nothing is paid without a score.
+
+use crate::providers::{ChatMessage, ChatRequest, Provider};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Sampling temperature for the LLM judge; scoring should be repeatable.
+const JUDGE_TEMPERATURE: f64 = 0.0;
+
+/// LLM tokens an evaluator spent producing a score.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvaluatorUsage {
+    /// Model that produced the score
+    pub model: String,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// Score an evaluator assigned to a deliverable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QualityScore {
+    /// Quality score (0.0-1.0)
+    pub score: f64,
+    /// Why the evaluator chose the score
+    pub reasoning: String,
+    /// Tokens to charge to the tracker under the `evaluator` channel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub usage: Option<EvaluatorUsage>,
+}
+
+/// Scores task deliverables for `EconomicTracker::complete_and_evaluate`.
+#[async_trait]
+pub trait QualityEvaluator: Send + Sync {
+    /// Score `deliverable` as an answer to `instruction`.
+    async fn evaluate(
+        &self,
+        task_id: &str,
+        instruction: &str,
+        deliverable: &str,
+    ) -> anyhow::Result<QualityScore>;
+}
+
+/// Evaluator that returns a fixed score without inspecting the deliverable.
+///
+/// Useful when quality is checked elsewhere, or in tests.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PassthroughEvaluator {
+    score: f64,
+}
+
+impl PassthroughEvaluator {
+    /// Always return `score`.
+    pub fn new(score: f64) -> Self {
+        Self { score }
+    }
+}
+
+impl Default for PassthroughEvaluator {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+#[async_trait]
+impl QualityEvaluator for PassthroughEvaluator {
+    async fn evaluate(
+        &self,
+        _task_id: &str,
+        _instruction: &str,
+        _deliverable: &str,
+    ) -> anyhow::Result<QualityScore> {
+        Ok(QualityScore {
+            score: self.score,
+            reasoning: "passthrough".to_string(),
+            usage: None,
+        })
+    }
+}
+
+/// Evaluator that asks an LLM to grade the deliverable against a rubric.
+///
+/// The judge must answer with a JSON object holding `score` (0.0-1.0) and
+/// `reasoning`; anything else is an evaluation failure.
+pub struct LlmJudgeEvaluator {
+    provider: Arc<dyn Provider>,
+    model: String,
+    rubric: String,
+}
+
+impl LlmJudgeEvaluator {
+    /// Judge with `model` on `provider`, grading against `rubric`.
+    pub fn new(
+        provider: Arc<dyn Provider>,
+        model: impl Into<String>,
+        rubric: impl Into<String>,
+    ) -> Self {
+        Self {
+            provider,
+            model: model.into(),
+            rubric: rubric.into(),
+        }
+    }
+
+    fn system_prompt(&self) -> String {
+        format!(
+            "You grade work delivered for a task. Score it from 0.0 (unusable) to 1.0 \
+             (fully meets the task) using this rubric:\n\n{}\n\n\
+             Reply with only a JSON object: {{\"score\": <number>, \"reasoning\": \"<why>\"}}",
+            self.rubric
+        )
+    }
+}
+
+#[async_trait]
+impl QualityEvaluator for LlmJudgeEvaluator {
+    async fn evaluate(
+        &self,
+        task_id: &str,
+        instruction: &str,
+        deliverable: &str,
+    ) -> anyhow::Result<QualityScore> {
+        let messages = [
+            ChatMessage::system(self.system_prompt()),
+            ChatMessage::user(format!(
+                "Task {task_id}\n\nInstruction:\n{instruction}\n\nDeliverable:\n{deliverable}"
+            )),
+        ];
+        let response = self
+            .provider
+            .chat(
+                ChatRequest {
+                    messages: &messages,
+                    tools: None,
+                },
+                &self.model,
+                JUDGE_TEMPERATURE,
+            )
+            .await?;
+
+        let (score, reasoning) = parse_judgement(response.text.as_deref().unwrap_or_default())?;
+        Ok(QualityScore {
+            score,
+            reasoning,
+            usage: response.usage.map(|usage| EvaluatorUsage {
+                model: self.model.clone(),
+                input_tokens: usage.input_tokens.unwrap_or(0),
+                output_tokens: usage.output_tokens.unwrap_or(0),
+            }),
+        })
+    }
+}
+
+/// Extract the score and reasoning from a judge reply.
+///
+/// Tolerates prose or code fences around the JSON object.
+fn parse_judgement(reply: &str) -> anyhow::Result<(f64, String)> {
+    #[derive(Deserialize)]
+    struct Judgement {
+        score: f64,
+        #[serde(default)]
+        reasoning: String,
+    }
+
+    let json = reply
+        .find('{')
+        .zip(reply.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .map(|(start, end)| &reply[start..=end])
+        .ok_or_else(|| anyhow::anyhow!("Judge reply has no JSON object: {reply}"))?;
+    let judgement: Judgement = serde_json::from_str(json)?;
+    if !(0.0..=1.0).contains(&judgement.score) {
+        anyhow::bail!("Judge score {} is outside 0.0-1.0", judgement.score);
+    }
+    Ok((judgement.score, judgement.reasoning))
+}
+
+/// Escrow transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EscrowEventKind {
+    /// Evaluation failed; the payment is held
+    Held,
+    /// The held payment was settled with a score
+    Released,
+}
+
+/// Escrow transition, persisted to `escrow.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EscrowRecord {
+    /// When the transition happened
+    pub timestamp: DateTime<Utc>,
+    /// What happened
+    pub kind: EscrowEventKind,
+    /// Task identifier
+    pub task_id: String,
+    /// Payment offered for the task in USD
+    pub amount: f64,
+    /// Evaluator error for `Held`, settlement note for `Released`
+    #[serde(default)]
+    pub reason: String,
+    /// Task instruction, kept so the deliverable can be evaluated again
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub instruction: String,
+    /// Deliverable awaiting evaluation
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub deliverable: String,
+}
+
+/// Result of `EconomicTracker::complete_and_evaluate`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EvaluationOutcome {
+    /// Scored and settled; `payment` is 0.0 below the threshold
+    Settled { score: QualityScore, payment: f64 },
+    /// The evaluator failed and the payment is held in escrow
+    Escrowed { reason: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn judge_replies_are_parsed_leniently() {
+        let reply = "Here is my grade:\n```json\n{\"score\": 0.75, \"reasoning\": \"mostly\"}\n```";
+        let (score, reasoning) = parse_judgement(reply).unwrap();
+        assert!((score - 0.75).abs() < f64::EPSILON);
+        assert_eq!(reasoning, "mostly");
+
+        assert!(parse_judgement("looks good to me").is_err());
+        assert!(parse_judgement("{\"score\": 7}").is_err());
+    }
+}
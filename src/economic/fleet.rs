@@ -0,0 +1,342 @@
+//! Comparing the economics of several agents.
+//!
+//! A benchmark run gives each agent its own data directory. [`FleetReport`]
+//! loads the summary of each one and ranks the agents against each other.
+//! Percentage metrics let agents that started with different balances be
+//! compared fairly.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Write};
+use std::path::{Path, PathBuf};
+
+use super::costs::{BalanceRecord, RecordReader};
+use super::error::{EconomicError, Result};
+use super::merge::BALANCE_LOG;
+use super::status::SurvivalStatus;
+use super::summary::{EconomicSummary, SummaryOptions};
+use super::tracker::{EconomicConfig, EconomicTracker};
+
+/// What a [`FleetReport`] ranks agents by.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FleetMetric {
+    /// Current balance in USD
+    Balance,
+    /// Balance change as a percentage of the initial balance
+    #[default]
+    ReturnPct,
+    /// Work and grant income in USD
+    TotalIncome,
+    /// Token and API cost in USD, cheapest first
+    TotalCost,
+    /// Income minus cost in USD
+    Profit,
+    /// Profit as a percentage of income; agents without income rank last
+    ProfitMarginPct,
+}
+
+impl fmt::Display for FleetMetric {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Balance => "balance",
+            Self::ReturnPct => "return %",
+            Self::TotalIncome => "total income",
+            Self::TotalCost => "total cost",
+            Self::Profit => "profit",
+            Self::ProfitMarginPct => "profit margin %",
+        };
+        f.write_str(name)
+    }
+}
+
+/// One agent's standing in a [`FleetReport`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetEntry {
+    /// 1 for the best agent by the report's metric
+    pub rank: usize,
+    /// Name the agent was listed under
+    pub name: String,
+    pub data_dir: PathBuf,
+    pub survival_status: SurvivalStatus,
+    pub balance: f64,
+    /// Opening balance from the first record in `balance.jsonl`
+    pub initial_balance: f64,
+    /// Work and grant income
+    pub total_income: f64,
+    /// Token and API cost
+    pub total_cost: f64,
+    /// `total_income - total_cost`
+    pub profit: f64,
+    /// `profit` as a percentage of `total_income` (`None` without income)
+    pub profit_margin_pct: Option<f64>,
+    /// Balance change as a percentage of `initial_balance` (`None` when the
+    /// agent started without capital)
+    pub return_pct: Option<f64>,
+    pub tasks_ended: u64,
+}
+
+impl FleetEntry {
+    fn from_summary(name: String, data_dir: PathBuf, summary: &EconomicSummary) -> Self {
+        let total_income = summary.total_work_income + summary.total_grant_income;
+        let total_cost = summary.total_token_cost;
+        let profit = total_income - total_cost;
+        Self {
+            rank: 0,
+            name,
+            data_dir,
+            survival_status: summary.survival_status,
+            balance: summary.balance,
+            initial_balance: summary.initial_balance,
+            total_income,
+            total_cost,
+            profit,
+            profit_margin_pct: (total_income > 0.0).then(|| profit / total_income * 100.0),
+            return_pct: (summary.initial_balance > 0.0).then(|| {
+                (summary.balance - summary.initial_balance) / summary.initial_balance * 100.0
+            }),
+            tasks_ended: summary.tasks_ended,
+        }
+    }
+
+    /// Value of `metric`, oriented so that higher is better.
+    fn score(&self, metric: FleetMetric) -> Option<f64> {
+        match metric {
+            FleetMetric::Balance => Some(self.balance),
+            FleetMetric::ReturnPct => self.return_pct,
+            FleetMetric::TotalIncome => Some(self.total_income),
+            FleetMetric::TotalCost => Some(-self.total_cost),
+            FleetMetric::Profit => Some(self.profit),
+            FleetMetric::ProfitMarginPct => self.profit_margin_pct,
+        }
+    }
+}
+
+/// An agent whose data directory could not be loaded.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FleetFailure {
+    pub name: String,
+    pub data_dir: PathBuf,
+    /// Why loading failed
+    pub error: String,
+}
+
+/// Side-by-side economics of several agents, best first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FleetReport {
+    pub generated_at: DateTime<Utc>,
+    /// Metric `entries` are ranked by
+    pub ranked_by: FleetMetric,
+    /// Loaded agents, in rank order
+    pub entries: Vec<FleetEntry>,
+    /// Agents left out because their data directory could not be loaded
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failures: Vec<FleetFailure>,
+}
+
+impl FleetReport {
+    /// Load the summary of each `(name, data_dir)` agent and rank them by
+    /// [`FleetMetric::ReturnPct`].
+    ///
+    /// A directory that cannot be loaded is listed in `failures` instead of
+    /// failing the report. Directories are only read; a missing one is not
+    /// created.
+    pub fn from_dirs<N, P>(agents: &[(N, P)]) -> Self
+    where
+        N: AsRef<str>,
+        P: AsRef<Path>,
+    {
+        let mut entries = Vec::new();
+        let mut failures = Vec::new();
+        for (name, data_dir) in agents {
+            let (name, data_dir) = (name.as_ref(), data_dir.as_ref());
+            match load_summary(name, data_dir) {
+                Ok(summary) => entries.push(FleetEntry::from_summary(
+                    name.to_string(),
+                    data_dir.to_path_buf(),
+                    &summary,
+                )),
+                Err(error) => {
+                    tracing::warn!("⚠️ Skipping agent {name} in fleet report: {error}");
+                    failures.push(FleetFailure {
+                        name: name.to_string(),
+                        data_dir: data_dir.to_path_buf(),
+                        error: error.to_string(),
+                    });
+                }
+            }
+        }
+
+        let mut report = Self {
+            generated_at: Utc::now(),
+            ranked_by: FleetMetric::default(),
+            entries,
+            failures,
+        };
+        report.rank_by(FleetMetric::default());
+        report
+    }
+
+    /// Re-rank the entries by `metric`, best first.
+    ///
+    /// Entries without a value for the metric come last; ties keep name
+    /// order.
+    pub fn rank_by(&mut self, metric: FleetMetric) {
+        self.entries.sort_by(|a, b| {
+            match (a.score(metric), b.score(metric)) {
+                (Some(a), Some(b)) => b.total_cmp(&a),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+            .then_with(|| a.name.cmp(&b.name))
+        });
+        for (index, entry) in self.entries.iter_mut().enumerate() {
+            entry.rank = index + 1;
+        }
+        self.ranked_by = metric;
+    }
+
+    /// The report as pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(self)?)
+    }
+
+    /// Render the report as a plain-text leaderboard table.
+    pub fn render_text(&self) -> String {
+        let mut out = String::new();
+        let _ = writeln!(
+            out,
+            "Fleet of {} agents, ranked by {}",
+            self.entries.len() + self.failures.len(),
+            self.ranked_by
+        );
+        let _ = writeln!(
+            out,
+            "{:>4}  {:<20} {:<10} {:>12} {:>9} {:>12} {:>12} {:>12} {:>9}",
+            "Rank", "Agent", "Status", "Balance", "Return", "Income", "Cost", "Profit", "Margin"
+        );
+        for entry in &self.entries {
+            let _ = writeln!(
+                out,
+                "{:>4}  {:<20} {:<10} {:>12} {:>9} {:>12} {:>12} {:>12} {:>9}",
+                entry.rank,
+                entry.name,
+                entry.survival_status.to_string(),
+                format!("${:.2}", entry.balance),
+                percent(entry.return_pct),
+                format!("${:.2}", entry.total_income),
+                format!("${:.2}", entry.total_cost),
+                format!("${:.2}", entry.profit),
+                percent(entry.profit_margin_pct),
+            );
+        }
+        for failure in &self.failures {
+            let _ = writeln!(out, "   -  {:<20} failed: {}", failure.name, failure.error);
+        }
+        out
+    }
+}
+
+fn percent(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{value:+.1}%"))
+}
+
+/// Summary of the tracker in `data_dir`, opened with the opening balance
+/// recorded there so survival status and returns match the agent's own.
+fn load_summary(name: &str, data_dir: &Path) -> Result<EconomicSummary> {
+    let balance_file = data_dir.join(BALANCE_LOG);
+    let opening = RecordReader::<BalanceRecord>::open(&balance_file)?
+        .find_map(|record| match record {
+            Ok(record) => Some(Ok(record)),
+            Err(EconomicError::MalformedRecord { .. }) => None,
+            Err(error) => Some(Err(error)),
+        })
+        .transpose()?
+        .ok_or(EconomicError::EmptyBalanceLog { path: balance_file })?;
+
+    let config = EconomicConfig {
+        enabled: true,
+        initial_balance: opening.balance,
+        ..Default::default()
+    };
+    let tracker = EconomicTracker::new(name, config, Some(data_dir.to_path_buf()));
+    tracker.initialize()?;
+    tracker.get_summary_with(SummaryOptions::minimal())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn agent(dir: &Path, initial_balance: f64, cost: f64, income: f64) {
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance,
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("agent", config, Some(dir.to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_model_tokens("gpt-4o", 1000, 500, "agent", Some(cost))
+            .unwrap();
+        tracker.end_task("task-1").unwrap();
+        if income > 0.0 {
+            tracker.add_work_income(income, "task-1", 0.9, "").unwrap();
+        }
+    }
+
+    #[test]
+    fn agents_are_ranked_and_broken_dirs_reported() {
+        let tmp = TempDir::new().unwrap();
+        let big = tmp.path().join("big");
+        let small = tmp.path().join("small");
+        let idle = tmp.path().join("idle");
+        // Big earns more in dollars, small earns more for its capital
+        agent(&big, 1000.0, 10.0, 60.0);
+        agent(&small, 100.0, 5.0, 25.0);
+        agent(&idle, 100.0, 20.0, 0.0);
+        let missing = tmp.path().join("missing");
+
+        let mut report = FleetReport::from_dirs(&[
+            ("big", &big),
+            ("small", &small),
+            ("missing", &missing),
+            ("idle", &idle),
+        ]);
+        assert_eq!(report.ranked_by, FleetMetric::ReturnPct);
+        let names: Vec<&str> = report.entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, ["small", "big", "idle"]);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].name, "missing");
+        assert!(!missing.exists());
+
+        let leader = &report.entries[0];
+        assert_eq!(leader.rank, 1);
+        assert!((leader.initial_balance - 100.0).abs() < 1e-9);
+        assert!((leader.profit - 20.0).abs() < 1e-9);
+        assert!((leader.return_pct.unwrap() - 20.0).abs() < 1e-9);
+        assert!((leader.profit_margin_pct.unwrap() - 80.0).abs() < 1e-9);
+        assert_eq!(report.entries[2].profit_margin_pct, None);
+
+        report.rank_by(FleetMetric::Profit);
+        assert_eq!(report.entries[0].name, "big");
+        report.rank_by(FleetMetric::TotalCost);
+        assert_eq!(report.entries[0].name, "small");
+        assert_eq!(report.entries[2].name, "idle");
+        report.rank_by(FleetMetric::ProfitMarginPct);
+        assert_eq!(report.entries[2].name, "idle");
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json().unwrap()).unwrap();
+        assert_eq!(json["ranked_by"], "profit_margin_pct");
+        assert_eq!(json["entries"][0]["rank"], 1);
+        assert_eq!(json["failures"][0]["name"], "missing");
+
+        let text = report.render_text();
+        assert!(text.starts_with("Fleet of 4 agents, ranked by profit margin %"));
+        assert!(text.contains("+20.0%"));
+        assert!(text.contains("missing"));
+    }
+}
@@ -0,0 +1,341 @@
+//! Spending forecasts for economic agents.
+//!
+//! Projects the balance forward from an exponentially weighted moving
+//! average (EWMA) of daily net spend, so the forecast follows workload shifts
+//! faster than a trailing average would.
+
+use super::costs::EconomicAnalytics;
+use super::status::SurvivalStatus;
+use chrono::{Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+
+/// EWMA smoothing factor used when none is configured.
+pub const DEFAULT_FORECAST_ALPHA: f64 = 0.3;
+
+/// Hours in the month `MonthlyProjection` extrapolates to (30 days).
+const HOURS_PER_MONTH: f64 = 30.0 * 24.0;
+
+/// Days of recent history used for the confidence band.
+const VARIANCE_WINDOW_DAYS: usize = 7;
+
+/// z-score for the 95% confidence band.
+const CONFIDENCE_Z: f64 = 1.96;
+
+/// How much session data a `MonthlyProjection` rests on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectionConfidence {
+    /// Less than an hour of data
+    Low,
+    /// One to 24 hours of data
+    Medium,
+    /// More than 24 hours of data
+    High,
+}
+
+impl ProjectionConfidence {
+    fn from_hours(hours: f64) -> Self {
+        if hours < 1.0 {
+            Self::Low
+        } else if hours <= 24.0 {
+            Self::Medium
+        } else {
+            Self::High
+        }
+    }
+}
+
+/// Session spend extrapolated to a 30-day month.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MonthlyProjection {
+    /// Projected cost over 30 days at the session rate (USD)
+    pub projected_cost_usd: f64,
+    /// Session duration the rate is based on
+    pub projection_basis_hours: f64,
+    pub confidence: ProjectionConfidence,
+    /// Session cost divided by session duration (USD)
+    pub cost_per_hour: f64,
+}
+
+impl MonthlyProjection {
+    /// Project `cost` spent over `hours` (which must be positive).
+    pub(crate) fn from_session(cost: f64, hours: f64) -> Self {
+        let cost_per_hour = cost / hours;
+        Self {
+            projected_cost_usd: cost_per_hour * HOURS_PER_MONTH,
+            projection_basis_hours: hours,
+            confidence: ProjectionConfidence::from_hours(hours),
+            cost_per_hour,
+        }
+    }
+}
+
+/// Projected balance trajectory.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpendForecast {
+    /// EWMA of daily net spend (costs minus income; negative means net income)
+    pub daily_net_spend: f64,
+    /// Standard deviation of recent daily net spend around the EWMA
+    pub daily_std_dev: f64,
+    /// Days of history the forecast is based on
+    pub history_days: usize,
+    /// Whether fewer than a week of history was available
+    pub limited_history: bool,
+    /// Projection for each future day, starting tomorrow
+    pub days: Vec<ForecastDay>,
+    /// Projected survival status transitions within the horizon, in order
+    pub transitions: Vec<StatusTransition>,
+}
+
+impl SpendForecast {
+    /// First projected status transition, if any.
+    pub fn next_transition(&self) -> Option<&StatusTransition> {
+        self.transitions.first()
+    }
+}
+
+/// Projected balance for one future day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ForecastDay {
+    /// Calendar date (UTC)
+    pub date: NaiveDate,
+    /// Projected balance at the end of the day
+    pub balance: f64,
+    /// Lower bound of the 95% confidence band
+    pub balance_low: f64,
+    /// Upper bound of the 95% confidence band
+    pub balance_high: f64,
+    /// Survival status at the projected balance
+    pub status: SurvivalStatus,
+}
+
+/// Date on which the projected balance enters a new survival status.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatusTransition {
+    /// Status entered
+    pub status: SurvivalStatus,
+    /// First day in the new status
+    pub date: NaiveDate,
+}
+
+impl EconomicAnalytics {
+    /// Forecast the balance `days_ahead` days into the future.
+    ///
+    /// Daily net spend is smoothed with an EWMA using `forecast_alpha`
+    /// (falling back to [`DEFAULT_FORECAST_ALPHA`] when it is outside
+    /// `(0, 1]`). Days without records from the first recorded date up to
+    /// today count as zero spend, so an idle agent's average decays; today
+    /// only counts once it has records. With no history the forecast is flat; with
+    /// fewer than two days the confidence band is as wide as the spend itself.
+    /// An agent with net-positive income never reaches a worse status.
+    pub fn forecast_spend(&self, days_ahead: u32) -> SpendForecast {
+        let alpha = if self.forecast_alpha > 0.0 && self.forecast_alpha <= 1.0 {
+            self.forecast_alpha
+        } else {
+            DEFAULT_FORECAST_ALPHA
+        };
+        let today = Utc::now().date_naive();
+        let history = self.daily_net_spend(today);
+
+        let mut ewma = history.first().copied().unwrap_or(0.0);
+        for spend in history.iter().skip(1) {
+            ewma = alpha * spend + (1.0 - alpha) * ewma;
+        }
+
+        let recent = &history[history.len().saturating_sub(VARIANCE_WINDOW_DAYS)..];
+        let std_dev = if recent.len() < 2 {
+            ewma.abs()
+        } else {
+            let variance = recent.iter().map(|spend| (spend - ewma).powi(2)).sum::<f64>()
+                / (recent.len() - 1) as f64;
+            variance.sqrt()
+        };
+
+        let mut status = SurvivalStatus::from_balance(self.balance, self.initial_balance);
+        let mut days = Vec::with_capacity(days_ahead as usize);
+        let mut transitions = Vec::new();
+        for ahead in 1..=days_ahead {
+            let Some(date) = today.checked_add_days(Days::new(u64::from(ahead))) else {
+                break;
+            };
+            let balance = self.balance - ewma * f64::from(ahead);
+            let spread = CONFIDENCE_Z * std_dev * f64::from(ahead).sqrt();
+            let day_status = SurvivalStatus::from_balance(balance, self.initial_balance);
+            if day_status != status {
+                transitions.push(StatusTransition {
+                    status: day_status,
+                    date,
+                });
+                status = day_status;
+            }
+            days.push(ForecastDay {
+                date,
+                balance,
+                balance_low: balance - spread,
+                balance_high: balance + spread,
+                status: day_status,
+            });
+        }
+
+        SpendForecast {
+            daily_net_spend: ewma,
+            daily_std_dev: std_dev,
+            history_days: history.len(),
+            limited_history: history.len() < VARIANCE_WINDOW_DAYS,
+            days,
+            transitions,
+        }
+    }
+
+    /// Net spend per calendar day, oldest first, with the days without
+    /// records before `today` filled by zeros.
+    fn daily_net_spend(&self, today: NaiveDate) -> Vec<f64> {
+        let mut dated: Vec<(NaiveDate, f64)> = self
+            .by_date
+            .iter()
+            .filter_map(|(date, summary)| {
+                let date = date.parse::<NaiveDate>().ok()?;
+                Some((date, summary.total - summary.income))
+            })
+            .collect();
+        dated.sort_by_key(|(date, _)| *date);
+
+        let mut daily = Vec::new();
+        let mut previous: Option<NaiveDate> = None;
+        for (date, spend) in dated {
+            if let Some(previous) = previous {
+                daily.extend(idle_days(previous, date));
+            }
+            daily.push(spend);
+            previous = Some(date);
+        }
+        if let Some(previous) = previous {
+            daily.extend(idle_days(previous, today));
+        }
+        daily
+    }
+}
+
+/// Zero spend for each day strictly between `from` and `to`.
+fn idle_days(from: NaiveDate, to: NaiveDate) -> impl Iterator<Item = f64> {
+    let gap = usize::try_from((to - from).num_days() - 1).unwrap_or(0);
+    std::iter::repeat_n(0.0, gap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::DateCostSummary;
+
+    /// Analytics with `(days ago, total, income)` entries in `by_date`.
+    fn analytics(balance: f64, days: &[(u64, f64, f64)]) -> EconomicAnalytics {
+        let mut analytics = EconomicAnalytics {
+            balance,
+            initial_balance: 100.0,
+            forecast_alpha: 0.5,
+            ..Default::default()
+        };
+        let today = Utc::now().date_naive();
+        for (ago, total, income) in days {
+            analytics.by_date.insert(
+                (today - Days::new(*ago)).to_string(),
+                DateCostSummary {
+                    total: *total,
+                    income: *income,
+                    ..Default::default()
+                },
+            );
+        }
+        analytics
+    }
+
+    #[test]
+    fn forecast_projects_status_transitions() {
+        let forecast =
+            analytics(50.0, &[(3, 5.0, 0.0), (2, 5.0, 0.0), (1, 5.0, 0.0)]).forecast_spend(30);
+
+        assert!((forecast.daily_net_spend - 5.0).abs() < 1e-9);
+        assert!(forecast.daily_std_dev.abs() < 1e-9);
+        assert!(forecast.limited_history);
+        assert_eq!(forecast.days.len(), 30);
+        assert!((forecast.days[0].balance - 45.0).abs() < 1e-9);
+
+        // 50 -> Struggling below 40 (day 3), Critical below 10 (day 9),
+        // Bankrupt at 0 (day 10)
+        let statuses: Vec<_> = forecast.transitions.iter().map(|t| t.status).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                SurvivalStatus::Struggling,
+                SurvivalStatus::Critical,
+                SurvivalStatus::Bankrupt
+            ]
+        );
+        let today = Utc::now().date_naive();
+        assert_eq!(forecast.transitions[0].date, today + Days::new(3));
+        assert_eq!(forecast.transitions[2].date, today + Days::new(10));
+        assert_eq!(
+            forecast.next_transition().unwrap().status,
+            SurvivalStatus::Struggling
+        );
+    }
+
+    #[test]
+    fn ewma_weights_recent_days_and_fills_gaps() {
+        // 10 on day 1, nothing on day 2, 2 on day 3
+        let forecast = analytics(90.0, &[(3, 10.0, 0.0), (1, 2.0, 0.0)]).forecast_spend(1);
+        // ewma = 10 -> 5 -> 3.5
+        assert_eq!(forecast.history_days, 3);
+        assert!((forecast.daily_net_spend - 3.5).abs() < 1e-9);
+        assert!(forecast.days[0].balance_low < forecast.days[0].balance);
+        assert!(forecast.days[0].balance_high > forecast.days[0].balance);
+    }
+
+    #[test]
+    fn net_positive_agent_never_declines() {
+        let forecast = analytics(50.0, &[(2, 1.0, 5.0), (1, 1.0, 5.0)]).forecast_spend(365);
+        assert!(forecast.daily_net_spend < 0.0);
+        assert!(forecast
+            .transitions
+            .iter()
+            .all(|t| t.status != SurvivalStatus::Bankrupt));
+        assert!(forecast.days.last().unwrap().balance > 50.0);
+    }
+
+    #[test]
+    fn idle_days_up_to_today_decay_the_average() {
+        // Nothing recorded since 10 on the first day
+        let forecast = analytics(90.0, &[(5, 10.0, 0.0)]).forecast_spend(1);
+        // ewma = 10 -> 5 -> 2.5 -> 1.25 -> 0.625
+        assert_eq!(forecast.history_days, 5);
+        assert!((forecast.daily_net_spend - 0.625).abs() < 1e-9);
+
+        // Today counts once it has records
+        let forecast = analytics(90.0, &[(1, 10.0, 0.0), (0, 2.0, 0.0)]).forecast_spend(1);
+        assert_eq!(forecast.history_days, 2);
+        assert!((forecast.daily_net_spend - 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn empty_history_gives_flat_forecast() {
+        let forecast = analytics(50.0, &[]).forecast_spend(7);
+        assert_eq!(forecast.history_days, 0);
+        assert!(forecast.transitions.is_empty());
+        assert!(forecast.days.iter().all(|d| (d.balance - 50.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn monthly_projection_confidence_follows_basis_hours() {
+        let projection = MonthlyProjection::from_session(0.5, 0.5);
+        assert!((projection.projected_cost_usd - 720.0).abs() < 1e-9);
+        assert_eq!(projection.confidence, ProjectionConfidence::Low);
+        assert_eq!(
+            MonthlyProjection::from_session(1.0, 24.0).confidence,
+            ProjectionConfidence::Medium
+        );
+        assert_eq!(
+            MonthlyProjection::from_session(1.0, 48.0).confidence,
+            ProjectionConfidence::High
+        );
+    }
+}
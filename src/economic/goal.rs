@@ -0,0 +1,107 @@
+//! Income targets and progress toward them.
+//!
+//! `EconomicTracker::set_income_goal` sets a single active [`IncomeGoal`];
+//! work and grant income received after that counts toward it.
+//! [`GoalProgress`] extrapolates the rate earned so far to the deadline to
+//! tell whether the goal will be met.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::time::SystemTime;
+
+/// An amount to earn by a deadline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IncomeGoal {
+    /// Income to earn in USD
+    pub target_usd: f64,
+    /// When the income must have been earned
+    pub deadline: SystemTime,
+    pub description: String,
+}
+
+/// Answer to `EconomicTracker::get_goal_progress`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GoalProgress {
+    pub target_usd: f64,
+    /// Income received since the goal was set
+    pub earned_usd: f64,
+    /// Income still needed (0 once the target is reached)
+    pub remaining_usd: f64,
+    /// `earned_usd` as a percentage of `target_usd`, capped at 100
+    pub percent_complete: f64,
+    /// Earning at the rate so far reaches the target by the deadline
+    pub is_on_track: bool,
+    /// How far short of the target that rate ends at the deadline
+    pub projected_shortfall: f64,
+}
+
+impl GoalProgress {
+    /// Progress at `now` on `goal`, set at `set_at`, with `earned_usd`
+    /// received since.
+    pub(crate) fn compute(
+        goal: &IncomeGoal,
+        set_at: DateTime<Utc>,
+        earned_usd: f64,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let deadline = DateTime::<Utc>::from(goal.deadline);
+        let elapsed = (now.min(deadline) - set_at).as_seconds_f64();
+        let period = (deadline - set_at).as_seconds_f64();
+        let projected = if elapsed > 0.0 && period > elapsed {
+            earned_usd * period / elapsed
+        } else {
+            earned_usd
+        };
+
+        let remaining_usd = (goal.target_usd - earned_usd).max(0.0);
+        let projected_shortfall = (goal.target_usd - projected).max(0.0);
+        Self {
+            target_usd: goal.target_usd,
+            earned_usd,
+            remaining_usd,
+            percent_complete: (earned_usd / goal.target_usd * 100.0).min(100.0),
+            is_on_track: projected_shortfall == 0.0,
+            projected_shortfall,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeDelta;
+
+    #[test]
+    fn progress_extrapolates_the_rate_so_far() {
+        let set_at: DateTime<Utc> = "2025-03-03T00:00:00Z".parse().unwrap();
+        let goal = IncomeGoal {
+            target_usd: 500.0,
+            deadline: (set_at + TimeDelta::days(7)).into(),
+            description: "earn $500 this week".into(),
+        };
+
+        // $200 in 2 of 7 days projects to $700
+        let day_two = set_at + TimeDelta::days(2);
+        let progress = GoalProgress::compute(&goal, set_at, 200.0, day_two);
+        assert!((progress.remaining_usd - 300.0).abs() < 1e-9);
+        assert!((progress.percent_complete - 40.0).abs() < 1e-9);
+        assert!(progress.is_on_track);
+        assert_eq!(progress.projected_shortfall, 0.0);
+
+        // $100 in 2 of 7 days projects to $350
+        let progress = GoalProgress::compute(&goal, set_at, 100.0, day_two);
+        assert!(!progress.is_on_track);
+        assert!((progress.projected_shortfall - 150.0).abs() < 1e-9);
+
+        // Past the deadline only what was earned counts
+        let late = set_at + TimeDelta::days(8);
+        let progress = GoalProgress::compute(&goal, set_at, 450.0, late);
+        assert!(!progress.is_on_track);
+        assert!((progress.projected_shortfall - 50.0).abs() < 1e-9);
+
+        let progress = GoalProgress::compute(&goal, set_at, 600.0, late);
+        assert!(progress.is_on_track);
+        assert_eq!(progress.remaining_usd, 0.0);
+        assert_eq!(progress.percent_complete, 100.0);
+    }
+}
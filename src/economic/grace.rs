@@ -0,0 +1,182 @@
+//! Grace period between running out of money and hard bankruptcy.
+//!
+//! When the balance first reaches zero the tracker can keep the agent
+//! operating for a limited time or a limited amount of further spend, so a
+//! pending payment still has a chance to rescue it. The agent only becomes
+//! fully bankrupt (and `on_bankruptcy` fires) once the grace budget is used
+//! up.
+
+use super::status::SurvivalStatus;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Longest time limit applied, so the end of grace is always representable
+const MAX_GRACE_HOURS: f64 = 100.0 * 365.0 * 24.0;
+
+/// How long an agent may keep operating after its balance reaches zero.
+///
+/// Grace ends after `hours` or after `spend_usd` of further costs,
+/// whichever comes first. A limit of `0.0` does not apply; with both at
+/// `0.0` (the default) there is no grace period.
+///
+/// In `config.toml`:
+///
+/// ```toml
+/// [economic.bankruptcy_grace]
+/// hours = 24.0
+/// spend_usd = 5.0
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct GracePolicy {
+    /// Maximum grace duration in hours
+    #[serde(default)]
+    pub hours: f64,
+    /// Maximum spend during grace in USD
+    #[serde(default)]
+    pub spend_usd: f64,
+}
+
+impl GracePolicy {
+    /// Whether a grace period applies at all.
+    pub fn is_enabled(&self) -> bool {
+        self.hours > 0.0 || self.spend_usd > 0.0
+    }
+
+    /// When a grace period entered at `since` runs out, if time-limited.
+    ///
+    /// `hours` is capped at a century. `EconomicConfig::validate` rejects a
+    /// value that is not a finite non-negative number; NaN sets no limit.
+    pub fn expires_at(&self, since: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if self.hours.is_nan() || self.hours <= 0.0 {
+            return None;
+        }
+        // Within i64 range after the cap
+        #[allow(clippy::cast_possible_truncation)]
+        let millis = (self.hours.min(MAX_GRACE_HOURS) * 3_600_000.0).round() as i64;
+        since.checked_add_signed(Duration::milliseconds(millis))
+    }
+
+    /// Spend left after spending `spent` during grace, if spend-limited.
+    pub fn spend_remaining(&self, spent: f64) -> Option<f64> {
+        (self.spend_usd > 0.0).then(|| (self.spend_usd - spent).max(0.0))
+    }
+
+    /// Whether a grace period entered at `since` with `spent` of costs is
+    /// used up at `now`.
+    pub(crate) fn is_exhausted(
+        &self,
+        since: DateTime<Utc>,
+        spent: f64,
+        now: DateTime<Utc>,
+    ) -> bool {
+        self.expires_at(since).is_some_and(|expires| now >= expires)
+            || self.spend_remaining(spent).is_some_and(|left| left <= 0.0)
+    }
+}
+
+/// Whether the agent may still operate.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "state")]
+pub enum OperationalState {
+    /// Balance above zero
+    Operating { status: SurvivalStatus },
+    /// Balance at or below zero, still operating on the grace budget
+    Grace {
+        /// When the balance reached zero
+        since: DateTime<Utc>,
+        /// When grace runs out by time, if time-limited
+        expires_at: Option<DateTime<Utc>>,
+        /// Spend left before grace runs out, if spend-limited
+        spend_remaining: Option<f64>,
+    },
+    /// Out of money and out of grace
+    Bankrupt,
+}
+
+impl OperationalState {
+    /// Whether the agent may keep operating (true during grace).
+    pub fn is_operational(&self) -> bool {
+        !matches!(self, Self::Bankrupt)
+    }
+
+    /// Get a human-readable emoji indicator.
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Self::Operating { status } => status.emoji(),
+            Self::Grace { .. } => "⏳",
+            Self::Bankrupt => SurvivalStatus::Bankrupt.emoji(),
+        }
+    }
+}
+
+impl fmt::Display for OperationalState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Operating { status } => write!(f, "{status}"),
+            Self::Grace { .. } => f.write_str("Grace"),
+            Self::Bankrupt => write!(f, "{}", SurvivalStatus::Bankrupt),
+        }
+    }
+}
+
+/// Grace period transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GraceEventKind {
+    /// Balance reached zero and grace began
+    Entered,
+    /// Grace budget used up; the agent is bankrupt
+    Exhausted,
+    /// Balance rose above zero during grace
+    Recovered,
+}
+
+/// Grace period transition, persisted to `grace.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GraceEvent {
+    /// When the transition happened
+    pub timestamp: DateTime<Utc>,
+    /// What happened
+    pub kind: GraceEventKind,
+    /// Balance at the time of the transition
+    pub balance: f64,
+    /// Spend during grace up to the transition
+    pub spent: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grace_ends_at_first_exhausted_limit() {
+        let since: DateTime<Utc> = "2025-01-01T00:00:00Z".parse().unwrap();
+        let policy = GracePolicy {
+            hours: 24.0,
+            spend_usd: 5.0,
+        };
+        assert!(policy.is_enabled());
+        assert_eq!(policy.expires_at(since), Some(since + Duration::hours(24)));
+        assert!(!policy.is_exhausted(since, 4.0, since + Duration::hours(23)));
+        assert!(policy.is_exhausted(since, 5.0, since + Duration::hours(1)));
+        assert!(policy.is_exhausted(since, 0.0, since + Duration::hours(24)));
+
+        // A zero limit does not apply
+        let spend_only = GracePolicy {
+            hours: 0.0,
+            spend_usd: 5.0,
+        };
+        assert_eq!(spend_only.expires_at(since), None);
+
+        // An unbounded window is capped rather than wrapping around
+        let unbounded = GracePolicy {
+            hours: f64::INFINITY,
+            spend_usd: 0.0,
+        };
+        let expires = unbounded.expires_at(since).unwrap();
+        assert_eq!(expires, since + Duration::days(100 * 365));
+        assert!(!spend_only.is_exhausted(since, 1.0, since + Duration::days(365)));
+        assert!(!GracePolicy::default().is_enabled());
+    }
+}
@@ -0,0 +1,318 @@
+//! Point-in-time balance reconstruction.
+//!
+//! Replays the charge, income, and refund records on top of the nearest
+//! earlier balance snapshot: task costs, work and grant income, refunds,
+//! interest, and transfers between agents. Trading P&L and costs tracked
+//! outside a task have no log of their own; the next snapshot carries them
+//! with the time each happened.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::costs::{
+    BalanceChange, BalanceRecord, GrantIncomeRecord, InterestRecord, RefundRecord, TaskCostRecord,
+    TransferRecord, WorkIncomeRecord,
+};
+
+/// Spacing of points returned by `EconomicTracker::balance_series`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BalanceGranularity {
+    Hour,
+    Day,
+    Week,
+}
+
+impl BalanceGranularity {
+    pub(crate) fn step(self) -> Duration {
+        match self {
+            Self::Hour => Duration::hours(1),
+            Self::Day => Duration::days(1),
+            Self::Week => Duration::weeks(1),
+        }
+    }
+}
+
+/// A record from `token_costs.jsonl`, which interleaves task costs and
+/// work income.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub(crate) enum CostLogRecord {
+    Task(Box<TaskCostRecord>),
+    Income(WorkIncomeRecord),
+}
+
+/// Balance snapshots and balance changes, ordered for replay.
+///
+/// Records are added in file order; [`sorted`](Self::sorted) orders them by
+/// time with a stable sort, so records sharing a timestamp keep file order.
+#[derive(Debug, Default)]
+pub(crate) struct BalanceHistory {
+    initial_balance: f64,
+    snapshots: Vec<(DateTime<Utc>, f64)>,
+    events: Vec<(DateTime<Utc>, f64)>,
+}
+
+impl BalanceHistory {
+    pub(crate) fn new(initial_balance: f64) -> Self {
+        Self {
+            initial_balance,
+            ..Default::default()
+        }
+    }
+
+    /// Add a balance snapshot and the unlogged changes it carries.
+    /// Snapshots written before timestamps were recorded are ignored.
+    pub(crate) fn add_snapshot(&mut self, record: &BalanceRecord) {
+        if let Some(timestamp) = record.timestamp {
+            self.snapshots.push((timestamp, record.balance));
+        }
+        for change in &record.unlogged_changes {
+            self.add_change(change);
+        }
+    }
+
+    pub(crate) fn add_cost_log(&mut self, record: CostLogRecord) {
+        match record {
+            CostLogRecord::Task(task) => self.add_task_costs(&task),
+            CostLogRecord::Income(income) => self.add_income(&income),
+        }
+    }
+
+    /// Add each charge of a task at the time it was made. Any part of the
+    /// task total not covered by call details is charged when the task ended.
+    pub(crate) fn add_task_costs(&mut self, record: &TaskCostRecord) {
+        let mut detailed = 0.0;
+        for call in &record.llm_usage.calls_detail {
+            self.events.push((call.timestamp, -call.cost));
+            detailed += call.cost;
+        }
+        for call in &record.api_usage.calls_detail {
+            self.events.push((call.timestamp, -call.cost));
+            detailed += call.cost;
+        }
+        let remainder = record.cost_summary.total() - detailed;
+        if remainder.abs() > 1e-12 {
+            self.events.push((record.timestamp_end, -remainder));
+        }
+    }
+
+    /// Add a charge not yet logged, e.g. a call of an active task.
+    pub(crate) fn add_charge(&mut self, at: DateTime<Utc>, cost: f64) {
+        self.events.push((at, -cost));
+    }
+
+    /// Add a change with no log of its own (see [`BalanceChange`]).
+    pub(crate) fn add_change(&mut self, change: &BalanceChange) {
+        self.events.push((change.timestamp, change.delta));
+    }
+
+    pub(crate) fn add_income(&mut self, record: &WorkIncomeRecord) {
+        if record.actual_payment > 0.0 {
+            self.events.push((record.timestamp, record.actual_payment));
+        }
+    }
+
+    pub(crate) fn add_grant(&mut self, record: &GrantIncomeRecord) {
+        self.events.push((record.timestamp, record.amount));
+    }
+
+    pub(crate) fn add_refund(&mut self, record: &RefundRecord) {
+        self.events.push((record.timestamp, record.amount()));
+    }
+
+    pub(crate) fn add_interest(&mut self, record: &InterestRecord) {
+        self.events.push((record.timestamp, record.delta()));
+    }
+
+    pub(crate) fn add_transfer(&mut self, record: &TransferRecord) {
+        self.events.push((record.timestamp, record.delta()));
+    }
+
+    pub(crate) fn sorted(mut self) -> Self {
+        self.snapshots.sort_by_key(|(time, _)| *time);
+        self.events.sort_by_key(|(time, _)| *time);
+        self
+    }
+
+    /// Time of the earliest snapshot or balance change.
+    pub(crate) fn earliest(&self) -> Option<DateTime<Utc>> {
+        let snapshot = self.snapshots.first().map(|(time, _)| *time);
+        let event = self.events.first().map(|(time, _)| *time);
+        snapshot.into_iter().chain(event).min()
+    }
+
+    /// Balance at `at`: the latest snapshot taken at or before `at` (the
+    /// initial balance if there is none), plus every later change up to and
+    /// including `at`.
+    pub(crate) fn balance_at(&self, at: DateTime<Utc>) -> f64 {
+        let snapshot = self.snapshots.iter().rev().find(|(time, _)| *time <= at);
+        let (from, mut balance) = match snapshot {
+            Some(&(time, balance)) => (Some(time), balance),
+            None => (None, self.initial_balance),
+        };
+        for &(time, delta) in &self.events {
+            if time > at {
+                break;
+            }
+            if from.is_none_or(|from| time > from) {
+                balance += delta;
+            }
+        }
+        balance
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{EconomicConfig, EconomicError, EconomicTracker};
+    use std::thread::sleep;
+    use std::time::{Duration as StdDuration, SystemTime};
+    use tempfile::TempDir;
+
+    /// Current time, separated from records made just before and after.
+    fn pause() -> DateTime<Utc> {
+        sleep(StdDuration::from_millis(5));
+        let now = Utc::now();
+        sleep(StdDuration::from_millis(5));
+        now
+    }
+
+    #[test]
+    fn balance_at_replays_records_after_nearest_snapshot() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        let before_start = pause();
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_tokens(1000, 500, "agent", Some(10.0), StdDuration::ZERO)
+            .unwrap();
+        let after_tokens = pause();
+        tracker.track_flat_api_call(5.0, "tavily_search");
+        let after_search = pause();
+        tracker.end_task("task-1").unwrap();
+        let after_task = pause();
+        tracker.add_work_income(20.0, "task-1", 0.9, "").unwrap();
+        let after_income = pause();
+
+        assert!((tracker.balance_at(before_start).unwrap() - 100.0).abs() < 1e-9);
+        assert!((tracker.balance_at(after_tokens).unwrap() - 90.0).abs() < 1e-9);
+        assert!((tracker.balance_at(after_search).unwrap() - 85.0).abs() < 1e-9);
+        assert!((tracker.balance_at(after_task).unwrap() - 85.0).abs() < 1e-9);
+        assert!((tracker.balance_at(after_income).unwrap() - 105.0).abs() < 1e-9);
+        assert!((tracker.balance_at(Utc::now()).unwrap() - tracker.get_balance()).abs() < 1e-9);
+
+        let series = tracker
+            .balance_series(after_tokens..=Utc::now(), BalanceGranularity::Hour)
+            .unwrap();
+        assert_eq!(series.len(), 1);
+        assert_eq!(series[0].0, after_tokens);
+        assert!((series[0].1 - 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn balance_at_time_replays_each_change() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let before_session = SystemTime::from(pause());
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        let started = SystemTime::from(pause());
+
+        // A charge outside any task, trading P&L, work income and a grant
+        tracker
+            .track_tokens(1000, 500, "agent", Some(10.0), StdDuration::ZERO)
+            .unwrap();
+        let after_charge = SystemTime::from(pause());
+        tracker.add_trading_profit(-4.0, "hedge");
+        let after_trade = SystemTime::from(pause());
+        // Neither is snapshotted on its own
+        let snapshots = std::fs::read_to_string(tmp.path().join("balance.jsonl")).unwrap();
+        assert_eq!(snapshots.lines().count(), 1);
+        tracker.add_work_income(34.0, "task-1", 0.9, "").unwrap();
+        let after_income = SystemTime::from(pause());
+        tracker.add_grant_income(5.0, "grant-1", "top-up").unwrap();
+        let after_grant = SystemTime::from(pause());
+        // Charged to a task that has not ended, so not logged yet
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker
+            .track_tokens(1000, 500, "agent", Some(7.0), StdDuration::ZERO)
+            .unwrap();
+        let after_task_charge = SystemTime::from(pause());
+
+        let balance_at = |at| tracker.get_balance_at_time(at).unwrap();
+        assert!((balance_at(started) - 100.0).abs() < 1e-9);
+        assert!((balance_at(after_charge) - 90.0).abs() < 1e-9);
+        assert!((balance_at(after_trade) - 86.0).abs() < 1e-9);
+        assert!((balance_at(after_income) - 120.0).abs() < 1e-9);
+        assert!((balance_at(after_grant) - 125.0).abs() < 1e-9);
+        assert!((balance_at(after_task_charge) - 118.0).abs() < 1e-9);
+        assert!((balance_at(SystemTime::now()) - tracker.get_balance()).abs() < 1e-9);
+
+        assert!(matches!(
+            tracker.get_balance_at_time(before_session),
+            Err(EconomicError::BeforeSessionStart { .. })
+        ));
+    }
+
+    #[test]
+    fn identical_timestamps_apply_in_file_order() {
+        let at: DateTime<Utc> = "2025-01-01T12:00:00Z".parse().unwrap();
+        let snapshot = |balance: f64| BalanceRecord {
+            date: "2025-01-01".to_string(),
+            balance,
+            token_cost_delta: 0.0,
+            work_income_delta: 0.0,
+            trading_profit_delta: 0.0,
+            total_token_cost: 0.0,
+            total_work_income: 0.0,
+            total_trading_profit: 0.0,
+            net_worth: balance,
+            survival_status: String::new(),
+            completed_tasks: Vec::new(),
+            task_id: None,
+            task_completion_time_seconds: None,
+            api_error: false,
+            total_refunds: 0.0,
+            total_grant_income: 0.0,
+            total_interest_earned: 0.0,
+            total_interest_paid: 0.0,
+            grace_spent: 0.0,
+            checkpoint: true,
+            timestamp: Some(at),
+            unlogged_changes: Vec::new(),
+        };
+        let refund = |amount: f64| RefundRecord {
+            timestamp: at + Duration::seconds(1),
+            original_record_id: "charge".to_string(),
+            cost: -amount,
+            reason: String::new(),
+            balance_after: 0.0,
+        };
+
+        let mut history = BalanceHistory::new(100.0);
+        history.add_snapshot(&snapshot(50.0));
+        history.add_snapshot(&snapshot(40.0));
+        history.add_refund(&refund(2.0));
+        history.add_refund(&refund(3.0));
+        let history = history.sorted();
+
+        // The later snapshot in the file wins; both refunds follow it
+        assert!((history.balance_at(at) - 40.0).abs() < 1e-9);
+        assert!((history.balance_at(at + Duration::seconds(1)) - 45.0).abs() < 1e-9);
+        assert!((history.balance_at(at - Duration::seconds(1)) - 100.0).abs() < 1e-9);
+        assert_eq!(history.earliest(), Some(at));
+    }
+}
@@ -0,0 +1,121 @@
+//! Automatic task-intake control based on survival status.
+//!
+//! With a hysteresis policy the tracker stops admitting new tasks once the
+//! survival status drops to `pause_at` and only admits them again after the
+//! status recovers to `resume_at`, so a balance hovering around a single
+//! threshold does not flip intake on and off.
+
+use super::status::SurvivalStatus;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// When the tracker pauses and resumes task intake.
+///
+/// In `config.toml`:
+///
+/// ```toml
+/// [economic.intake_policy]
+/// mode = "hysteresis"
+/// pause_at = "critical"
+/// resume_at = "struggling"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "mode")]
+pub enum IntakePolicy {
+    /// Never pause intake
+    #[default]
+    Disabled,
+    /// Pause at `pause_at` or worse; resume at `resume_at` or better
+    Hysteresis {
+        pause_at: SurvivalStatus,
+        resume_at: SurvivalStatus,
+    },
+}
+
+impl IntakePolicy {
+    /// Whether intake should pause at `status`.
+    pub(crate) fn should_pause(self, status: SurvivalStatus) -> bool {
+        match self {
+            Self::Disabled => false,
+            Self::Hysteresis { pause_at, .. } => status.severity() >= pause_at.severity(),
+        }
+    }
+
+    /// Whether paused intake should resume at `status`. Resuming always
+    /// requires a status better than `pause_at`, even if `resume_at` is not.
+    pub(crate) fn should_resume(self, status: SurvivalStatus) -> bool {
+        match self {
+            Self::Disabled => true,
+            Self::Hysteresis { resume_at, .. } => {
+                status.severity() <= resume_at.severity() && !self.should_pause(status)
+            }
+        }
+    }
+}
+
+/// Answer to `EconomicTracker::can_accept_work`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "admission")]
+pub enum WorkAdmission {
+    /// New tasks may be started
+    Accept,
+    /// Intake is paused by the intake policy
+    Paused {
+        /// Current survival status
+        status: SurvivalStatus,
+        /// When intake was paused
+        since: DateTime<Utc>,
+    },
+}
+
+impl WorkAdmission {
+    /// Whether new tasks may be started.
+    pub fn is_accepted(&self) -> bool {
+        matches!(self, Self::Accept)
+    }
+}
+
+/// Intake pause or resume, persisted to `intake.jsonl`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IntakeEvent {
+    /// When intake was paused or resumed
+    pub timestamp: DateTime<Utc>,
+    /// `true` when intake was paused, `false` when it resumed
+    pub paused: bool,
+    /// Survival status that triggered the change
+    pub status: SurvivalStatus,
+    /// Balance at the time of the change
+    pub balance: f64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hysteresis_band_keeps_current_state() {
+        let policy = IntakePolicy::Hysteresis {
+            pause_at: SurvivalStatus::Critical,
+            resume_at: SurvivalStatus::Stable,
+        };
+        assert!(policy.should_pause(SurvivalStatus::Critical));
+        assert!(policy.should_pause(SurvivalStatus::Bankrupt));
+        assert!(!policy.should_pause(SurvivalStatus::Struggling));
+
+        // Struggling is inside the band: neither pauses nor resumes
+        assert!(!policy.should_resume(SurvivalStatus::Struggling));
+        assert!(policy.should_resume(SurvivalStatus::Stable));
+        assert!(policy.should_resume(SurvivalStatus::Thriving));
+
+        // A resume threshold worse than the pause threshold still requires
+        // leaving the paused range
+        let inverted = IntakePolicy::Hysteresis {
+            pause_at: SurvivalStatus::Struggling,
+            resume_at: SurvivalStatus::Bankrupt,
+        };
+        assert!(!inverted.should_resume(SurvivalStatus::Critical));
+        assert!(inverted.should_resume(SurvivalStatus::Stable));
+
+        assert!(!IntakePolicy::Disabled.should_pause(SurvivalStatus::Bankrupt));
+    }
+}
@@ -0,0 +1,348 @@
+//! Hash-chained JSONL records for tamper detection.
+//!
+//! With `EconomicConfig::record_integrity` on, every record the tracker
+//! appends carries an `integrity` field holding the hash of the previous
+//! record in the file and its own hash: SHA-256 over the previous hash and
+//! the record's canonical JSON (keys sorted, without the `integrity`
+//! field). Editing, removing, or inserting a record breaks the chain at that
+//! line, which `EconomicTracker::verify_integrity` reports.
+//!
+//! Records written before integrity was turned on carry no link and are
+//! accepted at the head of a file. The first sealed record of a file is
+//! trusted to follow whatever preceded it, so archiving old records from
+//! the head of a file does not break the chain. There is no key: the chain
+//! catches hand edits and corruption, not someone who recomputes the hashes.
+
+use super::error::{IoResultExt, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+/// Field holding a record's link in the chain.
+pub(crate) const INTEGRITY_FIELD: &str = "integrity";
+
+/// Logs the tracker appends to, and so seals.
+pub(crate) const SEALED_LOGS: [&str; 12] = [
+    "balance.jsonl",
+    "token_costs.jsonl",
+    "task_completions.jsonl",
+    "refunds.jsonl",
+    "grant_income.jsonl",
+    "interest.jsonl",
+    "intake.jsonl",
+    "grace.jsonl",
+    "escrow.jsonl",
+    "transfers.jsonl",
+    "cost_corrections.jsonl",
+    "overhead.jsonl",
+];
+
+/// Result of `EconomicTracker::verify_integrity`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    /// One entry per log file present in the data directory
+    pub files: Vec<FileIntegrity>,
+}
+
+impl IntegrityReport {
+    /// Whether every file's chain is unbroken.
+    pub fn is_intact(&self) -> bool {
+        self.files.iter().all(|file| file.broken.is_none())
+    }
+
+    /// The first broken link, by file order, with the file it is in.
+    pub fn first_break(&self) -> Option<(&Path, &BrokenLink)> {
+        self.files
+            .iter()
+            .find_map(|file| Some((file.path.as_path(), file.broken.as_ref()?)))
+    }
+}
+
+/// Chain state of a single log file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileIntegrity {
+    pub path: PathBuf,
+    /// Non-blank lines checked, up to and including a broken link
+    pub records: usize,
+    /// Unsealed records ahead of the first sealed one
+    pub legacy_records: usize,
+    /// Sealed records whose link verified
+    pub sealed_records: usize,
+    /// First broken link, if any; lines after it are not checked
+    pub broken: Option<BrokenLink>,
+}
+
+/// Where and how a chain broke.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BrokenLink {
+    /// 1-based line number in the file
+    pub line: usize,
+    pub kind: BrokenLinkKind,
+}
+
+/// Why a link failed to verify.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrokenLinkKind {
+    /// The record's content no longer matches its hash (edited)
+    HashMismatch,
+    /// The record does not follow the previous sealed record (a record was
+    /// removed, reordered, or inserted)
+    PrevMismatch,
+    /// An unsealed record follows sealed ones
+    Unsealed,
+    /// The line is not valid JSON
+    Unparseable,
+}
+
+/// A record's link in the chain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Link {
+    /// Hash of the previous record, absent at the start of a chain
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    prev: Option<String>,
+    /// Hash of this record
+    hash: String,
+}
+
+/// Serialize `record` with a link following `prev`; returns the sealed
+/// record and its hash.
+pub(crate) fn seal<T: Serialize>(record: &T, prev: Option<&str>) -> Result<(Value, String)> {
+    let mut value = serde_json::to_value(record)?;
+    let hash = link_hash(prev, &value);
+    if let Value::Object(map) = &mut value {
+        let link = Link {
+            prev: prev.map(str::to_string),
+            hash: hash.clone(),
+        };
+        map.insert(INTEGRITY_FIELD.to_string(), serde_json::to_value(link)?);
+    }
+    Ok((value, hash))
+}
+
+/// Hash of the last sealed record of `path`, if any.
+pub(crate) fn last_hash(path: &Path) -> Result<Option<String>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut last = None;
+    for line in BufReader::new(File::open(path).at_path(path)?).lines() {
+        if let Some(link) = parse_link(&line.at_path(path)?).1 {
+            last = Some(link.hash);
+        }
+    }
+    Ok(last)
+}
+
+/// Reseal `lines` as one chain from the start, after a rewrite dropped
+/// records from the middle; returns the hash of the last record. Lines that
+/// are not JSON objects are left as they are.
+pub(crate) fn rechain(lines: &mut [String]) -> Result<Option<String>> {
+    let mut prev: Option<String> = None;
+    for line in lines.iter_mut() {
+        let Ok(Value::Object(mut map)) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        map.remove(INTEGRITY_FIELD);
+        let (sealed, hash) = seal(&Value::Object(map), prev.as_deref())?;
+        *line = sealed.to_string();
+        prev = Some(hash);
+    }
+    Ok(prev)
+}
+
+/// Walk the chain of `path` up to its first broken link.
+pub(crate) fn verify_file(path: &Path) -> Result<FileIntegrity> {
+    let mut report = FileIntegrity {
+        path: path.to_path_buf(),
+        records: 0,
+        legacy_records: 0,
+        sealed_records: 0,
+        broken: None,
+    };
+    let mut prev: Option<String> = None;
+
+    for (index, line) in BufReader::new(File::open(path).at_path(path)?)
+        .lines()
+        .enumerate()
+    {
+        let line = line.at_path(path)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        report.records += 1;
+
+        let (value, link) = parse_link(&line);
+        let kind = match (value, link) {
+            (Some(mut value), Some(link)) => {
+                if let Value::Object(map) = &mut value {
+                    map.remove(INTEGRITY_FIELD);
+                }
+                if prev.is_some() && link.prev != prev {
+                    Some(BrokenLinkKind::PrevMismatch)
+                } else if link_hash(link.prev.as_deref(), &value) != link.hash {
+                    Some(BrokenLinkKind::HashMismatch)
+                } else {
+                    report.sealed_records += 1;
+                    prev = Some(link.hash);
+                    None
+                }
+            }
+            // Legacy records are only accepted before the chain starts
+            (_, None) if prev.is_none() => {
+                report.legacy_records += 1;
+                None
+            }
+            (Some(_), None) => Some(BrokenLinkKind::Unsealed),
+            (None, _) => Some(BrokenLinkKind::Unparseable),
+        };
+        if let Some(kind) = kind {
+            report.broken = Some(BrokenLink {
+                line: index + 1,
+                kind,
+            });
+            break;
+        }
+    }
+
+    Ok(report)
+}
+
+/// The JSON value of `line` and its link, when present.
+fn parse_link(line: &str) -> (Option<Value>, Option<Link>) {
+    let Ok(value) = serde_json::from_str::<Value>(line) else {
+        return (None, None);
+    };
+    let link = value
+        .get(INTEGRITY_FIELD)
+        .and_then(|link| Link::deserialize(link).ok());
+    (Some(value), link)
+}
+
+/// SHA-256 over the previous hash and the canonical form of `value`.
+fn link_hash(prev: Option<&str>, value: &Value) -> String {
+    let mut canonical = String::new();
+    write_canonical(value, &mut canonical);
+    let mut hasher = Sha256::new();
+    hasher.update(prev.unwrap_or_default().as_bytes());
+    hasher.update(b"\n");
+    hasher.update(canonical.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+/// Compact JSON with object keys sorted, whatever the map's own order.
+fn write_canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            out.push('{');
+            for (i, (key, item)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn sealed_lines(records: &[Value]) -> Vec<String> {
+        let mut prev: Option<String> = None;
+        records
+            .iter()
+            .map(|record| {
+                let (sealed, hash) = seal(record, prev.as_deref()).unwrap();
+                prev = Some(hash);
+                sealed.to_string()
+            })
+            .collect()
+    }
+
+    fn verify(lines: &[String]) -> FileIntegrity {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("refunds.jsonl");
+        fs::write(&path, lines.join("\n") + "\n").unwrap();
+        verify_file(&path).unwrap()
+    }
+
+    #[test]
+    fn chain_detects_edits_removals_and_insertions() {
+        let records: Vec<Value> = (1..=4)
+            .map(|n| json!({"amount": n, "reason": format!("refund {n}")}))
+            .collect();
+        let lines = sealed_lines(&records);
+        let intact = verify(&lines);
+        assert_eq!((intact.sealed_records, intact.broken), (4, None));
+
+        let mut edited = lines.clone();
+        edited[1] = edited[1].replace(r#""amount":2"#, r#""amount":20"#);
+        let broken = verify(&edited).broken.unwrap();
+        assert_eq!(
+            (broken.line, broken.kind),
+            (2, BrokenLinkKind::HashMismatch)
+        );
+
+        let mut removed = lines.clone();
+        removed.remove(2);
+        let broken = verify(&removed).broken.unwrap();
+        assert_eq!(
+            (broken.line, broken.kind),
+            (3, BrokenLinkKind::PrevMismatch)
+        );
+
+        let mut inserted = lines.clone();
+        inserted.insert(2, json!({"amount": 9}).to_string());
+        let broken = verify(&inserted).broken.unwrap();
+        assert_eq!((broken.line, broken.kind), (3, BrokenLinkKind::Unsealed));
+
+        // Archiving records from the head leaves a valid chain
+        let trimmed = verify(&lines[2..]);
+        assert_eq!((trimmed.sealed_records, trimmed.broken), (2, None));
+    }
+
+    #[test]
+    fn legacy_records_are_accepted_at_the_head() {
+        let mut lines = vec![
+            json!({"amount": 1}).to_string(),
+            json!({"amount": 2}).to_string(),
+        ];
+        lines.extend(sealed_lines(&[json!({"amount": 3}), json!({"amount": 4})]));
+        let report = verify(&lines);
+        assert_eq!(report.legacy_records, 2);
+        assert_eq!(report.sealed_records, 2);
+        assert!(report.broken.is_none());
+
+        // Resealing after a rewrite chains every record
+        let mut rewritten = lines.clone();
+        rewritten.remove(2);
+        let head = rechain(&mut rewritten).unwrap();
+        let report = verify(&rewritten);
+        assert_eq!((report.sealed_records, report.broken), (3, None));
+        assert!(rewritten[2].contains(&head.unwrap()));
+    }
+}
@@ -0,0 +1,322 @@
+//! Range queries over active and archived JSONL logs.
+//!
+//! Rotation moves old records out of the active files into gzip archives.
+//! Two archive names are recognized, in the data directory or its
+//! `archive/` subdirectory:
+//! - `<stem>.<YYYY-MM>.jsonl.gz`: records from one calendar month (UTC)
+//! - `<stem>-<YYYYMMDDTHHMMSS.fffZ>.jsonl.gz`: records older than the
+//!   timestamp, as written by `EconomicTracker::drain_to_archive`
+//!
+//! A range query only opens archives whose embedded date can overlap the
+//! range, and merges their records with the active file in time order.
+//! In a build without the default `archive-read` feature, a query whose
+//! range reaches into an archive fails with
+//! [`EconomicError::ArchiveReadDisabled`] rather than silently leaving the
+//! archived records out.
+
+use super::costs::{
+    GrantIncomeRecord, OverheadRecord, RecordReader, RefundRecord, TaskCompletionRecord,
+    TaskCostRecord, WorkIncomeRecord,
+};
+use super::error::{EconomicError, Result};
+use super::range::DateRange;
+use chrono::{DateTime, Months, NaiveDate, NaiveDateTime, Utc};
+use serde::de::DeserializeOwned;
+use std::path::{Path, PathBuf};
+
+/// Record whose time decides which range it belongs to.
+pub(crate) trait LoggedRecord {
+    fn logged_at(&self) -> DateTime<Utc>;
+}
+
+impl LoggedRecord for TaskCostRecord {
+    fn logged_at(&self) -> DateTime<Utc> {
+        self.timestamp_end
+    }
+}
+
+impl LoggedRecord for TaskCompletionRecord {
+    fn logged_at(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
+
+impl LoggedRecord for WorkIncomeRecord {
+    fn logged_at(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
+
+impl LoggedRecord for GrantIncomeRecord {
+    fn logged_at(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
+
+impl LoggedRecord for RefundRecord {
+    fn logged_at(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
+
+impl LoggedRecord for OverheadRecord {
+    fn logged_at(&self) -> DateTime<Utc> {
+        self.timestamp
+    }
+}
+
+/// Files holding records of the log `name` (e.g. `token_costs.jsonl`) that
+/// may fall within `range`: the overlapping archives, oldest first, then
+/// the active file. Missing files are left out.
+pub(crate) fn log_files(data_dir: &Path, name: &str, range: &DateRange) -> Vec<PathBuf> {
+    let mut files = archives(data_dir, name, range);
+    let active = data_dir.join(name);
+    if active.exists() {
+        files.push(active);
+    }
+    files
+}
+
+/// Visit the records of `files` that decode as `T`, merged in time order.
+///
+/// Each file is assumed to be in time order already (the logs are
+/// append-only); records with equal times keep the order of `files`. Lines
+/// of other record kinds are skipped.
+pub(crate) fn for_each_merged<T, F>(files: &[PathBuf], mut on_record: F) -> Result<()>
+where
+    T: DeserializeOwned + LoggedRecord,
+    F: FnMut(T),
+{
+    let mut heads = Vec::with_capacity(files.len());
+    for path in files {
+        let mut reader = RecordReader::<T>::open(path)?;
+        let head = next_record(&mut reader)?;
+        heads.push((reader, head));
+    }
+
+    loop {
+        let mut earliest: Option<(usize, DateTime<Utc>)> = None;
+        for (i, (_, head)) in heads.iter().enumerate() {
+            if let Some(record) = head {
+                let time = record.logged_at();
+                if earliest.is_none_or(|(_, earliest)| time < earliest) {
+                    earliest = Some((i, time));
+                }
+            }
+        }
+        let Some((i, _)) = earliest else {
+            return Ok(());
+        };
+        let (reader, head) = &mut heads[i];
+        let next = next_record(reader)?;
+        if let Some(record) = std::mem::replace(head, next) {
+            on_record(record);
+        }
+    }
+}
+
+/// Next record of `reader` that decodes, skipping lines of other kinds.
+pub(crate) fn next_record<T: DeserializeOwned>(reader: &mut RecordReader<T>) -> Result<Option<T>> {
+    for record in reader.by_ref() {
+        match record {
+            Ok(record) => return Ok(Some(record)),
+            Err(EconomicError::MalformedRecord { .. }) => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(None)
+}
+
+/// Archives of the log `name` that overlap `range`, oldest first.
+fn archives(data_dir: &Path, name: &str, range: &DateRange) -> Vec<PathBuf> {
+    let stem = name.strip_suffix(".jsonl").unwrap_or(name);
+    let mut found: Vec<(DateTime<Utc>, PathBuf)> = Vec::new();
+    for dir in [data_dir.to_path_buf(), data_dir.join("archive")] {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(span) = file_name.to_str().and_then(|name| archive_span(name, stem)) else {
+                continue;
+            };
+            if span.overlaps(range) {
+                found.push((span.end, entry.path()));
+            }
+        }
+    }
+    found.sort();
+    found.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Period the records of an archive fall in, from its file name.
+#[derive(Debug, PartialEq, Eq)]
+struct ArchiveSpan {
+    /// No record is older than this, when known
+    start: Option<DateTime<Utc>>,
+    /// Every record is older than this
+    end: DateTime<Utc>,
+}
+
+impl ArchiveSpan {
+    fn overlaps(&self, range: &DateRange) -> bool {
+        self.start.is_none_or(|start| start < range.end) && range.start < self.end
+    }
+}
+
+/// Span of an archive of the log `stem`, or `None` for any other file.
+fn archive_span(file_name: &str, stem: &str) -> Option<ArchiveSpan> {
+    let rest = file_name.strip_suffix(".jsonl.gz")?.strip_prefix(stem)?;
+    if let Some(month) = rest.strip_prefix('.') {
+        let first = NaiveDate::parse_from_str(&format!("{month}-01"), "%Y-%m-%d").ok()?;
+        let next = first.checked_add_months(Months::new(1))?;
+        return Some(ArchiveSpan {
+            start: Some(first.and_hms_opt(0, 0, 0)?.and_utc()),
+            end: next.and_hms_opt(0, 0, 0)?.and_utc(),
+        });
+    }
+    let stamp = rest.strip_prefix('-')?;
+    let end = NaiveDateTime::parse_from_str(stamp, "%Y%m%dT%H%M%S%.3fZ").ok()?;
+    Some(ArchiveSpan {
+        start: None,
+        end: end.and_utc(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use serde::Deserialize;
+    use std::fs;
+    use tempfile::TempDir;
+    #[cfg(feature = "archive-read")]
+    use {
+        flate2::{write::GzEncoder, Compression},
+        std::{fs::File, io::Write},
+    };
+
+    #[derive(Deserialize)]
+    struct Stamped {
+        timestamp: DateTime<Utc>,
+        id: u32,
+    }
+
+    impl LoggedRecord for Stamped {
+        fn logged_at(&self) -> DateTime<Utc> {
+            self.timestamp
+        }
+    }
+
+    fn line(id: u32, day: &str) -> String {
+        format!(r#"{{"id":{id},"timestamp":"{day}T12:00:00Z"}}"#)
+    }
+
+    #[cfg(feature = "archive-read")]
+    fn write_gz(path: &Path, lines: &[String]) {
+        let mut encoder = GzEncoder::new(File::create(path).unwrap(), Compression::default());
+        for line in lines {
+            writeln!(encoder, "{line}").unwrap();
+        }
+        encoder.finish().unwrap();
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn archive_names_embed_their_period() {
+        let january = archive_span("token_costs.2025-01.jsonl.gz", "token_costs").unwrap();
+        assert_eq!(
+            january.start,
+            Some(Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap())
+        );
+        assert_eq!(
+            january.end,
+            Utc.with_ymd_and_hms(2025, 2, 1, 0, 0, 0).unwrap()
+        );
+
+        let drained = archive_span("token_costs-20250115T103000.250Z.jsonl.gz", "token_costs");
+        assert_eq!(drained.unwrap().start, None);
+
+        assert!(archive_span("token_costs.jsonl", "token_costs").is_none());
+        assert!(archive_span("refunds.2025-01.jsonl.gz", "token_costs").is_none());
+        assert!(archive_span("token_costs.backup.jsonl.gz", "token_costs").is_none());
+    }
+
+    #[cfg(feature = "archive-read")]
+    #[test]
+    fn mixed_directory_merges_only_overlapping_files() {
+        let tmp = TempDir::new().unwrap();
+        let archive_dir = tmp.path().join("archive");
+        fs::create_dir_all(&archive_dir).unwrap();
+
+        write_gz(
+            &tmp.path().join("grants.2024-12.jsonl.gz"),
+            &[line(1, "2024-12-10")],
+        );
+        write_gz(
+            &tmp.path().join("grants.2025-01.jsonl.gz"),
+            &[line(2, "2025-01-05"), line(4, "2025-01-20")],
+        );
+        // Drained in mid-January, overlapping the monthly archive
+        write_gz(
+            &archive_dir.join("grants-20250115T000000.000Z.jsonl.gz"),
+            &[line(3, "2025-01-10")],
+        );
+        fs::write(
+            tmp.path().join("grants.jsonl"),
+            format!(
+                "{}\nnot a record\n{}\n",
+                line(5, "2025-02-03"),
+                line(6, "2025-02-04")
+            ),
+        )
+        .unwrap();
+
+        let range = DateRange::days(date(2025, 1, 1), date(2025, 3, 1), &Utc);
+        let files = log_files(tmp.path(), "grants.jsonl", &range);
+        assert_eq!(files.len(), 3);
+        assert!(!files
+            .iter()
+            .any(|path| path.ends_with("grants.2024-12.jsonl.gz")));
+        assert!(files.last().unwrap().ends_with("grants.jsonl"));
+
+        let mut ids = Vec::new();
+        for_each_merged::<Stamped, _>(&files, |record| ids.push(record.id)).unwrap();
+        assert_eq!(ids, [2, 3, 4, 5, 6]);
+
+        // A range after every archive reads only the active file
+        let recent = DateRange::days(date(2025, 2, 1), date(2025, 3, 1), &Utc);
+        assert_eq!(log_files(tmp.path(), "grants.jsonl", &recent).len(), 1);
+    }
+
+    #[cfg(not(feature = "archive-read"))]
+    #[test]
+    fn archives_in_range_fail_without_archive_read() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("grants.2025-01.jsonl.gz"), [0x1f, 0x8b]).unwrap();
+        fs::write(
+            tmp.path().join("grants.jsonl"),
+            format!("{}\n", line(5, "2025-02-03")),
+        )
+        .unwrap();
+
+        let range = DateRange::days(date(2025, 1, 1), date(2025, 3, 1), &Utc);
+        let files = log_files(tmp.path(), "grants.jsonl", &range);
+        let err = for_each_merged::<Stamped, _>(&files, |_| {}).unwrap_err();
+        assert!(matches!(
+            err,
+            EconomicError::ArchiveReadDisabled { ref path }
+                if path.ends_with("grants.2025-01.jsonl.gz")
+        ));
+
+        // A range clear of the archive still reads the active file
+        let recent = DateRange::days(date(2025, 2, 1), date(2025, 3, 1), &Utc);
+        let files = log_files(tmp.path(), "grants.jsonl", &recent);
+        let mut ids = Vec::new();
+        for_each_merged::<Stamped, _>(&files, |record| ids.push(record.id)).unwrap();
+        assert_eq!(ids, [5]);
+    }
+}
@@ -0,0 +1,692 @@
+//! Merging economic data directories.
+//!
+//! Combines the JSONL logs of two data directories for the same agent (for
+//! example after a migration left two partial copies covering overlapping
+//! periods), drops duplicate records, and rebuilds `balance.jsonl` from the
+//! merged history.
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+use super::costs::{
+    BalanceRecord, CostCorrectionRecord, GrantIncomeRecord, InterestKind, InterestRecord,
+    RecordReader, RefundRecord, TransferRecord,
+};
+use super::error::{EconomicError, IoResultExt, Result};
+use super::history::CostLogRecord;
+use super::snapshot::IMPORTED_DATE;
+use super::status::SurvivalStatus;
+
+/// Logs merged record by record; `balance.jsonl` is rebuilt instead.
+pub(crate) const EVENT_LOGS: [&str; 11] = [
+    "token_costs.jsonl",
+    "task_completions.jsonl",
+    "refunds.jsonl",
+    "grant_income.jsonl",
+    "interest.jsonl",
+    "intake.jsonl",
+    "grace.jsonl",
+    "escrow.jsonl",
+    "transfers.jsonl",
+    "cost_corrections.jsonl",
+    "overhead.jsonl",
+];
+
+pub(crate) const BALANCE_LOG: &str = "balance.jsonl";
+
+/// Outcome of `EconomicTracker::merge_data_dirs`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Records written to the merged event logs
+    pub records_written: usize,
+    /// Records dropped because an identical record (same hash) was merged
+    pub duplicates_removed: usize,
+    /// Work income records dropped because the task was already paid the
+    /// same amount (income is credited once per task)
+    pub income_duplicates_removed: usize,
+    /// Balance snapshots written to the merged `balance.jsonl`
+    pub snapshots_rebuilt: usize,
+    /// Balance after replaying the merged history
+    pub final_balance: f64,
+    /// Disagreements that need manual review
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// A disagreement between the two directories.
+///
+/// Where a record had to be chosen, the primary directory's version was
+/// written; the conflict lists both so the choice can be reviewed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergeConflict {
+    /// File the conflicting records came from
+    pub file: String,
+    /// What the records disagree on (task id, date, line)
+    pub key: String,
+    /// Description of the disagreement
+    pub detail: String,
+}
+
+/// A decoded JSONL line.
+struct MergeLine {
+    raw: String,
+    value: Value,
+    time: Option<DateTime<Utc>>,
+    from_primary: bool,
+}
+
+impl MergeLine {
+    fn source(&self) -> &'static str {
+        if self.from_primary {
+            "primary"
+        } else {
+            "secondary"
+        }
+    }
+}
+
+/// Whether any economic log in `dir` holds records.
+pub(crate) fn has_economic_data(dir: &Path) -> Result<bool> {
+    for file in EVENT_LOGS.iter().chain([&BALANCE_LOG]) {
+        let path = dir.join(file);
+        if path.exists() && fs::metadata(&path).at_path(&path)?.len() > 0 {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Merge `primary` and `secondary` into the empty directory `output`.
+pub(crate) fn merge_data_dirs(
+    primary: &Path,
+    secondary: &Path,
+    output: &Path,
+) -> Result<MergeReport> {
+    if has_economic_data(output)? {
+        return Err(EconomicError::DataDirNotEmpty {
+            path: output.to_path_buf(),
+        });
+    }
+    fs::create_dir_all(output).at_path(output)?;
+
+    let mut report = MergeReport::default();
+    let mut merged: HashMap<&str, Vec<Value>> = HashMap::new();
+    for file in EVENT_LOGS {
+        let lines = merge_log(file, primary, secondary, &mut report)?;
+        write_lines(&output.join(file), lines.iter().map(|line| line.raw.as_str()))?;
+        report.records_written += lines.len();
+        merged.insert(file, lines.into_iter().map(|line| line.value).collect());
+    }
+
+    let primary_snapshots = read_snapshots(&primary.join(BALANCE_LOG))?;
+    let secondary_snapshots = read_snapshots(&secondary.join(BALANCE_LOG))?;
+    let Some(opening) = primary_snapshots
+        .first()
+        .or_else(|| secondary_snapshots.first())
+    else {
+        return Err(EconomicError::NoBalanceHistory {
+            primary: primary.to_path_buf(),
+            secondary: secondary.to_path_buf(),
+        });
+    };
+    if let (Some(a), Some(b)) = (primary_snapshots.first(), secondary_snapshots.first()) {
+        if (a.balance - b.balance).abs() > 1e-9 {
+            report.conflicts.push(MergeConflict {
+                file: BALANCE_LOG.to_string(),
+                key: "initialization".to_string(),
+                detail: format!(
+                    "opening balance {} in primary, {} in secondary; used primary",
+                    a.balance, b.balance
+                ),
+            });
+        }
+    }
+
+    let snapshots = rebuild_snapshots(
+        opening,
+        &primary_snapshots,
+        &secondary_snapshots,
+        &merged,
+        &mut report,
+    );
+    let encoded = snapshots
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<serde_json::Result<Vec<_>>>()?;
+    write_lines(&output.join(BALANCE_LOG), encoded.iter().map(String::as_str))?;
+    report.snapshots_rebuilt = snapshots.len();
+    report.final_balance = snapshots.last().map_or(0.0, |snapshot| snapshot.balance);
+
+    Ok(report)
+}
+
+/// Merge one log: drop duplicates, then order chronologically.
+///
+/// Primary lines are offered first, so when records compete for the same
+/// key the primary's is kept, and records sharing a timestamp keep primary
+/// before secondary.
+fn merge_log(
+    file: &str,
+    primary: &Path,
+    secondary: &Path,
+    report: &mut MergeReport,
+) -> Result<Vec<MergeLine>> {
+    let mut lines = read_lines(&primary.join(file), true, file, report)?;
+    lines.extend(read_lines(&secondary.join(file), false, file, report)?);
+
+    let mut hashes = HashSet::new();
+    let mut keyed: HashMap<String, usize> = HashMap::new();
+    let mut kept: Vec<MergeLine> = Vec::new();
+    for line in lines {
+        if !hashes.insert(record_hash(&line.value)) {
+            report.duplicates_removed += 1;
+            continue;
+        }
+
+        if let Some(key) = unique_key(file, &line.value) {
+            if let Some(&index) = keyed.get(&key) {
+                let existing = &kept[index];
+                if is_income(&existing.value) && payment(&existing.value) == payment(&line.value) {
+                    report.income_duplicates_removed += 1;
+                } else {
+                    report.conflicts.push(MergeConflict {
+                        file: file.to_string(),
+                        key,
+                        detail: format!(
+                            "{} record kept, differing {} record dropped: {}",
+                            existing.source(),
+                            line.source(),
+                            line.raw
+                        ),
+                    });
+                }
+                continue;
+            }
+            keyed.insert(key, kept.len());
+        }
+        kept.push(line);
+    }
+
+    kept.sort_by_key(|line| line.time);
+    Ok(kept)
+}
+
+/// Key under which only one record may exist: one work income record and
+/// one completion record per task.
+fn unique_key(file: &str, value: &Value) -> Option<String> {
+    let task_id = value.get("task_id")?.as_str()?;
+    match file {
+        "token_costs.jsonl" if is_income(value) => Some(format!("income:{task_id}")),
+        "task_completions.jsonl" => Some(format!("task:{task_id}")),
+        _ => None,
+    }
+}
+
+fn is_income(value: &Value) -> bool {
+    value.get("actual_payment").is_some()
+}
+
+fn payment(value: &Value) -> Option<f64> {
+    value.get("actual_payment")?.as_f64()
+}
+
+/// SHA-256 of the record's canonical JSON (keys sorted), so formatting and
+/// field order do not affect duplicate detection.
+fn record_hash(value: &Value) -> String {
+    hex::encode(Sha256::digest(value.to_string().as_bytes()))
+}
+
+fn read_lines(
+    path: &Path,
+    from_primary: bool,
+    file: &str,
+    report: &mut MergeReport,
+) -> Result<Vec<MergeLine>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let reader = BufReader::new(File::open(path).at_path(path)?);
+
+    let mut lines = Vec::new();
+    for (number, line) in reader.lines().enumerate() {
+        let raw = line.at_path(path)?;
+        if raw.trim().is_empty() {
+            continue;
+        }
+        let Ok(value) = serde_json::from_str::<Value>(&raw) else {
+            report.conflicts.push(MergeConflict {
+                file: file.to_string(),
+                key: format!("{}:{}", path.display(), number + 1),
+                detail: "undecodable line skipped".to_string(),
+            });
+            continue;
+        };
+        let time = value
+            .get("timestamp_end")
+            .or_else(|| value.get("timestamp"))
+            .and_then(Value::as_str)
+            .and_then(|time| time.parse().ok());
+        lines.push(MergeLine {
+            raw,
+            value,
+            time,
+            from_primary,
+        });
+    }
+    Ok(lines)
+}
+
+fn write_lines<'a>(path: &Path, lines: impl Iterator<Item = &'a str>) -> Result<()> {
+    let mut lines = lines.peekable();
+    if lines.peek().is_none() {
+        return Ok(());
+    }
+    let write = || -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        for line in lines {
+            writeln!(file, "{line}")?;
+        }
+        file.sync_all()
+    };
+    write().at_path(path)
+}
+
+/// Balance changes on one day of the merged history.
+#[derive(Default)]
+struct DayTotals {
+    token_cost: f64,
+    work_income: f64,
+    trading_profit: f64,
+    refunds: f64,
+    grant_income: f64,
+    interest_earned: f64,
+    interest_paid: f64,
+    /// Net of transfers received and sent
+    transfers: f64,
+    completed_tasks: Vec<String>,
+    last_change: Option<DateTime<Utc>>,
+}
+
+impl DayTotals {
+    fn touch(&mut self, time: DateTime<Utc>) {
+        self.last_change = self.last_change.max(Some(time));
+    }
+}
+
+/// Rebuild the balance snapshots of a single data directory from its event
+/// logs, as `merge_data_dirs` does for a merged one.
+///
+/// `events` holds the records of every log in `EVENT_LOGS` in time order;
+/// `snapshots` holds the existing snapshots, the first of which supplies the
+/// opening balance. Balance changes the snapshots recorded but the event
+/// logs cannot explain, such as costs tracked outside a task, are carried
+/// over as of the end of the day they were recorded. Returns `None` when
+/// there is no snapshot.
+pub(crate) fn rebuild_balance(
+    events: &HashMap<&str, Vec<Value>>,
+    snapshots: &[BalanceRecord],
+) -> Option<Vec<BalanceRecord>> {
+    let opening = snapshots.first()?;
+    let mut report = MergeReport::default();
+    let rebuilt = rebuild_snapshots(opening, snapshots, &[], events, &mut report);
+    Some(carry_unexplained(rebuilt, snapshots))
+}
+
+/// Shift `rebuilt` by the difference between each day's last recorded
+/// snapshot and the rebuilt balance for that day, adding a checkpoint for
+/// days that changed the balance without any logged event.
+fn carry_unexplained(
+    rebuilt: Vec<BalanceRecord>,
+    recorded: &[BalanceRecord],
+) -> Vec<BalanceRecord> {
+    let recorded: BTreeMap<NaiveDate, &BalanceRecord> = recorded
+        .iter()
+        .filter(|snapshot| snapshot.date != "initialization" && snapshot.date != IMPORTED_DATE)
+        .filter_map(|snapshot| {
+            let day = match snapshot.timestamp {
+                Some(time) => time.date_naive(),
+                None => snapshot.date.parse().ok()?,
+            };
+            Some((day, snapshot))
+        })
+        .collect();
+    let mut rebuilt = rebuilt.into_iter();
+    let Some(opening) = rebuilt.next() else {
+        return Vec::new();
+    };
+    let initial_balance = opening.balance;
+    let mut replayed: BTreeMap<NaiveDate, BalanceRecord> = rebuilt
+        .filter_map(|snapshot| Some((snapshot.date.parse().ok()?, snapshot)))
+        .collect();
+    let days: BTreeSet<NaiveDate> = recorded.keys().chain(replayed.keys()).copied().collect();
+
+    let mut explained = opening.clone();
+    let mut snapshots = vec![opening];
+    let mut unexplained = 0.0;
+    for day in days {
+        let replayed_day = replayed.remove(&day);
+        if let Some(snapshot) = &replayed_day {
+            explained = snapshot.clone();
+        }
+        let previous = unexplained;
+        if let Some(snapshot) = recorded.get(&day) {
+            unexplained = snapshot.balance - explained.balance;
+        }
+        let mut snapshot = match replayed_day {
+            Some(snapshot) => snapshot,
+            None if (unexplained - previous).abs() > 1e-9 => BalanceRecord {
+                date: day.to_string(),
+                token_cost_delta: 0.0,
+                work_income_delta: 0.0,
+                trading_profit_delta: 0.0,
+                completed_tasks: Vec::new(),
+                checkpoint: true,
+                timestamp: recorded.get(&day).and_then(|snapshot| snapshot.timestamp),
+                ..explained.clone()
+            },
+            None => continue,
+        };
+        snapshot.balance += unexplained;
+        snapshot.net_worth = snapshot.balance;
+        snapshot.survival_status =
+            SurvivalStatus::from_balance(snapshot.balance, initial_balance).to_string();
+        snapshots.push(snapshot);
+    }
+    snapshots
+}
+
+/// One end-of-day snapshot per day with activity, after an initialization
+/// snapshot carrying the balance of `opening`.
+///
+/// Charges, cost corrections, income, grants, refunds, interest, and
+/// transfers come from the merged logs. Trading P&L is only recorded in
+/// daily snapshots, so it is taken from those (primary first); costs
+/// tracked outside a task are not recoverable.
+fn rebuild_snapshots(
+    opening: &BalanceRecord,
+    primary_snapshots: &[BalanceRecord],
+    secondary_snapshots: &[BalanceRecord],
+    merged: &HashMap<&str, Vec<Value>>,
+    report: &mut MergeReport,
+) -> Vec<BalanceRecord> {
+    let mut days: BTreeMap<NaiveDate, DayTotals> = BTreeMap::new();
+    for value in &merged["token_costs.jsonl"] {
+        let Ok(record) = serde_json::from_value::<CostLogRecord>(value.clone()) else {
+            continue;
+        };
+        match record {
+            CostLogRecord::Task(task) => {
+                let day = days.entry(task.timestamp_end.date_naive()).or_default();
+                day.token_cost += task.cost_summary.total();
+                day.completed_tasks.push(task.task_id.clone());
+                day.touch(task.timestamp_end);
+            }
+            CostLogRecord::Income(income) => {
+                let day = days.entry(income.timestamp.date_naive()).or_default();
+                day.work_income += income.actual_payment;
+                day.touch(income.timestamp);
+            }
+        }
+    }
+    for value in &merged["refunds.jsonl"] {
+        if let Ok(refund) = serde_json::from_value::<RefundRecord>(value.clone()) {
+            let day = days.entry(refund.timestamp.date_naive()).or_default();
+            day.refunds += refund.amount();
+            day.touch(refund.timestamp);
+        }
+    }
+    for value in &merged["grant_income.jsonl"] {
+        if let Ok(grant) = serde_json::from_value::<GrantIncomeRecord>(value.clone()) {
+            let day = days.entry(grant.timestamp.date_naive()).or_default();
+            day.grant_income += grant.amount;
+            day.touch(grant.timestamp);
+        }
+    }
+    for value in &merged["interest.jsonl"] {
+        if let Ok(interest) = serde_json::from_value::<InterestRecord>(value.clone()) {
+            let day = days.entry(interest.timestamp.date_naive()).or_default();
+            match interest.kind {
+                InterestKind::Earned => day.interest_earned += interest.amount,
+                InterestKind::Paid => day.interest_paid += interest.amount,
+            }
+            day.touch(interest.timestamp);
+        }
+    }
+    for value in &merged["transfers.jsonl"] {
+        if let Ok(transfer) = serde_json::from_value::<TransferRecord>(value.clone()) {
+            let day = days.entry(transfer.timestamp.date_naive()).or_default();
+            day.transfers += transfer.delta();
+            day.touch(transfer.timestamp);
+        }
+    }
+    for value in &merged["cost_corrections.jsonl"] {
+        if let Ok(correction) = serde_json::from_value::<CostCorrectionRecord>(value.clone()) {
+            let day = days.entry(correction.timestamp.date_naive()).or_default();
+            day.token_cost += correction.delta;
+            day.touch(correction.timestamp);
+        }
+    }
+
+    let primary_daily = daily_snapshots(primary_snapshots);
+    let secondary_daily = daily_snapshots(secondary_snapshots);
+    for (date, secondary_day) in &secondary_daily {
+        if let Some(primary_day) = primary_daily.get(date) {
+            if (primary_day.balance - secondary_day.balance).abs() > 1e-9
+                || (primary_day.trading_profit_delta - secondary_day.trading_profit_delta).abs()
+                    > 1e-9
+            {
+                report.conflicts.push(MergeConflict {
+                    file: BALANCE_LOG.to_string(),
+                    key: date.to_string(),
+                    detail: format!(
+                        "snapshots differ (balance {} vs {}, trading P&L {} vs {}); \
+                         used primary trading P&L",
+                        primary_day.balance,
+                        secondary_day.balance,
+                        primary_day.trading_profit_delta,
+                        secondary_day.trading_profit_delta
+                    ),
+                });
+            }
+        }
+    }
+    for (date, snapshot) in secondary_daily.iter().chain(&primary_daily) {
+        if let Ok(date) = date.parse::<NaiveDate>() {
+            // Primary is applied last, so it wins for dates both recorded
+            days.entry(date).or_default().trading_profit = snapshot.trading_profit_delta;
+        }
+    }
+
+    let initial_balance = opening.balance;
+    let mut snapshots = vec![BalanceRecord {
+        date: "initialization".to_string(),
+        balance: initial_balance,
+        token_cost_delta: 0.0,
+        work_income_delta: 0.0,
+        trading_profit_delta: 0.0,
+        total_token_cost: 0.0,
+        total_work_income: 0.0,
+        total_trading_profit: 0.0,
+        net_worth: initial_balance,
+        survival_status: SurvivalStatus::from_balance(initial_balance, initial_balance)
+            .to_string(),
+        completed_tasks: Vec::new(),
+        task_id: None,
+        task_completion_time_seconds: None,
+        api_error: false,
+        total_refunds: 0.0,
+        total_grant_income: 0.0,
+        total_interest_earned: 0.0,
+        total_interest_paid: 0.0,
+        grace_spent: 0.0,
+        checkpoint: false,
+        timestamp: opening.timestamp,
+        unlogged_changes: Vec::new(),
+    }];
+
+    let (mut token_cost, mut work_income, mut trading_profit) = (0.0, 0.0, 0.0);
+    let (mut refunds, mut grant_income) = (0.0, 0.0);
+    let (mut interest_earned, mut interest_paid) = (0.0, 0.0);
+    let mut transfers = 0.0;
+    for (date, day) in days {
+        token_cost += day.token_cost;
+        work_income += day.work_income;
+        trading_profit += day.trading_profit;
+        refunds += day.refunds;
+        grant_income += day.grant_income;
+        interest_earned += day.interest_earned;
+        interest_paid += day.interest_paid;
+        transfers += day.transfers;
+        let balance = initial_balance - token_cost + work_income + trading_profit
+            + refunds
+            + grant_income
+            + interest_earned
+            - interest_paid
+            + transfers;
+        let end_of_day = date.and_hms_opt(23, 59, 59).map(|time| time.and_utc());
+
+        snapshots.push(BalanceRecord {
+            date: date.to_string(),
+            balance,
+            token_cost_delta: day.token_cost,
+            work_income_delta: day.work_income,
+            trading_profit_delta: day.trading_profit,
+            total_token_cost: token_cost,
+            total_work_income: work_income,
+            total_trading_profit: trading_profit,
+            net_worth: balance,
+            survival_status: SurvivalStatus::from_balance(balance, initial_balance).to_string(),
+            completed_tasks: day.completed_tasks,
+            task_id: None,
+            task_completion_time_seconds: None,
+            api_error: false,
+            total_refunds: refunds,
+            total_grant_income: grant_income,
+            total_interest_earned: interest_earned,
+            total_interest_paid: interest_paid,
+            grace_spent: 0.0,
+            checkpoint: false,
+            timestamp: day.last_change.or(end_of_day),
+            unlogged_changes: Vec::new(),
+        });
+    }
+
+    snapshots
+}
+
+fn read_snapshots(path: &Path) -> Result<Vec<BalanceRecord>> {
+    let mut snapshots = Vec::new();
+    if !path.exists() {
+        return Ok(snapshots);
+    }
+    for snapshot in RecordReader::<BalanceRecord>::open(path)? {
+        match snapshot {
+            Ok(snapshot) => snapshots.push(snapshot),
+            Err(EconomicError::MalformedRecord { .. }) => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(snapshots)
+}
+
+/// End-of-day snapshots by date (the last one written for each date).
+fn daily_snapshots(snapshots: &[BalanceRecord]) -> BTreeMap<String, &BalanceRecord> {
+    snapshots
+        .iter()
+        .filter(|snapshot| {
+            !snapshot.checkpoint
+                && snapshot.date != "initialization"
+                && snapshot.date != IMPORTED_DATE
+        })
+        .map(|snapshot| (snapshot.date.clone(), snapshot))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{EconomicConfig, EconomicTracker};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn tracker(dir: &Path) -> EconomicTracker {
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(dir.to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker
+    }
+
+    fn line_count(path: &Path) -> usize {
+        fs::read_to_string(path).unwrap().lines().count()
+    }
+
+    #[test]
+    fn merge_deduplicates_and_rebuilds_balance() {
+        let primary = TempDir::new().unwrap();
+        let secondary = TempDir::new().unwrap();
+        let output = TempDir::new().unwrap();
+
+        // Primary: task-1 costs 10 and earns 20
+        let first = tracker(primary.path());
+        first.start_task("task-1", None, &[]).unwrap();
+        first.track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO).unwrap();
+        first.end_task("task-1").unwrap();
+        first.add_work_income(20.0, "task-1", 0.9, "").unwrap();
+
+        // The secondary directory starts as a copy of the primary
+        for file in ["balance.jsonl", "token_costs.jsonl", "task_completions.jsonl"] {
+            fs::copy(primary.path().join(file), secondary.path().join(file)).unwrap();
+        }
+        let second = tracker(secondary.path());
+        second.start_task("task-2", None, &[]).unwrap();
+        second.track_tokens(1000, 500, "agent", Some(5.0), Duration::ZERO).unwrap();
+        second.end_task("task-2").unwrap();
+        second.add_work_income(30.0, "task-2", 0.9, "").unwrap();
+        // Paid differently on each machine
+        second.add_work_income(15.0, "task-3", 0.9, "").unwrap();
+        first.add_work_income(25.0, "task-3", 0.9, "").unwrap();
+        drop(first);
+        drop(second);
+
+        // Re-delivered payment for task-1, which the tracker itself refuses
+        let costs_path = secondary.path().join("token_costs.jsonl");
+        let contents = fs::read_to_string(&costs_path).unwrap();
+        let mut retry: Value = contents
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .find(|value| value["task_id"] == "task-1" && value.get("actual_payment").is_some())
+            .unwrap();
+        retry["description"] = "retry".into();
+        fs::write(&costs_path, format!("{contents}{retry}\n")).unwrap();
+
+        let report =
+            merge_data_dirs(primary.path(), secondary.path(), output.path()).unwrap();
+
+        // Copied cost record, income record, and completion record
+        assert_eq!(report.duplicates_removed, 3);
+        assert_eq!(report.income_duplicates_removed, 1);
+        assert_eq!(report.conflicts.len(), 1);
+        assert_eq!(report.conflicts[0].key, "income:task-3");
+        // 2 task cost records, 3 income records, 2 completions
+        assert_eq!(report.records_written, 7);
+        assert_eq!(line_count(&output.path().join("token_costs.jsonl")), 5);
+
+        // 100 - 10 + 20 - 5 + 30 + 25 (primary's task-3 payment)
+        assert!((report.final_balance - 160.0).abs() < 1e-9);
+        let merged = tracker(output.path());
+        assert!((merged.get_balance() - 160.0).abs() < 1e-9);
+        assert!((merged.balance_at(Utc::now()).unwrap() - 160.0).abs() < 1e-9);
+
+        // The output must be empty
+        assert!(merge_data_dirs(primary.path(), secondary.path(), output.path()).is_err());
+    }
+}
@@ -10,12 +10,15 @@
 //! The economic system models agent viability:
 //! - **Balance**: Starting capital minus costs plus earned income
 //! - **Costs**: LLM tokens, search APIs, OCR, and other service usage
-//! - **Income**: Payments for completed tasks (with quality threshold)
+//! - **Income**: Payments for completed tasks (with quality threshold and
+//!   optional custom validators); `complete_and_evaluate` scores the
+//!   deliverable with a pluggable `QualityEvaluator`
 //! - **Status**: Health indicator based on remaining capital percentage
 //!
 //! ## Example
 //!
 //! ```rust,ignore
+//! use std::time::Duration;
 //! use zeroclaw::economic::{EconomicTracker, EconomicConfig, SurvivalStatus};
 //!
 //! let config = EconomicConfig {
@@ -28,13 +31,17 @@
 //! tracker.initialize()?;
 //!
 //! // Start a task
-//! tracker.start_task("task-001", None);
+//! tracker.start_task("task-001", None, &["client:acme"])?;
 //!
-//! // Track LLM usage
-//! let cost = tracker.track_tokens(1000, 500, "agent", None);
+//! // Track LLM usage, with how long the call took
+//! let charge = tracker.track_tokens(1000, 500, "agent", None, Duration::from_millis(850))?;
+//! if charge.grace_warning {
+//!     println!("running on the bankruptcy grace budget");
+//! }
 //!
 //! // Complete task and earn income
-//! tracker.end_task()?;
+//! let summary = tracker.end_task("task-001")?;
+//! println!("task cost: ${:.4}", summary.total);
 //! let payment = tracker.add_work_income(10.0, "task-001", 0.85, "Completed task")?;
 //!
 //! // Check survival status
@@ -45,12 +52,160 @@
 //! }
 //! ```
 //!
+//! `EconomicTracker::get_summary` returns an `EconomicSummary` report of
+//! in-memory state with alerts; `render_text` prints it for the terminal.
+//! Pass `SummaryOptions` to `get_summary_with` to add the burn rate, top cost
+//! drivers, and weekly income, which read the logs, and use
+//! `EconomicSummary::diff` to compare two reports. For arbitrary periods,
+//! `cost_summary`, `income_summary`, and `task_summary` take a half-open
+//! `DateRange`.
+//!
+//! Fallible operations return `EconomicError` (see [`error::Result`]), which
+//! callers can match on or convert into `anyhow::Error` with `?`.
+//!
 //! ## Persistence
 //!
 //! Economic state is persisted to JSONL files:
-//! - `balance.jsonl`: Daily balance snapshots and cumulative totals
+//! - `balance.jsonl`: Daily balance snapshots and cumulative totals, plus
+//!   checkpoints written by `flush` (also run on drop)
 //! - `token_costs.jsonl`: Detailed per-task cost records
 //! - `task_completions.jsonl`: Task completion statistics
+//! - `refunds.jsonl`: Refunds and billing corrections
+//! - `grant_income.jsonl`: Grant income not tied to a task
+//! - `interest.jsonl`: Daily interest earned or paid on the balance
+//! - `intake.jsonl`: Task intake pauses and resumes (see `IntakePolicy`)
+//! - `grace.jsonl`: Bankruptcy grace period transitions (see `GracePolicy`)
+//! - `escrow.jsonl`: Work income held after a failed quality evaluation
+//! - `cost_corrections.jsonl`: Manual corrections of task costs to the bill
+//! - `overhead.jsonl`: Shared costs attributed to tasks or written off (see
+//!   `ProrationStrategy`)
+//!
+//! Each record is synced to disk as it is logged. `[economic.persistence]`
+//! (`PersistencePolicy`) can batch writes instead, holding records in memory
+//! until a count, size, or age threshold, `flush`, or drop; the tracker's
+//! own reads include records still held.
+//!
+//! With the `compress` feature, `EconomicTracker::drain_to_archive` moves old
+//! records into gzip archives under `archive/` to keep the active files small.
+//! Date-range queries (`cost_summary`, `income_summary`, `task_summary`, and
+//! `get_analytics_for`) also read the archives their range reaches into when
+//! the default `archive-read` feature is on; a build without it fails them
+//! with `EconomicError::ArchiveReadDisabled` instead (see [`logs`]).
+//! `EconomicTracker::apply_retention` does the same on the `[economic.retention]`
+//! schedule, first summarizing expiring records into monthly totals in
+//! `rollups.jsonl`.
+//!
+//! With `record_integrity` enabled, each appended record is chained to the
+//! previous one by a SHA-256 hash, and `EconomicTracker::verify_integrity`
+//! reports the first hand-edited, removed, or inserted record per file.
+//!
+//! `repair::repair_data_dir` recovers a data directory damaged by a crash or
+//! full disk: it sets undecodable lines aside, drops duplicates, re-sorts
+//! records, and rebuilds `balance.jsonl` (also `zeroclaw economic repair`).
+//!
+//! `fleet::FleetReport::from_dirs` loads the summaries of several agents'
+//! data directories into a leaderboard ranked by a `FleetMetric`, skipping
+//! directories that fail to load.
+//!
+//! `RecordQuery` filters the LLM calls, API calls, income, and refunds of a
+//! data directory by date, model, provider, task, tag, metadata, amount, and
+//! kind, streaming them as `EconomicRecord`s or exporting them as CSV (also
+//! `zeroclaw economic query`).
+//!
+//! `EconomicTracker::export_snapshot` condenses the state into a single JSON
+//! file that `import_snapshot` uses to seed a data directory on another host.
+//!
+//! `EconomicAnalytics::cost_distribution` reports cost percentiles per task,
+//! LLM call, and day, with 7- and 30-day moving averages of spend and income.
+//!
+//! `EconomicTracker::prorate_shared_cost` attributes costs incurred between
+//! tasks, such as start-up, to completed tasks or the next task, or writes
+//! them off as overhead (see `ProrationStrategy`).
+//!
+//! `EconomicTracker::get_average_task_duration`, `get_median_task_duration`,
+//! `get_p95_task_duration`, and `get_task_duration_histogram` summarize how
+//! long ended tasks ran; `get_income_per_hour_worked` and
+//! `get_profit_per_hour` divide work income, and income net of token costs,
+//! by their combined duration. `get_longest_task`, `get_most_expensive_task`
+//! and `get_top_n_expensive_tasks` pick out the outliers, active tasks
+//! included.
+//!
+//! `EconomicTracker::get_break_even_task_count` reports how many tasks, at
+//! the average profit per ended task, earn back `fixed_overhead_usd`.
+//!
+//! `EconomicTracker::set_task_category` records the `OccupationCategory` a
+//! task was classified under, and `get_category_performance_summary` reports
+//! cost, income, quality, and success rate per category.
+//!
+//! `EconomicTracker::get_income_quality_correlation` and
+//! `get_income_quality_regression` measure how closely payments follow
+//! evaluation scores across the work income log.
+//! `get_income_retention_rate` is the share of offered income actually paid,
+//! and `get_rejected_income_total` the amount withheld by the quality gates.
+//!
+//! `EconomicTracker::detect_cost_anomalies` flags tasks whose cost is an
+//! outlier by Z-score, as a sign of a misbehaving agent.
+//!
+//! `EconomicTracker::start_task_with_budget` records the cost a task is
+//! expected to stay within (e.g. a classification's `max_payment`);
+//! `get_tasks_over_budget` lists the tasks that exceeded it.
+//!
+//! `EconomicTracker::set_model_pricing` prices calls that name a model from
+//! the same `cost::SharedPricing` a `CostObserver` uses, so both report the
+//! same cost for a call.
+//!
+//! With `llm_usage_source = "observer"`, LLM usage is recorded from
+//! `LlmResponse` events through `observability::EconomicObserver` instead of
+//! explicit `track_tokens` calls, which are then ignored so no call is
+//! charged twice.
+//!
+//! `EconomicTracker::can_afford` checks a `cost::CostEstimate` for a request
+//! against the balance, `reserve_floor`, the daily limit, and the task budget
+//! before the request is sent.
+//!
+//! `EconomicTracker::cost_sensitivity_analysis` replays recorded LLM calls
+//! at new per-model prices to estimate the effect of a pricing change, and
+//! `simulate_pricing_model` compares their recorded cost with alternative
+//! token prices, per task.
+//!
+//! `EconomicTracker::get_cost_per_successful_output_token` and
+//! `get_output_efficiency_by_model` measure LLM spend against the output it
+//! produced, overall and per model.
+//!
+//! `EconomicTracker::set_model_context_window` registers context window
+//! sizes, and `get_context_utilization` reports how much of them calls fill
+//! with input tokens, per model.
+//!
+//! `EconomicTracker::get_working_capital` nets the balance against escrowed
+//! income, the unspent budgets of active tasks, and subscriptions registered
+//! with `add_subscription` that fall due within a day.
+//!
+//! `EconomicTracker::set_income_goal` sets an income target with a deadline;
+//! `get_goal_progress` reports the income received toward it and whether the
+//! rate so far meets it.
+//!
+//! `EconomicTracker::balance_at` reconstructs the balance at a past time by
+//! replaying the records after the nearest earlier snapshot;
+//! `get_balance_at_time` does the same for a `SystemTime` and rejects times
+//! before the tracker's first session.
+//!
+//! `EconomicTracker::watch_survival_status` streams survival status changes,
+//! so monitoring code can react instead of polling `get_survival_status`;
+//! callbacks registered with `on_status_change` run synchronously instead.
+//! `subscribe_cost_events` broadcasts every charge and credit as a
+//! `CostEvent` alongside status changes, buffering up to
+//! `event_buffer_size` events per receiver.
+//!
+//! `start_task_with_metadata` and `add_work_income_with_metadata` attach a
+//! string map (client ids, ticket numbers) to task and income records, up to
+//! `MAX_METADATA_KEYS` entries and `MAX_METADATA_BYTES`;
+//! `EconomicAnalytics::filter_by_metadata` selects tasks by one entry.
+//!
+//! `EconomicTracker::transfer_balance` pays one agent's tracker from
+//! another's, logging a `TransferRecord` with a shared id on both sides.
+//!
+//! `EconomicTracker::watch_pricing_file` reloads token prices from a JSON
+//! file when it changes, so long-running agents pick up new prices.
 //!
 //! ## Configuration
 //!
@@ -67,19 +222,83 @@
 //! output_price_per_million = 15.0
 //! ```
 
+pub mod accounting;
+#[cfg(feature = "compress")]
+pub mod archive;
 pub mod classifier;
 pub mod costs;
+pub mod distribution;
+pub mod error;
+pub mod evaluation;
+pub mod fleet;
+pub mod forecast;
+pub mod goal;
+pub mod grace;
+pub mod history;
+pub mod intake;
+pub mod integrity;
+pub mod logs;
+pub mod merge;
+pub mod persistence;
+pub mod range;
+pub mod repair;
+pub mod retention;
+pub mod snapshot;
 pub mod status;
+pub mod summary;
 pub mod tracker;
+pub mod validation;
 
 // Re-exports for convenient access
+pub use accounting::{AccountingFormat, LedgerEntry};
 pub use costs::{
-    ApiCallRecord, ApiUsageSummary, BalanceRecord, CostBreakdown, DateCostSummary,
-    EconomicAnalytics, LlmCallRecord, LlmUsageSummary, PricingModel, TaskCompletionRecord,
-    TaskCostRecord, TaskCostSummary, TokenPricing, WorkIncomeRecord,
+    AffordabilityDecision, AnomalySeverity, ApiCallRecord, ApiUsageSummary, BalanceChange,
+    BalanceRecord, BreakEvenAnalysis, CategoryPerformance, Charge, CostAnomaly, CostBreakdown,
+    CostCorrectionRecord, DateCostSummary, EconomicAnalytics, EconomicRecord, GrantIncomeRecord,
+    HourRange, ImagePricing, InterestKind, InterestRecord, LlmCallRecord, LlmUsageEntry,
+    LlmUsageSource, LlmUsageSummary, ModelCostEntry, ModelTokenUsage, ObservedApiCall,
+    ObservedLlmCall, OverBudgetTask, OverheadRecord, PricingModel, PricingSimulationResult,
+    PromptType, ProrationStrategy, QueryResults, RecordKind, RecordQuery, RecordReader,
+    RefundRecord, ResumeToken, SensitivityReport, SpendingLimit, TagSummary, TaskAbortReason,
+    TaskCompletionRecord, TaskCostRecord, TaskCostSummary, TaskStatus, TimeOfUsePricing,
+    TokenContext, TokenPricing, TransferDirection, TransferRecord, UsageBreakdown,
+    WorkIncomeRecord, MAX_METADATA_BYTES, MAX_METADATA_KEYS,
+};
+// Shared with the observability layer, which reports image generation
+pub use crate::cost::ImageSizeClass;
+#[cfg(feature = "compress")]
+pub use archive::ArchiveSummary;
+pub use distribution::{CostDistribution, DurationBucket, MovingAveragePoint, Percentiles};
+pub use error::EconomicError;
+pub use evaluation::{
+    EscrowEventKind, EscrowRecord, EvaluationOutcome, EvaluatorUsage, LlmJudgeEvaluator,
+    PassthroughEvaluator, QualityEvaluator, QualityScore,
+};
+pub use fleet::{FleetEntry, FleetFailure, FleetMetric, FleetReport};
+pub use forecast::{
+    ForecastDay, MonthlyProjection, ProjectionConfidence, SpendForecast, StatusTransition,
+};
+pub use goal::{GoalProgress, IncomeGoal};
+pub use grace::{GraceEvent, GraceEventKind, GracePolicy, OperationalState};
+pub use history::BalanceGranularity;
+pub use intake::{IntakeEvent, IntakePolicy, WorkAdmission};
+pub use integrity::{BrokenLink, BrokenLinkKind, FileIntegrity, IntegrityReport};
+pub use merge::{MergeConflict, MergeReport};
+pub use persistence::PersistencePolicy;
+pub use range::{DateRange, RangeCostSummary, RangeIncomeSummary, RangeTaskSummary};
+pub use repair::{FileRepair, RepairOptions, RepairReport};
+pub use retention::{MonthlyRollup, RetentionPolicy};
+#[cfg(feature = "compress")]
+pub use retention::{RetentionReport, RetentionRunner};
+pub use snapshot::EconomicSnapshot;
+pub use status::{CostEvent, StatusChangeCallback, SurvivalStatus, SurvivalStatusChange};
+pub use summary::{
+    BurnRate, CostDriver, CostDrivers, EconomicSummary, EconomicSummaryDiff, SummaryOptions,
+    SummarySection, WorkingCapital,
 };
-pub use status::SurvivalStatus;
-pub use tracker::{EconomicConfig, EconomicSummary, EconomicTracker};
+pub use tracker::{BankruptcyCallback, EconomicConfig, EconomicTracker, IntakeCallback};
+pub use validation::{IncomeValidator, ValidationResult, WorkIncomeCandidate};
 pub use classifier::{
-    ClassificationResult, Occupation, OccupationCategory, TaskClassifier,
+    CalibrationResult, CatalogFormat, ClassificationResult, Occupation, OccupationCategory,
+    SuggestedKeywords, TaskClassifier, UrgencyLevel,
 };
@@ -0,0 +1,246 @@
+//! Batched JSONL writes for the economic tracker.
+//!
+//! By default every record is appended and synced to disk as it is logged,
+//! so a crash loses nothing that was reported as recorded. Busy agents can
+//! trade that for fewer syscalls: with `max_buffered_records` above one, the
+//! tracker holds records in a [`WriteBuffer`] and writes each file's share
+//! in a single append and sync once a record, byte, or age threshold is hit.
+//! Records still buffered when the process dies are lost.
+
+use super::error::{IoResultExt, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// When buffered records are written to disk.
+///
+/// In `config.toml`:
+///
+/// ```toml
+/// [economic.persistence]
+/// max_buffered_records = 100
+/// max_buffered_bytes = 65536
+/// flush_interval_ms = 1000
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PersistencePolicy {
+    /// Records held before they are written (1 writes each record as it is
+    /// logged)
+    #[serde(default = "default_max_buffered_records")]
+    pub max_buffered_records: usize,
+    /// Bytes held before the buffer is written (0 for no byte limit)
+    #[serde(default = "default_max_buffered_bytes")]
+    pub max_buffered_bytes: usize,
+    /// Longest a record is held, in milliseconds (0 for no age limit);
+    /// checked whenever a record is logged
+    #[serde(default = "default_flush_interval_ms")]
+    pub flush_interval_ms: u64,
+}
+
+fn default_max_buffered_records() -> usize {
+    1
+}
+
+fn default_max_buffered_bytes() -> usize {
+    64 * 1024
+}
+
+fn default_flush_interval_ms() -> u64 {
+    1000
+}
+
+impl Default for PersistencePolicy {
+    fn default() -> Self {
+        Self {
+            max_buffered_records: default_max_buffered_records(),
+            max_buffered_bytes: default_max_buffered_bytes(),
+            flush_interval_ms: default_flush_interval_ms(),
+        }
+    }
+}
+
+impl PersistencePolicy {
+    /// Whether every record is written as it is logged.
+    pub fn is_write_through(&self) -> bool {
+        self.max_buffered_records <= 1
+    }
+}
+
+/// Serialized records waiting to be appended, in the order they were logged.
+#[derive(Debug)]
+pub(crate) struct WriteBuffer {
+    policy: PersistencePolicy,
+    pending: Vec<(PathBuf, String)>,
+    bytes: usize,
+    oldest: Option<Instant>,
+}
+
+impl WriteBuffer {
+    pub(crate) fn new(policy: PersistencePolicy) -> Self {
+        Self {
+            policy,
+            pending: Vec::new(),
+            bytes: 0,
+            oldest: None,
+        }
+    }
+
+    /// Queue `line` for `path`, writing the buffer out if a threshold is
+    /// reached.
+    pub(crate) fn push(&mut self, path: &Path, line: String) -> Result<()> {
+        self.bytes += line.len() + 1;
+        self.oldest.get_or_insert_with(Instant::now);
+        self.pending.push((path.to_path_buf(), line));
+        if self.is_due() {
+            self.drain()?;
+        }
+        Ok(())
+    }
+
+    /// Whether a record, byte, or age threshold has been reached.
+    fn is_due(&self) -> bool {
+        let policy = &self.policy;
+        let max_age = Duration::from_millis(policy.flush_interval_ms);
+        self.pending.len() >= policy.max_buffered_records
+            || (policy.max_buffered_bytes > 0 && self.bytes >= policy.max_buffered_bytes)
+            || (policy.flush_interval_ms > 0
+                && self
+                    .oldest
+                    .is_some_and(|oldest| oldest.elapsed() >= max_age))
+    }
+
+    /// Visit the buffered records for `path` that decode as `T`, oldest
+    /// first.
+    pub(crate) fn for_each_pending<T: DeserializeOwned>(
+        &self,
+        path: &Path,
+        mut on_record: impl FnMut(T),
+    ) {
+        for line in self.lines_for(path) {
+            if let Ok(record) = serde_json::from_str(line) {
+                on_record(record);
+            }
+        }
+    }
+
+    /// Buffered lines for `path`, oldest first.
+    pub(crate) fn lines_for<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = &'a str> {
+        self.pending
+            .iter()
+            .filter(move |(pending, _)| pending == path)
+            .map(|(_, line)| line.as_str())
+    }
+
+    /// Append every buffered line to its file, with one write and sync per
+    /// file. Files are written in the order their first record was logged.
+    ///
+    /// On failure, the lines of the failed file and of files not yet written
+    /// stay buffered.
+    pub(crate) fn drain(&mut self) -> Result<()> {
+        let mut files: Vec<(PathBuf, String)> = Vec::new();
+        for (path, line) in self.pending.drain(..) {
+            let index = match files.iter().position(|(file, _)| *file == path) {
+                Some(index) => index,
+                None => {
+                    files.push((path, String::new()));
+                    files.len() - 1
+                }
+            };
+            let batch = &mut files[index].1;
+            batch.push_str(&line);
+            batch.push('\n');
+        }
+        self.bytes = 0;
+        self.oldest = None;
+
+        for (index, (path, batch)) in files.iter().enumerate() {
+            if let Err(err) = append_batch(path, batch) {
+                for (path, batch) in &files[index..] {
+                    for line in batch.lines() {
+                        self.bytes += line.len() + 1;
+                        self.pending.push((path.clone(), line.to_string()));
+                    }
+                }
+                self.oldest = Some(Instant::now());
+                return Err(err);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Append `batch` (newline-terminated lines) to `path` and sync it.
+fn append_batch(path: &Path, batch: &str) -> Result<()> {
+    let append = || -> std::io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(batch.as_bytes())?;
+        file.sync_all()
+    };
+    append().at_path(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn buffer_writes_per_file_once_a_threshold_is_hit() {
+        let tmp = TempDir::new().unwrap();
+        let (costs, grants) = (
+            tmp.path().join("costs.jsonl"),
+            tmp.path().join("grants.jsonl"),
+        );
+        let mut buffer = WriteBuffer::new(PersistencePolicy {
+            max_buffered_records: 3,
+            max_buffered_bytes: 0,
+            flush_interval_ms: 0,
+        });
+
+        buffer.push(&costs, "1".to_string()).unwrap();
+        buffer.push(&grants, "2".to_string()).unwrap();
+        assert!(!costs.exists());
+        assert_eq!(buffer.lines_for(&costs).collect::<Vec<_>>(), ["1"]);
+
+        buffer.push(&costs, "3".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&costs).unwrap(), "1\n3\n");
+        assert_eq!(fs::read_to_string(&grants).unwrap(), "2\n");
+        assert_eq!(buffer.lines_for(&costs).count(), 0);
+
+        // A byte limit flushes before the record count is reached
+        let mut buffer = WriteBuffer::new(PersistencePolicy {
+            max_buffered_records: 100,
+            max_buffered_bytes: 8,
+            flush_interval_ms: 0,
+        });
+        buffer.push(&grants, "4444".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&grants).unwrap(), "2\n");
+        buffer.push(&grants, "5555".to_string()).unwrap();
+        assert_eq!(fs::read_to_string(&grants).unwrap(), "2\n4444\n5555\n");
+    }
+
+    #[test]
+    fn failed_files_stay_buffered() {
+        let tmp = TempDir::new().unwrap();
+        let good = tmp.path().join("good.jsonl");
+        let missing_dir = tmp.path().join("missing").join("bad.jsonl");
+        let mut buffer = WriteBuffer::new(PersistencePolicy {
+            max_buffered_records: 10,
+            ..PersistencePolicy::default()
+        });
+        buffer.push(&good, "1".to_string()).unwrap();
+        buffer.push(&missing_dir, "2".to_string()).unwrap();
+
+        assert!(buffer.drain().is_err());
+        assert_eq!(fs::read_to_string(&good).unwrap(), "1\n");
+        assert_eq!(buffer.lines_for(&missing_dir).collect::<Vec<_>>(), ["2"]);
+
+        fs::create_dir_all(missing_dir.parent().unwrap()).unwrap();
+        buffer.drain().unwrap();
+        assert_eq!(fs::read_to_string(&missing_dir).unwrap(), "2\n");
+    }
+}
@@ -0,0 +1,238 @@
+//! Arbitrary date-range summaries for economic agents.
+//!
+//! [`DateRange`] is a half-open interval of instants: the start is included
+//! and the end is not, so consecutive ranges such as "March 1st to March
+//! 15th" and "March 15th to April 1st" never count a record twice. Ranges
+//! built from calendar days use local midnight in the given time zone.
+//! The summaries are produced by `EconomicTracker::cost_summary`,
+//! `income_summary`, and `task_summary`.
+
+use super::costs::TaskCostSummary;
+use chrono::{DateTime, LocalResult, NaiveDate, TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Half-open range of instants `[start, end)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateRange {
+    /// First instant in the range (inclusive)
+    pub start: DateTime<Utc>,
+    /// First instant after the range (exclusive)
+    pub end: DateTime<Utc>,
+}
+
+impl DateRange {
+    /// Range from `start` up to, but not including, `end`.
+    pub fn new<Tz: TimeZone>(start: DateTime<Tz>, end: DateTime<Tz>) -> Self {
+        Self {
+            start: start.with_timezone(&Utc),
+            end: end.with_timezone(&Utc),
+        }
+    }
+
+    /// Calendar days from `first` up to, but not including, `end`, each day
+    /// starting at local midnight in `tz`.
+    ///
+    /// When midnight does not exist in `tz` (a DST gap), the day starts at
+    /// the first valid hour.
+    pub fn days<Tz: TimeZone>(first: NaiveDate, end: NaiveDate, tz: &Tz) -> Self {
+        Self {
+            start: start_of_day(first, tz),
+            end: start_of_day(end, tz),
+        }
+    }
+
+    /// Range of every representable instant, e.g. to read a log together
+    /// with all of its archives.
+    pub fn all() -> Self {
+        Self {
+            start: DateTime::<Utc>::MIN_UTC,
+            end: DateTime::<Utc>::MAX_UTC,
+        }
+    }
+
+    /// Whether `timestamp` falls within the range.
+    pub fn contains(&self, timestamp: &DateTime<Utc>) -> bool {
+        self.start <= *timestamp && *timestamp < self.end
+    }
+
+    /// Whether the range contains no instants.
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+}
+
+/// First instant of `date` in `tz`, in UTC.
+fn start_of_day<Tz: TimeZone>(date: NaiveDate, tz: &Tz) -> DateTime<Utc> {
+    for hour in 0..=2 {
+        let Some(local) = date.and_hms_opt(hour, 0, 0) else {
+            continue;
+        };
+        match tz.from_local_datetime(&local) {
+            LocalResult::Single(start) | LocalResult::Ambiguous(start, _) => {
+                return start.with_timezone(&Utc);
+            }
+            LocalResult::None => {}
+        }
+    }
+    date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc()
+}
+
+/// Costs incurred within a [`DateRange`], from `EconomicTracker::cost_summary`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeCostSummary {
+    pub range: DateRange,
+    /// Cost of LLM calls made in the range (USD)
+    pub llm_cost: f64,
+    /// Cost of non-LLM API calls made in the range (USD)
+    pub api_cost: f64,
+    /// LLM plus API cost (USD)
+    pub total: f64,
+    /// Number of LLM calls
+    pub llm_calls: usize,
+    /// Number of API calls
+    pub api_calls: usize,
+    /// Input tokens of the LLM calls
+    pub input_tokens: u64,
+    /// Output tokens of the LLM calls
+    pub output_tokens: u64,
+}
+
+/// Income received within a [`DateRange`], from
+/// `EconomicTracker::income_summary`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RangeIncomeSummary {
+    pub range: DateRange,
+    /// Payments for evaluated work (USD)
+    pub work_income: f64,
+    /// Grant income (USD)
+    pub grant_income: f64,
+    /// Refunds of earlier charges (USD)
+    pub refunds: f64,
+    /// Work plus grant income (refunds are cost corrections, not income)
+    pub total_income: f64,
+    /// Number of work payments awarded
+    pub payments_awarded: usize,
+    /// Number of work submissions that were not paid
+    pub payments_rejected: usize,
+}
+
+/// Tasks that ended within a [`DateRange`], from
+/// `EconomicTracker::task_summary`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RangeTaskSummary {
+    pub range: DateRange,
+    /// Number of tasks that ended in the range
+    pub tasks_ended: usize,
+    /// Tasks that ended with `TaskStatus::Completed`
+    pub completed: usize,
+    /// Tasks that ended aborted, failed, timed out, or rejected
+    pub aborted: usize,
+    /// Summed cost of the tasks (USD)
+    pub total_cost: f64,
+    /// Cost summary of each task, in completion order
+    pub tasks: Vec<TaskCostSummary>,
+}
+
+impl RangeCostSummary {
+    /// Summary with no costs.
+    pub fn empty(range: DateRange) -> Self {
+        Self {
+            range,
+            llm_cost: 0.0,
+            api_cost: 0.0,
+            total: 0.0,
+            llm_calls: 0,
+            api_calls: 0,
+            input_tokens: 0,
+            output_tokens: 0,
+        }
+    }
+}
+
+impl RangeIncomeSummary {
+    /// Summary with no income.
+    pub fn empty(range: DateRange) -> Self {
+        Self {
+            range,
+            work_income: 0.0,
+            grant_income: 0.0,
+            refunds: 0.0,
+            total_income: 0.0,
+            payments_awarded: 0,
+            payments_rejected: 0,
+        }
+    }
+}
+
+impl RangeTaskSummary {
+    /// Summary with no tasks.
+    pub fn empty(range: DateRange) -> Self {
+        Self {
+            range,
+            tasks_ended: 0,
+            completed: 0,
+            aborted: 0,
+            total_cost: 0.0,
+            tasks: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, FixedOffset};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn boundaries_are_half_open_at_midnight() {
+        let range = DateRange::days(date(2025, 3, 1), date(2025, 3, 15), &Utc);
+        let start = Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2025, 3, 15, 0, 0, 0).unwrap();
+
+        assert!(range.contains(&start));
+        assert!(!range.contains(&(start - Duration::milliseconds(1))));
+        assert!(range.contains(&(end - Duration::milliseconds(1))));
+        assert!(!range.contains(&end));
+        assert!(!range.is_empty());
+
+        // Adjacent ranges share no instant
+        let next = DateRange::days(date(2025, 3, 15), date(2025, 4, 1), &Utc);
+        assert!(next.contains(&end) && !range.contains(&end));
+    }
+
+    #[test]
+    fn days_start_at_local_midnight() {
+        let tz = FixedOffset::east_opt(9 * 3600).unwrap();
+        let range = DateRange::days(date(2025, 3, 1), date(2025, 3, 2), &tz);
+
+        // Local midnight in UTC+9 is 15:00 UTC the previous day
+        assert_eq!(
+            range.start,
+            Utc.with_ymd_and_hms(2025, 2, 28, 15, 0, 0).unwrap()
+        );
+        let local_midnight = tz.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap();
+        assert!(!range.contains(&local_midnight.with_timezone(&Utc)));
+        let utc_midnight = Utc.with_ymd_and_hms(2025, 3, 2, 0, 0, 0).unwrap();
+        assert!(!range.contains(&utc_midnight));
+        assert!(range.contains(&Utc.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap()));
+
+        let same = DateRange::new(
+            tz.with_ymd_and_hms(2025, 3, 1, 0, 0, 0).unwrap(),
+            local_midnight,
+        );
+        assert_eq!(same, range);
+    }
+
+    #[test]
+    fn reversed_or_equal_bounds_are_empty() {
+        let day = date(2025, 3, 1);
+        let range = DateRange::days(day, day, &Utc);
+        assert!(range.is_empty());
+        assert!(!range.contains(&range.start));
+        assert!(DateRange::days(date(2025, 3, 2), day, &Utc).is_empty());
+    }
+}
@@ -0,0 +1,413 @@
+//! Repairing damaged economic data directories.
+//!
+//! A crash or a full disk can leave the JSONL logs with partial lines,
+//! repeated appends, and records out of order, which the tracker then fails
+//! to load. `repair_data_dir` cleans each log in place and, when one was
+//! damaged, rebuilds `balance.jsonl` from the repaired history, as
+//! `merge_data_dirs` does for a merged directory. Run it while no tracker is using the directory.
+
+use super::costs::BalanceRecord;
+use super::error::{IoResultExt, Result};
+use super::integrity::{self, INTEGRITY_FIELD};
+use super::merge::{self, BALANCE_LOG, EVENT_LOGS};
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// How `repair_data_dir` treats a data directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RepairOptions {
+    /// Report what would change without writing anything
+    pub dry_run: bool,
+    /// How far a record may trail an earlier one before its file is
+    /// re-sorted; writers racing on the same file leave small inversions
+    pub reorder_tolerance: Duration,
+}
+
+impl Default for RepairOptions {
+    fn default() -> Self {
+        Self {
+            dry_run: false,
+            reorder_tolerance: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Outcome of `repair_data_dir`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RepairReport {
+    /// Nothing was written
+    pub dry_run: bool,
+    /// One entry per log file present in the data directory
+    pub files: Vec<FileRepair>,
+    /// Snapshots in the rebuilt `balance.jsonl`
+    pub snapshots_rebuilt: usize,
+    /// Balance after replaying the repaired history; `None` when
+    /// `balance.jsonl` is not rebuilt, because no log was damaged or no
+    /// balance snapshot survived to supply the opening balance
+    pub final_balance: Option<f64>,
+}
+
+impl RepairReport {
+    /// Whether any log had lines rejected, dropped, or out of order.
+    pub fn found_damage(&self) -> bool {
+        self.files.iter().any(FileRepair::is_damaged)
+    }
+}
+
+/// What was wrong with a single log file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileRepair {
+    pub path: PathBuf,
+    /// Records kept
+    pub records: usize,
+    /// Lines that are not JSON objects, moved to `<file>.rejected`
+    pub rejected_lines: usize,
+    /// Lines identical to an earlier line, dropped
+    pub duplicates_removed: usize,
+    /// Records older than an earlier record by more than the tolerance
+    pub records_out_of_order: usize,
+}
+
+impl FileRepair {
+    /// Whether the file needs rewriting.
+    pub fn is_damaged(&self) -> bool {
+        self.rejected_lines > 0 || self.duplicates_removed > 0 || self.records_out_of_order > 0
+    }
+}
+
+/// Repair every known log in `path`, then rebuild `balance.jsonl` from the
+/// repaired history if any log was damaged.
+///
+/// Undecodable lines are appended to a `.rejected` file next to their log
+/// (`token_costs.jsonl.rejected`) and exact duplicates of an earlier line
+/// are dropped. A file with a record older than an earlier one by more than
+/// `reorder_tolerance` is sorted by time, and resealed if it carries
+/// integrity links; `balance.jsonl` is rebuilt rather than sorted. Damaged
+/// files are rewritten through a temp file.
+///
+/// The rebuilt balance log holds one snapshot per day with activity, like
+/// a merged directory. Balance changes the old snapshots recorded but the
+/// event logs cannot explain, such as costs tracked outside a task, are
+/// carried over from the last snapshot of each day. A directory without
+/// damage is left as it is. With `dry_run`, the report describes the same
+/// changes and nothing is written.
+///
+/// # Errors
+/// Fails on IO errors.
+pub fn repair_data_dir(path: &Path, options: RepairOptions) -> Result<RepairReport> {
+    let mut report = RepairReport {
+        dry_run: options.dry_run,
+        ..RepairReport::default()
+    };
+
+    let mut snapshots: Vec<BalanceRecord> = Vec::new();
+    let mut events: HashMap<&str, Vec<Value>> = HashMap::new();
+    for file in std::iter::once(BALANCE_LOG).chain(EVENT_LOGS) {
+        let log = path.join(file);
+        let records = if log.exists() {
+            // Snapshots are rebuilt below, so there is no point sorting them
+            let (repair, records) = repair_file(&log, options, file != BALANCE_LOG)?;
+            report.files.push(repair);
+            records
+        } else {
+            Vec::new()
+        };
+        if file == BALANCE_LOG {
+            snapshots = records
+                .iter()
+                .filter_map(|record| BalanceRecord::deserialize(record).ok())
+                .collect();
+        } else {
+            events.insert(file, records);
+        }
+    }
+
+    if !report.found_damage() {
+        return Ok(report);
+    }
+    let Some(rebuilt) = merge::rebuild_balance(&events, &snapshots) else {
+        return Ok(report);
+    };
+    report.snapshots_rebuilt = rebuilt.len();
+    report.final_balance = rebuilt.last().map(|snapshot| snapshot.balance);
+    if !options.dry_run {
+        let lines = rebuilt
+            .iter()
+            .map(serde_json::to_string)
+            .collect::<serde_json::Result<Vec<_>>>()?;
+        replace_file(&path.join(BALANCE_LOG), &lines)?;
+    }
+
+    Ok(report)
+}
+
+/// Clean one log, sorting it by time if `sort` is set; returns what was
+/// wrong and the records kept, in order.
+fn repair_file(
+    path: &Path,
+    options: RepairOptions,
+    sort: bool,
+) -> Result<(FileRepair, Vec<Value>)> {
+    let bytes = fs::read(path).at_path(path)?;
+    let mut repair = FileRepair {
+        path: path.to_path_buf(),
+        records: 0,
+        rejected_lines: 0,
+        duplicates_removed: 0,
+        records_out_of_order: 0,
+    };
+
+    let mut rejected: Vec<&[u8]> = Vec::new();
+    let mut seen = HashSet::new();
+    let mut lines: Vec<(String, Value)> = Vec::new();
+    for raw in bytes.split(|&byte| byte == b'\n') {
+        let Ok(line) = std::str::from_utf8(raw).map(str::trim) else {
+            rejected.push(raw);
+            continue;
+        };
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<Value>(line) {
+            Ok(value) if value.is_object() => {
+                if seen.insert(line) {
+                    lines.push((line.to_string(), value));
+                } else {
+                    repair.duplicates_removed += 1;
+                }
+            }
+            _ => rejected.push(raw),
+        }
+    }
+    repair.rejected_lines = rejected.len();
+
+    if sort {
+        let tolerance = TimeDelta::from_std(options.reorder_tolerance).unwrap_or(TimeDelta::MAX);
+        let mut latest: Option<DateTime<Utc>> = None;
+        for time in lines.iter().filter_map(|(_, value)| record_time(value)) {
+            if latest.is_some_and(|latest| latest - time > tolerance) {
+                repair.records_out_of_order += 1;
+            }
+            latest = latest.max(Some(time));
+        }
+    }
+    let reorder = repair.records_out_of_order > 0;
+    if reorder {
+        // Undated lines keep their place; dated ones are sorted among
+        // themselves, keeping the file order for equal times
+        let slots: Vec<usize> = (0..lines.len())
+            .filter(|&i| record_time(&lines[i].1).is_some())
+            .collect();
+        let mut dated: Vec<(String, Value)> = slots
+            .iter()
+            .map(|&i| std::mem::take(&mut lines[i]))
+            .collect();
+        dated.sort_by_key(|(_, value)| record_time(value));
+        for (slot, line) in slots.into_iter().zip(dated) {
+            lines[slot] = line;
+        }
+    }
+
+    let (mut raw_lines, records): (Vec<String>, Vec<Value>) = lines.into_iter().unzip();
+    repair.records = records.len();
+    if options.dry_run || !repair.is_damaged() {
+        return Ok((repair, records));
+    }
+
+    if !rejected.is_empty() {
+        let sidecar = path.with_extension("jsonl.rejected");
+        let append = || -> std::io::Result<()> {
+            let mut file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&sidecar)?;
+            for line in &rejected {
+                file.write_all(line)?;
+                file.write_all(b"\n")?;
+            }
+            file.sync_all()
+        };
+        append().at_path(&sidecar)?;
+    }
+    // Dropped lines leave the chain intact, but moved records break it
+    if reorder
+        && records
+            .iter()
+            .any(|record| record.get(INTEGRITY_FIELD).is_some())
+    {
+        integrity::rechain(&mut raw_lines)?;
+    }
+    replace_file(path, &raw_lines)?;
+
+    Ok((repair, records))
+}
+
+/// Replace `path` with `lines` via a temp file, so a crash never truncates it.
+fn replace_file(path: &Path, lines: &[String]) -> Result<()> {
+    let tmp_path = path.with_extension("jsonl.tmp");
+    let write = || -> std::io::Result<()> {
+        let mut tmp = File::create(&tmp_path)?;
+        for line in lines {
+            writeln!(tmp, "{line}")?;
+        }
+        tmp.sync_all()
+    };
+    write().at_path(&tmp_path)?;
+    fs::rename(&tmp_path, path).at_path(path)
+}
+
+/// When a record was logged (`timestamp_end` or `timestamp`).
+fn record_time(value: &Value) -> Option<DateTime<Utc>> {
+    value
+        .get("timestamp_end")
+        .or_else(|| value.get("timestamp"))?
+        .as_str()?
+        .parse()
+        .ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{EconomicConfig, EconomicTracker};
+    use tempfile::TempDir;
+
+    fn tracker(dir: &Path) -> EconomicTracker {
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(dir.to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker
+    }
+
+    fn grant(id: &str, time: &str, amount: f64) -> String {
+        format!(
+            r#"{{"timestamp":"{time}","date":"{}","grant_id":"{id}","amount":{amount},"#,
+            &time[..10]
+        ) + r#""balance_after":0.0}"#
+    }
+
+    #[test]
+    fn repair_cleans_logs_and_rebuilds_balance() {
+        let tmp = TempDir::new().unwrap();
+        let first = tracker(tmp.path());
+        first.start_task("task-1", None, &[]).unwrap();
+        first
+            .track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO)
+            .unwrap();
+        first.end_task("task-1").unwrap();
+        for (id, amount) in [("later", 5.0), ("jitter", 1.0), ("earlier", 4.0)] {
+            first.add_grant_income(amount, id, "").unwrap();
+        }
+        first.add_work_income(20.0, "task-1", 0.9, "").unwrap();
+        drop(first);
+
+        // Garbage and a repeated append in the cost log, and the grants
+        // backdated out of order
+        let costs = tmp.path().join("token_costs.jsonl");
+        let mut damaged = fs::read(&costs).unwrap();
+        let last_line = fs::read_to_string(&costs)
+            .unwrap()
+            .lines()
+            .last()
+            .unwrap()
+            .to_string();
+        damaged.extend_from_slice(b"{\"task_id\":\"tas\0\0\0\n\xff\xfe\n");
+        damaged.extend_from_slice(format!("{last_line}\n").as_bytes());
+        fs::write(&costs, &damaged).unwrap();
+        let grants = tmp.path().join("grant_income.jsonl");
+        fs::write(
+            &grants,
+            [
+                grant("later", "2025-03-02T10:00:00Z", 5.0),
+                grant("jitter", "2025-03-02T09:59:30Z", 1.0),
+                grant("earlier", "2025-03-01T10:00:00Z", 4.0),
+            ]
+            .join("\n"),
+        )
+        .unwrap();
+
+        let dry_run = RepairOptions {
+            dry_run: true,
+            ..RepairOptions::default()
+        };
+        let report = repair_data_dir(tmp.path(), dry_run).unwrap();
+        assert!(report.found_damage());
+        let cost_repair = report.files.iter().find(|file| file.path == costs).unwrap();
+        assert_eq!(cost_repair.rejected_lines, 2);
+        assert_eq!(cost_repair.duplicates_removed, 1);
+        let grant_repair = report
+            .files
+            .iter()
+            .find(|file| file.path == grants)
+            .unwrap();
+        // Only the grant a day behind counts; 30 seconds is within tolerance
+        assert_eq!(grant_repair.records_out_of_order, 1);
+        // 100 - 10 + 20 + 5 + 1 + 4
+        assert!((report.final_balance.unwrap() - 120.0).abs() < 1e-9);
+        assert_eq!(fs::read(&costs).unwrap(), damaged);
+        assert!(!tmp.path().join("token_costs.jsonl.rejected").exists());
+
+        let report = repair_data_dir(tmp.path(), RepairOptions::default()).unwrap();
+        assert!(report.found_damage());
+        let rejected = fs::read(tmp.path().join("token_costs.jsonl.rejected")).unwrap();
+        assert_eq!(rejected, b"{\"task_id\":\"tas\0\0\0\n\xff\xfe\n");
+        let repaired = fs::read_to_string(&grants).unwrap();
+        let order: Vec<_> = ["earlier", "jitter", "later"]
+            .iter()
+            .map(|id| repaired.find(id).unwrap())
+            .collect();
+        assert!(order.is_sorted());
+
+        let repaired = tracker(tmp.path());
+        assert!((repaired.get_balance() - 120.0).abs() < 1e-9);
+        drop(repaired);
+        assert!(!repair_data_dir(tmp.path(), RepairOptions::default())
+            .unwrap()
+            .found_damage());
+    }
+
+    #[test]
+    fn repair_keeps_balance_changes_the_event_logs_cannot_explain() {
+        let tmp = TempDir::new().unwrap();
+        let first = tracker(tmp.path());
+        first
+            .track_tokens(1000, 500, "agent", Some(5.0), Duration::ZERO)
+            .unwrap();
+        first.start_task("task-1", None, &[]).unwrap();
+        first
+            .track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO)
+            .unwrap();
+        first.end_task("task-1").unwrap();
+        drop(first);
+
+        // Nothing damaged: balance.jsonl is left alone
+        let balance = tmp.path().join(BALANCE_LOG);
+        let snapshots = fs::read(&balance).unwrap();
+        let report = repair_data_dir(tmp.path(), RepairOptions::default()).unwrap();
+        assert!(!report.found_damage());
+        assert_eq!(report.final_balance, None);
+        assert_eq!(fs::read(&balance).unwrap(), snapshots);
+
+        // A rebuild keeps the charge made outside a task
+        let costs = tmp.path().join("token_costs.jsonl");
+        let mut damaged = fs::read(&costs).unwrap();
+        damaged.extend_from_slice(b"\xff\xfe\n");
+        fs::write(&costs, &damaged).unwrap();
+        let report = repair_data_dir(tmp.path(), RepairOptions::default()).unwrap();
+        assert!(report.found_damage());
+        // 100 - 5 - 10
+        assert!((report.final_balance.unwrap() - 85.0).abs() < 1e-9);
+        let repaired = tracker(tmp.path());
+        assert!((repaired.get_balance() - 85.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,528 @@
+//! Retention of raw economic records.
+//!
+//! Per-call records are only needed while they are recent; older months can
+//! be kept as totals. [`RetentionRunner`] summarizes records older than the
+//! configured age into [`MonthlyRollup`] entries in `rollups.jsonl`, then
+//! moves the raw lines into gzip archives, or deletes them.
+
+use super::costs::CostBreakdown;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[cfg(feature = "compress")]
+use {
+    super::archive::{self, ArchiveSummary},
+    super::costs::{
+        GrantIncomeRecord, InterestKind, InterestRecord, RefundRecord, TaskCompletionRecord,
+    },
+    super::error::{IoResultExt, Result},
+    super::history::CostLogRecord,
+    super::tracker::for_each_jsonl,
+    chrono::Days,
+    std::collections::BTreeMap,
+    std::fs::{self, OpenOptions},
+    std::io::Write,
+    std::path::Path,
+};
+
+/// Rollup log in the data directory.
+pub(crate) const ROLLUP_LOG: &str = "rollups.jsonl";
+
+/// When raw records expire and what happens to them.
+///
+/// In `config.toml`:
+///
+/// ```toml
+/// [economic.retention]
+/// max_age_days = 240
+/// aggregate_before_delete = true
+/// archive_dir = "archive"
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Age in days after which raw records expire (0 keeps them forever)
+    #[serde(default)]
+    pub max_age_days: u32,
+    /// Summarize expiring records into monthly rollups before removing them
+    #[serde(default = "default_aggregate_before_delete")]
+    pub aggregate_before_delete: bool,
+    /// Where expired records are archived, relative to the data directory
+    #[serde(default = "default_archive_dir")]
+    pub archive_dir: PathBuf,
+    /// Delete expired records instead of archiving them
+    #[serde(default)]
+    pub delete_expired: bool,
+}
+
+fn default_aggregate_before_delete() -> bool {
+    true
+}
+
+fn default_archive_dir() -> PathBuf {
+    PathBuf::from("archive")
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: 0,
+            aggregate_before_delete: default_aggregate_before_delete(),
+            archive_dir: default_archive_dir(),
+            delete_expired: false,
+        }
+    }
+}
+
+/// Totals of the expired records of one month, persisted to `rollups.jsonl`.
+///
+/// A month whose records expire over several runs gets one entry per run;
+/// sum the entries of a month for its totals.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MonthlyRollup {
+    /// Month the records fall in (YYYY-MM)
+    pub month: String,
+    /// Every record dated before this instant is covered by rollups
+    pub through: DateTime<Utc>,
+    /// Number of raw records summarized
+    pub records: usize,
+    /// Task cost records
+    pub tasks: usize,
+    /// Costs of those tasks by channel
+    pub costs: CostBreakdown,
+    /// Number of LLM calls
+    pub llm_calls: usize,
+    /// Input tokens of the LLM calls
+    pub input_tokens: u64,
+    /// Output tokens of the LLM calls
+    pub output_tokens: u64,
+    /// Work income received
+    pub work_income: f64,
+    /// Number of work payments awarded
+    pub payments_awarded: usize,
+    /// Grant income received
+    pub grant_income: f64,
+    /// Refunds credited
+    pub refunds: f64,
+    /// Interest earned on a positive balance
+    pub interest_earned: f64,
+    /// Interest paid on a negative balance
+    pub interest_paid: f64,
+    /// Tasks that ended
+    pub tasks_ended: usize,
+    /// Tasks that ended with `TaskStatus::Completed`
+    pub tasks_completed: usize,
+}
+
+/// Outcome of [`RetentionRunner::apply`].
+#[cfg(feature = "compress")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetentionReport {
+    /// Records dated before this instant had expired
+    pub cutoff: DateTime<Utc>,
+    /// Rollup entries appended to `rollups.jsonl`
+    pub rollups_written: usize,
+    /// Raw records summarized into those entries
+    pub records_rolled_up: usize,
+    /// Raw records moved into archives
+    pub records_archived: usize,
+    /// Raw records deleted
+    pub records_deleted: usize,
+    /// Archive files written
+    pub archive_files: Vec<PathBuf>,
+}
+
+/// Applies a [`RetentionPolicy`] to a data directory.
+///
+/// Call [`apply`](Self::apply) on a schedule. Expiry works in whole UTC days,
+/// so running it twice on the same day changes nothing the second time.
+#[cfg(feature = "compress")]
+#[derive(Debug, Clone)]
+pub struct RetentionRunner {
+    policy: RetentionPolicy,
+}
+
+#[cfg(feature = "compress")]
+impl RetentionRunner {
+    pub fn new(policy: RetentionPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// Roll up and archive (or delete) the records in `data_dir` that are
+    /// older than `max_age_days`.
+    ///
+    /// With `aggregate_before_delete`, only records already covered by a
+    /// persisted rollup are removed, and records rolled up by an earlier run
+    /// are never counted again. The latest balance snapshot is always kept.
+    /// Nothing else may write to `data_dir` meanwhile; from a running agent
+    /// use `EconomicTracker::apply_retention`.
+    pub fn apply(&self, data_dir: &Path) -> Result<RetentionReport> {
+        let today = Utc::now().date_naive();
+        let cutoff = today
+            .checked_sub_days(Days::new(u64::from(self.policy.max_age_days)))
+            .unwrap_or(today)
+            .and_hms_opt(0, 0, 0)
+            .unwrap_or_default()
+            .and_utc();
+        let mut report = RetentionReport {
+            cutoff,
+            rollups_written: 0,
+            records_rolled_up: 0,
+            records_archived: 0,
+            records_deleted: 0,
+            archive_files: Vec::new(),
+        };
+        if self.policy.max_age_days == 0 {
+            return Ok(report);
+        }
+
+        let mut remove_before = Some(cutoff);
+        if self.policy.aggregate_before_delete {
+            let rollup_path = data_dir.join(ROLLUP_LOG);
+            let covered = last_rollup_through(&rollup_path)?;
+            if covered.is_none_or(|covered| covered < cutoff) {
+                let rollups = summarize(data_dir, covered, cutoff)?;
+                append_rollups(&rollup_path, rollups.values())?;
+                report.rollups_written = rollups.len();
+                report.records_rolled_up = rollups.values().map(|rollup| rollup.records).sum();
+            }
+            // Never remove anything the persisted rollups do not cover
+            remove_before = last_rollup_through(&rollup_path)?.map(|covered| covered.min(cutoff));
+        }
+
+        if let Some(remove_before) = remove_before {
+            let archive_dir =
+                (!self.policy.delete_expired).then(|| data_dir.join(&self.policy.archive_dir));
+            if let Some(archive_dir) = &archive_dir {
+                fs::create_dir_all(archive_dir).at_path(archive_dir)?;
+            }
+            let mut drained = ArchiveSummary::default();
+            for (name, clock) in archive::ARCHIVED_LOGS {
+                let path = data_dir.join(name);
+                let archive_dir = archive_dir.as_deref();
+                archive::drain_file(&path, archive_dir, remove_before, clock, &mut drained)?;
+            }
+            if self.policy.delete_expired {
+                report.records_deleted = drained.records_archived;
+            } else {
+                report.records_archived = drained.records_archived;
+                report.archive_files = drained.archive_files;
+            }
+        }
+
+        tracing::info!(
+            "🗄️ Retention: {} records rolled up into {} entries, {} archived, {} deleted",
+            report.records_rolled_up,
+            report.rollups_written,
+            report.records_archived,
+            report.records_deleted
+        );
+        Ok(report)
+    }
+}
+
+/// `through` of the last rollup entry in `path`.
+#[cfg(feature = "compress")]
+fn last_rollup_through(path: &Path) -> Result<Option<DateTime<Utc>>> {
+    let mut through = None;
+    for_each_jsonl::<MonthlyRollup, _>(path, |rollup| through = Some(rollup.through))?;
+    Ok(through)
+}
+
+/// Monthly totals of the records dated in `[after, before)`.
+#[cfg(feature = "compress")]
+fn summarize(
+    data_dir: &Path,
+    after: Option<DateTime<Utc>>,
+    before: DateTime<Utc>,
+) -> Result<BTreeMap<String, MonthlyRollup>> {
+    let window = Window { after, before };
+    let mut rollups = BTreeMap::new();
+
+    for_each_jsonl::<CostLogRecord, _>(
+        &data_dir.join("token_costs.jsonl"),
+        |record| match record {
+            CostLogRecord::Task(task) => {
+                if let Some(rollup) = window.rollup(&mut rollups, task.timestamp_end) {
+                    rollup.tasks += 1;
+                    rollup.costs.add(&task.cost_summary);
+                    rollup.llm_calls += task.llm_usage.total_calls;
+                    rollup.input_tokens += task.llm_usage.total_input_tokens;
+                    rollup.output_tokens += task.llm_usage.total_output_tokens;
+                }
+            }
+            CostLogRecord::Income(income) => {
+                if let Some(rollup) = window.rollup(&mut rollups, income.timestamp) {
+                    rollup.work_income += income.actual_payment;
+                    if income.payment_awarded {
+                        rollup.payments_awarded += 1;
+                    }
+                }
+            }
+        },
+    )?;
+    for_each_jsonl::<TaskCompletionRecord, _>(
+        &data_dir.join("task_completions.jsonl"),
+        |record| {
+            if let Some(rollup) = window.rollup(&mut rollups, record.timestamp) {
+                rollup.tasks_ended += 1;
+                if record.status.is_income_eligible() {
+                    rollup.tasks_completed += 1;
+                }
+            }
+        },
+    )?;
+    for_each_jsonl::<RefundRecord, _>(&data_dir.join("refunds.jsonl"), |record| {
+        if let Some(rollup) = window.rollup(&mut rollups, record.timestamp) {
+            rollup.refunds += record.amount();
+        }
+    })?;
+    for_each_jsonl::<GrantIncomeRecord, _>(&data_dir.join("grant_income.jsonl"), |record| {
+        if let Some(rollup) = window.rollup(&mut rollups, record.timestamp) {
+            rollup.grant_income += record.amount;
+        }
+    })?;
+    for_each_jsonl::<InterestRecord, _>(&data_dir.join("interest.jsonl"), |record| {
+        if let Some(rollup) = window.rollup(&mut rollups, record.timestamp) {
+            match record.kind {
+                InterestKind::Earned => rollup.interest_earned += record.amount,
+                InterestKind::Paid => rollup.interest_paid += record.amount,
+            }
+        }
+    })?;
+
+    Ok(rollups)
+}
+
+/// Records rolled up by one run: dated in `[after, before)`.
+#[cfg(feature = "compress")]
+#[derive(Clone, Copy)]
+struct Window {
+    after: Option<DateTime<Utc>>,
+    before: DateTime<Utc>,
+}
+
+#[cfg(feature = "compress")]
+impl Window {
+    /// Rollup of the month `at` falls in, counting the record, or `None`
+    /// when `at` is outside the window.
+    fn rollup<'a>(
+        &self,
+        rollups: &'a mut BTreeMap<String, MonthlyRollup>,
+        at: DateTime<Utc>,
+    ) -> Option<&'a mut MonthlyRollup> {
+        if at >= self.before || self.after.is_some_and(|after| at < after) {
+            return None;
+        }
+        let month = at.format("%Y-%m").to_string();
+        let rollup = rollups
+            .entry(month.clone())
+            .or_insert_with(|| MonthlyRollup {
+                month,
+                through: self.before,
+                ..Default::default()
+            });
+        rollup.records += 1;
+        Some(rollup)
+    }
+}
+
+#[cfg(feature = "compress")]
+fn append_rollups<'a>(path: &Path, rollups: impl Iterator<Item = &'a MonthlyRollup>) -> Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .at_path(path)?;
+    for rollup in rollups {
+        writeln!(file, "{}", serde_json::to_string(rollup)?).at_path(path)?;
+    }
+    file.sync_all().at_path(path)
+}
+
+#[cfg(all(test, feature = "compress"))]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use tempfile::TempDir;
+
+    fn task_line(task_id: &str, at: &str, cost: f64) -> String {
+        json!({
+            "timestamp_end": at,
+            "timestamp_start": at,
+            "date": &at[..10],
+            "task_id": task_id,
+            "llm_usage": {
+                "total_calls": 3,
+                "total_input_tokens": 100,
+                "total_output_tokens": 50,
+                "total_tokens": 150,
+                "total_cost": cost,
+                "input_price_per_million": 3.0,
+                "output_price_per_million": 15.0
+            },
+            "api_usage": {
+                "total_calls": 0,
+                "search_api_cost": 0.0,
+                "ocr_api_cost": 0.0,
+                "other_api_cost": 0.0,
+                "token_based_calls": 0,
+                "flat_rate_calls": 0
+            },
+            "cost_summary": {
+                "llm_tokens": cost,
+                "search_api": 0.0,
+                "ocr_api": 0.0,
+                "other_api": 0.0
+            },
+            "balance_after": 100.0,
+            "session_cost": cost,
+            "daily_cost": cost
+        })
+        .to_string()
+    }
+
+    fn grant_line(at: &str, amount: f64) -> String {
+        json!({
+            "timestamp": at,
+            "date": &at[..10],
+            "grant_id": "grant",
+            "amount": amount,
+            "balance_after": 100.0
+        })
+        .to_string()
+    }
+
+    fn write_lines(path: &Path, lines: &[String]) {
+        fs::write(path, lines.join("\n") + "\n").unwrap();
+    }
+
+    fn read_rollups(data_dir: &Path) -> Vec<MonthlyRollup> {
+        let mut rollups = Vec::new();
+        for_each_jsonl::<MonthlyRollup, _>(&data_dir.join(ROLLUP_LOG), |rollup| {
+            rollups.push(rollup);
+        })
+        .unwrap();
+        rollups
+    }
+
+    fn policy() -> RetentionPolicy {
+        RetentionPolicy {
+            max_age_days: 30,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn expired_records_are_rolled_up_and_archived_once() {
+        let tmp = TempDir::new().unwrap();
+        let now = Utc::now().to_rfc3339();
+        let token_costs = tmp.path().join("token_costs.jsonl");
+        write_lines(
+            &token_costs,
+            &[
+                task_line("old-1", "2020-01-10T12:00:00Z", 2.0),
+                task_line("old-2", "2020-02-05T12:00:00Z", 1.0),
+                task_line("recent", &now, 4.0),
+            ],
+        );
+        write_lines(
+            &tmp.path().join("grant_income.jsonl"),
+            &[
+                grant_line("2020-01-20T08:00:00Z", 5.0),
+                grant_line(&now, 1.0),
+            ],
+        );
+
+        let runner = RetentionRunner::new(policy());
+        let report = runner.apply(tmp.path()).unwrap();
+        assert_eq!(report.rollups_written, 2);
+        assert_eq!(report.records_rolled_up, 3);
+        assert_eq!(report.records_archived, 3);
+        assert_eq!(report.records_deleted, 0);
+        assert_eq!(report.archive_files.len(), 2);
+        assert!(report
+            .archive_files
+            .iter()
+            .all(|path| path.starts_with(tmp.path().join("archive"))));
+
+        let rollups = read_rollups(tmp.path());
+        let january = rollups.iter().find(|r| r.month == "2020-01").unwrap();
+        assert_eq!(
+            (january.records, january.tasks, january.llm_calls),
+            (2, 1, 3)
+        );
+        assert!((january.costs.llm_tokens - 2.0).abs() < 1e-9);
+        assert!((january.grant_income - 5.0).abs() < 1e-9);
+        assert_eq!(january.through, report.cutoff);
+        let february = rollups.iter().find(|r| r.month == "2020-02").unwrap();
+        assert_eq!(february.tasks, 1);
+
+        let active = fs::read_to_string(&token_costs).unwrap();
+        assert_eq!(active.lines().count(), 1);
+        assert!(active.contains(r#""task_id":"recent""#));
+
+        // Second run the same day: nothing left to roll up or archive
+        let again = runner.apply(tmp.path()).unwrap();
+        assert_eq!((again.rollups_written, again.records_archived), (0, 0));
+        assert!(again.archive_files.is_empty());
+        assert_eq!(read_rollups(tmp.path()).len(), 2);
+        assert_eq!(fs::read_to_string(&token_costs).unwrap(), active);
+    }
+
+    #[test]
+    fn records_covered_by_earlier_rollups_are_not_counted_twice() {
+        let tmp = TempDir::new().unwrap();
+        // An earlier run rolled up everything before Jan 15th but stopped
+        // before removing the raw records
+        let earlier = MonthlyRollup {
+            month: "2020-01".to_string(),
+            through: "2020-01-15T00:00:00Z".parse().unwrap(),
+            records: 1,
+            tasks: 1,
+            ..Default::default()
+        };
+        append_rollups(&tmp.path().join(ROLLUP_LOG), [&earlier].into_iter()).unwrap();
+        write_lines(
+            &tmp.path().join("token_costs.jsonl"),
+            &[
+                task_line("rolled-up", "2020-01-10T12:00:00Z", 2.0),
+                task_line("expired", "2020-02-05T12:00:00Z", 1.0),
+            ],
+        );
+
+        let report = RetentionRunner::new(RetentionPolicy {
+            delete_expired: true,
+            ..policy()
+        })
+        .apply(tmp.path())
+        .unwrap();
+
+        assert_eq!(report.records_rolled_up, 1);
+        assert_eq!(report.records_deleted, 2);
+        assert_eq!(report.records_archived, 0);
+        assert!(!tmp.path().join("archive").exists());
+        let rollups = read_rollups(tmp.path());
+        assert_eq!(rollups.len(), 2);
+        assert_eq!(rollups[1].month, "2020-02");
+        let remaining = fs::read_to_string(tmp.path().join("token_costs.jsonl")).unwrap();
+        assert!(remaining.trim().is_empty());
+    }
+
+    #[test]
+    fn zero_max_age_keeps_everything() {
+        let tmp = TempDir::new().unwrap();
+        let token_costs = tmp.path().join("token_costs.jsonl");
+        write_lines(
+            &token_costs,
+            &[task_line("old", "2020-01-10T12:00:00Z", 2.0)],
+        );
+
+        let report = RetentionRunner::new(RetentionPolicy::default())
+            .apply(tmp.path())
+            .unwrap();
+        assert_eq!(report.records_archived + report.rollups_written, 0);
+        assert!(!tmp.path().join(ROLLUP_LOG).exists());
+        assert_eq!(fs::read_to_string(&token_costs).unwrap().lines().count(), 1);
+    }
+}
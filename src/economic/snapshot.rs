@@ -0,0 +1,168 @@
+//! Portable economic snapshots for moving an agent between hosts.
+//!
+//! `EconomicTracker::export_snapshot` condenses the tracker's state into a
+//! single [`EconomicSnapshot`]; `EconomicTracker::import_snapshot` seeds a
+//! fresh data directory from one. The imported history starts with a
+//! balance record dated `imported`, so the provenance stays visible.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use super::costs::{BalanceRecord, DateCostSummary};
+use super::error::{IoResultExt, Result};
+use super::evaluation::EscrowRecord;
+use super::merge::{BALANCE_LOG, EVENT_LOGS};
+use super::status::SurvivalStatus;
+
+/// Format version written to new snapshots.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Number of most recent days of daily aggregates kept in a snapshot.
+pub(crate) const SNAPSHOT_DAYS: usize = 30;
+
+/// `date` of the genesis balance record written by an import.
+pub const IMPORTED_DATE: &str = "imported";
+
+/// An agent's economic state at one point in time.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EconomicSnapshot {
+    /// Snapshot format version
+    pub version: u32,
+    /// Agent signature
+    pub signature: String,
+    /// When the snapshot was taken
+    pub exported_at: DateTime<Utc>,
+    pub balance: f64,
+    pub initial_balance: f64,
+    pub total_token_cost: f64,
+    pub total_work_income: f64,
+    pub total_trading_profit: f64,
+    pub total_refunds: f64,
+    pub total_grant_income: f64,
+    pub total_interest_earned: f64,
+    pub total_interest_paid: f64,
+    pub survival_status: SurvivalStatus,
+    /// Amount the balance is below zero
+    pub outstanding_debt: f64,
+    /// Payments held in escrow, carried over so they can still be released
+    #[serde(default)]
+    pub escrow: Vec<EscrowRecord>,
+    /// SHA-256 of the exporting tracker's configuration
+    pub config_hash: String,
+    /// Costs and income of the most recent days with activity, by date
+    #[serde(default)]
+    pub daily: BTreeMap<String, DateCostSummary>,
+}
+
+impl EconomicSnapshot {
+    /// Write the snapshot as pretty-printed JSON.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        fs::write(path, json).at_path(path)
+    }
+
+    /// Read a snapshot written by [`save`](Self::save).
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = fs::read_to_string(path).at_path(path)?;
+        Ok(serde_json::from_str(&json)?)
+    }
+
+    /// Genesis balance record of an imported history.
+    pub(crate) fn genesis_record(&self) -> BalanceRecord {
+        BalanceRecord {
+            date: IMPORTED_DATE.to_string(),
+            balance: self.balance,
+            token_cost_delta: 0.0,
+            work_income_delta: 0.0,
+            trading_profit_delta: 0.0,
+            total_token_cost: self.total_token_cost,
+            total_work_income: self.total_work_income,
+            total_trading_profit: self.total_trading_profit,
+            net_worth: self.balance,
+            survival_status: self.survival_status.to_string(),
+            completed_tasks: Vec::new(),
+            task_id: None,
+            task_completion_time_seconds: None,
+            api_error: false,
+            total_refunds: self.total_refunds,
+            total_grant_income: self.total_grant_income,
+            total_interest_earned: self.total_interest_earned,
+            total_interest_paid: self.total_interest_paid,
+            grace_spent: 0.0,
+            checkpoint: false,
+            timestamp: Some(Utc::now()),
+            unlogged_changes: Vec::new(),
+        }
+    }
+}
+
+/// Remove every economic log from `dir`.
+pub(crate) fn clear_economic_data(dir: &Path) -> Result<()> {
+    for file in EVENT_LOGS.iter().chain([&BALANCE_LOG]) {
+        let path = dir.join(file);
+        if path.exists() {
+            fs::remove_file(&path).at_path(&path)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{EconomicConfig, EconomicError, EconomicTracker, SurvivalStatus};
+    use tempfile::TempDir;
+
+    fn config() -> EconomicConfig {
+        EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn snapshot_round_trips_balance_and_status() {
+        let source = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new("agent", config(), Some(source.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_model_tokens("gpt-4o", 1000, 500, "agent", Some(70.0)).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.add_work_income(5.0, "task-1", 0.9, "").unwrap();
+
+        let snapshot = tracker.export_snapshot().unwrap();
+        assert_eq!(snapshot.survival_status, SurvivalStatus::Struggling);
+        assert_eq!(snapshot.config_hash, config().config_hash());
+        assert_eq!(snapshot.daily.len(), 1);
+        let file = source.path().join("snapshot.json");
+        snapshot.save(&file).unwrap();
+        let snapshot = EconomicSnapshot::load(&file).unwrap();
+
+        let target = TempDir::new().unwrap();
+        EconomicTracker::import_snapshot(&snapshot, target.path(), false).unwrap();
+        let imported = EconomicTracker::new("agent", config(), Some(target.path().to_path_buf()));
+        imported.initialize().unwrap();
+        assert_eq!(imported.get_balance(), tracker.get_balance());
+        assert_eq!(imported.get_survival_status(), tracker.get_survival_status());
+        let summary = imported.get_summary();
+        assert_eq!(summary.total_token_cost, snapshot.total_token_cost);
+        assert_eq!(summary.total_work_income, snapshot.total_work_income);
+
+        let history = fs::read_to_string(target.path().join(BALANCE_LOG)).unwrap();
+        assert!(history.lines().next().unwrap().contains("\"imported\""));
+
+        // A non-empty directory is only overwritten with `force`
+        assert!(matches!(
+            EconomicTracker::import_snapshot(&snapshot, target.path(), false).unwrap_err(),
+            EconomicError::DataDirNotEmpty { .. }
+        ));
+        drop(imported);
+        EconomicTracker::import_snapshot(&snapshot, target.path(), true).unwrap();
+        let history = fs::read_to_string(target.path().join(BALANCE_LOG)).unwrap();
+        assert_eq!(history.lines().count(), 1);
+    }
+}
@@ -5,6 +5,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::time::SystemTime;
 
 /// Survival status based on balance percentage relative to initial capital.
 ///
@@ -64,6 +65,17 @@ impl SurvivalStatus {
         matches!(self, Self::Critical | Self::Bankrupt)
     }
 
+    /// Severity rank, from 0 (`Thriving`) to 4 (`Bankrupt`).
+    pub fn severity(&self) -> u8 {
+        match self {
+            Self::Thriving => 0,
+            Self::Stable => 1,
+            Self::Struggling => 2,
+            Self::Critical => 3,
+            Self::Bankrupt => 4,
+        }
+    }
+
     /// Get a human-readable emoji indicator.
     pub fn emoji(&self) -> &'static str {
         match self {
@@ -106,6 +118,57 @@ impl Default for SurvivalStatus {
     }
 }
 
+/// A move from one survival status to another, from
+/// `EconomicTracker::watch_survival_status`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SurvivalStatusChange {
+    /// Status before the change
+    pub previous: SurvivalStatus,
+    /// Status after the change
+    pub current: SurvivalStatus,
+    /// When the change happened
+    pub timestamp: SystemTime,
+    /// Balance change that caused it, e.g. `tokens tracked`
+    pub triggering_event: String,
+}
+
+/// A balance change broadcast by `EconomicTracker::subscribe_cost_events`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum CostEvent {
+    /// An LLM call was charged
+    TokensTracked {
+        /// Task charged, if any was active
+        task_id: Option<String>,
+        /// Model that served the call, when known
+        model: Option<String>,
+        /// Cost of the call in USD
+        cost: f64,
+        /// Balance after the charge
+        balance: f64,
+    },
+    /// A work payment was credited
+    IncomeReceived {
+        /// Task that earned the payment
+        task_id: String,
+        /// Amount credited in USD
+        amount: f64,
+        /// Balance after the credit
+        balance: f64,
+    },
+    /// The survival status changed
+    StatusChanged {
+        /// Status before the change
+        previous: SurvivalStatus,
+        /// Status after the change
+        current: SurvivalStatus,
+    },
+}
+
+/// Callback run by the tracker with the previous and the new status, see
+/// `EconomicTracker::on_status_change`.
+pub type StatusChangeCallback = Box<dyn Fn(SurvivalStatus, SurvivalStatus) + Send + Sync>;
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,6 +267,14 @@ mod tests {
         assert!(SurvivalStatus::Bankrupt.needs_intervention());
     }
 
+    #[test]
+    fn severity_orders_statuses() {
+        assert!(SurvivalStatus::Thriving.severity() < SurvivalStatus::Stable.severity());
+        assert!(SurvivalStatus::Stable.severity() < SurvivalStatus::Struggling.severity());
+        assert!(SurvivalStatus::Struggling.severity() < SurvivalStatus::Critical.severity());
+        assert!(SurvivalStatus::Critical.severity() < SurvivalStatus::Bankrupt.severity());
+    }
+
     #[test]
     fn display_format() {
         assert_eq!(format!("{}", SurvivalStatus::Thriving), "Thriving");
@@ -0,0 +1,526 @@
+//! Structured economic report.
+//!
+//! `EconomicTracker::get_summary_with` fills the cheap in-memory fields of
+//! [`EconomicSummary`] on every call; sections that read the JSONL logs are
+//! only built when enabled in [`SummaryOptions`], and fail the call when a
+//! log cannot be read.
+
+use super::forecast::StatusTransition;
+use super::status::SurvivalStatus;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Number of entries in each cost-driver ranking.
+const TOP_COST_DRIVERS: usize = 3;
+
+/// Share of the daily spend limit that raises an alert.
+const DAILY_LIMIT_WARNING: f64 = 0.8;
+
+/// Runway (in days) below which an alert is raised.
+const RUNWAY_WARNING_DAYS: f64 = 7.0;
+
+/// Terminal escape that resets colors set by `SurvivalStatus::ansi_color`.
+const ANSI_RESET: &str = "\x1b[0m";
+
+/// Section of [`EconomicSummary`] beyond its in-memory fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SummarySection {
+    /// Burn rate, runway, and the next projected status transition; reads
+    /// the logs
+    Forecast,
+    /// Top cost drivers by model, provider, and task; reads the logs
+    CostDrivers,
+    /// Income received over the last 7 days; reads the logs
+    Income,
+    /// Active alerts, derived from the other fields
+    Alerts,
+}
+
+impl SummarySection {
+    /// Every section, in report order.
+    pub const ALL: [SummarySection; 4] = [
+        SummarySection::Forecast,
+        SummarySection::CostDrivers,
+        SummarySection::Income,
+        SummarySection::Alerts,
+    ];
+
+    const fn bit(self) -> u8 {
+        1 << self as u8
+    }
+}
+
+/// Sections to build in `EconomicTracker::get_summary_with`.
+///
+/// The default only builds alerts; the sections that read the JSONL logs
+/// are opt-in. Serialized as the list of enabled sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "Vec<SummarySection>", into = "Vec<SummarySection>")]
+pub struct SummaryOptions {
+    sections: u8,
+}
+
+impl SummaryOptions {
+    /// Only in-memory state; no log reads and no alerts.
+    pub const fn minimal() -> Self {
+        Self { sections: 0 }
+    }
+
+    /// Every section, including those that read the logs.
+    pub const fn all() -> Self {
+        Self::minimal()
+            .with(SummarySection::Forecast)
+            .with(SummarySection::CostDrivers)
+            .with(SummarySection::Income)
+            .with(SummarySection::Alerts)
+    }
+
+    /// These options with `section` enabled.
+    #[must_use]
+    pub const fn with(self, section: SummarySection) -> Self {
+        Self {
+            sections: self.sections | section.bit(),
+        }
+    }
+
+    /// These options with `section` disabled.
+    #[must_use]
+    pub const fn without(self, section: SummarySection) -> Self {
+        Self {
+            sections: self.sections & !section.bit(),
+        }
+    }
+
+    /// Whether `section` is enabled.
+    pub const fn contains(self, section: SummarySection) -> bool {
+        self.sections & section.bit() != 0
+    }
+}
+
+impl Default for SummaryOptions {
+    fn default() -> Self {
+        Self::minimal().with(SummarySection::Alerts)
+    }
+}
+
+impl From<Vec<SummarySection>> for SummaryOptions {
+    fn from(sections: Vec<SummarySection>) -> Self {
+        sections
+            .into_iter()
+            .fold(Self::minimal(), |options, section| options.with(section))
+    }
+}
+
+impl From<SummaryOptions> for Vec<SummarySection> {
+    fn from(options: SummaryOptions) -> Self {
+        SummarySection::ALL
+            .into_iter()
+            .filter(|section| options.contains(*section))
+            .collect()
+    }
+}
+
+/// Daily net spend and how long the balance lasts at that rate.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BurnRate {
+    /// EWMA of daily net spend (negative means net income)
+    pub daily_net_spend: f64,
+    /// Days until the balance reaches zero (`None` while not burning)
+    pub runway_days: Option<f64>,
+}
+
+/// One entry in a cost-driver ranking.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CostDriver {
+    /// Model name or task ID
+    pub name: String,
+    /// Total cost in USD
+    pub cost: f64,
+}
+
+/// Largest costs by model, provider, and task, most expensive first.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CostDrivers {
+    pub by_model: Vec<CostDriver>,
+    #[serde(default)]
+    pub by_provider: Vec<CostDriver>,
+    pub by_task: Vec<CostDriver>,
+}
+
+impl CostDrivers {
+    /// Rank accumulated costs, keeping the top entries of each.
+    pub(crate) fn rank(
+        by_model: HashMap<String, f64>,
+        by_provider: HashMap<String, f64>,
+        by_task: HashMap<String, f64>,
+    ) -> Self {
+        Self {
+            by_model: top_drivers(by_model),
+            by_provider: top_drivers(by_provider),
+            by_task: top_drivers(by_task),
+        }
+    }
+}
+
+fn top_drivers(costs: HashMap<String, f64>) -> Vec<CostDriver> {
+    let mut drivers: Vec<CostDriver> = costs
+        .into_iter()
+        .filter(|(_, cost)| *cost > 0.0)
+        .map(|(name, cost)| CostDriver { name, cost })
+        .collect();
+    drivers.sort_by(|a, b| b.cost.total_cmp(&a.cost).then_with(|| a.name.cmp(&b.name)));
+    drivers.truncate(TOP_COST_DRIVERS);
+    drivers
+}
+
+/// Comprehensive economic summary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EconomicSummary {
+    pub signature: String,
+    pub balance: f64,
+    pub initial_balance: f64,
+    pub net_worth: f64,
+    pub total_token_cost: f64,
+    pub total_work_income: f64,
+    pub total_trading_profit: f64,
+    #[serde(default)]
+    pub total_refunds: f64,
+    #[serde(default)]
+    pub total_grant_income: f64,
+    #[serde(default)]
+    pub total_interest_earned: f64,
+    #[serde(default)]
+    pub total_interest_paid: f64,
+    pub session_cost: f64,
+    pub daily_cost: f64,
+    /// Configured daily spend limit, compared against `daily_cost`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub daily_spend_limit: Option<f64>,
+    /// Amount the balance is below zero
+    #[serde(default)]
+    pub outstanding_debt: f64,
+    /// Work income held in escrow after failed evaluations
+    #[serde(default)]
+    pub pending_escrow: f64,
+    /// Tasks that have ended, whatever their outcome
+    #[serde(default)]
+    pub tasks_ended: u64,
+    pub session_input_tokens: u64,
+    pub session_output_tokens: u64,
+    pub survival_status: SurvivalStatus,
+    pub is_bankrupt: bool,
+    pub min_evaluation_threshold: f64,
+    #[serde(default)]
+    pub intake_paused: bool,
+    /// Operating on the bankruptcy grace budget
+    #[serde(default)]
+    pub in_grace_period: bool,
+    #[serde(default)]
+    pub next_status_transition: Option<StatusTransition>,
+    /// Burn rate and runway (`SummarySection::Forecast`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub burn_rate: Option<BurnRate>,
+    /// Top cost drivers (`SummarySection::CostDrivers`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_drivers: Option<CostDrivers>,
+    /// Work and grant income over the last 7 days (`SummarySection::Income`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub income_this_week: Option<f64>,
+    /// Conditions needing attention (`SummarySection::Alerts`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub alerts: Vec<String>,
+}
+
+/// Change between two summaries, from [`EconomicSummary::diff`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EconomicSummaryDiff {
+    pub balance_change: f64,
+    /// Change in cumulative token and API cost
+    pub cost_change: f64,
+    /// Change in cumulative work and grant income
+    pub income_change: f64,
+    /// Change in the number of ended tasks
+    pub task_count_change: i64,
+    /// Earlier and later status, only when the status changed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_change: Option<(SurvivalStatus, SurvivalStatus)>,
+}
+
+impl EconomicSummaryDiff {
+    /// Income grew faster than costs and the status did not get worse.
+    pub fn is_improving(&self) -> bool {
+        let worsened = self
+            .status_change
+            .is_some_and(|(earlier, later)| later.severity() > earlier.severity());
+        self.income_change > self.cost_change && !worsened
+    }
+}
+
+impl EconomicSummary {
+    /// What changed since `earlier`, a summary taken before this one.
+    pub fn diff(&self, earlier: &EconomicSummary) -> EconomicSummaryDiff {
+        let income =
+            |summary: &EconomicSummary| summary.total_work_income + summary.total_grant_income;
+        EconomicSummaryDiff {
+            balance_change: self.balance - earlier.balance,
+            cost_change: self.total_token_cost - earlier.total_token_cost,
+            income_change: income(self) - income(earlier),
+            task_count_change: self.tasks_ended as i64 - earlier.tasks_ended as i64,
+            status_change: (self.survival_status != earlier.survival_status)
+                .then_some((earlier.survival_status, self.survival_status)),
+        }
+    }
+
+    /// Conditions needing attention, derived from the other fields.
+    pub(crate) fn collect_alerts(&self) -> Vec<String> {
+        let mut alerts = Vec::new();
+        if self.survival_status.needs_intervention() {
+            alerts.push(format!("Survival status is {}", self.survival_status));
+        }
+        if self.in_grace_period {
+            alerts.push("Operating on the bankruptcy grace period".to_string());
+        }
+        if self.intake_paused {
+            alerts.push("Task intake is paused".to_string());
+        }
+        if let Some(limit) = self.daily_spend_limit.filter(|limit| *limit > 0.0) {
+            if self.daily_cost >= limit {
+                alerts.push(format!(
+                    "Daily spend ${:.2} has reached the ${:.2} limit",
+                    self.daily_cost, limit
+                ));
+            } else if self.daily_cost >= limit * DAILY_LIMIT_WARNING {
+                alerts.push(format!(
+                    "Daily spend at {:.0}% of the ${:.2} limit",
+                    self.daily_cost / limit * 100.0,
+                    limit
+                ));
+            }
+        }
+        let runway = self.burn_rate.as_ref().and_then(|burn| burn.runway_days);
+        if let Some(days) = runway.filter(|days| *days < RUNWAY_WARNING_DAYS) {
+            alerts.push(format!("Runway is {days:.1} days"));
+        }
+        if self.outstanding_debt > 0.0 {
+            alerts.push(format!("Outstanding debt ${:.2}", self.outstanding_debt));
+        }
+        alerts
+    }
+
+    /// Render the summary as a colored terminal block.
+    pub fn render_text(&self) -> String {
+        let status = self.survival_status;
+        let color = status.ansi_color();
+        let mut out = String::new();
+
+        let (emoji, label) = if self.in_grace_period {
+            ("⏳", format!("{status} (grace period)"))
+        } else {
+            (status.emoji(), status.to_string())
+        };
+        let _ = writeln!(
+            out,
+            "{color}{emoji} {} — {label}{ANSI_RESET}",
+            self.signature
+        );
+        let _ = writeln!(
+            out,
+            "  Balance:      ${:.2} (started at ${:.2})",
+            self.balance, self.initial_balance
+        );
+        match self.daily_spend_limit {
+            Some(limit) => {
+                let _ = writeln!(
+                    out,
+                    "  Today:        ${:.2} of ${limit:.2}",
+                    self.daily_cost
+                );
+            }
+            None => {
+                let _ = writeln!(out, "  Today:        ${:.2}", self.daily_cost);
+            }
+        }
+        if let Some(burn) = &self.burn_rate {
+            let runway = burn.runway_days.map_or_else(
+                || "not burning".to_string(),
+                |days| format!("{days:.1} days"),
+            );
+            let _ = writeln!(
+                out,
+                "  Burn rate:    ${:.2}/day, runway {runway}",
+                burn.daily_net_spend
+            );
+        }
+        if let Some(drivers) = &self.cost_drivers {
+            for (label, ranking) in [
+                ("Top models:", &drivers.by_model),
+                ("Providers:", &drivers.by_provider),
+                ("Top tasks:", &drivers.by_task),
+            ] {
+                if ranking.is_empty() {
+                    continue;
+                }
+                let ranking: Vec<String> = ranking
+                    .iter()
+                    .map(|driver| format!("{} ${:.2}", driver.name, driver.cost))
+                    .collect();
+                let _ = writeln!(out, "  {label:<13} {}", ranking.join(", "));
+            }
+        }
+        if let Some(income) = self.income_this_week {
+            let _ = writeln!(out, "  Income (7d):  ${income:.2}");
+        }
+        if self.pending_escrow > 0.0 {
+            let _ = writeln!(out, "  Escrow:       ${:.2}", self.pending_escrow);
+        }
+        if self.outstanding_debt > 0.0 {
+            let _ = writeln!(out, "  Debt:         ${:.2}", self.outstanding_debt);
+        }
+        for alert in &self.alerts {
+            let _ = writeln!(out, "  {color}⚠ {alert}{ANSI_RESET}");
+        }
+
+        out
+    }
+}
+
+/// Funds available once near-term obligations are settled, from
+/// `EconomicTracker::get_working_capital`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkingCapital {
+    pub current_balance: f64,
+    /// Work income held in escrow, expected to be released
+    pub pending_escrow: f64,
+    /// Remaining budget of active tasks that set one
+    pub estimated_active_task_cost: f64,
+    /// Subscriptions due within the next 24 hours
+    pub upcoming_subscriptions: f64,
+    /// Balance plus escrow, less active task costs and subscriptions
+    pub net_working_capital: f64,
+    /// Survival status `net_working_capital` would give
+    pub status: SurvivalStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::{EconomicConfig, EconomicTracker};
+    use tempfile::TempDir;
+
+    #[test]
+    fn summary_sections_follow_options() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            daily_spend_limit: Some(10.0),
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-big", None, &[]).unwrap();
+        tracker.track_model_tokens("gpt-4o", 1000, 500, "agent", Some(6.0)).unwrap();
+        tracker.end_task("task-big").unwrap();
+        tracker.start_task("task-small", None, &[]).unwrap();
+        tracker.track_model_tokens("gpt-4o-mini", 1000, 500, "agent", Some(3.0)).unwrap();
+        tracker.end_task("task-small").unwrap();
+        tracker.add_grant_income(2.0, "platform", "").unwrap();
+
+        // Only in-memory state and alerts by default
+        let summary = tracker.get_summary();
+        assert!(summary.cost_drivers.is_none());
+        assert!(summary.burn_rate.is_none());
+        assert!(summary.income_this_week.is_none());
+        assert!(summary.alerts.iter().any(|alert| alert.contains("90%")));
+
+        let summary = tracker.get_summary_with(SummaryOptions::all()).unwrap();
+        let drivers = summary.cost_drivers.as_ref().unwrap();
+        assert_eq!(drivers.by_model[0].name, "gpt-4o");
+        assert_eq!(drivers.by_task.len(), 2);
+        assert_eq!(drivers.by_task[0].name, "task-big");
+        assert!((summary.income_this_week.unwrap() - 2.0).abs() < 1e-9);
+        assert!(summary.burn_rate.is_some());
+        assert!(summary.alerts.iter().any(|alert| alert.contains("90%")));
+
+        let text = summary.render_text();
+        assert!(text.starts_with(summary.survival_status.ansi_color()));
+        assert!(text.contains(summary.survival_status.emoji()));
+        assert!(text.contains("$9.00 of $10.00"));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["cost_drivers"]["by_model"][1]["name"], "gpt-4o-mini");
+
+        let minimal = tracker.get_summary_with(SummaryOptions::minimal()).unwrap();
+        assert!(minimal.cost_drivers.is_none());
+        assert!(minimal.burn_rate.is_none());
+        assert!(minimal.income_this_week.is_none());
+        assert!(minimal.alerts.is_empty());
+        assert!((minimal.balance - summary.balance).abs() < 1e-9);
+        let json = serde_json::to_value(&minimal).unwrap();
+        assert!(json.get("cost_drivers").is_none());
+
+        let options = SummaryOptions::all().without(SummarySection::Forecast);
+        let json = serde_json::to_value(options).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!(["cost_drivers", "income", "alerts"])
+        );
+        assert_eq!(
+            serde_json::from_value::<SummaryOptions>(json).unwrap(),
+            options
+        );
+
+        // A section whose log cannot be read fails the call
+        std::fs::remove_file(tmp.path().join("token_costs.jsonl")).unwrap();
+        std::fs::create_dir(tmp.path().join("token_costs.jsonl")).unwrap();
+        assert!(tracker.get_summary_with(SummaryOptions::all()).is_err());
+        assert!(tracker.get_summary_with(SummaryOptions::minimal()).is_ok());
+    }
+
+    #[test]
+    fn diff_reports_changes_and_status_transitions() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            enabled: true,
+            initial_balance: 100.0,
+            ..Default::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        let start = tracker.get_summary_with(SummaryOptions::minimal()).unwrap();
+
+        // Thriving -> Struggling
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_model_tokens("gpt-4o", 1000, 500, "agent", Some(70.0)).unwrap();
+        tracker.end_task("task-1").unwrap();
+        let spent = tracker.get_summary_with(SummaryOptions::minimal()).unwrap();
+        let diff = spent.diff(&start);
+        assert!((diff.balance_change + 70.0).abs() < 1e-9);
+        assert!((diff.cost_change - 70.0).abs() < 1e-9);
+        assert_eq!(diff.task_count_change, 1);
+        assert_eq!(
+            diff.status_change,
+            Some((SurvivalStatus::Thriving, SurvivalStatus::Struggling))
+        );
+        assert!(!diff.is_improving());
+
+        // Struggling -> Stable
+        tracker.add_work_income(40.0, "task-1", 0.9, "").unwrap();
+        let paid = tracker.get_summary_with(SummaryOptions::minimal()).unwrap();
+        let diff = paid.diff(&spent);
+        assert!((diff.income_change - 40.0).abs() < 1e-9);
+        assert_eq!(diff.task_count_change, 0);
+        assert_eq!(
+            diff.status_change,
+            Some((SurvivalStatus::Struggling, SurvivalStatus::Stable))
+        );
+        assert!(diff.is_improving());
+
+        let unchanged = paid.diff(&paid);
+        assert_eq!(unchanged.status_change, None);
+        assert!(!unchanged.is_improving());
+    }
+}
@@ -4,19 +4,74 @@
 //! the ClawWork LiveBench economic model. Persists state to JSONL files.
 
 use super::costs::{
-    ApiCallRecord, BalanceRecord, CostBreakdown, LlmCallRecord, LlmUsageSummary,
-    ApiUsageSummary, PricingModel, TaskCompletionRecord, TaskCostRecord, TokenPricing,
-    WorkIncomeRecord,
+    validate_metadata, AffordabilityDecision, AnomalySeverity, ApiCallRecord, ApiUsageSummary,
+    BalanceChange, BalanceRecord, BreakEvenAnalysis, CategoryPerformance, Charge, CostAnomaly,
+    CostBreakdown, CostCorrectionRecord, EconomicAnalytics, GrantIncomeRecord, ImagePricing,
+    InterestKind, InterestRecord, LlmCallRecord, LlmUsageEntry, LlmUsageSource, LlmUsageSummary,
+    ModelCostEntry, ModelTokenUsage, ObservedApiCall, ObservedLlmCall, OverBudgetTask,
+    OverheadRecord, PricingModel, PricingSimulationResult, ProrationStrategy, RecordReader,
+    RefundRecord, SensitivityReport, SpendingLimit, TaskAbortReason, TaskCompletionRecord,
+    TaskCostRecord, TaskCostSummary, TaskStatus, TimeOfUsePricing, TokenContext, TokenPricing,
+    TransferDirection, TransferRecord, WorkIncomeRecord,
 };
-use super::status::SurvivalStatus;
-use anyhow::{Context, Result};
-use chrono::{DateTime, Utc};
-use parking_lot::Mutex;
+use super::accounting::{
+    self, AccountingFormat, LedgerEntry, ADJUSTMENTS_ACCOUNT, CASH_ACCOUNT,
+    GRANT_INCOME_ACCOUNT, INCOME_ACCOUNT, INTEREST_EXPENSE_ACCOUNT, INTEREST_INCOME_ACCOUNT,
+    OPENING_BALANCE_ACCOUNT, REFUNDS_ACCOUNT,
+};
+#[cfg(feature = "compress")]
+use super::archive::{self, ArchiveSummary};
+use super::classifier::{ClassificationResult, OccupationCategory};
+use super::distribution::{self, DurationBucket};
+use super::error::{EconomicError, IoResultExt, Result};
+use super::evaluation::{EscrowEventKind, EscrowRecord, EvaluationOutcome, QualityEvaluator};
+use super::forecast::{MonthlyProjection, SpendForecast, DEFAULT_FORECAST_ALPHA};
+use super::goal::{GoalProgress, IncomeGoal};
+use super::grace::{GraceEvent, GraceEventKind, GracePolicy, OperationalState};
+use super::history::{BalanceGranularity, BalanceHistory, CostLogRecord};
+use super::intake::{IntakeEvent, IntakePolicy, WorkAdmission};
+use super::integrity::{self, IntegrityReport};
+use super::logs::{self, LoggedRecord};
+use super::merge::{self, MergeReport};
+use super::persistence::{PersistencePolicy, WriteBuffer};
+use super::range::{DateRange, RangeCostSummary, RangeIncomeSummary, RangeTaskSummary};
+use super::retention::RetentionPolicy;
+#[cfg(feature = "compress")]
+use super::retention::{RetentionReport, RetentionRunner};
+use super::snapshot::{self, EconomicSnapshot, SNAPSHOT_DAYS, SNAPSHOT_VERSION};
+use super::status::{CostEvent, StatusChangeCallback, SurvivalStatus, SurvivalStatusChange};
+use super::summary::{
+    BurnRate, CostDrivers, EconomicSummary, SummaryOptions, SummarySection, WorkingCapital,
+};
+use super::validation::{run_validators, IncomeValidator, WorkIncomeCandidate};
+use crate::cost::pricing::file_modified;
+use crate::cost::{CostEstimate, ImageSizeClass, SharedPricing};
+use chrono::{DateTime, NaiveDate, Timelike, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fs::{self, File, OpenOptions};
 use std::io::{BufRead, BufReader, Write};
-use std::path::PathBuf;
+use std::ops::{Bound, RangeBounds};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use std::time::{Instant, SystemTime};
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+
+/// Forecast horizon used for `EconomicSummary::next_status_transition`.
+const SUMMARY_FORECAST_DAYS: u32 = 90;
+
+/// Status changes buffered per `watch_survival_status` subscriber.
+const STATUS_CHANGE_CAPACITY: usize = 64;
+
+/// Average share of a model's context window below which
+/// `get_context_utilization` logs that input tokens are oversized.
+const LOW_CONTEXT_UTILIZATION: f64 = 0.2;
 
 /// Economic configuration options.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -33,6 +88,128 @@ pub struct EconomicConfig {
     /// Minimum evaluation score to receive payment (0.0-1.0)
     #[serde(default = "default_min_threshold")]
     pub min_evaluation_threshold: f64,
+    /// Embedding pricing by model (USD per million tokens)
+    #[serde(default)]
+    pub embedding_pricing: HashMap<String, f64>,
+    /// Embedding price for models missing from `embedding_pricing`
+    #[serde(default = "default_embedding_price")]
+    pub default_embedding_price_per_million: f64,
+    /// Image-generation pricing by model
+    #[serde(default)]
+    pub image_pricing: HashMap<String, ImagePricing>,
+    /// Image pricing for models missing from `image_pricing`
+    #[serde(default)]
+    pub default_image_pricing: ImagePricing,
+    /// Discount applied to cached input tokens (0.9 = 90% cheaper)
+    #[serde(default = "default_cache_discount_rate")]
+    pub cache_discount_rate: f64,
+    /// EWMA smoothing factor for spend forecasts (higher reacts faster)
+    #[serde(default = "default_forecast_alpha")]
+    pub forecast_alpha: f64,
+    /// Invoked with the agent signature when the balance crosses into
+    /// `Bankrupt` (fires once per crossing, not on every later cost)
+    #[serde(skip)]
+    pub on_bankruptcy: Option<BankruptcyCallback>,
+    /// When to pause and resume task intake based on survival status
+    #[serde(default)]
+    pub intake_policy: IntakePolicy,
+    /// Invoked with the agent signature and the event whenever the intake
+    /// policy pauses or resumes intake
+    #[serde(skip)]
+    pub on_intake_change: Option<IntakeCallback>,
+    /// Daily interest earned on a positive balance (0.0001 = 0.01%/day),
+    /// applied by `EconomicTracker::accrue_interest`
+    #[serde(default)]
+    pub daily_yield_rate: f64,
+    /// Daily interest charged on a negative balance
+    #[serde(default)]
+    pub daily_debt_rate: f64,
+    /// Daily spend limit in USD reported against today's spend
+    #[serde(default)]
+    pub daily_spend_limit: Option<f64>,
+    /// How long the agent keeps operating after the balance reaches zero
+    /// before it is bankrupt
+    #[serde(default)]
+    pub bankruptcy_grace: GracePolicy,
+    /// Most a single task may spend in USD; once a task reaches it, further
+    /// LLM calls for that task are rejected
+    #[serde(default)]
+    pub max_cost_per_task: Option<f64>,
+    /// Balance in USD that `can_afford` keeps out of reach of new requests
+    #[serde(default)]
+    pub reserve_floor: Option<f64>,
+    /// When raw records expire and whether they are rolled up, archived, or
+    /// deleted (see `EconomicTracker::apply_retention`)
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    /// Seal each new JSONL record into a per-file SHA-256 hash chain so
+    /// edits can be detected with `EconomicTracker::verify_integrity`
+    #[serde(default)]
+    pub record_integrity: bool,
+    /// When logged records are written to disk; by default each record is
+    /// synced as it is logged (see `PersistencePolicy`)
+    #[serde(default)]
+    pub persistence: PersistencePolicy,
+    /// Startup cost in USD (API key fees, initialization) that tasks must
+    /// earn back, see `EconomicTracker::get_break_even_task_count`
+    #[serde(default)]
+    pub fixed_overhead_usd: f64,
+    /// Whether LLM usage is recorded from explicit `track_*` calls or from
+    /// observer events; the other source is ignored
+    #[serde(default)]
+    pub llm_usage_source: LlmUsageSource,
+    /// Events a `subscribe_cost_events` receiver may fall behind by before
+    /// it skips the oldest ones
+    #[serde(default = "default_event_buffer_size")]
+    pub event_buffer_size: usize,
+}
+
+/// Callback invoked with the agent signature when the agent goes bankrupt.
+///
+/// Wraps the closure in an `Arc` so [`EconomicConfig`] stays `Clone`.
+#[derive(Clone)]
+pub struct BankruptcyCallback(Arc<dyn Fn(&str) + Send + Sync>);
+
+impl BankruptcyCallback {
+    /// Wrap a closure as a bankruptcy callback.
+    pub fn new(callback: impl Fn(&str) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    fn call(&self, signature: &str) {
+        (self.0)(signature);
+    }
+}
+
+impl std::fmt::Debug for BankruptcyCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BankruptcyCallback(..)")
+    }
+}
+
+/// Closure wrapped by [`IntakeCallback`].
+type IntakeFn = dyn Fn(&str, &IntakeEvent) + Send + Sync;
+
+/// Callback invoked with the agent signature when task intake is paused or
+/// resumed.
+#[derive(Clone)]
+pub struct IntakeCallback(Arc<IntakeFn>);
+
+impl IntakeCallback {
+    /// Wrap a closure as an intake callback.
+    pub fn new(callback: impl Fn(&str, &IntakeEvent) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(callback))
+    }
+
+    fn call(&self, signature: &str, event: &IntakeEvent) {
+        (self.0)(signature, event);
+    }
+}
+
+impl std::fmt::Debug for IntakeCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("IntakeCallback(..)")
+    }
 }
 
 fn default_initial_balance() -> f64 {
@@ -43,6 +220,23 @@ fn default_min_threshold() -> f64 {
     0.6
 }
 
+fn default_embedding_price() -> f64 {
+    // OpenAI text-embedding-3-small
+    0.02
+}
+
+fn default_cache_discount_rate() -> f64 {
+    0.9
+}
+
+fn default_forecast_alpha() -> f64 {
+    DEFAULT_FORECAST_ALPHA
+}
+
+fn default_event_buffer_size() -> usize {
+    128
+}
+
 impl Default for EconomicConfig {
     fn default() -> Self {
         Self {
@@ -50,35 +244,167 @@ impl Default for EconomicConfig {
             initial_balance: default_initial_balance(),
             token_pricing: TokenPricing::default(),
             min_evaluation_threshold: default_min_threshold(),
+            embedding_pricing: HashMap::new(),
+            default_embedding_price_per_million: default_embedding_price(),
+            image_pricing: HashMap::new(),
+            default_image_pricing: ImagePricing::default(),
+            cache_discount_rate: default_cache_discount_rate(),
+            forecast_alpha: default_forecast_alpha(),
+            on_bankruptcy: None,
+            intake_policy: IntakePolicy::default(),
+            on_intake_change: None,
+            daily_yield_rate: 0.0,
+            daily_debt_rate: 0.0,
+            daily_spend_limit: None,
+            max_cost_per_task: None,
+            reserve_floor: None,
+            bankruptcy_grace: GracePolicy::default(),
+            retention: RetentionPolicy::default(),
+            record_integrity: false,
+            persistence: PersistencePolicy::default(),
+            fixed_overhead_usd: 0.0,
+            llm_usage_source: LlmUsageSource::default(),
+            event_buffer_size: default_event_buffer_size(),
+        }
+    }
+}
+
+impl EconomicConfig {
+    /// SHA-256 of the serialized configuration, hex-encoded.
+    ///
+    /// Callbacks are not serialized and so do not affect the hash.
+    pub fn config_hash(&self) -> String {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        hex::encode(Sha256::digest(value.to_string().as_bytes()))
+    }
+
+    /// Check that configured values are in range.
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |message: String| Err(EconomicError::InvalidConfig(message));
+        if !self.initial_balance.is_finite() {
+            return invalid(format!(
+                "initial_balance must be finite, got {}",
+                self.initial_balance
+            ));
+        }
+        if !(0.0..=1.0).contains(&self.min_evaluation_threshold) {
+            return invalid(format!(
+                "min_evaluation_threshold must be between 0 and 1, got {}",
+                self.min_evaluation_threshold
+            ));
+        }
+        for (name, value) in [
+            ("daily_yield_rate", self.daily_yield_rate),
+            ("daily_debt_rate", self.daily_debt_rate),
+            ("daily_spend_limit", self.daily_spend_limit.unwrap_or(0.0)),
+            ("max_cost_per_task", self.max_cost_per_task.unwrap_or(0.0)),
+            ("reserve_floor", self.reserve_floor.unwrap_or(0.0)),
+            ("fixed_overhead_usd", self.fixed_overhead_usd),
+            ("bankruptcy_grace.hours", self.bankruptcy_grace.hours),
+            (
+                "bankruptcy_grace.spend_usd",
+                self.bankruptcy_grace.spend_usd,
+            ),
+        ] {
+            if !(value.is_finite() && value >= 0.0) {
+                return invalid(format!("{name} must be non-negative, got {value}"));
+            }
+        }
+        validate_token_pricing(&self.token_pricing)
+    }
+}
+
+/// Check that token prices are finite and non-negative.
+fn validate_token_pricing(pricing: &TokenPricing) -> Result<()> {
+    for (name, value) in [
+        ("input_price_per_million", pricing.input_price_per_million),
+        ("output_price_per_million", pricing.output_price_per_million),
+    ] {
+        if !(value.is_finite() && value >= 0.0) {
+            return Err(EconomicError::InvalidConfig(format!(
+                "token_pricing.{name} must be non-negative, got {value}"
+            )));
         }
     }
+    Ok(())
 }
 
 /// Task-level tracking state (in-memory during task execution).
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 struct TaskState {
-    /// Current task ID
-    task_id: Option<String>,
+    /// Task ID
+    task_id: String,
     /// Date the task was assigned
-    task_date: Option<String>,
+    task_date: String,
     /// Task start timestamp
-    start_time: Option<DateTime<Utc>>,
+    start_time: DateTime<Utc>,
+    /// Normalized task tags
+    tags: Vec<String>,
+    /// Caller-supplied metadata, copied onto the task's records
+    metadata: BTreeMap<String, String>,
+    /// Cost the task is expected to stay within (USD)
+    estimated_budget: Option<f64>,
+    /// Occupation category, copied onto the task's completion record
+    category: Option<OccupationCategory>,
     /// Costs accumulated for this task
     costs: CostBreakdown,
     /// LLM call records
     llm_calls: Vec<LlmCallRecord>,
     /// API call records
     api_calls: Vec<ApiCallRecord>,
+    /// Tracing span covering the task; events recorded while the task is
+    /// current are nested under it
+    span: tracing::Span,
 }
 
 impl TaskState {
-    fn reset(&mut self) {
-        self.task_id = None;
-        self.task_date = None;
-        self.start_time = None;
-        self.costs.reset();
-        self.llm_calls.clear();
-        self.api_calls.clear();
+    /// Build the cost summary for this task as of `end`.
+    fn summary(&self, end: DateTime<Utc>) -> TaskCostSummary {
+        let mut tokens_by_model: HashMap<String, ModelTokenUsage> = HashMap::new();
+        for call in &self.llm_calls {
+            let model = call.model.as_deref().unwrap_or("unknown");
+            let usage = tokens_by_model.entry(model.to_string()).or_default();
+            usage.input_tokens += call.input_tokens;
+            usage.output_tokens += call.output_tokens;
+            usage.calls += 1;
+        }
+
+        TaskCostSummary {
+            costs: self.costs.clone(),
+            total: self.costs.total(),
+            date: self.task_date.clone(),
+            task_id: self.task_id.clone(),
+            tags: self.tags.clone(),
+            tokens_by_model,
+            llm_calls: self.llm_calls.len(),
+            duration_seconds: (end - self.start_time).num_milliseconds() as f64 / 1000.0,
+            metadata: self.metadata.clone(),
+            prorated_overhead: 0.0,
+        }
+    }
+}
+
+/// Service category a non-LLM API cost is attributed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ApiCategory {
+    Search,
+    Ocr,
+    Embedding,
+    ImageGeneration,
+    Other,
+}
+
+impl ApiCategory {
+    /// Categorize a generic API call by its name.
+    fn from_api_name(api_name: &str) -> Self {
+        let api_lower = api_name.to_lowercase();
+        if api_lower.contains("search") || api_lower.contains("jina") || api_lower.contains("tavily") {
+            Self::Search
+        } else if api_lower.contains("ocr") {
+            Self::Ocr
+        } else {
+            Self::Other
+        }
     }
 }
 
@@ -105,8 +431,10 @@ impl DailyState {
 }
 
 /// Session tracking state.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 struct SessionState {
+    /// When the session began
+    started_at: DateTime<Utc>,
     /// Input tokens this session
     input_tokens: u64,
     /// Output tokens this session
@@ -115,11 +443,20 @@ struct SessionState {
     cost: f64,
 }
 
+impl Default for SessionState {
+    fn default() -> Self {
+        Self {
+            started_at: Utc::now(),
+            input_tokens: 0,
+            output_tokens: 0,
+            cost: 0.0,
+        }
+    }
+}
+
 impl SessionState {
     fn reset(&mut self) {
-        self.input_tokens = 0;
-        self.output_tokens = 0;
-        self.cost = 0.0;
+        *self = Self::default();
     }
 }
 
@@ -142,6 +479,35 @@ pub struct EconomicTracker {
     data_path: PathBuf,
     /// Current balance (protected by mutex for thread safety)
     state: Arc<Mutex<TrackerState>>,
+    /// Custom payment gates run by `add_work_income`
+    income_validators: RwLock<Vec<IncomeValidator>>,
+    /// Scores deliverables for `complete_and_evaluate`
+    quality_evaluator: RwLock<Option<Arc<dyn QualityEvaluator>>>,
+    /// Token prices, starting from `config.token_pricing`; replaced by
+    /// `reload_pricing`
+    token_pricing: RwLock<TokenPricing>,
+    /// Pricing file watched by `watch_pricing_file`, with its modification
+    /// time when last loaded
+    pricing_file: Mutex<Option<(PathBuf, Option<SystemTime>)>>,
+    /// Per-model prices set by `set_model_pricing`; calls that name a model
+    /// are priced from them instead of `token_pricing`
+    model_pricing: RwLock<Option<Arc<SharedPricing>>>,
+    /// Survival status changes, for `watch_survival_status`
+    status_changes: broadcast::Sender<SurvivalStatusChange>,
+    /// Cost, income and status events, for `subscribe_cost_events`
+    cost_events: broadcast::Sender<CostEvent>,
+    /// Callbacks registered with `on_status_change`
+    status_callbacks: RwLock<Vec<SharedStatusCallback>>,
+    /// Status changes found while the state lock is held, run through
+    /// `status_callbacks` by `notify_status_changes` after it is released
+    pending_status_changes: Mutex<Vec<(SurvivalStatus, SurvivalStatus)>>,
+    /// Hash of the last sealed record per log file, loaded on first append;
+    /// held while appending so each file's chain stays in order
+    integrity_heads: Mutex<HashMap<PathBuf, Option<String>>>,
+    /// Records logged but not yet written, under `config.persistence`.
+    /// Also the lock every append to and rewrite of the logs goes through,
+    /// so a rewrite never loses a record appended while it runs
+    write_buffer: Mutex<WriteBuffer>,
 }
 
 /// Internal mutable state.
@@ -154,12 +520,110 @@ struct TrackerState {
     total_token_cost: f64,
     total_work_income: f64,
     total_trading_profit: f64,
-    /// Task-level tracking
-    task: TaskState,
+    /// Active tasks keyed by task ID
+    tasks: HashMap<String, TaskState>,
+    /// Task that new costs are attributed to (the most recently started one)
+    current_task: Option<String>,
     /// Daily tracking
     daily: DailyState,
     /// Session tracking
     session: SessionState,
+    /// Whether the current bankruptcy has already been reported
+    bankruptcy_notified: bool,
+    /// Whether the balance changed since the last persisted balance record
+    dirty: bool,
+    /// Balance changes with no log of their own since the last persisted
+    /// balance record, which carries them
+    unlogged_changes: Vec<BalanceChange>,
+    /// Cumulative refunds credited
+    total_refunds: f64,
+    /// Refunds credited so far, by original charge ID; loaded from the
+    /// refund log and its archives by the first refund
+    refunded: Option<HashMap<String, f64>>,
+    /// Net cost corrections by task ID; loaded from the correction log by
+    /// the first override
+    cost_corrections: Option<HashMap<String, f64>>,
+    /// Cumulative grant income
+    total_grant_income: f64,
+    /// When the intake policy paused task intake (`None` while accepting)
+    intake_paused_since: Option<DateTime<Utc>>,
+    /// Cumulative interest earned and paid
+    total_interest_earned: f64,
+    total_interest_paid: f64,
+    /// Last day interest was accrued for
+    last_interest_date: Option<NaiveDate>,
+    /// When the bankruptcy grace period began (`None` outside grace)
+    grace_since: Option<DateTime<Utc>>,
+    /// Costs recorded since the grace period began
+    grace_spent: f64,
+    /// Time-of-use multipliers for computed token costs
+    time_of_use: TimeOfUsePricing,
+    /// Context window sizes in tokens, by model
+    context_windows: HashMap<String, u64>,
+    /// ID of the most recent LLM or API charge
+    last_charge_id: Option<String>,
+    /// Payments held in escrow, by task ID
+    escrow: HashMap<String, f64>,
+    /// Tasks with a record in `task_completions.jsonl`
+    tasks_ended: u64,
+    /// When `initialize` last ran (tracker creation until then)
+    initialized_at: DateTime<Utc>,
+    /// Monotonic counterpart of `initialized_at`
+    initialized_instant: Instant,
+    /// Active income goal and when it was set
+    income_goal: Option<(IncomeGoal, DateTime<Utc>)>,
+    /// Subscription charges (amount, next due time), by name
+    subscriptions: HashMap<String, (f64, DateTime<Utc>)>,
+    /// Shared cost waiting to be added to the next task that ends,
+    /// restored from `overhead.jsonl`
+    pending_overhead: f64,
+    /// Shared cost written off as overhead, restored from `overhead.jsonl`
+    written_off_overhead: f64,
+}
+
+/// A [`StatusChangeCallback`] that can be cloned out of the registry before
+/// it runs.
+type SharedStatusCallback = Arc<dyn Fn(SurvivalStatus, SurvivalStatus) + Send + Sync>;
+
+/// Bankruptcy transitions found while the state lock is held, acted on by
+/// `notify_bankruptcy` after it is released.
+#[derive(Debug, Default)]
+struct BankruptcyChange {
+    /// The agent just became bankrupt
+    crossed: bool,
+    /// Grace period transition to persist
+    grace: Option<GraceEvent>,
+}
+
+/// One side of a transfer applied to the state, waiting to be persisted
+/// once the locks are released.
+struct PendingTransfer {
+    record: TransferRecord,
+    bankruptcy: BankruptcyChange,
+    intake_change: Option<IntakeEvent>,
+}
+
+/// Moments of evaluation score against payment over the work income log.
+struct IncomeQualityFit {
+    mean_score: f64,
+    mean_payment: f64,
+    /// Sum of squared score deviations
+    score_variation: f64,
+    /// Sum of squared payment deviations
+    payment_variation: f64,
+    /// Sum of products of score and payment deviations
+    covariation: f64,
+}
+
+impl TrackerState {
+    fn current_task(&self) -> Option<&TaskState> {
+        self.current_task.as_ref().and_then(|id| self.tasks.get(id))
+    }
+
+    fn current_task_mut(&mut self) -> Option<&mut TaskState> {
+        let id = self.current_task.as_ref()?;
+        self.tasks.get_mut(id)
+    }
 }
 
 impl EconomicTracker {
@@ -187,20 +651,60 @@ impl EconomicTracker {
                 total_token_cost: 0.0,
                 total_work_income: 0.0,
                 total_trading_profit: 0.0,
-                task: TaskState::default(),
+                tasks: HashMap::new(),
+                current_task: None,
                 daily: DailyState::default(),
                 session: SessionState::default(),
+                bankruptcy_notified: false,
+                dirty: false,
+                unlogged_changes: Vec::new(),
+                total_refunds: 0.0,
+                refunded: None,
+                cost_corrections: None,
+                total_grant_income: 0.0,
+                intake_paused_since: None,
+                total_interest_earned: 0.0,
+                total_interest_paid: 0.0,
+                last_interest_date: None,
+                grace_since: None,
+                grace_spent: 0.0,
+                time_of_use: TimeOfUsePricing::default(),
+                context_windows: HashMap::new(),
+                last_charge_id: None,
+                escrow: HashMap::new(),
+                tasks_ended: 0,
+                initialized_at: Utc::now(),
+                initialized_instant: Instant::now(),
+                income_goal: None,
+                subscriptions: HashMap::new(),
+                pending_overhead: 0.0,
+                written_off_overhead: 0.0,
             })),
+            token_pricing: RwLock::new(config.token_pricing.clone()),
+            write_buffer: Mutex::new(WriteBuffer::new(config.persistence.clone())),
+            cost_events: broadcast::channel(config.event_buffer_size.max(1)).0,
             config,
             data_path,
+            income_validators: RwLock::new(Vec::new()),
+            quality_evaluator: RwLock::new(None),
+            pricing_file: Mutex::new(None),
+            model_pricing: RwLock::new(None),
+            status_changes: broadcast::channel(STATUS_CHANGE_CAPACITY).0,
+            status_callbacks: RwLock::new(Vec::new()),
+            pending_status_changes: Mutex::new(Vec::new()),
+            integrity_heads: Mutex::new(HashMap::new()),
         }
     }
 
     /// Initialize the tracker, loading existing state or creating new.
     pub fn initialize(&self) -> Result<()> {
-        fs::create_dir_all(&self.data_path).with_context(|| {
-            format!("Failed to create data directory: {}", self.data_path.display())
-        })?;
+        self.config.validate()?;
+        fs::create_dir_all(&self.data_path).at_path(&self.data_path)?;
+        {
+            let mut state = self.state.lock();
+            state.initialized_at = Utc::now();
+            state.initialized_instant = Instant::now();
+        }
 
         let balance_file = self.balance_file_path();
 
@@ -221,6 +725,7 @@ impl EconomicTracker {
                 0.0,
                 Vec::new(),
                 false,
+                false,
             )?;
             tracing::info!(
                 "✅ Initialized economic tracker for {}: starting balance=${:.2}",
@@ -229,642 +734,7949 @@ impl EconomicTracker {
             );
         }
 
+        // Restore paused intake, then re-apply the (possibly changed) policy
+        let mut last_intake: Option<IntakeEvent> = None;
+        self.for_each_record::<IntakeEvent, _>(&self.intake_file_path(), |event| {
+            last_intake = Some(event);
+        })?;
+        let intake_change = {
+            let mut state = self.state.lock();
+            state.intake_paused_since = last_intake
+                .filter(|event| event.paused)
+                .map(|event| event.timestamp);
+            self.update_intake(&mut state)
+        };
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+
+        // Resume an unfinished grace period, which may have run out since
+        let mut last_grace: Option<GraceEvent> = None;
+        self.for_each_record::<GraceEvent, _>(&self.grace_file_path(), |event| {
+            last_grace = Some(event);
+        })?;
+        if let Some(event) = last_grace.filter(|event| event.kind == GraceEventKind::Entered) {
+            let bankruptcy = {
+                let mut state = self.state.lock();
+                if self.get_survival_status_inner(&state) == SurvivalStatus::Bankrupt {
+                    // The spend so far was restored with the balance: any
+                    // snapshot taken in bankruptcy was taken during this grace
+                    state.grace_since = Some(event.timestamp);
+                    state.bankruptcy_notified = false;
+                } else {
+                    state.grace_spent = 0.0;
+                }
+                self.update_bankruptcy_flag(&mut state)
+            };
+            self.notify_bankruptcy(bankruptcy);
+        }
+
+        let mut last_interest_date = None;
+        self.for_each_record::<InterestRecord, _>(&self.interest_file_path(), |record| {
+            last_interest_date = record.date.parse::<NaiveDate>().ok().or(last_interest_date);
+        })?;
+        self.state.lock().last_interest_date = last_interest_date;
+
+        let mut escrow = HashMap::new();
+        self.for_each_record::<EscrowRecord, _>(&self.escrow_file_path(), |record| {
+            match record.kind {
+                EscrowEventKind::Held => escrow.insert(record.task_id, record.amount),
+                EscrowEventKind::Released => escrow.remove(&record.task_id),
+            };
+        })?;
+        self.state.lock().escrow = escrow;
+
+        let mut last_overhead: Option<OverheadRecord> = None;
+        self.for_each_record::<OverheadRecord, _>(&self.overhead_file_path(), |record| {
+            last_overhead = Some(record);
+        })?;
+        {
+            let mut state = self.state.lock();
+            state.pending_overhead = last_overhead
+                .as_ref()
+                .map_or(0.0, |record| record.pending_overhead);
+            state.written_off_overhead = last_overhead
+                .as_ref()
+                .map_or(0.0, |record| record.written_off_overhead);
+        }
+        // Reloaded from disk by the next refund or cost override
+        {
+            let mut state = self.state.lock();
+            state.refunded = None;
+            state.cost_corrections = None;
+        }
+
+        let mut tasks_ended = 0;
+        self.for_each_record::<TaskCompletionRecord, _>(&self.task_completions_file_path(), |_| {
+            tasks_ended += 1;
+        })?;
+        self.state.lock().tasks_ended = tasks_ended;
+
         Ok(())
     }
 
     /// Start tracking costs for a new task.
-    pub fn start_task(&self, task_id: impl Into<String>, date: Option<String>) {
-        let task_id = task_id.into();
-        let date = date.unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
-        let now = Utc::now();
+    ///
+    /// The new task becomes the current task: subsequent costs are attributed
+    /// to it until it ends or another task starts. Starting an id that is
+    /// already active restarts its tracking from zero.
+    ///
+    /// `tags` are free-form labels (e.g. `"client:acme"`) used to slice costs
+    /// in analytics; they are trimmed, lowercased, and deduplicated.
+    ///
+    /// # Errors
+    /// Returns [`EconomicError::IntakePaused`] while the intake policy has
+    /// paused intake (see [`can_accept_work`](Self::can_accept_work)); use
+    /// [`start_task_forced`](Self::start_task_forced) to override.
+    pub fn start_task(
+        &self,
+        task_id: impl Into<String>,
+        date: Option<String>,
+        tags: &[&str],
+    ) -> Result<()> {
+        self.start_task_with_metadata(task_id, date, tags, BTreeMap::new())
+    }
 
-        let mut state = self.state.lock();
-        state.task.task_id = Some(task_id.clone());
-        state.task.task_date = Some(date);
-        state.task.start_time = Some(now);
-        state.task.costs.reset();
-        state.task.llm_calls.clear();
-        state.task.api_calls.clear();
+    /// Start a task carrying `metadata` (e.g. run id, prompt template
+    /// version, customer id), which is copied onto its cost and completion
+    /// records for downstream joins.
+    ///
+    /// Same as [`start_task`](Self::start_task) otherwise.
+    ///
+    /// # Errors
+    /// [`EconomicError::MetadataTooLarge`] if `metadata` has more than
+    /// `MAX_METADATA_KEYS` entries or `MAX_METADATA_BYTES` bytes, and the
+    /// errors of `start_task`.
+    pub fn start_task_with_metadata(
+        &self,
+        task_id: impl Into<String>,
+        date: Option<String>,
+        tags: &[&str],
+        metadata: BTreeMap<String, String>,
+    ) -> Result<()> {
+        validate_metadata(&metadata)?;
+        self.admit_task(task_id.into(), date, tags, metadata, None)
+    }
 
-        // Track daily window
-        if state.daily.first_task_start.is_none() {
-            state.daily.first_task_start = Some(now);
+    /// Start a task expected to cost at most `estimated_budget` USD (e.g.
+    /// the `max_payment` of its classification). The budget is kept on the
+    /// task's cost record, so overruns show up in
+    /// [`get_tasks_over_budget`](Self::get_tasks_over_budget); `None` sets
+    /// no budget.
+    ///
+    /// Same as [`start_task`](Self::start_task) otherwise.
+    ///
+    /// # Errors
+    /// [`EconomicError::InvalidAmount`] if the budget is not a positive,
+    /// finite amount, and the errors of `start_task`.
+    pub fn start_task_with_budget(
+        &self,
+        task_id: impl Into<String>,
+        date: Option<String>,
+        tags: &[&str],
+        estimated_budget: Option<f64>,
+    ) -> Result<()> {
+        if let Some(amount) = estimated_budget.filter(|b| !(b.is_finite() && *b > 0.0)) {
+            return Err(EconomicError::InvalidAmount { amount });
         }
-        state.daily.task_ids.push(task_id);
+        self.admit_task(task_id.into(), date, tags, BTreeMap::new(), estimated_budget)
     }
 
-    /// End tracking for current task and save consolidated record.
-    pub fn end_task(&self) -> Result<()> {
+    /// Start a task unless the intake policy has paused intake.
+    fn admit_task(
+        &self,
+        task_id: String,
+        date: Option<String>,
+        tags: &[&str],
+        metadata: BTreeMap<String, String>,
+        estimated_budget: Option<f64>,
+    ) -> Result<()> {
         let mut state = self.state.lock();
-
-        if state.task.task_id.is_some() {
-            self.save_task_record_inner(&state)?;
-            state.daily.last_task_end = Some(Utc::now());
-            state.task.reset();
+        if state.intake_paused_since.is_some() {
+            let status = self.get_survival_status_inner(&state);
+            return Err(EconomicError::IntakePaused { status });
         }
-
+        self.insert_task(&mut state, task_id, date, tags, metadata, estimated_budget);
         Ok(())
     }
 
-    /// Track LLM token usage.
-    ///
-    /// # Arguments
-    /// * `input_tokens` - Number of input tokens
-    /// * `output_tokens` - Number of output tokens
-    /// * `api_name` - Origin of the call (e.g., "agent", "wrapup")
-    /// * `cost` - Pre-computed cost (if provided, skips local calculation)
+    /// Start a task even while intake is paused.
     ///
-    /// # Returns
-    /// The cost in USD for this call.
-    pub fn track_tokens(
+    /// Same as [`start_task`](Self::start_task) otherwise.
+    pub fn start_task_forced(
         &self,
-        input_tokens: u64,
-        output_tokens: u64,
-        api_name: impl Into<String>,
-        cost: Option<f64>,
-    ) -> f64 {
-        let api_name = api_name.into();
-        let cost = cost.unwrap_or_else(|| {
-            self.config.token_pricing.calculate_cost(input_tokens, output_tokens)
-        });
-
+        task_id: impl Into<String>,
+        date: Option<String>,
+        tags: &[&str],
+    ) {
+        let task_id = task_id.into();
         let mut state = self.state.lock();
+        if state.intake_paused_since.is_some() {
+            tracing::warn!(
+                agent_id = %self.signature,
+                task_id = %task_id,
+                "economic: starting task while intake is paused"
+            );
+        }
+        self.insert_task(&mut state, task_id, date, tags, BTreeMap::new(), None);
+    }
 
-        // Update session tracking
-        state.session.input_tokens += input_tokens;
-        state.session.output_tokens += output_tokens;
-        state.session.cost += cost;
-        state.daily.cost += cost;
+    /// Whether new tasks may be started under the configured intake policy.
+    pub fn can_accept_work(&self) -> WorkAdmission {
+        let state = self.state.lock();
+        match state.intake_paused_since {
+            Some(since) => WorkAdmission::Paused {
+                status: self.get_survival_status_inner(&state),
+                since,
+            },
+            None => WorkAdmission::Accept,
+        }
+    }
 
-        // Update task-level tracking
-        state.task.costs.llm_tokens += cost;
-        state.task.llm_calls.push(LlmCallRecord {
-            timestamp: Utc::now(),
-            api_name,
-            input_tokens,
-            output_tokens,
-            cost,
-        });
+    /// Whether a classified task is expected to pay for itself.
+    ///
+    /// Accepts the task when `min_expected_quality` reaches the result's
+    /// `break_even_quality_score` (see
+    /// [`ClassificationResult::with_expected_cost`]).
+    pub fn should_accept_task(result: &ClassificationResult, min_expected_quality: f64) -> bool {
+        min_expected_quality >= result.break_even_quality_score
+    }
 
-        // Update totals
-        state.total_token_cost += cost;
-        state.balance -= cost;
+    fn insert_task(
+        &self,
+        state: &mut TrackerState,
+        task_id: String,
+        date: Option<String>,
+        tags: &[&str],
+        metadata: BTreeMap<String, String>,
+        estimated_budget: Option<f64>,
+    ) {
+        let date = date.unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
+        let now = Utc::now();
+
+        let span = tracing::info_span!(
+            "economic_task",
+            agent_id = %self.signature,
+            task_id = %task_id
+        );
+        state.tasks.insert(
+            task_id.clone(),
+            TaskState {
+                task_id: task_id.clone(),
+                task_date: date,
+                start_time: now,
+                tags: normalize_tags(tags),
+                metadata,
+                estimated_budget,
+                category: None,
+                costs: CostBreakdown::default(),
+                llm_calls: Vec::new(),
+                api_calls: Vec::new(),
+                span,
+            },
+        );
+        state.current_task = Some(task_id.clone());
+
+        // Track daily window
+        if state.daily.first_task_start.is_none() {
+            state.daily.first_task_start = Some(now);
+        }
+        state.daily.task_ids.push(task_id);
 
-        cost
+        self.log_state_change(state, "task started", 0.0);
     }
 
-    /// Track token-based API call cost.
+    /// End tracking for a task and save its consolidated records.
     ///
-    /// # Arguments
-    /// * `tokens` - Number of tokens used
-    /// * `price_per_million` - Price per million tokens
-    /// * `api_name` - Name of the API
+    /// Appends the detailed `TaskCostRecord` to `token_costs.jsonl` and stores
+    /// the returned summary as the task's `TaskCompletionRecord`.
     ///
-    /// # Returns
-    /// The cost in USD for this call.
-    pub fn track_api_call(
-        &self,
-        tokens: u64,
-        price_per_million: f64,
-        api_name: impl Into<String>,
-    ) -> f64 {
-        let api_name = api_name.into();
-        let cost = (tokens as f64 / 1_000_000.0) * price_per_million;
-
-        self.record_api_cost(&api_name, cost, Some(tokens), Some(price_per_million), PricingModel::PerToken);
-
-        cost
+    /// # Errors
+    /// Returns [`EconomicError::TaskNotFound`] if `task_id` is not active,
+    /// or an error if the records cannot be written. The task stays active
+    /// when its cost record was not written, so the end can be retried.
+    pub fn end_task(&self, task_id: &str) -> Result<TaskCostSummary> {
+        self.close_task(task_id, None, "")
     }
 
-    /// Track flat-rate API call cost.
+    /// Close a task that did not complete.
     ///
-    /// # Arguments
-    /// * `cost` - Flat cost in USD
-    /// * `api_name` - Name of the API
+    /// The task's costs stay on the books exactly as with `end_task`, but its
+    /// completion record carries the terminal status derived from `reason`,
+    /// which excludes it from income-eligible task counts in analytics.
     ///
-    /// # Returns
-    /// The cost (same as input).
-    pub fn track_flat_api_call(&self, cost: f64, api_name: impl Into<String>) -> f64 {
-        let api_name = api_name.into();
-        self.record_api_cost(&api_name, cost, None, None, PricingModel::FlatRate);
-        cost
+    /// # Errors
+    /// Returns [`EconomicError::TaskNotFound`] if `task_id` is not active,
+    /// including when it has already been ended or aborted.
+    pub fn abort_task(
+        &self,
+        task_id: &str,
+        reason: TaskAbortReason,
+        detail: &str,
+    ) -> Result<TaskCostSummary> {
+        self.close_task(task_id, Some(reason), detail)
     }
 
-    fn record_api_cost(
+    fn close_task(
         &self,
-        api_name: &str,
-        cost: f64,
-        tokens: Option<u64>,
-        price_per_million: Option<f64>,
-        pricing_model: PricingModel,
-    ) {
+        task_id: &str,
+        abort_reason: Option<TaskAbortReason>,
+        detail: &str,
+    ) -> Result<TaskCostSummary> {
         let mut state = self.state.lock();
 
-        // Update session/daily
-        state.session.cost += cost;
-        state.daily.cost += cost;
+        let Some(task) = state.tasks.remove(task_id) else {
+            return Err(EconomicError::TaskNotFound {
+                task_id: task_id.to_string(),
+            });
+        };
+        let was_current = state.current_task.as_deref() == Some(task_id);
+        if was_current {
+            state.current_task = None;
+        }
 
-        // Categorize by API type
-        let api_lower = api_name.to_lowercase();
-        if api_lower.contains("search") || api_lower.contains("jina") || api_lower.contains("tavily") {
-            state.task.costs.search_api += cost;
-        } else if api_lower.contains("ocr") {
-            state.task.costs.ocr_api += cost;
-        } else {
-            state.task.costs.other_api += cost;
+        let now = Utc::now();
+        let overhead = std::mem::take(&mut state.pending_overhead);
+        let mut summary = task.summary(now);
+        summary.prorated_overhead = overhead;
+        let task_status = abort_reason.map_or(TaskStatus::Completed, |reason| reason.status());
+        let mut record = self.build_task_record(&state, &task);
+        record.prorated_overhead = overhead;
+        drop(state);
+
+        // Balance first, so the cost record is never on disk without it.
+        // Until the cost record is written nothing about the task is logged,
+        // so a failure puts the task back and the close can be retried.
+        let logged = self
+            .checkpoint()
+            .and_then(|()| self.append_record(&self.token_costs_file_path(), &record));
+        if let Err(err) = logged {
+            self.restore_task(task, was_current, overhead);
+            return Err(err);
         }
 
-        // Record detailed call
-        state.task.api_calls.push(ApiCallRecord {
-            timestamp: Utc::now(),
-            api_name: api_name.to_string(),
-            pricing_model,
-            tokens,
-            price_per_million,
-            cost,
+        let mut state = self.state.lock();
+        task.span.in_scope(|| {
+            tracing::info!(
+                agent_id = %self.signature,
+                task_id = %task.task_id,
+                cost_usd = summary.total,
+                balance = state.balance,
+                status = %self.get_survival_status_inner(&state),
+                task_status = ?task_status,
+                abort_reason = ?abort_reason,
+                "economic: task {}",
+                if abort_reason.is_some() { "aborted" } else { "ended" }
+            );
         });
+        state.daily.last_task_end = Some(now);
+        if overhead > 0.0 {
+            // The cost record already carries the overhead, so it stays
+            // applied even if this record cannot be written
+            let applied = OverheadRecord {
+                timestamp: now,
+                strategy: ProrationStrategy::AddToNextTask,
+                amount: 0.0,
+                shares: BTreeMap::from([(task.task_id.clone(), overhead)]),
+                pending_overhead: state.pending_overhead,
+                written_off_overhead: state.written_off_overhead,
+            };
+            self.append_record(&self.overhead_file_path(), &applied)?;
+        }
+        drop(state);
 
-        // Update totals
-        state.total_token_cost += cost;
-        state.balance -= cost;
+        self.write_task_completion(TaskCompletionRecord {
+            task_id: summary.task_id.clone(),
+            date: summary.date.clone(),
+            attempt: 1,
+            work_submitted: abort_reason.is_none(),
+            evaluation_score: 0.0,
+            money_earned: 0.0,
+            wall_clock_seconds: summary.duration_seconds,
+            timestamp: now,
+            started_at: Some(task.start_time),
+            ended_at: Some(now),
+            tags: summary.tags.clone(),
+            metadata: summary.metadata.clone(),
+            cost_summary: Some(summary.clone()),
+            status: task_status,
+            abort_reason,
+            abort_detail: abort_reason
+                .is_some()
+                .then(|| detail.to_string())
+                .filter(|detail| !detail.is_empty()),
+            occupation_category: task.category,
+        })?;
+
+        Ok(summary)
     }
 
-    /// Add income from completed work with evaluation threshold.
-    ///
-    /// Payment is only awarded if `evaluation_score >= min_evaluation_threshold`.
+    /// Put back a task [`close_task`](Self::close_task) took out before any
+    /// of its records were written, along with the overhead it was charged.
+    fn restore_task(&self, task: TaskState, was_current: bool, overhead: f64) {
+        let mut state = self.state.lock();
+        state.pending_overhead += overhead;
+        if was_current && state.current_task.is_none() {
+            state.current_task = Some(task.task_id.clone());
+        }
+        // A task restarted under the same id in the meantime wins
+        state.tasks.entry(task.task_id.clone()).or_insert(task);
+    }
+
+    /// Close a task that failed (API error, quality rejection, ...).
     ///
-    /// # Arguments
-    /// * `amount` - Base payment amount in USD
-    /// * `task_id` - Task identifier
-    /// * `evaluation_score` - Score from 0.0 to 1.0
-    /// * `description` - Optional description
+    /// Shorthand for [`abort_task`](Self::abort_task) with
+    /// [`TaskAbortReason::Failed`]: the task's costs are recorded normally
+    /// and its completion record gets `status: "failed"` with `reason` as
+    /// the detail.
     ///
-    /// # Returns
-    /// Actual payment received (0.0 if below threshold).
-    pub fn add_work_income(
-        &self,
-        amount: f64,
-        task_id: impl Into<String>,
-        evaluation_score: f64,
-        description: impl Into<String>,
-    ) -> Result<f64> {
-        let task_id = task_id.into();
-        let description = description.into();
-        let threshold = self.config.min_evaluation_threshold;
-
-        let actual_payment = if evaluation_score >= threshold {
-            amount
-        } else {
-            0.0
-        };
+    /// # Errors
+    /// Returns [`EconomicError::TaskNotFound`] if `task_id` is not active,
+    /// or an error if the records cannot be written.
+    pub fn mark_task_failed(&self, task_id: &str, reason: &str) -> Result<()> {
+        let summary = self.close_task(task_id, Some(TaskAbortReason::Failed), reason)?;
+        tracing::warn!(
+            agent_id = %self.signature,
+            task_id,
+            cost_usd = summary.total,
+            reason,
+            "economic: task failed"
+        );
+        Ok(())
+    }
 
-        {
-            let mut state = self.state.lock();
-            if actual_payment > 0.0 {
-                state.balance += actual_payment;
-                state.total_work_income += actual_payment;
-                tracing::info!(
-                    "💰 Work income: +${:.2} (Task: {}, Score: {:.2})",
-                    actual_payment,
-                    task_id,
-                    evaluation_score
-                );
-            } else {
-                tracing::warn!(
-                    "⚠️ Work below threshold (score: {:.2} < {:.2}), no payment for task: {}",
-                    evaluation_score,
-                    threshold,
-                    task_id
-                );
-            }
+    /// List the IDs of tasks with the given status.
+    ///
+    /// Active tasks come from memory, in start order. Other statuses are
+    /// read from `task_completions.jsonl`, in the order the tasks ended; a
+    /// task that was restarted and is active again is only reported as
+    /// active.
+    pub fn get_tasks_by_status(&self, status: TaskStatus) -> Result<Vec<String>> {
+        let active = self.active_task_ids();
+        if status == TaskStatus::Active {
+            return Ok(active);
         }
 
-        self.log_work_income(
-            &task_id,
-            amount,
-            actual_payment,
-            evaluation_score,
-            &description,
-        )?;
+        let mut task_ids = Vec::new();
+        let completions = self.task_completions_file_path();
+        self.for_each_record::<TaskCompletionRecord, _>(&completions, |record| {
+            if record.status == status && !active.contains(&record.task_id) {
+                task_ids.push(record.task_id);
+            }
+        })?;
+        Ok(task_ids)
+    }
 
-        Ok(actual_payment)
+    /// List the IDs of every known task: ended tasks in the order they
+    /// ended, followed by active tasks in start order.
+    pub fn get_all_task_ids(&self) -> Result<Vec<String>> {
+        let active = self.active_task_ids();
+        let mut task_ids = Vec::new();
+        let completions = self.task_completions_file_path();
+        self.for_each_record::<TaskCompletionRecord, _>(&completions, |record| {
+            if !active.contains(&record.task_id) {
+                task_ids.push(record.task_id);
+            }
+        })?;
+        task_ids.extend(active);
+        Ok(task_ids)
     }
 
-    /// Add profit/loss from trading.
-    pub fn add_trading_profit(&self, profit: f64, _description: impl Into<String>) {
-        let mut state = self.state.lock();
-        state.balance += profit;
-        state.total_trading_profit += profit;
+    /// The task with the highest total cost, or `None` when no task has
+    /// started, see [`get_top_n_expensive_tasks`](Self::get_top_n_expensive_tasks).
+    pub fn get_most_expensive_task(&self) -> Result<Option<TaskCostSummary>> {
+        Ok(self.get_top_n_expensive_tasks(1)?.pop())
+    }
 
-        let sign = if profit >= 0.0 { "+" } else { "" };
-        tracing::info!(
-            "📈 Trading P&L: {}${:.2}, new balance: ${:.2}",
-            sign,
-            profit,
-            state.balance
-        );
+    /// The `n` tasks with the highest total cost, most expensive first and
+    /// ties by task ID.
+    ///
+    /// Active tasks are summarized from memory as of now, ended ones from
+    /// their latest completion record; a restarted task that is active again
+    /// only counts with its current costs.
+    pub fn get_top_n_expensive_tasks(&self, n: usize) -> Result<Vec<TaskCostSummary>> {
+        let mut tasks = self.task_cost_summaries()?;
+        tasks.sort_by(|a, b| {
+            b.total
+                .total_cmp(&a.total)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        tasks.truncate(n);
+        Ok(tasks)
     }
 
-    /// Save end-of-day economic state.
-    pub fn save_daily_state(
-        &self,
-        date: &str,
-        work_income: f64,
-        trading_profit: f64,
-        completed_tasks: Vec<String>,
-        api_error: bool,
-    ) -> Result<()> {
-        let daily_cost = {
+    /// The task that ran longest and its duration, or `None` when no task
+    /// has started. Active tasks count with the time elapsed so far.
+    pub fn get_longest_task(&self) -> Result<Option<(String, Duration)>> {
+        let now = Utc::now();
+        let mut longest: Option<(String, Duration)> = None;
+        let mut consider = |task_id: &str, duration: Duration| {
+            if longest.as_ref().is_none_or(|(_, longest)| duration > *longest) {
+                longest = Some((task_id.to_string(), duration));
+            }
+        };
+        let active = {
             let state = self.state.lock();
-            state.daily.cost
+            for task in state.tasks.values() {
+                consider(&task.task_id, (now - task.start_time).to_std().unwrap_or_default());
+            }
+            state.tasks.keys().cloned().collect::<HashSet<_>>()
         };
+        self.for_each_record::<TaskCompletionRecord, _>(
+            &self.task_completions_file_path(),
+            |record| {
+                if !active.contains(&record.task_id) {
+                    consider(&record.task_id, record.duration());
+                }
+            },
+        )?;
+        Ok(longest)
+    }
 
-        self.save_balance_record(
-            date,
-            daily_cost,
-            work_income,
-            trading_profit,
-            completed_tasks,
-            api_error,
+    /// Cost summaries of the active tasks as of now and of the ended ones
+    /// from their latest completion record.
+    fn task_cost_summaries(&self) -> Result<Vec<TaskCostSummary>> {
+        let now = Utc::now();
+        let mut summaries: HashMap<String, TaskCostSummary> = {
+            let state = self.state.lock();
+            state
+                .tasks
+                .values()
+                .map(|task| (task.task_id.clone(), task.summary(now)))
+                .collect()
+        };
+        let active: HashSet<String> = summaries.keys().cloned().collect();
+        self.for_each_record::<TaskCompletionRecord, _>(
+            &self.task_completions_file_path(),
+            |record| {
+                if active.contains(&record.task_id) {
+                    return;
+                }
+                if let Some(summary) = record.cost_summary {
+                    summaries.insert(record.task_id, summary);
+                }
+            },
         )?;
+        Ok(summaries.into_values().collect())
+    }
 
-        // Reset daily tracking
-        {
-            let mut state = self.state.lock();
-            state.daily.reset();
-            state.session.reset();
+    /// Mean duration of the ended tasks; zero when none has ended.
+    pub fn get_average_task_duration(&self) -> Result<Duration> {
+        let durations = self.task_durations()?;
+        if durations.is_empty() {
+            return Ok(Duration::ZERO);
         }
-
-        tracing::info!("💾 Saved daily state for {}", date);
-
-        Ok(())
+        let total: f64 = durations.iter().map(Duration::as_secs_f64).sum();
+        Ok(Duration::from_secs_f64(total / durations.len() as f64))
     }
 
-    /// Get current balance.
-    pub fn get_balance(&self) -> f64 {
-        self.state.lock().balance
+    /// Median (nearest-rank) duration of the ended tasks; zero when none
+    /// has ended.
+    pub fn get_median_task_duration(&self) -> Result<Duration> {
+        Ok(distribution::duration_percentile(&self.task_durations()?, 50))
     }
 
-    /// Get net worth (balance + portfolio value).
-    pub fn get_net_worth(&self) -> f64 {
-        // TODO: Add trading portfolio value
-        self.get_balance()
+    /// 95th-percentile (nearest-rank) duration of the ended tasks; zero when
+    /// none has ended.
+    pub fn get_p95_task_duration(&self) -> Result<Duration> {
+        Ok(distribution::duration_percentile(&self.task_durations()?, 95))
     }
 
-    /// Get current survival status.
-    pub fn get_survival_status(&self) -> SurvivalStatus {
-        let state = self.state.lock();
-        self.get_survival_status_inner(&state)
+    /// Histogram of the durations of the ended tasks in `buckets`
+    /// equal-width buckets from the shortest to the longest; empty when no
+    /// task has ended.
+    pub fn get_task_duration_histogram(&self, buckets: usize) -> Result<Vec<DurationBucket>> {
+        Ok(distribution::duration_histogram(&self.task_durations()?, buckets))
     }
 
-    fn get_survival_status_inner(&self, state: &TrackerState) -> SurvivalStatus {
-        SurvivalStatus::from_balance(state.balance, state.initial_balance)
+    /// Work income earned per hour spent on the ended tasks.
+    ///
+    /// # Errors
+    ///
+    /// [`EconomicError::NoTaskTime`] when the ended tasks took no time.
+    pub fn get_income_per_hour_worked(&self) -> Result<f64> {
+        let hours = self.total_task_hours()?;
+        Ok(self.state.lock().total_work_income / hours)
     }
 
-    /// Check if agent is bankrupt.
-    pub fn is_bankrupt(&self) -> bool {
-        self.get_survival_status() == SurvivalStatus::Bankrupt
+    /// Work income minus token costs per hour spent on the ended tasks.
+    ///
+    /// # Errors
+    ///
+    /// [`EconomicError::NoTaskTime`] when the ended tasks took no time.
+    pub fn get_profit_per_hour(&self) -> Result<f64> {
+        let hours = self.total_task_hours()?;
+        let state = self.state.lock();
+        Ok((state.total_work_income - state.total_token_cost) / hours)
     }
 
-    /// Get session cost so far.
-    pub fn get_session_cost(&self) -> f64 {
-        self.state.lock().session.cost
-    }
+    /// How many ended tasks it takes to earn back `fixed_overhead_usd`, at
+    /// the average profit of the tasks ended so far.
+    ///
+    /// A task's profit is the work income paid for it minus its cost.
+    ///
+    /// # Errors
+    ///
+    /// [`EconomicError::NoCompletedTasks`] before any task has ended;
+    /// [`EconomicError::BreakEvenUnreachable`] when the ended tasks make no
+    /// profit on average.
+    pub fn get_break_even_task_count(&self) -> Result<BreakEvenAnalysis> {
+        let mut task_ids = HashSet::new();
+        self.for_each_record::<TaskCompletionRecord, _>(
+            &self.task_completions_file_path(),
+            |record| {
+                task_ids.insert(record.task_id);
+            },
+        )?;
+        if task_ids.is_empty() {
+            return Err(EconomicError::NoCompletedTasks);
+        }
 
-    /// Get daily cost so far.
-    pub fn get_daily_cost(&self) -> f64 {
-        self.state.lock().daily.cost
+        let mut profit = 0.0;
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            if task_ids.contains(&record.task_id) {
+                profit += record.actual_payment;
+            }
+        })?;
+        self.for_each_task_cost(|id, total, _| {
+            if task_ids.contains(id) {
+                profit -= total;
+            }
+        })?;
+
+        let current_task_count = task_ids.len() as u64;
+        let average_task_profit = profit / current_task_count as f64;
+        if average_task_profit <= 0.0 {
+            return Err(EconomicError::BreakEvenUnreachable {
+                average_task_profit,
+            });
+        }
+        let fixed_overhead = self.config.fixed_overhead_usd;
+        // Non-negative, and saturating in the cast
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let break_even_tasks = (fixed_overhead / average_task_profit).ceil() as u64;
+        Ok(BreakEvenAnalysis {
+            fixed_overhead,
+            average_task_profit,
+            break_even_tasks,
+            current_task_count,
+            is_profitable: current_task_count >= break_even_tasks,
+        })
     }
 
-    /// Get comprehensive economic summary.
-    pub fn get_summary(&self) -> EconomicSummary {
-        let state = self.state.lock();
-        EconomicSummary {
-            signature: self.signature.clone(),
-            balance: state.balance,
-            initial_balance: state.initial_balance,
-            net_worth: state.balance, // TODO: Add portfolio
-            total_token_cost: state.total_token_cost,
-            total_work_income: state.total_work_income,
-            total_trading_profit: state.total_trading_profit,
-            session_cost: state.session.cost,
-            daily_cost: state.daily.cost,
-            session_input_tokens: state.session.input_tokens,
-            session_output_tokens: state.session.output_tokens,
-            survival_status: self.get_survival_status_inner(&state),
-            is_bankrupt: self.get_survival_status_inner(&state) == SurvivalStatus::Bankrupt,
-            min_evaluation_threshold: self.config.min_evaluation_threshold,
+    /// Combined duration of the ended tasks in hours, never zero.
+    fn total_task_hours(&self) -> Result<f64> {
+        let seconds: f64 = self.task_durations()?.iter().map(Duration::as_secs_f64).sum();
+        if seconds > 0.0 {
+            Ok(seconds / 3600.0)
+        } else {
+            Err(EconomicError::NoTaskTime)
         }
     }
 
-    /// Reset session tracking (for new decision/activity).
-    pub fn reset_session(&self) {
-        self.state.lock().session.reset();
+    /// Durations of the tasks in `task_completions.jsonl`, shortest first.
+    fn task_durations(&self) -> Result<Vec<Duration>> {
+        let mut durations = Vec::new();
+        self.for_each_record::<TaskCompletionRecord, _>(
+            &self.task_completions_file_path(),
+            |record| durations.push(record.duration()),
+        )?;
+        durations.sort_unstable();
+        Ok(durations)
     }
 
-    /// Record task completion statistics.
-    pub fn record_task_completion(
+    /// Cost, income and quality of the ended tasks, grouped by occupation
+    /// category.
+    ///
+    /// A task counts under the category recorded on its completion record
+    /// (see [`set_task_category`](Self::set_task_category)); tasks without
+    /// one are left out. Income and the success rate come from the task's
+    /// work income records: a task succeeded when it was paid. Its quality
+    /// is the score of its latest evaluation, or of its completion record
+    /// when it was never evaluated for payment.
+    pub fn get_category_performance_summary(
         &self,
-        task_id: impl Into<String>,
-        work_submitted: bool,
-        wall_clock_seconds: f64,
-        evaluation_score: f64,
-        money_earned: f64,
-        attempt: u32,
-        date: Option<String>,
-    ) -> Result<()> {
-        let task_id = task_id.into();
-        let date = date.or_else(|| {
-            self.state.lock().task.task_date.clone()
-        }).unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string());
-
-        let record = TaskCompletionRecord {
-            task_id: task_id.clone(),
-            date,
-            attempt,
-            work_submitted,
-            evaluation_score,
-            money_earned,
-            wall_clock_seconds,
-            timestamp: Utc::now(),
-        };
+    ) -> Result<HashMap<OccupationCategory, CategoryPerformance>> {
+        let mut tasks = Vec::new();
+        self.for_each_record::<TaskCompletionRecord, _>(
+            &self.task_completions_file_path(),
+            |record| {
+                if let Some(category) = record.occupation_category {
+                    tasks.push((record.task_id, category, record.evaluation_score));
+                }
+            },
+        )?;
 
-        // Read existing records, filter out this task_id
-        let completions_file = self.task_completions_file_path();
-        let mut existing: Vec<String> = Vec::new();
+        // task id -> (income, latest evaluation score, paid)
+        let mut income: HashMap<String, (f64, f64, bool)> = HashMap::new();
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            let entry = income.entry(record.task_id).or_default();
+            entry.0 += record.actual_payment;
+            entry.1 = record.evaluation_score;
+            entry.2 |= record.payment_awarded;
+        })?;
+        let mut costs: HashMap<String, f64> = HashMap::new();
+        self.for_each_task_cost(|id, total, _| {
+            *costs.entry(id.to_string()).or_default() += total;
+        })?;
 
-        if completions_file.exists() {
-            let file = File::open(&completions_file)?;
-            let reader = BufReader::new(file);
-            for line in reader.lines() {
-                let line = line?;
-                if line.trim().is_empty() {
-                    continue;
-                }
-                if let Ok(entry) = serde_json::from_str::<TaskCompletionRecord>(&line) {
-                    if entry.task_id != task_id {
-                        existing.push(line);
+        let mut summary: HashMap<OccupationCategory, CategoryPerformance> = HashMap::new();
+        let mut paid: HashMap<OccupationCategory, u64> = HashMap::new();
+        for (task_id, category, completion_score) in tasks {
+            let entry = summary.entry(category).or_default();
+            entry.task_count += 1;
+            entry.total_cost += costs.get(&task_id).copied().unwrap_or(0.0);
+            let quality = match income.get(&task_id) {
+                Some(&(amount, score, awarded)) => {
+                    entry.total_income += amount;
+                    if awarded {
+                        *paid.entry(category).or_default() += 1;
                     }
-                } else {
-                    existing.push(line);
+                    score
                 }
-            }
+                None => completion_score,
+            };
+            // Summed here, averaged below
+            entry.average_quality += quality;
         }
-
-        // Rewrite with updated record
-        let mut file = File::create(&completions_file)?;
-        for line in existing {
-            writeln!(file, "{}", line)?;
+        for (category, entry) in &mut summary {
+            let count = entry.task_count as f64;
+            entry.net_pnl = entry.total_income - entry.total_cost;
+            entry.average_quality /= count;
+            entry.success_rate = paid.get(category).copied().unwrap_or(0) as f64 / count;
         }
-        writeln!(file, "{}", serde_json::to_string(&record)?)?;
-        file.sync_all()?;
-
-        Ok(())
+        Ok(summary)
     }
 
-    // ── Private helpers ──
-
-    fn balance_file_path(&self) -> PathBuf {
-        self.data_path.join("balance.jsonl")
+    fn active_task_ids(&self) -> Vec<String> {
+        let state = self.state.lock();
+        let mut tasks: Vec<&TaskState> = state.tasks.values().collect();
+        tasks.sort_by_key(|task| task.start_time);
+        tasks.into_iter().map(|task| task.task_id.clone()).collect()
     }
 
-    fn token_costs_file_path(&self) -> PathBuf {
-        self.data_path.join("token_costs.jsonl")
+    /// Record the occupation category of a still-active task, e.g. the
+    /// [`ClassificationResult::category`] it was accepted under.
+    ///
+    /// The category is copied onto the task's completion record and groups
+    /// the task in
+    /// [`get_category_performance_summary`](Self::get_category_performance_summary).
+    ///
+    /// # Errors
+    /// Returns [`EconomicError::TaskNotFound`] if `task_id` is not active.
+    pub fn set_task_category(&self, task_id: &str, category: OccupationCategory) -> Result<()> {
+        let mut state = self.state.lock();
+        let task = state
+            .tasks
+            .get_mut(task_id)
+            .ok_or_else(|| EconomicError::TaskNotFound {
+                task_id: task_id.to_string(),
+            })?;
+        task.category = Some(category);
+        Ok(())
     }
 
-    fn task_completions_file_path(&self) -> PathBuf {
-        self.data_path.join("task_completions.jsonl")
+    /// Get the running cost of a still-active task.
+    ///
+    /// # Errors
+    /// Returns [`EconomicError::TaskNotFound`] if `task_id` is not active.
+    pub fn peek_task_cost(&self, task_id: &str) -> Result<f64> {
+        let state = self.state.lock();
+        state
+            .tasks
+            .get(task_id)
+            .map(|task| task.costs.total())
+            .ok_or_else(|| EconomicError::TaskNotFound {
+                task_id: task_id.to_string(),
+            })
     }
 
-    fn load_latest_state(&self) -> Result<()> {
-        let balance_file = self.balance_file_path();
-        let file = File::open(&balance_file)?;
-        let reader = BufReader::new(file);
+    /// Cost a task has accumulated so far, whether it is still active or
+    /// has ended.
+    ///
+    /// # Errors
+    /// Returns [`EconomicError::TaskNotFound`] if no task with this id was
+    /// tracked.
+    pub fn task_cost_so_far(&self, task_id: &str) -> Result<f64> {
+        if let Some(task) = self.state.lock().tasks.get(task_id) {
+            return Ok(task.costs.total());
+        }
 
-        let mut last_record: Option<BalanceRecord> = None;
-        for line in reader.lines() {
-            let line = line?;
-            if let Ok(record) = serde_json::from_str::<BalanceRecord>(&line) {
-                last_record = Some(record);
+        // Only ended tasks need the cost log
+        self.logged_task_cost(task_id)?
+            .ok_or_else(|| EconomicError::TaskNotFound {
+                task_id: task_id.to_string(),
+            })
+    }
+
+    /// Cost of an ended task, from its record in the cost log.
+    fn logged_task_cost(&self, task_id: &str) -> Result<Option<f64>> {
+        let mut cost = None;
+        self.for_each_record::<TaskCostRecord, _>(&self.token_costs_file_path(), |record| {
+            if record.task_id == task_id {
+                cost = Some(record.cost_summary.total());
             }
-        }
+        })?;
+        Ok(cost)
+    }
 
-        if let Some(record) = last_record {
-            let mut state = self.state.lock();
-            state.balance = record.balance;
-            state.total_token_cost = record.total_token_cost;
-            state.total_work_income = record.total_work_income;
-            state.total_trading_profit = record.total_trading_profit;
-        }
+    /// Track LLM token usage.
+    ///
+    /// # Arguments
+    /// * `input_tokens` - Number of input tokens
+    /// * `output_tokens` - Number of output tokens
+    /// * `api_name` - Origin of the call (e.g., "agent", "wrapup")
+    /// * `cost` - Pre-computed cost (if provided, skips local calculation)
+    /// * `duration` - Wall-clock time the call took, kept as `latency_ms`
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call: its cost in USD, with `grace_warning`
+    /// set when it was paid from the bankruptcy grace budget.
+    ///
+    /// Ignored, returning a zero charge, when `llm_usage_source` is
+    /// [`LlmUsageSource::Observer`].
+    ///
+    /// # Errors
+    /// [`EconomicError::TaskCostCeilingExceeded`] if the current task has
+    /// already spent `max_cost_per_task`; the call is not recorded.
+    pub fn track_tokens(
+        &self,
+        input_tokens: u64,
+        output_tokens: u64,
+        api_name: impl Into<String>,
+        cost: Option<f64>,
+        duration: Duration,
+    ) -> Result<Charge> {
+        let now = Utc::now();
+        let cost = cost.unwrap_or_else(|| {
+            self.token_pricing().calculate_cost(input_tokens, output_tokens)
+                * self.time_of_use_multiplier(now)
+        });
 
-        Ok(())
+        self.record_llm_call(LlmCallRecord {
+            id: new_charge_id(),
+            timestamp: now,
+            api_name: api_name.into(),
+            input_tokens,
+            output_tokens,
+            cost,
+            cache_hit: false,
+            cache_savings_usd: 0.0,
+            model: None,
+            provider: None,
+            prompt_type: None,
+            request_id: None,
+            retry_attempt: 0,
+            latency_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+            session_id: None,
+        })
     }
 
-    fn save_task_record_inner(&self, state: &TrackerState) -> Result<()> {
-        let Some(ref task_id) = state.task.task_id else {
-            return Ok(());
-        };
+    /// Track LLM token usage attributed to a specific model.
+    ///
+    /// Same as [`track_tokens`](Self::track_tokens), but the model is kept on
+    /// the call record so task summaries can break token counts down by model.
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    pub fn track_model_tokens(
+        &self,
+        model: impl Into<String>,
+        input_tokens: u64,
+        output_tokens: u64,
+        api_name: impl Into<String>,
+        cost: Option<f64>,
+    ) -> Result<Charge> {
+        let now = Utc::now();
+        let model = model.into();
+        let cost = cost.unwrap_or_else(|| {
+            self.model_token_pricing(None, Some(&model))
+                .calculate_cost(input_tokens, output_tokens)
+                * self.time_of_use_multiplier(now)
+        });
 
-        let total_input = state.task.llm_calls.iter().map(|c| c.input_tokens).sum();
-        let total_output = state.task.llm_calls.iter().map(|c| c.output_tokens).sum();
-        let llm_call_count = state.task.llm_calls.len();
+        self.record_llm_call(LlmCallRecord {
+            id: new_charge_id(),
+            timestamp: now,
+            api_name: api_name.into(),
+            input_tokens,
+            output_tokens,
+            cost,
+            cache_hit: false,
+            cache_savings_usd: 0.0,
+            model: Some(model),
+            provider: None,
+            prompt_type: None,
+            request_id: None,
+            retry_attempt: 0,
+            latency_ms: 0,
+            session_id: None,
+        })
+    }
 
-        let token_based = state.task.api_calls.iter()
-            .filter(|c| c.pricing_model == PricingModel::PerToken)
-            .count();
-        let flat_rate = state.task.api_calls.iter()
-            .filter(|c| c.pricing_model == PricingModel::FlatRate)
-            .count();
+    /// Track LLM token usage attributed to a provider and model.
+    ///
+    /// Same as [`track_model_tokens`](Self::track_model_tokens), but the
+    /// provider is kept on the call record too, so spend can be broken down
+    /// by provider.
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    pub fn track_provider_tokens(
+        &self,
+        provider: impl Into<String>,
+        model: impl Into<String>,
+        input_tokens: u64,
+        output_tokens: u64,
+        api_name: impl Into<String>,
+        cost: Option<f64>,
+    ) -> Result<Charge> {
+        let now = Utc::now();
+        let (provider, model) = (provider.into(), model.into());
+        let cost = cost.unwrap_or_else(|| {
+            self.model_token_pricing(Some(&provider), Some(&model))
+                .calculate_cost(input_tokens, output_tokens)
+                * self.time_of_use_multiplier(now)
+        });
 
-        let record = TaskCostRecord {
-            timestamp_end: Utc::now(),
-            timestamp_start: state.task.start_time.unwrap_or_else(Utc::now),
-            date: state.task.task_date.clone().unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string()),
-            task_id: task_id.clone(),
-            llm_usage: LlmUsageSummary {
-                total_calls: llm_call_count,
-                total_input_tokens: total_input,
-                total_output_tokens: total_output,
-                total_tokens: total_input + total_output,
-                total_cost: state.task.costs.llm_tokens,
-                input_price_per_million: self.config.token_pricing.input_price_per_million,
-                output_price_per_million: self.config.token_pricing.output_price_per_million,
-                calls_detail: state.task.llm_calls.clone(),
-            },
-            api_usage: ApiUsageSummary {
-                total_calls: state.task.api_calls.len(),
-                search_api_cost: state.task.costs.search_api,
-                ocr_api_cost: state.task.costs.ocr_api,
-                other_api_cost: state.task.costs.other_api,
-                token_based_calls: token_based,
-                flat_rate_calls: flat_rate,
-                calls_detail: state.task.api_calls.clone(),
-            },
-            cost_summary: state.task.costs.clone(),
-            balance_after: state.balance,
-            session_cost: state.session.cost,
-            daily_cost: state.daily.cost,
-        };
+        self.record_llm_call(LlmCallRecord {
+            id: new_charge_id(),
+            timestamp: now,
+            api_name: api_name.into(),
+            input_tokens,
+            output_tokens,
+            cost,
+            cache_hit: false,
+            cache_savings_usd: 0.0,
+            model: Some(model),
+            provider: Some(provider),
+            prompt_type: None,
+            request_id: None,
+            retry_attempt: 0,
+            latency_ms: 0,
+            session_id: None,
+        })
+    }
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(self.token_costs_file_path())?;
-        writeln!(file, "{}", serde_json::to_string(&record)?)?;
-        file.sync_all()?;
+    /// Track LLM token usage together with request-level metadata.
+    ///
+    /// Same as [`track_tokens`](Self::track_tokens) with a computed cost, but
+    /// the model (when given), prompt type, request ID, and retry attempt are
+    /// kept on the call record.
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    pub fn track_tokens_with_context(
+        &self,
+        input_tokens: u64,
+        output_tokens: u64,
+        role: &str,
+        model: Option<&str>,
+        ctx: TokenContext,
+    ) -> Result<Charge> {
+        let now = Utc::now();
+        let cost = self
+            .model_token_pricing(None, model)
+            .calculate_cost(input_tokens, output_tokens)
+            * self.time_of_use_multiplier(now);
 
-        Ok(())
+        self.record_llm_call(LlmCallRecord {
+            id: new_charge_id(),
+            timestamp: now,
+            api_name: role.to_string(),
+            input_tokens,
+            output_tokens,
+            cost,
+            cache_hit: false,
+            cache_savings_usd: 0.0,
+            model: model.map(str::to_string),
+            provider: None,
+            prompt_type: Some(ctx.prompt_type),
+            request_id: ctx.request_id,
+            retry_attempt: ctx.retry_attempt,
+            latency_ms: 0,
+            session_id: ctx.session_id,
+        })
     }
 
-    fn save_balance_record(
+    /// Track LLM token usage where part of the input was served from the
+    /// provider's prompt cache.
+    ///
+    /// `cached_input_tokens` is the subset of `input_tokens` read from cache;
+    /// those tokens are billed at the input price reduced by
+    /// `cache_discount_rate`.
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    ///
+    /// # Errors
+    /// Same as [`track_tokens`](Self::track_tokens).
+    pub fn track_tokens_with_cache(
         &self,
-        date: &str,
-        token_cost_delta: f64,
-        work_income_delta: f64,
-        trading_profit_delta: f64,
-        completed_tasks: Vec<String>,
-        api_error: bool,
-    ) -> Result<()> {
-        let state = self.state.lock();
+        input_tokens: u64,
+        cached_input_tokens: u64,
+        output_tokens: u64,
+        api_name: impl Into<String>,
+    ) -> Result<Charge> {
+        let now = Utc::now();
+        let multiplier = self.time_of_use_multiplier(now);
+        let cached_input_tokens = cached_input_tokens.min(input_tokens);
+        let pricing = self.token_pricing();
+        let full_cost = pricing.calculate_cost(input_tokens, output_tokens) * multiplier;
+        let cache_savings_usd = (cached_input_tokens as f64 / 1_000_000.0)
+            * pricing.input_price_per_million
+            * self.config.cache_discount_rate.clamp(0.0, 1.0)
+            * multiplier;
 
-        let task_completion_time = match (state.daily.first_task_start, state.daily.last_task_end) {
-            (Some(start), Some(end)) => Some((end - start).num_seconds() as f64),
-            _ => None,
+        self.record_llm_call(LlmCallRecord {
+            id: new_charge_id(),
+            timestamp: now,
+            api_name: api_name.into(),
+            input_tokens,
+            output_tokens,
+            cost: full_cost - cache_savings_usd,
+            cache_hit: cached_input_tokens > 0,
+            cache_savings_usd,
+            model: None,
+            provider: None,
+            prompt_type: None,
+            request_id: None,
+            retry_attempt: 0,
+            latency_ms: 0,
+            session_id: None,
+        })
+    }
+
+    /// Set time-of-use multipliers for token costs.
+    ///
+    /// Token costs computed from `token_pricing` (calls made without an
+    /// explicit cost) are multiplied by the multiplier for the current UTC
+    /// hour. Replaces any earlier pricing; pass the default to clear it.
+    pub fn set_time_of_use_pricing(&self, pricing: TimeOfUsePricing) {
+        self.state.lock().time_of_use = pricing;
+    }
+
+    /// Register the context window of `model`, in tokens, for
+    /// [`get_context_utilization`](Self::get_context_utilization). Replaces
+    /// any earlier size; `0` forgets the model.
+    pub fn set_model_context_window(&self, model: &str, max_tokens: u64) {
+        let mut state = self.state.lock();
+        if max_tokens == 0 {
+            state.context_windows.remove(model);
+        } else {
+            state.context_windows.insert(model.to_string(), max_tokens);
+        }
+    }
+
+    /// Average share of its context window that each LLM call filled with
+    /// input tokens, per model with a registered window.
+    ///
+    /// Covers the calls of every logged and active task, like
+    /// [`get_model_cost_ranking`](Self::get_model_cost_ranking). Models
+    /// averaging under 20% are logged: their calls pay for a context they
+    /// do not use, and a model with a smaller window may serve them cheaper.
+    pub fn get_context_utilization(&self) -> Result<HashMap<String, f64>> {
+        let windows = self.state.lock().context_windows.clone();
+        let mut ratios: HashMap<String, (f64, u64)> = HashMap::new();
+        self.for_each_task_cost(|_, _, calls| {
+            for call in calls {
+                let Some((model, &window)) = call
+                    .model
+                    .as_deref()
+                    .and_then(|model| windows.get_key_value(model))
+                else {
+                    continue;
+                };
+                let entry = ratios.entry(model.clone()).or_default();
+                entry.0 += call.input_tokens as f64 / window as f64;
+                entry.1 += 1;
+            }
+        })?;
+
+        let utilization: HashMap<String, f64> = ratios
+            .into_iter()
+            .map(|(model, (sum, calls))| (model, sum / calls as f64))
+            .collect();
+        for (model, ratio) in &utilization {
+            if *ratio < LOW_CONTEXT_UTILIZATION {
+                tracing::info!(
+                    agent_id = %self.signature,
+                    model = %model,
+                    utilization = ratio,
+                    "economic: calls to {model} use {:.1}% of its context window on average",
+                    ratio * 100.0
+                );
+            }
+        }
+        Ok(utilization)
+    }
+
+    fn time_of_use_multiplier(&self, at: DateTime<Utc>) -> f64 {
+        self.state.lock().time_of_use.multiplier_at(at.hour())
+    }
+
+    /// Current token prices.
+    ///
+    /// A copy, so a cost computed from it never mixes the input price of
+    /// one table with the output price of another across a reload.
+    pub fn token_pricing(&self) -> TokenPricing {
+        self.token_pricing.read().clone()
+    }
+
+    /// Price calls that name a model from `pricing`, the per-model prices a
+    /// `CostObserver` uses, so both report the same cost for a call. Models
+    /// without an entry get the resolver's defaults; calls without a model
+    /// keep using [`token_pricing`](Self::token_pricing).
+    pub fn set_model_pricing(&self, pricing: Arc<SharedPricing>) {
+        *self.model_pricing.write() = Some(pricing);
+    }
+
+    /// Prices for a call to `model`: from `set_model_pricing` when set and a
+    /// model is known, `token_pricing` otherwise.
+    fn model_token_pricing(&self, provider: Option<&str>, model: Option<&str>) -> TokenPricing {
+        let (Some(pricing), Some(model)) = (&*self.model_pricing.read(), model) else {
+            return self.token_pricing();
         };
+        let resolved = pricing.snapshot().resolve(provider.unwrap_or_default(), model);
+        TokenPricing {
+            input_price_per_million: resolved.input,
+            output_price_per_million: resolved.output,
+        }
+    }
 
-        let record = BalanceRecord {
-            date: date.to_string(),
-            balance: state.balance,
-            token_cost_delta,
-            work_income_delta,
-            trading_profit_delta,
-            total_token_cost: state.total_token_cost,
-            total_work_income: state.total_work_income,
-            total_trading_profit: state.total_trading_profit,
-            net_worth: state.balance,
-            survival_status: self.get_survival_status_inner(&state).to_string(),
-            completed_tasks,
-            task_id: state.daily.task_ids.first().cloned(),
-            task_completion_time_seconds: task_completion_time,
-            api_error,
+    /// Reload token prices from the file passed to
+    /// [`watch_pricing_file`](Self::watch_pricing_file).
+    ///
+    /// The file holds a JSON `TokenPricing` object. It is parsed and
+    /// validated before anything is replaced, so the current prices stay in
+    /// use when it is invalid.
+    ///
+    /// # Errors
+    /// [`EconomicError::InvalidConfig`] if no pricing file is watched or its
+    /// prices are invalid.
+    pub fn reload_pricing(&self) -> Result<TokenPricing> {
+        let Some(path) = self.pricing_file.lock().as_ref().map(|(path, _)| path.clone()) else {
+            return Err(EconomicError::InvalidConfig(
+                "no pricing file is watched".to_string(),
+            ));
         };
+        let modified = file_modified(&path);
+        let json = fs::read_to_string(&path).at_path(&path)?;
+        let pricing: TokenPricing = serde_json::from_str(&json)?;
+        validate_token_pricing(&pricing)?;
 
-        drop(state); // Release lock before IO
+        let previous = std::mem::replace(&mut *self.token_pricing.write(), pricing.clone());
+        if let Some((_, loaded)) = self.pricing_file.lock().as_mut() {
+            *loaded = modified;
+        }
+        tracing::info!(
+            "💲 Reloaded token pricing from {}: ${}/${} -> ${}/${} per 1M input/output tokens",
+            path.display(),
+            previous.input_price_per_million,
+            previous.output_price_per_million,
+            pricing.input_price_per_million,
+            pricing.output_price_per_million
+        );
+        Ok(pricing)
+    }
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(self.balance_file_path())?;
-        writeln!(file, "{}", serde_json::to_string(&record)?)?;
-        file.sync_all()?;
+    /// Load token prices from `path` and reload them whenever the file's
+    /// modification time changes, checking every `interval`.
+    ///
+    /// An invalid file is logged and the previous prices kept. The watcher
+    /// task ends once the tracker is dropped. Must be called within a Tokio
+    /// runtime.
+    ///
+    /// # Errors
+    /// Same as [`reload_pricing`](Self::reload_pricing) for the initial
+    /// load; nothing is watched when it fails.
+    pub fn watch_pricing_file(
+        self: &Arc<Self>,
+        path: impl Into<PathBuf>,
+        interval: Duration,
+    ) -> Result<tokio::task::JoinHandle<()>> {
+        let previous = self.pricing_file.lock().replace((path.into(), None));
+        if let Err(err) = self.reload_pricing() {
+            *self.pricing_file.lock() = previous;
+            return Err(err);
+        }
 
-        Ok(())
+        let tracker = Arc::downgrade(self);
+        Ok(tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let Some(tracker) = tracker.upgrade() else {
+                    break;
+                };
+                let changed = tracker
+                    .pricing_file
+                    .lock()
+                    .as_mut()
+                    .and_then(|(path, loaded)| {
+                        let modified = file_modified(path);
+                        // Remember the new time so a broken file is tried once
+                        (modified != *loaded).then(|| std::mem::replace(loaded, modified))
+                    })
+                    .is_some();
+                if changed {
+                    if let Err(err) = tracker.reload_pricing() {
+                        tracing::warn!("⚠️ Keeping current token pricing: {err}");
+                    }
+                }
+            }
+        }))
     }
 
-    fn log_work_income(
-        &self,
-        task_id: &str,
-        base_amount: f64,
-        actual_payment: f64,
-        evaluation_score: f64,
-        description: &str,
-    ) -> Result<()> {
-        let state = self.state.lock();
+    /// Whether economic tracking is enabled in the config.
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled
+    }
 
-        let record = WorkIncomeRecord {
-            timestamp: Utc::now(),
-            date: state.task.task_date.clone()
-                .unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string()),
-            task_id: task_id.to_string(),
-            base_amount,
-            actual_payment,
-            evaluation_score,
-            threshold: self.config.min_evaluation_threshold,
-            payment_awarded: actual_payment > 0.0,
-            description: description.to_string(),
-            balance_after: state.balance,
+    /// Where LLM token usage is recorded from.
+    pub fn llm_usage_source(&self) -> LlmUsageSource {
+        self.config.llm_usage_source
+    }
+
+    /// Track an LLM call reported through observer events, see
+    /// `EconomicObserver`.
+    ///
+    /// Priced like [`track_provider_tokens`](Self::track_provider_tokens),
+    /// with cached input tokens discounted like
+    /// [`track_tokens_with_cache`](Self::track_tokens_with_cache). The call
+    /// is charged to the active task its session ID names, if any, and to
+    /// the current task otherwise. Ignored, returning a zero charge, unless
+    /// `llm_usage_source` is [`LlmUsageSource::Observer`].
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    ///
+    /// # Errors
+    /// Same as [`track_tokens`](Self::track_tokens).
+    pub fn track_observed_llm_call(&self, call: ObservedLlmCall) -> Result<Charge> {
+        if self.config.llm_usage_source != LlmUsageSource::Observer {
+            return Ok(Charge::default());
+        }
+        let now = Utc::now();
+        let multiplier = self.time_of_use_multiplier(now);
+        let pricing = self.model_token_pricing(Some(&call.provider), Some(&call.model));
+        let cached_input_tokens = call.cached_input_tokens.min(call.input_tokens);
+        let full_cost = pricing.calculate_cost(call.input_tokens, call.output_tokens) * multiplier;
+        let cache_savings_usd = (cached_input_tokens as f64 / 1_000_000.0)
+            * pricing.input_price_per_million
+            * self.config.cache_discount_rate.clamp(0.0, 1.0)
+            * multiplier;
+
+        let task_id = call.session_id.clone();
+        let record = LlmCallRecord {
+            id: new_charge_id(),
+            timestamp: now,
+            api_name: "agent".to_string(),
+            input_tokens: call.input_tokens,
+            output_tokens: call.output_tokens,
+            cost: full_cost - cache_savings_usd,
+            cache_hit: cached_input_tokens > 0,
+            cache_savings_usd,
+            model: Some(call.model),
+            provider: Some(call.provider),
+            prompt_type: None,
+            request_id: None,
+            retry_attempt: 0,
+            latency_ms: u64::try_from(call.duration.as_millis()).unwrap_or(u64::MAX),
+            session_id: call.session_id,
         };
+        self.apply_llm_call(record, task_id.as_deref())
+    }
+
+    /// Apply an explicitly tracked LLM call, unless usage is recorded from
+    /// observer events.
+    fn record_llm_call(&self, record: LlmCallRecord) -> Result<Charge> {
+        if self.config.llm_usage_source == LlmUsageSource::Observer {
+            tracing::debug!(
+                "LLM usage is recorded from observer events, ignoring {} call",
+                record.api_name
+            );
+            return Ok(Charge::default());
+        }
+        self.apply_llm_call(record, None)
+    }
+
+    /// Apply a priced LLM call to session, daily, task, and balance state,
+    /// charging it to the active task `task_id` names or else to the
+    /// current task.
+    ///
+    /// Rejected without recording anything when that task has already
+    /// spent `max_cost_per_task`.
+    fn apply_llm_call(&self, record: LlmCallRecord, task_id: Option<&str>) -> Result<Charge> {
+        let cost = record.cost;
+        let mut state = self.state.lock();
+        let task_id = task_id
+            .filter(|id| state.tasks.contains_key(*id))
+            .map(str::to_string)
+            .or_else(|| state.current_task.clone());
+        let ceiling = self.config.max_cost_per_task;
+        let task = task_id.as_ref().and_then(|id| state.tasks.get(id));
+        if let (Some(ceiling), Some(task)) = (ceiling, task) {
+            if task.costs.total() >= ceiling {
+                tracing::warn!(
+                    "🛑 Task {} reached its ${ceiling:.4} cost ceiling; rejecting {} call",
+                    task.task_id,
+                    record.api_name
+                );
+                return Err(EconomicError::TaskCostCeilingExceeded {
+                    task_id: task.task_id.clone(),
+                    ceiling,
+                });
+            }
+        }
+        let previous_status = self.get_survival_status_inner(&state);
+
+        // Update session tracking
+        state.session.input_tokens += record.input_tokens;
+        state.session.output_tokens += record.output_tokens;
+        state.session.cost += cost;
+        state.daily.cost += cost;
+
+        // Update task-level tracking
+        state.last_charge_id = Some(record.id.clone());
+        let model = record.model.clone();
+        let timestamp = record.timestamp;
+        let untasked = task_id.is_none();
+        if let Some(task) = task_id.as_ref().and_then(|id| state.tasks.get_mut(id)) {
+            task.costs.llm_tokens += cost;
+            task.llm_calls.push(record);
+        }
+
+        // Update totals
+        state.total_token_cost += cost;
+        state.balance -= cost;
+        state.dirty = true;
+        if untasked {
+            // Task calls are logged with the task
+            state.unlogged_changes.push(BalanceChange {
+                timestamp,
+                delta: -cost,
+            });
+        }
+        let grace_warning = self.track_grace_spend(&mut state, cost);
+        // Sent under the lock so events arrive in balance order
+        let _ = self.cost_events.send(CostEvent::TokensTracked {
+            task_id,
+            model,
+            cost,
+            balance: state.balance,
+        });
 
+        self.log_state_change(&state, "tokens tracked", cost);
+        self.log_status_change(&state, previous_status, "tokens tracked");
+        let bankruptcy = self.update_bankruptcy_flag(&mut state);
+        let intake_change = self.update_intake(&mut state);
         drop(state);
 
-        let mut file = OpenOptions::new()
-            .create(true)
-            .append(true)
-            .open(self.token_costs_file_path())?;
-        writeln!(file, "{}", serde_json::to_string(&record)?)?;
-        file.sync_all()?;
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+        Ok(Charge {
+            cost,
+            grace_warning,
+        })
+    }
+
+    /// Track token-based API call cost.
+    ///
+    /// # Arguments
+    /// * `tokens` - Number of tokens used
+    /// * `price_per_million` - Price per million tokens
+    /// * `api_name` - Name of the API
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    ///
+    /// Ignored, returning a zero charge, when `llm_usage_source` is
+    /// [`LlmUsageSource::Observer`].
+    pub fn track_api_call(
+        &self,
+        tokens: u64,
+        price_per_million: f64,
+        api_name: impl Into<String>,
+    ) -> Charge {
+        let api_name = api_name.into();
+        let cost = (tokens as f64 / 1_000_000.0) * price_per_million;
+
+        let category = ApiCategory::from_api_name(&api_name);
+        self.record_api_cost(
+            &api_name,
+            cost,
+            Some(tokens),
+            Some(price_per_million),
+            PricingModel::PerToken,
+            category,
+        )
+    }
+
+    /// Track flat-rate API call cost.
+    ///
+    /// # Arguments
+    /// * `cost` - Flat cost in USD
+    /// * `api_name` - Name of the API
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call; its cost is the input.
+    ///
+    /// Ignored, returning a zero charge, when `llm_usage_source` is
+    /// [`LlmUsageSource::Observer`].
+    pub fn track_flat_api_call(&self, cost: f64, api_name: impl Into<String>) -> Charge {
+        let api_name = api_name.into();
+        let category = ApiCategory::from_api_name(&api_name);
+        self.record_api_cost(&api_name, cost, None, None, PricingModel::FlatRate, category)
+    }
+
+    /// Track an embedding API call.
+    ///
+    /// Priced per million tokens from `embedding_pricing`, falling back to
+    /// `default_embedding_price_per_million` for unknown models.
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    ///
+    /// Ignored, returning a zero charge, when `llm_usage_source` is
+    /// [`LlmUsageSource::Observer`].
+    pub fn track_embedding(&self, model: &str, tokens: u64) -> Charge {
+        let price_per_million = self.embedding_price(model);
+        let cost = (tokens as f64 / 1_000_000.0) * price_per_million;
+
+        self.record_api_cost(
+            model,
+            cost,
+            Some(tokens),
+            Some(price_per_million),
+            PricingModel::PerToken,
+            ApiCategory::Embedding,
+        )
+    }
+
+    /// Track an image-generation API call.
+    ///
+    /// Priced per image from `image_pricing`, falling back to
+    /// `default_image_pricing` for unknown models.
+    ///
+    /// # Returns
+    /// The [`Charge`] for all generated images.
+    ///
+    /// Ignored, returning a zero charge, when `llm_usage_source` is
+    /// [`LlmUsageSource::Observer`].
+    pub fn track_image_generation(
+        &self,
+        model: &str,
+        count: u32,
+        size_class: ImageSizeClass,
+    ) -> Charge {
+        let cost = self.image_price(model, size_class) * f64::from(count);
+
+        self.record_api_cost(
+            model,
+            cost,
+            None,
+            None,
+            PricingModel::FlatRate,
+            ApiCategory::ImageGeneration,
+        )
+    }
+
+    /// Track an embedding, image-generation, or paid tool call reported
+    /// through observer events, see `EconomicObserver`.
+    ///
+    /// Calls without a cost in the event are priced like
+    /// [`track_embedding`](Self::track_embedding) and
+    /// [`track_image_generation`](Self::track_image_generation). A tool
+    /// call is charged to the active task its `task_id` names, if any, and
+    /// to the current task otherwise. Ignored, returning a zero charge, unless
+    /// `llm_usage_source` is [`LlmUsageSource::Observer`].
+    ///
+    /// # Returns
+    /// The [`Charge`] for this call, as for [`track_tokens`](Self::track_tokens).
+    pub fn track_observed_api_call(&self, call: ObservedApiCall) -> Charge {
+        if self.config.llm_usage_source != LlmUsageSource::Observer {
+            return Charge::default();
+        }
+        let (record, category, task_id) = match call {
+            ObservedApiCall::Embedding {
+                model,
+                tokens,
+                cost_usd,
+            } => {
+                let price_per_million = self.embedding_price(&model);
+                let cost = cost_usd
+                    .unwrap_or_else(|| (tokens as f64 / 1_000_000.0) * price_per_million);
+                let record = api_call_record(model, cost, PricingModel::PerToken);
+                let record = ApiCallRecord {
+                    tokens: Some(tokens),
+                    price_per_million: cost_usd.is_none().then_some(price_per_million),
+                    ..record
+                };
+                (record, ApiCategory::Embedding, None)
+            }
+            ObservedApiCall::ImageGeneration {
+                model,
+                count,
+                size_class,
+                cost_usd,
+            } => {
+                let cost = cost_usd
+                    .unwrap_or_else(|| self.image_price(&model, size_class) * f64::from(count));
+                let record = api_call_record(model, cost, PricingModel::FlatRate);
+                (record, ApiCategory::ImageGeneration, None)
+            }
+            ObservedApiCall::Tool {
+                tool,
+                cost_usd,
+                task_id,
+            } => {
+                let category = ApiCategory::from_api_name(&tool);
+                let record = api_call_record(tool, cost_usd, PricingModel::FlatRate);
+                (record, category, task_id)
+            }
+        };
+        self.apply_api_cost(record, category, task_id.as_deref())
+    }
+
+    /// Embedding price per million tokens for `model`.
+    fn embedding_price(&self, model: &str) -> f64 {
+        match self.config.embedding_pricing.get(model) {
+            Some(price) => *price,
+            None => {
+                tracing::debug!(
+                    "No embedding pricing found for {}, using default (${} per 1M tokens)",
+                    model,
+                    self.config.default_embedding_price_per_million
+                );
+                self.config.default_embedding_price_per_million
+            }
+        }
+    }
+
+    /// Price of one image of `size_class` from `model`.
+    fn image_price(&self, model: &str, size_class: ImageSizeClass) -> f64 {
+        let pricing = match self.config.image_pricing.get(model) {
+            Some(pricing) => pricing,
+            None => {
+                tracing::debug!(
+                    "No image pricing found for {}, using default pricing",
+                    model
+                );
+                &self.config.default_image_pricing
+            }
+        };
+        pricing.price_for(size_class)
+    }
+
+    /// Apply an explicitly tracked API call, unless usage is recorded from
+    /// observer events.
+    fn record_api_cost(
+        &self,
+        api_name: &str,
+        cost: f64,
+        tokens: Option<u64>,
+        price_per_million: Option<f64>,
+        pricing_model: PricingModel,
+        category: ApiCategory,
+    ) -> Charge {
+        if self.config.llm_usage_source == LlmUsageSource::Observer {
+            tracing::debug!(
+                "API usage is recorded from observer events, ignoring {} call",
+                api_name
+            );
+            return Charge::default();
+        }
+        let record = ApiCallRecord {
+            tokens,
+            price_per_million,
+            ..api_call_record(api_name.to_string(), cost, pricing_model)
+        };
+        self.apply_api_cost(record, category, None)
+    }
+
+    /// Apply a priced API call to session, daily, task, and balance state,
+    /// charging it to the active task `task_id` names or else to the
+    /// current task.
+    fn apply_api_cost(
+        &self,
+        record: ApiCallRecord,
+        category: ApiCategory,
+        task_id: Option<&str>,
+    ) -> Charge {
+        let cost = record.cost;
+        let mut state = self.state.lock();
+        let previous_status = self.get_survival_status_inner(&state);
+
+        // Update session/daily
+        state.session.cost += cost;
+        state.daily.cost += cost;
+
+        state.last_charge_id = Some(record.id.clone());
+        let task_id = task_id
+            .filter(|id| state.tasks.contains_key(*id))
+            .map(str::to_string)
+            .or_else(|| state.current_task.clone());
+        let timestamp = record.timestamp;
+        let task = task_id.as_ref().and_then(|id| state.tasks.get_mut(id));
+        let untasked = task.is_none();
+        if let Some(task) = task {
+            // Attribute to the service category
+            match category {
+                ApiCategory::Search => task.costs.search_api += cost,
+                ApiCategory::Ocr => task.costs.ocr_api += cost,
+                ApiCategory::Embedding => task.costs.embedding_api += cost,
+                ApiCategory::ImageGeneration => task.costs.image_api += cost,
+                ApiCategory::Other => task.costs.other_api += cost,
+            }
+
+            // Record detailed call
+            task.api_calls.push(record);
+        }
+
+        // Update totals
+        state.total_token_cost += cost;
+        state.balance -= cost;
+        state.dirty = true;
+        if untasked {
+            // Task calls are logged with the task
+            state.unlogged_changes.push(BalanceChange {
+                timestamp,
+                delta: -cost,
+            });
+        }
+        let grace_warning = self.track_grace_spend(&mut state, cost);
+
+        self.log_state_change(&state, "api cost recorded", cost);
+        self.log_status_change(&state, previous_status, "api cost recorded");
+        let bankruptcy = self.update_bankruptcy_flag(&mut state);
+        let intake_change = self.update_intake(&mut state);
+        drop(state);
+
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+        Charge {
+            cost,
+            grace_warning,
+        }
+    }
+
+    /// Register a custom payment gate for [`add_work_income`](Self::add_work_income).
+    ///
+    /// Validators run in registration order once the evaluation threshold
+    /// passes; all of them must approve, and an `Adjusted` amount is what
+    /// later validators see and what gets paid.
+    pub fn register_income_validator(&self, validator: IncomeValidator) {
+        self.income_validators.write().push(validator);
+    }
+
+    /// Add income from completed work with evaluation threshold.
+    ///
+    /// Payment is only awarded if `evaluation_score >= min_evaluation_threshold`
+    /// and every registered income validator approves.
+    ///
+    /// # Arguments
+    /// * `amount` - Base payment amount in USD
+    /// * `task_id` - Task identifier
+    /// * `evaluation_score` - Score from 0.0 to 1.0
+    /// * `description` - Optional description
+    ///
+    /// # Returns
+    /// Actual payment received (0.0 if below threshold or rejected).
+    ///
+    /// # Errors
+    /// [`EconomicError::DuplicateIncome`] if the task was already paid.
+    pub fn add_work_income(
+        &self,
+        amount: f64,
+        task_id: impl Into<String>,
+        evaluation_score: f64,
+        description: impl Into<String>,
+    ) -> Result<f64> {
+        self.add_work_income_with_metadata(
+            amount,
+            task_id,
+            evaluation_score,
+            description,
+            BTreeMap::new(),
+        )
+    }
+
+    /// [`add_work_income`](Self::add_work_income), storing `metadata` with
+    /// the income record.
+    ///
+    /// # Errors
+    /// [`EconomicError::MetadataTooLarge`] if `metadata` has more than
+    /// `MAX_METADATA_KEYS` entries or `MAX_METADATA_BYTES` bytes;
+    /// [`EconomicError::DuplicateIncome`] if the task was already paid.
+    pub fn add_work_income_with_metadata(
+        &self,
+        amount: f64,
+        task_id: impl Into<String>,
+        evaluation_score: f64,
+        description: impl Into<String>,
+        metadata: BTreeMap<String, String>,
+    ) -> Result<f64> {
+        validate_metadata(&metadata)?;
+        let candidate = WorkIncomeCandidate {
+            task_id: task_id.into(),
+            evaluation_score,
+            amount,
+            description: description.into(),
+            metadata,
+        };
+        self.credit_work_income(candidate, None, RetryPolicy::NONE)
+    }
+
+    /// [`add_work_income`](Self::add_work_income), retrying the log writes
+    /// after transient I/O errors (full disk, a locked file).
+    ///
+    /// The payment is credited to the in-memory balance once, up front; the
+    /// balance and income records are then written with up to `max_retries`
+    /// retries, waiting `retry_delay` before the first and doubling the wait
+    /// each time. Failed attempts are logged with `tracing::warn!`.
+    ///
+    /// # Errors
+    /// [`EconomicError::Io`] if the writes still fail after the last retry.
+    /// The payment stays in the balance, which the next flush persists, but
+    /// no income record is written for it.
+    pub fn add_work_income_with_retry(
+        &self,
+        amount: f64,
+        task_id: &str,
+        evaluation_score: f64,
+        description: &str,
+        max_retries: u32,
+        retry_delay: Duration,
+    ) -> Result<f64> {
+        let candidate = WorkIncomeCandidate {
+            task_id: task_id.to_string(),
+            evaluation_score,
+            amount,
+            description: description.to_string(),
+            metadata: BTreeMap::new(),
+        };
+        let retry = RetryPolicy {
+            max_retries,
+            retry_delay,
+        };
+        self.credit_work_income(candidate, None, retry)
+    }
+
+    /// Pay `candidate` if it passes the threshold and the validators,
+    /// storing `evaluation_reasoning` with the income record. The log writes
+    /// are retried according to `retry`.
+    fn credit_work_income(
+        &self,
+        candidate: WorkIncomeCandidate,
+        evaluation_reasoning: Option<String>,
+        retry: RetryPolicy,
+    ) -> Result<f64> {
+        let task_id = candidate.task_id.as_str();
+        let evaluation_score = candidate.evaluation_score;
+        let amount = candidate.amount;
+        let threshold = self.config.min_evaluation_threshold;
+
+        let mut actual_payment = if evaluation_score >= threshold {
+            amount
+        } else {
+            0.0
+        };
+        if actual_payment > 0.0 && self.is_task_paid(task_id)? {
+            return Err(EconomicError::DuplicateIncome {
+                task_id: task_id.to_string(),
+            });
+        }
+        let mut rejection_reason = None;
+        if actual_payment > 0.0 {
+            match run_validators(&self.income_validators.read(), candidate.clone()) {
+                Ok(amount) => actual_payment = amount,
+                Err(reason) => {
+                    tracing::warn!(
+                        "⚠️ Income validator rejected payment for task {}: {}",
+                        task_id,
+                        reason
+                    );
+                    actual_payment = 0.0;
+                    rejection_reason = Some(reason);
+                }
+            }
+        }
+
+        let (received_at, bankruptcy, intake_change) = {
+            let mut state = self.state.lock();
+            // Stamped under the lock so the flush below never predates it
+            let received_at = Utc::now();
+            let mut bankruptcy = BankruptcyChange::default();
+            let mut intake_change = None;
+            if actual_payment > 0.0 {
+                let previous_status = self.get_survival_status_inner(&state);
+                state.balance += actual_payment;
+                state.total_work_income += actual_payment;
+                state.dirty = true;
+                tracing::info!(
+                    "💰 Work income: +${:.2} (Task: {}, Score: {:.2})",
+                    actual_payment,
+                    task_id,
+                    evaluation_score
+                );
+                let _ = self.cost_events.send(CostEvent::IncomeReceived {
+                    task_id: task_id.to_string(),
+                    amount: actual_payment,
+                    balance: state.balance,
+                });
+                self.log_state_change(&state, "income added", -actual_payment);
+                self.log_status_change(&state, previous_status, "income added");
+                bankruptcy = self.update_bankruptcy_flag(&mut state);
+                intake_change = self.update_intake(&mut state);
+            } else if evaluation_score < threshold {
+                tracing::warn!(
+                    "⚠️ Work below threshold (score: {:.2} < {:.2}), no payment for task: {}",
+                    evaluation_score,
+                    threshold,
+                    task_id
+                );
+            }
+            (received_at, bankruptcy, intake_change)
+        };
+
+        // Balance first, so the income record is never on disk without it
+        let persisted = retry.run("work income", || {
+            self.checkpoint()?;
+            self.log_work_income(
+                received_at,
+                &candidate,
+                actual_payment,
+                rejection_reason.clone(),
+                evaluation_reasoning.clone(),
+            )
+        });
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+
+        persisted?;
+        Ok(actual_payment)
+    }
+
+    /// Set the evaluator used by [`complete_and_evaluate`](Self::complete_and_evaluate).
+    pub fn set_quality_evaluator(&self, evaluator: Arc<dyn QualityEvaluator>) {
+        *self.quality_evaluator.write() = Some(evaluator);
+    }
+
+    /// Score a deliverable with the quality evaluator and pay for it.
+    ///
+    /// The evaluator's score goes through the same threshold and income
+    /// validators as [`add_work_income`](Self::add_work_income), and its
+    /// reasoning is stored with the income record. Tokens the evaluator
+    /// reports are charged under the `evaluator` channel.
+    ///
+    /// If the evaluator fails, `amount` is held in escrow instead of being
+    /// paid; settle it later with [`release_escrow`](Self::release_escrow).
+    ///
+    /// # Errors
+    /// [`EconomicError::InvalidConfig`] if no evaluator is set.
+    pub async fn complete_and_evaluate(
+        &self,
+        task_id: &str,
+        instruction: &str,
+        deliverable: &str,
+        amount: f64,
+    ) -> Result<EvaluationOutcome> {
+        let evaluator = self.quality_evaluator.read().clone().ok_or_else(|| {
+            EconomicError::InvalidConfig("no quality evaluator is set".to_string())
+        })?;
+
+        let evaluation = evaluator.evaluate(task_id, instruction, deliverable).await;
+        if let Some(usage) = evaluation.as_ref().ok().and_then(|score| score.usage.as_ref()) {
+            if let Err(err) = self.track_model_tokens(
+                &usage.model,
+                usage.input_tokens,
+                usage.output_tokens,
+                "evaluator",
+                None,
+            ) {
+                tracing::warn!("⚠️ Evaluator usage for {task_id} not tracked: {err}");
+            }
+        }
+        let score = match evaluation {
+            Ok(score) if (0.0..=1.0).contains(&score.score) => score,
+            Ok(score) => {
+                let reason = format!("evaluator returned out-of-range score {}", score.score);
+                return self.hold_in_escrow(task_id, instruction, deliverable, amount, reason);
+            }
+            Err(e) => {
+                let reason = format!("{e:#}");
+                return self.hold_in_escrow(task_id, instruction, deliverable, amount, reason);
+            }
+        };
+
+        let payment = self.credit_work_income(
+            WorkIncomeCandidate {
+                task_id: task_id.to_string(),
+                evaluation_score: score.score,
+                amount,
+                description: String::new(),
+                metadata: BTreeMap::new(),
+            },
+            Some(score.reasoning.clone()),
+            RetryPolicy::NONE,
+        )?;
+        Ok(EvaluationOutcome::Settled { score, payment })
+    }
+
+    /// Settle a payment held in escrow with a score obtained elsewhere.
+    ///
+    /// The held amount is paid like [`add_work_income`](Self::add_work_income)
+    /// would pay it, with `reasoning` stored as the evaluation reasoning.
+    ///
+    /// # Errors
+    /// [`EconomicError::TaskNotFound`] if nothing is held for the task.
+    pub fn release_escrow(
+        &self,
+        task_id: &str,
+        evaluation_score: f64,
+        reasoning: &str,
+    ) -> Result<f64> {
+        let Some(amount) = self.state.lock().escrow.get(task_id).copied() else {
+            return Err(EconomicError::TaskNotFound {
+                task_id: task_id.to_string(),
+            });
+        };
+
+        let payment = self.credit_work_income(
+            WorkIncomeCandidate {
+                task_id: task_id.to_string(),
+                evaluation_score,
+                amount,
+                description: String::new(),
+                metadata: BTreeMap::new(),
+            },
+            Some(reasoning.to_string()),
+            RetryPolicy::NONE,
+        )?;
+        let record = EscrowRecord {
+            timestamp: Utc::now(),
+            kind: EscrowEventKind::Released,
+            task_id: task_id.to_string(),
+            amount,
+            reason: reasoning.to_string(),
+            instruction: String::new(),
+            deliverable: String::new(),
+        };
+        self.append_record(&self.escrow_file_path(), &record)?;
+        self.state.lock().escrow.remove(task_id);
+        Ok(payment)
+    }
+
+    /// Payments currently held in escrow, oldest first.
+    pub fn get_escrowed_income(&self) -> Result<Vec<EscrowRecord>> {
+        let mut held: Vec<EscrowRecord> = Vec::new();
+        self.for_each_record::<EscrowRecord, _>(&self.escrow_file_path(), |record| {
+            held.retain(|earlier| earlier.task_id != record.task_id);
+            if record.kind == EscrowEventKind::Held {
+                held.push(record);
+            }
+        })?;
+        Ok(held)
+    }
+
+    /// Total amount held in escrow.
+    pub fn get_pending_escrow(&self) -> f64 {
+        self.state.lock().escrow.values().sum()
+    }
+
+    /// Register a subscription charge of `amount_usd` due at `next_due`,
+    /// replacing any earlier one with the same name.
+    ///
+    /// Subscriptions are kept in memory and only count toward
+    /// [`get_working_capital`](Self::get_working_capital); register the next
+    /// due time again once a charge has been paid.
+    ///
+    /// # Errors
+    /// Fails when `amount_usd` is not a positive amount.
+    pub fn add_subscription(
+        &self,
+        name: impl Into<String>,
+        amount_usd: f64,
+        next_due: DateTime<Utc>,
+    ) -> Result<()> {
+        if !amount_usd.is_finite() || amount_usd <= 0.0 {
+            return Err(EconomicError::InvalidAmount { amount: amount_usd });
+        }
+        self.state
+            .lock()
+            .subscriptions
+            .insert(name.into(), (amount_usd, next_due));
+        Ok(())
+    }
+
+    /// Remove a subscription; returns whether it was registered.
+    pub fn remove_subscription(&self, name: &str) -> bool {
+        self.state.lock().subscriptions.remove(name).is_some()
+    }
+
+    /// Funds available once near-term obligations are settled.
+    ///
+    /// Escrowed income is counted as incoming. Active tasks count the part of
+    /// their budget not yet spent (costs so far are already deducted from the
+    /// balance); tasks without a budget add nothing. Subscriptions due in the
+    /// next 24 hours, or overdue, are counted as outgoing.
+    pub fn get_working_capital(&self) -> WorkingCapital {
+        let state = self.state.lock();
+        let pending_escrow: f64 = state.escrow.values().sum();
+        let estimated_active_task_cost: f64 = state
+            .tasks
+            .values()
+            .filter_map(|task| {
+                task.estimated_budget
+                    .map(|budget| (budget - task.costs.total()).max(0.0))
+            })
+            .sum();
+        let due_by = Utc::now() + chrono::Duration::hours(24);
+        let upcoming_subscriptions: f64 = state
+            .subscriptions
+            .values()
+            .filter(|(_, next_due)| *next_due <= due_by)
+            .map(|(amount, _)| amount)
+            .sum();
+        let net_working_capital = state.balance + pending_escrow
+            - estimated_active_task_cost
+            - upcoming_subscriptions;
+        WorkingCapital {
+            current_balance: state.balance,
+            pending_escrow,
+            estimated_active_task_cost,
+            upcoming_subscriptions,
+            net_working_capital,
+            status: SurvivalStatus::from_balance(net_working_capital, state.initial_balance),
+        }
+    }
+
+    /// Attribute a cost incurred outside any task (start-up, tool loading,
+    /// ...) to tasks according to `strategy`.
+    ///
+    /// The cost is taken to be charged already, e.g. through `track_tokens`
+    /// with no task active, so the balance does not change. Each call is
+    /// appended to `overhead.jsonl` as an [`OverheadRecord`], with the shares
+    /// of completed tasks when the cost is split; cost held for the next
+    /// task and cost written off are restored from it by `initialize`.
+    ///
+    /// # Errors
+    /// Fails when `total_cost` is not a positive amount, or with
+    /// [`EconomicError::NoCompletedTasks`] when the cost is to be split and
+    /// no task has completed.
+    pub fn prorate_shared_cost(&self, total_cost: f64, strategy: ProrationStrategy) -> Result<()> {
+        if !total_cost.is_finite() || total_cost <= 0.0 {
+            return Err(EconomicError::InvalidAmount { amount: total_cost });
+        }
+        let shares = match strategy {
+            ProrationStrategy::AddToNextTask | ProrationStrategy::WriteOffAsOverhead => {
+                BTreeMap::new()
+            }
+            ProrationStrategy::EvenlyAcrossAllTasks | ProrationStrategy::ProportionalToTokens => {
+                self.split_shared_cost(total_cost, strategy)?
+            }
+        };
+
+        // Appended under the state lock, so the last record holds the totals
+        let mut state = self.state.lock();
+        let (mut pending, mut written_off) = (state.pending_overhead, state.written_off_overhead);
+        match strategy {
+            ProrationStrategy::AddToNextTask => pending += total_cost,
+            ProrationStrategy::WriteOffAsOverhead => written_off += total_cost,
+            ProrationStrategy::EvenlyAcrossAllTasks | ProrationStrategy::ProportionalToTokens => {}
+        }
+        let record = OverheadRecord {
+            timestamp: Utc::now(),
+            strategy,
+            amount: total_cost,
+            shares,
+            pending_overhead: pending,
+            written_off_overhead: written_off,
+        };
+        self.append_record(&self.overhead_file_path(), &record)?;
+        state.pending_overhead = pending;
+        state.written_off_overhead = written_off;
+        Ok(())
+    }
+
+    /// Shares of `total_cost` for each completed task under `strategy`.
+    fn split_shared_cost(
+        &self,
+        total_cost: f64,
+        strategy: ProrationStrategy,
+    ) -> Result<BTreeMap<String, f64>> {
+        let mut completed: Vec<String> = Vec::new();
+        self.for_each_record::<TaskCompletionRecord, _>(
+            &self.task_completions_file_path(),
+            |record| {
+                if record.status == TaskStatus::Completed {
+                    completed.push(record.task_id);
+                }
+            },
+        )?;
+        if completed.is_empty() {
+            return Err(EconomicError::NoCompletedTasks);
+        }
+
+        let mut weights = vec![1.0; completed.len()];
+        if strategy == ProrationStrategy::ProportionalToTokens {
+            let mut tokens: HashMap<String, u64> = HashMap::new();
+            self.for_each_record::<TaskCostRecord, _>(&self.token_costs_file_path(), |record| {
+                *tokens.entry(record.task_id).or_default() += record.llm_usage.total_tokens;
+            })?;
+            let task_tokens: Vec<f64> = completed
+                .iter()
+                .map(|task_id| tokens.get(task_id).copied().unwrap_or(0) as f64)
+                .collect();
+            // Tasks that used no tokens at all share evenly instead
+            if task_tokens.iter().any(|&tokens| tokens > 0.0) {
+                weights = task_tokens;
+            }
+        }
+        let total_weight: f64 = weights.iter().sum();
+
+        let mut shares = BTreeMap::new();
+        for (task_id, weight) in completed.into_iter().zip(weights) {
+            *shares.entry(task_id).or_default() += total_cost * weight / total_weight;
+        }
+        Ok(shares)
+    }
+
+    /// Shared cost written off with [`ProrationStrategy::WriteOffAsOverhead`],
+    /// including earlier runs recorded in `overhead.jsonl`.
+    pub fn get_written_off_overhead(&self) -> f64 {
+        self.state.lock().written_off_overhead
+    }
+
+    fn hold_in_escrow(
+        &self,
+        task_id: &str,
+        instruction: &str,
+        deliverable: &str,
+        amount: f64,
+        reason: String,
+    ) -> Result<EvaluationOutcome> {
+        tracing::warn!(
+            "⚠️ Evaluation failed for task {}, holding ${:.2} in escrow: {}",
+            task_id,
+            amount,
+            reason
+        );
+        let record = EscrowRecord {
+            timestamp: Utc::now(),
+            kind: EscrowEventKind::Held,
+            task_id: task_id.to_string(),
+            amount,
+            reason: reason.clone(),
+            instruction: instruction.to_string(),
+            deliverable: deliverable.to_string(),
+        };
+        self.append_record(&self.escrow_file_path(), &record)?;
+        self.state.lock().escrow.insert(task_id.to_string(), amount);
+        Ok(EvaluationOutcome::Escrowed { reason })
+    }
+
+    /// Add grant income not tied to task completion.
+    ///
+    /// Unlike [`add_work_income`](Self::add_work_income), no evaluation
+    /// threshold applies: the amount is always credited. The grant is
+    /// appended to `grant_income.jsonl`.
+    ///
+    /// # Returns
+    /// The balance after the grant.
+    pub fn add_grant_income(&self, amount: f64, grant_id: &str, description: &str) -> Result<f64> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(EconomicError::InvalidAmount { amount });
+        }
+
+        let (record, bankruptcy, intake_change) = {
+            let mut state = self.state.lock();
+            let previous_status = self.get_survival_status_inner(&state);
+            state.balance += amount;
+            state.total_grant_income += amount;
+            state.dirty = true;
+            tracing::info!("💰 Grant income: +${:.2} (Grant: {})", amount, grant_id);
+            self.log_state_change(&state, "grant income added", -amount);
+            self.log_status_change(&state, previous_status, "grant income added");
+            let bankruptcy = self.update_bankruptcy_flag(&mut state);
+            let intake_change = self.update_intake(&mut state);
+            let now = Utc::now();
+            let record = GrantIncomeRecord {
+                timestamp: now,
+                date: now.format("%Y-%m-%d").to_string(),
+                grant_id: grant_id.to_string(),
+                amount,
+                description: description.to_string(),
+                balance_after: state.balance,
+            };
+            (record, bankruptcy, intake_change)
+        };
+
+        // Balance first, so the grant record is never on disk without it
+        self.checkpoint()?;
+        self.append_record(&self.grant_income_file_path(), &record)?;
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+
+        Ok(record.balance_after)
+    }
+
+    /// Apply daily interest for every day not yet accrued, up to and
+    /// including `through`.
+    ///
+    /// Each day, a positive balance earns `daily_yield_rate` and a negative
+    /// balance is charged `daily_debt_rate`, compounding on the previous
+    /// day's result. A day is never accrued twice, so calling this again for
+    /// the same date is a no-op; the first call accrues `through` only.
+    /// Live agents call it once a day with today's date, simulations with
+    /// their simulated date. Each day is appended to `interest.jsonl`.
+    ///
+    /// # Returns
+    /// Net interest applied (negative when more was paid than earned).
+    pub fn accrue_interest(&self, through: NaiveDate) -> Result<f64> {
+        let (yield_rate, debt_rate) = (self.config.daily_yield_rate, self.config.daily_debt_rate);
+        if yield_rate == 0.0 && debt_rate == 0.0 {
+            return Ok(0.0);
+        }
+
+        let (records, bankruptcy, intake_change) = {
+            let mut state = self.state.lock();
+            let previous_status = self.get_survival_status_inner(&state);
+            let now = Utc::now();
+            let mut records = Vec::new();
+            let mut day = match state.last_interest_date {
+                Some(last) => last.succ_opt(),
+                None => Some(through),
+            };
+            while let Some(date) = day.filter(|date| *date <= through) {
+                day = date.succ_opt();
+                state.last_interest_date = Some(date);
+
+                let principal = state.balance;
+                let (kind, rate) = if principal >= 0.0 {
+                    (InterestKind::Earned, yield_rate)
+                } else {
+                    (InterestKind::Paid, debt_rate)
+                };
+                let amount = principal.abs() * rate;
+                if amount == 0.0 {
+                    continue;
+                }
+                match kind {
+                    InterestKind::Earned => {
+                        state.balance += amount;
+                        state.total_interest_earned += amount;
+                    }
+                    InterestKind::Paid => {
+                        state.balance -= amount;
+                        state.total_interest_paid += amount;
+                    }
+                }
+                records.push(InterestRecord {
+                    timestamp: now,
+                    date: date.format("%Y-%m-%d").to_string(),
+                    kind,
+                    rate,
+                    principal,
+                    amount,
+                    balance_after: state.balance,
+                });
+            }
+            if records.is_empty() {
+                return Ok(0.0);
+            }
+
+            state.dirty = true;
+            let net: f64 = records.iter().map(InterestRecord::delta).sum();
+            tracing::info!(
+                "🏦 Interest for {} day(s): {:+.4}, new balance: ${:.2}",
+                records.len(),
+                net,
+                state.balance
+            );
+            self.log_state_change(&state, "interest accrued", -net);
+            self.log_status_change(&state, previous_status, "interest accrued");
+            let bankruptcy = self.update_bankruptcy_flag(&mut state);
+            let intake_change = self.update_intake(&mut state);
+            (records, bankruptcy, intake_change)
+        };
+
+        // Balance first, so an interest record is never on disk without it
+        self.checkpoint()?;
+        for record in &records {
+            self.append_record(&self.interest_file_path(), record)?;
+        }
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+
+        Ok(records.iter().map(InterestRecord::delta).sum())
+    }
+
+    /// Credit a refund or billing correction against an earlier charge.
+    ///
+    /// `original_record_id` is the `id` of an `LlmCallRecord` or
+    /// `ApiCallRecord` recorded under a task (see
+    /// [`last_charge_id`](Self::last_charge_id)). The refund, together with
+    /// any earlier refunds of the same charge, may not exceed its cost. The
+    /// refund is appended to `refunds.jsonl` as a negative-cost
+    /// `RefundRecord`.
+    ///
+    /// # Returns
+    /// The balance after the refund.
+    pub fn track_refund(&self, original_record_id: &str, amount: f64, reason: &str) -> Result<f64> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(EconomicError::InvalidAmount { amount });
+        }
+
+        let (record, bankruptcy, intake_change) = {
+            // Look up, check, and credit under one lock, so concurrent
+            // refunds of the same charge cannot together exceed it
+            let mut state = self.state.lock();
+            let original_cost = self
+                .find_charge_cost(&state, original_record_id)?
+                .ok_or_else(|| EconomicError::RecordNotFound {
+                    record_id: original_record_id.to_string(),
+                })?;
+            let refunded = match state.refunded.take() {
+                Some(refunded) => refunded,
+                None => self.load_refunded()?,
+            };
+            let refunded = state.refunded.insert(refunded);
+            let already_refunded = refunded.get(original_record_id).copied().unwrap_or(0.0);
+            let refundable = (original_cost - already_refunded).max(0.0);
+            if amount > refundable + f64::EPSILON {
+                return Err(EconomicError::RefundExceedsCharge {
+                    record_id: original_record_id.to_string(),
+                    requested: amount,
+                    refundable,
+                });
+            }
+            *refunded.entry(original_record_id.to_string()).or_default() += amount;
+            let previous_status = self.get_survival_status_inner(&state);
+            state.balance += amount;
+            state.total_refunds += amount;
+            state.dirty = true;
+            self.log_state_change(&state, "refund credited", -amount);
+            self.log_status_change(&state, previous_status, "refund credited");
+            let bankruptcy = self.update_bankruptcy_flag(&mut state);
+            let intake_change = self.update_intake(&mut state);
+            let record = RefundRecord {
+                timestamp: Utc::now(),
+                original_record_id: original_record_id.to_string(),
+                cost: -amount,
+                reason: reason.to_string(),
+                balance_after: state.balance,
+            };
+            (record, bankruptcy, intake_change)
+        };
+
+        // Balance first, so the refund record is never on disk without it
+        self.checkpoint()?;
+        self.append_record(&self.refunds_file_path(), &record)?;
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+
+        Ok(record.balance_after)
+    }
+
+    /// Total refunds credited so far.
+    pub fn total_refunds(&self) -> f64 {
+        self.state.lock().total_refunds
+    }
+
+    /// ID of the most recent LLM or API charge, for use with
+    /// [`track_refund`](Self::track_refund).
+    pub fn last_charge_id(&self) -> Option<String> {
+        self.state.lock().last_charge_id.clone()
+    }
+
+    /// Correct the tracked cost of a task to the billed `corrected_cost`,
+    /// e.g. when a cached response was charged at the full rate.
+    ///
+    /// The task may be active or ended. Its charges stay as recorded: a
+    /// `CostCorrectionRecord` with the difference is appended to
+    /// `cost_corrections.jsonl`, and the balance and total token cost move
+    /// by it. Corrections stack, so a second one starts from the cost as
+    /// already corrected.
+    ///
+    /// # Returns
+    /// The balance after the correction.
+    ///
+    /// # Errors
+    /// - [`EconomicError::InvalidAmount`] if `corrected_cost` is negative or
+    ///   not finite
+    /// - [`EconomicError::TaskNotFound`] if no task with this id was tracked
+    pub fn override_task_cost(
+        &self,
+        task_id: &str,
+        corrected_cost: f64,
+        reason: &str,
+    ) -> Result<f64> {
+        if !corrected_cost.is_finite() || corrected_cost < 0.0 {
+            return Err(EconomicError::InvalidAmount {
+                amount: corrected_cost,
+            });
+        }
+
+        let (record, bankruptcy, intake_change) = {
+            // The cost is read and corrected under one lock, so concurrent
+            // overrides each correct what the previous one left
+            let mut state = self.state.lock();
+            let charged = match state.tasks.get(task_id) {
+                Some(task) => task.costs.total(),
+                None => {
+                    self.logged_task_cost(task_id)?
+                        .ok_or_else(|| EconomicError::TaskNotFound {
+                            task_id: task_id.to_string(),
+                        })?
+                }
+            };
+            let corrections = match state.cost_corrections.take() {
+                Some(corrections) => corrections,
+                None => self.load_cost_corrections()?,
+            };
+            let correction = state
+                .cost_corrections
+                .insert(corrections)
+                .entry(task_id.to_string())
+                .or_default();
+            let original_cost = charged + *correction;
+            let delta = corrected_cost - original_cost;
+            *correction += delta;
+
+            let previous_status = self.get_survival_status_inner(&state);
+            state.balance -= delta;
+            state.total_token_cost += delta;
+            state.dirty = true;
+            self.log_state_change(&state, "task cost corrected", delta);
+            self.log_status_change(&state, previous_status, "task cost corrected");
+            let bankruptcy = self.update_bankruptcy_flag(&mut state);
+            let intake_change = self.update_intake(&mut state);
+            let record = CostCorrectionRecord {
+                timestamp: Utc::now(),
+                task_id: task_id.to_string(),
+                original_cost,
+                corrected_cost,
+                delta,
+                reason: reason.to_string(),
+                balance_after: state.balance,
+            };
+            (record, bankruptcy, intake_change)
+        };
+
+        // Balance first, so the correction is never on disk without it
+        self.checkpoint()?;
+        self.append_record(&self.cost_corrections_file_path(), &record)?;
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+
+        Ok(record.balance_after)
+    }
+
+    /// Pay `amount` from one agent's tracker to another's, e.g. for a
+    /// sub-task one agent delegated to the other.
+    ///
+    /// Both balances change under both trackers' locks, so no reader sees
+    /// one side without the other. Each tracker then appends a
+    /// `TransferRecord` to its own `transfers.jsonl`, sharing one
+    /// `transfer_id`.
+    ///
+    /// # Errors
+    /// - [`EconomicError::InvalidAmount`] if `amount` is not positive and finite
+    /// - [`EconomicError::SelfTransfer`] if `from` and `to` are the same tracker
+    /// - [`EconomicError::InsufficientFunds`] if the payment would leave
+    ///   `from` bankrupt
+    ///
+    /// Neither tracker is modified when one of these is returned. Once the
+    /// balances have moved, both sides are logged even if one fails, and
+    /// [`EconomicError::TransferNotLogged`] reports every side that failed.
+    pub fn transfer_balance(
+        from: &EconomicTracker,
+        to: &EconomicTracker,
+        amount: f64,
+        reason: &str,
+    ) -> Result<()> {
+        if !amount.is_finite() || amount <= 0.0 {
+            return Err(EconomicError::InvalidAmount { amount });
+        }
+        if std::ptr::eq(from, to) {
+            return Err(EconomicError::SelfTransfer {
+                agent: from.signature.clone(),
+            });
+        }
+
+        let transfer_id = uuid::Uuid::new_v4().to_string();
+        let (sent, received) = {
+            // Lock in address order so concurrent transfers in opposite
+            // directions cannot deadlock
+            let (mut from_state, mut to_state) =
+                if std::ptr::from_ref(from) < std::ptr::from_ref(to) {
+                    let from_state = from.state.lock();
+                    (from_state, to.state.lock())
+                } else {
+                    let to_state = to.state.lock();
+                    (from.state.lock(), to_state)
+                };
+            let remaining = from_state.balance - amount;
+            if SurvivalStatus::from_balance(remaining, from_state.initial_balance)
+                == SurvivalStatus::Bankrupt
+            {
+                return Err(EconomicError::InsufficientFunds {
+                    needed: amount,
+                    available: from_state.balance,
+                });
+            }
+
+            let record = TransferRecord {
+                timestamp: Utc::now(),
+                transfer_id: transfer_id.clone(),
+                direction: TransferDirection::Sent,
+                counterparty: to.signature.clone(),
+                amount,
+                reason: reason.to_string(),
+                balance_after: 0.0,
+            };
+            let received = TransferRecord {
+                direction: TransferDirection::Received,
+                counterparty: from.signature.clone(),
+                ..record.clone()
+            };
+            let sent = from.apply_transfer(&mut from_state, record);
+            let received = to.apply_transfer(&mut to_state, received);
+            (sent, received)
+        };
+
+        let failures: Vec<(String, EconomicError)> = [(from, sent), (to, received)]
+            .into_iter()
+            .filter_map(|(tracker, pending)| {
+                let finished = tracker.finish_transfer(pending);
+                finished.err().map(|err| (tracker.signature.clone(), err))
+            })
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(EconomicError::TransferNotLogged {
+                transfer_id,
+                failures,
+            })
+        }
+    }
+
+    /// Look up the cost of a charge in active tasks, then in the cost log
+    /// and its archives.
+    fn find_charge_cost(&self, state: &TrackerState, record_id: &str) -> Result<Option<f64>> {
+        for task in state.tasks.values() {
+            let llm = task.llm_calls.iter().find(|c| c.id == record_id).map(|c| c.cost);
+            let api = task.api_calls.iter().find(|c| c.id == record_id).map(|c| c.cost);
+            if let Some(cost) = llm.or(api) {
+                return Ok(Some(cost));
+            }
+        }
+
+        let mut found = None;
+        self.for_each_logged::<TaskCostRecord, _>(
+            "token_costs.jsonl",
+            Some(&DateRange::all()),
+            |record| {
+                if found.is_some() {
+                    return;
+                }
+                let llm = record.llm_usage.calls_detail.iter().find(|c| c.id == record_id);
+                let api = record.api_usage.calls_detail.iter().find(|c| c.id == record_id);
+                found = llm.map(|c| c.cost).or(api.map(|c| c.cost));
+            },
+        )?;
+
+        Ok(found)
+    }
+
+    /// Net cost corrections by task ID, from the correction log.
+    fn load_cost_corrections(&self) -> Result<HashMap<String, f64>> {
+        let mut corrections: HashMap<String, f64> = HashMap::new();
+        self.for_each_record::<CostCorrectionRecord, _>(
+            &self.cost_corrections_file_path(),
+            |record| *corrections.entry(record.task_id).or_default() += record.delta,
+        )?;
+        Ok(corrections)
+    }
+
+    /// Refunds credited so far by original charge ID, from the refund log
+    /// and its archives.
+    fn load_refunded(&self) -> Result<HashMap<String, f64>> {
+        let mut refunded: HashMap<String, f64> = HashMap::new();
+        self.for_each_logged::<RefundRecord, _>(
+            "refunds.jsonl",
+            Some(&DateRange::all()),
+            |record| {
+                let amount = record.amount();
+                *refunded.entry(record.original_record_id).or_default() += amount;
+            },
+        )?;
+        Ok(refunded)
+    }
+
+    /// Add profit/loss from trading.
+    pub fn add_trading_profit(&self, profit: f64, _description: impl Into<String>) {
+        let mut state = self.state.lock();
+        let previous_status = self.get_survival_status_inner(&state);
+        state.balance += profit;
+        state.total_trading_profit += profit;
+        state.dirty = true;
+        state.unlogged_changes.push(BalanceChange {
+            timestamp: Utc::now(),
+            delta: profit,
+        });
+
+        let sign = if profit >= 0.0 { "+" } else { "" };
+        tracing::info!(
+            "📈 Trading P&L: {}${:.2}, new balance: ${:.2}",
+            sign,
+            profit,
+            state.balance
+        );
+        self.log_state_change(&state, "trading profit added", -profit);
+        self.log_status_change(&state, previous_status, "trading profit added");
+        let bankruptcy = self.update_bankruptcy_flag(&mut state);
+        let intake_change = self.update_intake(&mut state);
+        drop(state);
+
+        self.notify_status_changes();
+        self.notify_bankruptcy(bankruptcy);
+        if let Some(event) = intake_change {
+            self.notify_intake_change(event);
+        }
+    }
+
+    /// Save end-of-day economic state.
+    pub fn save_daily_state(
+        &self,
+        date: &str,
+        work_income: f64,
+        trading_profit: f64,
+        completed_tasks: Vec<String>,
+        api_error: bool,
+    ) -> Result<()> {
+        let daily_cost = {
+            let state = self.state.lock();
+            state.daily.cost
+        };
+
+        self.save_balance_record(
+            date,
+            daily_cost,
+            work_income,
+            trading_profit,
+            completed_tasks,
+            api_error,
+            false,
+        )?;
+
+        // Reset daily tracking
+        {
+            let mut state = self.state.lock();
+            state.daily.reset();
+            state.session.reset();
+        }
+
+        tracing::info!("💾 Saved daily state for {}", date);
+
+        Ok(())
+    }
+
+    /// Persist in-memory balance changes and buffered records that are not
+    /// yet on disk.
+    ///
+    /// The balance itself is otherwise only snapshotted by
+    /// `save_daily_state`. When costs, income, or trading results have moved
+    /// the balance since the last snapshot, this appends a checkpoint
+    /// `BalanceRecord` (zero deltas) so a fresh tracker restores the same
+    /// state on `initialize`. With batched writes (`[economic.persistence]`),
+    /// it then writes out every buffered record.
+    ///
+    /// Cost and income records are always logged after their checkpoint, so
+    /// a record on disk never lacks its balance effect. `Drop` calls this on a
+    /// best-effort basis.
+    pub fn flush(&self) -> Result<()> {
+        self.checkpoint()?;
+        self.write_buffer.lock().drain()
+    }
+
+    /// Log a checkpoint `BalanceRecord` if the balance moved since the last
+    /// snapshot.
+    fn checkpoint(&self) -> Result<()> {
+        if !self.state.lock().dirty {
+            return Ok(());
+        }
+
+        let date = Utc::now().format("%Y-%m-%d").to_string();
+        self.save_balance_record(&date, 0.0, 0.0, 0.0, Vec::new(), false, true)
+    }
+
+    /// Get current balance.
+    pub fn get_balance(&self) -> f64 {
+        self.state.lock().balance
+    }
+
+    /// Get net worth (balance + portfolio value).
+    pub fn get_net_worth(&self) -> f64 {
+        // TODO: Add trading portfolio value
+        self.get_balance()
+    }
+
+    /// Get current survival status.
+    pub fn get_survival_status(&self) -> SurvivalStatus {
+        let state = self.state.lock();
+        self.get_survival_status_inner(&state)
+    }
+
+    /// Stream of survival status changes, starting with the next one.
+    ///
+    /// Emits only when a cost or credit moves the agent into a different
+    /// status, not on every balance change. A subscriber that falls more than
+    /// `STATUS_CHANGE_CAPACITY` (64) changes behind skips the oldest ones.
+    pub fn watch_survival_status(
+        &self,
+    ) -> impl Stream<Item = SurvivalStatusChange> + Send + 'static {
+        BroadcastStream::new(self.status_changes.subscribe()).filter_map(Result::ok)
+    }
+
+    /// Receive every charge, credit and status change from now on.
+    ///
+    /// A receiver that falls more than `event_buffer_size` events behind
+    /// gets `RecvError::Lagged` and skips the oldest ones.
+    pub fn subscribe_cost_events(&self) -> broadcast::Receiver<CostEvent> {
+        self.cost_events.subscribe()
+    }
+
+    /// Run `callback` with the previous and new status whenever a cost or
+    /// credit changes the survival status.
+    ///
+    /// Callbacks run in registration order on the thread that changed the
+    /// balance, once the tracker's state lock is released, so they may call
+    /// back into the tracker.
+    pub fn on_status_change(&self, callback: StatusChangeCallback) {
+        self.status_callbacks.write().push(Arc::from(callback));
+    }
+
+    /// Remove every callback registered with
+    /// [`on_status_change`](Self::on_status_change).
+    pub fn clear_status_callbacks(&self) {
+        self.status_callbacks.write().clear();
+    }
+
+    /// Run the status callbacks for the changes queued by
+    /// `log_status_change`. Must be called without the state lock held.
+    fn notify_status_changes(&self) {
+        let changes = std::mem::take(&mut *self.pending_status_changes.lock());
+        if changes.is_empty() {
+            return;
+        }
+        let callbacks = self.status_callbacks.read().clone();
+        for (previous, current) in changes {
+            for callback in &callbacks {
+                callback(previous, current);
+            }
+        }
+    }
+
+    fn get_survival_status_inner(&self, state: &TrackerState) -> SurvivalStatus {
+        SurvivalStatus::from_balance(state.balance, state.initial_balance)
+    }
+
+    /// Apply one side of a transfer to `state`, filling in the record's
+    /// balance.
+    fn apply_transfer(
+        &self,
+        state: &mut TrackerState,
+        mut record: TransferRecord,
+    ) -> PendingTransfer {
+        let previous_status = self.get_survival_status_inner(state);
+        let action = match record.direction {
+            TransferDirection::Sent => "transfer sent",
+            TransferDirection::Received => "transfer received",
+        };
+        state.balance += record.delta();
+        state.dirty = true;
+        self.log_state_change(state, action, -record.delta());
+        self.log_status_change(state, previous_status, action);
+        let bankruptcy = self.update_bankruptcy_flag(state);
+        let intake_change = self.update_intake(state);
+        record.balance_after = state.balance;
+        PendingTransfer {
+            record,
+            bankruptcy,
+            intake_change,
+        }
+    }
+
+    /// Persist one side of a transfer applied by `apply_transfer`.
+    fn finish_transfer(&self, pending: PendingTransfer) -> Result<()> {
+        // Balance first, so the transfer record is never on disk without it
+        self.checkpoint()?;
+        self.append_record(&self.transfers_file_path(), &pending.record)?;
+        self.notify_status_changes();
+        self.notify_bankruptcy(pending.bankruptcy);
+        if let Some(event) = pending.intake_change {
+            self.notify_intake_change(event);
+        }
+        Ok(())
+    }
+
+    /// Sync the bankruptcy flag with the current balance.
+    ///
+    /// Returns `true` only on the transition into `Bankrupt`; recovering
+    /// above zero re-arms the flag so a later bankruptcy is reported again.
+    /// Update bankruptcy tracking after a balance change.
+    ///
+    /// With a grace policy, reaching zero starts a grace period and the
+    /// agent only crosses into bankruptcy once the grace budget is used up.
+    /// Rising above zero ends any grace period and re-arms the crossing.
+    fn update_bankruptcy_flag(&self, state: &mut TrackerState) -> BankruptcyChange {
+        let mut change = BankruptcyChange::default();
+        let now = Utc::now();
+        if self.get_survival_status_inner(state) != SurvivalStatus::Bankrupt {
+            state.bankruptcy_notified = false;
+            if state.grace_since.take().is_some() {
+                change.grace = Some(Self::grace_event(state, GraceEventKind::Recovered, now));
+                state.grace_spent = 0.0;
+            }
+            return change;
+        }
+        if state.bankruptcy_notified {
+            return change;
+        }
+
+        let policy = self.config.bankruptcy_grace;
+        if policy.is_enabled() {
+            let Some(since) = state.grace_since else {
+                state.grace_since = Some(now);
+                state.grace_spent = 0.0;
+                change.grace = Some(Self::grace_event(state, GraceEventKind::Entered, now));
+                return change;
+            };
+            if !policy.is_exhausted(since, state.grace_spent, now) {
+                return change;
+            }
+            change.grace = Some(Self::grace_event(state, GraceEventKind::Exhausted, now));
+            state.grace_since = None;
+            state.grace_spent = 0.0;
+        }
+        state.bankruptcy_notified = true;
+        change.crossed = true;
+        change
+    }
+
+    fn grace_event(
+        state: &TrackerState,
+        kind: GraceEventKind,
+        timestamp: DateTime<Utc>,
+    ) -> GraceEvent {
+        GraceEvent {
+            timestamp,
+            kind,
+            balance: state.balance,
+            spent: state.grace_spent,
+        }
+    }
+
+    /// Count a cost against the grace budget, warning that the agent is
+    /// operating on borrowed time. Returns whether it was in grace.
+    fn track_grace_spend(&self, state: &mut TrackerState, cost: f64) -> bool {
+        if state.grace_since.is_none() {
+            return false;
+        }
+        state.grace_spent += cost;
+        tracing::warn!(
+            agent_id = %self.signature,
+            cost_usd = cost,
+            grace_spent = state.grace_spent,
+            balance = state.balance,
+            "economic: cost recorded during bankruptcy grace period"
+        );
+        true
+    }
+
+    fn operational_state_inner(&self, state: &TrackerState) -> OperationalState {
+        let policy = self.config.bankruptcy_grace;
+        if let Some(since) = state.grace_since {
+            return OperationalState::Grace {
+                since,
+                expires_at: policy.expires_at(since),
+                spend_remaining: policy.spend_remaining(state.grace_spent),
+            };
+        }
+        match self.get_survival_status_inner(state) {
+            SurvivalStatus::Bankrupt => OperationalState::Bankrupt,
+            status => OperationalState::Operating { status },
+        }
+    }
+
+    /// Apply the intake policy to the current status.
+    ///
+    /// Returns the event when intake was paused or resumed; pass it to
+    /// [`notify_intake_change`](Self::notify_intake_change) once the lock is
+    /// released.
+    fn update_intake(&self, state: &mut TrackerState) -> Option<IntakeEvent> {
+        let status = self.get_survival_status_inner(state);
+        let policy = self.config.intake_policy;
+        let paused = match state.intake_paused_since {
+            None if policy.should_pause(status) => true,
+            Some(_) if policy.should_resume(status) => false,
+            _ => return None,
+        };
+        let timestamp = Utc::now();
+        state.intake_paused_since = paused.then_some(timestamp);
+        Some(IntakeEvent {
+            timestamp,
+            paused,
+            status,
+            balance: state.balance,
+        })
+    }
+
+    /// Persist an intake change and invoke the intake callback.
+    ///
+    /// Must be called without the state lock held so the callback can query
+    /// the tracker.
+    fn notify_intake_change(&self, event: IntakeEvent) {
+        if event.paused {
+            tracing::warn!(
+                agent_id = %self.signature,
+                balance = event.balance,
+                status = %event.status,
+                "economic: intake paused"
+            );
+        } else {
+            tracing::info!(
+                agent_id = %self.signature,
+                balance = event.balance,
+                status = %event.status,
+                "economic: intake resumed"
+            );
+        }
+        if let Err(e) = self.append_record(&self.intake_file_path(), &event) {
+            tracing::warn!("Failed to persist intake event: {e:#}");
+        }
+        if let Some(callback) = &self.config.on_intake_change {
+            callback.call(&self.signature, &event);
+        }
+    }
+
+    /// Invoke the configured bankruptcy callback.
+    ///
+    /// Must be called without the state lock held so the callback can query
+    /// the tracker.
+    fn notify_bankruptcy(&self, change: BankruptcyChange) {
+        if let Some(event) = change.grace {
+            match event.kind {
+                GraceEventKind::Entered => tracing::warn!(
+                    agent_id = %self.signature,
+                    balance = event.balance,
+                    "⏳ Balance exhausted, bankruptcy grace period started"
+                ),
+                GraceEventKind::Exhausted => tracing::warn!(
+                    agent_id = %self.signature,
+                    spent = event.spent,
+                    "economic: bankruptcy grace period exhausted"
+                ),
+                GraceEventKind::Recovered => tracing::info!(
+                    agent_id = %self.signature,
+                    balance = event.balance,
+                    "economic: recovered during bankruptcy grace period"
+                ),
+            }
+            if let Err(e) = self.append_record(&self.grace_file_path(), &event) {
+                tracing::warn!("Failed to persist grace event: {e:#}");
+            }
+        }
+        if change.crossed {
+            tracing::warn!(agent_id = %self.signature, "💀 Agent is bankrupt");
+            if let Some(callback) = &self.config.on_bankruptcy {
+                callback.call(&self.signature);
+            }
+        }
+    }
+
+    /// Emit a structured `info` event for a state change.
+    ///
+    /// Every event carries the same field set (`agent_id`, `task_id`,
+    /// `cost_usd`, `balance`, `status`) and is nested under the active task
+    /// span, if any. Credits are reported as negative `cost_usd`.
+    fn log_state_change(&self, state: &TrackerState, action: &str, cost_usd: f64) {
+        let status = self.get_survival_status_inner(state);
+        let task = state.current_task();
+        let emit = || {
+            tracing::info!(
+                agent_id = %self.signature,
+                task_id = task.map_or("", |t| t.task_id.as_str()),
+                cost_usd,
+                balance = state.balance,
+                status = %status,
+                "economic: {action}"
+            );
+        };
+        match task {
+            Some(task) => task.span.in_scope(emit),
+            None => emit(),
+        }
+    }
+
+    /// Emit a `status changed` event when the survival status moved, publish
+    /// it to `watch_survival_status` subscribers, and queue it for the
+    /// `on_status_change` callbacks.
+    fn log_status_change(&self, state: &TrackerState, previous: SurvivalStatus, action: &str) {
+        let current = self.get_survival_status_inner(state);
+        if current == previous {
+            return;
+        }
+        self.pending_status_changes.lock().push((previous, current));
+        // No subscribers is not an error
+        let _ = self.status_changes.send(SurvivalStatusChange {
+            previous,
+            current,
+            timestamp: SystemTime::now(),
+            triggering_event: action.to_string(),
+        });
+        let _ = self
+            .cost_events
+            .send(CostEvent::StatusChanged { previous, current });
+        let task = state.current_task();
+        let emit = || {
+            tracing::info!(
+                agent_id = %self.signature,
+                task_id = task.map_or("", |t| t.task_id.as_str()),
+                balance = state.balance,
+                previous_status = %previous,
+                status = %current,
+                "economic: status changed"
+            );
+        };
+        match task {
+            Some(task) => task.span.in_scope(emit),
+            None => emit(),
+        }
+    }
+
+    /// Check if agent is bankrupt: out of money and out of any grace period.
+    pub fn is_bankrupt(&self) -> bool {
+        !self.operational_state().is_operational()
+    }
+
+    /// Whether the agent may keep operating, distinguishing a bankruptcy
+    /// grace period (see `EconomicConfig::bankruptcy_grace`) from both
+    /// normal operation and bankruptcy.
+    ///
+    /// A grace period that ran out by time since the last balance change
+    /// ends here.
+    pub fn operational_state(&self) -> OperationalState {
+        let (bankruptcy, operational) = {
+            let mut state = self.state.lock();
+            let bankruptcy = if state.grace_since.is_some() {
+                self.update_bankruptcy_flag(&mut state)
+            } else {
+                BankruptcyChange::default()
+            };
+            (bankruptcy, self.operational_state_inner(&state))
+        };
+        self.notify_bankruptcy(bankruptcy);
+        operational
+    }
+    /// Get session cost so far.
+    pub fn get_session_cost(&self) -> f64 {
+        self.state.lock().session.cost
+    }
+
+    /// Get daily cost so far.
+    pub fn get_daily_cost(&self) -> f64 {
+        self.state.lock().daily.cost
+    }
+
+    /// Whether a request estimated at `estimate` can be sent now.
+    ///
+    /// Checks the balance (less `reserve_floor`, when set), what is left of
+    /// `daily_spend_limit` today, and what is left of `max_cost_per_task`
+    /// for the current task. When the answer is no, the decision names the
+    /// tightest limit and how much could still be spent, so the caller can
+    /// switch to a cheaper model or trim its context and ask again.
+    pub fn can_afford(&self, estimate: &CostEstimate) -> AffordabilityDecision {
+        let state = self.state.lock();
+        let mut limits = vec![match self.config.reserve_floor {
+            Some(floor) => (SpendingLimit::ReserveFloor, state.balance - floor),
+            None => (SpendingLimit::Balance, state.balance),
+        }];
+        if let Some(limit) = self.config.daily_spend_limit {
+            limits.push((SpendingLimit::DailyLimit, limit - state.daily.cost));
+        }
+        if let (Some(ceiling), Some(task)) = (self.config.max_cost_per_task, state.current_task()) {
+            limits.push((SpendingLimit::TaskBudget, ceiling - task.costs.total()));
+        }
+        drop(state);
+
+        let (limit, headroom_usd) = limits
+            .into_iter()
+            .min_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(limit, headroom)| (limit, headroom.max(0.0)))
+            .unwrap_or((SpendingLimit::Balance, 0.0));
+        if estimate.cost_usd <= headroom_usd {
+            AffordabilityDecision::Affordable { headroom_usd }
+        } else {
+            AffordabilityDecision::Unaffordable {
+                limit,
+                headroom_usd,
+            }
+        }
+    }
+
+    /// Get an economic summary of in-memory state, with alerts.
+    ///
+    /// Sections that read the logs are left out; request them with
+    /// [`get_summary_with`](Self::get_summary_with).
+    pub fn get_summary(&self) -> EconomicSummary {
+        self.build_summary(SummaryOptions::default(), None, None, None)
+    }
+
+    /// Get an economic summary with the sections enabled in `options`.
+    ///
+    /// The forecast section projects the next survival status transition
+    /// with `EconomicAnalytics::forecast_spend` within the next
+    /// `SUMMARY_FORECAST_DAYS` days.
+    ///
+    /// # Errors
+    /// Returns an error if a log read by an enabled section cannot be read.
+    pub fn get_summary_with(&self, options: SummaryOptions) -> Result<EconomicSummary> {
+        let forecast = if options.contains(SummarySection::Forecast) {
+            Some(
+                self.get_analytics(None)?
+                    .forecast_spend(SUMMARY_FORECAST_DAYS),
+            )
+        } else {
+            None
+        };
+        let cost_drivers = options
+            .contains(SummarySection::CostDrivers)
+            .then(|| self.cost_drivers())
+            .transpose()?;
+        let income_this_week = options
+            .contains(SummarySection::Income)
+            .then(|| self.income_since(Utc::now() - chrono::Duration::days(7)))
+            .transpose()?;
+        Ok(self.build_summary(options, forecast, cost_drivers, income_this_week))
+    }
+
+    /// Summary of in-memory state, with the sections built from the logs.
+    fn build_summary(
+        &self,
+        options: SummaryOptions,
+        forecast: Option<SpendForecast>,
+        cost_drivers: Option<CostDrivers>,
+        income_this_week: Option<f64>,
+    ) -> EconomicSummary {
+        let state = self.state.lock();
+        let burn_rate = forecast.as_ref().map(|forecast| BurnRate {
+            daily_net_spend: forecast.daily_net_spend,
+            runway_days: (forecast.daily_net_spend > 0.0)
+                .then(|| state.balance.max(0.0) / forecast.daily_net_spend),
+        });
+        let mut summary = EconomicSummary {
+            signature: self.signature.clone(),
+            balance: state.balance,
+            initial_balance: state.initial_balance,
+            net_worth: state.balance, // TODO: Add portfolio
+            total_token_cost: state.total_token_cost,
+            total_work_income: state.total_work_income,
+            total_trading_profit: state.total_trading_profit,
+            total_refunds: state.total_refunds,
+            total_grant_income: state.total_grant_income,
+            total_interest_earned: state.total_interest_earned,
+            total_interest_paid: state.total_interest_paid,
+            session_cost: state.session.cost,
+            daily_cost: state.daily.cost,
+            daily_spend_limit: self.config.daily_spend_limit,
+            outstanding_debt: (-state.balance).max(0.0),
+            pending_escrow: state.escrow.values().sum(),
+            tasks_ended: state.tasks_ended,
+            session_input_tokens: state.session.input_tokens,
+            session_output_tokens: state.session.output_tokens,
+            survival_status: self.get_survival_status_inner(&state),
+            is_bankrupt: self.get_survival_status_inner(&state) == SurvivalStatus::Bankrupt,
+            min_evaluation_threshold: self.config.min_evaluation_threshold,
+            intake_paused: state.intake_paused_since.is_some(),
+            in_grace_period: state.grace_since.is_some(),
+            next_status_transition: forecast
+                .as_ref()
+                .and_then(|forecast| forecast.next_transition().cloned()),
+            burn_rate,
+            cost_drivers,
+            income_this_week,
+            alerts: Vec::new(),
+        };
+        drop(state);
+
+        if options.contains(SummarySection::Alerts) {
+            summary.alerts = summary.collect_alerts();
+        }
+        summary
+    }
+
+    /// Costs by model and by task, including still-active tasks.
+    fn cost_drivers(&self) -> Result<CostDrivers> {
+        let mut by_model: HashMap<String, f64> = HashMap::new();
+        let mut by_provider: HashMap<String, f64> = HashMap::new();
+        let mut by_task: HashMap<String, f64> = HashMap::new();
+        self.for_each_task_cost(|task_id, total, calls| {
+            *by_task.entry(task_id.to_string()).or_default() += total;
+            for call in calls {
+                let model = call.model.as_deref().unwrap_or("unknown");
+                *by_model.entry(model.to_string()).or_default() += call.cost;
+                let provider = call.provider.as_deref().unwrap_or("unknown");
+                *by_provider.entry(provider.to_string()).or_default() += call.cost;
+            }
+        })?;
+
+        Ok(CostDrivers::rank(by_model, by_provider, by_task))
+    }
+
+    /// Visit the total cost and LLM calls of every logged and active task.
+    fn for_each_task_cost(
+        &self,
+        mut visit: impl FnMut(&str, f64, &[LlmCallRecord]),
+    ) -> Result<()> {
+        self.for_each_record::<TaskCostRecord, _>(&self.token_costs_file_path(), |record| {
+            visit(
+                &record.task_id,
+                record.cost_summary.total(),
+                &record.llm_usage.calls_detail,
+            );
+        })?;
+        let state = self.state.lock();
+        for task in state.tasks.values() {
+            visit(&task.task_id, task.costs.total(), &task.llm_calls);
+        }
+        Ok(())
+    }
+
+    /// Visit the records of the log `name`: all of the active file, or with
+    /// `range`, those dated within it, including archived months the range
+    /// reaches into.
+    fn for_each_logged<T, F>(
+        &self,
+        name: &str,
+        range: Option<&DateRange>,
+        mut on_record: F,
+    ) -> Result<()>
+    where
+        T: DeserializeOwned + LoggedRecord,
+        F: FnMut(T),
+    {
+        let path = self.data_path.join(name);
+        let Some(range) = range else {
+            return self.for_each_record(&path, on_record);
+        };
+        let mut in_range = |record: T| {
+            if range.contains(&record.logged_at()) {
+                on_record(record);
+            }
+        };
+        let buffer = self.write_buffer.lock();
+        let files = logs::log_files(&self.data_path, name, range);
+        logs::for_each_merged::<T, _>(&files, &mut in_range)?;
+        buffer.for_each_pending(&path, in_range);
+        Ok(())
+    }
+
+    /// Rank LLM models by total spend, most expensive first.
+    ///
+    /// Covers the calls of every logged and active task; calls tracked
+    /// outside a task are not itemized and so are not included.
+    pub fn get_model_cost_ranking(&self) -> Result<Vec<ModelCostEntry>> {
+        let mut by_model: HashMap<String, ModelCostEntry> = HashMap::new();
+        self.for_each_task_cost(|_, _, calls| {
+            for call in calls {
+                let model = call.model.as_deref().unwrap_or("unknown");
+                let entry = by_model
+                    .entry(model.to_string())
+                    .or_insert_with(|| ModelCostEntry {
+                        model_name: model.to_string(),
+                        ..Default::default()
+                    });
+                entry.total_cost_usd += call.cost;
+                entry.total_input_tokens += call.input_tokens;
+                entry.total_output_tokens += call.output_tokens;
+                entry.call_count += 1;
+            }
+        })?;
+
+        let mut ranking: Vec<ModelCostEntry> = by_model.into_values().collect();
+        for entry in &mut ranking {
+            entry.average_cost_per_call = entry.total_cost_usd / entry.call_count as f64;
+        }
+        ranking.sort_by(|a, b| {
+            b.total_cost_usd
+                .total_cmp(&a.total_cost_usd)
+                .then_with(|| a.model_name.cmp(&b.model_name))
+        });
+        Ok(ranking)
+    }
+
+    /// LLM spend per output token across every logged and active task.
+    ///
+    /// Every call's cost counts, so calls that produced no output (failed
+    /// attempts, retries) raise the figure. Returns `f64::NAN` when no output
+    /// tokens were recorded.
+    pub fn get_cost_per_successful_output_token(&self) -> Result<f64> {
+        let (cost, output_tokens) = self.llm_cost_and_output_tokens()?;
+        Ok(if output_tokens == 0 {
+            f64::NAN
+        } else {
+            cost / output_tokens as f64
+        })
+    }
+
+    /// Output tokens produced per dollar of LLM spend, the inverse of
+    /// [`get_cost_per_successful_output_token`](Self::get_cost_per_successful_output_token).
+    /// Returns `f64::NAN` when nothing was spent.
+    pub fn total_output_tokens_per_dollar(&self) -> Result<f64> {
+        let (cost, output_tokens) = self.llm_cost_and_output_tokens()?;
+        Ok(if cost > 0.0 {
+            output_tokens as f64 / cost
+        } else {
+            f64::NAN
+        })
+    }
+
+    /// Output tokens per dollar for each model, from the same calls as
+    /// [`get_model_cost_ranking`](Self::get_model_cost_ranking). Models
+    /// without spend are left out.
+    pub fn get_output_efficiency_by_model(&self) -> Result<HashMap<String, f64>> {
+        Ok(self
+            .get_model_cost_ranking()?
+            .into_iter()
+            .filter(|entry| entry.total_cost_usd > 0.0)
+            .map(|entry| {
+                let per_dollar = entry.total_output_tokens as f64 / entry.total_cost_usd;
+                (entry.model_name, per_dollar)
+            })
+            .collect())
+    }
+
+    /// Total cost and output tokens of the LLM calls of every logged and
+    /// active task.
+    fn llm_cost_and_output_tokens(&self) -> Result<(f64, u64)> {
+        let mut cost = 0.0;
+        let mut output_tokens = 0;
+        self.for_each_task_cost(|_, _, calls| {
+            for call in calls {
+                cost += call.cost;
+                output_tokens += call.output_tokens;
+            }
+        })?;
+        Ok((cost, output_tokens))
+    }
+
+    /// Replay the recorded LLM calls of every logged and active task at new
+    /// prices, without changing any record.
+    ///
+    /// `price_changes` maps model names to new input prices per million
+    /// tokens. Each call to a repriced model is scaled by the ratio of its
+    /// new input price to the configured one, on the assumption that output
+    /// prices move in proportion. Calls to other models, API calls, and
+    /// other task costs keep their recorded cost.
+    ///
+    /// # Errors
+    /// [`EconomicError::InvalidAmount`] for a negative or non-finite price;
+    /// [`EconomicError::InvalidConfig`] when the configured input price is
+    /// zero, so there is nothing to scale from; IO errors.
+    pub fn cost_sensitivity_analysis(
+        &self,
+        price_changes: HashMap<String, f64>,
+    ) -> Result<SensitivityReport> {
+        if let Some(&amount) = price_changes
+            .values()
+            .find(|price| !price.is_finite() || **price < 0.0)
+        {
+            return Err(EconomicError::InvalidAmount { amount });
+        }
+        let base_price = self.token_pricing().input_price_per_million;
+        if !price_changes.is_empty() && base_price <= 0.0 {
+            return Err(EconomicError::InvalidConfig(
+                "input_price_per_million must be positive to reprice calls".to_string(),
+            ));
+        }
+
+        let mut impact: HashMap<&str, f64> =
+            price_changes.keys().map(|model| (model.as_str(), 0.0)).collect();
+        let mut current_total_cost = 0.0;
+        self.for_each_task_cost(|_, total, calls| {
+            current_total_cost += total;
+            for call in calls {
+                let Some(model) = call.model.as_deref() else {
+                    continue;
+                };
+                if let Some((model, new_price)) = price_changes.get_key_value(model) {
+                    *impact.entry(model.as_str()).or_default() +=
+                        call.cost * (new_price / base_price - 1.0);
+                }
+            }
+        })?;
+
+        let delta_usd: f64 = impact.values().sum();
+        let mut per_model_impact: Vec<(String, f64)> = impact
+            .into_iter()
+            .map(|(model, delta)| (model.to_string(), delta))
+            .collect();
+        per_model_impact.sort_by(|a, b| {
+            b.1.abs()
+                .total_cmp(&a.1.abs())
+                .then_with(|| a.0.cmp(&b.0))
+        });
+        Ok(SensitivityReport {
+            current_total_cost,
+            revised_total_cost: current_total_cost + delta_usd,
+            delta_usd,
+            delta_pct: if current_total_cost > 0.0 {
+                delta_usd / current_total_cost * 100.0
+            } else {
+                0.0
+            },
+            per_model_impact,
+        })
+    }
+
+    /// Replay the recorded LLM calls of every logged and active task at the
+    /// `alternative` token prices, without changing any record.
+    ///
+    /// The current cost of each call is its recorded cost, so cache
+    /// discounts and per-model prices stay as they were billed; the
+    /// alternative prices every input and output token at the flat
+    /// `alternative` rates. Calls tracked outside a task are not itemized
+    /// and so are not replayed.
+    ///
+    /// # Errors
+    /// [`EconomicError::InvalidAmount`] for a negative or non-finite price;
+    /// IO errors.
+    pub fn simulate_pricing_model(
+        &self,
+        alternative: &TokenPricing,
+    ) -> Result<PricingSimulationResult> {
+        for amount in [
+            alternative.input_price_per_million,
+            alternative.output_price_per_million,
+        ] {
+            if !amount.is_finite() || amount < 0.0 {
+                return Err(EconomicError::InvalidAmount { amount });
+            }
+        }
+
+        let mut result = PricingSimulationResult::default();
+        let mut index: HashMap<String, usize> = HashMap::new();
+        let mut tokens = 0_u64;
+        let mut breakeven = None;
+        self.for_each_task_cost(|task_id, _, calls| {
+            if calls.is_empty() {
+                return;
+            }
+            let i = *index.entry(task_id.to_string()).or_insert_with(|| {
+                result.per_task_comparison.push((task_id.to_string(), 0.0, 0.0));
+                result.per_task_comparison.len() - 1
+            });
+            for call in calls {
+                let alternative_cost =
+                    alternative.calculate_cost(call.input_tokens, call.output_tokens);
+                result.per_task_comparison[i].1 += call.cost;
+                result.per_task_comparison[i].2 += alternative_cost;
+                result.current_total_cost += call.cost;
+                result.alternative_total_cost += alternative_cost;
+                tokens = tokens.saturating_add(call.input_tokens + call.output_tokens);
+                if result.alternative_total_cost > result.current_total_cost {
+                    breakeven = None;
+                } else if breakeven.is_none() {
+                    breakeven = Some(tokens);
+                }
+            }
+        })?;
+
+        result.savings_usd = result.current_total_cost - result.alternative_total_cost;
+        result.savings_pct = if result.current_total_cost > 0.0 {
+            result.savings_usd / result.current_total_cost * 100.0
+        } else {
+            0.0
+        };
+        result.breakeven_at_tokens = breakeven;
+        Ok(result)
+    }
+
+    /// Tasks started with an estimated budget that cost more than it, largest
+    /// overage first.
+    ///
+    /// Covers every logged and active task; a task logged more than once
+    /// counts once with its combined cost and its latest budget.
+    pub fn get_tasks_over_budget(&self) -> Result<Vec<OverBudgetTask>> {
+        let mut by_task: HashMap<String, (f64, Option<f64>)> = HashMap::new();
+        let mut add = |task_id: &str, cost: f64, budget: Option<f64>| {
+            let entry = by_task.entry(task_id.to_string()).or_insert((0.0, None));
+            entry.0 += cost;
+            entry.1 = budget.or(entry.1);
+        };
+        self.for_each_record::<TaskCostRecord, _>(&self.token_costs_file_path(), |record| {
+            add(&record.task_id, record.cost_summary.total(), record.estimated_budget);
+        })?;
+        for task in self.state.lock().tasks.values() {
+            add(&task.task_id, task.costs.total(), task.estimated_budget);
+        }
+
+        let mut over: Vec<OverBudgetTask> = by_task
+            .into_iter()
+            .filter_map(|(task_id, (actual_cost, budget))| {
+                let estimated_cost = budget?;
+                let overage = actual_cost - estimated_cost;
+                (overage > 0.0).then(|| OverBudgetTask {
+                    task_id,
+                    estimated_cost,
+                    actual_cost,
+                    overage,
+                    overage_pct: overage / estimated_cost * 100.0,
+                })
+            })
+            .collect();
+        over.sort_by(|a, b| {
+            b.overage
+                .total_cmp(&a.overage)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        Ok(over)
+    }
+
+    /// Tasks whose total cost is more than `sensitivity` standard deviations
+    /// above the mean task cost, highest Z-score first.
+    ///
+    /// Covers every logged and active task; a task logged more than once
+    /// counts once with its combined cost. With fewer than two tasks, or
+    /// when every task cost the same, nothing is flagged.
+    ///
+    /// # Errors
+    /// Fails when `sensitivity` is not a positive finite number, or on IO
+    /// errors.
+    pub fn detect_cost_anomalies(&self, sensitivity: f64) -> Result<Vec<CostAnomaly>> {
+        if !sensitivity.is_finite() || sensitivity <= 0.0 {
+            return Err(EconomicError::InvalidSensitivity { sensitivity });
+        }
+
+        let mut by_task: HashMap<String, (f64, DateTime<Utc>)> = HashMap::new();
+        let mut add = |task_id: &str, cost: f64, end: DateTime<Utc>| {
+            let entry = by_task.entry(task_id.to_string()).or_insert((0.0, end));
+            entry.0 += cost;
+            entry.1 = entry.1.max(end);
+        };
+        self.for_each_record::<TaskCostRecord, _>(&self.token_costs_file_path(), |record| {
+            add(&record.task_id, record.cost_summary.total(), record.timestamp_end);
+        })?;
+        let now = Utc::now();
+        for task in self.state.lock().tasks.values() {
+            add(&task.task_id, task.costs.total(), now);
+        }
+
+        if by_task.len() < 2 {
+            return Ok(Vec::new());
+        }
+        let count = by_task.len() as f64;
+        let mean = by_task.values().map(|(cost, _)| cost).sum::<f64>() / count;
+        let variance = by_task
+            .values()
+            .map(|(cost, _)| (cost - mean).powi(2))
+            .sum::<f64>()
+            / count;
+        let std_dev = variance.sqrt();
+        if std_dev == 0.0 {
+            return Ok(Vec::new());
+        }
+
+        let mut anomalies: Vec<CostAnomaly> = by_task
+            .into_iter()
+            .filter_map(|(task_id, (cost_usd, end))| {
+                let z_score = (cost_usd - mean) / std_dev;
+                (z_score > sensitivity).then(|| CostAnomaly {
+                    timestamp: end.into(),
+                    task_id,
+                    cost_usd,
+                    z_score,
+                    severity: AnomalySeverity::from_z_score(z_score, sensitivity),
+                })
+            })
+            .collect();
+        anomalies.sort_by(|a, b| {
+            b.z_score
+                .total_cmp(&a.z_score)
+                .then_with(|| a.task_id.cmp(&b.task_id))
+        });
+        Ok(anomalies)
+    }
+
+    /// Pearson correlation between the evaluation score and the payment of
+    /// every work income record.
+    ///
+    /// Close to 1.0 when better work is paid more. Payments withheld for a
+    /// low score or a validator rejection count as 0.
+    ///
+    /// # Errors
+    /// Fails with fewer than 3 income records, when every record has the
+    /// same score or the same payment, or on IO errors.
+    pub fn get_income_quality_correlation(&self) -> Result<f64> {
+        let fit = self.income_quality_fit()?;
+        if fit.payment_variation == 0.0 {
+            return Err(EconomicError::ConstantIncomeField {
+                field: "actual_payment",
+            });
+        }
+        Ok(fit.covariation / (fit.score_variation * fit.payment_variation).sqrt())
+    }
+
+    /// Least-squares line of payment against evaluation score over every
+    /// work income record, as `(slope, intercept)`: a task scored `s` is
+    /// expected to earn `slope * s + intercept`.
+    ///
+    /// # Errors
+    /// Fails with fewer than 3 income records, when every record has the
+    /// same score, or on IO errors.
+    pub fn get_income_quality_regression(&self) -> Result<(f64, f64)> {
+        let fit = self.income_quality_fit()?;
+        let slope = fit.covariation / fit.score_variation;
+        Ok((slope, fit.mean_payment - slope * fit.mean_score))
+    }
+
+    /// Share of the offered work income that was actually paid: the
+    /// payments received over the base amounts of every work income record.
+    ///
+    /// Falls below 1.0 as payments are withheld for low scores, rejected by
+    /// income validators, or adjusted down. NAN before any income was
+    /// offered.
+    pub fn get_income_retention_rate(&self) -> Result<f64> {
+        let (mut received, mut eligible) = (0.0, 0.0);
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            received += record.actual_payment;
+            eligible += record.base_amount;
+        })?;
+        if eligible <= 0.0 {
+            return Ok(f64::NAN);
+        }
+        Ok(received / eligible)
+    }
+
+    /// Total base amount of the work income payments that were withheld.
+    pub fn get_rejected_income_total(&self) -> Result<f64> {
+        let mut rejected = 0.0;
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            if record.was_rejected {
+                rejected += record.base_amount;
+            }
+        })?;
+        Ok(rejected)
+    }
+
+    /// Means and sums of squared deviations of the scores and payments of
+    /// the work income records.
+    fn income_quality_fit(&self) -> Result<IncomeQualityFit> {
+        let mut pairs = Vec::new();
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            pairs.push((record.evaluation_score, record.actual_payment));
+        })?;
+        if pairs.len() < 3 {
+            return Err(EconomicError::NotEnoughIncomeRecords {
+                needed: 3,
+                found: pairs.len(),
+            });
+        }
+
+        let count = pairs.len() as f64;
+        let mean_score = pairs.iter().map(|(score, _)| score).sum::<f64>() / count;
+        let mean_payment = pairs.iter().map(|(_, payment)| payment).sum::<f64>() / count;
+        let mut fit = IncomeQualityFit {
+            mean_score,
+            mean_payment,
+            score_variation: 0.0,
+            payment_variation: 0.0,
+            covariation: 0.0,
+        };
+        for (score, payment) in pairs {
+            let (dx, dy) = (score - mean_score, payment - mean_payment);
+            fit.score_variation += dx * dx;
+            fit.payment_variation += dy * dy;
+            fit.covariation += dx * dy;
+        }
+        if fit.score_variation == 0.0 {
+            return Err(EconomicError::ConstantIncomeField {
+                field: "evaluation_score",
+            });
+        }
+        Ok(fit)
+    }
+
+    /// Set the income target, replacing any earlier goal.
+    ///
+    /// Work and grant income received from now on counts toward it; see
+    /// [`get_goal_progress`](Self::get_goal_progress).
+    ///
+    /// # Errors
+    /// Fails when `target_usd` is not a positive amount or the deadline has
+    /// already passed.
+    pub fn set_income_goal(&self, goal: IncomeGoal) -> Result<()> {
+        if !goal.target_usd.is_finite() || goal.target_usd <= 0.0 {
+            return Err(EconomicError::InvalidAmount {
+                amount: goal.target_usd,
+            });
+        }
+        let now = Utc::now();
+        let deadline = DateTime::<Utc>::from(goal.deadline);
+        if deadline <= now {
+            return Err(EconomicError::DeadlinePassed { deadline });
+        }
+        self.state.lock().income_goal = Some((goal, now));
+        Ok(())
+    }
+
+    /// Progress toward the goal set with
+    /// [`set_income_goal`](Self::set_income_goal).
+    ///
+    /// # Errors
+    /// Fails when no goal is set, or on IO errors.
+    pub fn get_goal_progress(&self) -> Result<GoalProgress> {
+        let (goal, set_at) = self
+            .state
+            .lock()
+            .income_goal
+            .clone()
+            .ok_or(EconomicError::NoIncomeGoal)?;
+        let earned = self.income_since(set_at)?;
+        Ok(GoalProgress::compute(&goal, set_at, earned, Utc::now()))
+    }
+
+    /// Work and grant income received since `since`.
+    fn income_since(&self, since: DateTime<Utc>) -> Result<f64> {
+        let mut income = 0.0;
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            if record.timestamp >= since {
+                income += record.actual_payment;
+            }
+        })?;
+        self.for_each_record::<GrantIncomeRecord, _>(&self.grant_income_file_path(), |record| {
+            if record.timestamp >= since {
+                income += record.amount;
+            }
+        })?;
+        Ok(income)
+    }
+
+    /// Get total cost bucketed by UTC hour of day.
+    ///
+    /// Index `h` of the returned array holds the summed cost of every LLM and
+    /// API call made between `h:00` and `h:59` UTC, across all recorded days
+    /// and sessions. Calls from still-active tasks are included.
+    pub fn get_cost_by_time_of_day(&self) -> Result<[f64; 24]> {
+        let mut by_hour = [0.0; 24];
+
+        self.for_each_record::<TaskCostRecord, _>(&self.token_costs_file_path(), |record| {
+            for call in &record.llm_usage.calls_detail {
+                by_hour[call.timestamp.hour() as usize] += call.cost;
+            }
+            for call in &record.api_usage.calls_detail {
+                by_hour[call.timestamp.hour() as usize] += call.cost;
+            }
+        })?;
+
+        let state = self.state.lock();
+        for task in state.tasks.values() {
+            for call in &task.llm_calls {
+                by_hour[call.timestamp.hour() as usize] += call.cost;
+            }
+            for call in &task.api_calls {
+                by_hour[call.timestamp.hour() as usize] += call.cost;
+            }
+        }
+
+        Ok(by_hour)
+    }
+
+    /// LLM and API costs of calls made within `range`.
+    ///
+    /// Streams the task cost log, including archives the range reaches into,
+    /// and filters each call on its timestamp, so a task spanning the range
+    /// boundary is split between ranges. Calls from still-active tasks are
+    /// included.
+    pub fn cost_summary(&self, range: &DateRange) -> Result<RangeCostSummary> {
+        let mut summary = RangeCostSummary::empty(*range);
+        let mut add_calls = |llm_calls: &[LlmCallRecord], api_calls: &[ApiCallRecord]| {
+            for call in llm_calls.iter().filter(|call| range.contains(&call.timestamp)) {
+                summary.llm_cost += call.cost;
+                summary.llm_calls += 1;
+                summary.input_tokens += call.input_tokens;
+                summary.output_tokens += call.output_tokens;
+            }
+            for call in api_calls.iter().filter(|call| range.contains(&call.timestamp)) {
+                summary.api_cost += call.cost;
+                summary.api_calls += 1;
+            }
+        };
+
+        let files = logs::log_files(&self.data_path, "token_costs.jsonl", range);
+        logs::for_each_merged::<TaskCostRecord, _>(&files, |record| {
+            add_calls(&record.llm_usage.calls_detail, &record.api_usage.calls_detail);
+        })?;
+        {
+            let state = self.state.lock();
+            for task in state.tasks.values() {
+                add_calls(&task.llm_calls, &task.api_calls);
+            }
+        }
+
+        summary.total = summary.llm_cost + summary.api_cost;
+        Ok(summary)
+    }
+
+    /// Work income, grant income, and refunds received within `range`.
+    pub fn income_summary(&self, range: &DateRange) -> Result<RangeIncomeSummary> {
+        let mut summary = RangeIncomeSummary::empty(*range);
+
+        let range = Some(range);
+        self.for_each_logged::<WorkIncomeRecord, _>("token_costs.jsonl", range, |record| {
+            summary.work_income += record.actual_payment;
+            if record.payment_awarded {
+                summary.payments_awarded += 1;
+            } else {
+                summary.payments_rejected += 1;
+            }
+        })?;
+        self.for_each_logged::<GrantIncomeRecord, _>("grant_income.jsonl", range, |record| {
+            summary.grant_income += record.amount;
+        })?;
+        self.for_each_logged::<RefundRecord, _>("refunds.jsonl", range, |record| {
+            summary.refunds += record.amount();
+        })?;
+
+        summary.total_income = summary.work_income + summary.grant_income;
+        Ok(summary)
+    }
+
+    /// Tasks that ended within `range`, filtered on their completion time.
+    pub fn task_summary(&self, range: &DateRange) -> Result<RangeTaskSummary> {
+        let mut summary = RangeTaskSummary::empty(*range);
+
+        self.for_each_logged::<TaskCompletionRecord, _>(
+            "task_completions.jsonl",
+            Some(range),
+            |record| {
+                summary.tasks_ended += 1;
+                if record.status.is_income_eligible() {
+                    summary.completed += 1;
+                } else {
+                    summary.aborted += 1;
+                }
+                if let Some(cost) = record.cost_summary {
+                    summary.total_cost += cost.total;
+                    summary.tasks.push(cost);
+                }
+            },
+        )?;
+
+        Ok(summary)
+    }
+
+    /// Build analytics from the persisted cost, income, and completion logs.
+    ///
+    /// Tasks that were aborted, timed out, or rejected still count towards
+    /// costs but are excluded from the paid/rejected income counts. With
+    /// `tag` set, only tasks carrying that tag (after normalization) are
+    /// included.
+    pub fn get_analytics(&self, tag: Option<&str>) -> Result<EconomicAnalytics> {
+        self.build_analytics(tag, None)
+    }
+
+    /// Like [`Self::get_analytics`], but only for records dated within
+    /// `range`; archived months the range reaches into are read as well.
+    ///
+    /// Tasks are dated by their end time.
+    pub fn get_analytics_for(
+        &self,
+        range: &DateRange,
+        tag: Option<&str>,
+    ) -> Result<EconomicAnalytics> {
+        self.build_analytics(tag, Some(range))
+    }
+
+    fn build_analytics(
+        &self,
+        tag: Option<&str>,
+        range: Option<&DateRange>,
+    ) -> Result<EconomicAnalytics> {
+        let tag = tag.map(normalize_tag);
+        let has_tag = |tags: &[String]| tag.as_ref().is_none_or(|tag| tags.contains(tag));
+        let mut analytics = {
+            let state = self.state.lock();
+            EconomicAnalytics {
+                balance: state.balance,
+                initial_balance: state.initial_balance,
+                forecast_alpha: self.config.forecast_alpha,
+                ..Default::default()
+            }
+        };
+
+        let mut tagged_tasks: Vec<String> = Vec::new();
+        let mut llm_usage: HashMap<(NaiveDate, String, String, String), LlmUsageEntry> =
+            HashMap::new();
+        self.for_each_logged::<TaskCostRecord, _>("token_costs.jsonl", range, |record| {
+            if !has_tag(&record.tags) {
+                return;
+            }
+            tagged_tasks.push(record.task_id.clone());
+            for call in &record.llm_usage.calls_detail {
+                let date = call.timestamp.date_naive();
+                let provider = call.provider.clone().unwrap_or_else(|| "unknown".to_string());
+                let model = call.model.clone().unwrap_or_else(|| "unknown".to_string());
+                let session_id = call.session_id.clone().unwrap_or_else(|| "none".to_string());
+                let entry = llm_usage
+                    .entry((date, provider.clone(), model.clone(), session_id.clone()))
+                    .or_insert_with(|| LlmUsageEntry {
+                        date,
+                        provider,
+                        model,
+                        session_id,
+                        input_tokens: 0,
+                        output_tokens: 0,
+                        calls: 0,
+                        cost: 0.0,
+                    });
+                entry.input_tokens += call.input_tokens;
+                entry.output_tokens += call.output_tokens;
+                entry.calls += 1;
+                entry.cost += call.cost;
+                analytics.llm_call_costs.push((date, call.cost));
+            }
+            let total = record.cost_summary.total();
+            analytics.total_costs.add(&record.cost_summary);
+
+            let by_date = analytics.by_date.entry(record.date.clone()).or_default();
+            by_date.costs.add(&record.cost_summary);
+            by_date.total += total;
+
+            let by_task = analytics
+                .by_task
+                .entry(record.task_id.clone())
+                .or_insert_with(|| TaskCostSummary {
+                    date: record.date.clone(),
+                    task_id: record.task_id.clone(),
+                    tags: record.tags.clone(),
+                    metadata: record.metadata.clone(),
+                    ..Default::default()
+                });
+            by_task.costs.add(&record.cost_summary);
+            by_task.total += total;
+            by_task.llm_calls += record.llm_usage.total_calls;
+            by_task.prorated_overhead += record.prorated_overhead;
+        })?;
+        self.for_each_logged::<OverheadRecord, _>("overhead.jsonl", range, |record| {
+            for (task_id, share) in record.split_shares() {
+                if let Some(by_task) = analytics.by_task.get_mut(task_id) {
+                    by_task.prorated_overhead += share;
+                }
+            }
+        })?;
+
+        let mut llm_usage: Vec<(_, LlmUsageEntry)> = llm_usage.into_iter().collect();
+        llm_usage.sort_by(|(a, _), (b, _)| a.cmp(b));
+        analytics.llm_usage = llm_usage.into_iter().map(|(_, entry)| entry).collect();
+
+        let mut ineligible: HashMap<String, f64> = HashMap::new();
+        self.for_each_logged::<TaskCompletionRecord, _>("task_completions.jsonl", range, |record| {
+            if !has_tag(&record.tags) {
+                return;
+            }
+            analytics.total_tasks += 1;
+            if record.status.is_income_eligible() {
+                analytics.tasks_completed += 1;
+            } else {
+                analytics.tasks_aborted += 1;
+                let cost = record.cost_summary.as_ref().map_or(0.0, |summary| summary.total);
+                ineligible.insert(record.task_id, cost);
+            }
+        })?;
+        analytics.aborted_task_cost = ineligible.values().sum();
+        if analytics.total_tasks > 0 {
+            analytics.completion_rate =
+                analytics.tasks_completed as f64 / analytics.total_tasks as f64;
+        }
+
+        self.for_each_logged::<WorkIncomeRecord, _>("token_costs.jsonl", range, |record| {
+            if tag.is_some() && !tagged_tasks.contains(&record.task_id) {
+                return;
+            }
+            analytics.total_income += record.actual_payment;
+            analytics
+                .by_date
+                .entry(record.date.clone())
+                .or_default()
+                .income += record.actual_payment;
+            if ineligible.contains_key(&record.task_id) {
+                return;
+            }
+            if record.payment_awarded {
+                analytics.tasks_paid += 1;
+            } else {
+                analytics.tasks_rejected += 1;
+            }
+        })?;
+
+        Ok(analytics)
+    }
+
+    /// Reconstruct the balance at a past point in time.
+    ///
+    /// Starts from the latest balance snapshot taken at or before `at` and
+    /// replays the charges, work income, and refunds recorded after it, up
+    /// to and including `at`. Records sharing a timestamp are applied in file
+    /// order. Charges of active tasks are replayed from memory; charges
+    /// outside a task and trading P&L are carried by the next snapshot, and
+    /// replayed from memory until it is taken.
+    pub fn balance_at(&self, at: DateTime<Utc>) -> Result<f64> {
+        Ok(self.load_balance_history()?.balance_at(at))
+    }
+
+    /// Reconstruct the balance at `at` for auditing, see
+    /// [`balance_at`](Self::balance_at).
+    ///
+    /// # Errors
+    /// Returns [`EconomicError::BeforeSessionStart`] when `at` is before the
+    /// tracker's first session started, or an error if the logs cannot be
+    /// read.
+    pub fn get_balance_at_time(&self, at: SystemTime) -> Result<f64> {
+        let at = DateTime::<Utc>::from(at);
+        let history = self.load_balance_history()?;
+        let session_start = self.state.lock().session.started_at;
+        let session_start = history
+            .earliest()
+            .map_or(session_start, |earliest| earliest.min(session_start));
+        if at < session_start {
+            return Err(EconomicError::BeforeSessionStart { at, session_start });
+        }
+        Ok(history.balance_at(at))
+    }
+
+    /// Reconstructed balance at evenly spaced points in `range`, for
+    /// plotting.
+    ///
+    /// Points start at the range start (or the earliest record when
+    /// unbounded) and are spaced by `granularity`; an unbounded end stops at
+    /// the current time. See [`balance_at`](Self::balance_at).
+    pub fn balance_series(
+        &self,
+        range: impl RangeBounds<DateTime<Utc>>,
+        granularity: BalanceGranularity,
+    ) -> Result<Vec<(DateTime<Utc>, f64)>> {
+        let history = self.load_balance_history()?;
+        let now = Utc::now();
+        let step = granularity.step();
+
+        let mut point = match range.start_bound() {
+            Bound::Included(start) => *start,
+            Bound::Excluded(start) => *start + step,
+            Bound::Unbounded => history.earliest().unwrap_or(now),
+        };
+        let unbounded_end = matches!(range.end_bound(), Bound::Unbounded);
+
+        let mut series = Vec::new();
+        while range.contains(&point) && (!unbounded_end || point <= now) {
+            series.push((point, history.balance_at(point)));
+            match point.checked_add_signed(step) {
+                Some(next) => point = next,
+                None => break,
+            }
+        }
+        Ok(series)
+    }
+
+    /// Load balance snapshots and balance changes from the JSONL logs.
+    fn load_balance_history(&self) -> Result<BalanceHistory> {
+        let mut history = {
+            let state = self.state.lock();
+            let mut history = BalanceHistory::new(state.initial_balance);
+            // Not logged until the task ends or the next snapshot
+            for task in state.tasks.values() {
+                for call in &task.llm_calls {
+                    history.add_charge(call.timestamp, call.cost);
+                }
+                for call in &task.api_calls {
+                    history.add_charge(call.timestamp, call.cost);
+                }
+            }
+            for change in &state.unlogged_changes {
+                history.add_change(change);
+            }
+            history
+        };
+        self.for_each_record::<BalanceRecord, _>(&self.balance_file_path(), |record| {
+            history.add_snapshot(&record);
+        })?;
+        self.for_each_record::<CostLogRecord, _>(&self.token_costs_file_path(), |record| {
+            history.add_cost_log(record);
+        })?;
+        self.for_each_record::<RefundRecord, _>(&self.refunds_file_path(), |record| {
+            history.add_refund(&record);
+        })?;
+        self.for_each_record::<GrantIncomeRecord, _>(&self.grant_income_file_path(), |record| {
+            history.add_grant(&record);
+        })?;
+        self.for_each_record::<InterestRecord, _>(&self.interest_file_path(), |record| {
+            history.add_interest(&record);
+        })?;
+        self.for_each_record::<TransferRecord, _>(&self.transfers_file_path(), |record| {
+            history.add_transfer(&record);
+        })?;
+        Ok(history.sorted())
+    }
+
+    /// Merge two data directories of the same agent into `output`.
+    ///
+    /// Records from both directories are interleaved chronologically.
+    /// Identical records (same hash) are written once, and work income is
+    /// credited once per task: a second income record for the same task
+    /// with the same payment is dropped. `balance.jsonl` is rebuilt from the
+    /// merged history with one snapshot per day. Disagreements, such as
+    /// differing payments for one task or different snapshots for the same
+    /// day, are listed in the report for manual review; the primary's
+    /// version is the one written.
+    ///
+    /// # Errors
+    /// Fails if `output` already contains economic data, if neither
+    /// directory has a balance history, or on IO errors.
+    pub fn merge_data_dirs(primary: &Path, secondary: &Path, output: &Path) -> Result<MergeReport> {
+        merge::merge_data_dirs(primary, secondary, output)
+    }
+
+    /// Condense the current state into a portable snapshot.
+    ///
+    /// Holds the balance, cumulative totals, escrowed payments, a hash of
+    /// the configuration, and the daily aggregates of the last 30 days with
+    /// activity.
+    pub fn export_snapshot(&self) -> Result<EconomicSnapshot> {
+        let analytics = self.get_analytics(None)?;
+        let daily: BTreeMap<_, _> = analytics.by_date.into_iter().collect();
+        let skip = daily.len().saturating_sub(SNAPSHOT_DAYS);
+        let daily = daily.into_iter().skip(skip).collect();
+        let escrow = self.get_escrowed_income()?;
+
+        let state = self.state.lock();
+        Ok(EconomicSnapshot {
+            version: SNAPSHOT_VERSION,
+            signature: self.signature.clone(),
+            exported_at: Utc::now(),
+            balance: state.balance,
+            initial_balance: state.initial_balance,
+            total_token_cost: state.total_token_cost,
+            total_work_income: state.total_work_income,
+            total_trading_profit: state.total_trading_profit,
+            total_refunds: state.total_refunds,
+            total_grant_income: state.total_grant_income,
+            total_interest_earned: state.total_interest_earned,
+            total_interest_paid: state.total_interest_paid,
+            survival_status: self.get_survival_status_inner(&state),
+            outstanding_debt: (-state.balance).max(0.0),
+            escrow,
+            config_hash: self.config.config_hash(),
+            daily,
+        })
+    }
+
+    /// Seed `data_dir` from a snapshot.
+    ///
+    /// Writes a genesis balance record dated `imported` carrying the
+    /// snapshot's balance and totals, and re-holds its escrowed payments.
+    /// Open the directory with a tracker whose configuration matches
+    /// `snapshot.config_hash` (in particular `initial_balance`) for the
+    /// survival status to carry over.
+    ///
+    /// # Errors
+    /// [`EconomicError::DataDirNotEmpty`] if `data_dir` already contains
+    /// economic data, unless `force` is set, in which case that data is
+    /// removed first.
+    pub fn import_snapshot(
+        snapshot: &EconomicSnapshot,
+        data_dir: &Path,
+        force: bool,
+    ) -> Result<()> {
+        if merge::has_economic_data(data_dir)? {
+            if !force {
+                return Err(EconomicError::DataDirNotEmpty {
+                    path: data_dir.to_path_buf(),
+                });
+            }
+            snapshot::clear_economic_data(data_dir)?;
+        }
+        fs::create_dir_all(data_dir).at_path(data_dir)?;
+
+        append_jsonl(&data_dir.join("balance.jsonl"), &snapshot.genesis_record())?;
+        for record in &snapshot.escrow {
+            append_jsonl(&data_dir.join("escrow.jsonl"), record)?;
+        }
+        tracing::info!(
+            "📥 Imported economic snapshot of {} into {}: balance=${:.2}",
+            snapshot.signature,
+            data_dir.display(),
+            snapshot.balance
+        );
+        Ok(())
+    }
+
+    /// Export persisted costs, income, and refunds as bookkeeping entries.
+    ///
+    /// Every entry posts against `Assets:Cash`, starting from the opening
+    /// balance. Balance changes without a persisted record (trading P&L,
+    /// costs recorded outside a task or in still-active tasks) are posted to
+    /// `Equity:Adjustments`, so the cash account ends at the current balance.
+    pub fn export_for_accounting(&self, format: AccountingFormat) -> Result<String> {
+        let mut entries: Vec<LedgerEntry> = Vec::new();
+
+        self.for_each_record::<TaskCostRecord, _>(&self.token_costs_file_path(), |record| {
+            entries.extend(accounting::expense_entries(
+                record.timestamp_end,
+                &record.task_id,
+                &record.cost_summary,
+            ));
+        })?;
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            if record.actual_payment <= 0.0 {
+                return;
+            }
+            let mut memo = format!("Work income for task {}", record.task_id);
+            if !record.description.is_empty() {
+                memo = format!("{memo}: {}", record.description);
+            }
+            entries.push(LedgerEntry::new(
+                record.timestamp,
+                CASH_ACCOUNT,
+                INCOME_ACCOUNT,
+                record.actual_payment,
+                memo,
+            ));
+        })?;
+        self.for_each_record::<GrantIncomeRecord, _>(&self.grant_income_file_path(), |record| {
+            let mut memo = format!("Grant {}", record.grant_id);
+            if !record.description.is_empty() {
+                memo = format!("{memo}: {}", record.description);
+            }
+            entries.push(LedgerEntry::new(
+                record.timestamp,
+                CASH_ACCOUNT,
+                GRANT_INCOME_ACCOUNT,
+                record.amount,
+                memo,
+            ));
+        })?;
+        self.for_each_record::<InterestRecord, _>(&self.interest_file_path(), |record| {
+            let (debit, credit) = match record.kind {
+                InterestKind::Earned => (CASH_ACCOUNT, INTEREST_INCOME_ACCOUNT),
+                InterestKind::Paid => (INTEREST_EXPENSE_ACCOUNT, CASH_ACCOUNT),
+            };
+            entries.push(LedgerEntry::new(
+                record.timestamp,
+                debit,
+                credit,
+                record.amount,
+                format!("Interest for {}", record.date),
+            ));
+        })?;
+        self.for_each_record::<RefundRecord, _>(&self.refunds_file_path(), |record| {
+            entries.push(LedgerEntry::new(
+                record.timestamp,
+                CASH_ACCOUNT,
+                REFUNDS_ACCOUNT,
+                record.amount(),
+                format!("Refund of {}: {}", record.original_record_id, record.reason),
+            ));
+        })?;
+        entries.sort_by_key(|entry| entry.timestamp);
+
+        let (initial_balance, balance) = {
+            let state = self.state.lock();
+            (state.initial_balance, state.balance)
+        };
+        let opened_at = entries.first().map_or_else(Utc::now, |entry| entry.timestamp);
+        if initial_balance > 0.0 {
+            entries.insert(
+                0,
+                LedgerEntry::new(
+                    opened_at,
+                    CASH_ACCOUNT,
+                    OPENING_BALANCE_ACCOUNT,
+                    initial_balance,
+                    "Opening balance",
+                ),
+            );
+        }
+
+        let unrecorded = balance - accounting::cash_balance(&entries);
+        if unrecorded.abs() > 1e-9 {
+            let (debit, credit) = if unrecorded > 0.0 {
+                (CASH_ACCOUNT, ADJUSTMENTS_ACCOUNT)
+            } else {
+                (ADJUSTMENTS_ACCOUNT, CASH_ACCOUNT)
+            };
+            entries.push(LedgerEntry::new(
+                Utc::now(),
+                debit,
+                credit,
+                unrecorded.abs(),
+                "Balance adjustment",
+            ));
+        }
+
+        accounting::render(&entries, format)
+    }
+
+    /// Walk the hash chain of each JSONL log and report the first broken
+    /// link per file.
+    ///
+    /// Unsealed records at the head of a file (written before
+    /// `record_integrity` was enabled) are accepted; an unsealed record after
+    /// sealed ones is a break. See [`integrity`] for what the chain covers.
+    pub fn verify_integrity(&self) -> Result<IntegrityReport> {
+        // Hold off sealed appends so each file is read at a chain boundary
+        let _heads = self.integrity_heads.lock();
+        self.write_buffer.lock().drain()?;
+        let mut report = IntegrityReport::default();
+        for name in integrity::SEALED_LOGS {
+            let path = self.data_path.join(name);
+            if path.exists() {
+                report.files.push(integrity::verify_file(&path)?);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Move JSONL records older than `before` into gzip archives.
+    ///
+    /// Archives are written to `archive/` under the data directory, one per
+    /// active file per call. The latest balance snapshot is always kept so the
+    /// tracker can still restore its state, and in-memory state is untouched.
+    #[cfg(feature = "compress")]
+    pub fn drain_to_archive(&self, before: SystemTime) -> Result<ArchiveSummary> {
+        let cutoff: DateTime<Utc> = before.into();
+        let archive_dir = self.data_path.join("archive");
+        fs::create_dir_all(&archive_dir).at_path(&archive_dir)?;
+
+        // Held from each read to its rename, so appends wait for the rewrite
+        let mut buffer = self.write_buffer.lock();
+        buffer.drain()?;
+        let mut summary = ArchiveSummary::default();
+        for (name, clock) in archive::ARCHIVED_LOGS {
+            let path = self.data_path.join(name);
+            archive::drain_file(&path, Some(&*archive_dir), cutoff, clock, &mut summary)?;
+        }
+        drop(buffer);
+
+        tracing::info!(
+            "🗄️ Archived {} economic records ({} bytes freed)",
+            summary.records_archived,
+            summary.bytes_freed
+        );
+
+        Ok(summary)
+    }
+
+    /// Apply the configured retention policy to the data directory.
+    ///
+    /// Records appended meanwhile wait until the logs are rewritten.
+    /// In-memory state is untouched.
+    #[cfg(feature = "compress")]
+    pub fn apply_retention(&self) -> Result<RetentionReport> {
+        let mut buffer = self.write_buffer.lock();
+        buffer.drain()?;
+        RetentionRunner::new(self.config.retention.clone()).apply(&self.data_path)
+    }
+
+    /// Reset session tracking (for new decision/activity).
+    pub fn reset_session(&self) {
+        self.state.lock().session.reset();
+    }
+
+    /// When the tracker was initialized (its creation, until
+    /// [`initialize`](Self::initialize) runs).
+    pub fn get_session_start(&self) -> SystemTime {
+        self.state.lock().initialized_at.into()
+    }
+
+    /// Time elapsed since [`get_session_start`](Self::get_session_start),
+    /// measured with a monotonic clock.
+    pub fn get_session_duration(&self) -> Duration {
+        self.state.lock().initialized_instant.elapsed()
+    }
+
+    /// Extrapolate the current session's spend rate to a 30-day month.
+    ///
+    /// The session runs from tracker creation or the last
+    /// [`reset_session`](Self::reset_session) until now.
+    ///
+    /// # Errors
+    /// [`EconomicError::EmptySession`] if no time has elapsed in the session.
+    pub fn project_monthly_cost(&self) -> Result<MonthlyProjection> {
+        let (started_at, cost) = {
+            let state = self.state.lock();
+            (state.session.started_at, state.session.cost)
+        };
+        let elapsed_ms = (Utc::now() - started_at).num_milliseconds();
+        if elapsed_ms <= 0 {
+            return Err(EconomicError::EmptySession);
+        }
+        Ok(MonthlyProjection::from_session(cost, elapsed_ms as f64 / 3_600_000.0))
+    }
+
+    /// Record task completion statistics.
+    pub fn record_task_completion(
+        &self,
+        task_id: impl Into<String>,
+        work_submitted: bool,
+        wall_clock_seconds: f64,
+        evaluation_score: f64,
+        money_earned: f64,
+        attempt: u32,
+        date: Option<String>,
+    ) -> Result<()> {
+        let task_id = task_id.into();
+        let (date, occupation_category) = {
+            let state = self.state.lock();
+            let task = state.tasks.get(&task_id);
+            let date = date.or_else(|| {
+                task.or_else(|| state.current_task())
+                    .map(|task| task.task_date.clone())
+            });
+            (date, task.and_then(|task| task.category))
+        };
+        let date = match date {
+            Some(date) => date,
+            // A task that already ended keeps the date it was tracked under
+            None => {
+                let mut ended_date = None;
+                self.for_each_record::<TaskCompletionRecord, _>(
+                    &self.task_completions_file_path(),
+                    |record| {
+                        if record.task_id == task_id {
+                            ended_date = Some(record.date);
+                        }
+                    },
+                )?;
+                ended_date.unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string())
+            }
+        };
+
+        self.write_task_completion(TaskCompletionRecord {
+            task_id,
+            date,
+            attempt,
+            work_submitted,
+            evaluation_score,
+            money_earned,
+            wall_clock_seconds,
+            timestamp: Utc::now(),
+            started_at: None,
+            ended_at: None,
+            cost_summary: None,
+            tags: Vec::new(),
+            status: TaskStatus::Completed,
+            abort_reason: None,
+            abort_detail: None,
+            metadata: BTreeMap::new(),
+            occupation_category,
+        })
+    }
+
+    // ── Private helpers ──
+
+    /// Append `record` to the log at `path`, sealing it into the file's hash
+    /// chain when `record_integrity` is on.
+    fn append_record<T: Serialize>(&self, path: &Path, record: &T) -> Result<()> {
+        if !self.config.record_integrity {
+            return self.write_line(path, serde_json::to_string(record)?);
+        }
+        let mut heads = self.integrity_heads.lock();
+        let prev = match heads.get(path) {
+            Some(hash) => hash.clone(),
+            None => integrity::last_hash(path)?,
+        };
+        let (sealed, hash) = integrity::seal(record, prev.as_deref())?;
+        let written = self.write_line(path, sealed.to_string());
+        // A buffered record stays queued when writing the buffer fails, so
+        // the chain moves on either way
+        if written.is_ok() || !self.config.persistence.is_write_through() {
+            heads.insert(path.to_path_buf(), Some(hash));
+        }
+        written
+    }
+
+    /// Append a serialized record to `path`, or queue it in the write buffer
+    /// when writes are batched. Either way under the buffer lock, so the
+    /// append cannot land in the middle of a rewrite.
+    fn write_line(&self, path: &Path, line: String) -> Result<()> {
+        let mut buffer = self.write_buffer.lock();
+        if self.config.persistence.is_write_through() {
+            append_line(path, &line)
+        } else {
+            buffer.push(path, line)
+        }
+    }
+
+    /// Visit every record of the log at `path` that decodes as `T`, followed
+    /// by those still in the write buffer, so reads see records that are not
+    /// on disk yet.
+    fn for_each_record<T, F>(&self, path: &Path, mut on_record: F) -> Result<()>
+    where
+        T: DeserializeOwned,
+        F: FnMut(T),
+    {
+        // Held throughout so a concurrent write-out cannot move records
+        // between the file pass and the buffer pass
+        let buffer = self.write_buffer.lock();
+        for_each_jsonl(path, &mut on_record)?;
+        buffer.for_each_pending(path, on_record);
+        Ok(())
+    }
+
+    /// Store the completion record for a task, replacing any earlier one.
+    ///
+    /// The cost summary and terminal status captured by `end_task` or
+    /// `abort_task` are carried over when the new record does not have them.
+    fn write_task_completion(&self, mut record: TaskCompletionRecord) -> Result<()> {
+        // Both held from the read to the rewrite, so a completion appended or
+        // stored meanwhile is neither lost nor brought back
+        let mut heads = self.integrity_heads.lock();
+        let mut buffer = self.write_buffer.lock();
+        // The file is rewritten below, so buffered records must be in it
+        buffer.drain()?;
+
+        // Read existing records, filter out this task_id
+        let completions_file = self.task_completions_file_path();
+        // Resealing below would hide an edit made since the last rewrite
+        if self.config.record_integrity && completions_file.exists() {
+            if let Some(broken) = integrity::verify_file(&completions_file)?.broken {
+                return Err(EconomicError::IntegrityBroken {
+                    path: completions_file,
+                    line: broken.line,
+                });
+            }
+        }
+        let mut existing: Vec<String> = Vec::new();
+        let mut other_tasks = 0;
+
+        if completions_file.exists() {
+            let file = File::open(&completions_file).at_path(&completions_file)?;
+            let reader = BufReader::new(file);
+            for line in reader.lines() {
+                let line = line.at_path(&completions_file)?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                if let Ok(entry) = serde_json::from_str::<TaskCompletionRecord>(&line) {
+                    if entry.task_id != record.task_id {
+                        existing.push(line);
+                        other_tasks += 1;
+                    } else if record.cost_summary.is_none() {
+                        record.cost_summary = entry.cost_summary;
+                        record.status = entry.status;
+                        record.abort_reason = entry.abort_reason;
+                        record.abort_detail = entry.abort_detail;
+                        record.tags = entry.tags;
+                        record.metadata = entry.metadata;
+                        record.occupation_category =
+                            record.occupation_category.or(entry.occupation_category);
+                    }
+                } else {
+                    existing.push(line);
+                }
+            }
+        }
+
+        // Rewrite with updated record
+        existing.push(serde_json::to_string(&record)?);
+        // Dropping the replaced record breaks the chain, so reseal the file
+        let head = if self.config.record_integrity {
+            Some(integrity::rechain(&mut existing)?)
+        } else {
+            None
+        };
+        let rewrite = || -> std::io::Result<()> {
+            let mut file = File::create(&completions_file)?;
+            for line in &existing {
+                writeln!(file, "{}", line)?;
+            }
+            file.sync_all()
+        };
+        rewrite().at_path(&completions_file)?;
+        if let Some(head) = head {
+            heads.insert(completions_file, head);
+        }
+        drop(buffer);
+        drop(heads);
+        self.state.lock().tasks_ended = other_tasks + 1;
+        Ok(())
+    }
+
+    fn balance_file_path(&self) -> PathBuf {
+        self.data_path.join("balance.jsonl")
+    }
+
+    fn token_costs_file_path(&self) -> PathBuf {
+        self.data_path.join("token_costs.jsonl")
+    }
+
+    fn task_completions_file_path(&self) -> PathBuf {
+        self.data_path.join("task_completions.jsonl")
+    }
+
+    fn refunds_file_path(&self) -> PathBuf {
+        self.data_path.join("refunds.jsonl")
+    }
+
+    fn grant_income_file_path(&self) -> PathBuf {
+        self.data_path.join("grant_income.jsonl")
+    }
+
+    fn grace_file_path(&self) -> PathBuf {
+        self.data_path.join("grace.jsonl")
+    }
+
+    fn interest_file_path(&self) -> PathBuf {
+        self.data_path.join("interest.jsonl")
+    }
+
+    fn escrow_file_path(&self) -> PathBuf {
+        self.data_path.join("escrow.jsonl")
+    }
+
+    fn intake_file_path(&self) -> PathBuf {
+        self.data_path.join("intake.jsonl")
+    }
+
+    fn transfers_file_path(&self) -> PathBuf {
+        self.data_path.join("transfers.jsonl")
+    }
+
+    fn cost_corrections_file_path(&self) -> PathBuf {
+        self.data_path.join("cost_corrections.jsonl")
+    }
+
+    fn overhead_file_path(&self) -> PathBuf {
+        self.data_path.join("overhead.jsonl")
+    }
+
+    fn load_latest_state(&self) -> Result<()> {
+        let balance_file = self.balance_file_path();
+        let mut last_record: Option<BalanceRecord> = None;
+        for record in RecordReader::<BalanceRecord>::open(&balance_file)? {
+            match record {
+                Ok(record) => last_record = Some(record),
+                Err(EconomicError::MalformedRecord { .. }) => {}
+                Err(error) => return Err(error),
+            }
+        }
+
+        if let Some(record) = last_record {
+            let mut state = self.state.lock();
+            state.balance = record.balance;
+            state.total_token_cost = record.total_token_cost;
+            state.total_work_income = record.total_work_income;
+            state.total_trading_profit = record.total_trading_profit;
+            state.total_refunds = record.total_refunds;
+            state.total_grant_income = record.total_grant_income;
+            state.total_interest_earned = record.total_interest_earned;
+            state.total_interest_paid = record.total_interest_paid;
+            state.grace_spent = record.grace_spent;
+            // A tracker restored in bankruptcy has not crossed into it now
+            state.bankruptcy_notified =
+                self.get_survival_status_inner(&state) == SurvivalStatus::Bankrupt;
+        }
+
+        Ok(())
+    }
+
+    fn build_task_record(&self, state: &TrackerState, task: &TaskState) -> TaskCostRecord {
+        let pricing = self.token_pricing();
+        let total_input = task.llm_calls.iter().map(|c| c.input_tokens).sum();
+        let total_output = task.llm_calls.iter().map(|c| c.output_tokens).sum();
+        let llm_call_count = task.llm_calls.len();
+
+        let token_based = task.api_calls.iter()
+            .filter(|c| c.pricing_model == PricingModel::PerToken)
+            .count();
+        let flat_rate = task.api_calls.iter()
+            .filter(|c| c.pricing_model == PricingModel::FlatRate)
+            .count();
+
+        TaskCostRecord {
+            timestamp_end: Utc::now(),
+            timestamp_start: task.start_time,
+            date: task.task_date.clone(),
+            task_id: task.task_id.clone(),
+            tags: task.tags.clone(),
+            metadata: task.metadata.clone(),
+            estimated_budget: task.estimated_budget,
+            llm_usage: LlmUsageSummary {
+                total_calls: llm_call_count,
+                total_input_tokens: total_input,
+                total_output_tokens: total_output,
+                total_tokens: total_input + total_output,
+                total_cost: task.costs.llm_tokens,
+                total_cache_savings_usd: task
+                    .llm_calls
+                    .iter()
+                    .map(|c| c.cache_savings_usd)
+                    .sum(),
+                input_price_per_million: pricing.input_price_per_million,
+                output_price_per_million: pricing.output_price_per_million,
+                calls_detail: task.llm_calls.clone(),
+            },
+            api_usage: ApiUsageSummary {
+                total_calls: task.api_calls.len(),
+                search_api_cost: task.costs.search_api,
+                ocr_api_cost: task.costs.ocr_api,
+                other_api_cost: task.costs.other_api,
+                embedding_api_cost: task.costs.embedding_api,
+                image_generation_cost: task.costs.image_api,
+                token_based_calls: token_based,
+                flat_rate_calls: flat_rate,
+                calls_detail: task.api_calls.clone(),
+            },
+            cost_summary: task.costs.clone(),
+            balance_after: state.balance,
+            session_cost: state.session.cost,
+            daily_cost: state.daily.cost,
+            prorated_overhead: 0.0,
+        }
+    }
+
+    fn save_balance_record(
+        &self,
+        date: &str,
+        token_cost_delta: f64,
+        work_income_delta: f64,
+        trading_profit_delta: f64,
+        completed_tasks: Vec<String>,
+        api_error: bool,
+        checkpoint: bool,
+    ) -> Result<()> {
+        let mut state = self.state.lock();
+
+        let task_completion_time = match (state.daily.first_task_start, state.daily.last_task_end) {
+            (Some(start), Some(end)) => Some((end - start).num_seconds() as f64),
+            _ => None,
+        };
+
+        let record = BalanceRecord {
+            date: date.to_string(),
+            balance: state.balance,
+            token_cost_delta,
+            work_income_delta,
+            trading_profit_delta,
+            total_token_cost: state.total_token_cost,
+            total_work_income: state.total_work_income,
+            total_trading_profit: state.total_trading_profit,
+            net_worth: state.balance,
+            survival_status: self.get_survival_status_inner(&state).to_string(),
+            completed_tasks,
+            task_id: state.daily.task_ids.first().cloned(),
+            task_completion_time_seconds: task_completion_time,
+            api_error,
+            total_refunds: state.total_refunds,
+            total_grant_income: state.total_grant_income,
+            total_interest_earned: state.total_interest_earned,
+            total_interest_paid: state.total_interest_paid,
+            grace_spent: state.grace_spent,
+            checkpoint,
+            timestamp: Some(Utc::now()),
+            unlogged_changes: std::mem::take(&mut state.unlogged_changes),
+        };
+
+        // Clear before releasing the lock so changes made during IO stay dirty
+        state.dirty = false;
+        drop(state); // Release lock before IO
+
+        let written = self.append_record(&self.balance_file_path(), &record);
+        if written.is_err() {
+            let mut state = self.state.lock();
+            state.dirty = true;
+            // Left for the next snapshot, ahead of changes made since
+            state.unlogged_changes.splice(0..0, record.unlogged_changes);
+        }
+
+        written
+    }
+
+    /// Whether work income was already paid out for `task_id`.
+    fn is_task_paid(&self, task_id: &str) -> Result<bool> {
+        let mut paid = false;
+        self.for_each_record::<WorkIncomeRecord, _>(&self.token_costs_file_path(), |record| {
+            paid |= record.task_id == task_id && record.actual_payment > 0.0;
+        })?;
+        Ok(paid)
+    }
+
+    /// Append the income record for `candidate`, as offered before any
+    /// validator adjustment.
+    fn log_work_income(
+        &self,
+        timestamp: DateTime<Utc>,
+        candidate: &WorkIncomeCandidate,
+        actual_payment: f64,
+        rejection_reason: Option<String>,
+        evaluation_reasoning: Option<String>,
+    ) -> Result<()> {
+        let state = self.state.lock();
+        let task_id = candidate.task_id.as_str();
+
+        let record = WorkIncomeRecord {
+            timestamp,
+            date: state
+                .tasks
+                .get(task_id)
+                .or_else(|| state.current_task())
+                .map(|task| task.task_date.clone())
+                .unwrap_or_else(|| Utc::now().format("%Y-%m-%d").to_string()),
+            task_id: task_id.to_string(),
+            base_amount: candidate.amount,
+            actual_payment,
+            evaluation_score: candidate.evaluation_score,
+            threshold: self.config.min_evaluation_threshold,
+            payment_awarded: actual_payment > 0.0,
+            was_rejected: candidate.amount > 0.0 && actual_payment <= 0.0,
+            rejection_reason,
+            evaluation_reasoning,
+            description: candidate.description.clone(),
+            balance_after: state.balance,
+            metadata: candidate.metadata.clone(),
+        };
+
+        drop(state);
+
+        self.append_record(&self.token_costs_file_path(), &record)
+    }
+}
+
+/// Normalize a task tag: trimmed and lowercased.
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Normalize task tags, dropping empty and duplicate ones.
+fn normalize_tags(tags: &[&str]) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags.iter().map(|tag| normalize_tag(tag)) {
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+fn new_charge_id() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Record of an API call charged now, without token details.
+fn api_call_record(api_name: String, cost: f64, pricing_model: PricingModel) -> ApiCallRecord {
+    ApiCallRecord {
+        id: new_charge_id(),
+        timestamp: Utc::now(),
+        api_name,
+        pricing_model,
+        tokens: None,
+        price_per_million: None,
+        cost,
+    }
+}
+
+/// How often to retry a write that failed with an I/O error.
+#[derive(Debug, Clone, Copy)]
+struct RetryPolicy {
+    max_retries: u32,
+    /// Wait before the first retry, doubled for each further one
+    retry_delay: Duration,
+}
+
+impl RetryPolicy {
+    /// Fail on the first error.
+    const NONE: Self = Self {
+        max_retries: 0,
+        retry_delay: Duration::ZERO,
+    };
+
+    /// Run `write`, retrying it after I/O errors. Other errors are returned
+    /// immediately, since writing again would not help.
+    fn run<T>(self, what: &str, mut write: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut attempt = 0;
+        loop {
+            match write() {
+                Err(err @ EconomicError::Io { .. }) if attempt < self.max_retries => {
+                    let delay = self.retry_delay.saturating_mul(1 << attempt.min(16));
+                    attempt += 1;
+                    tracing::warn!(
+                        "⚠️ Writing {what} failed (attempt {attempt}/{}), retrying in {:?}: {err}",
+                        self.max_retries + 1,
+                        delay
+                    );
+                    std::thread::sleep(delay);
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Append one record to a JSONL file and sync it to disk.
+fn append_jsonl<T: Serialize>(path: &Path, record: &T) -> Result<()> {
+    append_line(path, &serde_json::to_string(record)?)
+}
+
+fn append_line(path: &Path, line: &str) -> Result<()> {
+    let append = || -> std::io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", line)?;
+        file.sync_all()
+    };
+    append().at_path(path)
+}
+
+/// Visit every line of a JSONL file that decodes as `T`.
+///
+/// Lines of other record kinds (several files mix record types) and blank
+/// lines are skipped. A missing file is treated as empty.
+pub(crate) fn for_each_jsonl<T, F>(path: &Path, mut on_record: F) -> Result<()>
+where
+    T: DeserializeOwned,
+    F: FnMut(T),
+{
+    if !path.exists() {
+        return Ok(());
+    }
+
+    // Logs mix record kinds, so lines of another kind are skipped
+    for record in RecordReader::<T>::open(path)? {
+        match record {
+            Ok(record) => on_record(record),
+            Err(EconomicError::MalformedRecord { .. }) => {}
+            Err(error) => return Err(error),
+        }
+    }
+
+    Ok(())
+}
+
+impl Drop for EconomicTracker {
+    fn drop(&mut self) {
+        if let Err(e) = self.flush() {
+            tracing::warn!(
+                "⚠️ Failed to flush economic state for {}: {e:#}",
+                self.signature
+            );
+        }
+    }
+}
+
+impl std::fmt::Display for EconomicTracker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let state = self.state.lock();
+        write!(
+            f,
+            "EconomicTracker(signature='{}', balance=${:.2}, status={})",
+            self.signature,
+            state.balance,
+            self.get_survival_status_inner(&state)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::economic::costs::{PromptType, MAX_METADATA_BYTES, MAX_METADATA_KEYS};
+    use crate::economic::evaluation::{PassthroughEvaluator, QualityScore};
+    use crate::economic::forecast::ProjectionConfidence;
+    use crate::economic::integrity::BrokenLinkKind;
+    use crate::economic::validation::ValidationResult;
+    use tempfile::TempDir;
+
+    fn test_config() -> EconomicConfig {
+        EconomicConfig {
+            enabled: true,
+            initial_balance: 1000.0,
+            token_pricing: TokenPricing {
+                input_price_per_million: 3.0,
+                output_price_per_million: 15.0,
+            },
+            min_evaluation_threshold: 0.6,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tracker_initialization() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            config,
+            Some(tmp.path().to_path_buf()),
+        );
+
+        tracker.initialize().unwrap();
+
+        assert!((tracker.get_balance() - 1000.0).abs() < f64::EPSILON);
+        assert_eq!(tracker.get_survival_status(), SurvivalStatus::Thriving);
+    }
+
+    #[test]
+    fn track_tokens_reduces_balance() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        let cost = tracker
+            .track_tokens(1000, 500, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        tracker.end_task("task-1").unwrap();
+
+        // (1000/1M)*3 + (500/1M)*15 = 0.003 + 0.0075 = 0.0105
+        assert!((cost - 0.0105).abs() < 0.0001);
+        assert!((tracker.get_balance() - (1000.0 - 0.0105)).abs() < 0.0001);
+    }
+
+    type Fields = BTreeMap<String, String>;
+
+    /// A tracing event with its fields and the spans it is nested in,
+    /// outermost first.
+    struct CapturedEvent {
+        fields: Fields,
+        spans: Vec<(String, Fields)>,
+    }
+
+    #[derive(Default)]
+    struct FieldVisitor(Fields);
+
+    impl tracing::field::Visit for FieldVisitor {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0.insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    struct CaptureLayer(Arc<Mutex<Vec<CapturedEvent>>>);
+
+    impl<S> tracing_subscriber::Layer<S> for CaptureLayer
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(
+            &self,
+            attrs: &tracing::span::Attributes<'_>,
+            id: &tracing::span::Id,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut visitor = FieldVisitor::default();
+            attrs.record(&mut visitor);
+            if let Some(span) = ctx.span(id) {
+                span.extensions_mut().insert(visitor);
+            }
+        }
+
+        fn on_event(
+            &self,
+            event: &tracing::Event<'_>,
+            ctx: tracing_subscriber::layer::Context<'_, S>,
+        ) {
+            let mut visitor = FieldVisitor::default();
+            event.record(&mut visitor);
+            let spans = ctx
+                .event_scope(event)
+                .map(|scope| {
+                    scope
+                        .from_root()
+                        .map(|span| {
+                            let fields = span
+                                .extensions()
+                                .get::<FieldVisitor>()
+                                .map(|visitor| visitor.0.clone())
+                                .unwrap_or_default();
+                            (span.name().to_string(), fields)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            self.0.lock().push(CapturedEvent {
+                fields: visitor.0,
+                spans,
+            });
+        }
+    }
+
+    #[test]
+    fn state_changes_emit_structured_events_under_the_task_span() {
+        use tracing_subscriber::layer::SubscriberExt;
+        use tracing_subscriber::util::SubscriberInitExt;
+
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config();
+        config.initial_balance = 100.0;
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let subscriber =
+            tracing_subscriber::registry().with(CaptureLayer(Arc::clone(&captured)));
+
+        let guard = subscriber.set_default();
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 0, "agent", Some(30.0), Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.add_grant_income(5.0, "grant-1", "seed").unwrap();
+        drop(guard);
+
+        let events = captured.lock();
+        let find = |message: &str| {
+            events
+                .iter()
+                .find(|event| event.fields.get("message").map(String::as_str) == Some(message))
+                .unwrap_or_else(|| panic!("no {message:?} event"))
+        };
+        let fields = |pairs: &[(&str, &str)]| -> Fields {
+            pairs
+                .iter()
+                .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+                .collect()
+        };
+        let task_span = vec![(
+            "economic_task".to_string(),
+            fields(&[("agent_id", "test-agent"), ("task_id", "task-1")]),
+        )];
+
+        let tracked = find("economic: tokens tracked");
+        assert_eq!(
+            tracked.fields,
+            fields(&[
+                ("message", "economic: tokens tracked"),
+                ("agent_id", "test-agent"),
+                ("task_id", "task-1"),
+                ("cost_usd", "30.0"),
+                ("balance", "70.0"),
+                ("status", "Stable"),
+            ])
+        );
+        assert_eq!(tracked.spans, task_span);
+
+        let changed = find("economic: status changed");
+        assert_eq!(changed.fields["previous_status"], "Thriving");
+        assert_eq!(changed.fields["status"], "Stable");
+        assert_eq!(changed.fields["task_id"], "task-1");
+        assert_eq!(changed.spans, task_span);
+
+        // Outside a task the event has no parent span and an empty task id
+        let grant = find("economic: grant income added");
+        assert_eq!(grant.fields["task_id"], "");
+        assert_eq!(grant.fields["cost_usd"], "-5.0");
+        assert_eq!(grant.fields["balance"], "75.0");
+        assert!(grant.spans.is_empty());
+    }
+
+    #[test]
+    fn work_income_with_threshold() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        // Below threshold - no payment
+        let payment = tracker.add_work_income(100.0, "task-1", 0.5, "").unwrap();
+        assert!((payment - 0.0).abs() < f64::EPSILON);
+        assert!((tracker.get_balance() - 1000.0).abs() < f64::EPSILON);
+
+        // At threshold - payment awarded
+        let payment = tracker.add_work_income(100.0, "task-2", 0.6, "").unwrap();
+        assert!((payment - 100.0).abs() < f64::EPSILON);
+        assert!((tracker.get_balance() - 1100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn income_validators_gate_payment_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.register_income_validator(Box::new(|candidate| {
+            if candidate.description.contains("unreviewed") {
+                ValidationResult::Rejected("not reviewed".to_string())
+            } else {
+                ValidationResult::Approved
+            }
+        }));
+        tracker.register_income_validator(Box::new(|candidate| {
+            ValidationResult::Adjusted(candidate.amount.min(50.0))
+        }));
+
+        let payment = tracker
+            .add_work_income(100.0, "task-1", 0.9, "unreviewed draft")
+            .unwrap();
+        assert!((payment - 0.0).abs() < f64::EPSILON);
+        let payment = tracker.add_work_income(100.0, "task-2", 0.9, "").unwrap();
+        assert!((payment - 50.0).abs() < f64::EPSILON);
+        assert!((tracker.get_balance() - 1050.0).abs() < f64::EPSILON);
+
+        let mut records = Vec::new();
+        for_each_jsonl::<WorkIncomeRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            records.push(record);
+        })
+        .unwrap();
+        assert_eq!(records[0].rejection_reason.as_deref(), Some("not reviewed"));
+        assert!(!records[0].payment_awarded);
+        assert!((records[1].base_amount - 100.0).abs() < f64::EPSILON);
+        assert!((records[1].actual_payment - 50.0).abs() < f64::EPSILON);
+
+        // The rejected task was never paid, so it can still be
+        tracker.add_work_income(100.0, "task-1", 0.9, "").unwrap();
+    }
+
+    struct FailingEvaluator;
+
+    #[async_trait::async_trait]
+    impl QualityEvaluator for FailingEvaluator {
+        async fn evaluate(&self, _: &str, _: &str, _: &str) -> anyhow::Result<QualityScore> {
+            anyhow::bail!("judge unavailable")
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_evaluations_hold_income_in_escrow() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(matches!(
+            tracker
+                .complete_and_evaluate("task-0", "", "", 10.0)
+                .await
+                .unwrap_err(),
+            EconomicError::InvalidConfig(_)
+        ));
+
+        tracker.set_quality_evaluator(Arc::new(PassthroughEvaluator::new(0.9)));
+        let outcome = tracker
+            .complete_and_evaluate("task-1", "summarize", "summary", 10.0)
+            .await
+            .unwrap();
+        assert!(matches!(outcome, EvaluationOutcome::Settled { payment, .. } if payment == 10.0));
+
+        tracker.set_quality_evaluator(Arc::new(FailingEvaluator));
+        let outcome = tracker
+            .complete_and_evaluate("task-2", "translate", "traduction", 20.0)
+            .await
+            .unwrap();
+        assert!(matches!(
+            outcome,
+            EvaluationOutcome::Escrowed { ref reason } if reason.contains("unavailable")
+        ));
+        assert!((tracker.get_balance() - 1010.0).abs() < f64::EPSILON);
+        assert!((tracker.get_summary().pending_escrow - 20.0).abs() < f64::EPSILON);
+        drop(tracker);
+
+        // Escrow survives a restart and is paid once released
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        let held = tracker.get_escrowed_income().unwrap();
+        assert_eq!(held.len(), 1);
+        assert_eq!(held[0].deliverable, "traduction");
+        let payment = tracker.release_escrow("task-2", 0.8, "manual review").unwrap();
+        assert!((payment - 20.0).abs() < f64::EPSILON);
+        assert!(tracker.get_escrowed_income().unwrap().is_empty());
+        assert!((tracker.get_pending_escrow() - 0.0).abs() < f64::EPSILON);
+        assert!(matches!(
+            tracker.release_escrow("task-2", 0.8, "again").unwrap_err(),
+            EconomicError::TaskNotFound { .. }
+        ));
+
+        let mut reasoning = Vec::new();
+        for_each_jsonl::<WorkIncomeRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            reasoning.push(record.evaluation_reasoning);
+        })
+        .unwrap();
+        assert_eq!(
+            reasoning,
+            [Some("passthrough".to_string()), Some("manual review".to_string())]
+        );
+    }
+
+    #[test]
+    fn errors_are_typed() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        // A task paid once can't be paid again; an unpaid one can
+        tracker.add_work_income(10.0, "task-1", 0.1, "").unwrap();
+        tracker.add_work_income(10.0, "task-1", 0.9, "").unwrap();
+        let err = tracker.add_work_income(10.0, "task-1", 0.9, "").unwrap_err();
+        assert!(matches!(
+            err,
+            EconomicError::DuplicateIncome { ref task_id } if task_id == "task-1"
+        ));
+        assert!((tracker.get_balance() - 1010.0).abs() < f64::EPSILON);
+
+        let invalid = EconomicTracker::new(
+            "test-agent",
+            EconomicConfig {
+                min_evaluation_threshold: 1.5,
+                ..test_config()
+            },
+            Some(tmp.path().to_path_buf()),
+        );
+        assert!(matches!(
+            invalid.initialize().unwrap_err(),
+            EconomicError::InvalidConfig(_)
+        ));
+
+        // I/O errors name the file and still convert into anyhow
+        let blocker = tmp.path().join("not-a-dir");
+        fs::write(&blocker, "").unwrap();
+        let blocked = EconomicTracker::new("test-agent", test_config(), Some(blocker.clone()));
+        let err = blocked.initialize().unwrap_err();
+        assert!(matches!(err, EconomicError::Io { ref path, .. } if *path == blocker));
+        let err = anyhow::Error::from(err);
+        assert!(err.to_string().contains("not-a-dir"));
+    }
+
+    #[test]
+    fn time_of_use_pricing_scales_computed_token_costs() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        let base = tracker
+            .track_tokens(1_000_000, 1_000_000, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        let all_day = crate::economic::HourRange::new(0, 24);
+        tracker.set_time_of_use_pricing(TimeOfUsePricing::new(vec![(all_day, 1.5)]));
+        let peak = tracker
+            .track_tokens(1_000_000, 1_000_000, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert!((peak - base * 1.5).abs() < 1e-12);
+
+        // Explicit costs are what the provider billed and are not scaled
+        let explicit = tracker
+            .track_tokens(1000, 500, "agent", Some(2.0), Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert!((explicit - 2.0).abs() < 1e-12);
+
+        tracker.set_time_of_use_pricing(TimeOfUsePricing::default());
+        let off_peak = tracker
+            .track_tokens(1_000_000, 1_000_000, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert!((off_peak - base).abs() < 1e-12);
+    }
+
+    #[test]
+    fn cached_tokens_cost_less_and_record_savings() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        let uncached = tracker
+            .track_tokens(1_000_000, 0, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        let cached = tracker
+            .track_tokens_with_cache(1_000_000, 800_000, 0, "agent")
+            .unwrap()
+            .cost;
+        tracker.end_task("task-1").unwrap();
+
+        // 800K cached tokens at 90% off the $3/1M input price saves $2.16
+        assert!((uncached - 3.0).abs() < 1e-9);
+        assert!((cached - 0.84).abs() < 1e-9);
+        assert!(cached < uncached);
+
+        let mut records = Vec::new();
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            records.push(record);
+        })
+        .unwrap();
+        let usage = &records[0].llm_usage;
+        assert!(!usage.calls_detail[0].cache_hit);
+        assert!(usage.calls_detail[1].cache_hit);
+        assert!((usage.calls_detail[1].cache_savings_usd - 2.16).abs() < 1e-9);
+        assert!((usage.total_cache_savings_usd - 2.16).abs() < 1e-9);
+    }
+
+    #[test]
+    fn end_task_returns_cost_summary() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", Some("2025-01-01".into()), &[]).unwrap();
+        tracker.track_model_tokens("claude-sonnet", 1000, 500, "agent", Some(0.5)).unwrap();
+        tracker.track_model_tokens("claude-sonnet", 2000, 100, "agent", Some(0.25)).unwrap();
+        tracker.track_model_tokens("gpt-4o-mini", 300, 30, "wrapup", Some(0.01)).unwrap();
+        tracker.track_tokens(10, 10, "agent", Some(0.01), Duration::ZERO).unwrap();
+        tracker.track_flat_api_call(0.1, "tavily_search");
+        assert!((tracker.peek_task_cost("task-1").unwrap() - 0.87).abs() < 1e-9);
+
+        let summary = tracker.end_task("task-1").unwrap();
+        assert_eq!(summary.task_id, "task-1");
+        assert_eq!(summary.date, "2025-01-01");
+        assert_eq!(summary.llm_calls, 4);
+        assert!((summary.total - 0.87).abs() < 1e-9);
+        assert!((summary.service_cost() - 0.1).abs() < 1e-9);
+        assert!(summary.duration_seconds >= 0.0);
+        assert_eq!(
+            summary.tokens_by_model["claude-sonnet"],
+            ModelTokenUsage {
+                input_tokens: 3000,
+                output_tokens: 600,
+                calls: 2,
+            }
+        );
+        assert_eq!(summary.tokens_by_model["gpt-4o-mini"].calls, 1);
+        assert_eq!(summary.tokens_by_model["unknown"].input_tokens, 10);
+
+        // The summary is persisted as the task's completion record and
+        // survives a later record_task_completion for the same task
+        tracker
+            .record_task_completion("task-1", true, 12.0, 0.9, 10.0, 1, None)
+            .unwrap();
+        let mut completions = Vec::new();
+        for_each_jsonl::<TaskCompletionRecord, _>(
+            &tmp.path().join("task_completions.jsonl"),
+            |record| completions.push(record),
+        )
+        .unwrap();
+        assert_eq!(completions.len(), 1);
+        assert_eq!(completions[0].date, "2025-01-01");
+        let persisted = completions[0].cost_summary.as_ref().unwrap();
+        assert!((persisted.total - 0.87).abs() < 1e-9);
+        assert_eq!(persisted.llm_calls, 4);
+    }
+
+    #[test]
+    fn aborted_tasks_count_costs_but_not_completions() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(1.0), Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.add_work_income(10.0, "task-1", 0.9, "").unwrap();
+
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(2.0), Duration::ZERO).unwrap();
+        let summary = tracker
+            .abort_task("task-2", TaskAbortReason::TimedOut, "exceeded 10m")
+            .unwrap();
+        assert!((summary.total - 2.0).abs() < f64::EPSILON);
+        tracker.add_work_income(10.0, "task-2", 0.1, "").unwrap();
+
+        // Aborting an already-closed task is an error
+        assert!(tracker
+            .abort_task("task-2", TaskAbortReason::Cancelled, "")
+            .is_err());
+        assert!((tracker.get_balance() - (1000.0 - 3.0 + 10.0)).abs() < 1e-9);
+
+        let analytics = tracker.get_analytics(None).unwrap();
+        assert_eq!(analytics.total_tasks, 2);
+        assert_eq!(analytics.tasks_completed, 1);
+        assert_eq!(analytics.tasks_aborted, 1);
+        assert!((analytics.completion_rate - 0.5).abs() < f64::EPSILON);
+        assert!((analytics.aborted_task_cost - 2.0).abs() < f64::EPSILON);
+        assert!((analytics.total_costs.total() - 3.0).abs() < f64::EPSILON);
+        assert_eq!(analytics.tasks_paid, 1);
+        assert_eq!(analytics.tasks_rejected, 0);
+
+        let mut completions = Vec::new();
+        for_each_jsonl::<TaskCompletionRecord, _>(
+            &tmp.path().join("task_completions.jsonl"),
+            |record| completions.push(record),
+        )
+        .unwrap();
+        let aborted = completions.iter().find(|r| r.task_id == "task-2").unwrap();
+        assert_eq!(aborted.status, TaskStatus::TimedOut);
+        assert_eq!(aborted.abort_reason, Some(TaskAbortReason::TimedOut));
+        assert_eq!(aborted.abort_detail.as_deref(), Some("exceeded 10m"));
+    }
+
+    #[test]
+    fn hourly_rates_divide_by_task_time() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(matches!(
+            tracker.get_income_per_hour_worked(),
+            Err(EconomicError::NoTaskTime)
+        ));
+
+        // 30 and 90 minutes of work
+        for (task_id, seconds) in [("task-1", 1800.0), ("task-2", 5400.0)] {
+            tracker
+                .record_task_completion(task_id, true, seconds, 0.9, 0.0, 1, None)
+                .unwrap();
+        }
+        tracker.add_work_income(30.0, "task-1", 0.9, "report").unwrap();
+        tracker.add_work_income(10.0, "task-2", 0.9, "summary").unwrap();
+        tracker
+            .track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO)
+            .unwrap();
+
+        assert!((tracker.get_income_per_hour_worked().unwrap() - 20.0).abs() < 1e-9);
+        assert!((tracker.get_profit_per_hour().unwrap() - 18.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn category_performance_groups_ended_tasks() {
+        use crate::economic::OccupationCategory::{
+            BusinessFinance, HealthcareSocialServices, LegalMediaOperations, TechnologyEngineering,
+        };
+
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(tracker.get_category_performance_summary().unwrap().is_empty());
+        assert!(matches!(
+            tracker.set_task_category("missing", BusinessFinance),
+            Err(EconomicError::TaskNotFound { .. })
+        ));
+
+        let fixtures = [
+            ("api", Some(TechnologyEngineering), 2.0),
+            ("refactor", Some(TechnologyEngineering), 1.0),
+            ("forecast", Some(BusinessFinance), 3.0),
+            ("triage", Some(HealthcareSocialServices), 0.5),
+            ("contract", Some(LegalMediaOperations), 1.5),
+            ("untagged", None, 4.0),
+        ];
+        for (task_id, category, cost) in fixtures {
+            tracker.start_task(task_id, None, &[]).unwrap();
+            if let Some(category) = category {
+                tracker.set_task_category(task_id, category).unwrap();
+            }
+            tracker
+                .track_tokens(1000, 500, "agent", Some(cost), Duration::ZERO)
+                .unwrap();
+            if task_id == "contract" {
+                tracker.mark_task_failed(task_id, "rejected").unwrap();
+            } else {
+                tracker.end_task(task_id).unwrap();
+            }
+        }
+        tracker.add_work_income(10.0, "api", 0.9, "endpoint").unwrap();
+        // Below the threshold: evaluated, but not paid
+        tracker.add_work_income(10.0, "refactor", 0.4, "cleanup").unwrap();
+        tracker.add_work_income(5.0, "forecast", 0.8, "model").unwrap();
+        tracker.add_work_income(8.0, "untagged", 0.9, "other").unwrap();
+        // Replacing the completion record keeps its category
+        tracker
+            .record_task_completion("triage", true, 60.0, 0.7, 0.0, 2, None)
+            .unwrap();
+
+        let summary = tracker.get_category_performance_summary().unwrap();
+        assert_eq!(summary.len(), 4);
+
+        let tech = &summary[&TechnologyEngineering];
+        assert_eq!(tech.task_count, 2);
+        assert!((tech.total_cost - 3.0).abs() < 1e-9);
+        assert!((tech.total_income - 10.0).abs() < 1e-9);
+        assert!((tech.net_pnl - 7.0).abs() < 1e-9);
+        assert!((tech.average_quality - 0.65).abs() < 1e-9);
+        assert!((tech.success_rate - 0.5).abs() < 1e-9);
+
+        let business = &summary[&BusinessFinance];
+        assert_eq!(business.task_count, 1);
+        assert!((business.net_pnl - 2.0).abs() < 1e-9);
+        assert!((business.average_quality - 0.8).abs() < 1e-9);
+        assert!((business.success_rate - 1.0).abs() < 1e-9);
+
+        let healthcare = &summary[&HealthcareSocialServices];
+        assert_eq!(healthcare.task_count, 1);
+        assert!((healthcare.net_pnl + 0.5).abs() < 1e-9);
+        assert!((healthcare.average_quality - 0.7).abs() < 1e-9);
+        assert!(healthcare.success_rate.abs() < f64::EPSILON);
+
+        let legal = &summary[&LegalMediaOperations];
+        assert_eq!(legal.task_count, 1);
+        assert!((legal.total_cost - 1.5).abs() < 1e-9);
+        assert!(legal.total_income.abs() < f64::EPSILON);
+        assert!(legal.success_rate.abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn outlier_tasks_are_found_by_cost_and_duration() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        assert!(tracker.get_most_expensive_task().unwrap().is_none());
+        assert!(tracker.get_longest_task().unwrap().is_none());
+
+        let costs = [3.0, 7.5, 1.0, 12.0, 0.5, 9.0, 4.0, 11.0, 2.0, 6.0];
+        for (i, cost) in costs.into_iter().enumerate() {
+            let task_id = format!("task-{i}");
+            tracker.start_task(&task_id, None, &[]).unwrap();
+            tracker
+                .track_tokens(1000, 500, "agent", Some(cost), Duration::ZERO)
+                .unwrap();
+            // The last task is still running
+            if i < costs.len() - 1 {
+                tracker.end_task(&task_id).unwrap();
+            }
+        }
+
+        let most_expensive = tracker.get_most_expensive_task().unwrap().unwrap();
+        assert_eq!(most_expensive.task_id, "task-3");
+        assert!((most_expensive.total - 12.0).abs() < 1e-9);
+        let top: Vec<String> = tracker
+            .get_top_n_expensive_tasks(4)
+            .unwrap()
+            .into_iter()
+            .map(|task| task.task_id)
+            .collect();
+        assert_eq!(top, ["task-3", "task-7", "task-5", "task-1"]);
+        assert_eq!(tracker.get_top_n_expensive_tasks(20).unwrap().len(), 10);
+        assert!(tracker.get_top_n_expensive_tasks(0).unwrap().is_empty());
+
+        for (task_id, seconds) in [("batch-a", 5.0), ("batch-b", 42.0), ("batch-c", 17.0)] {
+            tracker
+                .record_task_completion(task_id, true, seconds, 0.9, 0.0, 1, None)
+                .unwrap();
+        }
+        let (task_id, duration) = tracker.get_longest_task().unwrap().unwrap();
+        assert_eq!(task_id, "batch-b");
+        assert_eq!(duration, Duration::from_secs(42));
+    }
+
+    #[test]
+    fn task_duration_stats_use_nearest_rank_percentiles() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert_eq!(tracker.get_average_task_duration().unwrap(), Duration::ZERO);
+        assert_eq!(tracker.get_p95_task_duration().unwrap(), Duration::ZERO);
+        assert!(tracker.get_task_duration_histogram(4).unwrap().is_empty());
+
+        // Tasks running 1s..20s, recorded out of order
+        for seconds in (1..=20).rev() {
+            tracker
+                .record_task_completion(
+                    format!("task-{seconds}"),
+                    true,
+                    f64::from(seconds),
+                    0.9,
+                    0.0,
+                    1,
+                    Some("2025-01-01".into()),
+                )
+                .unwrap();
+        }
+        assert_eq!(
+            tracker.get_average_task_duration().unwrap(),
+            Duration::from_millis(10_500)
+        );
+        assert_eq!(tracker.get_median_task_duration().unwrap(), Duration::from_secs(10));
+        assert_eq!(tracker.get_p95_task_duration().unwrap(), Duration::from_secs(19));
+
+        let histogram = tracker.get_task_duration_histogram(4).unwrap();
+        assert_eq!(histogram.len(), 4);
+        assert_eq!(
+            histogram.iter().map(|bucket| bucket.count).collect::<Vec<_>>(),
+            [5, 5, 5, 5]
+        );
+        assert_eq!(histogram[0].lower_bound, Duration::from_secs(1));
+        assert_eq!(histogram[1].lower_bound, Duration::from_millis(5750));
+        assert_eq!(histogram[3].upper_bound, Duration::from_secs(20));
+        assert!(tracker.get_task_duration_histogram(0).unwrap().is_empty());
+
+        // Tasks ended through the tracker record when they started and ended
+        tracker.start_task("tracked", None, &[]).unwrap();
+        tracker.end_task("tracked").unwrap();
+        let mut tracked = None;
+        for_each_jsonl::<TaskCompletionRecord, _>(
+            &tmp.path().join("task_completions.jsonl"),
+            |record| {
+                if record.task_id == "tracked" {
+                    tracked = Some(record);
+                }
+            },
+        )
+        .unwrap();
+        let tracked = tracked.unwrap();
+        assert!(tracked.started_at.unwrap() <= tracked.ended_at.unwrap());
+        assert!(tracked.duration() < Duration::from_secs(1));
+        assert_eq!(tracker.get_task_duration_histogram(1).unwrap()[0].count, 21);
+    }
+
+    #[test]
+    fn shared_costs_are_prorated_across_completed_tasks() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(matches!(
+            tracker.prorate_shared_cost(0.9, ProrationStrategy::EvenlyAcrossAllTasks),
+            Err(EconomicError::NoCompletedTasks)
+        ));
+
+        for (task_id, tokens) in [("task-a", 1000), ("task-b", 2000), ("task-c", 3000)] {
+            tracker.start_task(task_id, None, &[]).unwrap();
+            tracker.track_tokens(tokens, 0, "agent", Some(0.01), Duration::ZERO).unwrap();
+            tracker.end_task(task_id).unwrap();
+        }
+        // Aborted tasks get no share
+        tracker.start_task("task-aborted", None, &[]).unwrap();
+        tracker
+            .abort_task("task-aborted", TaskAbortReason::Cancelled, "")
+            .unwrap();
+        let balance = tracker.get_balance();
+
+        tracker
+            .prorate_shared_cost(0.9, ProrationStrategy::EvenlyAcrossAllTasks)
+            .unwrap();
+        let overhead = |tracker: &EconomicTracker| -> HashMap<String, f64> {
+            let analytics = tracker.get_analytics(None).unwrap();
+            analytics
+                .by_task
+                .into_iter()
+                .map(|(task_id, summary)| (task_id, summary.prorated_overhead))
+                .collect()
+        };
+        let shares = overhead(&tracker);
+        for task_id in ["task-a", "task-b", "task-c"] {
+            assert!((shares[task_id] - 0.3).abs() < 1e-9);
+        }
+        assert!(shares["task-aborted"].abs() < f64::EPSILON);
+        assert!((tracker.get_balance() - balance).abs() < f64::EPSILON);
+        let analytics = tracker.get_analytics(None).unwrap();
+        assert_eq!(analytics.by_task["task-a"].llm_calls, 1);
+        assert!((analytics.total_costs.total() - 0.03).abs() < 1e-9);
+
+        tracker
+            .prorate_shared_cost(0.6, ProrationStrategy::ProportionalToTokens)
+            .unwrap();
+        let shares = overhead(&tracker);
+        assert!((shares["task-a"] - 0.4).abs() < 1e-9);
+        assert!((shares["task-c"] - 0.6).abs() < 1e-9);
+
+        tracker
+            .prorate_shared_cost(0.25, ProrationStrategy::AddToNextTask)
+            .unwrap();
+        tracker
+            .prorate_shared_cost(0.5, ProrationStrategy::WriteOffAsOverhead)
+            .unwrap();
+        tracker.start_task("task-d", None, &[]).unwrap();
+        let summary = tracker.end_task("task-d").unwrap();
+        assert!((summary.prorated_overhead - 0.25).abs() < 1e-9);
+        assert!((overhead(&tracker)["task-d"] - 0.25).abs() < 1e-9);
+        assert!((tracker.get_written_off_overhead() - 0.5).abs() < 1e-9);
+
+        assert!(tracker
+            .prorate_shared_cost(-1.0, ProrationStrategy::WriteOffAsOverhead)
+            .is_err());
+
+        // Held and written-off cost survive a restart
+        tracker
+            .prorate_shared_cost(0.1, ProrationStrategy::AddToNextTask)
+            .unwrap();
+        let reloaded = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        reloaded.initialize().unwrap();
+        assert!((reloaded.get_written_off_overhead() - 0.5).abs() < 1e-9);
+        reloaded.start_task("task-e", None, &[]).unwrap();
+        let summary = reloaded.end_task("task-e").unwrap();
+        assert!((summary.prorated_overhead - 0.1).abs() < 1e-9);
+        let shares = overhead(&reloaded);
+        assert!((shares["task-a"] - 0.7).abs() < 1e-9);
+
+        // Shares are not logged as task costs
+        let mut cost_records = 0;
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |_| {
+            cost_records += 1;
+        })
+        .unwrap();
+        assert_eq!(cost_records, 6);
+    }
+
+    #[test]
+    fn failed_tasks_record_costs_and_failure_share() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-ok", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(3.0), Duration::ZERO).unwrap();
+        tracker.end_task("task-ok").unwrap();
+
+        tracker.start_task("task-api-error", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(1.0), Duration::ZERO).unwrap();
+        tracker.mark_task_failed("task-api-error", "provider returned 500").unwrap();
+
+        // Failed before spending anything
+        tracker.start_task("task-rejected", None, &[]).unwrap();
+        tracker.mark_task_failed("task-rejected", "quality rejection").unwrap();
+
+        assert!(tracker.mark_task_failed("task-rejected", "again").is_err());
+        assert!((tracker.get_balance() - 996.0).abs() < 1e-9);
+
+        let mut completions = Vec::new();
+        for_each_jsonl::<TaskCompletionRecord, _>(
+            &tmp.path().join("task_completions.jsonl"),
+            |record| completions.push(record),
+        )
+        .unwrap();
+        assert_eq!(completions.len(), 3);
+        let failed = completions.iter().find(|r| r.task_id == "task-api-error").unwrap();
+        assert_eq!(failed.status, TaskStatus::Failed);
+        assert_eq!(failed.abort_detail.as_deref(), Some("provider returned 500"));
+        assert!((failed.cost_summary.as_ref().unwrap().total - 1.0).abs() < 1e-9);
+        let raw = fs::read_to_string(tmp.path().join("task_completions.jsonl")).unwrap();
+        assert!(raw.contains(r#""status":"failed""#));
+
+        assert!((EconomicAnalytics::failure_rate(&completions) - 2.0 / 3.0).abs() < 1e-9);
+        assert!((EconomicAnalytics::failure_cost_percentage(&completions) - 25.0).abs() < 1e-9);
+        assert!(EconomicAnalytics::failure_rate(&[]).abs() < f64::EPSILON);
+        assert!(EconomicAnalytics::failure_cost_percentage(&completions[2..]).abs() < f64::EPSILON);
+
+        let analytics = tracker.get_analytics(None).unwrap();
+        assert_eq!(analytics.tasks_aborted, 2);
+        assert!((analytics.aborted_task_cost - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tasks_are_accepted_above_break_even_quality() {
+        let classifier = crate::economic::TaskClassifier::new();
+        let result = classifier.classify("Write a REST API in Rust with authentication");
+        assert!(result.max_payment > 0.0);
+
+        // Free tasks always break even
+        assert!(result.break_even_quality_score.abs() < f64::EPSILON);
+        assert!(EconomicTracker::should_accept_task(&result, 0.0));
+
+        let profitable = result.clone().with_expected_cost(result.max_payment * 0.3);
+        assert!((profitable.break_even_quality_score - 0.3).abs() < 1e-9);
+        assert!(EconomicTracker::should_accept_task(&profitable, 0.8));
+        assert!(!EconomicTracker::should_accept_task(&profitable, 0.2));
+
+        // No quality covers a cost above the maximum payment
+        let unprofitable = result.clone().with_expected_cost(result.max_payment * 2.0);
+        assert!((unprofitable.break_even_quality_score - 1.0).abs() < 1e-9);
+        assert!(!EconomicTracker::should_accept_task(&unprofitable, 0.99));
+    }
+
+    #[test]
+    fn monthly_cost_is_projected_from_session_rate() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.state.lock().session.started_at = Utc::now() - chrono::Duration::hours(2);
+        tracker.track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO).unwrap();
+        let projection = tracker.project_monthly_cost().unwrap();
+        assert!((projection.projection_basis_hours - 2.0).abs() < 1e-3);
+        assert!((projection.cost_per_hour - 2.0).abs() < 1e-3);
+        assert!((projection.projected_cost_usd - 1440.0).abs() < 1.0);
+        assert_eq!(projection.confidence, ProjectionConfidence::Medium);
+
+        tracker.state.lock().session.started_at = Utc::now() + chrono::Duration::minutes(1);
+        assert!(matches!(
+            tracker.project_monthly_cost().unwrap_err(),
+            EconomicError::EmptySession
+        ));
+    }
+
+    #[test]
+    fn models_are_ranked_by_total_spend() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_model_tokens("gpt-4o", 1000, 500, "agent", Some(2.0)).unwrap();
+        tracker.track_model_tokens("claude-haiku", 800, 200, "agent", Some(0.5)).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker.track_model_tokens("gpt-4o", 2000, 1000, "agent", Some(3.0)).unwrap();
+        tracker.track_model_tokens("o3", 500, 500, "agent", Some(4.0)).unwrap();
+
+        let ranking = tracker.get_model_cost_ranking().unwrap();
+        let names: Vec<&str> = ranking.iter().map(|e| e.model_name.as_str()).collect();
+        assert_eq!(names, ["gpt-4o", "o3", "claude-haiku"]);
+        assert_eq!(ranking[0].call_count, 2);
+        assert_eq!(ranking[0].total_input_tokens, 3000);
+        assert_eq!(ranking[0].total_output_tokens, 1500);
+        assert!((ranking[0].average_cost_per_call - 2.5).abs() < 1e-9);
+
+        let total: f64 = ranking.iter().map(|e| e.total_cost_usd).sum();
+        assert!((total - tracker.get_summary().session_cost).abs() < 1e-9);
+    }
+
+    #[test]
+    fn output_efficiency_ranks_models_by_tokens_per_dollar() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        assert!(tracker.get_cost_per_successful_output_token().unwrap().is_nan());
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_model_tokens("model-a", 1000, 4000, "agent", Some(1.0)).unwrap();
+        tracker.track_model_tokens("model-b", 1000, 1000, "agent", Some(2.0)).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker.track_model_tokens("model-a", 500, 2000, "agent", Some(1.0)).unwrap();
+        // A failed call costs money without producing output
+        tracker.track_model_tokens("model-b", 1000, 0, "agent", Some(1.0)).unwrap();
+
+        let cost_per_token = tracker.get_cost_per_successful_output_token().unwrap();
+        assert!((cost_per_token - 5.0 / 7000.0).abs() < 1e-12);
+        let per_dollar = tracker.total_output_tokens_per_dollar().unwrap();
+        assert!((per_dollar - 1400.0).abs() < 1e-9);
+
+        let by_model = tracker.get_output_efficiency_by_model().unwrap();
+        assert!((by_model["model-a"] - 3000.0).abs() < 1e-9);
+        assert!((by_model["model-b"] - 1000.0 / 3.0).abs() < 1e-9);
+        let mut ranking: Vec<(&String, &f64)> = by_model.iter().collect();
+        ranking.sort_by(|a, b| b.1.total_cmp(a.1));
+        assert_eq!(ranking[0].0, "model-a");
+    }
+
+    #[test]
+    fn spend_is_broken_down_by_provider() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_provider_tokens("openrouter", "gpt-4o", 1000, 500, "agent", Some(2.0))
+            .unwrap();
+        tracker
+            .track_provider_tokens("anthropic", "claude-sonnet", 2000, 100, "agent", Some(3.0))
+            .unwrap();
+        tracker
+            .track_provider_tokens("openrouter", "llama-70b", 500, 500, "agent", Some(1.5))
+            .unwrap();
+        tracker.track_provider_tokens("vllm", "llama-70b", 4000, 1000, "agent", Some(0.0)).unwrap();
+        // Recorded without a provider
+        tracker.track_model_tokens("gpt-4o", 100, 100, "agent", Some(0.5)).unwrap();
+        tracker.end_task("task-1").unwrap();
+
+        let analytics = tracker.get_analytics(None).unwrap();
+        let providers = analytics.cost_by_provider(..);
+        let names: Vec<&str> = providers.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["openrouter", "anthropic", "unknown", "vllm"]);
+        assert!((providers[0].cost - 3.5).abs() < 1e-9);
+        assert_eq!(providers[0].calls, 2);
+        assert_eq!(providers[0].input_tokens, 1500);
+        assert_eq!(providers[3].output_tokens, 1000);
+
+        let models = analytics.cost_by_model(..);
+        assert_eq!(models[0].name, "claude-sonnet");
+        assert!((models[1].cost - 2.5).abs() < 1e-9);
+        let tomorrow = Utc::now().date_naive().succ_opt().unwrap();
+        assert!(analytics.cost_by_provider(tomorrow..).is_empty());
+
+        let summary = tracker.get_summary();
+        let drivers = summary.cost_drivers.unwrap();
+        assert_eq!(drivers.by_provider[0].name, "openrouter");
+        assert_eq!(drivers.by_provider.len(), 3);
+    }
+
+    #[test]
+    fn spend_is_broken_down_by_session() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        let prompts = [("chat-a", 1_000_000), ("chat-b", 2_000_000), ("chat-a", 500_000)];
+        for (session, input) in prompts {
+            let ctx = TokenContext::new(PromptType::User).with_session_id(session);
+            tracker
+                .track_tokens_with_context(input, 0, "agent", None, ctx)
+                .unwrap();
+        }
+        tracker.track_tokens(1000, 1000, "agent", None, Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+
+        let mut calls = Vec::new();
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            calls.extend(record.llm_usage.calls_detail);
+        })
+        .unwrap();
+        assert_eq!(calls[0].session_id.as_deref(), Some("chat-a"));
+        assert_eq!(calls[3].session_id, None);
+
+        let analytics = tracker.get_analytics(None).unwrap();
+        let sessions = analytics.cost_by_session(..);
+        let names: Vec<&str> = sessions.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["chat-b", "chat-a", "none"]);
+        assert!((sessions[1].cost - 4.5).abs() < 1e-9);
+        assert_eq!(sessions[1].calls, 2);
+        // Splitting by session leaves the provider totals unchanged
+        assert_eq!(analytics.cost_by_provider(..)[0].calls, 4);
+    }
+
+    #[test]
+    fn token_context_is_kept_on_call_records() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        let system = TokenContext::new(PromptType::System);
+        let system = tracker
+            .track_tokens_with_context(1_000_000, 0, "agent", None, system)
+            .unwrap()
+            .cost;
+        let retry = TokenContext {
+            prompt_type: PromptType::Tool,
+            request_id: Some("req-7".to_string()),
+            retry_attempt: 2,
+            session_id: None,
+        };
+        let tool = tracker
+            .track_tokens_with_context(0, 100_000, "agent", Some("gpt-4o"), retry)
+            .unwrap()
+            .cost;
+        tracker.track_tokens(1000, 1000, "agent", None, Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+        assert!((system - 3.0).abs() < 1e-9);
+        assert!((tool - 1.5).abs() < 1e-9);
+
+        let mut records = Vec::new();
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            records.push(record);
+        })
+        .unwrap();
+        let usage = &records[0].llm_usage;
+        let call = &usage.calls_detail[1];
+        assert_eq!(call.model.as_deref(), Some("gpt-4o"));
+        assert_eq!(call.request_id.as_deref(), Some("req-7"));
+        assert_eq!(call.retry_attempt, 2);
+        assert_eq!(usage.calls_detail[2].prompt_type, None);
+
+        // Calls without a prompt type are left out of the breakdown
+        let by_type = usage.cost_by_prompt_type();
+        assert_eq!(by_type.len(), 2);
+        assert!((by_type[&PromptType::System] - 3.0).abs() < 1e-9);
+        assert!((by_type[&PromptType::Tool] - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn write_retries_back_off_on_io_errors_only() {
+        let io_error = || EconomicError::Io {
+            path: PathBuf::from("token_costs.jsonl"),
+            source: std::io::Error::other("disk full"),
+        };
+        let retry = RetryPolicy {
+            max_retries: 3,
+            retry_delay: Duration::from_millis(1),
+        };
+
+        // Two transient failures, then the write goes through
+        let mut attempts = 0;
+        let result = retry.run("test", || {
+            attempts += 1;
+            if attempts <= 2 {
+                Err(io_error())
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+
+        attempts = 0;
+        let result: Result<()> = retry.run("test", || {
+            attempts += 1;
+            Err(io_error())
+        });
+        assert!(matches!(result.unwrap_err(), EconomicError::Io { .. }));
+        assert_eq!(attempts, 4);
+
+        attempts = 0;
+        let result: Result<()> = retry.run("test", || {
+            attempts += 1;
+            Err(EconomicError::InvalidConfig("not transient".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn income_is_credited_once_when_log_writes_fail() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        let retry_delay = Duration::from_millis(1);
+
+        let payment = tracker
+            .add_work_income_with_retry(10.0, "task-1", 0.9, "", 2, retry_delay)
+            .unwrap();
+        assert!((payment - 10.0).abs() < 1e-9);
+
+        // Block the balance log so every write attempt fails
+        let balance_file = tmp.path().join("balance.jsonl");
+        fs::rename(&balance_file, tmp.path().join("balance.bak")).unwrap();
+        fs::create_dir(&balance_file).unwrap();
+        let err = tracker
+            .add_work_income_with_retry(5.0, "task-2", 0.9, "", 2, retry_delay)
+            .unwrap_err();
+        assert!(matches!(err, EconomicError::Io { .. }));
+        assert!((tracker.get_balance() - 1015.0).abs() < 1e-9);
+
+        let mut paid = Vec::new();
+        for_each_jsonl::<WorkIncomeRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            paid.push(record.task_id);
+        })
+        .unwrap();
+        assert_eq!(paid, ["task-1"]);
+
+        // The credited balance is persisted once the log is writable again
+        fs::remove_dir(&balance_file).unwrap();
+        tracker.flush().unwrap();
+        assert!(fs::read_to_string(&balance_file).unwrap().contains("1015"));
+    }
+
+    #[test]
+    fn income_is_logged_once_the_log_is_writable_again() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        // Block the balance log, and unblock it while the write is retried
+        let balance_file = tmp.path().join("balance.jsonl");
+        fs::rename(&balance_file, tmp.path().join("balance.bak")).unwrap();
+        fs::create_dir(&balance_file).unwrap();
+        let payment = std::thread::scope(|scope| {
+            scope.spawn(|| {
+                std::thread::sleep(Duration::from_millis(50));
+                fs::remove_dir(&balance_file).unwrap();
+            });
+            tracker.add_work_income_with_retry(
+                10.0,
+                "task-1",
+                0.9,
+                "",
+                8,
+                Duration::from_millis(10),
+            )
+        })
+        .unwrap();
+        assert!((payment - 10.0).abs() < 1e-9);
+        assert!((tracker.get_balance() - 1010.0).abs() < 1e-9);
+
+        let mut paid = Vec::new();
+        for_each_jsonl::<WorkIncomeRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            paid.push(record.task_id);
+        })
+        .unwrap();
+        assert_eq!(paid, ["task-1"]);
+        assert!(fs::read_to_string(&balance_file).unwrap().contains("1010"));
+    }
+
+    #[test]
+    fn task_stays_active_when_its_records_cannot_be_written() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_tokens(1000, 500, "agent", Some(1.0), Duration::ZERO)
+            .unwrap();
+
+        // Block the balance log so the checkpoint before the cost record fails
+        let balance_file = tmp.path().join("balance.jsonl");
+        fs::rename(&balance_file, tmp.path().join("balance.bak")).unwrap();
+        fs::create_dir(&balance_file).unwrap();
+        assert!(matches!(
+            tracker.end_task("task-1").unwrap_err(),
+            EconomicError::Io { .. }
+        ));
+        assert_eq!(tracker.active_task_ids(), ["task-1"]);
+
+        // The retried end logs the task exactly once
+        fs::remove_dir(&balance_file).unwrap();
+        let summary = tracker.end_task("task-1").unwrap();
+        assert!((summary.total - 1.0).abs() < 1e-9);
+        assert!(tracker.active_task_ids().is_empty());
+
+        let mut logged = Vec::new();
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            logged.push(record.task_id);
+        })
+        .unwrap();
+        assert_eq!(logged, ["task-1"]);
+    }
+
+    #[tokio::test]
+    async fn token_pricing_reloads_from_watched_file() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = Arc::new(EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        ));
+        tracker.initialize().unwrap();
+        assert!(matches!(
+            tracker.reload_pricing().unwrap_err(),
+            EconomicError::InvalidConfig(_)
+        ));
+
+        let path = tmp.path().join("pricing.json");
+        let write_prices = |input: &str, output: &str| {
+            let json = format!(
+                "{{\"input_price_per_million\": {input}, \"output_price_per_million\": {output}}}"
+            );
+            fs::write(&path, json).unwrap();
+        };
+        write_prices("1.0", "2.0");
+        let watcher = tracker
+            .watch_pricing_file(&path, Duration::from_millis(10))
+            .unwrap();
+        let cost = tracker
+            .track_tokens(1_000_000, 1_000_000, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert!((cost - 3.0).abs() < 1e-9);
+
+        // Invalid prices are rejected and the current ones kept
+        write_prices("-1.0", "2.0");
+        assert!(tracker.reload_pricing().is_err());
+        assert!((tracker.token_pricing().input_price_per_million - 1.0).abs() < 1e-9);
+
+        write_prices("4.0", "8.0");
+        for _ in 0..100 {
+            if tracker.token_pricing().input_price_per_million == 4.0 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let cost = tracker
+            .track_tokens(1_000_000, 1_000_000, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert!((cost - 12.0).abs() < 1e-9);
+        watcher.abort();
+    }
+
+    #[test]
+    fn session_duration_runs_from_initialization() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        let start = tracker.get_session_start();
+
+        let mut first: Option<BalanceRecord> = None;
+        for_each_jsonl::<BalanceRecord, _>(&tmp.path().join("balance.jsonl"), |record| {
+            first.get_or_insert(record);
+        })
+        .unwrap();
+        let recorded: SystemTime = first.unwrap().timestamp.unwrap().into();
+        let gap = recorded.duration_since(start).unwrap();
+        assert!(gap < Duration::from_secs(1));
+
+        let earlier = tracker.get_session_duration();
+        std::thread::sleep(Duration::from_millis(5));
+        let later = tracker.get_session_duration();
+        assert!(later >= earlier + Duration::from_millis(5));
+        assert_eq!(tracker.get_session_start(), start);
+    }
+
+    #[test]
+    fn range_summaries_filter_on_timestamps() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", None, Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.add_work_income(10.0, "task-1", 0.9, "done").unwrap();
+        tracker.add_grant_income(5.0, "grant-1", "seed").unwrap();
+
+        let mut call_time = None;
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            call_time = Some(record.llm_usage.calls_detail[0].timestamp);
+        })
+        .unwrap();
+        let call_time = call_time.unwrap();
+
+        // Start is inclusive, end exclusive
+        let after = call_time + chrono::Duration::hours(1);
+        let from_call = tracker.cost_summary(&DateRange::new(call_time, after)).unwrap();
+        assert_eq!(from_call.llm_calls, 1);
+        assert_eq!((from_call.input_tokens, from_call.output_tokens), (1000, 500));
+        assert!((from_call.total - 0.0105).abs() < 1e-9);
+        let before = call_time - chrono::Duration::hours(1);
+        let to_call = tracker.cost_summary(&DateRange::new(before, call_time)).unwrap();
+        assert_eq!(to_call.llm_calls, 0);
+
+        let now = DateRange::new(before, Utc::now() + chrono::Duration::hours(1));
+        let income = tracker.income_summary(&now).unwrap();
+        assert!((income.work_income - 10.0).abs() < 1e-9);
+        assert!((income.grant_income - 5.0).abs() < 1e-9);
+        assert!((income.total_income - 15.0).abs() < 1e-9);
+        assert_eq!((income.payments_awarded, income.payments_rejected), (1, 0));
+        let tasks = tracker.task_summary(&now).unwrap();
+        assert_eq!((tasks.tasks_ended, tasks.completed), (1, 1));
+        assert_eq!(tasks.tasks[0].task_id, "task-1");
+
+        // No data: valid, zeroed summaries
+        let day = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let empty = DateRange::days(day, day + chrono::Days::new(1), &Utc);
+        assert_eq!(
+            tracker.cost_summary(&empty).unwrap(),
+            RangeCostSummary::empty(empty)
+        );
+        assert_eq!(
+            tracker.income_summary(&empty).unwrap(),
+            RangeIncomeSummary::empty(empty)
+        );
+        let no_tasks = tracker.task_summary(&empty).unwrap();
+        assert_eq!((no_tasks.tasks_ended, no_tasks.total_cost), (0, 0.0));
+        assert!(no_tasks.tasks.is_empty());
+    }
+
+    #[cfg(feature = "archive-read")]
+    #[test]
+    fn range_queries_read_archived_months() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &["client:acme"]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", None, Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.add_work_income(10.0, "task-1", 0.9, "done").unwrap();
+        tracker.add_grant_income(5.0, "grant-1", "seed").unwrap();
+
+        // Rotate everything so far into monthly archives
+        let month = Utc::now().format("%Y-%m");
+        let archive_dir = tmp.path().join("archive");
+        fs::create_dir_all(&archive_dir).unwrap();
+        for stem in ["token_costs", "grant_income", "task_completions"] {
+            let live = tmp.path().join(format!("{stem}.jsonl"));
+            let archive = archive_dir.join(format!("{stem}.{month}.jsonl.gz"));
+            let mut encoder =
+                GzEncoder::new(File::create(&archive).unwrap(), Compression::default());
+            encoder.write_all(&fs::read(&live).unwrap()).unwrap();
+            encoder.finish().unwrap();
+            fs::write(&live, "").unwrap();
+        }
+        tracker.add_grant_income(2.0, "grant-2", "top-up").unwrap();
+
+        let now = Utc::now();
+        let range = DateRange::new(now - chrono::Duration::hours(1), now);
+        let costs = tracker.cost_summary(&range).unwrap();
+        assert_eq!(costs.llm_calls, 1);
+        assert!((costs.total - 0.0105).abs() < 1e-9);
+        let income = tracker.income_summary(&range).unwrap();
+        assert!((income.work_income - 10.0).abs() < 1e-9);
+        assert!((income.grant_income - 7.0).abs() < 1e-9);
+        assert_eq!(tracker.task_summary(&range).unwrap().tasks_ended, 1);
+
+        let analytics = tracker.get_analytics_for(&range, Some("client:acme")).unwrap();
+        assert_eq!((analytics.total_tasks, analytics.tasks_paid), (1, 1));
+        assert!((analytics.total_income - 10.0).abs() < 1e-9);
+        // Unbounded analytics only read the active files
+        assert_eq!(tracker.get_analytics(None).unwrap().total_tasks, 0);
+
+        // Ranges before the archived month do not open it
+        let day = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+        let old = DateRange::days(day, day + chrono::Days::new(1), &Utc);
+        assert_eq!(tracker.cost_summary(&old).unwrap(), RangeCostSummary::empty(old));
+    }
+
+    #[tokio::test]
+    async fn survival_status_stream_emits_only_changes() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        let changes = tracker.watch_survival_status();
+
+        tracker.track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(200.0), Duration::ZERO).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO).unwrap();
+        tracker.add_grant_income(100.0, "grant-1", "top-up").unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(880.0), Duration::ZERO).unwrap();
+        assert!(tracker.get_balance().abs() < 1e-9);
+
+        // The stream ends once the tracker is gone
+        drop(tracker);
+        let changes: Vec<SurvivalStatusChange> = changes.collect().await;
+        let moves: Vec<_> = changes
+            .iter()
+            .map(|change| (change.previous, change.current, change.triggering_event.as_str()))
+            .collect();
+        assert_eq!(
+            moves,
+            [
+                (SurvivalStatus::Thriving, SurvivalStatus::Stable, "tokens tracked"),
+                (SurvivalStatus::Stable, SurvivalStatus::Thriving, "grant income added"),
+                (SurvivalStatus::Thriving, SurvivalStatus::Bankrupt, "tokens tracked"),
+            ]
+        );
+        assert!(changes[0].timestamp <= changes[2].timestamp);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn cost_events_arrive_in_balance_order_from_concurrent_writers() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = Arc::new(EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        ));
+        tracker.initialize().unwrap();
+        let mut events = tracker.subscribe_cost_events();
+
+        let charges = tokio::spawn({
+            let tracker = Arc::clone(&tracker);
+            async move {
+                for _ in 0..3 {
+                    tracker
+                        .track_tokens(1000, 500, "agent", Some(100.0), Duration::ZERO)
+                        .unwrap();
+                }
+            }
+        });
+        let income = tokio::spawn({
+            let tracker = Arc::clone(&tracker);
+            async move {
+                tracker.add_work_income(50.0, "task-1", 0.9, "report").unwrap();
+            }
+        });
+
+        // Three charges and one payment, plus any status changes between them
+        let is_balance_event =
+            |event: &CostEvent| !matches!(event, CostEvent::StatusChanged { .. });
+        let mut received = Vec::new();
+        let timeout = tokio::time::sleep(Duration::from_secs(5));
+        tokio::pin!(timeout);
+        while received.iter().filter(|event| is_balance_event(event)).count() < 4 {
+            tokio::select! {
+                event = events.recv() => received.push(event.unwrap()),
+                () = &mut timeout => panic!("only received {received:?}"),
+            }
+        }
+        charges.await.unwrap();
+        income.await.unwrap();
+        while let Ok(event) = events.try_recv() {
+            received.push(event);
+        }
+
+        // Each event's balance follows from the one before it
+        let mut balance = 1000.0;
+        for event in &received {
+            match event {
+                CostEvent::TokensTracked { cost, balance: after, .. } => {
+                    balance -= cost;
+                    assert!((after - balance).abs() < 1e-9);
+                }
+                CostEvent::IncomeReceived { task_id, amount, balance: after } => {
+                    assert_eq!(task_id, "task-1");
+                    balance += amount;
+                    assert!((after - balance).abs() < 1e-9);
+                }
+                CostEvent::StatusChanged { .. } => {}
+            }
+        }
+        assert!((tracker.get_balance() - 750.0).abs() < 1e-9);
+        let last_status = received.iter().rev().find_map(|event| match event {
+            CostEvent::StatusChanged { current, .. } => Some(*current),
+            _ => None,
+        });
+        assert_eq!(last_status, Some(SurvivalStatus::Stable));
+    }
+
+    #[test]
+    fn status_callbacks_fire_on_each_change() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        let first = Arc::new(Mutex::new(Vec::new()));
+        let second = Arc::new(Mutex::new(Vec::new()));
+        for seen in [&first, &second] {
+            let seen = Arc::clone(seen);
+            tracker.on_status_change(Box::new(move |previous, current| {
+                seen.lock().push((previous, current));
+            }));
+        }
+
+        tracker.track_tokens(1000, 500, "agent", Some(250.0), Duration::ZERO).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(400.0), Duration::ZERO).unwrap();
+        let expected = [
+            (SurvivalStatus::Thriving, SurvivalStatus::Stable),
+            (SurvivalStatus::Stable, SurvivalStatus::Struggling),
+        ];
+        assert_eq!(*first.lock(), expected);
+        assert_eq!(*second.lock(), expected);
+
+        tracker.clear_status_callbacks();
+        tracker.track_tokens(1000, 500, "agent", Some(300.0), Duration::ZERO).unwrap();
+        assert_eq!(first.lock().len(), 2);
+    }
+
+    #[test]
+    fn integrity_chain_flags_hand_edits() {
+        let tmp = TempDir::new().unwrap();
+        let token_costs = tmp.path().join("token_costs.jsonl");
+        // A legacy record written before integrity was turned on
+        {
+            let tracker = EconomicTracker::new(
+                "test-agent",
+                test_config(),
+                Some(tmp.path().to_path_buf()),
+            );
+            tracker.initialize().unwrap();
+            tracker.add_grant_income(5.0, "grant-0", "seed").unwrap();
+        }
+
+        let config = EconomicConfig {
+            record_integrity: true,
+            ..test_config()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        for task in ["task-1", "task-2"] {
+            tracker.start_task(task, None, &[]).unwrap();
+            tracker.track_tokens(1000, 500, "agent", None, Duration::ZERO).unwrap();
+            tracker.end_task(task).unwrap();
+            tracker.add_work_income(10.0, task, 0.9, "done").unwrap();
+        }
+        tracker.add_grant_income(5.0, "grant-1", "top-up").unwrap();
+
+        let report = tracker.verify_integrity().unwrap();
+        assert!(report.is_intact(), "{report:?}");
+        let grants = report
+            .files
+            .iter()
+            .find(|file| file.path.ends_with("grant_income.jsonl"))
+            .unwrap();
+        assert_eq!((grants.legacy_records, grants.sealed_records), (1, 1));
+
+        // Lower the cost of the second task by hand
+        let content = fs::read_to_string(&token_costs).unwrap();
+        let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+        let edited = lines
+            .iter()
+            .position(|line| {
+                line.contains(r#""task_id":"task-2""#) && line.contains(r#""input_tokens":1000"#)
+            })
+            .unwrap();
+        lines[edited] = lines[edited].replacen(r#""input_tokens":1000"#, r#""input_tokens":10"#, 1);
+        fs::write(&token_costs, lines.join("\n") + "\n").unwrap();
+
+        let report = tracker.verify_integrity().unwrap();
+        assert!(!report.is_intact());
+        let (path, link) = report.first_break().unwrap();
+        assert!(path.ends_with("token_costs.jsonl"));
+        assert_eq!(link.line, edited + 1);
+        assert_eq!(link.kind, BrokenLinkKind::HashMismatch);
+    }
+
+    #[test]
+    fn task_ends_do_not_reseal_edited_completions() {
+        let tmp = TempDir::new().unwrap();
+        let completions = tmp.path().join("task_completions.jsonl");
+        let config = EconomicConfig {
+            record_integrity: true,
+            ..test_config()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.end_task("task-1").unwrap();
+
+        let edited = fs::read_to_string(&completions).unwrap().replacen(
+            r#""work_submitted":true"#,
+            r#""work_submitted":false"#,
+            1,
+        );
+        fs::write(&completions, &edited).unwrap();
+
+        tracker.start_task("task-2", None, &[]).unwrap();
+        assert!(matches!(
+            tracker.end_task("task-2"),
+            Err(EconomicError::IntegrityBroken { line: 1, .. })
+        ));
+        assert_eq!(fs::read_to_string(&completions).unwrap(), edited);
+        assert!(!tracker.verify_integrity().unwrap().is_intact());
+    }
+
+    #[test]
+    fn latency_percentiles_use_nearest_rank() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        // Latencies 1..=101 ms in scrambled order, plus one unmeasured call
+        for n in 0..101_u64 {
+            let latency = Duration::from_millis(n * 37 % 101 + 1);
+            tracker.track_tokens(10, 10, "agent", None, latency).unwrap();
+        }
+        tracker.track_tokens(10, 10, "agent", None, Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+
+        let mut usage = None;
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            usage = Some(record.llm_usage);
+        })
+        .unwrap();
+        let mut usage = usage.unwrap();
+        assert_eq!(usage.calls_detail.len(), 102);
+        // Ranks ceil(p * 101 / 100): 51, 96, and 100
+        assert_eq!(usage.p50_latency_ms(), Some(51));
+        assert_eq!(usage.p95_latency_ms(), Some(96));
+        assert_eq!(usage.p99_latency_ms(), Some(100));
+
+        // With 100 measured calls p99 is exactly the 99th value
+        usage.calls_detail.retain(|call| call.latency_ms != 101);
+        assert_eq!(usage.p99_latency_ms(), Some(99));
+        assert_eq!(usage.p50_latency_ms(), Some(50));
+
+        usage.calls_detail.retain(|call| call.latency_ms == 0);
+        assert_eq!(usage.p50_latency_ms(), None);
+    }
+
+    #[test]
+    fn metadata_is_persisted_and_filterable() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        let metadata = BTreeMap::from([
+            ("client".to_string(), "acme".to_string()),
+            ("ticket".to_string(), "OPS-42".to_string()),
+        ]);
+        tracker
+            .start_task_with_metadata("task-1", None, &[], metadata.clone())
+            .unwrap();
+        tracker.track_tokens(1000, 500, "agent", None, Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker.end_task("task-2").unwrap();
+        tracker
+            .add_work_income_with_metadata(10.0, "task-1", 0.9, "done", metadata.clone())
+            .unwrap();
+
+        let mut costs = Vec::new();
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            costs.push(record);
+        })
+        .unwrap();
+        assert_eq!(costs.len(), 2);
+        assert_eq!(costs[0].metadata, metadata);
+        assert!(costs[1].metadata.is_empty());
+        let mut incomes = Vec::new();
+        for_each_jsonl::<WorkIncomeRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            incomes.push(record);
+        })
+        .unwrap();
+        assert_eq!(incomes.len(), 1);
+        assert_eq!(incomes[0].metadata, metadata);
+        let mut completions = Vec::new();
+        for_each_jsonl::<TaskCompletionRecord, _>(
+            &tmp.path().join("task_completions.jsonl"),
+            |record| completions.push(record),
+        )
+        .unwrap();
+        assert_eq!(completions[0].metadata, metadata);
+
+        let analytics = tracker.get_analytics(None).unwrap();
+        let matching = analytics.filter_by_metadata("client", "acme", ..);
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].task_id, "task-1");
+        assert!(analytics.filter_by_metadata("client", "globex", ..).is_empty());
+
+        let too_many: BTreeMap<String, String> = (0..=MAX_METADATA_KEYS)
+            .map(|i| (format!("k{i}"), String::new()))
+            .collect();
+        assert!(matches!(
+            tracker.start_task_with_metadata("task-3", None, &[], too_many),
+            Err(EconomicError::MetadataTooLarge { keys: 65, .. })
+        ));
+        let oversize = BTreeMap::from([("note".to_string(), "x".repeat(MAX_METADATA_BYTES))]);
+        assert!(matches!(
+            tracker.add_work_income_with_metadata(1.0, "task-2", 0.9, "", oversize),
+            Err(EconomicError::MetadataTooLarge { keys: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn transfer_balance_moves_funds_atomically() {
+        let tmp = TempDir::new().unwrap();
+        let payer = EconomicTracker::new(
+            "payer",
+            test_config(),
+            Some(tmp.path().join("payer")),
+        );
+        let payee = EconomicTracker::new(
+            "payee",
+            test_config(),
+            Some(tmp.path().join("payee")),
+        );
+        payer.initialize().unwrap();
+        payee.initialize().unwrap();
+
+        EconomicTracker::transfer_balance(&payer, &payee, 250.0, "sub-task: summarize")
+            .unwrap();
+        assert!((payer.get_balance() - 750.0).abs() < 1e-9);
+        assert!((payee.get_balance() - 1250.0).abs() < 1e-9);
+
+        let read = |dir: &str| {
+            let mut records = Vec::new();
+            let path = tmp.path().join(dir).join("transfers.jsonl");
+            for_each_jsonl::<TransferRecord, _>(&path, |record| records.push(record)).unwrap();
+            records
+        };
+        let (sent, received) = (read("payer"), read("payee"));
+        assert_eq!(sent.len(), 1);
+        assert_eq!(received.len(), 1);
+        assert_eq!(sent[0].transfer_id, received[0].transfer_id);
+        assert_eq!(sent[0].direction, TransferDirection::Sent);
+        assert_eq!(sent[0].counterparty, "payee");
+        assert_eq!(received[0].direction, TransferDirection::Received);
+        assert!((received[0].balance_after - 1250.0).abs() < 1e-9);
+
+        // Paying out the whole balance would bankrupt the payer
+        let err = EconomicTracker::transfer_balance(&payer, &payee, 750.0, "too much")
+            .unwrap_err();
+        assert!(matches!(err, EconomicError::InsufficientFunds { .. }));
+        assert!(matches!(
+            EconomicTracker::transfer_balance(&payer, &payer, 1.0, "self"),
+            Err(EconomicError::SelfTransfer { .. })
+        ));
+        assert!(matches!(
+            EconomicTracker::transfer_balance(&payer, &payee, -1.0, "negative"),
+            Err(EconomicError::InvalidAmount { .. })
+        ));
+        assert!((payer.get_balance() - 750.0).abs() < 1e-9);
+        assert!((payee.get_balance() - 1250.0).abs() < 1e-9);
+        assert_eq!(read("payer").len(), 1);
+        assert_eq!(read("payee").len(), 1);
+
+        // Transfers are part of the balance history
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        assert!((payee.balance_at(later).unwrap() - 1250.0).abs() < 1e-9);
+
+        // A side that cannot log the transfer does not stop the other
+        let payer_log = tmp.path().join("payer").join("transfers.jsonl");
+        fs::rename(&payer_log, tmp.path().join("payer").join("transfers.bak")).unwrap();
+        fs::create_dir(&payer_log).unwrap();
+        let failures = match EconomicTracker::transfer_balance(&payer, &payee, 50.0, "retry") {
+            Err(EconomicError::TransferNotLogged { failures, .. }) => failures,
+            other => panic!("unexpected result: {other:?}"),
+        };
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, "payer");
+        assert_eq!(read("payee").len(), 2);
+        assert!((payer.get_balance() - 700.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn model_pricing_prices_calls_that_name_a_model() {
+        use crate::config::schema::ModelPricing;
+
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        let prices = HashMap::from([(
+            "openai/gpt-4o".to_string(),
+            ModelPricing {
+                input: 5.0,
+                output: 20.0,
+                ..Default::default()
+            },
+        )]);
+        tracker.set_model_pricing(Arc::new(SharedPricing::fixed(prices)));
+
+        let cost = tracker
+            .track_provider_tokens("openai", "gpt-4o-2024-05-13", 1_000_000, 0, "agent", None)
+            .unwrap()
+            .cost;
+        assert!((cost - 5.0).abs() < 1e-9);
+        // Unknown models get the resolver defaults, like in CostObserver
+        let cost = tracker
+            .track_model_tokens("mystery-model", 0, 1_000_000, "agent", None)
+            .unwrap()
+            .cost;
+        assert!((cost - 15.0).abs() < 1e-9);
+        // Calls without a model keep the tracker's token pricing
+        let cost = tracker
+            .track_tokens(1_000_000, 0, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert!((cost - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn tasks_over_budget_compare_cost_to_the_estimate() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        let spend = |task_id: &str, budget: Option<f64>, cost: f64| {
+            tracker
+                .start_task_with_budget(task_id, None, &[], budget)
+                .unwrap();
+            tracker
+                .track_tokens(1000, 500, "agent", Some(cost), Duration::ZERO)
+                .unwrap();
+        };
+
+        spend("no-budget", None, 5.0);
+        tracker.end_task("no-budget").unwrap();
+        spend("within", Some(2.0), 1.5);
+        tracker.end_task("within").unwrap();
+        spend("over", Some(2.0), 3.0);
+        tracker.end_task("over").unwrap();
+        // Still running, already over
+        spend("active", Some(0.5), 1.5);
+
+        let over = tracker.get_tasks_over_budget().unwrap();
+        let ids: Vec<&str> = over.iter().map(|task| task.task_id.as_str()).collect();
+        assert_eq!(ids, ["active", "over"]);
+        assert!((over[1].estimated_cost - 2.0).abs() < 1e-9);
+        assert!((over[1].actual_cost - 3.0).abs() < 1e-9);
+        assert!((over[1].overage - 1.0).abs() < 1e-9);
+        assert!((over[1].overage_pct - 50.0).abs() < 1e-9);
+        assert!((over[0].overage_pct - 200.0).abs() < 1e-9);
+
+        assert!(matches!(
+            tracker.start_task_with_budget("bad", None, &[], Some(0.0)),
+            Err(EconomicError::InvalidAmount { .. })
+        ));
+    }
+
+    #[test]
+    fn working_capital_nets_escrow_tasks_and_subscriptions() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        let capital = tracker.get_working_capital();
+        assert!((capital.net_working_capital - 1000.0).abs() < 1e-9);
+        assert_eq!(capital.status, SurvivalStatus::Thriving);
+
+        // Income held in escrow is expected to arrive
+        tracker.state.lock().escrow.insert("held".into(), 50.0);
+        let capital = tracker.get_working_capital();
+        assert!((capital.pending_escrow - 50.0).abs() < 1e-9);
+        assert!((capital.net_working_capital - 1050.0).abs() < 1e-9);
+
+        // Only the unspent part of a budget counts; 100 of 400 is spent
+        tracker
+            .start_task_with_budget("budgeted", None, &[], Some(400.0))
+            .unwrap();
+        tracker
+            .track_tokens(1000, 500, "agent", Some(100.0), Duration::ZERO)
+            .unwrap();
+        tracker.start_task("unbudgeted", None, &[]).unwrap();
+        let capital = tracker.get_working_capital();
+        assert!((capital.current_balance - 900.0).abs() < 1e-9);
+        assert!((capital.estimated_active_task_cost - 300.0).abs() < 1e-9);
+        assert!((capital.net_working_capital - 650.0).abs() < 1e-9);
+
+        // Subscriptions due after the next 24 hours are left out
+        let now = Utc::now();
+        tracker
+            .add_subscription("hosting", 200.0, now + chrono::Duration::hours(3))
+            .unwrap();
+        tracker
+            .add_subscription("overdue", 100.0, now - chrono::Duration::hours(1))
+            .unwrap();
+        tracker
+            .add_subscription("monthly", 500.0, now + chrono::Duration::days(20))
+            .unwrap();
+        let capital = tracker.get_working_capital();
+        assert!((capital.upcoming_subscriptions - 300.0).abs() < 1e-9);
+        assert!((capital.net_working_capital - 350.0).abs() < 1e-9);
+        assert_eq!(capital.status, SurvivalStatus::Struggling);
+
+        assert!(tracker.remove_subscription("hosting"));
+        assert!(!tracker.remove_subscription("hosting"));
+        assert!((tracker.get_working_capital().net_working_capital - 550.0).abs() < 1e-9);
+        assert!(matches!(
+            tracker.add_subscription("free", 0.0, now),
+            Err(EconomicError::InvalidAmount { .. })
+        ));
+    }
+
+    #[test]
+    fn can_afford_reports_the_tightest_limit() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            initial_balance: 10.0,
+            reserve_floor: Some(4.0),
+            daily_spend_limit: Some(8.0),
+            max_cost_per_task: Some(3.0),
+            ..test_config()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        let estimate = |cost_usd: f64| CostEstimate {
+            model: "gpt-4o".to_string(),
+            input_tokens: 1000,
+            expected_output_tokens: 500,
+            input_price_per_million: 3.0,
+            output_price_per_million: 15.0,
+            default_pricing: false,
+            cost_usd,
+        };
+
+        // Outside a task only the reserve floor and the daily limit apply
+        let decision = tracker.can_afford(&estimate(5.0));
+        assert!(decision.is_affordable());
+        assert!((decision.headroom_usd() - 6.0).abs() < 1e-9);
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_tokens(1000, 500, "agent", Some(1.0), Duration::ZERO)
+            .unwrap();
+        match tracker.can_afford(&estimate(2.5)) {
+            AffordabilityDecision::Unaffordable {
+                limit,
+                headroom_usd,
+            } => {
+                assert_eq!(limit, SpendingLimit::TaskBudget);
+                assert!((headroom_usd - 2.0).abs() < 1e-9);
+            }
+            other @ AffordabilityDecision::Affordable { .. } => {
+                panic!("expected the task budget to bind, got {other:?}")
+            }
+        }
+        assert!(tracker.can_afford(&estimate(2.0)).is_affordable());
+        tracker.end_task("task-1").unwrap();
+
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker
+            .track_tokens(1000, 500, "agent", Some(2.5), Duration::ZERO)
+            .unwrap();
+        assert_eq!(
+            tracker.can_afford(&estimate(1.0)),
+            AffordabilityDecision::Unaffordable {
+                limit: SpendingLimit::TaskBudget,
+                headroom_usd: 0.5,
+            }
+        );
+        tracker.end_task("task-2").unwrap();
+        // $6.50 left, $2.50 above the reserve floor
+        assert_eq!(
+            tracker.can_afford(&estimate(3.0)),
+            AffordabilityDecision::Unaffordable {
+                limit: SpendingLimit::ReserveFloor,
+                headroom_usd: 2.5,
+            }
+        );
+    }
+
+    #[test]
+    fn cost_sensitivity_scales_repriced_models() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_model_tokens("gpt-4o", 1000, 500, "agent", Some(9.9))
+            .unwrap();
+        tracker
+            .track_model_tokens("claude-sonnet", 1000, 500, "agent", Some(0.05))
+            .unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker
+            .track_model_tokens("claude-sonnet", 1000, 500, "agent", Some(0.05))
+            .unwrap();
+
+        // 10% over the configured $3.00 input price on the dominant model
+        let report = tracker
+            .cost_sensitivity_analysis(HashMap::from([("gpt-4o".to_string(), 3.3)]))
+            .unwrap();
+        assert!((report.current_total_cost - 10.0).abs() < 1e-9);
+        assert!((report.revised_total_cost - 10.99).abs() < 1e-9);
+        assert!((report.delta_usd - 0.99).abs() < 1e-9);
+        assert!((report.delta_pct - 9.9).abs() < 1e-9);
+        assert_eq!(report.per_model_impact.len(), 1);
+        assert_eq!(report.per_model_impact[0].0, "gpt-4o");
+
+        let report = tracker
+            .cost_sensitivity_analysis(HashMap::from([
+                ("gpt-4o".to_string(), 3.3),
+                ("claude-sonnet".to_string(), 1.5),
+                ("unused".to_string(), 1.0),
+            ]))
+            .unwrap();
+        assert!((report.delta_usd - 0.94).abs() < 1e-9);
+        let models: Vec<&str> = report.per_model_impact.iter().map(|(m, _)| m.as_str()).collect();
+        assert_eq!(models, ["gpt-4o", "claude-sonnet", "unused"]);
+        assert!((report.per_model_impact[1].1 + 0.05).abs() < 1e-9);
+
+        let unchanged = tracker.cost_sensitivity_analysis(HashMap::new()).unwrap();
+        assert!(unchanged.delta_usd.abs() < f64::EPSILON);
+        assert!(matches!(
+            tracker.cost_sensitivity_analysis(HashMap::from([("gpt-4o".to_string(), -1.0)])),
+            Err(EconomicError::InvalidAmount { .. })
+        ));
+    }
+
+    #[test]
+    fn context_utilization_averages_input_over_window() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker.set_model_context_window("claude-sonnet", 100_000);
+        tracker.set_model_context_window("gpt-4o", 128_000);
+        tracker.set_model_context_window("unused", 8_000);
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_model_tokens("claude-sonnet", 30_000, 500, "agent", None)
+            .unwrap();
+        tracker
+            .track_model_tokens("gpt-4o", 6_400, 500, "agent", None)
+            .unwrap();
+        tracker
+            .track_model_tokens("mystery", 90_000, 500, "agent", None)
+            .unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker
+            .track_model_tokens("gpt-4o", 19_200, 500, "agent", None)
+            .unwrap();
+
+        let utilization = tracker.get_context_utilization().unwrap();
+        assert_eq!(utilization.len(), 2);
+        assert!((utilization["claude-sonnet"] - 0.3).abs() < 1e-9);
+        assert!((utilization["gpt-4o"] - 0.1).abs() < 1e-9);
+
+        tracker.set_model_context_window("gpt-4o", 0);
+        let utilization = tracker.get_context_utilization().unwrap();
+        assert!(!utilization.contains_key("gpt-4o"));
+    }
+
+    #[test]
+    fn pricing_simulation_replays_calls_at_alternative_prices() {
+        let tmp = TempDir::new().unwrap();
+        let tracker =
+            EconomicTracker::new("test-agent", test_config(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker
+            .track_tokens(1_000_000, 0, "agent", Some(3.0), Duration::ZERO)
+            .unwrap();
+        tracker
+            .track_tokens(0, 1_000_000, "agent", Some(15.0), Duration::ZERO)
+            .unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+        tracker
+            .track_tokens(2_000_000, 0, "agent", Some(6.0), Duration::ZERO)
+            .unwrap();
+
+        // Cheaper input, dearer output: ahead, behind, then ahead for good
+        let cheaper_input = TokenPricing {
+            input_price_per_million: 1.0,
+            output_price_per_million: 20.0,
+        };
+        let result = tracker.simulate_pricing_model(&cheaper_input).unwrap();
+        assert!((result.current_total_cost - 24.0).abs() < 1e-9);
+        assert!((result.alternative_total_cost - 23.0).abs() < 1e-9);
+        assert!((result.savings_usd - 1.0).abs() < 1e-9);
+        assert!((result.savings_pct - 100.0 / 24.0).abs() < 1e-9);
+        assert_eq!(result.breakeven_at_tokens, Some(4_000_000));
+        assert_eq!(result.per_task_comparison.len(), 2);
+        let (task_id, current, alternative) = &result.per_task_comparison[0];
+        assert_eq!(task_id, "task-1");
+        assert!((current - 18.0).abs() < 1e-9);
+        assert!((alternative - 21.0).abs() < 1e-9);
+        assert_eq!(result.per_task_comparison[1].0, "task-2");
+
+        let dearer = TokenPricing {
+            input_price_per_million: 10.0,
+            output_price_per_million: 10.0,
+        };
+        let result = tracker.simulate_pricing_model(&dearer).unwrap();
+        assert!((result.savings_usd + 16.0).abs() < 1e-9);
+        assert_eq!(result.breakeven_at_tokens, None);
+
+        let invalid = TokenPricing {
+            input_price_per_million: f64::NAN,
+            output_price_per_million: 1.0,
+        };
+        assert!(matches!(
+            tracker.simulate_pricing_model(&invalid),
+            Err(EconomicError::InvalidAmount { .. })
+        ));
+    }
+
+    #[test]
+    fn cost_anomalies_flag_outlier_tasks() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(tracker.detect_cost_anomalies(2.0).unwrap().is_empty());
+        assert!(matches!(
+            tracker.detect_cost_anomalies(0.0),
+            Err(EconomicError::InvalidSensitivity { .. })
+        ));
+
+        for i in 0..9 {
+            let task_id = format!("task-{i}");
+            tracker.start_task(&task_id, None, &[]).unwrap();
+            tracker.track_tokens(1000, 0, "agent", Some(0.01), Duration::ZERO).unwrap();
+            tracker.end_task(&task_id).unwrap();
+        }
+        tracker.start_task("runaway", None, &[]).unwrap();
+        tracker.track_tokens(1000, 0, "agent", Some(1.0), Duration::ZERO).unwrap();
+        tracker.end_task("runaway").unwrap();
+
+        // Nine tasks at $0.01 and one at $1.00 put the outlier sqrt(9)
+        // standard deviations above the mean
+        let anomalies = tracker.detect_cost_anomalies(2.5).unwrap();
+        assert_eq!(anomalies.len(), 1);
+        let anomaly = &anomalies[0];
+        assert_eq!(anomaly.task_id, "runaway");
+        assert!((anomaly.cost_usd - 1.0).abs() < 1e-9);
+        assert!((anomaly.z_score - 3.0).abs() < 1e-9);
+        assert_eq!(anomaly.severity, AnomalySeverity::Elevated);
+        let mut runaway_end = None;
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            if record.task_id == "runaway" {
+                runaway_end = Some(record.timestamp_end);
+            }
+        })
+        .unwrap();
+        assert_eq!(anomaly.timestamp, SystemTime::from(runaway_end.unwrap()));
+
+        assert_eq!(
+            tracker.detect_cost_anomalies(1.0).unwrap()[0].severity,
+            AnomalySeverity::Critical
+        );
+        assert!(tracker.detect_cost_anomalies(3.5).unwrap().is_empty());
+    }
+
+    #[test]
+    fn income_goal_progress_counts_income_since_it_was_set() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(matches!(
+            tracker.get_goal_progress(),
+            Err(EconomicError::NoIncomeGoal)
+        ));
+
+        let week = SystemTime::now() + Duration::from_secs(7 * 24 * 3600);
+        let goal = |target_usd| IncomeGoal {
+            target_usd,
+            deadline: week,
+            description: "earn this week".into(),
+        };
+        assert!(matches!(
+            tracker.set_income_goal(IncomeGoal {
+                deadline: SystemTime::now() - Duration::from_secs(60),
+                ..goal(500.0)
+            }),
+            Err(EconomicError::DeadlinePassed { .. })
+        ));
+
+        // Income from before the goal does not count
+        tracker.add_work_income(50.0, "task-0", 0.9, "").unwrap();
+        tracker.set_income_goal(goal(500.0)).unwrap();
+        let progress = tracker.get_goal_progress().unwrap();
+        assert_eq!(progress.earned_usd, 0.0);
+        assert!(!progress.is_on_track);
+        assert!((progress.projected_shortfall - 500.0).abs() < 1e-9);
+
+        // $100 within moments of a week-long goal extrapolates far past it
+        tracker.add_work_income(80.0, "task-1", 0.9, "").unwrap();
+        tracker.add_grant_income(20.0, "grant-1", "").unwrap();
+        let progress = tracker.get_goal_progress().unwrap();
+        assert!((progress.earned_usd - 100.0).abs() < 1e-9);
+        assert!((progress.remaining_usd - 400.0).abs() < 1e-9);
+        assert!((progress.percent_complete - 20.0).abs() < 1e-9);
+        assert!(progress.is_on_track);
+
+        // A new goal replaces the old one and starts from zero
+        tracker.set_income_goal(goal(1000.0)).unwrap();
+        let progress = tracker.get_goal_progress().unwrap();
+        assert_eq!(progress.target_usd, 1000.0);
+        assert_eq!(progress.earned_usd, 0.0);
+    }
+
+    #[test]
+    fn income_quality_correlation_and_regression() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.add_work_income(2.0, "task-1", 0.6, "").unwrap();
+        tracker.add_work_income(1.0, "task-2", 0.7, "").unwrap();
+        assert!(matches!(
+            tracker.get_income_quality_correlation(),
+            Err(EconomicError::NotEnoughIncomeRecords { found: 2, .. })
+        ));
+
+        // Deviations from the means (0.75, 2.5) give r = 0.3 / sqrt(0.05 * 5)
+        tracker.add_work_income(4.0, "task-3", 0.8, "").unwrap();
+        tracker.add_work_income(3.0, "task-4", 0.9, "").unwrap();
+        let r = tracker.get_income_quality_correlation().unwrap();
+        assert!((r - 0.6).abs() < 1e-9);
+        let (slope, intercept) = tracker.get_income_quality_regression().unwrap();
+        assert!((slope - 6.0).abs() < 1e-9);
+        assert!((intercept + 2.0).abs() < 1e-9);
+
+        // Payment proportional to quality is a perfect fit
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        for (i, score) in [0.6, 0.7, 0.8, 0.9, 1.0].into_iter().enumerate() {
+            tracker
+                .add_work_income(10.0 * score, format!("task-{i}"), score, "")
+                .unwrap();
+        }
+        assert!((tracker.get_income_quality_correlation().unwrap() - 1.0).abs() < 1e-9);
+        let (slope, intercept) = tracker.get_income_quality_regression().unwrap();
+        assert!((slope - 10.0).abs() < 1e-9);
+        assert!(intercept.abs() < 1e-9);
+    }
+
+    #[test]
+    fn income_retention_counts_withheld_payments() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(tracker.get_income_retention_rate().unwrap().is_nan());
+
+        tracker.register_income_validator(Box::new(|candidate| {
+            if candidate.description.contains("unreviewed") {
+                ValidationResult::Rejected("not reviewed".to_string())
+            } else {
+                ValidationResult::Approved
+            }
+        }));
+        tracker.add_work_income(10.0, "task-1", 0.9, "").unwrap();
+        tracker.add_work_income(10.0, "task-2", 0.3, "").unwrap();
+        tracker.add_work_income(10.0, "task-3", 0.8, "").unwrap();
+        tracker.add_work_income(10.0, "task-4", 0.9, "unreviewed").unwrap();
+        tracker.add_work_income(10.0, "task-5", 0.7, "").unwrap();
+
+        assert!((tracker.get_income_retention_rate().unwrap() - 0.6).abs() < 1e-9);
+        assert!((tracker.get_rejected_income_total().unwrap() - 20.0).abs() < 1e-9);
+
+        let mut rejected = Vec::new();
+        for_each_jsonl::<WorkIncomeRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            if record.was_rejected {
+                rejected.push(record.task_id);
+            }
+        })
+        .unwrap();
+        assert_eq!(rejected, ["task-2", "task-4"]);
+    }
+
+    #[test]
+    fn break_even_divides_overhead_by_average_task_profit() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            fixed_overhead_usd: 20.0,
+            ..test_config()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        assert!(matches!(
+            tracker.get_break_even_task_count(),
+            Err(EconomicError::NoCompletedTasks)
+        ));
+
+        // $5 paid against $1 of cost: $4 profit per task
+        let run_task = |task_id: &str| {
+            tracker.start_task(task_id, None, &[]).unwrap();
+            tracker
+                .track_tokens(1000, 500, "agent", Some(1.0), Duration::ZERO)
+                .unwrap();
+            tracker.end_task(task_id).unwrap();
+            tracker.add_work_income(5.0, task_id, 0.9, "").unwrap();
+        };
+        for task_id in ["task-1", "task-2", "task-3"] {
+            run_task(task_id);
+        }
+        let analysis = tracker.get_break_even_task_count().unwrap();
+        assert!((analysis.average_task_profit - 4.0).abs() < 1e-9);
+        assert_eq!(analysis.fixed_overhead, 20.0);
+        assert_eq!(analysis.break_even_tasks, 5);
+        assert_eq!(analysis.current_task_count, 3);
+        assert!(!analysis.is_profitable);
+
+        run_task("task-4");
+        run_task("task-5");
+        let analysis = tracker.get_break_even_task_count().unwrap();
+        assert_eq!(analysis.current_task_count, 5);
+        assert!(analysis.is_profitable);
+
+        // Unpaid work loses money on average
+        for task_id in ["task-6", "task-7", "task-8", "task-9", "task-10"] {
+            tracker.start_task(task_id, None, &[]).unwrap();
+            tracker
+                .track_tokens(1000, 500, "agent", Some(5.0), Duration::ZERO)
+                .unwrap();
+            tracker.end_task(task_id).unwrap();
+        }
+        assert!(matches!(
+            tracker.get_break_even_task_count(),
+            Err(EconomicError::BreakEvenUnreachable { average_task_profit })
+                if (average_task_profit + 0.5).abs() < 1e-9
+        ));
+    }
+
+    #[test]
+    fn batched_writes_are_read_through_and_flushed() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            persistence: PersistencePolicy {
+                max_buffered_records: 100,
+                max_buffered_bytes: 0,
+                flush_interval_ms: 0,
+            },
+            ..test_config()
+        };
+        let tracker =
+            EconomicTracker::new("test-agent", config.clone(), Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        tracker.add_work_income(10.0, "task-1", 0.9, "").unwrap();
+        tracker.add_grant_income(5.0, "grant-1", "").unwrap();
+
+        // Nothing is on disk yet, but reads see the buffered records
+        let costs = tmp.path().join("token_costs.jsonl");
+        assert!(!costs.exists());
+        assert!(matches!(
+            tracker.add_work_income(10.0, "task-1", 0.9, ""),
+            Err(EconomicError::DuplicateIncome { .. })
+        ));
+        let analytics = tracker.get_analytics(None).unwrap();
+        assert!((analytics.total_income - 10.0).abs() < 1e-9);
+
+        tracker.flush().unwrap();
+        let mut incomes = 0;
+        for_each_jsonl::<WorkIncomeRecord, _>(&costs, |_| incomes += 1).unwrap();
+        assert_eq!(incomes, 1);
+        assert!(tmp.path().join("grant_income.jsonl").exists());
+
+        // Dropping the tracker drains what was logged after the last flush
+        tracker.add_grant_income(2.5, "grant-2", "").unwrap();
+        drop(tracker);
+        let reloaded = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        reloaded.initialize().unwrap();
+        assert!((reloaded.get_balance() - 1017.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cost_ceiling_stops_only_the_runaway_task() {
+        let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            max_cost_per_task: Some(5.0),
+            ..test_config()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+
+        tracker.start_task("runaway", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(3.0), Duration::ZERO).unwrap();
+        // The call that crosses the ceiling is still recorded
+        tracker.track_tokens(1000, 500, "agent", Some(3.0), Duration::ZERO).unwrap();
+        let err = tracker.track_tokens(1000, 500, "agent", Some(0.1), Duration::ZERO).unwrap_err();
+        assert!(matches!(
+            err,
+            EconomicError::TaskCostCeilingExceeded { ref task_id, ceiling }
+                if task_id == "runaway" && (ceiling - 5.0).abs() < f64::EPSILON
+        ));
+        assert!((tracker.task_cost_so_far("runaway").unwrap() - 6.0).abs() < 1e-9);
+        assert!((tracker.get_balance() - 994.0).abs() < 1e-9);
+
+        // Another task gets its own budget
+        tracker.start_task("other", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO).unwrap();
+        assert!((tracker.task_cost_so_far("other").unwrap() - 4.0).abs() < 1e-9);
+        assert!((tracker.task_cost_so_far("runaway").unwrap() - 6.0).abs() < 1e-9);
+
+        tracker.end_task("other").unwrap();
+        assert!((tracker.task_cost_so_far("other").unwrap() - 4.0).abs() < 1e-9);
+        assert!(matches!(
+            tracker.task_cost_so_far("missing"),
+            Err(EconomicError::TaskNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn tasks_are_listed_by_status() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-done", None, &[]).unwrap();
+        tracker.start_task("task-broken", None, &[]).unwrap();
+        assert_eq!(
+            tracker.get_tasks_by_status(TaskStatus::Active).unwrap(),
+            ["task-done", "task-broken"]
+        );
+        assert!(tracker.get_tasks_by_status(TaskStatus::Completed).unwrap().is_empty());
+
+        tracker.end_task("task-done").unwrap();
+        tracker.mark_task_failed("task-broken", "provider returned 500").unwrap();
+        tracker.start_task("task-next", None, &[]).unwrap();
+
+        assert_eq!(tracker.get_tasks_by_status(TaskStatus::Active).unwrap(), ["task-next"]);
+        assert_eq!(tracker.get_tasks_by_status(TaskStatus::Completed).unwrap(), ["task-done"]);
+        assert_eq!(tracker.get_tasks_by_status(TaskStatus::Failed).unwrap(), ["task-broken"]);
+        assert_eq!(
+            tracker.get_all_task_ids().unwrap(),
+            ["task-done", "task-broken", "task-next"]
+        );
+
+        // A restarted task is only reported as active
+        tracker.start_task("task-broken", None, &[]).unwrap();
+        assert!(tracker.get_tasks_by_status(TaskStatus::Failed).unwrap().is_empty());
+        assert_eq!(
+            tracker.get_all_task_ids().unwrap(),
+            ["task-done", "task-next", "task-broken"]
+        );
+    }
+
+    #[test]
+    fn task_tags_are_normalized_persisted_and_filterable() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[" Client:ACME ", "experiment:prompt-v2", "client:acme", ""]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(1.0), Duration::ZERO).unwrap();
+        let summary = tracker.end_task("task-1").unwrap();
+        assert_eq!(summary.tags, vec!["client:acme", "experiment:prompt-v2"]);
+
+        tracker.start_task("task-2", None, &["client:globex"]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(2.0), Duration::ZERO).unwrap();
+        tracker.end_task("task-2").unwrap();
+        tracker.add_work_income(10.0, "task-2", 0.9, "").unwrap();
+
+        // Tags survive the JSONL round-trip
+        let mut records = Vec::new();
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            records.push(record);
+        })
+        .unwrap();
+        assert_eq!(records[0].tags, vec!["client:acme", "experiment:prompt-v2"]);
+
+        let acme = tracker.get_analytics(Some("CLIENT:acme")).unwrap();
+        assert_eq!(acme.by_task.len(), 1);
+        assert!((acme.total_costs.total() - 1.0).abs() < f64::EPSILON);
+        assert_eq!(acme.total_tasks, 1);
+        assert!(acme.total_income.abs() < f64::EPSILON);
+
+        let all = tracker.get_analytics(None).unwrap();
+        let by_tag = all.cost_by_tag(..);
+        assert!((by_tag["client:acme"].total - 1.0).abs() < f64::EPSILON);
+        assert!((by_tag["experiment:prompt-v2"].total - 1.0).abs() < f64::EPSILON);
+        assert!((by_tag["client:globex"].total - 2.0).abs() < f64::EPSILON);
+        assert!((all.total_income - 10.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn unknown_task_is_a_typed_error() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        let err = tracker.end_task("missing").unwrap_err();
+        assert!(matches!(
+            err,
+            EconomicError::TaskNotFound { ref task_id } if task_id == "missing"
+        ));
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.end_task("task-1").unwrap();
+        assert!(tracker.end_task("task-1").is_err());
+        assert!(tracker.peek_task_cost("task-1").is_err());
+    }
+
+    #[test]
+    fn refund_credits_balance_up_to_original_charge() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO).unwrap();
+        let llm_charge = tracker.last_charge_id().unwrap();
+        tracker.track_flat_api_call(1.0, "tavily_search");
+        let api_charge = tracker.last_charge_id().unwrap();
+
+        // Refund an active task's charge
+        let balance = tracker.track_refund(&llm_charge, 3.0, "provider outage").unwrap();
+        assert!((balance - (1000.0 - 5.0 + 3.0)).abs() < 1e-9);
+        assert!((tracker.get_balance() - balance).abs() < f64::EPSILON);
+
+        // Only $1 of the LLM charge is left to refund
+        let err = tracker.track_refund(&llm_charge, 1.5, "double refund").unwrap_err();
+        assert!(matches!(
+            err,
+            EconomicError::RefundExceedsCharge { .. }
+        ));
+
+        // Charges in ended tasks are found in the cost log
+        tracker.end_task("task-1").unwrap();
+        tracker.track_refund(&api_charge, 1.0, "billing error").unwrap();
+        assert!(tracker.track_refund(&api_charge, 0.01, "again").is_err());
+        assert!(tracker.track_refund("no-such-charge", 1.0, "").is_err());
+        assert!(tracker.track_refund(&llm_charge, -1.0, "").is_err());
+
+        assert!((tracker.total_refunds() - 4.0).abs() < 1e-9);
+        assert!((tracker.get_summary().total_refunds - 4.0).abs() < 1e-9);
+        let mut refunds = Vec::new();
+        for_each_jsonl::<RefundRecord, _>(&tmp.path().join("refunds.jsonl"), |r| refunds.push(r))
+            .unwrap();
+        assert_eq!(refunds.len(), 2);
+        assert!(refunds.iter().all(|r| r.cost < 0.0));
+    }
+
+    #[test]
+    fn concurrent_refunds_never_exceed_the_charge() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO).unwrap();
+        let charge = tracker.last_charge_id().unwrap();
+
+        // Eight $1 refunds of a $4 charge race; exactly four may succeed
+        let barrier = std::sync::Barrier::new(8);
+        let credited = std::thread::scope(|scope| {
+            let handles: Vec<_> = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        barrier.wait();
+                        tracker.track_refund(&charge, 1.0, "duplicate charge").is_ok()
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .filter(|&ok| ok)
+                .count()
+        });
+        assert_eq!(credited, 4);
+        assert!((tracker.total_refunds() - 4.0).abs() < 1e-9);
+        assert!((tracker.get_balance() - 1000.0).abs() < 1e-9);
+
+        // The refunded total survives a restart, once the charge is logged
+        tracker.end_task("task-1").unwrap();
+        let reloaded = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        reloaded.initialize().unwrap();
+        assert!(matches!(
+            reloaded.track_refund(&charge, 0.5, "again"),
+            Err(EconomicError::RefundExceedsCharge { .. })
+        ));
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn refund_limits_include_archived_records() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO).unwrap();
+        let charge = tracker.last_charge_id().unwrap();
+        tracker.track_refund(&charge, 1.0, "partial outage").unwrap();
+        tracker.end_task("task-1").unwrap();
+        tracker
+            .drain_to_archive(SystemTime::now() + Duration::from_secs(3600))
+            .unwrap();
+
+        let reloaded = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        reloaded.initialize().unwrap();
+        assert!(matches!(
+            reloaded.track_refund(&charge, 3.5, "again"),
+            Err(EconomicError::RefundExceedsCharge { .. })
+        ));
+        reloaded.track_refund(&charge, 3.0, "rest of the charge").unwrap();
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn records_appended_while_archiving_are_kept() {
+        use flate2::read::GzDecoder;
+        use std::io::Read;
+
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        std::thread::scope(|scope| {
+            scope.spawn(|| {
+                for i in 0..50 {
+                    tracker
+                        .add_grant_income(1.0, &format!("grant-{i}"), "")
+                        .unwrap();
+                }
+            });
+            for _ in 0..10 {
+                tracker
+                    .drain_to_archive(SystemTime::now() + Duration::from_secs(3600))
+                    .unwrap();
+                // Archive names have millisecond resolution
+                std::thread::sleep(Duration::from_millis(2));
+            }
+        });
 
-        Ok(())
+        let mut grants = fs::read_to_string(tmp.path().join("grant_income.jsonl")).unwrap();
+        for entry in fs::read_dir(tmp.path().join("archive")).unwrap() {
+            let path = entry.unwrap().path();
+            if path.to_string_lossy().contains("grant_income-") {
+                GzDecoder::new(File::open(&path).unwrap())
+                    .read_to_string(&mut grants)
+                    .unwrap();
+            }
+        }
+        let logged = grants.lines().filter(|line| line.contains("grant_id"));
+        assert_eq!(logged.count(), 50);
     }
-}
 
-impl std::fmt::Display for EconomicTracker {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let state = self.state.lock();
-        write!(
-            f,
-            "EconomicTracker(signature='{}', balance=${:.2}, status={})",
-            self.signature,
-            state.balance,
-            self.get_survival_status_inner(&state)
+    #[test]
+    fn concurrent_task_ends_keep_every_completion() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        for i in 0..8 {
+            tracker.start_task(format!("task-{i}"), None, &[]).unwrap();
+        }
+
+        // Each end rewrites the completion log; none may drop another's
+        let barrier = std::sync::Barrier::new(8);
+        std::thread::scope(|scope| {
+            for i in 0..8 {
+                let (tracker, barrier) = (&tracker, &barrier);
+                scope.spawn(move || {
+                    barrier.wait();
+                    tracker.end_task(&format!("task-{i}")).unwrap();
+                });
+            }
+        });
+
+        let mut completions = Vec::new();
+        for_each_jsonl::<TaskCompletionRecord, _>(
+            &tmp.path().join("task_completions.jsonl"),
+            |record| completions.push(record.task_id),
         )
+        .unwrap();
+        completions.sort();
+        let expected: Vec<_> = (0..8).map(|i| format!("task-{i}")).collect();
+        assert_eq!(completions, expected);
     }
-}
 
-/// Comprehensive economic summary.
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct EconomicSummary {
-    pub signature: String,
-    pub balance: f64,
-    pub initial_balance: f64,
-    pub net_worth: f64,
-    pub total_token_cost: f64,
-    pub total_work_income: f64,
-    pub total_trading_profit: f64,
-    pub session_cost: f64,
-    pub daily_cost: f64,
-    pub session_input_tokens: u64,
-    pub session_output_tokens: u64,
-    pub survival_status: SurvivalStatus,
-    pub is_bankrupt: bool,
-    pub min_evaluation_threshold: f64,
-}
+    #[test]
+    fn task_cost_overrides_adjust_balance_by_the_difference() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use tempfile::TempDir;
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
+        assert!((tracker.get_balance() - 996.0).abs() < 1e-9);
 
-    fn test_config() -> EconomicConfig {
-        EconomicConfig {
-            enabled: true,
-            initial_balance: 1000.0,
-            token_pricing: TokenPricing {
-                input_price_per_million: 3.0,
-                output_price_per_million: 15.0,
-            },
-            min_evaluation_threshold: 0.6,
-        }
+        // Over-reported: a cached response was tracked at the full rate
+        let balance = tracker.override_task_cost("task-1", 1.5, "cache hit").unwrap();
+        assert!((balance - 998.5).abs() < 1e-9);
+
+        // Under-reported, relative to the corrected cost
+        let balance = tracker.override_task_cost("task-1", 3.0, "invoice").unwrap();
+        assert!((balance - 997.0).abs() < 1e-9);
+        assert!((tracker.get_summary().total_token_cost - 3.0).abs() < 1e-9);
+
+        assert!(tracker.override_task_cost("task-1", -1.0, "").is_err());
+        assert!(matches!(
+            tracker.override_task_cost("no-such-task", 1.0, ""),
+            Err(EconomicError::TaskNotFound { .. })
+        ));
+
+        // The original charge stays in the cost log
+        assert!((tracker.task_cost_so_far("task-1").unwrap() - 4.0).abs() < 1e-9);
+        let mut corrections = Vec::new();
+        for_each_jsonl::<CostCorrectionRecord, _>(
+            &tmp.path().join("cost_corrections.jsonl"),
+            |record| corrections.push(record),
+        )
+        .unwrap();
+        assert_eq!(corrections.len(), 2);
+        assert!((corrections[0].original_cost - 4.0).abs() < 1e-9);
+        assert!((corrections[0].delta + 2.5).abs() < 1e-9);
+        assert!((corrections[1].original_cost - 1.5).abs() < 1e-9);
+        assert!((corrections[1].delta - 1.5).abs() < 1e-9);
     }
 
     #[test]
-    fn tracker_initialization() {
+    fn concurrent_task_cost_overrides_apply_once() {
         let tmp = TempDir::new().unwrap();
-        let config = test_config();
         let tracker = EconomicTracker::new(
             "test-agent",
-            config,
+            test_config(),
             Some(tmp.path().to_path_buf()),
         );
-
         tracker.initialize().unwrap();
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(4.0), Duration::ZERO).unwrap();
+        tracker.end_task("task-1").unwrap();
 
-        assert!((tracker.get_balance() - 1000.0).abs() < f64::EPSILON);
-        assert_eq!(tracker.get_survival_status(), SurvivalStatus::Thriving);
+        // Every override sets the same cost; only the first moves the balance
+        let barrier = std::sync::Barrier::new(8);
+        std::thread::scope(|scope| {
+            for _ in 0..8 {
+                scope.spawn(|| {
+                    barrier.wait();
+                    tracker.override_task_cost("task-1", 1.5, "cache hit").unwrap();
+                });
+            }
+        });
+        assert!((tracker.get_balance() - 998.5).abs() < 1e-9);
+
+        // Corrections logged before a restart still count
+        let reloaded = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        reloaded.initialize().unwrap();
+        let balance = reloaded.override_task_cost("task-1", 2.0, "invoice").unwrap();
+        assert!((balance - 998.0).abs() < 1e-9);
     }
 
     #[test]
-    fn track_tokens_reduces_balance() {
+    fn grant_income_bypasses_quality_threshold() {
         let tmp = TempDir::new().unwrap();
         let tracker = EconomicTracker::new(
             "test-agent",
@@ -873,34 +8685,90 @@ mod tests {
         );
         tracker.initialize().unwrap();
 
-        tracker.start_task("task-1", None);
-        let cost = tracker.track_tokens(1000, 500, "agent", None);
-        tracker.end_task().unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO).unwrap();
+        let balance = tracker.add_grant_income(50.0, "research-2025", "Q1 tranche").unwrap();
+        assert!((balance - 1040.0).abs() < 1e-9);
+        assert!((tracker.get_balance() - 1040.0).abs() < 1e-9);
+        assert!(tracker.add_grant_income(0.0, "research-2025", "").is_err());
 
-        // (1000/1M)*3 + (500/1M)*15 = 0.003 + 0.0075 = 0.0105
-        assert!((cost - 0.0105).abs() < 0.0001);
-        assert!((tracker.get_balance() - (1000.0 - 0.0105)).abs() < 0.0001);
+        let summary = tracker.get_summary();
+        assert!((summary.total_grant_income - 50.0).abs() < 1e-9);
+        assert!(summary.total_work_income.abs() < f64::EPSILON);
+
+        let mut grants = Vec::new();
+        for_each_jsonl::<GrantIncomeRecord, _>(&tmp.path().join("grant_income.jsonl"), |r| {
+            grants.push(r)
+        })
+        .unwrap();
+        assert_eq!(grants.len(), 1);
+        assert_eq!(grants[0].grant_id, "research-2025");
+
+        // The grant survives a reload
+        drop(tracker);
+        let reloaded = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        reloaded.initialize().unwrap();
+        assert!((reloaded.get_balance() - 1040.0).abs() < 1e-9);
+        assert!((reloaded.get_summary().total_grant_income - 50.0).abs() < 1e-9);
     }
 
     #[test]
-    fn work_income_with_threshold() {
+    fn interest_compounds_daily_and_is_idempotent() {
         let tmp = TempDir::new().unwrap();
+        let config = EconomicConfig {
+            daily_yield_rate: 0.001,
+            daily_debt_rate: 0.01,
+            ..test_config()
+        };
         let tracker = EconomicTracker::new(
             "test-agent",
-            test_config(),
+            config.clone(),
             Some(tmp.path().to_path_buf()),
         );
         tracker.initialize().unwrap();
 
-        // Below threshold - no payment
-        let payment = tracker.add_work_income(100.0, "task-1", 0.5, "").unwrap();
-        assert!((payment - 0.0).abs() < f64::EPSILON);
-        assert!((tracker.get_balance() - 1000.0).abs() < f64::EPSILON);
+        // The first call accrues one day, the second catches up 89 more
+        let start = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        tracker.accrue_interest(start).unwrap();
+        tracker.accrue_interest(start + chrono::Days::new(89)).unwrap();
+        let expected = 1000.0 * 1.001_f64.powi(90);
+        assert!((tracker.get_balance() - expected).abs() < 1e-6);
 
-        // At threshold - payment awarded
-        let payment = tracker.add_work_income(100.0, "task-2", 0.6, "").unwrap();
-        assert!((payment - 100.0).abs() < f64::EPSILON);
-        assert!((tracker.get_balance() - 1100.0).abs() < f64::EPSILON);
+        // Days already accrued are not accrued again
+        assert_eq!(tracker.accrue_interest(start + chrono::Days::new(89)).unwrap(), 0.0);
+        assert_eq!(tracker.accrue_interest(start).unwrap(), 0.0);
+        assert!((tracker.get_summary().total_interest_earned - (expected - 1000.0)).abs() < 1e-6);
+
+        // A negative balance pays the debt rate instead
+        tracker.add_trading_profit(-expected - 100.0, "margin call");
+        let paid = tracker.accrue_interest(start + chrono::Days::new(90)).unwrap();
+        assert!((paid + 1.0).abs() < 1e-9);
+        assert!((tracker.get_balance() + 101.0).abs() < 1e-6);
+
+        // Accrual dates and totals survive a reload
+        drop(tracker);
+        let reloaded = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        reloaded.initialize().unwrap();
+        assert_eq!(reloaded.accrue_interest(start + chrono::Days::new(90)).unwrap(), 0.0);
+        let summary = reloaded.get_summary();
+        assert!((summary.total_interest_paid - 1.0).abs() < 1e-9);
+        assert!((summary.total_interest_earned - (expected - 1000.0)).abs() < 1e-6);
+
+        // Zero rates never touch the interest log
+        let plain = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(plain.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert_eq!(tracker.accrue_interest(start).unwrap(), 0.0);
+        assert!(!plain.path().join("interest.jsonl").exists());
+        let balance_log = fs::read_to_string(plain.path().join("balance.jsonl")).unwrap();
+        assert!(!balance_log.contains("interest"));
     }
 
     #[test]
@@ -919,21 +8787,226 @@ mod tests {
         assert_eq!(tracker.get_survival_status(), SurvivalStatus::Thriving);
 
         // Spend 30% - should be stable
-        tracker.track_tokens(10_000_000, 0, "agent", Some(30.0));
+        tracker.track_tokens(10_000_000, 0, "agent", Some(30.0), Duration::ZERO).unwrap();
         assert_eq!(tracker.get_survival_status(), SurvivalStatus::Stable);
 
         // Spend more to reach struggling
-        tracker.track_tokens(10_000_000, 0, "agent", Some(35.0));
+        tracker.track_tokens(10_000_000, 0, "agent", Some(35.0), Duration::ZERO).unwrap();
         assert_eq!(tracker.get_survival_status(), SurvivalStatus::Struggling);
 
         // Spend more to reach critical
-        tracker.track_tokens(10_000_000, 0, "agent", Some(25.0));
+        tracker.track_tokens(10_000_000, 0, "agent", Some(25.0), Duration::ZERO).unwrap();
         assert_eq!(tracker.get_survival_status(), SurvivalStatus::Critical);
 
         // Bankrupt
-        tracker.track_tokens(10_000_000, 0, "agent", Some(20.0));
+        tracker.track_tokens(10_000_000, 0, "agent", Some(20.0), Duration::ZERO).unwrap();
+        assert_eq!(tracker.get_survival_status(), SurvivalStatus::Bankrupt);
+        assert!(tracker.is_bankrupt());
+    }
+
+    #[test]
+    fn bankruptcy_callback_fires_once_per_crossing() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let tmp = TempDir::new().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&calls);
+        let mut config = test_config();
+        config.initial_balance = 10.0;
+        config.on_bankruptcy = Some(BankruptcyCallback::new(move |agent| {
+            assert_eq!(agent, "test-agent");
+            seen.fetch_add(1, Ordering::SeqCst);
+        }));
+
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            config,
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.track_tokens(1000, 0, "agent", Some(5.0), Duration::ZERO).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // Crossing into bankruptcy fires the callback
+        tracker.track_tokens(1000, 0, "agent", Some(6.0), Duration::ZERO).unwrap();
+        assert!(tracker.is_bankrupt());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Further costs while already bankrupt do not
+        tracker.track_tokens(1000, 0, "agent", Some(1.0), Duration::ZERO).unwrap();
+        tracker.track_flat_api_call(1.0, "some_api");
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Recovering and going bankrupt again is a new event
+        tracker.add_trading_profit(10.0, "rescue");
+        assert!(!tracker.is_bankrupt());
+        tracker.track_tokens(1000, 0, "agent", Some(10.0), Duration::ZERO).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn bankruptcy_grace_period_delays_shutdown() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let tmp = TempDir::new().unwrap();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let seen = Arc::clone(&calls);
+        let mut config = test_config();
+        config.initial_balance = 10.0;
+        config.bankruptcy_grace = GracePolicy {
+            hours: 24.0,
+            spend_usd: 5.0,
+        };
+        config.on_bankruptcy = Some(BankruptcyCallback::new(move |_| {
+            seen.fetch_add(1, Ordering::SeqCst);
+        }));
+        let new_tracker = || {
+            let tracker = EconomicTracker::new(
+                "test-agent",
+                config.clone(),
+                Some(tmp.path().to_path_buf()),
+            );
+            tracker.initialize().unwrap();
+            tracker
+        };
+
+        // Reaching zero starts grace instead of bankruptcy
+        let tracker = new_tracker();
+        let crossing = tracker
+            .track_tokens(1000, 0, "agent", Some(12.0), Duration::ZERO)
+            .unwrap();
+        assert!(!crossing.grace_warning);
         assert_eq!(tracker.get_survival_status(), SurvivalStatus::Bankrupt);
+        assert!(!tracker.is_bankrupt());
+        let OperationalState::Grace { spend_remaining, expires_at, .. } =
+            tracker.operational_state()
+        else {
+            panic!("expected grace period");
+        };
+        assert_eq!(spend_remaining, Some(5.0));
+        assert!(expires_at.is_some());
+        assert!(tracker.get_summary().in_grace_period);
+        let charge = tracker
+            .track_tokens(1000, 0, "agent", Some(3.0), Duration::ZERO)
+            .unwrap();
+        assert!(charge.grace_warning);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        // A restart resumes the grace period with its spend so far
+        drop(tracker);
+        let tracker = new_tracker();
+        assert!(matches!(
+            tracker.operational_state(),
+            OperationalState::Grace { spend_remaining: Some(left), .. } if (left - 2.0).abs() < 1e-9
+        ));
+
+        // Spending the rest of the budget makes the agent bankrupt
+        assert!(tracker.track_flat_api_call(2.0, "some_api").grace_warning);
         assert!(tracker.is_bankrupt());
+        assert_eq!(tracker.operational_state(), OperationalState::Bankrupt);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        tracker.track_tokens(1000, 0, "agent", Some(1.0), Duration::ZERO).unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        // Recovering re-arms grace for the next crossing
+        tracker.add_trading_profit(20.0, "rescue");
+        assert!(matches!(tracker.operational_state(), OperationalState::Operating { .. }));
+        tracker.track_tokens(1000, 0, "agent", Some(15.0), Duration::ZERO).unwrap();
+        assert!(matches!(tracker.operational_state(), OperationalState::Grace { .. }));
+        tracker.add_grant_income(10.0, "bailout", "").unwrap();
+        assert!(!tracker.get_summary().in_grace_period);
+
+        let mut kinds = Vec::new();
+        for_each_jsonl::<GraceEvent, _>(&tmp.path().join("grace.jsonl"), |event| {
+            kinds.push(event.kind)
+        })
+        .unwrap();
+        assert_eq!(
+            kinds,
+            [
+                GraceEventKind::Entered,
+                GraceEventKind::Exhausted,
+                GraceEventKind::Entered,
+                GraceEventKind::Recovered,
+            ]
+        );
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn intake_pauses_on_drawdown_and_resumes_with_hysteresis() {
+        let tmp = TempDir::new().unwrap();
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&events);
+        let mut config = test_config();
+        config.initial_balance = 100.0;
+        config.intake_policy = IntakePolicy::Hysteresis {
+            pause_at: SurvivalStatus::Critical,
+            resume_at: SurvivalStatus::Stable,
+        };
+        config.on_intake_change = Some(IntakeCallback::new(move |agent, event| {
+            assert_eq!(agent, "test-agent");
+            seen.lock().push(event.paused);
+        }));
+
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            config.clone(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(tracker.can_accept_work().is_accepted());
+
+        // Drawdown into Critical pauses intake
+        tracker.track_tokens(1000, 0, "agent", Some(95.0), Duration::ZERO).unwrap();
+        assert!(matches!(
+            tracker.can_accept_work(),
+            WorkAdmission::Paused {
+                status: SurvivalStatus::Critical,
+                ..
+            }
+        ));
+        let err = tracker.start_task("task-1", None, &[]).unwrap_err();
+        assert!(matches!(
+            err,
+            EconomicError::IntakePaused { .. }
+        ));
+        assert!(tracker.get_summary().intake_paused);
+
+        // A forced start goes through regardless
+        tracker.start_task_forced("urgent", None, &[]);
+        tracker.end_task("urgent").unwrap();
+
+        // Recovering only to Struggling stays paused
+        tracker.add_grant_income(20.0, "bridge", "").unwrap();
+        assert_eq!(tracker.get_survival_status(), SurvivalStatus::Struggling);
+        assert!(!tracker.can_accept_work().is_accepted());
+
+        // The pause survives a reload
+        drop(tracker);
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            config,
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!(!tracker.can_accept_work().is_accepted());
+
+        // Recovering to Stable resumes intake
+        tracker.add_grant_income(30.0, "bridge", "").unwrap();
+        assert!(tracker.can_accept_work().is_accepted());
+        tracker.start_task("task-2", None, &[]).unwrap();
+
+        assert_eq!(*events.lock(), vec![true, false]);
+        let mut persisted = Vec::new();
+        for_each_jsonl::<IntakeEvent, _>(&tmp.path().join("intake.jsonl"), |event| {
+            persisted.push(event)
+        })
+        .unwrap();
+        assert_eq!(persisted.len(), 2);
+        assert!(persisted[0].paused && !persisted[1].paused);
+        assert_eq!(persisted[0].status, SurvivalStatus::Critical);
     }
 
     #[test]
@@ -949,7 +9022,7 @@ mod tests {
                 Some(tmp.path().to_path_buf()),
             );
             tracker.initialize().unwrap();
-            tracker.track_tokens(1000, 500, "agent", Some(10.0));
+            tracker.track_tokens(1000, 500, "agent", Some(10.0), Duration::ZERO).unwrap();
             tracker.save_daily_state("2025-01-01", 0.0, 0.0, vec![], false).unwrap();
         }
 
@@ -965,6 +9038,227 @@ mod tests {
         }
     }
 
+    #[test]
+    fn dropped_tracker_state_is_restored_on_reload() {
+        let tmp = TempDir::new().unwrap();
+        let config = test_config();
+
+        {
+            let tracker = EconomicTracker::new(
+                "test-agent",
+                config.clone(),
+                Some(tmp.path().to_path_buf()),
+            );
+            tracker.initialize().unwrap();
+            tracker.start_task("task-1", None, &[]).unwrap();
+            for _ in 0..20 {
+                tracker.track_tokens(1000, 500, "agent", Some(0.5), Duration::ZERO).unwrap();
+            }
+            tracker.track_flat_api_call(1.0, "tavily_search");
+            tracker.end_task("task-1").unwrap();
+            tracker.add_trading_profit(-2.0, "bad trade");
+            // Dropped without save_daily_state or an explicit flush
+        }
+
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            config,
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+        assert!((tracker.get_balance() - (1000.0 - 11.0 - 2.0)).abs() < 1e-9);
+        let summary = tracker.get_summary();
+        assert!((summary.total_token_cost - 11.0).abs() < 1e-9);
+        assert!((summary.total_trading_profit + 2.0).abs() < 1e-9);
+
+        // Flushing a clean tracker writes nothing
+        let lines = fs::read_to_string(tmp.path().join("balance.jsonl"))
+            .unwrap()
+            .lines()
+            .count();
+        tracker.flush().unwrap();
+        let after = fs::read_to_string(tmp.path().join("balance.jsonl"))
+            .unwrap()
+            .lines()
+            .count();
+        assert_eq!(lines, after);
+    }
+
+    fn fixture_task_record(task_id: &str, calls: &[(&str, f64)]) -> TaskCostRecord {
+        let calls_detail: Vec<LlmCallRecord> = calls
+            .iter()
+            .map(|(timestamp, cost)| LlmCallRecord {
+                id: String::new(),
+                timestamp: timestamp.parse().unwrap(),
+                api_name: "agent".into(),
+                input_tokens: 1000,
+                output_tokens: 500,
+                cost: *cost,
+                cache_hit: false,
+                cache_savings_usd: 0.0,
+                model: None,
+                provider: None,
+                prompt_type: None,
+                request_id: None,
+                retry_attempt: 0,
+                latency_ms: 0,
+                session_id: None,
+            })
+            .collect();
+        let total_cost: f64 = calls.iter().map(|(_, cost)| cost).sum();
+        let start = calls_detail.first().unwrap().timestamp;
+        let end = calls_detail.last().unwrap().timestamp;
+
+        TaskCostRecord {
+            timestamp_end: end,
+            timestamp_start: start,
+            date: start.format("%Y-%m-%d").to_string(),
+            task_id: task_id.into(),
+            tags: Vec::new(),
+            metadata: BTreeMap::new(),
+            estimated_budget: None,
+            llm_usage: LlmUsageSummary {
+                total_calls: calls_detail.len(),
+                total_input_tokens: 1000 * calls_detail.len() as u64,
+                total_output_tokens: 500 * calls_detail.len() as u64,
+                total_tokens: 1500 * calls_detail.len() as u64,
+                total_cost,
+                total_cache_savings_usd: 0.0,
+                input_price_per_million: 3.0,
+                output_price_per_million: 15.0,
+                calls_detail,
+            },
+            api_usage: ApiUsageSummary::default(),
+            cost_summary: CostBreakdown {
+                llm_tokens: total_cost,
+                ..Default::default()
+            },
+            balance_after: 1000.0 - total_cost,
+            session_cost: total_cost,
+            daily_cost: total_cost,
+            prorated_overhead: 0.0,
+        }
+    }
+
+    fn write_fixture_records(dir: &Path, records: &[TaskCostRecord]) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join("token_costs.jsonl"))
+            .unwrap();
+        for record in records {
+            writeln!(file, "{}", serde_json::to_string(record).unwrap()).unwrap();
+        }
+    }
+
+    #[test]
+    fn cost_by_time_of_day_buckets_by_utc_hour() {
+        let tmp = TempDir::new().unwrap();
+        write_fixture_records(
+            tmp.path(),
+            &[
+                fixture_task_record(
+                    "task-1",
+                    &[("2025-01-01T09:15:00Z", 1.0), ("2025-01-01T14:59:59Z", 2.0)],
+                ),
+                fixture_task_record(
+                    "task-2",
+                    &[("2025-01-02T09:45:00Z", 0.5), ("2025-01-02T23:00:00Z", 0.25)],
+                ),
+                // Non-UTC offsets are normalized: 01:30+05:00 is 20:30 UTC the previous day
+                fixture_task_record("task-3", &[("2025-01-03T01:30:00+05:00", 4.0)]),
+            ],
+        );
+
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        let by_hour = tracker.get_cost_by_time_of_day().unwrap();
+
+        assert!((by_hour[9] - 1.5).abs() < f64::EPSILON);
+        assert!((by_hour[14] - 2.0).abs() < f64::EPSILON);
+        assert!((by_hour[20] - 4.0).abs() < f64::EPSILON);
+        assert!((by_hour[23] - 0.25).abs() < f64::EPSILON);
+        assert!((by_hour.iter().sum::<f64>() - 7.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cost_by_time_of_day_includes_active_task() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            test_config(),
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.track_tokens(1000, 500, "agent", Some(3.0), Duration::ZERO).unwrap();
+
+        let by_hour = tracker.get_cost_by_time_of_day().unwrap();
+        let hour = Utc::now().hour() as usize;
+        // Allow for the hour rolling over between the call and the assertion
+        let total = by_hour[hour] + by_hour[(hour + 23) % 24];
+        assert!((total - 3.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn embedding_and_image_costs_are_tracked_separately() {
+        let tmp = TempDir::new().unwrap();
+        let mut config = test_config();
+        config
+            .embedding_pricing
+            .insert("text-embedding-3-large".into(), 0.13);
+        config.image_pricing.insert(
+            "dall-e-3".into(),
+            ImagePricing {
+                small: 0.02,
+                medium: 0.04,
+                large: 0.12,
+            },
+        );
+        let tracker = EconomicTracker::new(
+            "test-agent",
+            config,
+            Some(tmp.path().to_path_buf()),
+        );
+        tracker.initialize().unwrap();
+
+        tracker.start_task("task-1", None, &[]).unwrap();
+        let embedding = tracker
+            .track_embedding("text-embedding-3-large", 1_000_000)
+            .cost;
+        let unknown_embedding = tracker.track_embedding("mystery-embedder", 1_000_000).cost;
+        let images = tracker
+            .track_image_generation("dall-e-3", 2, ImageSizeClass::Large)
+            .cost;
+        let unknown_images = tracker
+            .track_image_generation("mystery-painter", 1, ImageSizeClass::Medium)
+            .cost;
+        tracker.end_task("task-1").unwrap();
+
+        assert!((embedding - 0.13).abs() < 1e-9);
+        assert!((unknown_embedding - 0.02).abs() < 1e-9);
+        assert!((images - 0.24).abs() < 1e-9);
+        assert!((unknown_images - 0.04).abs() < 1e-9);
+        assert!((tracker.get_balance() - (1000.0 - 0.43)).abs() < 1e-9);
+
+        let mut records = Vec::new();
+        for_each_jsonl::<TaskCostRecord, _>(&tmp.path().join("token_costs.jsonl"), |record| {
+            records.push(record);
+        })
+        .unwrap();
+        assert_eq!(records.len(), 1);
+        let usage = &records[0].api_usage;
+        assert_eq!(usage.total_calls, 4);
+        assert!((usage.embedding_api_cost - 0.15).abs() < 1e-9);
+        assert!((usage.image_generation_cost - 0.28).abs() < 1e-9);
+        assert!(usage.other_api_cost.abs() < f64::EPSILON);
+        assert!((records[0].cost_summary.total() - 0.43).abs() < 1e-9);
+    }
+
     #[test]
     fn api_call_categorization() {
         let tmp = TempDir::new().unwrap();
@@ -975,7 +9269,7 @@ mod tests {
         );
         tracker.initialize().unwrap();
 
-        tracker.start_task("task-1", None);
+        tracker.start_task("task-1", None, &[]).unwrap();
 
         // Search API
         tracker.track_flat_api_call(0.001, "tavily_search");
@@ -986,7 +9280,7 @@ mod tests {
         // Other API
         tracker.track_flat_api_call(0.01, "some_api");
 
-        tracker.end_task().unwrap();
+        tracker.end_task("task-1").unwrap();
 
         // Balance should reflect all costs
         let expected_reduction = 0.001 + 0.001 + 0.01; // search + ocr + other
@@ -0,0 +1,60 @@
+//! Custom payment gating for work income.
+//!
+//! Validators registered with `EconomicTracker::register_income_validator`
+//! run after the evaluation threshold passes. They run in registration
+//! order, each seeing the amount left by the previous one, and every
+//! validator must approve for the payment to be credited.
+
+use std::collections::BTreeMap;
+
+/// Work income about to be credited.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WorkIncomeCandidate {
+    /// Task identifier
+    pub task_id: String,
+    /// Evaluation score (0.0-1.0)
+    pub evaluation_score: f64,
+    /// Payment amount in USD, after earlier adjustments
+    pub amount: f64,
+    /// Description passed to `add_work_income`
+    pub description: String,
+    /// Metadata passed to `add_work_income_with_metadata`
+    pub metadata: BTreeMap<String, String>,
+}
+
+/// Decision of an income validator.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValidationResult {
+    /// Pay the candidate amount
+    Approved,
+    /// Pay nothing, for the given reason
+    Rejected(String),
+    /// Pay a different amount (negative or non-finite amounts pay nothing)
+    Adjusted(f64),
+}
+
+/// Custom payment gate for `add_work_income`.
+pub type IncomeValidator = Box<dyn Fn(&WorkIncomeCandidate) -> ValidationResult + Send + Sync>;
+
+/// Run `validators` in order over `candidate`.
+///
+/// Returns the amount to pay, or the reason of the first rejection.
+pub(crate) fn run_validators(
+    validators: &[IncomeValidator],
+    mut candidate: WorkIncomeCandidate,
+) -> Result<f64, String> {
+    for validator in validators {
+        match validator(&candidate) {
+            ValidationResult::Approved => {}
+            ValidationResult::Rejected(reason) => return Err(reason),
+            ValidationResult::Adjusted(amount) => {
+                candidate.amount = if amount.is_finite() {
+                    amount.max(0.0)
+                } else {
+                    0.0
+                };
+            }
+        }
+    }
+    Ok(candidate.amount)
+}
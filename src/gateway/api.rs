@@ -495,6 +495,7 @@ pub async fn handle_api_cost(
                 "monthly_cost_usd": 0.0,
                 "total_tokens": 0,
                 "request_count": 0,
+                "failed_call_cost_usd": 0.0,
                 "by_model": {},
             }
         }))
@@ -838,12 +838,12 @@ async fn handle_metrics(
         );
     }
 
-    let body = if let Some(prom) = state
-        .observer
-        .as_ref()
+    let observer: &dyn crate::observability::Observer = state.observer.as_ref();
+    let observer = observer
         .as_any()
-        .downcast_ref::<crate::observability::PrometheusObserver>()
-    {
+        .downcast_ref::<sse::BroadcastObserver>()
+        .map_or(observer, sse::BroadcastObserver::inner);
+    let body = if let Some(prom) = crate::observability::PrometheusObserver::find(observer) {
         prom.encode()
     } else {
         String::from("# Prometheus backend not enabled. Set [observability] backend = \"prometheus\" in config.\n")
@@ -1241,6 +1241,10 @@ fn handle_webhook_streaming(
                             error_message: None,
                             input_tokens: None,
                             output_tokens: None,
+                            cache_read_tokens: None,
+                            cache_write_tokens: None,
+                            reasoning_tokens: None,
+                            session_id: None,
                         },
                     );
                     state_for_call.observer.record_metric(
@@ -1274,6 +1278,10 @@ fn handle_webhook_streaming(
                             error_message: Some(sanitized.clone()),
                             input_tokens: None,
                             output_tokens: None,
+                            cache_read_tokens: None,
+                            cache_write_tokens: None,
+                            reasoning_tokens: None,
+                            session_id: None,
                         },
                     );
                     state_for_call.observer.record_metric(
@@ -1325,12 +1333,24 @@ fn handle_webhook_streaming(
     let state_for_stream = state.clone();
     let provider_label_for_stream = provider_label.clone();
     let model_label_for_stream = model_label.clone();
+    let stream_request_id = Uuid::new_v4().to_string();
     let mut stream_failed = false;
 
     let sse_stream = provider_stream.map(move |result| match result {
         Ok(chunk) if chunk.is_final => {
             if !stream_failed {
                 let duration = started_at.elapsed();
+                state_for_stream.observer.record_event(
+                    &crate::observability::ObserverEvent::LlmStreamCompleted {
+                        provider: provider_label_for_stream.clone(),
+                        model: model_label_for_stream.clone(),
+                        request_id: stream_request_id.clone(),
+                        duration,
+                        success: true,
+                        final_input_tokens: None,
+                        final_output_tokens: None,
+                    },
+                );
                 state_for_stream.observer.record_event(
                     &crate::observability::ObserverEvent::LlmResponse {
                         provider: provider_label_for_stream.clone(),
@@ -1340,6 +1360,10 @@ fn handle_webhook_streaming(
                         error_message: None,
                         input_tokens: None,
                         output_tokens: None,
+                        cache_read_tokens: None,
+                        cache_write_tokens: None,
+                        reasoning_tokens: None,
+                        session_id: None,
                     },
                 );
                 state_for_stream.observer.record_metric(
@@ -1361,6 +1385,19 @@ fn handle_webhook_streaming(
             if chunk.delta.is_empty() {
                 return Ok(Bytes::new());
             }
+            let token_count = if chunk.token_count == 0 {
+                chunk.delta.len().div_ceil(4)
+            } else {
+                chunk.token_count
+            };
+            state_for_stream.observer.record_event(
+                &crate::observability::ObserverEvent::LlmStreamChunk {
+                    provider: provider_label_for_stream.clone(),
+                    model: model_label_for_stream.clone(),
+                    request_id: stream_request_id.clone(),
+                    output_tokens_delta: token_count as u64,
+                },
+            );
             let payload = serde_json::json!({
                 "delta": chunk.delta,
                 "model": model_label_for_stream
@@ -1372,6 +1409,17 @@ fn handle_webhook_streaming(
             let duration = started_at.elapsed();
             let sanitized = providers::sanitize_api_error(&e.to_string());
 
+            state_for_stream.observer.record_event(
+                &crate::observability::ObserverEvent::LlmStreamCompleted {
+                    provider: provider_label_for_stream.clone(),
+                    model: model_label_for_stream.clone(),
+                    request_id: stream_request_id.clone(),
+                    duration,
+                    success: false,
+                    final_input_tokens: None,
+                    final_output_tokens: None,
+                },
+            );
             state_for_stream.observer.record_event(
                 &crate::observability::ObserverEvent::LlmResponse {
                     provider: provider_label_for_stream.clone(),
@@ -1381,6 +1429,10 @@ fn handle_webhook_streaming(
                     error_message: Some(sanitized.clone()),
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
+                    reasoning_tokens: None,
+                    session_id: None,
                 },
             );
             state_for_stream.observer.record_metric(
@@ -1571,6 +1623,10 @@ async fn handle_webhook(
                         error_message: Some(sanitized.clone()),
                         input_tokens: None,
                         output_tokens: None,
+                        cache_read_tokens: None,
+                        cache_write_tokens: None,
+                        reasoning_tokens: None,
+                        session_id: None,
                     });
                 state.observer.record_metric(
                     &crate::observability::traits::ObserverMetric::RequestLatency(duration),
@@ -1621,6 +1677,10 @@ async fn handle_webhook(
                     error_message: None,
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
+                    reasoning_tokens: None,
+                    session_id: None,
                 });
             state.observer.record_metric(
                 &crate::observability::traits::ObserverMetric::RequestLatency(duration),
@@ -1652,6 +1712,10 @@ async fn handle_webhook(
                     error_message: Some(sanitized.clone()),
                     input_tokens: None,
                     output_tokens: None,
+                    cache_read_tokens: None,
+                    cache_write_tokens: None,
+                    reasoning_tokens: None,
+                    session_id: None,
                 });
             state.observer.record_metric(
                 &crate::observability::traits::ObserverMetric::RequestLatency(duration),
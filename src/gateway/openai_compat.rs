@@ -413,6 +413,14 @@ fn handle_streaming(
         Ok(chunk) if chunk.is_final => {
             if !errored {
                 let duration = started_at.elapsed();
+                record_stream_completed(
+                    &state_for_stream,
+                    &provider_label_for_stream,
+                    &model_for_stream,
+                    &request_id,
+                    duration,
+                    true,
+                );
                 record_success(
                     &state_for_stream,
                     &provider_label_for_stream,
@@ -423,6 +431,21 @@ fn handle_streaming(
             Ok::<_, std::io::Error>(axum::body::Bytes::from("data: [DONE]\n\n"))
         }
         Ok(chunk) => {
+            if !chunk.delta.is_empty() {
+                let token_count = if chunk.token_count == 0 {
+                    chunk.delta.len().div_ceil(4)
+                } else {
+                    chunk.token_count
+                };
+                state_for_stream.observer.record_event(
+                    &crate::observability::ObserverEvent::LlmStreamChunk {
+                        provider: provider_label_for_stream.clone(),
+                        model: model_for_stream.clone(),
+                        request_id: request_id.clone(),
+                        output_tokens_delta: token_count as u64,
+                    },
+                );
+            }
             let role = if first_chunk {
                 first_chunk = false;
                 Some("assistant")
@@ -455,6 +478,14 @@ fn handle_streaming(
             errored = true;
             let duration = started_at.elapsed();
             let msg = e.to_string();
+            record_stream_completed(
+                &state_for_stream,
+                &provider_label_for_stream,
+                &model_for_stream,
+                &request_id,
+                duration,
+                false,
+            );
             record_failure(
                 &state_for_stream,
                 &provider_label_for_stream,
@@ -544,12 +575,39 @@ fn record_success(
             error_message: None,
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
     state
         .observer
         .record_metric(&crate::observability::traits::ObserverMetric::RequestLatency(duration));
 }
 
+/// End a native stream; its cost comes from the chunk events, since
+/// providers do not report final token counts for streams.
+fn record_stream_completed(
+    state: &AppState,
+    provider_label: &str,
+    model: &str,
+    request_id: &str,
+    duration: std::time::Duration,
+    success: bool,
+) {
+    state
+        .observer
+        .record_event(&crate::observability::ObserverEvent::LlmStreamCompleted {
+            provider: provider_label.to_string(),
+            model: model.to_string(),
+            request_id: request_id.to_string(),
+            duration,
+            success,
+            final_input_tokens: None,
+            final_output_tokens: None,
+        });
+}
+
 fn record_failure(
     state: &AppState,
     provider_label: &str,
@@ -567,6 +625,10 @@ fn record_failure(
             error_message: Some(error_message.to_string()),
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
     state
         .observer
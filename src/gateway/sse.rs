@@ -70,6 +70,11 @@ impl BroadcastObserver {
     ) -> Self {
         Self { inner, tx }
     }
+
+    /// The wrapped observer.
+    pub fn inner(&self) -> &dyn crate::observability::Observer {
+        self.inner.as_ref()
+    }
 }
 
 impl crate::observability::Observer for BroadcastObserver {
@@ -91,11 +96,14 @@ impl crate::observability::Observer for BroadcastObserver {
                 tool,
                 duration,
                 success,
+                cost_usd,
+                ..
             } => serde_json::json!({
                 "type": "tool_call",
                 "tool": tool,
                 "duration_ms": duration.as_millis(),
                 "success": success,
+                "cost_usd": cost_usd,
                 "timestamp": chrono::Utc::now().to_rfc3339(),
             }),
             crate::observability::ObserverEvent::ToolCallStart { tool } => serde_json::json!({
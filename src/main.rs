@@ -62,6 +62,8 @@ mod cost;
 mod cron;
 mod daemon;
 mod doctor;
+#[allow(unused_imports)]
+mod economic;
 mod gateway;
 mod goals;
 mod hardware;
@@ -109,6 +111,44 @@ enum CompletionShell {
     Elvish,
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum RecordKindArg {
+    #[value(name = "llm-call")]
+    LlmCall,
+    #[value(name = "api-call")]
+    ApiCall,
+    #[value(name = "work-income")]
+    WorkIncome,
+    #[value(name = "grant-income")]
+    GrantIncome,
+    #[value(name = "refund")]
+    Refund,
+}
+
+impl From<RecordKindArg> for zeroclaw::economic::RecordKind {
+    fn from(kind: RecordKindArg) -> Self {
+        match kind {
+            RecordKindArg::LlmCall => Self::LlmCall,
+            RecordKindArg::ApiCall => Self::ApiCall,
+            RecordKindArg::WorkIncome => Self::WorkIncome,
+            RecordKindArg::GrantIncome => Self::GrantIncome,
+            RecordKindArg::Refund => Self::Refund,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum QueryFormatArg {
+    #[value(name = "csv")]
+    Csv,
+    #[value(name = "json")]
+    Json,
+    #[value(name = "count")]
+    Count,
+    #[value(name = "sum")]
+    Sum,
+}
+
 #[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
 enum EstopLevelArg {
     #[value(name = "kill-all")]
@@ -502,6 +542,27 @@ Examples:
         config_command: ConfigCommands,
     },
 
+    /// Inspect and repair economic tracker data
+    #[command(long_about = "\
+Inspect and repair economic tracker data.
+
+Use 'repair' on a data directory the tracker fails to load after a crash \
+or a full disk. Undecodable lines are kept in '<file>.rejected' next to \
+each log, and balance.jsonl is rebuilt from the repaired history.
+
+Use 'query' to filter LLM calls, API calls, income, and refunds and print \
+them as CSV or JSON, or just their count or total amount.
+
+Examples:
+  zeroclaw economic repair ./data/agent_data/my-agent/economic --dry-run
+  zeroclaw economic repair ./data/agent_data/my-agent/economic
+  zeroclaw economic query ./data/agent_data/my-agent/economic --provider openrouter --since 2026-01-01
+  zeroclaw economic query ./data/agent_data/my-agent/economic --kind llm-call --tag research --format sum")]
+    Economic {
+        #[command(subcommand)]
+        economic_command: EconomicCommands,
+    },
+
     /// Generate shell completion script to stdout
     #[command(long_about = "\
 Generate shell completion scripts for `zeroclaw`.
@@ -525,6 +586,66 @@ enum ConfigCommands {
     Schema,
 }
 
+#[derive(Subcommand, Debug)]
+enum EconomicCommands {
+    /// Repair damaged JSONL logs and rebuild the balance history
+    Repair {
+        /// Economic data directory (e.g. ./data/agent_data/<agent>/economic)
+        path: std::path::PathBuf,
+        /// Report what would change without writing anything
+        #[arg(long)]
+        dry_run: bool,
+        /// Seconds a record may trail an earlier one before its log is re-sorted
+        #[arg(long, default_value = "60")]
+        tolerance_secs: u64,
+    },
+    /// Filter economic records and print them, their count, or their total
+    Query {
+        /// Economic data directory (e.g. ./data/agent_data/<agent>/economic)
+        path: std::path::PathBuf,
+        /// Only LLM calls served by this model
+        #[arg(long)]
+        model: Option<String>,
+        /// Only LLM calls served by this provider
+        #[arg(long)]
+        provider: Option<String>,
+        /// Only calls and income of this task
+        #[arg(long = "task")]
+        task_id: Option<String>,
+        /// Only calls of tasks with this tag (repeatable)
+        #[arg(long = "tag")]
+        tags: Vec<String>,
+        /// Only records with this task or payment metadata, as KEY=VALUE (repeatable)
+        #[arg(long = "metadata", value_parser = parse_key_value)]
+        metadata: Vec<(String, String)>,
+        /// Minimum amount in USD
+        #[arg(long)]
+        min_cost: Option<f64>,
+        /// Maximum amount in USD
+        #[arg(long)]
+        max_cost: Option<f64>,
+        /// Only records of this kind (repeatable)
+        #[arg(long = "kind", value_enum)]
+        kinds: Vec<RecordKindArg>,
+        /// First day to include (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        since: Option<chrono::NaiveDate>,
+        /// First day to leave out (YYYY-MM-DD, UTC)
+        #[arg(long)]
+        until: Option<chrono::NaiveDate>,
+        /// Output format
+        #[arg(long, value_enum, default_value = "csv")]
+        format: QueryFormatArg,
+    },
+}
+
+fn parse_key_value(value: &str) -> std::result::Result<(String, String), String> {
+    value
+        .split_once('=')
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .ok_or_else(|| format!("expected KEY=VALUE, got '{value}'"))
+}
+
 #[derive(Subcommand, Debug)]
 enum EstopSubcommands {
     /// Print current estop status.
@@ -1153,6 +1274,116 @@ async fn main() -> Result<()> {
                 Ok(())
             }
         },
+
+        Commands::Economic { economic_command } => handle_economic_command(economic_command),
+    }
+}
+
+fn handle_economic_command(command: EconomicCommands) -> Result<()> {
+    match command {
+        EconomicCommands::Repair {
+            path,
+            dry_run,
+            tolerance_secs,
+        } => {
+            let options = zeroclaw::economic::RepairOptions {
+                dry_run,
+                reorder_tolerance: std::time::Duration::from_secs(tolerance_secs),
+            };
+            let report = zeroclaw::economic::repair::repair_data_dir(&path, options)
+                .with_context(|| format!("Failed to repair {}", path.display()))?;
+            print_repair_report(&report);
+            Ok(())
+        }
+        EconomicCommands::Query {
+            path,
+            model,
+            provider,
+            task_id,
+            tags,
+            metadata,
+            min_cost,
+            max_cost,
+            kinds,
+            since,
+            until,
+            format,
+        } => {
+            if !path.is_dir() {
+                bail!("{} is not a directory", path.display());
+            }
+            let mut query = zeroclaw::economic::RecordQuery::new();
+            if since.is_some() || until.is_some() {
+                query = query.range(zeroclaw::economic::DateRange::days(
+                    since.unwrap_or(chrono::NaiveDate::MIN),
+                    until.unwrap_or(chrono::NaiveDate::MAX),
+                    &chrono::Utc,
+                ));
+            }
+            if let Some(model) = model {
+                query = query.model(model);
+            }
+            if let Some(provider) = provider {
+                query = query.provider(provider);
+            }
+            if let Some(task_id) = task_id {
+                query = query.task_id(task_id);
+            }
+            for tag in &tags {
+                query = query.tag(tag);
+            }
+            for (key, value) in metadata {
+                query = query.metadata(key, value);
+            }
+            if let Some(min_cost) = min_cost {
+                query = query.min_cost(min_cost);
+            }
+            if let Some(max_cost) = max_cost {
+                query = query.max_cost(max_cost);
+            }
+            for kind in kinds {
+                query = query.kind(kind.into());
+            }
+
+            let context = || format!("Failed to query {}", path.display());
+            match format {
+                QueryFormatArg::Csv => print!("{}", query.export_csv(&path).with_context(context)?),
+                QueryFormatArg::Json => {
+                    let records = query.collect_vec(&path).with_context(context)?;
+                    println!("{}", serde_json::to_string_pretty(&records)?);
+                }
+                QueryFormatArg::Count => println!("{}", query.count(&path).with_context(context)?),
+                QueryFormatArg::Sum => {
+                    println!("{:.6}", query.sum_cost(&path).with_context(context)?);
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+fn print_repair_report(report: &zeroclaw::economic::RepairReport) {
+    for file in &report.files {
+        println!(
+            "{}: {} records, {} rejected, {} duplicates, {} out of order",
+            file.path.display(),
+            file.records,
+            file.rejected_lines,
+            file.duplicates_removed,
+            file.records_out_of_order
+        );
+    }
+    match report.final_balance {
+        Some(balance) => println!(
+            "balance.jsonl: {} snapshots rebuilt, final balance ${balance:.2}",
+            report.snapshots_rebuilt
+        ),
+        None => println!("balance.jsonl: no opening snapshot, not rebuilt"),
+    }
+    if report.dry_run {
+        println!("Dry run: nothing was written.");
+    } else if !report.found_damage() {
+        println!("No damage found.");
     }
 }
 
@@ -2098,6 +2329,83 @@ mod tests {
         }
     }
 
+    #[test]
+    fn economic_repair_cli_accepts_dry_run() {
+        let cli = Cli::try_parse_from(["zeroclaw", "economic", "repair", "/tmp/data", "--dry-run"])
+            .expect("economic repair --dry-run should parse");
+
+        match cli.command {
+            Commands::Economic {
+                economic_command:
+                    EconomicCommands::Repair {
+                        path,
+                        dry_run,
+                        tolerance_secs,
+                    },
+            } => {
+                assert_eq!(path, std::path::PathBuf::from("/tmp/data"));
+                assert!(dry_run);
+                assert_eq!(tolerance_secs, 60);
+            }
+            other => panic!("expected economic repair command, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn economic_query_cli_accepts_repeated_filters() {
+        let cli = Cli::try_parse_from([
+            "zeroclaw",
+            "economic",
+            "query",
+            "/tmp/data",
+            "--kind",
+            "llm-call",
+            "--kind",
+            "api-call",
+            "--tag",
+            "research",
+            "--metadata",
+            "customer=acme",
+            "--since",
+            "2026-01-01",
+            "--format",
+            "sum",
+        ])
+        .expect("economic query filters should parse");
+
+        match cli.command {
+            Commands::Economic {
+                economic_command:
+                    EconomicCommands::Query {
+                        kinds,
+                        tags,
+                        metadata,
+                        since,
+                        until,
+                        format,
+                        ..
+                    },
+            } => {
+                assert_eq!(kinds, vec![RecordKindArg::LlmCall, RecordKindArg::ApiCall]);
+                assert_eq!(tags, vec!["research".to_string()]);
+                assert_eq!(metadata, vec![("customer".to_string(), "acme".to_string())]);
+                assert_eq!(since, chrono::NaiveDate::from_ymd_opt(2026, 1, 1));
+                assert_eq!(until, None);
+                assert_eq!(format, QueryFormatArg::Sum);
+            }
+            other => panic!("expected economic query command, got {other:?}"),
+        }
+        assert!(Cli::try_parse_from([
+            "zeroclaw",
+            "economic",
+            "query",
+            "/tmp/data",
+            "--metadata",
+            "customer"
+        ])
+        .is_err());
+    }
+
     #[test]
     fn onboard_cli_accepts_no_totp_flag() {
         let cli = Cli::try_parse_from(["zeroclaw", "onboard", "--no-totp"])
@@ -0,0 +1,473 @@
+//! Hard spending limits enforced at the event layer.
+//!
+//! [`BudgetGuardObserver`] prices every successful `LlmResponse` with the
+//! shared model prices and keeps a session and a daily total, independent of
+//! the cost and economic trackers. When either total reaches its limit the
+//! guard flips a shared kill switch, which the agent loop checks before each
+//! provider call, and fires its trip callback. Soft thresholds below the
+//! limits are logged as warnings.
+
+use super::multi::{CompositeObserver, MultiObserver};
+use super::registry::ObserverRegistry;
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::config::schema::CostBudgetConfig;
+use crate::cost::{SharedPricing, TokenUsage};
+use chrono::{DateTime, NaiveTime, Utc};
+use parking_lot::Mutex;
+use std::any::Any;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Spending limit of a [`BudgetGuardObserver`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BudgetScope {
+    /// Everything spent since the guard was created
+    Session,
+    /// Everything spent since the last daily reset
+    Daily,
+}
+
+/// Spending limit approached or reached, passed to the guard's callbacks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BudgetAlert {
+    pub scope: BudgetScope,
+    /// Spent within the scope, USD
+    pub spent_usd: f64,
+    /// Limit of the scope, USD
+    pub limit_usd: f64,
+    /// Threshold crossed in percent of the limit; 100 once it is reached
+    pub percent: u8,
+}
+
+type AlertCallback = Box<dyn Fn(&BudgetAlert) + Send + Sync>;
+
+/// Spending within one scope.
+#[derive(Debug, Default)]
+struct Spend {
+    spent_usd: f64,
+    /// Number of warning thresholds already crossed
+    warned: usize,
+    tripped: bool,
+}
+
+#[derive(Debug)]
+struct BudgetState {
+    session: Spend,
+    daily: Spend,
+    /// Start of the current daily period
+    day_start: DateTime<Utc>,
+}
+
+/// Observer that stops provider calls once a spending limit is reached.
+pub struct BudgetGuardObserver {
+    pricing: Arc<SharedPricing>,
+    /// 0 disables the limit
+    session_limit_usd: f64,
+    /// 0 disables the limit
+    daily_limit_usd: f64,
+    /// Ascending, below 100
+    warn_at_percent: Vec<u8>,
+    reset_hour_utc: u32,
+    state: Mutex<BudgetState>,
+    kill_switch: Arc<AtomicBool>,
+    on_trip: Option<AlertCallback>,
+    on_warning: Option<AlertCallback>,
+}
+
+impl BudgetGuardObserver {
+    /// Guard enforcing the limits of `config`, pricing responses with
+    /// `pricing`.
+    pub fn new(config: &CostBudgetConfig, pricing: Arc<SharedPricing>) -> Self {
+        let mut warn_at_percent: Vec<u8> = config
+            .warn_at_percent
+            .iter()
+            .copied()
+            .filter(|percent| (1..100).contains(percent))
+            .collect();
+        warn_at_percent.sort_unstable();
+        warn_at_percent.dedup();
+        let reset_hour_utc = u32::from(config.daily_reset_hour_utc.min(23));
+        Self {
+            pricing,
+            session_limit_usd: valid_limit(config.session_limit_usd),
+            daily_limit_usd: valid_limit(config.daily_limit_usd),
+            warn_at_percent,
+            reset_hour_utc,
+            state: Mutex::new(BudgetState {
+                session: Spend::default(),
+                daily: Spend::default(),
+                day_start: day_start(Utc::now(), reset_hour_utc),
+            }),
+            kill_switch: Arc::new(AtomicBool::new(false)),
+            on_trip: None,
+            on_warning: None,
+        }
+    }
+
+    /// Flip `kill_switch` instead of a switch of the guard's own.
+    pub fn with_kill_switch(mut self, kill_switch: Arc<AtomicBool>) -> Self {
+        self.kill_switch = kill_switch;
+        self
+    }
+
+    /// Call `callback` when a limit flips the kill switch.
+    pub fn with_on_trip(
+        mut self,
+        callback: impl Fn(&BudgetAlert) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_trip = Some(Box::new(callback));
+        self
+    }
+
+    /// Call `callback` when spending crosses a warning threshold.
+    pub fn with_on_warning(
+        mut self,
+        callback: impl Fn(&BudgetAlert) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_warning = Some(Box::new(callback));
+        self
+    }
+
+    /// Switch set once a limit is reached, for callers polling it directly.
+    pub fn kill_switch(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.kill_switch)
+    }
+
+    /// Whether a limit was reached.
+    ///
+    /// A trip caused by the daily limit alone is cleared once the daily
+    /// period resets.
+    pub fn is_tripped(&self) -> bool {
+        self.is_tripped_at(Utc::now())
+    }
+
+    /// Spent within `scope` so far, USD.
+    pub fn spent_usd(&self, scope: BudgetScope) -> f64 {
+        let state = self.state.lock();
+        match scope {
+            BudgetScope::Session => state.session.spent_usd,
+            BudgetScope::Daily => state.daily.spent_usd,
+        }
+    }
+
+    /// Whether `observer` is, or a [`MultiObserver`], [`CompositeObserver`]
+    /// or [`ObserverRegistry`] holding, a tripped budget guard. The agent loop
+    /// checks this before each provider call.
+    pub fn tripped_in(observer: &dyn Observer) -> bool {
+        let any = observer.as_any();
+        if let Some(guard) = any.downcast_ref::<Self>() {
+            return guard.is_tripped();
+        }
+        if let Some(composite) = any.downcast_ref::<CompositeObserver>() {
+            return composite
+                .observers()
+                .iter()
+                .any(|observer| Self::tripped_in(observer));
+        }
+        if let Some(registry) = any.downcast_ref::<ObserverRegistry>() {
+            return registry
+                .snapshot()
+                .iter()
+                .any(|observer| Self::tripped_in(observer.as_ref()));
+        }
+        any.downcast_ref::<MultiObserver>().is_some_and(|multi| {
+            multi
+                .observers()
+                .iter()
+                .any(|observer| Self::tripped_in(observer.as_ref()))
+        })
+    }
+
+    fn is_tripped_at(&self, now: DateTime<Utc>) -> bool {
+        self.roll_day(&mut self.state.lock(), now);
+        self.kill_switch.load(Ordering::SeqCst)
+    }
+
+    /// Add `cost_usd` spent at `now` to both scopes and report the
+    /// thresholds it crossed.
+    fn add_cost(&self, cost_usd: f64, now: DateTime<Utc>) {
+        let mut alerts = Vec::new();
+        {
+            let mut state = self.state.lock();
+            self.roll_day(&mut state, now);
+            let state = &mut *state;
+            for (scope, spend, limit_usd) in [
+                (BudgetScope::Session, &mut state.session, self.session_limit_usd),
+                (BudgetScope::Daily, &mut state.daily, self.daily_limit_usd),
+            ] {
+                spend.spent_usd += cost_usd;
+                if limit_usd > 0.0 {
+                    self.check_limit(scope, spend, limit_usd, &mut alerts);
+                }
+            }
+        }
+
+        for alert in &alerts {
+            let scope = match alert.scope {
+                BudgetScope::Session => "session",
+                BudgetScope::Daily => "daily",
+            };
+            if alert.percent >= 100 {
+                tracing::error!(
+                    "Budget guard: {scope} spending ${:.4} reached its ${:.2} limit, stopping provider calls",
+                    alert.spent_usd,
+                    alert.limit_usd
+                );
+                if let Some(callback) = &self.on_trip {
+                    callback(alert);
+                }
+            } else {
+                tracing::warn!(
+                    "Budget guard: {scope} spending ${:.4} passed {}% of its ${:.2} limit",
+                    alert.spent_usd,
+                    alert.percent,
+                    alert.limit_usd
+                );
+                if let Some(callback) = &self.on_warning {
+                    callback(alert);
+                }
+            }
+        }
+    }
+
+    /// Collect the warnings `spend` newly crossed, and trip the switch if it
+    /// reached `limit_usd`.
+    fn check_limit(
+        &self,
+        scope: BudgetScope,
+        spend: &mut Spend,
+        limit_usd: f64,
+        alerts: &mut Vec<BudgetAlert>,
+    ) {
+        let spent_usd = spend.spent_usd;
+        let alert = |percent| BudgetAlert {
+            scope,
+            spent_usd,
+            limit_usd,
+            percent,
+        };
+        while let Some(&percent) = self.warn_at_percent.get(spend.warned) {
+            if spent_usd < limit_usd * f64::from(percent) / 100.0 {
+                break;
+            }
+            spend.warned += 1;
+            alerts.push(alert(percent));
+        }
+        if !spend.tripped && spent_usd >= limit_usd {
+            spend.tripped = true;
+            // Only the limit that flips the switch reports the trip
+            if !self.kill_switch.swap(true, Ordering::SeqCst) {
+                alerts.push(alert(100));
+            }
+        }
+    }
+
+    /// Start a new daily period if `now` passed the reset hour, clearing a
+    /// trip caused by the daily limit alone.
+    fn roll_day(&self, state: &mut BudgetState, now: DateTime<Utc>) {
+        let day_start = day_start(now, self.reset_hour_utc);
+        if day_start <= state.day_start {
+            return;
+        }
+        state.day_start = day_start;
+        if state.daily.tripped && !state.session.tripped {
+            self.kill_switch.store(false, Ordering::SeqCst);
+        }
+        state.daily = Spend::default();
+    }
+}
+
+impl Observer for BudgetGuardObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        let ObserverEvent::LlmResponse {
+            provider,
+            model,
+            success: true,
+            input_tokens,
+            output_tokens,
+            ..
+        } = event
+        else {
+            return;
+        };
+        let price = self.pricing.snapshot().resolve(provider, model);
+        let usage = TokenUsage::new(
+            model.as_str(),
+            input_tokens.unwrap_or(0),
+            output_tokens.unwrap_or(0),
+            price.input,
+            price.output,
+        );
+        if usage.cost_usd > 0.0 {
+            self.add_cost(usage.cost_usd, Utc::now());
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn name(&self) -> &str {
+        "budget-guard"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn valid_limit(limit_usd: f64) -> f64 {
+    if limit_usd.is_finite() && limit_usd > 0.0 {
+        limit_usd
+    } else {
+        0.0
+    }
+}
+
+/// Most recent `reset_hour` o'clock UTC at or before `now`.
+fn day_start(now: DateTime<Utc>, reset_hour: u32) -> DateTime<Utc> {
+    let reset = NaiveTime::from_hms_opt(reset_hour, 0, 0).unwrap_or(NaiveTime::MIN);
+    let start = now.date_naive().and_time(reset).and_utc();
+    if start > now {
+        start - chrono::Duration::days(1)
+    } else {
+        start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::ModelPricing;
+    use chrono::TimeZone;
+    use std::collections::HashMap;
+    use std::sync::atomic::AtomicUsize;
+    use std::time::Duration;
+
+    /// $10 per 1M input tokens: 100k input tokens cost $1
+    fn pricing() -> Arc<SharedPricing> {
+        let prices = HashMap::from([(
+            "test/model".to_string(),
+            ModelPricing {
+                input: 10.0,
+                output: 0.0,
+                ..Default::default()
+            },
+        )]);
+        Arc::new(SharedPricing::fixed(prices))
+    }
+
+    fn response(input_tokens: u64) -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: "test".into(),
+            model: "model".into(),
+            duration: Duration::from_millis(100),
+            success: true,
+            error_message: None,
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(0),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn session_limit_trips_the_switch_once() {
+        let config = CostBudgetConfig {
+            enabled: true,
+            session_limit_usd: 1.0,
+            ..CostBudgetConfig::default()
+        };
+        let trips = Arc::new(AtomicUsize::new(0));
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let guard = BudgetGuardObserver::new(&config, pricing())
+            .with_on_trip({
+                let trips = Arc::clone(&trips);
+                move |alert| {
+                    assert_eq!(alert.scope, BudgetScope::Session);
+                    trips.fetch_add(1, Ordering::SeqCst);
+                }
+            })
+            .with_on_warning({
+                let warnings = Arc::clone(&warnings);
+                move |alert| warnings.lock().push(alert.percent)
+            });
+        let kill_switch = guard.kill_switch();
+
+        // $0.30 per response: 50% after the second, 80% after the third
+        for _ in 0..3 {
+            guard.record_event(&response(30_000));
+        }
+        assert!(!kill_switch.load(Ordering::SeqCst));
+        assert_eq!(*warnings.lock(), [50, 80]);
+
+        for _ in 0..3 {
+            guard.record_event(&response(30_000));
+        }
+        assert!(kill_switch.load(Ordering::SeqCst));
+        assert!(guard.is_tripped());
+        assert_eq!(trips.load(Ordering::SeqCst), 1);
+        assert_eq!(warnings.lock().len(), 2);
+        assert!((guard.spent_usd(BudgetScope::Session) - 1.8).abs() < 1e-9);
+
+        // Failed responses and other events cost nothing
+        let mut failed = response(1_000_000);
+        if let ObserverEvent::LlmResponse { success, .. } = &mut failed {
+            *success = false;
+        }
+        guard.record_event(&failed);
+        guard.record_event(&ObserverEvent::HeartbeatTick);
+        assert!((guard.spent_usd(BudgetScope::Session) - 1.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn daily_limit_resets_at_the_configured_hour() {
+        let config = CostBudgetConfig {
+            enabled: true,
+            daily_limit_usd: 1.0,
+            session_limit_usd: 5.0,
+            daily_reset_hour_utc: 6,
+            ..CostBudgetConfig::default()
+        };
+        let trips = Arc::new(AtomicUsize::new(0));
+        let guard = BudgetGuardObserver::new(&config, pricing()).with_on_trip({
+            let trips = Arc::clone(&trips);
+            move |_| {
+                trips.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        let at = |day, hour| Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap();
+        guard.state.lock().day_start = day_start(at(1, 7), 6);
+
+        guard.add_cost(0.6, at(1, 7));
+        guard.add_cost(0.6, at(1, 23));
+        assert!(guard.is_tripped_at(at(2, 5)));
+        assert_eq!(trips.load(Ordering::SeqCst), 1);
+
+        // The next period starts at 06:00 with the daily spending cleared
+        assert!(!guard.is_tripped_at(at(2, 6)));
+        assert_eq!(guard.spent_usd(BudgetScope::Daily), 0.0);
+        assert!((guard.spent_usd(BudgetScope::Session) - 1.2).abs() < 1e-9);
+
+        guard.add_cost(1.0, at(2, 8));
+        assert!(guard.is_tripped_at(at(2, 9)));
+        assert_eq!(trips.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn tripped_guards_are_found_inside_multi_observers() {
+        let config = CostBudgetConfig {
+            enabled: true,
+            session_limit_usd: 0.5,
+            ..CostBudgetConfig::default()
+        };
+        let guard = BudgetGuardObserver::new(&config, pricing());
+        let kill_switch = guard.kill_switch();
+        let multi = MultiObserver::new(vec![Box::new(guard)]);
+        assert!(!BudgetGuardObserver::tripped_in(&multi));
+
+        multi.record_event(&response(100_000));
+        assert!(kill_switch.load(Ordering::SeqCst));
+        assert!(BudgetGuardObserver::tripped_in(&multi));
+    }
+}
@@ -1,94 +1,531 @@
 //! Cost-tracking observer that wires provider token usage to the cost tracker.
 //!
 //! Intercepts `LlmResponse` events and records usage to the `CostTracker`,
-//! calculating costs based on model pricing configuration.
+//! calculating costs based on model pricing configuration. Calls to models
+//! without pricing are handled by the configured [`UnknownModelPolicy`].
+//! `ToolCall` events that carry a direct cost are recorded under the tool's
+//! name. With a [`MetricSink`], each priced response is also published as
+//! `llm.cost_usd`, `llm.input_tokens` and `llm.output_tokens` counters.
 
-use super::traits::{Observer, ObserverEvent, ObserverMetric};
-use crate::config::schema::ModelPricing;
-use crate::cost::{CostTracker, TokenUsage};
-use std::collections::HashMap;
+use super::traits::{MetricSink, Observer, ObserverEvent, ObserverMetric};
+use crate::config::schema::{ModelPricing, UnknownModelPolicy};
+use crate::cost::pricing::PricingRule;
+use crate::cost::{CostTracker, PricingReload, SharedPricing, TokenUsage};
+use parking_lot::Mutex;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Events kept on the dead-letter list; the oldest are dropped first.
+const MAX_DEAD_LETTERS: usize = 1000;
+
+/// Expired streams remembered for reconciling late completions.
+const MAX_EXPIRED_STREAMS: usize = 1000;
+
+/// Idle time after which a stream without completion event is expired.
+const DEFAULT_STREAM_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// In-flight stream and the time of its last chunk.
+type PendingStream = (StreamCostAccumulator, Instant);
 
 /// Observer that records token usage to a CostTracker.
 ///
 /// Listens for `LlmResponse` events and calculates costs using model pricing.
 pub struct CostObserver {
     tracker: Arc<CostTracker>,
-    prices: HashMap<String, ModelPricing>,
-    /// Default pricing for unknown models (USD per 1M tokens)
-    default_input_price: f64,
-    default_output_price: f64,
+    pricing: Arc<SharedPricing>,
+    /// Discount applied to cached input tokens (0.9 = 90% cheaper)
+    cache_discount_rate: f64,
+    unknown_model_policy: UnknownModelPolicy,
+    /// Models already warned about under `UnknownModelPolicy::Warn`
+    warned_models: Mutex<HashSet<String>>,
+    /// Events for unpriced models under `UnknownModelPolicy::Error`
+    dead_letters: Mutex<VecDeque<ObserverEvent>>,
+    /// Record the usage of failed responses too
+    record_failed_responses: bool,
+    /// Streams seen through `LlmStreamChunk` events, by request id
+    streams: Mutex<HashMap<String, PendingStream>>,
+    /// Request ids of expired streams and the output tokens recorded for them
+    expired_streams: Mutex<VecDeque<(String, u64)>>,
+    stream_timeout: Duration,
+    /// Receives the cost and token counters of each priced response
+    metric_sink: Option<Arc<dyn MetricSink>>,
 }
 
 impl CostObserver {
     /// Create a new cost observer with the given tracker and pricing config.
     pub fn new(tracker: Arc<CostTracker>, prices: HashMap<String, ModelPricing>) -> Self {
+        Self::with_shared_pricing(tracker, Arc::new(SharedPricing::fixed(prices)))
+    }
+
+    /// Create a cost observer whose prices can be reloaded at runtime.
+    pub fn with_shared_pricing(tracker: Arc<CostTracker>, pricing: Arc<SharedPricing>) -> Self {
         Self {
             tracker,
-            prices,
-            // Conservative defaults for unknown models
-            default_input_price: 3.0,
-            default_output_price: 15.0,
+            pricing,
+            cache_discount_rate: 0.9,
+            unknown_model_policy: UnknownModelPolicy::default(),
+            warned_models: Mutex::new(HashSet::new()),
+            dead_letters: Mutex::new(VecDeque::new()),
+            record_failed_responses: false,
+            streams: Mutex::new(HashMap::new()),
+            expired_streams: Mutex::new(VecDeque::new()),
+            stream_timeout: DEFAULT_STREAM_TIMEOUT,
+            metric_sink: None,
         }
     }
 
-    /// Look up pricing for a model, trying various name formats.
-    fn get_pricing(&self, provider: &str, model: &str) -> (f64, f64) {
-        // Try exact match first: "provider/model"
-        let full_name = format!("{provider}/{model}");
-        if let Some(pricing) = self.prices.get(&full_name) {
-            return (pricing.input, pricing.output);
+    /// Publish the cost and token counts of every priced response to
+    /// `sink`, labelled with provider and model, so metrics exporters reuse
+    /// this observer's pricing.
+    pub fn with_metric_sink(mut self, sink: Arc<dyn MetricSink>) -> Self {
+        self.metric_sink = Some(sink);
+        self
+    }
+
+    /// Expire streams that received no chunk for `timeout` (default 5
+    /// minutes) and no completion event.
+    pub fn with_stream_timeout(mut self, timeout: Duration) -> Self {
+        self.stream_timeout = timeout;
+        self
+    }
+
+    /// Also record failed responses that report token usage, marked as
+    /// failed so reports can separate the wasted spend. Providers bill
+    /// requests that time out or hit a content filter after generating
+    /// tokens.
+    pub fn with_failed_response_costs(mut self, enabled: bool) -> Self {
+        self.record_failed_responses = enabled;
+        self
+    }
+
+    /// Price models without pricing at `input` / `output` USD per 1M tokens.
+    ///
+    /// The defaults belong to the shared prices, so anything else pricing
+    /// calls from [`pricing`](Self::pricing) uses them too.
+    pub fn with_default_pricing(self, input: f64, output: f64) -> Self {
+        self.pricing.set_default_pricing(ModelPricing {
+            input,
+            output,
+            ..Default::default()
+        });
+        self
+    }
+
+    /// Set how calls to models without pricing are recorded.
+    pub fn with_unknown_model_policy(mut self, policy: UnknownModelPolicy) -> Self {
+        self.unknown_model_policy = policy;
+        self
+    }
+
+    /// Events left out of the cost tracker under
+    /// [`UnknownModelPolicy::Error`], oldest first.
+    pub fn dead_letters(&self) -> Vec<ObserverEvent> {
+        self.dead_letters.lock().iter().cloned().collect()
+    }
+
+    /// Remove and return the dead-letter events, e.g. once their models
+    /// have been priced.
+    pub fn take_dead_letters(&self) -> Vec<ObserverEvent> {
+        self.dead_letters.lock().drain(..).collect()
+    }
+
+    /// Set the discount applied to cached input tokens.
+    ///
+    /// `0.9` bills cached tokens at 10% of the input price; the value is
+    /// clamped to `0.0..=1.0`.
+    pub fn with_cache_discount_rate(mut self, rate: f64) -> Self {
+        self.cache_discount_rate = if rate.is_finite() {
+            rate.clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self
+    }
+
+    /// Prices used by this observer, e.g. to start a
+    /// [`watch_pricing_file`](SharedPricing::watch_pricing_file) task.
+    pub fn pricing(&self) -> &Arc<SharedPricing> {
+        &self.pricing
+    }
+
+    /// Reload model prices from the pricing file.
+    ///
+    /// Calls priced while the reload runs use either the old or the new
+    /// table, never a mix of both.
+    pub fn reload_pricing(&self) -> anyhow::Result<PricingReload> {
+        self.pricing.reload_pricing()
+    }
+
+    /// Start accumulating a streamed response from `provider`/`model`.
+    pub fn start_stream(&self, provider: &str, model: &str) -> StreamCostAccumulator {
+        StreamCostAccumulator::new(provider, model)
+    }
+
+    /// Record a completed stream from its per-chunk output token counts.
+    ///
+    /// Equivalent to accumulating every chunk with
+    /// [`start_stream`](Self::start_stream) and recording the finalized event.
+    pub fn observe_stream_chunks(
+        &self,
+        provider: &str,
+        model: &str,
+        input_tokens: u64,
+        chunks: impl IntoIterator<Item = u64>,
+    ) {
+        let mut stream = self.start_stream(provider, model);
+        for chunk_tokens in chunks {
+            stream.accumulate_chunk(chunk_tokens);
         }
+        self.record_event(&stream.finalize(model, input_tokens));
+    }
 
-        // Try just the model name
-        if let Some(pricing) = self.prices.get(model) {
-            return (pricing.input, pricing.output);
+    /// Number of streams with chunks but no completion event yet.
+    pub fn pending_streams(&self) -> usize {
+        self.streams.lock().len()
+    }
+
+    /// Record the accumulated estimate of every stream idle for longer than
+    /// the stream timeout, and stop tracking it. Returns how many expired.
+    ///
+    /// Expiry also runs whenever a new stream starts. A completion event
+    /// arriving after its stream expired only records the difference to
+    /// the estimate.
+    pub fn expire_stale_streams(&self) -> usize {
+        let expired: Vec<(String, StreamCostAccumulator)> = {
+            let mut streams = self.streams.lock();
+            let stale: Vec<String> = streams
+                .iter()
+                .filter(|(_, (_, last_chunk))| last_chunk.elapsed() >= self.stream_timeout)
+                .map(|(request_id, _)| request_id.clone())
+                .collect();
+            stale
+                .into_iter()
+                .filter_map(|id| streams.remove(&id).map(|(stream, _)| (id, stream)))
+                .collect()
+        };
+
+        let count = expired.len();
+        for (request_id, stream) in expired {
+            tracing::warn!(
+                "Stream {request_id} timed out without completion, recording its estimate"
+            );
+            let output_tokens = stream.output_tokens();
+            self.record_event(&stream.finalize("", 0));
+            let mut expired_streams = self.expired_streams.lock();
+            if expired_streams.len() == MAX_EXPIRED_STREAMS {
+                expired_streams.pop_front();
+            }
+            expired_streams.push_back((request_id, output_tokens));
         }
+        count
+    }
 
-        // Try model family matching (e.g., "claude-sonnet-4" matches any claude-sonnet-4-*)
-        for (key, pricing) in &self.prices {
-            // Strip provider prefix if present
-            let key_model = key.split('/').last().unwrap_or(key);
+    /// Add the output tokens of a stream chunk to its request's total.
+    fn record_stream_chunk(&self, provider: &str, model: &str, request_id: &str, tokens: u64) {
+        if !self.streams.lock().contains_key(request_id) {
+            self.expire_stale_streams();
+        }
+        let mut streams = self.streams.lock();
+        let (stream, last_chunk) = streams
+            .entry(request_id.to_string())
+            .or_insert_with(|| (StreamCostAccumulator::new(provider, model), Instant::now()));
+        stream.accumulate_chunk(tokens);
+        *last_chunk = Instant::now();
+    }
 
-            // Check if model starts with the key (family match)
-            if model.starts_with(key_model) || key_model.starts_with(model) {
-                return (pricing.input, pricing.output);
+    /// Record a completed stream, preferring the final token counts over
+    /// the accumulated chunk deltas.
+    #[allow(clippy::too_many_arguments)]
+    fn record_stream_completed(
+        &self,
+        provider: &str,
+        model: &str,
+        request_id: &str,
+        duration: Duration,
+        success: bool,
+        final_input_tokens: Option<u64>,
+        final_output_tokens: Option<u64>,
+    ) {
+        let pending = self.streams.lock().remove(request_id);
+        let output_tokens = if let Some((stream, _)) = pending {
+            final_output_tokens.unwrap_or(stream.output_tokens())
+        } else {
+            let mut expired_streams = self.expired_streams.lock();
+            match expired_streams.iter().position(|(id, _)| id == request_id) {
+                // The estimate is already recorded, only top it up
+                Some(index) => {
+                    let (_, recorded) = expired_streams.remove(index).unwrap_or_default();
+                    final_output_tokens.map_or(0, |output| output.saturating_sub(recorded))
+                }
+                None => final_output_tokens.unwrap_or(0),
             }
+        };
 
-            // Check for common model name patterns
-            // e.g., "claude-3-5-sonnet-20241022" should match "claude-3.5-sonnet"
-            let normalized_model = model.replace('-', ".");
-            let normalized_key = key_model.replace('-', ".");
-            if normalized_model.contains(&normalized_key)
-                || normalized_key.contains(&normalized_model)
-            {
-                return (pricing.input, pricing.output);
+        self.record_event(&ObserverEvent::LlmResponse {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            duration,
+            success,
+            error_message: None,
+            input_tokens: final_input_tokens,
+            output_tokens: Some(output_tokens),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        });
+    }
+
+    /// Look up pricing for a model, applying the unknown-model policy when
+    /// it has none. `None` means the call must not be recorded.
+    fn get_pricing(&self, provider: &str, model: &str) -> Option<ModelPricing> {
+        let resolution = self.pricing.snapshot().explain_for(provider, model);
+        let (input, output) = (resolution.pricing.input, resolution.pricing.output);
+        if resolution.rule != PricingRule::Default {
+            return Some(resolution.pricing);
+        }
+        match self.unknown_model_policy {
+            UnknownModelPolicy::UseDefault => {
+                tracing::debug!(
+                    "No pricing found for {}/{}, using defaults (${}/{} per 1M tokens)",
+                    provider,
+                    model,
+                    input,
+                    output
+                );
+                Some(resolution.pricing)
             }
+            UnknownModelPolicy::Zero => Some(ModelPricing::default()),
+            UnknownModelPolicy::Warn => {
+                let full_name = format!("{provider}/{model}");
+                if self.warned_models.lock().insert(full_name.clone()) {
+                    tracing::warn!(
+                        "No pricing found for {}, using defaults (${}/{} per 1M tokens)",
+                        full_name,
+                        input,
+                        output
+                    );
+                }
+                Some(resolution.pricing)
+            }
+            UnknownModelPolicy::Error => None,
         }
+    }
 
-        // Fall back to defaults
-        tracing::debug!(
-            "No pricing found for {}/{}, using defaults (${}/{} per 1M tokens)",
-            provider,
-            model,
-            self.default_input_price,
-            self.default_output_price
-        );
-        (self.default_input_price, self.default_output_price)
+    /// Cost of the cache and reasoning tokens of a call relative to billing
+    /// them as plain input and output, which `TokenUsage::new` assumes.
+    ///
+    /// Cache reads without their own rate get the cache discount, cache
+    /// writes fall back to the input price, and reasoning tokens to the
+    /// output price.
+    fn token_breakdown_adjustment(
+        &self,
+        pricing: &ModelPricing,
+        cache_read: u64,
+        cache_write: u64,
+        reasoning: u64,
+    ) -> f64 {
+        let price = |value: f64| {
+            if value.is_finite() && value > 0.0 {
+                value
+            } else {
+                0.0
+            }
+        };
+        let per_million = |tokens: u64| tokens as f64 / 1_000_000.0;
+        let input = price(pricing.input);
+        let output = price(pricing.output);
+        let cache_read_price = pricing
+            .cache_read
+            .map_or(input * (1.0 - self.cache_discount_rate), price);
+        let cache_write_price = pricing.cache_write.map_or(input, price);
+        let reasoning_price = pricing.reasoning.map_or(output, price);
+        per_million(cache_read) * (cache_read_price - input)
+            + per_million(cache_write) * (cache_write_price - input)
+            + per_million(reasoning) * (reasoning_price - output)
+    }
+
+    /// Record the direct cost of a tool call, if it has one.
+    fn record_tool_call(&self, tool: &str, success: bool, cost_usd: Option<f64>) {
+        let Some(cost_usd) = cost_usd.filter(|cost| cost.is_finite() && *cost > 0.0) else {
+            return;
+        };
+        if !success && !self.record_failed_responses {
+            return;
+        }
+        let mut usage = TokenUsage::tool_call(tool, cost_usd);
+        usage.failed = !success;
+        if let Err(e) = self.tracker.record_usage(usage) {
+            tracing::warn!("Failed to record tool cost: {e}");
+        }
+    }
+
+    /// Publish the counters of a priced response to the metric sink.
+    fn publish_usage_metrics(&self, provider: &str, model: &str, usage: &TokenUsage) {
+        let Some(sink) = &self.metric_sink else {
+            return;
+        };
+        let labels = vec![
+            ("provider".to_string(), provider.to_string()),
+            ("model".to_string(), model.to_string()),
+        ];
+        let counters = [
+            ("llm.cost_usd", usage.cost_usd),
+            ("llm.input_tokens", usage.input_tokens as f64),
+            ("llm.output_tokens", usage.output_tokens as f64),
+        ];
+        for (name, value) in counters {
+            sink.publish_metric(&ObserverMetric::Counter {
+                name: name.to_string(),
+                value,
+                labels: labels.clone(),
+            });
+        }
+    }
+
+    /// Keep `event` for later inspection instead of recording it.
+    fn dead_letter(&self, event: &ObserverEvent) {
+        let mut dead_letters = self.dead_letters.lock();
+        if dead_letters.len() == MAX_DEAD_LETTERS {
+            dead_letters.pop_front();
+        }
+        dead_letters.push_back(event.clone());
+    }
+}
+
+/// Accumulates the output tokens of a streamed LLM response.
+///
+/// Token counts of a stream are only final once it completes, so chunks are
+/// summed here and the whole response is turned into a single `LlmResponse`
+/// event by [`finalize`](Self::finalize), which prices it exactly like a
+/// non-streamed call with the same totals.
+#[derive(Debug, Clone)]
+pub struct StreamCostAccumulator {
+    provider: String,
+    model: String,
+    started: Instant,
+    output_tokens: u64,
+    chunks: usize,
+}
+
+impl StreamCostAccumulator {
+    /// Start a stream from `provider`/`model`.
+    pub fn new(provider: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            model: model.to_string(),
+            started: Instant::now(),
+            output_tokens: 0,
+            chunks: 0,
+        }
+    }
+
+    /// Add the output tokens of one chunk.
+    pub fn accumulate_chunk(&mut self, chunk_tokens: u64) {
+        self.output_tokens = self.output_tokens.saturating_add(chunk_tokens);
+        self.chunks += 1;
+    }
+
+    /// Output tokens accumulated so far.
+    pub fn output_tokens(&self) -> u64 {
+        self.output_tokens
+    }
+
+    /// Number of chunks accumulated so far.
+    pub fn chunks(&self) -> usize {
+        self.chunks
+    }
+
+    /// Build the `LlmResponse` event for the completed stream.
+    ///
+    /// `model` is the model reported when the stream completed, which may be
+    /// more specific than the requested one; an empty string keeps the
+    /// model the stream was started with.
+    pub fn finalize(self, model: &str, input_tokens: u64) -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: self.provider,
+            model: if model.is_empty() {
+                self.model
+            } else {
+                model.to_string()
+            },
+            duration: self.started.elapsed(),
+            success: true,
+            error_message: None,
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(self.output_tokens),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        }
     }
 }
 
 impl Observer for CostObserver {
     fn record_event(&self, event: &ObserverEvent) {
+        if let ObserverEvent::ToolCall {
+            tool,
+            success,
+            cost_usd,
+            ..
+        } = event
+        {
+            self.record_tool_call(tool, *success, *cost_usd);
+            return;
+        }
+
+        match event {
+            ObserverEvent::LlmStreamChunk {
+                provider,
+                model,
+                request_id,
+                output_tokens_delta,
+            } => {
+                self.record_stream_chunk(provider, model, request_id, *output_tokens_delta);
+                return;
+            }
+            ObserverEvent::LlmStreamCompleted {
+                provider,
+                model,
+                request_id,
+                duration,
+                success,
+                final_input_tokens,
+                final_output_tokens,
+            } => {
+                self.record_stream_completed(
+                    provider,
+                    model,
+                    request_id,
+                    *duration,
+                    *success,
+                    *final_input_tokens,
+                    *final_output_tokens,
+                );
+                return;
+            }
+            _ => {}
+        }
+
         if let ObserverEvent::LlmResponse {
             provider,
             model,
-            success: true,
+            success,
             input_tokens,
             output_tokens,
+            cache_read_tokens,
+            cache_write_tokens,
+            reasoning_tokens,
+            session_id,
             ..
         } = event
         {
+            if !success && !self.record_failed_responses {
+                return;
+            }
+
             // Only record if we have token counts
             let input = input_tokens.unwrap_or(0);
             let output = output_tokens.unwrap_or(0);
@@ -97,17 +534,39 @@ impl Observer for CostObserver {
                 return;
             }
 
-            let (input_price, output_price) = self.get_pricing(provider, model);
+            let Some(pricing) = self.get_pricing(provider, model) else {
+                tracing::warn!("No pricing found for {provider}/{model}, not recording its cost");
+                self.dead_letter(event);
+                return;
+            };
             let full_model_name = format!("{provider}/{model}");
 
-            let usage = TokenUsage::new(
+            let mut usage = TokenUsage::new(
                 full_model_name,
                 input,
                 output,
-                input_price,
-                output_price,
+                pricing.input,
+                pricing.output,
             );
 
+            // Cache and reasoning tokens are subsets of the input and output,
+            // billed at their own rates
+            let cache_read = cache_read_tokens.unwrap_or(0).min(input);
+            let cache_write = cache_write_tokens.unwrap_or(0).min(input - cache_read);
+            let reasoning = reasoning_tokens.unwrap_or(0).min(output);
+            if cache_read > 0 || cache_write > 0 || reasoning > 0 {
+                let adjustment =
+                    self.token_breakdown_adjustment(&pricing, cache_read, cache_write, reasoning);
+                usage.cost_usd = (usage.cost_usd + adjustment).max(0.0);
+            }
+            usage.cache_read_tokens = cache_read;
+            usage.cache_write_tokens = cache_write;
+            usage.reasoning_tokens = reasoning;
+
+            usage.session_id = session_id.clone();
+            usage.failed = !success;
+
+            self.publish_usage_metrics(provider, model, &usage);
             if let Err(e) = self.tracker.record_usage(usage) {
                 tracing::warn!("Failed to record cost usage: {e}");
             }
@@ -153,6 +612,7 @@ mod tests {
             ModelPricing {
                 input: 3.0,
                 output: 15.0,
+                ..Default::default()
             },
         );
 
@@ -166,6 +626,10 @@ mod tests {
             error_message: None,
             input_tokens: Some(1000),
             output_tokens: Some(500),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
 
         let summary = tracker.get_summary().unwrap();
@@ -175,91 +639,512 @@ mod tests {
     }
 
     #[test]
-    fn cost_observer_ignores_failed_responses() {
-        let (_tmp, tracker) = create_test_tracker();
-        let observer = CostObserver::new(tracker.clone(), HashMap::new());
+    fn cost_observer_discounts_cached_input_tokens() {
+        let mut prices = HashMap::new();
+        prices.insert(
+            "anthropic/claude-sonnet-4".into(),
+            ModelPricing {
+                input: 3.0,
+                output: 15.0,
+                ..Default::default()
+            },
+        );
+        let event = |cache_read_tokens| ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(100),
+            success: true,
+            error_message: None,
+            input_tokens: Some(1_000_000),
+            output_tokens: Some(0),
+            cache_read_tokens,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        };
 
-        observer.record_event(&ObserverEvent::LlmResponse {
+        let (_tmp, uncached_tracker) = create_test_tracker();
+        CostObserver::new(uncached_tracker.clone(), prices.clone()).record_event(&event(None));
+        let uncached = uncached_tracker.get_summary().unwrap().session_cost_usd;
+
+        let (_tmp, cached_tracker) = create_test_tracker();
+        CostObserver::new(cached_tracker.clone(), prices.clone())
+            .record_event(&event(Some(500_000)));
+        let cached = cached_tracker.get_summary().unwrap().session_cost_usd;
+
+        // Half the input from cache at the default 90% discount: 1.5 + 0.15
+        assert!((uncached - 3.0).abs() < 0.0001);
+        assert!((cached - 1.65).abs() < 0.0001);
+        assert!(cached < uncached);
+
+        let (_tmp, custom_tracker) = create_test_tracker();
+        CostObserver::new(custom_tracker.clone(), prices)
+            .with_cache_discount_rate(0.5)
+            .record_event(&event(Some(500_000)));
+        let custom = custom_tracker.get_summary().unwrap().session_cost_usd;
+        assert!((custom - 2.25).abs() < 0.0001);
+    }
+
+    #[test]
+    fn cost_observer_prices_prompt_caching_at_cache_rates() {
+        let prices = HashMap::from([(
+            "anthropic/claude-sonnet-4".to_string(),
+            ModelPricing {
+                input: 3.0,
+                output: 15.0,
+                cache_read: Some(0.3),
+                cache_write: Some(3.75),
+                ..Default::default()
+            },
+        )]);
+        // A 100k-token system prompt cached on the first turn and read back
+        // on the nine after it, each turn adding 1k fresh input tokens
+        let turn = |cache_read, cache_write| ObserverEvent::LlmResponse {
             provider: "anthropic".into(),
             model: "claude-sonnet-4".into(),
             duration: Duration::from_millis(100),
-            success: false,
-            error_message: Some("API error".into()),
-            input_tokens: Some(1000),
-            output_tokens: Some(500),
-        });
+            success: true,
+            error_message: None,
+            input_tokens: Some(101_000),
+            output_tokens: Some(1_000),
+            cache_read_tokens: cache_read,
+            cache_write_tokens: cache_write,
+            reasoning_tokens: None,
+            session_id: None,
+        };
 
-        let summary = tracker.get_summary().unwrap();
-        assert_eq!(summary.request_count, 0);
+        let (_tmp, naive_tracker) = create_test_tracker();
+        let naive_observer = CostObserver::new(naive_tracker.clone(), prices.clone());
+        let (_tmp, cached_tracker) = create_test_tracker();
+        let cached_observer = CostObserver::new(cached_tracker.clone(), prices);
+        cached_observer.record_event(&turn(None, Some(100_000)));
+        for _ in 0..10 {
+            naive_observer.record_event(&turn(None, None));
+        }
+        for _ in 1..10 {
+            cached_observer.record_event(&turn(Some(100_000), None));
+        }
+
+        let naive = naive_tracker.get_summary().unwrap().session_cost_usd;
+        let cached = cached_tracker.get_summary().unwrap().session_cost_usd;
+        // Naive: 10 * (0.303 + 0.015) = 3.18
+        assert!((naive - 3.18).abs() < 0.0001);
+        // Write turn: 0.375 + 0.003 + 0.015, then 9 * (0.03 + 0.003 + 0.015)
+        assert!((cached - 0.825).abs() < 0.0001);
+        assert!(cached < naive / 3.0);
     }
 
     #[test]
-    fn cost_observer_ignores_zero_token_responses() {
+    fn cost_observer_prices_reasoning_tokens() {
+        let event = ObserverEvent::LlmResponse {
+            provider: "openai".into(),
+            model: "o3".into(),
+            duration: Duration::from_millis(100),
+            success: true,
+            error_message: None,
+            input_tokens: Some(0),
+            output_tokens: Some(1_000_000),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: Some(800_000),
+            session_id: None,
+        };
+        let pricing = |reasoning| {
+            HashMap::from([(
+                "openai/o3".to_string(),
+                ModelPricing {
+                    input: 2.0,
+                    output: 8.0,
+                    reasoning,
+                    ..Default::default()
+                },
+            )])
+        };
+
+        // Without a reasoning rate the tokens are billed as output
         let (_tmp, tracker) = create_test_tracker();
-        let observer = CostObserver::new(tracker.clone(), HashMap::new());
+        CostObserver::new(tracker.clone(), pricing(None)).record_event(&event);
+        assert!((tracker.get_summary().unwrap().session_cost_usd - 8.0).abs() < 0.0001);
 
-        observer.record_event(&ObserverEvent::LlmResponse {
+        let (_tmp, tracker) = create_test_tracker();
+        CostObserver::new(tracker.clone(), pricing(Some(12.0))).record_event(&event);
+        // 0.2 * 8 + 0.8 * 12
+        assert!((tracker.get_summary().unwrap().session_cost_usd - 11.2).abs() < 0.0001);
+    }
+
+    #[test]
+    fn streamed_response_costs_the_same_as_a_single_call() {
+        let mut prices = HashMap::new();
+        prices.insert(
+            "anthropic/claude-sonnet-4".into(),
+            ModelPricing {
+                input: 3.0,
+                output: 15.0,
+                ..Default::default()
+            },
+        );
+
+        let (_tmp, streamed_tracker) = create_test_tracker();
+        let observer = CostObserver::new(streamed_tracker.clone(), prices.clone());
+        let mut stream = observer.start_stream("anthropic", "claude-sonnet-4");
+        for chunk in 0..10 {
+            stream.accumulate_chunk(40 + chunk * 2);
+        }
+        assert_eq!(stream.chunks(), 10);
+        assert_eq!(stream.output_tokens(), 490);
+        let event = stream.finalize("", 1200);
+        assert!(matches!(
+            &event,
+            ObserverEvent::LlmResponse {
+                output_tokens: Some(490),
+                input_tokens: Some(1200),
+                ..
+            }
+        ));
+        observer.record_event(&event);
+
+        let (_tmp, single_tracker) = create_test_tracker();
+        CostObserver::new(single_tracker.clone(), prices.clone()).record_event(
+            &ObserverEvent::LlmResponse {
+                provider: "anthropic".into(),
+                model: "claude-sonnet-4".into(),
+                duration: Duration::from_millis(100),
+                success: true,
+                error_message: None,
+                input_tokens: Some(1200),
+                output_tokens: Some(490),
+                cache_read_tokens: None,
+                cache_write_tokens: None,
+                reasoning_tokens: None,
+                session_id: None,
+            },
+        );
+
+        let streamed = streamed_tracker.get_summary().unwrap();
+        let single = single_tracker.get_summary().unwrap();
+        assert_eq!(streamed.request_count, 1);
+        assert!((streamed.session_cost_usd - single.session_cost_usd).abs() < 1e-12);
+
+        let (_tmp, chunked_tracker) = create_test_tracker();
+        CostObserver::new(chunked_tracker.clone(), prices).observe_stream_chunks(
+            "anthropic",
+            "claude-sonnet-4",
+            1200,
+            (0..10).map(|chunk| 40 + chunk * 2),
+        );
+        let chunked = chunked_tracker.get_summary().unwrap();
+        assert!((chunked.session_cost_usd - single.session_cost_usd).abs() < 1e-12);
+    }
+
+    fn sonnet_pricing() -> HashMap<String, ModelPricing> {
+        HashMap::from([(
+            "anthropic/claude-sonnet-4".to_string(),
+            ModelPricing {
+                input: 1.0,
+                output: 1.0,
+                ..Default::default()
+            },
+        )])
+    }
+
+    fn stream_chunk(request_id: &str, tokens: u64) -> ObserverEvent {
+        ObserverEvent::LlmStreamChunk {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            request_id: request_id.into(),
+            output_tokens_delta: tokens,
+        }
+    }
+
+    fn stream_completed(
+        request_id: &str,
+        input: Option<u64>,
+        output: Option<u64>,
+    ) -> ObserverEvent {
+        ObserverEvent::LlmStreamCompleted {
             provider: "anthropic".into(),
             model: "claude-sonnet-4".into(),
+            request_id: request_id.into(),
             duration: Duration::from_millis(100),
             success: true,
-            error_message: None,
-            input_tokens: None,
-            output_tokens: None,
-        });
+            final_input_tokens: input,
+            final_output_tokens: output,
+        }
+    }
+
+    #[test]
+    fn stream_events_reconcile_against_final_counts() {
+        let (_tmp, tracker) = create_test_tracker();
+        let observer = CostObserver::new(tracker.clone(), sonnet_pricing());
+
+        for _ in 0..5 {
+            observer.record_event(&stream_chunk("a", 100));
+            observer.record_event(&stream_chunk("b", 10));
+        }
+        assert_eq!(observer.pending_streams(), 2);
+
+        // Final counts win over the 500 estimated tokens
+        observer.record_event(&stream_completed("a", Some(1000), Some(450)));
+        // Without final counts the estimate stands
+        observer.record_event(&stream_completed("b", None, None));
+        assert_eq!(observer.pending_streams(), 0);
 
         let summary = tracker.get_summary().unwrap();
-        assert_eq!(summary.request_count, 0);
+        assert_eq!(summary.request_count, 2);
+        assert_eq!(summary.total_tokens, 1000 + 450 + 50);
     }
 
     #[test]
-    fn cost_observer_uses_default_pricing_for_unknown_models() {
+    fn stale_streams_record_their_estimate_and_expire() {
         let (_tmp, tracker) = create_test_tracker();
-        let observer = CostObserver::new(tracker.clone(), HashMap::new());
+        let observer = CostObserver::new(tracker.clone(), sonnet_pricing())
+            .with_stream_timeout(Duration::ZERO);
+
+        observer.record_event(&stream_chunk("a", 300));
+        assert_eq!(observer.expire_stale_streams(), 1);
+        assert_eq!(observer.pending_streams(), 0);
+        assert_eq!(tracker.get_summary().unwrap().total_tokens, 300);
+
+        // A late completion only records what the estimate missed
+        observer.record_event(&stream_completed("a", Some(1000), Some(320)));
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.request_count, 2);
+        assert_eq!(summary.total_tokens, 1000 + 320);
+
+        // Starting a new stream sweeps the stale ones
+        observer.record_event(&stream_chunk("b", 10));
+        observer.record_event(&stream_chunk("c", 10));
+        assert_eq!(observer.pending_streams(), 1);
+    }
+
+    #[derive(Default)]
+    struct RecordingSink(Mutex<Vec<ObserverMetric>>);
+
+    impl MetricSink for RecordingSink {
+        fn publish_metric(&self, metric: &ObserverMetric) {
+            self.0.lock().push(metric.clone());
+        }
+    }
+
+    #[test]
+    fn priced_responses_are_published_to_the_metric_sink() {
+        let (_tmp, tracker) = create_test_tracker();
+        let sink = Arc::new(RecordingSink::default());
+        let observer = CostObserver::new(tracker.clone(), sonnet_pricing())
+            .with_unknown_model_policy(UnknownModelPolicy::Error)
+            .with_metric_sink(sink.clone());
 
+        observer.record_event(&stream_completed("a", Some(1_000_000), Some(500_000)));
+        // Unpriced calls are not recorded, so nothing is published either
         observer.record_event(&ObserverEvent::LlmResponse {
-            provider: "unknown".into(),
-            model: "mystery-model".into(),
+            provider: "acme".into(),
+            model: "mystery".into(),
             duration: Duration::from_millis(100),
             success: true,
             error_message: None,
-            input_tokens: Some(1_000_000), // 1M tokens
-            output_tokens: Some(1_000_000),
+            input_tokens: Some(1000),
+            output_tokens: Some(1000),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
 
-        let summary = tracker.get_summary().unwrap();
-        assert_eq!(summary.request_count, 1);
-        // Default: $3 input + $15 output = $18 for 1M each
-        assert!((summary.session_cost_usd - 18.0).abs() < 0.01);
+        let metrics = sink.0.lock();
+        let counters: Vec<(&str, f64)> = metrics
+            .iter()
+            .map(|metric| match metric {
+                ObserverMetric::Counter { name, value, .. } => (name.as_str(), *value),
+                other => panic!("unexpected metric {other:?}"),
+            })
+            .collect();
+        assert_eq!(
+            counters,
+            [
+                ("llm.cost_usd", 1.5),
+                ("llm.input_tokens", 1_000_000.0),
+                ("llm.output_tokens", 500_000.0),
+            ]
+        );
+        assert_eq!(metrics[0].label("provider"), Some("anthropic"));
+        assert_eq!(metrics[0].label("model"), Some("claude-sonnet-4"));
+        let session_cost = tracker.get_summary().unwrap().session_cost_usd;
+        assert!((session_cost - 1.5).abs() < 1e-9);
     }
 
     #[test]
-    fn cost_observer_matches_model_family() {
+    fn cost_observer_passes_session_id_through() {
         let (_tmp, tracker) = create_test_tracker();
-        let mut prices = HashMap::new();
-        prices.insert(
-            "openai/gpt-4o".into(),
-            ModelPricing {
-                input: 5.0,
-                output: 15.0,
-            },
-        );
+        let observer = CostObserver::new(tracker.clone(), HashMap::new());
 
-        let observer = CostObserver::new(tracker.clone(), prices);
+        for session_id in [Some("chat-42".to_string()), None] {
+            observer.record_event(&ObserverEvent::LlmResponse {
+                provider: "anthropic".into(),
+                model: "claude-sonnet-4".into(),
+                duration: Duration::from_millis(100),
+                success: true,
+                error_message: None,
+                input_tokens: Some(1000),
+                output_tokens: Some(500),
+                cache_read_tokens: None,
+                cache_write_tokens: None,
+                reasoning_tokens: None,
+                session_id,
+            });
+        }
+
+        let sessions = tracker.cost_by_session(..).unwrap();
+        let mut names: Vec<&str> = sessions.iter().map(|s| s.session_id.as_str()).collect();
+        names.sort_unstable();
+        assert_eq!(names, ["chat-42", "none"]);
+    }
+
+    #[test]
+    fn cost_observer_records_tool_costs_per_tool() {
+        let (_tmp, tracker) = create_test_tracker();
+        let observer = CostObserver::new(tracker.clone(), HashMap::new());
+        let call = |tool: &str, success, cost_usd| ObserverEvent::ToolCall {
+            tool: tool.into(),
+            duration: Duration::from_millis(300),
+            success,
+            error_message: None,
+            cost_usd,
+            task_id: Some("task-1".into()),
+        };
+
+        observer.record_event(&call("web_search", true, Some(0.005)));
+        observer.record_event(&call("web_search", true, Some(0.005)));
+        observer.record_event(&call("shell", true, None));
+        observer.record_event(&call("browser", true, Some(0.0)));
+        observer.record_event(&call("web_search", false, Some(0.005)));
+
+        let tools = tracker.cost_by_tool(..).unwrap();
+        assert_eq!(tools.len(), 1);
+        assert_eq!(tools[0].tool, "web_search");
+        assert_eq!(tools[0].call_count, 2);
+        assert!((tools[0].cost_usd - 0.01).abs() < 1e-12);
+        assert_eq!(tracker.get_summary().unwrap().request_count, 2);
+    }
+
+    #[test]
+    fn cost_observer_ignores_failed_responses() {
+        let (_tmp, tracker) = create_test_tracker();
+        let observer = CostObserver::new(tracker.clone(), HashMap::new());
 
-        // Model name with version suffix should still match
         observer.record_event(&ObserverEvent::LlmResponse {
-            provider: "openai".into(),
-            model: "gpt-4o-2024-05-13".into(),
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(100),
+            success: false,
+            error_message: Some("API error".into()),
+            input_tokens: Some(1000),
+            output_tokens: Some(500),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        });
+
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.request_count, 0);
+    }
+
+    #[test]
+    fn unknown_model_policy_controls_unpriced_calls() {
+        let event = |model: &str| ObserverEvent::LlmResponse {
+            provider: "local".into(),
+            model: model.into(),
             duration: Duration::from_millis(100),
             success: true,
             error_message: None,
             input_tokens: Some(1_000_000),
             output_tokens: Some(0),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        };
+        let cost_with = |configure: fn(CostObserver) -> CostObserver| {
+            let (_tmp, tracker) = create_test_tracker();
+            let observer = configure(CostObserver::new(tracker.clone(), HashMap::new()));
+            observer.record_event(&event("llama-3-8b"));
+            let summary = tracker.get_summary().unwrap();
+            (summary.request_count, summary.session_cost_usd, observer)
+        };
+
+        let (_, cost, _) = cost_with(|observer| observer);
+        assert!((cost - 3.0).abs() < 1e-9);
+        let (_, cost, _) = cost_with(|observer| observer.with_default_pricing(0.5, 1.0));
+        assert!((cost - 0.5).abs() < 1e-9);
+        let (_, cost, _) =
+            cost_with(|observer| observer.with_unknown_model_policy(UnknownModelPolicy::Warn));
+        assert!((cost - 3.0).abs() < 1e-9);
+
+        let (requests, cost, _) =
+            cost_with(|observer| observer.with_unknown_model_policy(UnknownModelPolicy::Zero));
+        assert_eq!(requests, 1);
+        assert_eq!(cost, 0.0);
+
+        let (requests, _, observer) =
+            cost_with(|observer| observer.with_unknown_model_policy(UnknownModelPolicy::Error));
+        assert_eq!(requests, 0);
+        let dead_letters = observer.take_dead_letters();
+        assert_eq!(dead_letters.len(), 1);
+        assert!(matches!(
+            &dead_letters[0],
+            ObserverEvent::LlmResponse { model, .. } if model == "llama-3-8b"
+        ));
+        assert!(observer.dead_letters().is_empty());
+    }
+
+    #[test]
+    fn cost_observer_can_record_failed_responses_as_wasted_spend() {
+        let (_tmp, tracker) = create_test_tracker();
+        let observer =
+            CostObserver::new(tracker.clone(), HashMap::new()).with_failed_response_costs(true);
+        let event = |success: bool, output_tokens| ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(100),
+            success,
+            error_message: (!success).then(|| "content filter".to_string()),
+            input_tokens: Some(1_000_000),
+            output_tokens,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        };
+
+        observer.record_event(&event(true, Some(0)));
+        observer.record_event(&event(false, Some(100_000)));
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.request_count, 2);
+        // Default pricing: 3.0 for the input plus 1.5 for the output
+        assert!((summary.failed_call_cost_usd - 4.5).abs() < 1e-9);
+        assert!((summary.session_cost_usd - 7.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn cost_observer_ignores_zero_token_responses() {
+        let (_tmp, tracker) = create_test_tracker();
+        let observer = CostObserver::new(tracker.clone(), HashMap::new());
+
+        observer.record_event(&ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(100),
+            success: true,
+            error_message: None,
+            input_tokens: None,
+            output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
 
         let summary = tracker.get_summary().unwrap();
-        // Should use $5 input price, not default $3
-        assert!((summary.session_cost_usd - 5.0).abs() < 0.01);
+        assert_eq!(summary.request_count, 0);
     }
 }
@@ -0,0 +1,326 @@
+//! Off-the-hot-path delivery of observer events to async observers.
+//!
+//! [`ObserverDispatcher`] is itself an [`Observer`], so it slots in wherever
+//! the runtime holds one. Recording an event only clones it onto a bounded
+//! Tokio channel; a dedicated task delivers the queue to each
+//! [`AsyncObserver`] in order. Synchronous observers such as `CostObserver`
+//! run on that task through a [`SyncObserverAdapter`].
+
+use super::traits::{AsyncObserver, Observer, ObserverEvent, ObserverMetric};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::any::Any;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::runtime::{Handle, RuntimeFlavor};
+use tokio::sync::mpsc::{self, error::TryRecvError, error::TrySendError};
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+/// Queue capacity of a dispatcher unless configured otherwise.
+pub const DEFAULT_DISPATCH_CAPACITY: usize = 1024;
+
+/// What an [`ObserverDispatcher`] does with an item when its queue is full.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued item to make room, counted in
+    /// [`ObserverDispatcher::dropped`].
+    #[default]
+    DropOldest,
+    /// Block the caller until the observers catch up.
+    ///
+    /// Blocking needs a multi-threaded runtime, or no runtime at all; on a
+    /// current-thread runtime it would stall the delivery task too, so the
+    /// item is dropped and counted instead.
+    Backpressure,
+}
+
+enum Dispatch {
+    Event(ObserverEvent),
+    Metric(ObserverMetric),
+}
+
+/// Channel shared by the recording side and the delivery task.
+struct Queue {
+    /// Taken on shutdown, which disconnects the channel
+    sender: Mutex<Option<mpsc::Sender<Dispatch>>>,
+    /// Shared so `DropOldest` can evict from the front of the queue
+    receiver: Mutex<mpsc::Receiver<Dispatch>>,
+    /// Wakes the delivery task when the queue was empty
+    ready: Notify,
+    dropped: AtomicU64,
+}
+
+/// Fans observer events out to async observers from a background task.
+pub struct ObserverDispatcher {
+    queue: Arc<Queue>,
+    policy: OverflowPolicy,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl ObserverDispatcher {
+    /// Start delivering to `observers` from a task on the current Tokio
+    /// runtime, queueing at most `capacity` items.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside a Tokio runtime.
+    pub fn spawn(
+        observers: Vec<Arc<dyn AsyncObserver>>,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+        let queue = Arc::new(Queue {
+            sender: Mutex::new(Some(sender)),
+            receiver: Mutex::new(receiver),
+            ready: Notify::new(),
+            dropped: AtomicU64::new(0),
+        });
+        let worker = tokio::spawn(deliver(Arc::clone(&queue), observers));
+        Self {
+            queue,
+            policy,
+            worker: Mutex::new(Some(worker)),
+        }
+    }
+
+    /// Number of events and metrics dropped because the queue was full.
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Stop accepting events, deliver everything still queued, and flush
+    /// the observers.
+    ///
+    /// Events recorded after shutdown are ignored.
+    pub async fn shutdown(&self) {
+        self.queue.sender.lock().take();
+        self.queue.ready.notify_one();
+        let worker = self.worker.lock().take();
+        if let Some(worker) = worker {
+            if let Err(e) = worker.await {
+                tracing::warn!("Observer dispatch task failed: {e}");
+            }
+        }
+    }
+
+    fn enqueue(&self, item: Dispatch) {
+        let Some(sender) = self.queue.sender.lock().clone() else {
+            return;
+        };
+        let item = match sender.try_send(item) {
+            Ok(()) => None,
+            Err(TrySendError::Full(item)) => Some(item),
+            Err(TrySendError::Closed(_)) => return,
+        };
+        if let Some(item) = item {
+            let sent = match self.policy {
+                OverflowPolicy::DropOldest => self.evict_and_send(&sender, item),
+                OverflowPolicy::Backpressure => send_blocking(&sender, item),
+            };
+            if !sent {
+                self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+                return;
+            }
+        }
+        self.queue.ready.notify_one();
+    }
+
+    /// Make room by dropping the oldest queued items until `item` fits.
+    fn evict_and_send(&self, sender: &mpsc::Sender<Dispatch>, mut item: Dispatch) -> bool {
+        loop {
+            if self.queue.receiver.lock().try_recv().is_ok() {
+                self.queue.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            match sender.try_send(item) {
+                Ok(()) => return true,
+                Err(TrySendError::Full(rejected)) => item = rejected,
+                Err(TrySendError::Closed(_)) => return false,
+            }
+        }
+    }
+}
+
+/// Wait for room in the queue, if the current thread may block.
+fn send_blocking(sender: &mpsc::Sender<Dispatch>, item: Dispatch) -> bool {
+    match Handle::try_current() {
+        Err(_) => sender.blocking_send(item).is_ok(),
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(|| sender.blocking_send(item).is_ok())
+        }
+        Ok(_) => false,
+    }
+}
+
+/// Delivery task: drains the queue in order until it is disconnected and
+/// empty, then flushes every observer.
+async fn deliver(queue: Arc<Queue>, observers: Vec<Arc<dyn AsyncObserver>>) {
+    loop {
+        let next = queue.receiver.lock().try_recv();
+        match next {
+            Ok(Dispatch::Event(event)) => {
+                for observer in &observers {
+                    observer.record_event(&event).await;
+                }
+            }
+            Ok(Dispatch::Metric(metric)) => {
+                for observer in &observers {
+                    observer.record_metric(&metric).await;
+                }
+            }
+            Err(TryRecvError::Empty) => queue.ready.notified().await,
+            Err(TryRecvError::Disconnected) => break,
+        }
+    }
+    for observer in &observers {
+        observer.flush().await;
+    }
+}
+
+impl Observer for ObserverDispatcher {
+    fn record_event(&self, event: &ObserverEvent) {
+        self.enqueue(Dispatch::Event(event.clone()));
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        self.enqueue(Dispatch::Metric(metric.clone()));
+    }
+
+    fn name(&self) -> &str {
+        "dispatch"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Runs a synchronous [`Observer`] as an [`AsyncObserver`].
+pub struct SyncObserverAdapter {
+    observer: Arc<dyn Observer>,
+}
+
+impl SyncObserverAdapter {
+    pub fn new(observer: Arc<dyn Observer>) -> Self {
+        Self { observer }
+    }
+}
+
+#[async_trait]
+impl AsyncObserver for SyncObserverAdapter {
+    async fn record_event(&self, event: &ObserverEvent) {
+        self.observer.record_event(event);
+    }
+
+    async fn record_metric(&self, metric: &ObserverMetric) {
+        self.observer.record_metric(metric);
+    }
+
+    async fn flush(&self) {
+        self.observer.flush();
+    }
+
+    fn name(&self) -> &str {
+        self.observer.name()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicUsize;
+
+    /// Sync observer that keeps the messages of `Error` events
+    #[derive(Default)]
+    struct RecordingObserver {
+        messages: Mutex<Vec<String>>,
+        flushes: AtomicUsize,
+    }
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::Error { message, .. } = event {
+                self.messages.lock().push(message.clone());
+            }
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {}
+
+        fn flush(&self) {
+            self.flushes.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn error_event(index: usize) -> ObserverEvent {
+        ObserverEvent::Error {
+            component: "test".into(),
+            message: index.to_string(),
+        }
+    }
+
+    fn start_dispatcher(
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> (Arc<RecordingObserver>, ObserverDispatcher) {
+        let recording = Arc::new(RecordingObserver::default());
+        let adapter = SyncObserverAdapter::new(recording.clone());
+        let dispatcher = ObserverDispatcher::spawn(vec![Arc::new(adapter)], capacity, policy);
+        (recording, dispatcher)
+    }
+
+    fn messages(range: std::ops::Range<usize>) -> Vec<String> {
+        range.map(|index| index.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn shutdown_drains_queued_events_in_order() {
+        let (recording, dispatcher) =
+            start_dispatcher(DEFAULT_DISPATCH_CAPACITY, OverflowPolicy::default());
+        for index in 0..100 {
+            dispatcher.record_event(&error_event(index));
+        }
+        dispatcher.shutdown().await;
+
+        assert_eq!(*recording.messages.lock(), messages(0..100));
+        assert_eq!(recording.flushes.load(Ordering::SeqCst), 1);
+        assert_eq!(dispatcher.dropped(), 0);
+
+        // Ignored once shut down
+        dispatcher.record_event(&error_event(100));
+        assert_eq!(recording.messages.lock().len(), 100);
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_the_oldest_events() {
+        let (recording, dispatcher) = start_dispatcher(2, OverflowPolicy::DropOldest);
+        // The delivery task cannot run before the first await
+        for index in 0..5 {
+            dispatcher.record_event(&error_event(index));
+        }
+        dispatcher.shutdown().await;
+
+        assert_eq!(dispatcher.dropped(), 3);
+        assert_eq!(*recording.messages.lock(), messages(3..5));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn backpressure_delivers_every_event() {
+        let (recording, dispatcher) = start_dispatcher(1, OverflowPolicy::Backpressure);
+        for index in 0..50 {
+            dispatcher.record_event(&error_event(index));
+        }
+        dispatcher.shutdown().await;
+
+        assert_eq!(dispatcher.dropped(), 0);
+        assert_eq!(*recording.messages.lock(), messages(0..50));
+    }
+}
@@ -0,0 +1,257 @@
+//! Observer that records provider usage into the `EconomicTracker`.
+//!
+//! `CostObserver` keeps the `CostTracker` in step with provider usage;
+//! [`EconomicObserver`] does the same for the economic ledger, so the agent
+//! needs no explicit `track_*` calls and the two ledgers cannot drift. LLM
+//! responses, embeddings, generated images, and tool calls that report a
+//! direct cost are all recorded.
+//! It records only when the tracker is enabled and its `llm_usage_source`
+//! is `Observer`, which also makes the tracker ignore explicit calls.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::economic::{EconomicTracker, LlmUsageSource, ObservedApiCall, ObservedLlmCall};
+use std::any::Any;
+use std::sync::Arc;
+
+/// Observer that records LLM, embedding, image, and tool costs to an
+/// `EconomicTracker`.
+pub struct EconomicObserver {
+    tracker: Arc<EconomicTracker>,
+}
+
+impl EconomicObserver {
+    pub fn new(tracker: Arc<EconomicTracker>) -> Self {
+        Self { tracker }
+    }
+
+    /// The tracker usage is recorded to.
+    pub fn tracker(&self) -> &Arc<EconomicTracker> {
+        &self.tracker
+    }
+}
+
+impl Observer for EconomicObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if !self.tracker.is_enabled()
+            || self.tracker.llm_usage_source() != LlmUsageSource::Observer
+        {
+            return;
+        }
+        let call = match event {
+            ObserverEvent::LlmResponse {
+                provider,
+                model,
+                duration,
+                success: true,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens,
+                session_id,
+                ..
+            } => {
+                let input_tokens = input_tokens.unwrap_or(0);
+                let output_tokens = output_tokens.unwrap_or(0);
+                if input_tokens == 0 && output_tokens == 0 {
+                    return;
+                }
+                let call = ObservedLlmCall {
+                    provider: provider.clone(),
+                    model: model.clone(),
+                    input_tokens,
+                    output_tokens,
+                    cached_input_tokens: cache_read_tokens.unwrap_or(0),
+                    duration: *duration,
+                    session_id: session_id.clone(),
+                };
+                if let Err(e) = self.tracker.track_observed_llm_call(call) {
+                    tracing::warn!("Failed to record LLM usage to the economic tracker: {e}");
+                }
+                return;
+            }
+            ObserverEvent::EmbeddingResponse {
+                model,
+                success: true,
+                tokens,
+                cost_usd,
+                ..
+            } => ObservedApiCall::Embedding {
+                model: model.clone(),
+                tokens: tokens.unwrap_or(0),
+                cost_usd: *cost_usd,
+            },
+            ObserverEvent::ImageGeneration {
+                model,
+                success: true,
+                count,
+                size_class,
+                cost_usd,
+                ..
+            } => ObservedApiCall::ImageGeneration {
+                model: model.clone(),
+                count: *count,
+                size_class: *size_class,
+                cost_usd: *cost_usd,
+            },
+            ObserverEvent::ToolCall {
+                tool,
+                success: true,
+                cost_usd: Some(cost_usd),
+                task_id,
+                ..
+            } if cost_usd.is_finite() && *cost_usd > 0.0 => ObservedApiCall::Tool {
+                tool: tool.clone(),
+                cost_usd: *cost_usd,
+                task_id: task_id.clone(),
+            },
+            _ => return,
+        };
+        self.tracker.track_observed_api_call(call);
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn name(&self) -> &str {
+        "economic"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cost::ImageSizeClass;
+    use crate::economic::{Charge, EconomicConfig, TokenPricing};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn tracker(tmp: &TempDir, enabled: bool, source: LlmUsageSource) -> Arc<EconomicTracker> {
+        let config = EconomicConfig {
+            enabled,
+            token_pricing: TokenPricing {
+                input_price_per_million: 3.0,
+                output_price_per_million: 15.0,
+            },
+            llm_usage_source: source,
+            ..EconomicConfig::default()
+        };
+        let tracker = EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf()));
+        tracker.initialize().unwrap();
+        Arc::new(tracker)
+    }
+
+    fn response(session_id: Option<&str>) -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(1200),
+            success: true,
+            error_message: None,
+            input_tokens: Some(100_000),
+            output_tokens: Some(10_000),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: session_id.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn llm_responses_are_charged_to_the_named_task() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = tracker(&tmp, true, LlmUsageSource::Observer);
+        let observer = EconomicObserver::new(Arc::clone(&tracker));
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+
+        // $0.30 input + $0.15 output
+        observer.record_event(&response(Some("task-1")));
+        observer.record_event(&response(Some("conversation-7")));
+        assert!((tracker.peek_task_cost("task-1").unwrap() - 0.45).abs() < 1e-9);
+        assert!((tracker.peek_task_cost("task-2").unwrap() - 0.45).abs() < 1e-9);
+        assert!((tracker.get_balance() - 999.1).abs() < 1e-9);
+
+        // Explicit calls would count the same usage twice
+        let cost = tracker
+            .track_tokens(100_000, 10_000, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert_eq!(cost, 0.0);
+        assert!((tracker.get_balance() - 999.1).abs() < 1e-9);
+    }
+
+    #[test]
+    fn embedding_image_and_tool_costs_are_recorded() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = tracker(&tmp, true, LlmUsageSource::Observer);
+        let observer = EconomicObserver::new(Arc::clone(&tracker));
+        tracker.start_task("task-1", None, &[]).unwrap();
+        tracker.start_task("task-2", None, &[]).unwrap();
+
+        // Default embedding price of $0.02 per 1M tokens
+        observer.record_event(&ObserverEvent::EmbeddingResponse {
+            provider: "openai".into(),
+            model: "text-embedding-3-small".into(),
+            duration: Duration::from_millis(80),
+            success: true,
+            tokens: Some(1_000_000),
+            cost_usd: None,
+        });
+        // Two large images at the default $0.08
+        observer.record_event(&ObserverEvent::ImageGeneration {
+            provider: "openai".into(),
+            model: "dall-e-3".into(),
+            duration: Duration::from_secs(9),
+            success: true,
+            count: 2,
+            size_class: ImageSizeClass::Large,
+            cost_usd: None,
+        });
+        let tool_call = |success, cost_usd| ObserverEvent::ToolCall {
+            tool: "web_search".into(),
+            duration: Duration::from_millis(300),
+            success,
+            error_message: None,
+            cost_usd,
+            task_id: Some("task-1".into()),
+        };
+        observer.record_event(&tool_call(true, Some(0.5)));
+        observer.record_event(&tool_call(false, Some(0.5)));
+        observer.record_event(&tool_call(true, None));
+
+        assert!((tracker.peek_task_cost("task-1").unwrap() - 0.5).abs() < 1e-9);
+        assert!((tracker.peek_task_cost("task-2").unwrap() - 0.18).abs() < 1e-9);
+        assert!((tracker.get_balance() - 999.32).abs() < 1e-9);
+
+        // Explicit calls would count the same usage twice
+        assert_eq!(
+            tracker.track_embedding("text-embedding-3-small", 1_000_000),
+            Charge::default()
+        );
+        assert_eq!(
+            tracker.track_flat_api_call(0.5, "tavily_search"),
+            Charge::default()
+        );
+        assert!((tracker.get_balance() - 999.32).abs() < 1e-9);
+    }
+
+    #[test]
+    fn events_are_ignored_unless_observed_usage_is_enabled() {
+        let tmp = TempDir::new().unwrap();
+        let manual = tracker(&tmp, true, LlmUsageSource::Manual);
+        EconomicObserver::new(Arc::clone(&manual)).record_event(&response(None));
+        assert_eq!(manual.get_balance(), 1000.0);
+        let cost = manual
+            .track_tokens(100_000, 10_000, "agent", None, Duration::ZERO)
+            .unwrap()
+            .cost;
+        assert!((cost - 0.45).abs() < 1e-9);
+
+        let tmp = TempDir::new().unwrap();
+        let disabled = tracker(&tmp, false, LlmUsageSource::Observer);
+        EconomicObserver::new(Arc::clone(&disabled)).record_event(&response(None));
+        assert_eq!(disabled.get_balance(), 1000.0);
+    }
+}
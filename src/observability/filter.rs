@@ -0,0 +1,212 @@
+//! Per-observer filtering of events and metrics.
+//!
+//! A [`FilteredObserver`] passes on only the events and metrics its filters
+//! accept, so an observer that cares about one event type (a cost observer
+//! only needs `LlmResponse`) is not called for everything else. Filters are
+//! either plain predicates or a list of [`ObserverEventKind`]s, which
+//! deserialize from config files as `"llm_response"`, `"tool_call"`, ...
+//! [`CompositeObserver`](super::multi::CompositeObserver) fans out to several
+//! filtered observers.
+
+use super::traits::{Observer, ObserverEvent, ObserverEventKind, ObserverMetric};
+use std::any::Any;
+
+/// Events a [`FilteredObserver`] passes on.
+#[derive(Debug, Clone, Default)]
+pub enum EventFilter {
+    /// Every event
+    #[default]
+    All,
+    /// Events of these kinds
+    Kinds(Vec<ObserverEventKind>),
+    /// Events for which the predicate returns `true`
+    Predicate(fn(&ObserverEvent) -> bool),
+}
+
+impl EventFilter {
+    /// Whether `event` passes the filter.
+    pub fn matches(&self, event: &ObserverEvent) -> bool {
+        match self {
+            Self::All => true,
+            Self::Kinds(kinds) => kinds.contains(&event.kind()),
+            Self::Predicate(predicate) => predicate(event),
+        }
+    }
+}
+
+/// Metrics a [`FilteredObserver`] passes on.
+#[derive(Debug, Clone, Default)]
+pub enum MetricFilter {
+    /// Every metric
+    #[default]
+    All,
+    /// No metrics
+    Ignore,
+    /// Metrics for which the predicate returns `true`
+    Predicate(fn(&ObserverMetric) -> bool),
+}
+
+impl MetricFilter {
+    /// Whether `metric` passes the filter.
+    pub fn matches(&self, metric: &ObserverMetric) -> bool {
+        match self {
+            Self::All => true,
+            Self::Ignore => false,
+            Self::Predicate(predicate) => predicate(metric),
+        }
+    }
+}
+
+/// Observer that forwards only the events and metrics its filters accept.
+///
+/// Flushes always reach the inner observer, and `name` and `as_any` are
+/// the inner observer's, so downcasting sees through the wrapper.
+pub struct FilteredObserver {
+    inner: Box<dyn Observer>,
+    events: EventFilter,
+    metrics: MetricFilter,
+}
+
+impl FilteredObserver {
+    /// Forward the events for which `predicate` returns `true`, and every
+    /// metric.
+    pub fn new(inner: Box<dyn Observer>, predicate: fn(&ObserverEvent) -> bool) -> Self {
+        Self::with_event_filter(inner, EventFilter::Predicate(predicate))
+    }
+
+    /// Forward the events of `kinds`, and every metric.
+    pub fn for_kinds(
+        inner: Box<dyn Observer>,
+        kinds: impl IntoIterator<Item = ObserverEventKind>,
+    ) -> Self {
+        Self::with_event_filter(inner, EventFilter::Kinds(kinds.into_iter().collect()))
+    }
+
+    /// Forward the events `events` accepts, and every metric.
+    pub fn with_event_filter(inner: Box<dyn Observer>, events: EventFilter) -> Self {
+        Self {
+            inner,
+            events,
+            metrics: MetricFilter::All,
+        }
+    }
+
+    /// Forward only the metrics `metrics` accepts.
+    pub fn with_metric_filter(mut self, metrics: MetricFilter) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// The wrapped observer.
+    pub fn inner(&self) -> &dyn Observer {
+        self.inner.as_ref()
+    }
+}
+
+impl Observer for FilteredObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if self.events.matches(event) {
+            self.inner.record_event(event);
+        }
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        if self.metrics.matches(metric) {
+            self.inner.record_metric(metric);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self.inner.as_any()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<ObserverEventKind>>,
+        metrics: Mutex<usize>,
+    }
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            self.events.lock().push(event.kind());
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {
+            *self.metrics.lock() += 1;
+        }
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn tool_call(success: bool) -> ObserverEvent {
+        ObserverEvent::ToolCall {
+            tool: "shell".into(),
+            duration: Duration::from_millis(10),
+            success,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
+        }
+    }
+
+    #[test]
+    fn kind_filter_forwards_only_listed_events() {
+        let recorder = Arc::new(RecordingObserver::default());
+        let observer = FilteredObserver::for_kinds(
+            Box::new(Arc::clone(&recorder)),
+            [ObserverEventKind::ToolCall, ObserverEventKind::Error],
+        )
+        .with_metric_filter(MetricFilter::Ignore);
+
+        observer.record_event(&ObserverEvent::HeartbeatTick);
+        observer.record_event(&tool_call(true));
+        observer.record_event(&ObserverEvent::TurnComplete);
+        observer.record_metric(&ObserverMetric::TokensUsed(10));
+
+        assert_eq!(*recorder.events.lock(), [ObserverEventKind::ToolCall]);
+        assert_eq!(*recorder.metrics.lock(), 0);
+        assert_eq!(observer.name(), "recording");
+        assert!(observer.as_any().downcast_ref::<RecordingObserver>().is_some());
+    }
+
+    #[test]
+    fn predicate_filters_apply_to_events_and_metrics() {
+        let recorder = Arc::new(RecordingObserver::default());
+        let observer = FilteredObserver::new(Box::new(Arc::clone(&recorder)), |event| {
+            matches!(event, ObserverEvent::ToolCall { success: false, .. })
+        })
+        .with_metric_filter(MetricFilter::Predicate(|metric| {
+            matches!(metric, ObserverMetric::QueueDepth(_))
+        }));
+
+        observer.record_event(&tool_call(true));
+        observer.record_event(&tool_call(false));
+        observer.record_metric(&ObserverMetric::TokensUsed(10));
+        observer.record_metric(&ObserverMetric::QueueDepth(3));
+
+        assert_eq!(*recorder.events.lock(), [ObserverEventKind::ToolCall]);
+        assert_eq!(*recorder.metrics.lock(), 1);
+    }
+}
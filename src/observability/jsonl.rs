@@ -0,0 +1,618 @@
+//! Append-only JSONL log of observer events, for replaying sessions offline.
+//!
+//! [`JsonlEventObserver`] writes every [`ObserverEvent`] as one
+//! [`EventLogEntry`] line, numbered and timestamped, rotating the file by
+//! size. [`EventReplayer`] feeds a recorded log back through any observers,
+//! e.g. a fresh `CostObserver` with updated prices to recompute what a
+//! session would have cost.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::borrow::Cow;
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// One line of an event log.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventLogEntry {
+    /// Position in the log, increasing by one per event across rotations
+    pub seq: u64,
+    /// When the event was recorded
+    pub timestamp: DateTime<Utc>,
+    pub event: ObserverEvent,
+}
+
+/// Borrowed form of [`EventLogEntry`], to write events without cloning them.
+#[derive(Serialize)]
+struct EventLogLine<'a> {
+    seq: u64,
+    timestamp: DateTime<Utc>,
+    event: &'a ObserverEvent,
+}
+
+/// When [`JsonlEventObserver`] starts a new file.
+///
+/// The active file is renamed to `<path>.1` and earlier rotations move up
+/// by one (`<path>.2`, ...); the oldest beyond `max_files` is deleted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RotationPolicy {
+    /// Rotate before a line would grow the file past this size; `None`
+    /// never rotates
+    pub max_bytes: Option<u64>,
+    /// Rotated files to keep
+    pub max_files: usize,
+    /// Gzip rotated files (`<path>.1.gz`); needs the `compress` feature
+    pub compress: bool,
+}
+
+impl Default for RotationPolicy {
+    fn default() -> Self {
+        Self {
+            max_bytes: Some(64 * 1024 * 1024),
+            max_files: 5,
+            compress: false,
+        }
+    }
+}
+
+impl RotationPolicy {
+    /// Keep appending to a single file.
+    pub fn never() -> Self {
+        Self {
+            max_bytes: None,
+            ..Self::default()
+        }
+    }
+}
+
+struct EventLogWriter {
+    file: BufWriter<File>,
+    size: u64,
+    next_seq: u64,
+}
+
+/// Writes every observer event to an append-only JSONL file.
+///
+/// Lines are buffered; [`flush`](Observer::flush) or dropping the observer
+/// writes them out. Write errors are logged, never raised to the caller.
+pub struct JsonlEventObserver {
+    path: PathBuf,
+    policy: RotationPolicy,
+    max_text_len: Option<usize>,
+    writer: Mutex<EventLogWriter>,
+}
+
+impl JsonlEventObserver {
+    /// Append events to `path`, rotating it per `rotation_policy`.
+    ///
+    /// An existing log is continued, numbering events after its last one.
+    pub fn new(path: impl Into<PathBuf>, rotation_policy: RotationPolicy) -> Result<Self> {
+        let path = path.into();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        if rotation_policy.compress && !cfg!(feature = "compress") {
+            tracing::warn!(
+                "Event log compression needs the `compress` feature; rotated files of {} stay uncompressed",
+                path.display()
+            );
+        }
+
+        let next_seq = match last_entry(&path)? {
+            Some(entry) => entry.seq + 1,
+            None => 0,
+        };
+        let file = open_append(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self {
+            path,
+            policy: rotation_policy,
+            max_text_len: None,
+            writer: Mutex::new(EventLogWriter {
+                file: BufWriter::new(file),
+                size,
+                next_seq,
+            }),
+        })
+    }
+
+    /// Truncate free-text fields (error messages) to `max_chars` characters.
+    ///
+    /// Events carry no prompt or response content, but provider error
+    /// messages can echo parts of a request.
+    pub fn with_max_text_len(mut self, max_chars: usize) -> Self {
+        self.max_text_len = Some(max_chars);
+        self
+    }
+
+    /// Path of the active log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn append(&self, event: &ObserverEvent) -> Result<()> {
+        let event = match self.max_text_len {
+            Some(max_chars) => Cow::Owned(truncate_text(event.clone(), max_chars)),
+            None => Cow::Borrowed(event),
+        };
+        let mut writer = self.writer.lock();
+        let line = serde_json::to_string(&EventLogLine {
+            seq: writer.next_seq,
+            timestamp: Utc::now(),
+            event: &event,
+        })?;
+        let len = line.len() as u64 + 1;
+        if let Some(max_bytes) = self.policy.max_bytes {
+            if writer.size > 0 && writer.size + len > max_bytes {
+                self.rotate(&mut writer)?;
+            }
+        }
+        writeln!(writer.file, "{line}")?;
+        writer.size += len;
+        writer.next_seq += 1;
+        Ok(())
+    }
+
+    fn rotate(&self, writer: &mut EventLogWriter) -> Result<()> {
+        writer.file.flush()?;
+        let keep = self.policy.max_files;
+        // Drop the oldest rotation and move the others up by one
+        for index in (1..=keep).rev() {
+            for gzip in [false, true] {
+                let from = rotated_path(&self.path, index, gzip);
+                if !from.exists() {
+                    continue;
+                }
+                if index == keep {
+                    fs::remove_file(&from)?;
+                } else {
+                    fs::rename(&from, rotated_path(&self.path, index + 1, gzip))?;
+                }
+            }
+        }
+        if keep == 0 {
+            fs::remove_file(&self.path)?;
+        } else {
+            let rotated = rotated_path(&self.path, 1, false);
+            fs::rename(&self.path, &rotated)?;
+            #[cfg(feature = "compress")]
+            if self.policy.compress {
+                gzip_file(&rotated)?;
+            }
+        }
+        writer.file = BufWriter::new(open_append(&self.path)?);
+        writer.size = 0;
+        Ok(())
+    }
+}
+
+impl Observer for JsonlEventObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if let Err(e) = self.append(event) {
+            tracing::warn!("Failed to write event log {}: {e:#}", self.path.display());
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    fn flush(&self) {
+        if let Err(e) = self.writer.lock().file.flush() {
+            tracing::warn!("Failed to flush event log {}: {e}", self.path.display());
+        }
+    }
+
+    fn name(&self) -> &str {
+        "jsonl"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+/// Plays recorded event logs back through observers.
+pub struct EventReplayer;
+
+impl EventReplayer {
+    /// Feed the events of the log at `path` to `observers` in recorded
+    /// order, then flush them. Returns the number of events replayed.
+    ///
+    /// Rotated files can be replayed the same way, oldest (highest number)
+    /// first; gzipped ones (`.gz`) need the `archive-read` feature. Lines
+    /// that do not parse, such as one cut short by a crash, are skipped.
+    pub fn replay(path: impl AsRef<Path>, observers: &[&dyn Observer]) -> Result<u64> {
+        let path = path.as_ref();
+        let mut replayed = 0;
+        for line in open_log(path)?.lines() {
+            let line = line.with_context(|| format!("Failed to read {}", path.display()))?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str::<EventLogEntry>(&line) {
+                Ok(entry) => {
+                    for observer in observers {
+                        observer.record_event(&entry.event);
+                    }
+                    replayed += 1;
+                }
+                Err(e) => tracing::warn!("Skipping event log line in {}: {e}", path.display()),
+            }
+        }
+        for observer in observers {
+            observer.flush();
+        }
+        Ok(replayed)
+    }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+    let mut options = OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options
+        .open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))
+}
+
+fn open_log(path: &Path) -> Result<Box<dyn BufRead>> {
+    let file = File::open(path).with_context(|| format!("Failed to open {}", path.display()))?;
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        #[cfg(feature = "archive-read")]
+        {
+            return Ok(Box::new(BufReader::new(flate2::read::GzDecoder::new(file))));
+        }
+        #[cfg(not(feature = "archive-read"))]
+        {
+            anyhow::bail!(
+                "Reading gzipped event log {} needs the `archive-read` feature",
+                path.display()
+            );
+        }
+    }
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Last parseable entry of the log at `path`, if any.
+fn last_entry(path: &Path) -> Result<Option<EventLogEntry>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut last = None;
+    for line in open_log(path)?.lines() {
+        if let Ok(entry) = serde_json::from_str::<EventLogEntry>(&line?) {
+            last = Some(entry);
+        }
+    }
+    Ok(last)
+}
+
+/// `<path>.<index>`, with `.gz` appended for a compressed rotation.
+fn rotated_path(path: &Path, index: usize, gzip: bool) -> PathBuf {
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{index}"));
+    if gzip {
+        name.push(".gz");
+    }
+    PathBuf::from(name)
+}
+
+/// Replace `path` with a gzipped copy at `<path>.gz`.
+#[cfg(feature = "compress")]
+fn gzip_file(path: &Path) -> std::io::Result<()> {
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+
+    let mut gzipped = path.as_os_str().to_owned();
+    gzipped.push(".gz");
+    let mut encoder = GzEncoder::new(File::create(gzipped)?, Compression::default());
+    std::io::copy(&mut File::open(path)?, &mut encoder)?;
+    encoder.finish()?;
+    fs::remove_file(path)
+}
+
+/// `event` with its free-text fields cut to `max_chars` characters.
+fn truncate_text(mut event: ObserverEvent, max_chars: usize) -> ObserverEvent {
+    let truncate = |text: &mut String| {
+        if let Some((cut, _)) = text.char_indices().nth(max_chars) {
+            text.truncate(cut);
+            text.push('…');
+        }
+    };
+    match &mut event {
+        ObserverEvent::LlmResponse {
+            error_message: Some(message),
+            ..
+        }
+        | ObserverEvent::ToolCall {
+            error_message: Some(message),
+            ..
+        }
+        | ObserverEvent::Error { message, .. } => truncate(message),
+        _ => {}
+    }
+    event
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::schema::{CostConfig, ModelPricing};
+    use crate::cost::CostTracker;
+    use crate::cost::ImageSizeClass;
+    use crate::observability::CostObserver;
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    /// One event of every variant
+    fn all_events() -> Vec<ObserverEvent> {
+        vec![
+            ObserverEvent::AgentStart {
+                provider: "anthropic".into(),
+                model: "claude-sonnet-4".into(),
+            },
+            ObserverEvent::LlmRequest {
+                provider: "anthropic".into(),
+                model: "claude-sonnet-4".into(),
+                messages_count: 3,
+            },
+            llm_response(1_000_000, 100_000),
+            ObserverEvent::LlmStreamChunk {
+                provider: "openai".into(),
+                model: "gpt-4o".into(),
+                request_id: "req-1".into(),
+                output_tokens_delta: 12,
+            },
+            ObserverEvent::LlmStreamCompleted {
+                provider: "openai".into(),
+                model: "gpt-4o".into(),
+                request_id: "req-1".into(),
+                duration: Duration::from_millis(1500),
+                success: true,
+                final_input_tokens: Some(200),
+                final_output_tokens: None,
+            },
+            ObserverEvent::EmbeddingResponse {
+                provider: "openai".into(),
+                model: "text-embedding-3-small".into(),
+                duration: Duration::from_millis(40),
+                success: true,
+                tokens: Some(512),
+                cost_usd: Some(0.000_01),
+            },
+            ObserverEvent::ImageGeneration {
+                provider: "openai".into(),
+                model: "dall-e-3".into(),
+                duration: Duration::from_secs(8),
+                success: false,
+                count: 2,
+                size_class: ImageSizeClass::Large,
+                cost_usd: None,
+            },
+            ObserverEvent::AgentEnd {
+                provider: "anthropic".into(),
+                model: "claude-sonnet-4".into(),
+                duration: Duration::from_secs(12),
+                tokens_used: Some(1_100_000),
+                cost_usd: Some(4.5),
+            },
+            ObserverEvent::ToolCallStart {
+                tool: "shell".into(),
+            },
+            ObserverEvent::ToolCall {
+                tool: "shell".into(),
+                duration: Duration::from_millis(30),
+                success: false,
+                error_message: Some("exit status 1".into()),
+                cost_usd: Some(0.01),
+                task_id: Some("task-1".into()),
+            },
+            ObserverEvent::TurnComplete,
+            ObserverEvent::ChannelMessage {
+                channel: "telegram".into(),
+                direction: "inbound".into(),
+            },
+            ObserverEvent::HeartbeatTick,
+            ObserverEvent::Error {
+                component: "provider".into(),
+                message: "timeout".into(),
+            },
+        ]
+    }
+
+    fn llm_response(input_tokens: u64, output_tokens: u64) -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(250),
+            success: true,
+            error_message: None,
+            input_tokens: Some(input_tokens),
+            output_tokens: Some(output_tokens),
+            cache_read_tokens: Some(0),
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: Some("session-1".into()),
+        }
+    }
+
+    /// Observer keeping the debug form of every event
+    #[derive(Default)]
+    struct RecordingObserver {
+        events: Mutex<Vec<String>>,
+    }
+
+    impl Observer for RecordingObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            self.events.lock().push(format!("{event:?}"));
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "recording"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn read_entries(path: &Path) -> Vec<EventLogEntry> {
+        fs::read_to_string(path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn every_event_variant_round_trips() {
+        for event in all_events() {
+            let json = serde_json::to_string(&event).unwrap();
+            let parsed: ObserverEvent = serde_json::from_str(&json).unwrap();
+            assert_eq!(format!("{parsed:?}"), format!("{event:?}"), "{json}");
+        }
+        let json = serde_json::to_value(ObserverEvent::TurnComplete).unwrap();
+        assert_eq!(json, serde_json::json!({"type": "turn_complete"}));
+    }
+
+    #[test]
+    fn replay_feeds_recorded_events_in_order() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let observer = JsonlEventObserver::new(&path, RotationPolicy::never()).unwrap();
+        for event in all_events() {
+            observer.record_event(&event);
+        }
+        observer.flush();
+        drop(observer);
+
+        // Reopening continues the numbering
+        let observer = JsonlEventObserver::new(&path, RotationPolicy::never()).unwrap();
+        observer.record_event(&ObserverEvent::HeartbeatTick);
+        observer.flush();
+        let seqs: Vec<u64> = read_entries(&path).iter().map(|entry| entry.seq).collect();
+        assert_eq!(seqs, (0..=all_events().len() as u64).collect::<Vec<_>>());
+
+        let recording = RecordingObserver::default();
+        let replayed = EventReplayer::replay(&path, &[&recording]).unwrap();
+        assert_eq!(replayed, all_events().len() as u64 + 1);
+        let mut expected: Vec<String> = all_events().iter().map(|e| format!("{e:?}")).collect();
+        expected.push(format!("{:?}", ObserverEvent::HeartbeatTick));
+        assert_eq!(*recording.events.lock(), expected);
+    }
+
+    #[test]
+    fn replay_recomputes_costs_at_new_prices() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let observer = JsonlEventObserver::new(&path, RotationPolicy::default()).unwrap();
+        observer.record_event(&llm_response(1_000_000, 100_000));
+        observer.record_event(&llm_response(1_000_000, 100_000));
+        observer.flush();
+
+        let config = CostConfig {
+            enabled: true,
+            ..Default::default()
+        };
+        let tracker = Arc::new(CostTracker::new(config, tmp.path()).unwrap());
+        let prices = HashMap::from([(
+            "anthropic/claude-sonnet-4".to_string(),
+            ModelPricing {
+                input: 1.0,
+                output: 5.0,
+                ..Default::default()
+            },
+        )]);
+        let cost_observer = CostObserver::new(tracker.clone(), prices);
+        assert_eq!(EventReplayer::replay(&path, &[&cost_observer]).unwrap(), 2);
+
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.request_count, 2);
+        assert!((summary.session_cost_usd - 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn size_rotation_keeps_the_newest_files() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let policy = RotationPolicy {
+            max_bytes: Some(1),
+            max_files: 2,
+            compress: false,
+        };
+        let observer = JsonlEventObserver::new(&path, policy).unwrap();
+        // One event per file
+        for _ in 0..4 {
+            observer.record_event(&ObserverEvent::HeartbeatTick);
+        }
+        observer.flush();
+
+        assert_eq!(read_entries(&path)[0].seq, 3);
+        assert_eq!(read_entries(&rotated_path(&path, 1, false))[0].seq, 2);
+        assert_eq!(read_entries(&rotated_path(&path, 2, false))[0].seq, 1);
+        assert!(!rotated_path(&path, 3, false).exists());
+    }
+
+    #[cfg(feature = "compress")]
+    #[test]
+    fn compressed_rotations_replay() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let policy = RotationPolicy {
+            max_bytes: Some(1),
+            max_files: 2,
+            compress: true,
+        };
+        let observer = JsonlEventObserver::new(&path, policy).unwrap();
+        for _ in 0..3 {
+            observer.record_event(&ObserverEvent::HeartbeatTick);
+        }
+        observer.flush();
+
+        assert!(rotated_path(&path, 1, true).exists());
+        assert!(rotated_path(&path, 2, true).exists());
+        let recording = RecordingObserver::default();
+        let replayed = EventReplayer::replay(rotated_path(&path, 2, true), &[&recording]);
+        assert_eq!(replayed.unwrap(), 1);
+    }
+
+    #[test]
+    fn long_error_messages_are_truncated() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("events.jsonl");
+        let observer = JsonlEventObserver::new(&path, RotationPolicy::never())
+            .unwrap()
+            .with_max_text_len(5);
+        observer.record_event(&ObserverEvent::Error {
+            component: "provider".into(),
+            message: "détails of the failed request".into(),
+        });
+        observer.record_event(&ObserverEvent::Error {
+            component: "provider".into(),
+            message: "short".into(),
+        });
+        observer.flush();
+
+        let messages: Vec<String> = read_entries(&path)
+            .into_iter()
+            .filter_map(|entry| match entry.event {
+                ObserverEvent::Error { message, .. } => Some(message),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(messages, ["détai…", "short"]);
+    }
+}
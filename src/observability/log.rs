@@ -34,9 +34,20 @@ impl Observer for LogObserver {
                 tool,
                 duration,
                 success,
+                error_message,
+                cost_usd,
+                task_id,
             } => {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
-                info!(tool = %tool, duration_ms = ms, success = success, "tool.call");
+                info!(
+                    tool = %tool,
+                    duration_ms = ms,
+                    success = success,
+                    error = ?error_message,
+                    cost_usd = ?cost_usd,
+                    task_id = ?task_id,
+                    "tool.call"
+                );
             }
             ObserverEvent::TurnComplete => {
                 info!("turn.complete");
@@ -70,6 +81,10 @@ impl Observer for LogObserver {
                 error_message,
                 input_tokens,
                 output_tokens,
+                cache_read_tokens,
+                cache_write_tokens,
+                reasoning_tokens,
+                session_id,
             } => {
                 let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
                 info!(
@@ -80,9 +95,76 @@ impl Observer for LogObserver {
                     error = ?error_message,
                     input_tokens = ?input_tokens,
                     output_tokens = ?output_tokens,
+                    cache_read_tokens = ?cache_read_tokens,
+                    cache_write_tokens = ?cache_write_tokens,
+                    reasoning_tokens = ?reasoning_tokens,
+                    session_id = ?session_id,
                     "llm.response"
                 );
             }
+            // Chunks are summed into the llm.stream.completed line
+            ObserverEvent::LlmStreamChunk { .. } => {}
+            ObserverEvent::LlmStreamCompleted {
+                provider,
+                model,
+                request_id,
+                duration,
+                success,
+                final_input_tokens,
+                final_output_tokens,
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(
+                    provider = %provider,
+                    model = %model,
+                    request_id = %request_id,
+                    duration_ms = ms,
+                    success = success,
+                    input_tokens = ?final_input_tokens,
+                    output_tokens = ?final_output_tokens,
+                    "llm.stream.completed"
+                );
+            }
+            ObserverEvent::EmbeddingResponse {
+                provider,
+                model,
+                duration,
+                success,
+                tokens,
+                cost_usd,
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(
+                    provider = %provider,
+                    model = %model,
+                    duration_ms = ms,
+                    success = success,
+                    tokens = ?tokens,
+                    cost_usd = ?cost_usd,
+                    "embedding.response"
+                );
+            }
+            ObserverEvent::ImageGeneration {
+                provider,
+                model,
+                duration,
+                success,
+                count,
+                size_class,
+                cost_usd,
+            } => {
+                let ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+                info!(
+                    provider = %provider,
+                    model = %model,
+                    duration_ms = ms,
+                    success = success,
+                    count = count,
+                    size_class = ?size_class,
+                    cost_usd = ?cost_usd,
+                    "image.generation"
+                );
+            }
         }
     }
 
@@ -101,6 +183,13 @@ impl Observer for LogObserver {
             ObserverMetric::QueueDepth(d) => {
                 info!(depth = d, "metric.queue_depth");
             }
+            ObserverMetric::Counter {
+                name,
+                value,
+                labels,
+            } => {
+                info!(metric = %name, value, labels = ?labels, "metric.counter");
+            }
         }
     }
 
@@ -152,6 +241,10 @@ mod tests {
             error_message: None,
             input_tokens: Some(100),
             output_tokens: Some(50),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
         obs.record_event(&ObserverEvent::LlmResponse {
             provider: "openrouter".into(),
@@ -161,11 +254,35 @@ mod tests {
             error_message: Some("rate limited".into()),
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        });
+        obs.record_event(&ObserverEvent::EmbeddingResponse {
+            provider: "openai".into(),
+            model: "text-embedding-3-small".into(),
+            duration: Duration::from_millis(30),
+            success: true,
+            tokens: Some(512),
+            cost_usd: Some(0.00001),
+        });
+        obs.record_event(&ObserverEvent::ImageGeneration {
+            provider: "openai".into(),
+            model: "dall-e-3".into(),
+            duration: Duration::from_secs(8),
+            success: true,
+            count: 2,
+            size_class: crate::cost::ImageSizeClass::Medium,
+            cost_usd: None,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: false,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
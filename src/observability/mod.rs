@@ -1,71 +1,163 @@
+pub mod budget;
 pub mod cost;
+pub mod dispatch;
+pub mod economic;
+pub mod filter;
+pub mod jsonl;
 pub mod log;
 pub mod multi;
 pub mod noop;
 #[cfg(feature = "observability-otel")]
 pub mod otel;
 pub mod prometheus;
+pub mod registry;
 pub mod runtime_trace;
 pub mod traits;
 pub mod verbose;
+pub mod webhook;
 
-pub use cost::CostObserver;
+pub use budget::{BudgetAlert, BudgetGuardObserver, BudgetScope};
+pub use cost::{CostObserver, StreamCostAccumulator};
+pub use dispatch::{ObserverDispatcher, OverflowPolicy, SyncObserverAdapter};
+pub use economic::EconomicObserver;
+pub use filter::{EventFilter, FilteredObserver, MetricFilter};
+pub use jsonl::{EventReplayer, JsonlEventObserver, RotationPolicy};
 #[allow(unused_imports)]
 pub use self::log::LogObserver;
 #[allow(unused_imports)]
-pub use self::multi::MultiObserver;
+pub use self::multi::{CompositeObserver, MultiObserver};
 pub use noop::NoopObserver;
 #[cfg(feature = "observability-otel")]
 pub use otel::OtelObserver;
 pub use prometheus::PrometheusObserver;
-pub use traits::{Observer, ObserverEvent};
+pub use registry::ObserverRegistry;
+pub use traits::{AsyncObserver, MetricSink, Observer, ObserverEvent, ObserverEventKind};
 #[allow(unused_imports)]
 pub use verbose::VerboseObserver;
+pub use webhook::{WebhookConfig, WebhookObserver, WebhookStats};
 
 use crate::config::ObservabilityConfig;
-use crate::config::schema::CostConfig;
-use crate::cost::CostTracker;
+use crate::config::schema::{CostConfig, ModelPricing};
+use crate::cost::{CostTracker, SharedPricing};
+use crate::economic::{EconomicTracker, LlmUsageSource};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Factory: create the right observer from config
 pub fn create_observer(config: &ObservabilityConfig) -> Box<dyn Observer> {
-    create_observer_internal(config)
+    create_observer_internal(config, None)
 }
 
 /// Create an observer stack with optional cost tracking.
 ///
 /// When cost tracking is enabled, wraps the base observer in a MultiObserver
-/// that also includes a CostObserver for recording token usage.
+/// that also includes a CostObserver for recording token usage. Both share
+/// the configured prices, which the OpenTelemetry backend uses to attach
+/// costs to LLM spans, and the CostObserver publishes each priced response
+/// to the base observer as metrics. With `[cost.budget]` enabled, a
+/// BudgetGuardObserver joins the stack too, with or without a cost tracker.
 pub fn create_observer_with_cost_tracking(
     config: &ObservabilityConfig,
     cost_tracker: Option<Arc<CostTracker>>,
     cost_config: &CostConfig,
 ) -> Box<dyn Observer> {
-    let base_observer = create_observer_internal(config);
+    create_observer_with_economic_tracking(config, cost_tracker, cost_config, None)
+}
+
+/// Create an observer stack with optional cost and economic tracking.
+///
+/// Same as [`create_observer_with_cost_tracking`], plus an
+/// EconomicObserver recording usage into `economic_tracker` when that
+/// tracker is enabled and its `llm_usage_source` is `Observer`.
+pub fn create_observer_with_economic_tracking(
+    config: &ObservabilityConfig,
+    cost_tracker: Option<Arc<CostTracker>>,
+    cost_config: &CostConfig,
+    economic_tracker: Option<Arc<EconomicTracker>>,
+) -> Box<dyn Observer> {
+    let cost_tracker = cost_tracker.filter(|_| cost_config.enabled);
+    let economic_tracker = economic_tracker.filter(|tracker| {
+        tracker.is_enabled() && tracker.llm_usage_source() == LlmUsageSource::Observer
+    });
+    if cost_tracker.is_none() && economic_tracker.is_none() && !cost_config.budget.enabled {
+        return create_observer_internal(config, None);
+    }
 
+    let pricing = configured_pricing(cost_config);
+    let mut observers: Vec<Box<dyn Observer>> = Vec::new();
     match cost_tracker {
-        Some(tracker) if cost_config.enabled => {
-            let cost_observer = CostObserver::new(tracker, cost_config.prices.clone());
-            Box::new(MultiObserver::new(vec![
-                base_observer,
-                Box::new(cost_observer),
-            ]))
+        Some(tracker) => {
+            let base: Arc<dyn Observer> =
+                Arc::from(create_observer_internal(config, Some(&pricing)));
+            let cost_observer = CostObserver::with_shared_pricing(tracker, Arc::clone(&pricing))
+                .with_unknown_model_policy(cost_config.unknown_model_policy)
+                .with_failed_response_costs(cost_config.record_failed_response_costs)
+                .with_metric_sink(Arc::new(Arc::clone(&base)));
+            observers.push(Box::new(base));
+            observers.push(Box::new(cost_observer));
         }
-        _ => base_observer,
+        None => observers.push(create_observer_internal(config, None)),
+    }
+    if cost_config.budget.enabled {
+        observers.push(Box::new(BudgetGuardObserver::new(&cost_config.budget, pricing)));
     }
+    if let Some(tracker) = economic_tracker {
+        observers.push(Box::new(EconomicObserver::new(tracker)));
+    }
+    Box::new(MultiObserver::new(observers))
+}
+
+/// Configured model prices, completed with the imported `pricing_file` and
+/// reloaded every `pricing_reload_secs` when running inside a Tokio runtime.
+/// Models without pricing use the `[cost]` default prices.
+fn configured_pricing(cost_config: &CostConfig) -> Arc<SharedPricing> {
+    let prices = cost_config.prices.clone();
+    let pricing = match cost_config.pricing_file.as_deref() {
+        None => Arc::new(SharedPricing::fixed(prices)),
+        Some(path) => match SharedPricing::from_file(prices.clone(), path) {
+            Ok(pricing) => {
+                let pricing = Arc::new(pricing);
+                if cost_config.pricing_reload_secs > 0
+                    && tokio::runtime::Handle::try_current().is_ok()
+                {
+                    pricing
+                        .watch_pricing_file(Duration::from_secs(cost_config.pricing_reload_secs));
+                }
+                pricing
+            }
+            Err(error) => {
+                tracing::warn!("Failed to import model prices from {path}: {error:#}");
+                Arc::new(SharedPricing::fixed(prices))
+            }
+        },
+    };
+    pricing.set_default_pricing(ModelPricing {
+        input: cost_config.default_input_price,
+        output: cost_config.default_output_price,
+        ..Default::default()
+    });
+    pricing
 }
 
-fn create_observer_internal(config: &ObservabilityConfig) -> Box<dyn Observer> {
+fn create_observer_internal(
+    config: &ObservabilityConfig,
+    pricing: Option<&Arc<SharedPricing>>,
+) -> Box<dyn Observer> {
     match config.backend.as_str() {
         "log" => Box::new(LogObserver::new()),
-        "prometheus" => Box::new(PrometheusObserver::new()),
+        "prometheus" => Box::new(
+            PrometheusObserver::new().with_full_model_names(config.prometheus_full_model_names),
+        ),
         "otel" | "opentelemetry" | "otlp" => {
             #[cfg(feature = "observability-otel")]
             match OtelObserver::new(
                 config.otel_endpoint.as_deref(),
                 config.otel_service_name.as_deref(),
             ) {
-                Ok(obs) => {
+                Ok(mut obs) => {
+                    if let Some(pricing) = pricing {
+                        obs = obs.with_pricing(Arc::clone(pricing));
+                    }
                     tracing::info!(
                         endpoint = config
                             .otel_endpoint
@@ -82,6 +174,7 @@ fn create_observer_internal(config: &ObservabilityConfig) -> Box<dyn Observer> {
             }
             #[cfg(not(feature = "observability-otel"))]
             {
+                let _ = pricing;
                 tracing::warn!(
                     "OpenTelemetry backend requested but this build was compiled without `observability-otel`; falling back to noop."
                 );
@@ -213,4 +306,42 @@ mod tests {
         };
         assert_eq!(create_observer(&cfg).name(), "noop");
     }
+
+    #[test]
+    fn economic_observer_joins_the_stack_only_for_observed_usage() {
+        use crate::economic::EconomicConfig;
+
+        let cfg = ObservabilityConfig {
+            backend: "log".into(),
+            ..ObservabilityConfig::default()
+        };
+        let cost_config = CostConfig::default();
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tracker = |llm_usage_source| {
+            let config = EconomicConfig {
+                enabled: true,
+                llm_usage_source,
+                ..EconomicConfig::default()
+            };
+            Arc::new(EconomicTracker::new("test-agent", config, Some(tmp.path().to_path_buf())))
+        };
+
+        let observer = create_observer_with_economic_tracking(
+            &cfg,
+            None,
+            &cost_config,
+            Some(tracker(LlmUsageSource::Observer)),
+        );
+        let stack = observer.as_any().downcast_ref::<MultiObserver>().unwrap();
+        let names: Vec<_> = stack.observers().iter().map(|o| o.name()).collect();
+        assert_eq!(names, ["log", "economic"]);
+
+        let observer = create_observer_with_economic_tracking(
+            &cfg,
+            None,
+            &cost_config,
+            Some(tracker(LlmUsageSource::Manual)),
+        );
+        assert_eq!(observer.name(), "log");
+    }
 }
@@ -1,3 +1,4 @@
+use super::filter::{EventFilter, FilteredObserver};
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
 use std::any::Any;
 
@@ -10,6 +11,11 @@ impl MultiObserver {
     pub fn new(observers: Vec<Box<dyn Observer>>) -> Self {
         Self { observers }
     }
+
+    /// The observers events are fanned out to.
+    pub fn observers(&self) -> &[Box<dyn Observer>] {
+        &self.observers
+    }
 }
 
 impl Observer for MultiObserver {
@@ -40,9 +46,71 @@ impl Observer for MultiObserver {
     }
 }
 
+/// Fan out to observers that each declare the events they want.
+///
+/// Unlike [`MultiObserver`], every observer is wrapped in a
+/// [`FilteredObserver`], so one interested only in `LlmResponse` events is
+/// not called for the rest.
+pub struct CompositeObserver {
+    observers: Vec<FilteredObserver>,
+}
+
+impl CompositeObserver {
+    /// Send each observer the events its filter accepts, and every metric.
+    pub fn new(observers: Vec<(EventFilter, Box<dyn Observer>)>) -> Self {
+        Self {
+            observers: observers
+                .into_iter()
+                .map(|(filter, observer)| FilteredObserver::with_event_filter(observer, filter))
+                .collect(),
+        }
+    }
+
+    /// Add an observer with its own event and metric filters.
+    pub fn with_filtered(mut self, observer: FilteredObserver) -> Self {
+        self.observers.push(observer);
+        self
+    }
+
+    /// The filtered observers events are fanned out to.
+    pub fn observers(&self) -> &[FilteredObserver] {
+        &self.observers
+    }
+}
+
+impl Observer for CompositeObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        for obs in &self.observers {
+            obs.record_event(event);
+        }
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        for obs in &self.observers {
+            obs.record_metric(metric);
+        }
+    }
+
+    fn flush(&self) {
+        for obs in &self.observers {
+            obs.flush();
+        }
+    }
+
+    fn name(&self) -> &str {
+        "composite"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::observability::filter::MetricFilter;
+    use crate::observability::traits::ObserverEventKind;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
     use std::time::Duration;
@@ -160,4 +228,49 @@ mod tests {
         assert_eq!(fc1.load(Ordering::SeqCst), 1);
         assert_eq!(fc2.load(Ordering::SeqCst), 1);
     }
+
+    #[test]
+    fn composite_routes_by_filter() {
+        let heartbeats = Arc::new(AtomicUsize::new(0));
+        let all_events = Arc::new(AtomicUsize::new(0));
+        let metrics = Arc::new(AtomicUsize::new(0));
+        let ignored_metrics = Arc::new(AtomicUsize::new(0));
+        let flushes = Arc::new(AtomicUsize::new(0));
+
+        let m = CompositeObserver::new(vec![(
+            EventFilter::Kinds(vec![ObserverEventKind::HeartbeatTick]),
+            Box::new(CountingObserver::new(
+                heartbeats.clone(),
+                metrics.clone(),
+                flushes.clone(),
+            )),
+        )])
+        .with_filtered(
+            FilteredObserver::with_event_filter(
+                Box::new(CountingObserver::new(
+                    all_events.clone(),
+                    ignored_metrics.clone(),
+                    flushes.clone(),
+                )),
+                EventFilter::All,
+            )
+            .with_metric_filter(MetricFilter::Ignore),
+        );
+
+        m.record_event(&ObserverEvent::HeartbeatTick);
+        m.record_event(&ObserverEvent::TurnComplete);
+        m.record_metric(&ObserverMetric::TokensUsed(100));
+        m.flush();
+
+        assert_eq!(heartbeats.load(Ordering::SeqCst), 1);
+        assert_eq!(all_events.load(Ordering::SeqCst), 2);
+        assert_eq!(metrics.load(Ordering::SeqCst), 1);
+        assert_eq!(ignored_metrics.load(Ordering::SeqCst), 0);
+        assert_eq!(flushes.load(Ordering::SeqCst), 2);
+        assert_eq!(m.name(), "composite");
+        assert!(m.observers()[0]
+            .as_any()
+            .downcast_ref::<CountingObserver>()
+            .is_some());
+    }
 }
@@ -56,6 +56,9 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_secs(1),
             success: true,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "cli".into(),
@@ -1,17 +1,28 @@
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
-use opentelemetry::metrics::{Counter, Gauge, Histogram};
+use crate::cost::{SharedPricing, TokenUsage};
+use opentelemetry::global::BoxedTracer;
+use opentelemetry::metrics::{Counter, Gauge, Histogram, Meter};
 use opentelemetry::trace::{Span, SpanKind, Status, Tracer};
 use opentelemetry::{global, KeyValue};
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::metrics::SdkMeterProvider;
 use opentelemetry_sdk::trace::SdkTracerProvider;
 use std::any::Any;
-use std::time::SystemTime;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
 
 /// OpenTelemetry-backed observer — exports traces and metrics via OTLP.
+///
+/// LLM calls are recorded as GenAI client spans and metrics following the
+/// OpenTelemetry semantic conventions (`gen_ai.request.model`,
+/// `gen_ai.usage.input_tokens`, ...), with their cost as `zeroclaw.cost_usd`
+/// when the observer has [prices](Self::with_pricing).
 pub struct OtelObserver {
-    tracer_provider: SdkTracerProvider,
-    meter_provider: SdkMeterProvider,
+    /// Providers built by [`new`](Self::new); `None` when exporting through
+    /// the host's providers, which the host flushes
+    tracer_provider: Option<SdkTracerProvider>,
+    meter_provider: Option<SdkMeterProvider>,
+    pricing: Option<Arc<SharedPricing>>,
 
     // Metrics instruments
     agent_starts: Counter<u64>,
@@ -27,6 +38,11 @@ pub struct OtelObserver {
     tokens_used: Counter<u64>,
     active_sessions: Gauge<u64>,
     queue_depth: Gauge<u64>,
+
+    // GenAI semantic-convention instruments
+    genai_token_usage: Histogram<u64>,
+    genai_operation_duration: Histogram<f64>,
+    llm_cost: Counter<f64>,
 }
 
 impl OtelObserver {
@@ -80,9 +96,34 @@ impl OtelObserver {
         let meter_provider_clone = meter_provider.clone();
         global::set_meter_provider(meter_provider);
 
-        // ── Create metric instruments ────────────────────────────
-        let meter = global::meter("zeroclaw");
+        Ok(Self::with_instruments(
+            &global::meter("zeroclaw"),
+            Some(tracer_provider),
+            Some(meter_provider_clone),
+        ))
+    }
+
+    /// Create an OTel observer exporting through the tracer and meter
+    /// providers the host installed globally, e.g. its own OTLP pipeline.
+    ///
+    /// Without installed providers the global no-op ones are used, so
+    /// recording costs next to nothing.
+    pub fn from_global() -> Self {
+        Self::with_instruments(&global::meter("zeroclaw"), None, None)
+    }
 
+    /// Price LLM calls with `pricing`, e.g. the prices of a `CostObserver`,
+    /// to attach their cost to spans and the `zeroclaw.llm.cost` counter.
+    pub fn with_pricing(mut self, pricing: Arc<SharedPricing>) -> Self {
+        self.pricing = Some(pricing);
+        self
+    }
+
+    fn with_instruments(
+        meter: &Meter,
+        tracer_provider: Option<SdkTracerProvider>,
+        meter_provider: Option<SdkMeterProvider>,
+    ) -> Self {
         let agent_starts = meter
             .u64_counter("zeroclaw.agent.starts")
             .with_description("Total agent invocations")
@@ -152,9 +193,28 @@ impl OtelObserver {
             .with_description("Current message queue depth")
             .build();
 
-        Ok(Self {
+        let genai_token_usage = meter
+            .u64_histogram("gen_ai.client.token.usage")
+            .with_description("Number of input and output tokens used")
+            .with_unit("{token}")
+            .build();
+
+        let genai_operation_duration = meter
+            .f64_histogram("gen_ai.client.operation.duration")
+            .with_description("GenAI operation duration")
+            .with_unit("s")
+            .build();
+
+        let llm_cost = meter
+            .f64_counter("zeroclaw.llm.cost")
+            .with_description("Cost of LLM calls")
+            .with_unit("USD")
+            .build();
+
+        Self {
             tracer_provider,
-            meter_provider: meter_provider_clone,
+            meter_provider,
+            pricing: None,
             agent_starts,
             agent_duration,
             llm_calls,
@@ -168,7 +228,102 @@ impl OtelObserver {
             tokens_used,
             active_sessions,
             queue_depth,
-        })
+            genai_token_usage,
+            genai_operation_duration,
+            llm_cost,
+        }
+    }
+
+    /// Record an LLM call as a GenAI client span and metrics.
+    #[allow(clippy::too_many_arguments)]
+    fn record_llm_call(
+        &self,
+        tracer: &BoxedTracer,
+        provider: &str,
+        model: &str,
+        duration: Duration,
+        success: bool,
+        error_message: Option<&str>,
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+    ) {
+        let attrs = [
+            KeyValue::new("gen_ai.operation.name", "chat"),
+            KeyValue::new("gen_ai.provider.name", provider.to_string()),
+            KeyValue::new("gen_ai.request.model", model.to_string()),
+        ];
+        let error_type = (!success).then(|| KeyValue::new("error.type", "_OTHER"));
+        let duration_attrs: Vec<KeyValue> =
+            attrs.iter().cloned().chain(error_type.clone()).collect();
+        self.genai_operation_duration
+            .record(duration.as_secs_f64(), &duration_attrs);
+        for (token_type, tokens) in [("input", input_tokens), ("output", output_tokens)] {
+            if let Some(tokens) = tokens {
+                let mut token_attrs = attrs.to_vec();
+                token_attrs.push(KeyValue::new("gen_ai.token.type", token_type));
+                self.genai_token_usage.record(tokens, &token_attrs);
+            }
+        }
+        let cost_usd = self.llm_cost_usd(provider, model, input_tokens, output_tokens);
+        if let Some(cost_usd) = cost_usd {
+            self.llm_cost.add(cost_usd, &attrs);
+        }
+
+        let start_time = SystemTime::now()
+            .checked_sub(duration)
+            .unwrap_or(SystemTime::now());
+        let mut span = tracer.build(
+            opentelemetry::trace::SpanBuilder::from_name(format!("chat {model}"))
+                .with_kind(SpanKind::Client)
+                .with_start_time(start_time),
+        );
+        // No provider installed: nothing is exported, skip the attributes
+        if !span.is_recording() {
+            return;
+        }
+        span.set_attributes(attrs);
+        if let Some(tokens) = input_tokens {
+            span.set_attribute(KeyValue::new("gen_ai.usage.input_tokens", tokens as i64));
+        }
+        if let Some(tokens) = output_tokens {
+            span.set_attribute(KeyValue::new("gen_ai.usage.output_tokens", tokens as i64));
+        }
+        if let Some(cost_usd) = cost_usd {
+            span.set_attribute(KeyValue::new("zeroclaw.cost_usd", cost_usd));
+        }
+        match error_type {
+            None => span.set_status(Status::Ok),
+            Some(error_type) => {
+                span.set_attribute(error_type);
+                let message = error_message.unwrap_or("LLM call failed");
+                span.set_status(Status::error(message.to_string()));
+            }
+        }
+        span.end();
+    }
+
+    /// Cost of an LLM call at the configured prices; `None` without prices
+    /// or token counts.
+    fn llm_cost_usd(
+        &self,
+        provider: &str,
+        model: &str,
+        input_tokens: Option<u64>,
+        output_tokens: Option<u64>,
+    ) -> Option<f64> {
+        let pricing = self.pricing.as_ref()?;
+        if input_tokens.is_none() && output_tokens.is_none() {
+            return None;
+        }
+        let price = pricing.snapshot().resolve(provider, model);
+        let usage = TokenUsage::new(
+            model,
+            input_tokens.unwrap_or(0),
+            output_tokens.unwrap_or(0),
+            price.input,
+            price.output,
+        );
+        Some(usage.cost_usd)
     }
 }
 
@@ -187,16 +342,24 @@ impl Observer for OtelObserver {
                 );
             }
             ObserverEvent::LlmRequest { .. }
+            | ObserverEvent::LlmStreamChunk { .. }
+            | ObserverEvent::LlmStreamCompleted { .. }
             | ObserverEvent::ToolCallStart { .. }
-            | ObserverEvent::TurnComplete => {}
+            | ObserverEvent::TurnComplete
+            | ObserverEvent::EmbeddingResponse { .. }
+            | ObserverEvent::ImageGeneration { .. } => {}
             ObserverEvent::LlmResponse {
                 provider,
                 model,
                 duration,
                 success,
-                error_message: _,
-                input_tokens: _,
-                output_tokens: _,
+                error_message,
+                input_tokens,
+                output_tokens,
+                cache_read_tokens: _,
+                cache_write_tokens: _,
+                reasoning_tokens: _,
+                session_id: _,
             } => {
                 let secs = duration.as_secs_f64();
                 let attrs = [
@@ -207,27 +370,16 @@ impl Observer for OtelObserver {
                 self.llm_calls.add(1, &attrs);
                 self.llm_duration.record(secs, &attrs);
 
-                // Create a completed span for visibility in trace backends.
-                let start_time = SystemTime::now()
-                    .checked_sub(*duration)
-                    .unwrap_or(SystemTime::now());
-                let mut span = tracer.build(
-                    opentelemetry::trace::SpanBuilder::from_name("llm.call")
-                        .with_kind(SpanKind::Internal)
-                        .with_start_time(start_time)
-                        .with_attributes(vec![
-                            KeyValue::new("provider", provider.clone()),
-                            KeyValue::new("model", model.clone()),
-                            KeyValue::new("success", *success),
-                            KeyValue::new("duration_s", secs),
-                        ]),
+                self.record_llm_call(
+                    &tracer,
+                    provider,
+                    model,
+                    *duration,
+                    *success,
+                    error_message.as_deref(),
+                    *input_tokens,
+                    *output_tokens,
                 );
-                if *success {
-                    span.set_status(Status::Ok);
-                } else {
-                    span.set_status(Status::error(""));
-                }
-                span.end();
             }
             ObserverEvent::AgentEnd {
                 provider,
@@ -274,6 +426,7 @@ impl Observer for OtelObserver {
                 tool,
                 duration,
                 success,
+                ..
             } => {
                 let secs = duration.as_secs_f64();
                 let start_time = SystemTime::now()
@@ -352,14 +505,16 @@ impl Observer for OtelObserver {
             ObserverMetric::QueueDepth(d) => {
                 self.queue_depth.record(*d as u64, &[]);
             }
+            // LLM costs and tokens are recorded from the response events
+            ObserverMetric::Counter { .. } => {}
         }
     }
 
     fn flush(&self) {
-        if let Err(e) = self.tracer_provider.force_flush() {
+        if let Some(Err(e)) = self.tracer_provider.as_ref().map(SdkTracerProvider::force_flush) {
             tracing::warn!("OTel trace flush failed: {e}");
         }
-        if let Err(e) = self.meter_provider.force_flush() {
+        if let Some(Err(e)) = self.meter_provider.as_ref().map(SdkMeterProvider::force_flush) {
             tracing::warn!("OTel metric flush failed: {e}");
         }
     }
@@ -376,7 +531,6 @@ impl Observer for OtelObserver {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use std::time::Duration;
 
     // Note: OtelObserver::new() requires an OTLP endpoint.
     // In tests we verify the struct creation fails gracefully
@@ -417,6 +571,10 @@ mod tests {
             error_message: None,
             input_tokens: Some(100),
             output_tokens: Some(50),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
         obs.record_event(&ObserverEvent::AgentEnd {
             provider: "openrouter".into(),
@@ -439,11 +597,17 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: true,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "file_read".into(),
             duration: Duration::from_millis(5),
             success: false,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::TurnComplete);
         obs.record_event(&ObserverEvent::ChannelMessage {
@@ -497,6 +661,10 @@ mod tests {
             error_message: Some("404 Not Found".into()),
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
     }
 
@@ -518,6 +686,76 @@ mod tests {
         obs.record_metric(&ObserverMetric::QueueDepth(0));
     }
 
+    fn llm_response(success: bool, error_message: Option<&str>) -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(300),
+            success,
+            error_message: error_message.map(Into::into),
+            input_tokens: Some(1_000_000),
+            output_tokens: Some(100_000),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        }
+    }
+
+    fn sonnet_pricing() -> Arc<SharedPricing> {
+        let prices = std::collections::HashMap::from([(
+            "anthropic/claude-sonnet-4".to_string(),
+            crate::config::schema::ModelPricing {
+                input: 3.0,
+                output: 15.0,
+                ..Default::default()
+            },
+        )]);
+        Arc::new(SharedPricing::fixed(prices))
+    }
+
+    #[test]
+    fn llm_calls_are_priced_with_shared_pricing() {
+        let obs = test_observer();
+        assert!(obs
+            .llm_cost_usd("anthropic", "claude-sonnet-4", Some(1000), Some(10))
+            .is_none());
+
+        let obs = obs.with_pricing(sonnet_pricing());
+        let cost = obs
+            .llm_cost_usd("anthropic", "claude-sonnet-4", Some(1_000_000), Some(100_000))
+            .unwrap();
+        assert!((cost - 4.5).abs() < 1e-9);
+        assert!(obs
+            .llm_cost_usd("anthropic", "claude-sonnet-4", None, None)
+            .is_none());
+
+        obs.record_event(&llm_response(true, None));
+        obs.record_event(&llm_response(false, Some("rate limited")));
+        obs.record_event(&llm_response(false, None));
+    }
+
+    #[test]
+    fn global_observer_without_provider_records_without_panic() {
+        let obs = OtelObserver::from_global().with_pricing(sonnet_pricing());
+        obs.record_event(&llm_response(true, None));
+        obs.record_event(&llm_response(false, Some("503 Service Unavailable")));
+        obs.record_metric(&ObserverMetric::TokensUsed(10));
+        obs.flush();
+    }
+
+    #[test]
+    fn records_after_exporter_shutdown_without_panic() {
+        let obs = test_observer().with_pricing(sonnet_pricing());
+        let _ = obs.tracer_provider.as_ref().unwrap().shutdown();
+        let _ = obs.meter_provider.as_ref().unwrap().shutdown();
+
+        obs.record_event(&llm_response(true, None));
+        obs.record_event(&llm_response(false, Some("connection reset")));
+        obs.record_metric(&ObserverMetric::QueueDepth(1));
+        obs.flush();
+    }
+
     #[test]
     fn otel_observer_creation_with_valid_endpoint_succeeds() {
         // Even though endpoint is unreachable, creation should succeed
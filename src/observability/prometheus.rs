@@ -1,15 +1,35 @@
+use super::multi::MultiObserver;
 use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use crate::economic::SurvivalStatus;
 use prometheus::{
-    Encoder, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec, Registry, TextEncoder,
+    CounterVec, Encoder, Gauge, GaugeVec, Histogram, HistogramOpts, HistogramVec, IntCounterVec,
+    IntGaugeVec, Registry, TextEncoder,
 };
 
+/// Statuses exported by the `zeroclaw_survival_status` gauge.
+const SURVIVAL_STATUSES: [SurvivalStatus; 5] = [
+    SurvivalStatus::Thriving,
+    SurvivalStatus::Stable,
+    SurvivalStatus::Struggling,
+    SurvivalStatus::Critical,
+    SurvivalStatus::Bankrupt,
+];
+
 /// Prometheus-backed observer — exposes metrics for scraping via `/metrics`.
+///
+/// Model labels are normalized to the model family (see [`model_family`])
+/// to keep label cardinality bounded; enable
+/// [`with_full_model_names`](Self::with_full_model_names) to keep dated
+/// names.
 pub struct PrometheusObserver {
     registry: Registry,
+    full_model_names: bool,
 
     // Counters
     agent_starts: IntCounterVec,
     llm_requests: IntCounterVec,
+    llm_tokens: IntCounterVec,
+    llm_cost_usd: CounterVec,
     tokens_input_total: IntCounterVec,
     tokens_output_total: IntCounterVec,
     tool_calls: IntCounterVec,
@@ -19,6 +39,7 @@ pub struct PrometheusObserver {
 
     // Histograms
     agent_duration: HistogramVec,
+    llm_request_duration: HistogramVec,
     tool_duration: HistogramVec,
     request_latency: Histogram,
 
@@ -26,6 +47,8 @@ pub struct PrometheusObserver {
     tokens_used: prometheus::IntGauge,
     active_sessions: GaugeVec,
     queue_depth: GaugeVec,
+    economic_balance: Gauge,
+    survival_status: IntGaugeVec,
 }
 
 impl PrometheusObserver {
@@ -44,6 +67,21 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let llm_tokens = IntCounterVec::new(
+            prometheus::Opts::new("zeroclaw_llm_tokens_total", "Total LLM tokens by direction"),
+            &["direction"],
+        )
+        .expect("valid metric");
+
+        let llm_cost_usd = CounterVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_llm_cost_usd_total",
+                "Total LLM cost in USD priced per response or reported by finished agent runs",
+            ),
+            &["provider", "model"],
+        )
+        .expect("valid metric");
+
         let tokens_input_total = IntCounterVec::new(
             prometheus::Opts::new("zeroclaw_tokens_input_total", "Total input tokens consumed"),
             &["provider", "model"],
@@ -91,6 +129,16 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let llm_request_duration = HistogramVec::new(
+            HistogramOpts::new(
+                "zeroclaw_llm_request_duration_seconds",
+                "LLM request duration in seconds",
+            )
+            .buckets(vec![0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0, 120.0]),
+            &["provider", "model"],
+        )
+        .expect("valid metric");
+
         let tool_duration = HistogramVec::new(
             HistogramOpts::new(
                 "zeroclaw_tool_duration_seconds",
@@ -128,9 +176,26 @@ impl PrometheusObserver {
         )
         .expect("valid metric");
 
+        let economic_balance = Gauge::new(
+            "zeroclaw_economic_balance_usd",
+            "Economic tracker balance in USD",
+        )
+        .expect("valid metric");
+
+        let survival_status = IntGaugeVec::new(
+            prometheus::Opts::new(
+                "zeroclaw_survival_status",
+                "Economic survival status; 1 for the current status, 0 for the others",
+            ),
+            &["status"],
+        )
+        .expect("valid metric");
+
         // Register all metrics
         registry.register(Box::new(agent_starts.clone())).ok();
         registry.register(Box::new(llm_requests.clone())).ok();
+        registry.register(Box::new(llm_tokens.clone())).ok();
+        registry.register(Box::new(llm_cost_usd.clone())).ok();
         registry.register(Box::new(tokens_input_total.clone())).ok();
         registry
             .register(Box::new(tokens_output_total.clone()))
@@ -140,16 +205,24 @@ impl PrometheusObserver {
         registry.register(Box::new(heartbeat_ticks.clone())).ok();
         registry.register(Box::new(errors.clone())).ok();
         registry.register(Box::new(agent_duration.clone())).ok();
+        registry
+            .register(Box::new(llm_request_duration.clone()))
+            .ok();
         registry.register(Box::new(tool_duration.clone())).ok();
         registry.register(Box::new(request_latency.clone())).ok();
         registry.register(Box::new(tokens_used.clone())).ok();
         registry.register(Box::new(active_sessions.clone())).ok();
         registry.register(Box::new(queue_depth.clone())).ok();
+        registry.register(Box::new(economic_balance.clone())).ok();
+        registry.register(Box::new(survival_status.clone())).ok();
 
         Self {
             registry,
+            full_model_names: false,
             agent_starts,
             llm_requests,
+            llm_tokens,
+            llm_cost_usd,
             tokens_input_total,
             tokens_output_total,
             tool_calls,
@@ -157,11 +230,47 @@ impl PrometheusObserver {
             heartbeat_ticks,
             errors,
             agent_duration,
+            llm_request_duration,
             tool_duration,
             request_latency,
             tokens_used,
             active_sessions,
             queue_depth,
+            economic_balance,
+            survival_status,
+        }
+    }
+
+    /// Label metrics with the full model name, e.g.
+    /// `claude-sonnet-4-20250514` instead of `claude-sonnet-4`.
+    pub fn with_full_model_names(mut self, enabled: bool) -> Self {
+        self.full_model_names = enabled;
+        self
+    }
+
+    /// Registry holding every metric, for hosts that gather it with their
+    /// own metrics.
+    pub fn registry(&self) -> &Registry {
+        &self.registry
+    }
+
+    /// Set the economic balance and survival status gauges, e.g. from an
+    /// `EconomicTracker` status-change callback or a periodic poll.
+    pub fn record_economic_state(&self, balance: f64, status: SurvivalStatus) {
+        self.economic_balance.set(balance);
+        for candidate in SURVIVAL_STATUSES {
+            let label = candidate.to_string().to_lowercase();
+            self.survival_status
+                .with_label_values(&[label.as_str()])
+                .set(i64::from(candidate == status));
+        }
+    }
+
+    fn model_label<'a>(&self, model: &'a str) -> &'a str {
+        if self.full_model_names {
+            model
+        } else {
+            model_family(model)
         }
     }
 
@@ -173,6 +282,82 @@ impl PrometheusObserver {
         encoder.encode(&families, &mut buf).unwrap_or_default();
         String::from_utf8(buf).unwrap_or_default()
     }
+
+    /// The Prometheus observer in `observer`, which may be wrapped in a
+    /// [`MultiObserver`].
+    pub fn find(observer: &dyn Observer) -> Option<&Self> {
+        let any = observer.as_any();
+        if let Some(prometheus) = any.downcast_ref::<Self>() {
+            return Some(prometheus);
+        }
+        any.downcast_ref::<MultiObserver>()?
+            .observers()
+            .iter()
+            .find_map(|inner| Self::find(inner.as_ref()))
+    }
+}
+
+#[cfg(feature = "prometheus-listener")]
+impl PrometheusObserver {
+    /// Serve the metrics at `GET /metrics` on `listener`, for hosts that do
+    /// not run the gateway. Runs until the listener fails.
+    pub async fn serve(
+        self: std::sync::Arc<Self>,
+        listener: tokio::net::TcpListener,
+    ) -> std::io::Result<()> {
+        use axum::{http::header, routing::get, Router};
+
+        let app = Router::new().route(
+            "/metrics",
+            get(move || {
+                let observer = std::sync::Arc::clone(&self);
+                async move {
+                    (
+                        [(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+                        observer.encode(),
+                    )
+                }
+            }),
+        );
+        axum::serve(listener, app).await
+    }
+}
+
+/// Model name without its release date or snapshot suffix, so dated
+/// releases share one label: `claude-sonnet-4-20250514`,
+/// `gpt-4o-2024-08-06`, and `gemini-1.5-pro@002` all keep only the family
+/// name.
+fn model_family(model: &str) -> &str {
+    let model = model.split_once('@').map_or(model, |(family, _)| family);
+    let model = model.strip_suffix("-latest").unwrap_or(model);
+
+    // -YYYY-MM-DD
+    let iso_date = model.len().checked_sub(11).and_then(|start| {
+        let suffix = model.get(start..)?.as_bytes();
+        suffix
+            .iter()
+            .enumerate()
+            .all(|(i, byte)| match i {
+                0 | 5 | 8 => *byte == b'-',
+                _ => byte.is_ascii_digit(),
+            })
+            .then(|| &model[..start])
+    });
+    if let Some(family) = iso_date.filter(|family| !family.is_empty()) {
+        return family;
+    }
+
+    // -20250514, -0613, -002
+    match model.rsplit_once('-') {
+        Some((family, suffix))
+            if !family.is_empty()
+                && suffix.len() >= 3
+                && suffix.bytes().all(|byte| byte.is_ascii_digit()) =>
+        {
+            family
+        }
+        _ => model,
+    }
 }
 
 impl Observer for PrometheusObserver {
@@ -180,7 +365,7 @@ impl Observer for PrometheusObserver {
         match event {
             ObserverEvent::AgentStart { provider, model } => {
                 self.agent_starts
-                    .with_label_values(&[provider, model])
+                    .with_label_values(&[provider.as_str(), self.model_label(model)])
                     .inc();
             }
             ObserverEvent::AgentEnd {
@@ -188,46 +373,64 @@ impl Observer for PrometheusObserver {
                 model,
                 duration,
                 tokens_used,
-                cost_usd: _,
+                cost_usd,
             } => {
+                let model = self.model_label(model);
                 // Agent duration is recorded via the histogram with provider/model labels
                 self.agent_duration
-                    .with_label_values(&[provider, model])
+                    .with_label_values(&[provider.as_str(), model])
                     .observe(duration.as_secs_f64());
                 if let Some(t) = tokens_used {
                     self.tokens_used.set(i64::try_from(*t).unwrap_or(i64::MAX));
                 }
+                if let Some(cost) = cost_usd.filter(|cost| cost.is_finite() && *cost > 0.0) {
+                    self.llm_cost_usd
+                        .with_label_values(&[provider.as_str(), model])
+                        .inc_by(cost);
+                }
             }
             ObserverEvent::LlmResponse {
                 provider,
                 model,
+                duration,
                 success,
                 input_tokens,
                 output_tokens,
                 ..
             } => {
+                let model = self.model_label(model);
                 let success_str = if *success { "true" } else { "false" };
                 self.llm_requests
-                    .with_label_values(&[provider.as_str(), model.as_str(), success_str])
+                    .with_label_values(&[provider.as_str(), model, success_str])
                     .inc();
+                self.llm_request_duration
+                    .with_label_values(&[provider.as_str(), model])
+                    .observe(duration.as_secs_f64());
                 if let Some(input) = input_tokens {
                     self.tokens_input_total
-                        .with_label_values(&[provider.as_str(), model.as_str()])
+                        .with_label_values(&[provider.as_str(), model])
                         .inc_by(*input);
+                    self.llm_tokens.with_label_values(&["input"]).inc_by(*input);
                 }
                 if let Some(output) = output_tokens {
                     self.tokens_output_total
-                        .with_label_values(&[provider.as_str(), model.as_str()])
+                        .with_label_values(&[provider.as_str(), model])
                         .inc_by(*output);
+                    self.llm_tokens.with_label_values(&["output"]).inc_by(*output);
                 }
             }
             ObserverEvent::ToolCallStart { tool: _ }
             | ObserverEvent::TurnComplete
-            | ObserverEvent::LlmRequest { .. } => {}
+            | ObserverEvent::LlmRequest { .. }
+            | ObserverEvent::LlmStreamChunk { .. }
+            | ObserverEvent::LlmStreamCompleted { .. }
+            | ObserverEvent::EmbeddingResponse { .. }
+            | ObserverEvent::ImageGeneration { .. } => {}
             ObserverEvent::ToolCall {
                 tool,
                 duration,
                 success,
+                ..
             } => {
                 let success_str = if *success { "true" } else { "false" };
                 self.tool_calls
@@ -272,6 +475,16 @@ impl Observer for PrometheusObserver {
                     .with_label_values(&[] as &[&str])
                     .set(*d as f64);
             }
+            // Token counters are already counted from `LlmResponse` events
+            ObserverMetric::Counter { name, value, .. } => {
+                if name == "llm.cost_usd" && value.is_finite() && *value > 0.0 {
+                    let provider = metric.label("provider").unwrap_or_default();
+                    let model = self.model_label(metric.label("model").unwrap_or_default());
+                    self.llm_cost_usd
+                        .with_label_values(&[provider, model])
+                        .inc_by(*value);
+                }
+            }
         }
     }
 
@@ -319,11 +532,17 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: true,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "file_read".into(),
             duration: Duration::from_millis(5),
             success: false,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
@@ -357,6 +576,9 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_millis(100),
             success: true,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::HeartbeatTick);
         obs.record_metric(&ObserverMetric::RequestLatency(Duration::from_millis(250)));
@@ -388,16 +610,25 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: true,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: true,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
         obs.record_event(&ObserverEvent::ToolCall {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: false,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         });
 
         let output = obs.encode();
@@ -448,6 +679,10 @@ mod tests {
             error_message: None,
             input_tokens: Some(100),
             output_tokens: Some(50),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
         obs.record_event(&ObserverEvent::LlmResponse {
             provider: "openrouter".into(),
@@ -457,6 +692,10 @@ mod tests {
             error_message: None,
             input_tokens: Some(200),
             output_tokens: Some(80),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
 
         let output = obs.encode();
@@ -483,6 +722,10 @@ mod tests {
             error_message: Some("timeout".into()),
             input_tokens: None,
             output_tokens: None,
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
         });
 
         let output = obs.encode();
@@ -493,4 +736,144 @@ mod tests {
         assert!(!output.contains("zeroclaw_tokens_input_total{"));
         assert!(!output.contains("zeroclaw_tokens_output_total{"));
     }
+
+    #[test]
+    fn model_labels_are_normalized_to_the_family() {
+        for (model, family) in [
+            ("claude-sonnet-4-20250514", "claude-sonnet-4"),
+            ("gpt-4o-2024-08-06", "gpt-4o"),
+            ("gpt-3.5-turbo-0125", "gpt-3.5-turbo"),
+            ("gemini-1.5-pro@002", "gemini-1.5-pro"),
+            ("claude-3-5-sonnet-latest", "claude-3-5-sonnet"),
+            ("claude-3-5-sonnet", "claude-3-5-sonnet"),
+            ("llama-3.1-405b", "llama-3.1-405b"),
+            ("20250514", "20250514"),
+        ] {
+            assert_eq!(model_family(model), family, "{model}");
+        }
+    }
+
+    fn llm_response(model: &str) -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: model.into(),
+            duration: Duration::from_millis(1500),
+            success: true,
+            error_message: None,
+            input_tokens: Some(1000),
+            output_tokens: Some(200),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        }
+    }
+
+    #[test]
+    fn exposition_includes_llm_cost_and_duration_metrics() {
+        let obs = PrometheusObserver::new();
+        obs.record_event(&llm_response("claude-sonnet-4-20250514"));
+        obs.record_event(&llm_response("claude-sonnet-4"));
+        obs.record_event(&ObserverEvent::AgentEnd {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4-20250514".into(),
+            duration: Duration::from_secs(3),
+            tokens_used: Some(2400),
+            cost_usd: Some(0.25),
+        });
+
+        let output = obs.encode();
+        assert!(output.contains(
+            r#"zeroclaw_llm_requests_total{model="claude-sonnet-4",provider="anthropic",success="true"} 2"#
+        ));
+        assert!(output.contains(r#"zeroclaw_llm_tokens_total{direction="input"} 2000"#));
+        assert!(output.contains(r#"zeroclaw_llm_tokens_total{direction="output"} 400"#));
+        assert!(output.contains(
+            r#"zeroclaw_llm_cost_usd_total{model="claude-sonnet-4",provider="anthropic"} 0.25"#
+        ));
+        assert!(output.contains(
+            r#"zeroclaw_llm_request_duration_seconds_bucket{model="claude-sonnet-4",provider="anthropic",le="2.5"} 2"#
+        ));
+        assert!(output.contains(
+            r#"zeroclaw_llm_request_duration_seconds_bucket{model="claude-sonnet-4",provider="anthropic",le="1"} 0"#
+        ));
+    }
+
+    #[test]
+    fn priced_response_counter_feeds_the_cost_metric() {
+        let obs = PrometheusObserver::new();
+        let counter = |name: &str, value: f64| ObserverMetric::Counter {
+            name: name.into(),
+            value,
+            labels: vec![
+                ("provider".into(), "anthropic".into()),
+                ("model".into(), "claude-sonnet-4-20250514".into()),
+            ],
+        };
+        obs.record_metric(&counter("llm.cost_usd", 0.5));
+        obs.record_metric(&counter("llm.cost_usd", 0.25));
+        obs.record_metric(&counter("llm.input_tokens", 1000.0));
+
+        let output = obs.encode();
+        assert!(output.contains(
+            r#"zeroclaw_llm_cost_usd_total{model="claude-sonnet-4",provider="anthropic"} 0.75"#
+        ));
+        assert!(!output.contains(r#"zeroclaw_llm_tokens_total{direction="input"}"#));
+    }
+
+    #[test]
+    fn full_model_names_keep_dated_labels() {
+        let obs = PrometheusObserver::new().with_full_model_names(true);
+        obs.record_event(&llm_response("claude-sonnet-4-20250514"));
+
+        let output = obs.encode();
+        assert!(output.contains(
+            r#"zeroclaw_tokens_input_total{model="claude-sonnet-4-20250514",provider="anthropic"} 1000"#
+        ));
+    }
+
+    #[test]
+    fn economic_state_sets_balance_and_status_gauges() {
+        let obs = PrometheusObserver::new();
+        obs.record_economic_state(412.5, SurvivalStatus::Stable);
+        obs.record_economic_state(87.5, SurvivalStatus::Struggling);
+
+        let output = obs.encode();
+        assert!(output.contains("zeroclaw_economic_balance_usd 87.5"));
+        assert!(output.contains(r#"zeroclaw_survival_status{status="struggling"} 1"#));
+        assert!(output.contains(r#"zeroclaw_survival_status{status="stable"} 0"#));
+        assert!(output.contains(r#"zeroclaw_survival_status{status="bankrupt"} 0"#));
+    }
+
+    #[test]
+    fn find_looks_inside_multi_observers() {
+        use crate::observability::NoopObserver;
+
+        let multi = MultiObserver::new(vec![
+            Box::new(NoopObserver),
+            Box::new(PrometheusObserver::new()),
+        ]);
+        assert!(PrometheusObserver::find(&multi).is_some());
+        assert!(PrometheusObserver::find(&NoopObserver).is_none());
+    }
+
+    #[cfg(feature = "prometheus-listener")]
+    #[tokio::test]
+    async fn listener_serves_the_exposition_format() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let obs = std::sync::Arc::new(PrometheusObserver::new());
+        obs.record_event(&ObserverEvent::HeartbeatTick);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(obs.serve(listener));
+
+        let mut stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let request = "GET /metrics HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n";
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200"));
+        assert!(response.contains("zeroclaw_heartbeat_ticks_total 1"));
+    }
 }
@@ -0,0 +1,217 @@
+//! Observers added and removed by name while the agent runs.
+//!
+//! [`ObserverRegistry`] lets an admin command switch expensive observers
+//! (event log, webhook) on and off without restarting a long-running agent.
+//! Dispatch loads an immutable snapshot of the observer list without
+//! taking a lock, and an observer removed while an event is being
+//! dispatched still receives that event.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use arc_swap::ArcSwap;
+use parking_lot::Mutex;
+use std::any::Any;
+use std::sync::Arc;
+
+type ObserverList = Arc<Vec<Arc<dyn Observer>>>;
+
+/// Runtime-mutable set of observers, keyed by [`Observer::name`].
+///
+/// Clones share the same set. Changes replace the whole list, so a dispatch
+/// that already took its snapshot finishes with the observers it started
+/// with.
+#[derive(Clone, Default)]
+pub struct ObserverRegistry {
+    observers: Arc<ArcSwap<Vec<Arc<dyn Observer>>>>,
+    /// Serializes changes, so concurrent ones never drop each other's update
+    updates: Arc<Mutex<()>>,
+}
+
+impl ObserverRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `observer` under its name.
+    ///
+    /// # Errors
+    /// Fails if an observer with the same name is registered.
+    pub fn register(&self, observer: Box<dyn Observer>) -> anyhow::Result<()> {
+        let _update = self.updates.lock();
+        let observers = self.observers.load();
+        let name = observer.name();
+        if observers.iter().any(|registered| registered.name() == name) {
+            anyhow::bail!("An observer named {name:?} is already registered");
+        }
+        let mut updated = Vec::clone(&observers);
+        updated.push(Arc::from(observer));
+        self.observers.store(Arc::new(updated));
+        Ok(())
+    }
+
+    /// Remove the observer named `name` and return it, e.g. to flush it.
+    ///
+    /// Dispatches already in flight still deliver their event to it.
+    pub fn unregister(&self, name: &str) -> Option<Arc<dyn Observer>> {
+        let _update = self.updates.lock();
+        let observers = self.observers.load();
+        let index = observers
+            .iter()
+            .position(|observer| observer.name() == name)?;
+        let mut updated = Vec::clone(&observers);
+        let removed = updated.remove(index);
+        self.observers.store(Arc::new(updated));
+        Some(removed)
+    }
+
+    /// Names of the registered observers, in registration order.
+    pub fn list(&self) -> Vec<String> {
+        self.snapshot()
+            .iter()
+            .map(|observer| observer.name().to_string())
+            .collect()
+    }
+
+    /// Whether an observer named `name` is registered.
+    pub fn contains(&self, name: &str) -> bool {
+        self.snapshot()
+            .iter()
+            .any(|observer| observer.name() == name)
+    }
+
+    /// The observers registered right now.
+    pub fn snapshot(&self) -> ObserverList {
+        self.observers.load_full()
+    }
+}
+
+impl Observer for ObserverRegistry {
+    fn record_event(&self, event: &ObserverEvent) {
+        for obs in self.snapshot().iter() {
+            obs.record_event(event);
+        }
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        for obs in self.snapshot().iter() {
+            obs.record_metric(metric);
+        }
+    }
+
+    fn flush(&self) {
+        for obs in self.snapshot().iter() {
+            obs.flush();
+        }
+    }
+
+    fn name(&self) -> &str {
+        "registry"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct CountingObserver {
+        name: &'static str,
+        events: Arc<AtomicUsize>,
+    }
+
+    impl Observer for CountingObserver {
+        fn record_event(&self, _event: &ObserverEvent) {
+            self.events.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    /// Unregisters `target` from `registry` when it sees an event.
+    struct UnregisteringObserver {
+        registry: ObserverRegistry,
+        target: &'static str,
+    }
+
+    impl Observer for UnregisteringObserver {
+        fn record_event(&self, _event: &ObserverEvent) {
+            self.registry.unregister(self.target);
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "unregistering"
+        }
+
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+    }
+
+    fn counting(name: &'static str) -> (Box<dyn Observer>, Arc<AtomicUsize>) {
+        let events = Arc::new(AtomicUsize::new(0));
+        let observer = CountingObserver {
+            name,
+            events: Arc::clone(&events),
+        };
+        (Box::new(observer), events)
+    }
+
+    #[test]
+    fn observers_are_registered_and_removed_by_name() {
+        let registry = ObserverRegistry::new();
+        let (cost, cost_events) = counting("cost");
+        let (webhook, webhook_events) = counting("webhook");
+        registry.register(cost).unwrap();
+        registry.register(webhook).unwrap();
+        assert_eq!(registry.list(), ["cost", "webhook"]);
+
+        let (duplicate, _) = counting("cost");
+        let err = registry.register(duplicate).unwrap_err();
+        assert!(err.to_string().contains("already registered"));
+
+        registry.record_event(&ObserverEvent::HeartbeatTick);
+        let removed = registry.unregister("webhook").unwrap();
+        assert_eq!(removed.name(), "webhook");
+        assert!(registry.unregister("webhook").is_none());
+        assert!(!registry.contains("webhook"));
+        registry.record_event(&ObserverEvent::HeartbeatTick);
+
+        assert_eq!(cost_events.load(Ordering::SeqCst), 2);
+        assert_eq!(webhook_events.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn observer_removed_mid_dispatch_receives_the_in_flight_event() {
+        let registry = ObserverRegistry::new();
+        registry
+            .register(Box::new(UnregisteringObserver {
+                registry: registry.clone(),
+                target: "event-log",
+            }))
+            .unwrap();
+        let (event_log, events) = counting("event-log");
+        registry.register(event_log).unwrap();
+
+        registry.record_event(&ObserverEvent::HeartbeatTick);
+        assert_eq!(events.load(Ordering::SeqCst), 1);
+        assert_eq!(registry.list(), ["unregistering"]);
+
+        registry.record_event(&ObserverEvent::HeartbeatTick);
+        assert_eq!(events.load(Ordering::SeqCst), 1);
+        // Break the cycle between the registry and its observer
+        registry.unregister("unregistering");
+    }
+}
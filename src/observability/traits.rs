@@ -1,3 +1,7 @@
+use crate::cost::ImageSizeClass;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 use std::time::Duration;
 
 /// Discrete events emitted by the agent runtime for observability.
@@ -6,7 +10,11 @@ use std::time::Duration;
 /// aggregate, or forward to external monitoring systems. Events carry
 /// just enough context for tracing and diagnostics without exposing
 /// sensitive prompt or response content.
-#[derive(Debug, Clone)]
+///
+/// Events serialize internally tagged by `type` (`"llm_response"`, ...), as
+/// written to event logs by `JsonlEventObserver`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum ObserverEvent {
     /// The agent orchestration loop has started a new session.
     AgentStart { provider: String, model: String },
@@ -20,6 +28,12 @@ pub enum ObserverEvent {
         messages_count: usize,
     },
     /// Result of a single LLM provider call.
+    ///
+    /// `cache_read_tokens` and `cache_write_tokens` are the subsets of
+    /// `input_tokens` the provider read from and wrote to its prompt cache,
+    /// and `reasoning_tokens` the subset of `output_tokens` spent on hidden
+    /// reasoning; each may be billed at its own rate. `session_id` names the
+    /// conversation the call served, when the emitter knows it.
     LlmResponse {
         provider: String,
         model: String,
@@ -28,6 +42,58 @@ pub enum ObserverEvent {
         error_message: Option<String>,
         input_tokens: Option<u64>,
         output_tokens: Option<u64>,
+        cache_read_tokens: Option<u64>,
+        cache_write_tokens: Option<u64>,
+        reasoning_tokens: Option<u64>,
+        session_id: Option<String>,
+    },
+    /// Output tokens of one chunk of a streamed LLM response.
+    ///
+    /// `output_tokens_delta` may be an estimate; chunks of the same response
+    /// share `request_id`.
+    LlmStreamChunk {
+        provider: String,
+        model: String,
+        request_id: String,
+        output_tokens_delta: u64,
+    },
+    /// A streamed LLM response has ended.
+    ///
+    /// The final token counts are authoritative when the provider reported
+    /// them; otherwise the summed `LlmStreamChunk` deltas stand. Cost
+    /// observers price the stream from this event, so an `LlmResponse` for
+    /// the same call should not carry token counts.
+    LlmStreamCompleted {
+        provider: String,
+        model: String,
+        request_id: String,
+        duration: Duration,
+        success: bool,
+        final_input_tokens: Option<u64>,
+        final_output_tokens: Option<u64>,
+    },
+    /// Result of a single embedding API call.
+    ///
+    /// `cost_usd` is set when the emitter has already priced the call.
+    EmbeddingResponse {
+        provider: String,
+        model: String,
+        duration: Duration,
+        success: bool,
+        tokens: Option<u64>,
+        cost_usd: Option<f64>,
+    },
+    /// Result of a single image-generation API call.
+    ///
+    /// `cost_usd` is set when the emitter has already priced the call.
+    ImageGeneration {
+        provider: String,
+        model: String,
+        duration: Duration,
+        success: bool,
+        count: u32,
+        size_class: ImageSizeClass,
+        cost_usd: Option<f64>,
     },
     /// The agent session has finished.
     ///
@@ -42,10 +108,17 @@ pub enum ObserverEvent {
     /// A tool call is about to be executed.
     ToolCallStart { tool: String },
     /// A tool call has completed with a success/failure outcome.
+    ///
+    /// `cost_usd` is the direct cost of the call for tools backed by paid
+    /// APIs, and `task_id` the economic task it served, when the emitter
+    /// knows them.
     ToolCall {
         tool: String,
         duration: Duration,
         success: bool,
+        error_message: Option<String>,
+        cost_usd: Option<f64>,
+        task_id: Option<String>,
     },
     /// The agent produced a final answer for the current user message.
     TurnComplete,
@@ -67,6 +140,49 @@ pub enum ObserverEvent {
     },
 }
 
+/// Variant of an [`ObserverEvent`] without its data, e.g. to select the
+/// events an observer receives from a config file (`"llm_response"`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObserverEventKind {
+    AgentStart,
+    LlmRequest,
+    LlmResponse,
+    LlmStreamChunk,
+    LlmStreamCompleted,
+    EmbeddingResponse,
+    ImageGeneration,
+    AgentEnd,
+    ToolCallStart,
+    ToolCall,
+    TurnComplete,
+    ChannelMessage,
+    HeartbeatTick,
+    Error,
+}
+
+impl ObserverEvent {
+    /// The variant of this event.
+    pub fn kind(&self) -> ObserverEventKind {
+        match self {
+            Self::AgentStart { .. } => ObserverEventKind::AgentStart,
+            Self::LlmRequest { .. } => ObserverEventKind::LlmRequest,
+            Self::LlmResponse { .. } => ObserverEventKind::LlmResponse,
+            Self::LlmStreamChunk { .. } => ObserverEventKind::LlmStreamChunk,
+            Self::LlmStreamCompleted { .. } => ObserverEventKind::LlmStreamCompleted,
+            Self::EmbeddingResponse { .. } => ObserverEventKind::EmbeddingResponse,
+            Self::ImageGeneration { .. } => ObserverEventKind::ImageGeneration,
+            Self::AgentEnd { .. } => ObserverEventKind::AgentEnd,
+            Self::ToolCallStart { .. } => ObserverEventKind::ToolCallStart,
+            Self::ToolCall { .. } => ObserverEventKind::ToolCall,
+            Self::TurnComplete => ObserverEventKind::TurnComplete,
+            Self::ChannelMessage { .. } => ObserverEventKind::ChannelMessage,
+            Self::HeartbeatTick => ObserverEventKind::HeartbeatTick,
+            Self::Error { .. } => ObserverEventKind::Error,
+        }
+    }
+}
+
 /// Numeric metrics emitted by the agent runtime.
 ///
 /// Observers can aggregate these into dashboards, alerts, or structured logs.
@@ -81,6 +197,29 @@ pub enum ObserverMetric {
     ActiveSessions(u64),
     /// Current depth of the inbound message queue.
     QueueDepth(u64),
+    /// Increment of a named counter, e.g. `llm.cost_usd` published by the
+    /// `CostObserver` for each priced response.
+    Counter {
+        /// Dotted metric name, e.g. `llm.output_tokens`.
+        name: String,
+        /// Amount to add to the counter.
+        value: f64,
+        /// Label pairs, e.g. `("provider", "anthropic")`.
+        labels: Vec<(String, String)>,
+    },
+}
+
+impl ObserverMetric {
+    /// Value of the label `key` on a [`Counter`](Self::Counter).
+    pub fn label(&self, key: &str) -> Option<&str> {
+        match self {
+            Self::Counter { labels, .. } => labels
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value.as_str()),
+            _ => None,
+        }
+    }
 }
 
 /// Core observability trait for recording agent runtime telemetry.
@@ -126,6 +265,69 @@ pub trait Observer: Send + Sync + 'static {
     fn as_any(&self) -> &dyn std::any::Any;
 }
 
+impl<T: Observer + ?Sized> Observer for Arc<T> {
+    fn record_event(&self, event: &ObserverEvent) {
+        (**self).record_event(event);
+    }
+
+    fn record_metric(&self, metric: &ObserverMetric) {
+        (**self).record_metric(metric);
+    }
+
+    fn flush(&self) {
+        (**self).flush();
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        (**self).as_any()
+    }
+}
+
+/// Destination for metrics derived by another observer.
+///
+/// Lets an observer that computes a value (the `CostObserver` pricing a
+/// response) hand it to the metrics pipeline without holding the whole
+/// observer stack. Every [`Observer`] is a sink through its
+/// [`record_metric`](Observer::record_metric).
+pub trait MetricSink: Send + Sync {
+    /// Publish one metric sample.
+    fn publish_metric(&self, metric: &ObserverMetric);
+}
+
+impl<T: Observer + ?Sized> MetricSink for T {
+    fn publish_metric(&self, metric: &ObserverMetric) {
+        self.record_metric(metric);
+    }
+}
+
+/// Observer backend that may await I/O while recording.
+///
+/// Async observers (webhooks, databases) never run on the hot path: an
+/// [`ObserverDispatcher`](super::dispatch::ObserverDispatcher) queues events
+/// and delivers them from its own task, one at a time and in order. Wrap a
+/// synchronous [`Observer`] in a
+/// [`SyncObserverAdapter`](super::dispatch::SyncObserverAdapter) to run it
+/// on the same task.
+#[async_trait]
+pub trait AsyncObserver: Send + Sync + 'static {
+    /// Record a discrete lifecycle event.
+    async fn record_event(&self, event: &ObserverEvent);
+
+    /// Record a numeric metric sample. The default ignores metrics.
+    async fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    /// Flush any buffered data; called once the dispatcher has drained its
+    /// queue on shutdown.
+    async fn flush(&self) {}
+
+    /// Return the human-readable name of this observer backend.
+    fn name(&self) -> &str;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -188,6 +390,9 @@ mod tests {
             tool: "shell".into(),
             duration: Duration::from_millis(10),
             success: true,
+            error_message: None,
+            cost_usd: None,
+            task_id: None,
         };
         let metric = ObserverMetric::RequestLatency(Duration::from_millis(8));
 
@@ -197,4 +402,25 @@ mod tests {
         assert!(matches!(cloned_event, ObserverEvent::ToolCall { .. }));
         assert!(matches!(cloned_metric, ObserverMetric::RequestLatency(_)));
     }
+
+    #[test]
+    fn event_kind_matches_the_serialized_type() {
+        let events = [
+            ObserverEvent::HeartbeatTick,
+            ObserverEvent::TurnComplete,
+            ObserverEvent::ToolCallStart {
+                tool: "shell".into(),
+            },
+            ObserverEvent::Error {
+                component: "provider".into(),
+                message: "timeout".into(),
+            },
+        ];
+        for event in events {
+            let tagged = serde_json::to_value(&event).unwrap();
+            assert_eq!(tagged["type"], serde_json::to_value(event.kind()).unwrap());
+        }
+        let kind: ObserverEventKind = serde_json::from_str("\"llm_response\"").unwrap();
+        assert_eq!(kind, ObserverEventKind::LlmResponse);
+    }
 }
@@ -0,0 +1,520 @@
+//! Batched delivery of observer events to an HTTP endpoint.
+//!
+//! [`WebhookObserver`] queues events on a bounded channel; a background task
+//! POSTs them in batches as JSON, retrying server errors and network failures
+//! with exponential backoff. Recording an event never waits on the network.
+//!
+//! Each request body has the form
+//! `{"events": [{"timestamp": "...", "event": {"type": "llm_response", ...}}]}`.
+
+use super::traits::{Observer, ObserverEvent, ObserverMetric};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::Mutex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+/// Delivery settings of a [`WebhookObserver`].
+#[derive(Clone)]
+pub struct WebhookConfig {
+    /// Extra request headers
+    pub headers: HashMap<String, String>,
+    /// Sent as `Authorization: Bearer <token>`
+    pub auth_token: Option<String>,
+    /// Most events sent in one request
+    pub batch_size: usize,
+    /// Longest an event waits for its batch to fill
+    pub flush_interval: Duration,
+    /// Attempts after the first before a batch is dropped
+    pub max_retries: u32,
+    /// Delay before the first retry, doubled for each one after it
+    pub initial_backoff: Duration,
+    /// Longest delay between retries
+    pub max_backoff: Duration,
+    /// Timeout of each request
+    pub timeout: Duration,
+    /// Events queued for delivery before new ones are dropped
+    pub queue_capacity: usize,
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            headers: HashMap::new(),
+            auth_token: None,
+            batch_size: 100,
+            flush_interval: Duration::from_secs(5),
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+            queue_capacity: 1024,
+        }
+    }
+}
+
+/// Event counts of a [`WebhookObserver`], from
+/// [`stats`](WebhookObserver::stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct WebhookStats {
+    /// Events the endpoint accepted
+    pub sent: u64,
+    /// Events dropped: rejected by the endpoint, undeliverable after every
+    /// retry, or recorded while the queue was full
+    pub failed: u64,
+    /// Events queued or being delivered
+    pub pending: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    sent: AtomicU64,
+    failed: AtomicU64,
+    pending: AtomicU64,
+}
+
+#[derive(Serialize)]
+struct WebhookEvent {
+    timestamp: DateTime<Utc>,
+    event: ObserverEvent,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    events: &'a [WebhookEvent],
+}
+
+enum Message {
+    /// Boxed, since events are far larger than the other variants
+    Event(Box<WebhookEvent>),
+    /// Send the partial batch now
+    Flush,
+}
+
+type EventFilter = Arc<dyn Fn(&ObserverEvent) -> bool + Send + Sync>;
+
+/// Pushes observer events to an HTTP endpoint in batches.
+pub struct WebhookObserver {
+    /// Taken on shutdown, which ends the delivery task once it drains
+    sender: Mutex<Option<mpsc::Sender<Message>>>,
+    filter: Option<EventFilter>,
+    counters: Arc<Counters>,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+impl WebhookObserver {
+    /// Start delivering events to `url` from a task on the current Tokio
+    /// runtime.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a configured header is invalid or the HTTP
+    /// client cannot be built.
+    ///
+    /// # Panics
+    ///
+    /// Panics when called outside a Tokio runtime.
+    pub fn new(url: impl Into<String>, config: WebhookConfig) -> Result<Self> {
+        let builder = reqwest::Client::builder()
+            .timeout(config.timeout)
+            .default_headers(request_headers(&config)?);
+        let client = crate::config::apply_runtime_proxy_to_builder(builder, "observability.webhook")
+            .build()
+            .context("Failed to build webhook HTTP client")?;
+
+        let (sender, receiver) = mpsc::channel(config.queue_capacity.max(1));
+        let counters = Arc::new(Counters::default());
+        let delivery = Delivery {
+            client,
+            url: url.into(),
+            config,
+            counters: Arc::clone(&counters),
+        };
+        let worker = tokio::spawn(delivery.run(receiver));
+        Ok(Self {
+            sender: Mutex::new(Some(sender)),
+            filter: None,
+            counters,
+            worker: Mutex::new(Some(worker)),
+        })
+    }
+
+    /// Only send events for which `filter` returns `true`, e.g.
+    /// `|event| matches!(event, ObserverEvent::LlmResponse { .. })`.
+    pub fn with_filter(
+        mut self,
+        filter: impl Fn(&ObserverEvent) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Delivery counts so far.
+    pub fn stats(&self) -> WebhookStats {
+        WebhookStats {
+            sent: self.counters.sent.load(Ordering::Relaxed),
+            failed: self.counters.failed.load(Ordering::Relaxed),
+            pending: self.counters.pending.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Stop accepting events and wait until everything still queued has
+    /// been delivered or dropped.
+    pub async fn shutdown(&self) {
+        self.sender.lock().take();
+        let worker = self.worker.lock().take();
+        if let Some(worker) = worker {
+            if let Err(e) = worker.await {
+                tracing::warn!("Webhook delivery task failed: {e}");
+            }
+        }
+    }
+}
+
+impl Observer for WebhookObserver {
+    fn record_event(&self, event: &ObserverEvent) {
+        if self.filter.as_ref().is_some_and(|filter| !filter(event)) {
+            return;
+        }
+        let sender = self.sender.lock();
+        let Some(sender) = sender.as_ref() else {
+            return;
+        };
+        // Counted first so the delivery task never sees it go negative
+        self.counters.pending.fetch_add(1, Ordering::Relaxed);
+        let message = Message::Event(Box::new(WebhookEvent {
+            timestamp: Utc::now(),
+            event: event.clone(),
+        }));
+        if sender.try_send(message).is_err() {
+            self.counters.pending.fetch_sub(1, Ordering::Relaxed);
+            self.counters.failed.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    fn record_metric(&self, _metric: &ObserverMetric) {}
+
+    /// Send the partial batch without waiting for the flush interval.
+    fn flush(&self) {
+        if let Some(sender) = self.sender.lock().as_ref() {
+            let _ = sender.try_send(Message::Flush);
+        }
+    }
+
+    fn name(&self) -> &str {
+        "webhook"
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
+
+fn request_headers(config: &WebhookConfig) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.headers {
+        let header_name = HeaderName::from_bytes(name.as_bytes())
+            .with_context(|| format!("Invalid webhook header name {name:?}"))?;
+        let header_value = HeaderValue::from_str(value)
+            .with_context(|| format!("Invalid value for webhook header {name:?}"))?;
+        headers.insert(header_name, header_value);
+    }
+    if let Some(token) = &config.auth_token {
+        let mut value = HeaderValue::from_str(&format!("Bearer {token}"))
+            .context("Invalid webhook auth token")?;
+        value.set_sensitive(true);
+        headers.insert(AUTHORIZATION, value);
+    }
+    Ok(headers)
+}
+
+/// State of the delivery task.
+struct Delivery {
+    client: reqwest::Client,
+    url: String,
+    config: WebhookConfig,
+    counters: Arc<Counters>,
+}
+
+impl Delivery {
+    /// Batch queued events until the channel is closed and drained.
+    async fn run(self, mut receiver: mpsc::Receiver<Message>) {
+        let mut batch: Vec<WebhookEvent> = Vec::new();
+        let mut deadline = Instant::now();
+        loop {
+            let next = if batch.is_empty() {
+                receiver.recv().await
+            } else {
+                match tokio::time::timeout_at(deadline, receiver.recv()).await {
+                    Ok(next) => next,
+                    // The oldest event in the batch waited `flush_interval`
+                    Err(_) => {
+                        self.send(std::mem::take(&mut batch)).await;
+                        continue;
+                    }
+                }
+            };
+            match next {
+                Some(Message::Event(event)) => {
+                    if batch.is_empty() {
+                        deadline = Instant::now() + self.config.flush_interval;
+                    }
+                    batch.push(*event);
+                    if batch.len() >= self.config.batch_size {
+                        self.send(std::mem::take(&mut batch)).await;
+                    }
+                }
+                Some(Message::Flush) => {
+                    if !batch.is_empty() {
+                        self.send(std::mem::take(&mut batch)).await;
+                    }
+                }
+                None => break,
+            }
+        }
+        if !batch.is_empty() {
+            self.send(batch).await;
+        }
+    }
+
+    async fn send(&self, batch: Vec<WebhookEvent>) {
+        let count = batch.len() as u64;
+        let counter = if self.post(&batch).await {
+            &self.counters.sent
+        } else {
+            &self.counters.failed
+        };
+        counter.fetch_add(count, Ordering::Relaxed);
+        self.counters.pending.fetch_sub(count, Ordering::Relaxed);
+    }
+
+    /// POST `events`, retrying server errors and network failures. Returns
+    /// whether the endpoint accepted them.
+    async fn post(&self, events: &[WebhookEvent]) -> bool {
+        let payload = WebhookPayload { events };
+        let mut backoff = self.config.initial_backoff;
+        for attempt in 0..=self.config.max_retries {
+            if attempt > 0 {
+                tokio::time::sleep(backoff).await;
+                backoff = backoff.saturating_mul(2).min(self.config.max_backoff);
+            }
+            match self.client.post(&self.url).json(&payload).send().await {
+                Ok(response) if response.status().is_success() => return true,
+                Ok(response) if !is_retryable(response.status()) => {
+                    tracing::warn!(
+                        "Webhook {} rejected {} events with HTTP {}",
+                        self.url,
+                        events.len(),
+                        response.status()
+                    );
+                    return false;
+                }
+                Ok(response) => tracing::debug!(
+                    "Webhook {} returned HTTP {} (attempt {})",
+                    self.url,
+                    response.status(),
+                    attempt + 1
+                ),
+                Err(e) => tracing::debug!(
+                    "Webhook {} request failed (attempt {}): {e}",
+                    self.url,
+                    attempt + 1
+                ),
+            }
+        }
+        tracing::warn!(
+            "Webhook {} failed {} times, dropping {} events",
+            self.url,
+            self.config.max_retries + 1,
+            events.len()
+        );
+        false
+    }
+}
+
+fn is_retryable(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::extract::State;
+    use axum::http::HeaderMap as RequestHeaders;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use std::collections::VecDeque;
+
+    /// Endpoint answering with scripted statuses, then 200
+    #[derive(Default)]
+    struct TestServer {
+        statuses: Mutex<VecDeque<u16>>,
+        requests: Mutex<Vec<(RequestHeaders, serde_json::Value)>>,
+    }
+
+    async fn receive(
+        State(server): State<Arc<TestServer>>,
+        headers: RequestHeaders,
+        Json(body): Json<serde_json::Value>,
+    ) -> axum::http::StatusCode {
+        server.requests.lock().push((headers, body));
+        let status = server.statuses.lock().pop_front().unwrap_or(200);
+        axum::http::StatusCode::from_u16(status).unwrap()
+    }
+
+    async fn start_server(statuses: &[u16]) -> (Arc<TestServer>, String) {
+        let server = Arc::new(TestServer {
+            statuses: Mutex::new(statuses.iter().copied().collect()),
+            ..TestServer::default()
+        });
+        let app = Router::new()
+            .route("/hook", post(receive))
+            .with_state(Arc::clone(&server));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (server, url)
+    }
+
+    fn fast_retries() -> WebhookConfig {
+        WebhookConfig {
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..WebhookConfig::default()
+        }
+    }
+
+    fn llm_response() -> ObserverEvent {
+        ObserverEvent::LlmResponse {
+            provider: "anthropic".into(),
+            model: "claude-sonnet-4".into(),
+            duration: Duration::from_millis(250),
+            success: true,
+            error_message: None,
+            input_tokens: Some(1000),
+            output_tokens: Some(200),
+            cache_read_tokens: None,
+            cache_write_tokens: None,
+            reasoning_tokens: None,
+            session_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn filtered_events_are_batched_with_headers() {
+        let (server, url) = start_server(&[]).await;
+        let config = WebhookConfig {
+            headers: HashMap::from([("x-source".to_string(), "zeroclaw".to_string())]),
+            auth_token: Some("secret".into()),
+            batch_size: 2,
+            ..WebhookConfig::default()
+        };
+        let observer = WebhookObserver::new(url, config)
+            .unwrap()
+            .with_filter(|event| matches!(event, ObserverEvent::LlmResponse { .. }));
+        for _ in 0..3 {
+            observer.record_event(&llm_response());
+            observer.record_event(&ObserverEvent::HeartbeatTick);
+        }
+        observer.shutdown().await;
+
+        assert_eq!(
+            observer.stats(),
+            WebhookStats {
+                sent: 3,
+                failed: 0,
+                pending: 0
+            }
+        );
+        let requests = server.requests.lock();
+        let sizes: Vec<usize> = requests
+            .iter()
+            .map(|(_, body)| body["events"].as_array().unwrap().len())
+            .collect();
+        assert_eq!(sizes, [2, 1]);
+        let (headers, body) = &requests[0];
+        assert_eq!(headers["authorization"], "Bearer secret");
+        assert_eq!(headers["x-source"], "zeroclaw");
+        assert_eq!(body["events"][0]["event"]["type"], "llm_response");
+        assert_eq!(body["events"][0]["event"]["input_tokens"], 1000);
+    }
+
+    #[tokio::test]
+    async fn partial_batches_are_sent_after_the_flush_interval() {
+        let (server, url) = start_server(&[]).await;
+        let config = WebhookConfig {
+            flush_interval: Duration::from_millis(20),
+            ..WebhookConfig::default()
+        };
+        let observer = WebhookObserver::new(url, config).unwrap();
+        observer.record_event(&llm_response());
+        assert_eq!(observer.stats().pending, 1);
+
+        for _ in 0..200 {
+            if observer.stats().sent == 1 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(observer.stats().sent, 1);
+        assert_eq!(server.requests.lock().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn server_errors_are_retried_with_backoff() {
+        let (server, url) = start_server(&[503, 500]).await;
+        let observer = WebhookObserver::new(url, fast_retries()).unwrap();
+        observer.record_event(&llm_response());
+        observer.shutdown().await;
+
+        assert_eq!(observer.stats().sent, 1);
+        assert_eq!(server.requests.lock().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn batches_are_dropped_after_max_retries() {
+        let (server, url) = start_server(&[503, 503, 503, 400]).await;
+        let observer = WebhookObserver::new(url, fast_retries()).unwrap();
+        observer.record_event(&llm_response());
+        observer.flush();
+        observer.record_event(&llm_response());
+        observer.shutdown().await;
+
+        // Three attempts for the first batch; the 400 is not retried
+        assert_eq!(server.requests.lock().len(), 4);
+        assert_eq!(
+            observer.stats(),
+            WebhookStats {
+                sent: 0,
+                failed: 2,
+                pending: 0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn unreachable_endpoints_count_as_failed() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/hook", listener.local_addr().unwrap());
+        drop(listener);
+
+        let observer = WebhookObserver::new(url, fast_retries()).unwrap();
+        observer.record_event(&llm_response());
+        observer.shutdown().await;
+        assert_eq!(observer.stats().failed, 1);
+
+        // Ignored once shut down
+        observer.record_event(&llm_response());
+        assert_eq!(observer.stats().failed, 1);
+    }
+}
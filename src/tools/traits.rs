@@ -32,6 +32,13 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Direct cost in USD of one call, for tools backed by paid APIs
+    /// (search, scraping, ...). Reported on `ToolCall` events so the cost
+    /// observer can track spend per tool.
+    fn cost_per_call(&self) -> Option<f64> {
+        None
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
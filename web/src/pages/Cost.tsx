@@ -102,7 +102,7 @@ export default function Cost() {
         <h3 className="text-base font-semibold text-white mb-4">
           Token Statistics
         </h3>
-        <div className="grid grid-cols-1 sm:grid-cols-3 gap-4">
+        <div className="grid grid-cols-1 sm:grid-cols-4 gap-4">
           <div className="bg-gray-800/50 rounded-lg p-4">
             <p className="text-sm text-gray-400">Total Tokens</p>
             <p className="text-xl font-bold text-white mt-1">
@@ -125,6 +125,12 @@ export default function Cost() {
                 : '$0.0000'}
             </p>
           </div>
+          <div className="bg-gray-800/50 rounded-lg p-4">
+            <p className="text-sm text-gray-400">Spend on Failed Calls</p>
+            <p className="text-xl font-bold text-white mt-1">
+              {formatUSD(cost.failed_call_cost_usd ?? 0)}
+            </p>
+          </div>
         </div>
       </div>
 
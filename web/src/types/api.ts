@@ -99,6 +99,7 @@ export interface CostSummary {
   monthly_cost_usd: number;
   total_tokens: number;
   request_count: number;
+  failed_call_cost_usd?: number;
   by_model: Record<string, ModelStats>;
 }
 